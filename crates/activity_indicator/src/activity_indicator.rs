@@ -5,7 +5,7 @@ use futures::StreamExt;
 use gpui::{
     Animation, AnimationExt as _, App, Context, CursorStyle, Entity, EventEmitter,
     InteractiveElement as _, ParentElement as _, Render, SharedString, StatefulInteractiveElement,
-    Styled, Transformation, Window, actions, percentage,
+    Styled, Subscription, Transformation, Window, actions, percentage,
 };
 use language::{
     BinaryStatus, LanguageRegistry, LanguageServerId, LanguageServerName,
@@ -15,7 +15,9 @@ use project::{
     EnvironmentErrorMessage, LanguageServerProgress, LspStoreEvent, Project,
     ProjectEnvironmentEvent,
     git_store::{GitStoreEvent, Repository},
+    terminals::BackgroundTaskStatus,
 };
+use semantic_index::{ProjectIndex, SemanticDb};
 use smallvec::SmallVec;
 use std::{
     cmp::Reverse,
@@ -51,6 +53,7 @@ pub struct ActivityIndicator {
     project: Entity<Project>,
     auto_updater: Option<Entity<AutoUpdater>>,
     context_menu_handle: PopoverMenuHandle<ContextMenu>,
+    _project_index_subscription: Option<Subscription>,
 }
 
 #[derive(Debug)]
@@ -196,6 +199,13 @@ impl ActivityIndicator {
             )
             .detach();
 
+            cx.subscribe(&project, |_, _, event, cx| {
+                if let project::Event::BackgroundTasksChanged = event {
+                    cx.notify();
+                }
+            })
+            .detach();
+
             cx.subscribe(
                 &project.read(cx).environment().clone(),
                 |_, _, event, cx| match event {
@@ -217,12 +227,18 @@ impl ActivityIndicator {
                 cx.observe(auto_updater, |_, _, cx| cx.notify()).detach();
             }
 
-            Self {
+            cx.observe_global::<SemanticDb>(|this, cx| this.observe_project_index(cx))
+                .detach();
+
+            let mut this = Self {
                 statuses: Vec::new(),
                 project: project.clone(),
                 auto_updater,
                 context_menu_handle: Default::default(),
-            }
+                _project_index_subscription: None,
+            };
+            this.observe_project_index(cx);
+            this
         });
 
         cx.subscribe_in(&this, window, move |_, _, event, window, cx| match event {
@@ -350,6 +366,20 @@ impl ActivityIndicator {
             .flatten()
     }
 
+    fn project_index(&self, cx: &App) -> Option<Entity<ProjectIndex>> {
+        cx.try_global::<SemanticDb>()?
+            .project_index(self.project.clone(), cx)
+    }
+
+    fn observe_project_index(&mut self, cx: &mut Context<Self>) {
+        self._project_index_subscription = self.project_index(cx).map(|project_index| {
+            cx.subscribe(&project_index, |_, _, _: &semantic_index::Status, cx| {
+                cx.notify()
+            })
+        });
+        cx.notify();
+    }
+
     fn pending_environment_errors<'a>(
         &'a self,
         cx: &'a App,
@@ -420,7 +450,7 @@ impl ActivityIndicator {
                             .into_any_element(),
                     ),
                     message,
-                    on_click: Some(Arc::new(Self::toggle_language_server_work_context_menu)),
+                    on_click: Some(Arc::new(Self::toggle_context_menu)),
                     tooltip_message: None,
                 });
             }
@@ -451,6 +481,17 @@ impl ActivityIndicator {
             });
         }
 
+        // Show the background tasks while any of them is processing changes
+        if self
+            .project
+            .read(cx)
+            .background_tasks(cx)
+            .iter()
+            .any(|(_, status)| *status == BackgroundTaskStatus::Busy)
+        {
+            return self.background_tasks_content(cx);
+        }
+
         let current_job = self
             .project
             .read(cx)
@@ -677,6 +718,35 @@ impl ActivityIndicator {
             });
         }
 
+        // Show the progress of semantic indexing
+        let index_status = self
+            .project_index(cx)
+            .map(|project_index| project_index.read(cx).status());
+        let index_message = match index_status {
+            Some(semantic_index::Status::Loading) => Some("Loading semantic index…".to_string()),
+            Some(semantic_index::Status::Scanning { remaining_count }) => {
+                Some(format!("Indexing {remaining_count} files…"))
+            }
+            Some(semantic_index::Status::Idle) | None => None,
+        };
+        if let Some(message) = index_message {
+            return Some(Content {
+                icon: Some(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::Small)
+                        .with_animation(
+                            "arrow-circle",
+                            Animation::new(Duration::from_secs(2)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        )
+                        .into_any_element(),
+                ),
+                message,
+                on_click: None,
+                tooltip_message: Some("Building the semantic search index".to_string()),
+            });
+        }
+
         // Show any application auto-update info.
         if let Some(updater) = &self.auto_updater {
             return match &updater.read(cx).status() {
@@ -742,7 +812,7 @@ impl ActivityIndicator {
                     })),
                     tooltip_message: None,
                 }),
-                AutoUpdateStatus::Idle => None,
+                AutoUpdateStatus::Idle => self.background_tasks_content(cx),
             };
         }
 
@@ -765,7 +835,53 @@ impl ActivityIndicator {
             }
         }
 
-        None
+        self.background_tasks_content(cx)
+    }
+
+    /// Lists each running background task with its state, with a spinner while any of them is busy.
+    fn background_tasks_content(&self, cx: &App) -> Option<Content> {
+        let background_tasks = self.project.read(cx).background_tasks(cx);
+        if background_tasks.is_empty() {
+            return None;
+        }
+        let icon = if background_tasks
+            .iter()
+            .any(|(_, status)| *status == BackgroundTaskStatus::Busy)
+        {
+            Icon::new(IconName::ArrowCircle)
+                .size(IconSize::Small)
+                .with_animation(
+                    "arrow-circle",
+                    Animation::new(Duration::from_secs(2)).repeat(),
+                    |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                )
+                .into_any_element()
+        } else {
+            Icon::new(IconName::Check)
+                .size(IconSize::Small)
+                .into_any_element()
+        };
+        let descriptions = background_tasks
+            .iter()
+            .map(|(label, status)| match status {
+                BackgroundTaskStatus::Busy => format!("{label} (busy)"),
+                BackgroundTaskStatus::Ready => format!("{label} (ready)"),
+            })
+            .collect::<Vec<_>>();
+        Some(Content {
+            icon: Some(icon),
+            message: format!(
+                "{}: {}",
+                if descriptions.len() > 1 {
+                    "Tasks"
+                } else {
+                    "Task"
+                },
+                descriptions.join(", ")
+            ),
+            tooltip_message: Some(descriptions.join("\n")),
+            on_click: Some(Arc::new(Self::toggle_context_menu)),
+        })
     }
 
     fn version_tooltip_message(version: &VersionCheckType) -> String {
@@ -779,11 +895,7 @@ impl ActivityIndicator {
         })
     }
 
-    fn toggle_language_server_work_context_menu(
-        &mut self,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
+    fn toggle_context_menu(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.context_menu_handle.toggle(window, cx);
     }
 }
@@ -893,6 +1005,16 @@ impl Render for ActivityIndicator {
                                 menu = menu.label(title);
                             }
                         }
+                        for (label, status) in
+                            strong_this.read(cx).project.read(cx).background_tasks(cx)
+                        {
+                            has_work = true;
+                            let status = match status {
+                                BackgroundTaskStatus::Busy => "busy",
+                                BackgroundTaskStatus::Ready => "ready",
+                            };
+                            menu = menu.label(format!("{label}: {status}"));
+                        }
                         menu
                     });
                     has_work.then_some(menu)
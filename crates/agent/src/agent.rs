@@ -4,6 +4,7 @@ pub mod context_server_tool;
 pub mod context_store;
 pub mod history_store;
 pub mod thread;
+pub mod thread_export;
 pub mod thread_store;
 pub mod tool_use;
 
@@ -13,6 +14,7 @@ pub use thread::{
     LastRestoreCheckpoint, Message, MessageCrease, MessageId, MessageSegment, Thread, ThreadError,
     ThreadEvent, ThreadFeedback, ThreadId, ThreadSummary, TokenUsageRatio,
 };
+pub use thread_export::ExportedThread;
 pub use thread_store::{SerializedThread, TextThreadStore, ThreadStore};
 
 pub fn init(cx: &mut gpui::App) {
@@ -40,6 +40,10 @@ impl AgentProfile {
                 .as_ref()
                 .map(|profile| profile.tools.clone())
                 .unwrap_or_default(),
+            tool_permissions: base_profile
+                .as_ref()
+                .map(|profile| profile.tool_permissions.clone())
+                .unwrap_or_default(),
             enable_all_context_servers: base_profile
                 .as_ref()
                 .map(|profile| profile.enable_all_context_servers)
@@ -249,6 +253,7 @@ mod tests {
                 AgentProfileSettings {
                     name: "mcp".into(),
                     tools: IndexMap::default(),
+                    tool_permissions: Vec::new(),
                     enable_all_context_servers: false,
                     context_servers: IndexMap::from_iter([("mcp".into(), context_server_preset())]),
                 },
@@ -2,9 +2,11 @@ use crate::thread::Thread;
 use assistant_context::AssistantContext;
 use assistant_tool::outline;
 use collections::HashSet;
+use context_server::ContextServerId;
 use futures::future;
 use futures::{FutureExt, future::Shared};
 use gpui::{App, AppContext as _, ElementId, Entity, SharedString, Task};
+use http_client::Url;
 use icons::IconName;
 use language::{Buffer, ParseStatus};
 use language_model::{LanguageModelImage, LanguageModelRequestMessage, MessageContent};
@@ -31,6 +33,7 @@ pub enum ContextKind {
     Thread,
     TextThread,
     Rules,
+    McpResource,
     Image,
 }
 
@@ -45,6 +48,7 @@ impl ContextKind {
             ContextKind::Thread => IconName::Thread,
             ContextKind::TextThread => IconName::TextThread,
             ContextKind::Rules => RULES_ICON,
+            ContextKind::McpResource => IconName::Server,
             ContextKind::Image => IconName::Image,
         }
     }
@@ -65,6 +69,7 @@ pub enum AgentContextHandle {
     Thread(ThreadContextHandle),
     TextThread(TextThreadContextHandle),
     Rules(RulesContextHandle),
+    McpResource(McpResourceContextHandle),
     Image(ImageContext),
 }
 
@@ -79,6 +84,7 @@ impl AgentContextHandle {
             Self::Thread(context) => context.context_id,
             Self::TextThread(context) => context.context_id,
             Self::Rules(context) => context.context_id,
+            Self::McpResource(context) => context.context_id,
             Self::Image(context) => context.context_id,
         }
     }
@@ -100,6 +106,7 @@ pub enum AgentContext {
     Thread(ThreadContext),
     TextThread(TextThreadContext),
     Rules(RulesContext),
+    McpResource(McpResourceContext),
     Image(ImageContext),
 }
 
@@ -120,6 +127,9 @@ impl AgentContext {
                 AgentContextHandle::TextThread(context.handle.clone())
             }
             AgentContext::Rules(context) => AgentContextHandle::Rules(context.handle.clone()),
+            AgentContext::McpResource(context) => {
+                AgentContextHandle::McpResource(context.handle.clone())
+            }
             AgentContext::Image(context) => AgentContextHandle::Image(context.clone()),
         }
     }
@@ -726,6 +736,69 @@ impl Display for RulesContext {
     }
 }
 
+/// A resource served by a context server. Its contents are read when it is added, and read again
+/// whenever the server reports that the resource was updated.
+#[derive(Debug, Clone)]
+pub struct McpResourceContextHandle {
+    pub server_id: ContextServerId,
+    pub uri: Url,
+    pub name: SharedString,
+    pub text_task: Shared<Task<Option<SharedString>>>,
+    pub context_id: ContextId,
+}
+
+#[derive(Debug, Clone)]
+pub struct McpResourceContext {
+    pub handle: McpResourceContextHandle,
+    pub text: SharedString,
+}
+
+impl McpResourceContextHandle {
+    pub fn eq_for_key(&self, other: &Self) -> bool {
+        self.server_id == other.server_id && self.uri == other.uri
+    }
+
+    pub fn hash_for_key<H: Hasher>(&self, state: &mut H) {
+        self.server_id.hash(state);
+        self.uri.hash(state);
+    }
+
+    pub fn lookup_key(server_id: ContextServerId, uri: Url) -> AgentContextKey {
+        AgentContextKey(AgentContextHandle::McpResource(McpResourceContextHandle {
+            server_id,
+            uri,
+            name: SharedString::default(),
+            text_task: Task::ready(None).shared(),
+            context_id: ContextId::for_lookup(),
+        }))
+    }
+
+    /// The contents of the resource, if they have been read.
+    pub fn text(&self) -> Option<SharedString> {
+        self.text_task.clone().now_or_never().flatten()
+    }
+
+    fn load(self, cx: &App) -> Task<Option<(AgentContext, Vec<Entity<Buffer>>)>> {
+        cx.background_spawn(async move {
+            let text = self.text_task.clone().await?;
+            let context = AgentContext::McpResource(McpResourceContext { handle: self, text });
+            Some((context, vec![]))
+        })
+    }
+}
+
+impl Display for McpResourceContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "<resource server=\"{}\" uri=\"{}\">",
+            self.handle.server_id, self.handle.uri
+        )?;
+        writeln!(f, "{}", self.text.trim())?;
+        write!(f, "</resource>")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ImageContext {
     pub project_path: Option<ProjectPath>,
@@ -839,6 +912,7 @@ pub fn load_context(
             AgentContextHandle::Thread(context) => context.load(cx),
             AgentContextHandle::TextThread(context) => context.load(cx),
             AgentContextHandle::Rules(context) => context.load(prompt_store, cx),
+            AgentContextHandle::McpResource(context) => context.load(cx),
             AgentContextHandle::Image(context) => context.load(cx),
         })
         .collect();
@@ -865,6 +939,7 @@ pub fn load_context(
         let mut thread_context = Vec::new();
         let mut text_thread_context = Vec::new();
         let mut rules_context = Vec::new();
+        let mut mcp_resource_context = Vec::new();
         let mut images = Vec::new();
         for context in &contexts {
             match context {
@@ -876,6 +951,7 @@ pub fn load_context(
                 AgentContext::Thread(context) => thread_context.push(context),
                 AgentContext::TextThread(context) => text_thread_context.push(context),
                 AgentContext::Rules(context) => rules_context.push(context),
+                AgentContext::McpResource(context) => mcp_resource_context.push(context),
                 AgentContext::Image(context) => images.extend(context.image()),
             }
         }
@@ -890,6 +966,7 @@ pub fn load_context(
             && thread_context.is_empty()
             && text_thread_context.is_empty()
             && rules_context.is_empty()
+            && mcp_resource_context.is_empty()
         {
             return ContextLoadResult {
                 loaded_context: LoadedContext {
@@ -982,6 +1059,15 @@ pub fn load_context(
             text.push_str("</user_rules>\n");
         }
 
+        if !mcp_resource_context.is_empty() {
+            text.push_str("<mcp_resources>");
+            for context in mcp_resource_context {
+                text.push('\n');
+                let _ = writeln!(text, "{context}");
+            }
+            text.push_str("</mcp_resources>\n");
+        }
+
         text.push_str("</context>\n");
 
         ContextLoadResult {
@@ -1081,6 +1167,11 @@ impl PartialEq for AgentContextKey {
                     return context.eq_for_key(other_context);
                 }
             }
+            AgentContextHandle::McpResource(context) => {
+                if let AgentContextHandle::McpResource(other_context) = &other.0 {
+                    return context.eq_for_key(other_context);
+                }
+            }
             AgentContextHandle::Image(context) => {
                 if let AgentContextHandle::Image(other_context) = &other.0 {
                     return context.eq_for_key(other_context);
@@ -1107,6 +1198,7 @@ impl Hash for AgentContextKey {
             AgentContextHandle::Thread(context) => context.hash_for_key(state),
             AgentContextHandle::TextThread(context) => context.hash_for_key(state),
             AgentContextHandle::Rules(context) => context.hash_for_key(state),
+            AgentContextHandle::McpResource(context) => context.hash_for_key(state),
             AgentContextHandle::Image(context) => context.hash_for_key(state),
         }
     }
@@ -1183,6 +1275,41 @@ mod tests {
         assert_eq!(file_context.text, small_content);
     }
 
+    #[gpui::test]
+    async fn test_mcp_resource_context(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+        let project = create_test_project(cx, json!({})).await;
+
+        let context_handle = AgentContextHandle::McpResource(McpResourceContextHandle {
+            server_id: ContextServerId("docs".into()),
+            uri: Url::parse("docs://guides/setup").unwrap(),
+            name: "Setup".into(),
+            text_task: Task::ready(Some("Run the installer.".into())).shared(),
+            context_id: ContextId::zero(),
+        });
+        let unreadable_handle = AgentContextHandle::McpResource(McpResourceContextHandle {
+            server_id: ContextServerId("docs".into()),
+            uri: Url::parse("docs://guides/missing").unwrap(),
+            name: "Missing".into(),
+            text_task: Task::ready(None).shared(),
+            context_id: ContextId::zero(),
+        });
+
+        let loaded_context = cx
+            .update(|cx| load_context(vec![context_handle, unreadable_handle], &project, &None, cx))
+            .await
+            .loaded_context;
+
+        assert_eq!(loaded_context.contexts.len(), 1);
+        assert!(loaded_context.text.contains(
+            "<mcp_resources>\n\
+            <resource server=\"docs\" uri=\"docs://guides/setup\">\n\
+            Run the installer.\n\
+            </resource>\n\
+            </mcp_resources>\n"
+        ));
+    }
+
     async fn file_context_for(content: String, cx: &mut TestAppContext) -> FileContext {
         // Create a test project with the file
         let project = create_test_project(
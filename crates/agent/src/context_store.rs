@@ -1,8 +1,9 @@
 use crate::{
     context::{
         AgentContextHandle, AgentContextKey, ContextId, ContextKind, DirectoryContextHandle,
-        FetchedUrlContext, FileContextHandle, ImageContext, RulesContextHandle,
-        SelectionContextHandle, SymbolContextHandle, TextThreadContextHandle, ThreadContextHandle,
+        FetchedUrlContext, FileContextHandle, ImageContext, McpResourceContextHandle,
+        RulesContextHandle, SelectionContextHandle, SymbolContextHandle, TextThreadContextHandle,
+        ThreadContextHandle,
     },
     thread::{MessageId, Thread, ThreadId},
     thread_store::ThreadStore,
@@ -10,11 +11,19 @@ use crate::{
 use anyhow::{Context as _, Result, anyhow};
 use assistant_context::AssistantContext;
 use collections::{HashSet, IndexSet};
-use futures::{self, FutureExt};
-use gpui::{App, Context, Entity, EventEmitter, Image, SharedString, Task, WeakEntity};
+use context_server::{ContextServer, ContextServerId, types};
+use futures::{self, FutureExt, future::Shared};
+use gpui::{
+    App, Context, Entity, EventEmitter, Image, SharedString, Subscription, Task, WeakEntity,
+};
+use http_client::Url;
 use language::{Buffer, File as _};
 use language_model::LanguageModelImage;
-use project::{Project, ProjectItem, ProjectPath, Symbol, image_store::is_image_file};
+use project::{
+    Project, ProjectItem, ProjectPath, Symbol,
+    context_server_store::{self, ContextServerStore},
+    image_store::is_image_file,
+};
 use prompt_store::UserPromptId;
 use ref_cast::RefCast as _;
 use std::{
@@ -23,6 +32,7 @@ use std::{
     sync::Arc,
 };
 use text::{Anchor, OffsetRangeExt};
+use util::ResultExt as _;
 
 pub struct ContextStore {
     project: WeakEntity<Project>,
@@ -31,6 +41,7 @@ pub struct ContextStore {
     context_set: IndexSet<AgentContextKey>,
     context_thread_ids: HashSet<ThreadId>,
     context_text_thread_paths: HashSet<Arc<Path>>,
+    _context_server_subscription: Option<Subscription>,
 }
 
 pub enum ContextStoreEvent {
@@ -51,6 +62,7 @@ impl ContextStore {
             context_set: IndexSet::default(),
             context_thread_ids: HashSet::default(),
             context_text_thread_paths: HashSet::default(),
+            _context_server_subscription: None,
         }
     }
 
@@ -292,6 +304,93 @@ impl ContextStore {
         context
     }
 
+    pub fn add_mcp_resource(
+        &mut self,
+        server_id: ContextServerId,
+        uri: Url,
+        name: SharedString,
+        remove_if_exists: bool,
+        cx: &mut Context<ContextStore>,
+    ) -> Option<AgentContextHandle> {
+        if let Some(existing) = self
+            .context_set
+            .get(&McpResourceContextHandle::lookup_key(
+                server_id.clone(),
+                uri.clone(),
+            ))
+            .map(|key| key.as_ref().clone())
+        {
+            if remove_if_exists {
+                self.remove_context(&existing, cx);
+                return None;
+            } else {
+                return Some(existing);
+            }
+        }
+
+        let context_server_store = self.project.upgrade()?.read(cx).context_server_store();
+        self._context_server_subscription.get_or_insert_with(|| {
+            cx.subscribe(&context_server_store, Self::handle_context_server_event)
+        });
+
+        let server = context_server_store.read(cx).get_running_server(&server_id);
+        if let Some(server) = server.clone() {
+            subscribe_to_mcp_resource(server, uri.clone(), cx);
+        }
+        let text_task = read_mcp_resource(server, uri.clone(), cx);
+        self.notify_when_read(text_task.clone(), cx);
+        let context = AgentContextHandle::McpResource(McpResourceContextHandle {
+            text_task,
+            server_id,
+            uri,
+            name,
+            context_id: self.next_context_id.post_inc(),
+        });
+
+        self.insert_context(context.clone(), cx);
+        Some(context)
+    }
+
+    fn handle_context_server_event(
+        &mut self,
+        context_server_store: Entity<ContextServerStore>,
+        event: &context_server_store::Event,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            context_server_store::Event::ResourceUpdated { server_id, uri } => {
+                let key = McpResourceContextHandle::lookup_key(server_id.clone(), uri.clone());
+                let Some(AgentContextHandle::McpResource(handle)) =
+                    self.context_set.get(&key).map(|key| key.as_ref().clone())
+                else {
+                    return;
+                };
+                let server = context_server_store.read(cx).get_running_server(server_id);
+                let text_task = read_mcp_resource(server, uri.clone(), cx);
+                self.notify_when_read(text_task.clone(), cx);
+                let context = AgentContextHandle::McpResource(McpResourceContextHandle {
+                    text_task,
+                    ..handle
+                });
+                self.context_set.replace(AgentContextKey(context));
+                cx.notify();
+            }
+            _ => {}
+        }
+    }
+
+    fn notify_when_read(
+        &self,
+        text_task: Shared<Task<Option<SharedString>>>,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn(async move |this, cx| {
+            text_task.await;
+            this.update(cx, |_, cx| cx.notify()).ok();
+        })
+        .detach();
+    }
+
     pub fn add_image_from_path(
         &mut self,
         project_path: ProjectPath,
@@ -448,6 +547,17 @@ impl ContextStore {
                         self.context_text_thread_paths.remove(path);
                     }
                 }
+                AgentContextHandle::McpResource(mcp_resource_context) => {
+                    if let Some(server) = self.project.upgrade().and_then(|project| {
+                        project
+                            .read(cx)
+                            .context_server_store()
+                            .read(cx)
+                            .get_running_server(&mcp_resource_context.server_id)
+                    }) {
+                        unsubscribe_from_mcp_resource(server, mcp_resource_context.uri.clone(), cx);
+                    }
+                }
                 _ => {}
             }
             cx.emit(ContextStoreEvent::ContextRemoved(key));
@@ -531,6 +641,14 @@ impl ContextStore {
             .contains(&FetchedUrlContext::lookup_key(url.into()))
     }
 
+    pub fn includes_mcp_resource(&self, server_id: &ContextServerId, uri: &Url) -> bool {
+        self.context_set
+            .contains(&McpResourceContextHandle::lookup_key(
+                server_id.clone(),
+                uri.clone(),
+            ))
+    }
+
     pub fn get_url_context(&self, url: SharedString) -> Option<AgentContextHandle> {
         self.context_set
             .get(&FetchedUrlContext::lookup_key(url))
@@ -551,6 +669,7 @@ impl ContextStore {
                 | AgentContextHandle::Thread(_)
                 | AgentContextHandle::TextThread(_)
                 | AgentContextHandle::Rules(_)
+                | AgentContextHandle::McpResource(_)
                 | AgentContextHandle::Image(_) => None,
             })
             .collect()
@@ -561,6 +680,94 @@ impl ContextStore {
     }
 }
 
+/// Reads the text contents of a context server resource. Binary contents are described rather than
+/// included.
+fn read_mcp_resource(
+    server: Option<Arc<ContextServer>>,
+    uri: Url,
+    cx: &App,
+) -> Shared<Task<Option<SharedString>>> {
+    cx.spawn(async move |_cx| {
+        let protocol = server?.client()?;
+        let response = protocol
+            .request::<types::requests::ResourcesRead>(types::ResourcesReadParams {
+                uri,
+                meta: None,
+            })
+            .await
+            .log_err()?;
+
+        let mut text = String::new();
+        for contents in response.contents {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            match contents {
+                types::ResourceContentsType::Text(contents) => text.push_str(&contents.text),
+                types::ResourceContentsType::Blob(contents) => {
+                    text.push_str(&format!(
+                        "[Binary contents of {} ({})]",
+                        contents.uri,
+                        contents.mime_type.as_deref().unwrap_or("unknown type")
+                    ));
+                }
+            }
+        }
+        Some(text.into())
+    })
+    .shared()
+}
+
+/// Asks the server to send `notifications/resources/updated` for the resource, if it supports
+/// resource subscriptions.
+fn subscribe_to_mcp_resource(server: Arc<ContextServer>, uri: Url, cx: &App) {
+    let Some(protocol) = server.client() else {
+        return;
+    };
+    if !supports_resource_subscriptions(&protocol) {
+        return;
+    }
+    cx.spawn(async move |_cx| {
+        protocol
+            .request::<types::requests::ResourcesSubscribe>(types::ResourcesSubscribeParams {
+                uri,
+                meta: None,
+            })
+            .await
+    })
+    .detach_and_log_err(cx);
+}
+
+fn unsubscribe_from_mcp_resource(server: Arc<ContextServer>, uri: Url, cx: &App) {
+    let Some(protocol) = server.client() else {
+        return;
+    };
+    if !supports_resource_subscriptions(&protocol) {
+        return;
+    }
+    cx.spawn(async move |_cx| {
+        protocol
+            .request::<types::requests::ResourcesUnsubscribe>(types::ResourcesUnsubscribeParams {
+                uri,
+                meta: None,
+            })
+            .await
+    })
+    .detach_and_log_err(cx);
+}
+
+fn supports_resource_subscriptions(
+    protocol: &context_server::protocol::InitializedContextServerProtocol,
+) -> bool {
+    protocol
+        .initialize
+        .capabilities
+        .resources
+        .as_ref()
+        .and_then(|resources| resources.subscribe)
+        .unwrap_or(false)
+}
+
 #[derive(Clone)]
 pub enum SuggestedContext {
     File {
@@ -1,6 +1,7 @@
 use crate::{
     agent_profile::AgentProfile,
     context::{AgentContext, AgentContextHandle, ContextLoadResult, LoadedContext},
+    thread_export::{ExportedFileChange, ExportedThread},
     thread_store::{
         SerializedCrease, SerializedLanguageModel, SerializedMessage, SerializedMessageSegment,
         SerializedThread, SerializedToolResult, SerializedToolUse, SharedProjectContext,
@@ -8,13 +9,15 @@ use crate::{
     },
     tool_use::{PendingToolUse, ToolUse, ToolUseMetadata, ToolUseState},
 };
-use agent_settings::{AgentProfileId, AgentSettings, CompletionMode, SUMMARIZE_THREAD_PROMPT};
+use agent_settings::{
+    AgentProfileId, AgentSettings, CompletionMode, SUMMARIZE_THREAD_PROMPT, ToolPermission,
+};
 use anyhow::{Result, anyhow};
-use assistant_tool::{ActionLog, AnyToolCard, Tool, ToolWorkingSet};
+use assistant_tool::{ActionLog, AnyToolCard, Tool, ToolWorkingSet, UniqueToolName};
 use chrono::{DateTime, Utc};
 use client::{ModelRequestUsage, RequestUsage};
 use cloud_llm_client::{CompletionIntent, CompletionRequestStatus, Plan, UsageLimit};
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use feature_flags::{self, FeatureFlagAppExt};
 use futures::{FutureExt, StreamExt as _, future::Shared};
 use git::repository::DiffType;
@@ -251,8 +254,8 @@ pub struct GitState {
 
 #[derive(Clone, Debug)]
 pub struct ThreadCheckpoint {
-    message_id: MessageId,
-    git_checkpoint: GitStoreCheckpoint,
+    pub(crate) message_id: MessageId,
+    pub(crate) git_checkpoint: GitStoreCheckpoint,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -384,6 +387,13 @@ pub struct Thread {
     cumulative_token_usage: TokenUsage,
     exceeded_window_error: Option<ExceededWindowError>,
     tool_use_limit_reached: bool,
+    read_only: bool,
+    file_changes: Vec<ExportedFileChange>,
+    parent_id: Option<ThreadId>,
+    /// The only tools a sub-agent may use, out of the ones its profile enables.
+    allowed_tools: Option<HashSet<String>>,
+    /// The profile of the thread a sub-agent works for, whose permission rules bind the sub-agent too.
+    parent_profile_id: Option<AgentProfileId>,
     feedback: Option<ThreadFeedback>,
     retry_state: Option<RetryState>,
     message_feedback: HashMap<MessageId, ThreadFeedback>,
@@ -485,6 +495,11 @@ impl Thread {
             cumulative_token_usage: TokenUsage::default(),
             exceeded_window_error: None,
             tool_use_limit_reached: false,
+            read_only: false,
+            file_changes: Vec::new(),
+            parent_id: None,
+            allowed_tools: None,
+            parent_profile_id: None,
             feedback: None,
             retry_state: None,
             message_feedback: HashMap::default(),
@@ -498,6 +513,29 @@ impl Thread {
         }
     }
 
+    /// Creates a thread for a sub-agent working on a goal delegated by the thread with
+    /// `parent_id`, whose edits are tracked in the parent's `action_log`. The sub-agent can only
+    /// use the `allowed_tools` its profile enables, and the permission rules of the parent's
+    /// profile apply on top of its own.
+    pub fn new_subagent(
+        parent_id: ThreadId,
+        parent_profile_id: AgentProfileId,
+        action_log: Entity<ActionLog>,
+        allowed_tools: HashSet<String>,
+        project: Entity<Project>,
+        tools: Entity<ToolWorkingSet>,
+        prompt_builder: Arc<PromptBuilder>,
+        system_prompt: SharedProjectContext,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut this = Self::new(project, tools, prompt_builder, system_prompt, cx);
+        this.parent_id = Some(parent_id);
+        this.action_log = action_log;
+        this.allowed_tools = Some(allowed_tools);
+        this.parent_profile_id = Some(parent_profile_id);
+        this
+    }
+
     pub fn deserialize(
         id: ThreadId,
         serialized: SerializedThread,
@@ -544,6 +582,20 @@ impl Thread {
         let profile_id = serialized
             .profile
             .unwrap_or_else(|| AgentSettings::get_global(cx).default_profile.clone());
+        let checkpoints_by_message = serialized
+            .messages
+            .iter()
+            .filter_map(|message| {
+                let git_checkpoint = message.checkpoint.clone()?;
+                Some((
+                    message.id,
+                    ThreadCheckpoint {
+                        message_id: message.id,
+                        git_checkpoint,
+                    },
+                ))
+            })
+            .collect();
 
         Self {
             id,
@@ -596,7 +648,7 @@ impl Thread {
             next_message_id,
             last_prompt_id: PromptId::new(),
             project_context,
-            checkpoints_by_message: HashMap::default(),
+            checkpoints_by_message,
             completion_count: 0,
             pending_completions: Vec::new(),
             last_restore_checkpoint: None,
@@ -611,6 +663,11 @@ impl Thread {
             cumulative_token_usage: serialized.cumulative_token_usage,
             exceeded_window_error: None,
             tool_use_limit_reached: serialized.tool_use_limit_reached,
+            read_only: serialized.read_only,
+            file_changes: serialized.file_changes,
+            parent_id: None,
+            allowed_tools: None,
+            parent_profile_id: None,
             feedback: None,
             message_feedback: HashMap::default(),
             last_auto_capture_at: None,
@@ -639,6 +696,22 @@ impl Thread {
         &self.profile
     }
 
+    /// Returns the tools enabled by the thread's profile that the thread may use.
+    pub fn enabled_tools(&self, cx: &App) -> Vec<(UniqueToolName, Arc<dyn Tool>)> {
+        let mut tools = self.profile.enabled_tools(cx);
+        if let Some(allowed_tools) = &self.allowed_tools {
+            tools.retain(|(name, _)| allowed_tools.contains(&name.to_string()));
+        }
+        tools
+    }
+
+    fn is_tool_enabled(&self, name: &str, tool: &Arc<dyn Tool>, cx: &App) -> bool {
+        self.allowed_tools
+            .as_ref()
+            .is_none_or(|allowed_tools| allowed_tools.contains(name))
+            && self.profile.is_tool_enabled(tool.source(), tool.name(), cx)
+    }
+
     pub fn set_profile(&mut self, id: AgentProfileId, cx: &mut Context<Self>) {
         if &id != self.profile.id() {
             self.profile = AgentProfile::new(id, self.tools.clone());
@@ -921,6 +994,16 @@ impl Thread {
         self.tool_use_limit_reached
     }
 
+    /// Returns whether the thread was imported to be read without being continued.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Returns the thread that delegated a goal to this one, if it's a sub-agent's thread.
+    pub fn parent_id(&self) -> Option<&ThreadId> {
+        self.parent_id.as_ref()
+    }
+
     /// Returns whether all of the tool uses have finished running.
     pub fn all_tools_finished(&self) -> bool {
         // If the only pending tool uses left are the ones with errors, then
@@ -976,8 +1059,7 @@ impl Thread {
         model: Arc<dyn LanguageModel>,
     ) -> Vec<LanguageModelRequestTool> {
         if model.supports_tools() {
-            self.profile
-                .enabled_tools(cx)
+            self.enabled_tools(cx)
                 .into_iter()
                 .filter_map(|(name, tool)| {
                     // Skip tools that cannot be supported
@@ -1094,6 +1176,9 @@ impl Thread {
         checkpoint: Option<GitStoreCheckpoint>,
         cx: &mut Context<Self>,
     ) -> bool {
+        if self.read_only {
+            return false;
+        }
         let Some(message) = self.messages.iter_mut().find(|message| message.id == id) else {
             return false;
         };
@@ -1118,6 +1203,9 @@ impl Thread {
     }
 
     pub fn delete_message(&mut self, id: MessageId, cx: &mut Context<Self>) -> bool {
+        if self.read_only {
+            return false;
+        }
         let Some(index) = self.messages.iter().position(|message| message.id == id) else {
             return false;
         };
@@ -1222,6 +1310,10 @@ impl Thread {
                             })
                             .collect(),
                         is_hidden: message.is_hidden,
+                        checkpoint: this
+                            .checkpoints_by_message
+                            .get(&message.id)
+                            .map(|checkpoint| checkpoint.git_checkpoint.clone()),
                     })
                     .collect(),
                 initial_project_snapshot,
@@ -1239,6 +1331,8 @@ impl Thread {
                 completion_mode: Some(this.completion_mode),
                 tool_use_limit_reached: this.tool_use_limit_reached,
                 profile: Some(this.profile.id().clone()),
+                read_only: this.read_only,
+                file_changes: this.file_changes(cx),
             })
         })
     }
@@ -1258,7 +1352,7 @@ impl Thread {
         window: Option<AnyWindowHandle>,
         cx: &mut Context<Self>,
     ) {
-        if self.remaining_turns == 0 {
+        if self.remaining_turns == 0 || self.read_only {
             return;
         }
 
@@ -1348,6 +1442,7 @@ impl Thread {
             tool_choice: None,
             stop: Vec::new(),
             temperature: AgentSettings::temperature_for_model(&model, cx),
+            max_tokens: None,
             thinking_allowed: true,
         };
 
@@ -1514,6 +1609,7 @@ impl Thread {
             tool_choice: None,
             stop: Vec::new(),
             temperature: AgentSettings::temperature_for_model(model, cx),
+            max_tokens: None,
             thinking_allowed: false,
         };
 
@@ -1585,7 +1681,7 @@ impl Thread {
         let tool_name = Arc::from("project_notifications");
         let tool = self.tools.read(cx).tool(&tool_name, cx)?;
 
-        if !self.profile.is_tool_enabled(tool.source(), tool.name(), cx) {
+        if !self.is_tool_enabled(&tool_name, &tool, cx) {
             return None;
         }
 
@@ -2550,13 +2646,45 @@ impl Thread {
             return self.handle_hallucinated_tool_use(tool_use.id, tool_use.name, window, cx);
         };
 
-        if !self.profile.is_tool_enabled(tool.source(), tool.name(), cx) {
+        if !self.is_tool_enabled(&tool_use.name, &tool, cx) {
             return self.handle_hallucinated_tool_use(tool_use.id, tool_use.name, window, cx);
         }
 
-        if tool.needs_confirmation(&tool_use.input, &self.project, cx)
-            && !AgentSettings::get_global(cx).always_allow_tool_actions
-        {
+        let settings = AgentSettings::get_global(cx);
+        let subject = tool.permission_subject(&tool_use.input);
+        let permission =
+            settings.tool_permission(self.profile.id(), &tool_use.name, subject.as_ref());
+        // A sub-agent can't escape the rules of the thread it works for by using a more permissive profile.
+        let permission = match &self.parent_profile_id {
+            Some(parent_profile_id) => stricter_permission(
+                permission,
+                settings.tool_permission(parent_profile_id, &tool_use.name, subject.as_ref()),
+            ),
+            None => permission,
+        };
+        let needs_confirmation = match permission {
+            Some(ToolPermission::Allow) => false,
+            Some(ToolPermission::Ask) => true,
+            Some(ToolPermission::Deny) => {
+                self.tool_use.insert_tool_output(
+                    tool_use.id.clone(),
+                    tool_use.name,
+                    Err(anyhow!(
+                        "Tool call denied by the user's tool permission rules"
+                    )),
+                    self.configured_model.as_ref(),
+                    self.completion_mode,
+                );
+                self.tool_finished(tool_use.id, None, true, window, cx);
+                return;
+            }
+            None => {
+                tool.needs_confirmation(&tool_use.input, &self.project, cx)
+                    && !settings.always_allow_tool_actions
+            }
+        };
+
+        if needs_confirmation {
             self.tool_use.confirm_tool_use(
                 tool_use.id,
                 tool_use.ui_text,
@@ -2586,7 +2714,7 @@ impl Thread {
         window: Option<AnyWindowHandle>,
         cx: &mut Context<Thread>,
     ) {
-        let available_tools = self.profile.enabled_tools(cx);
+        let available_tools = self.enabled_tools(cx);
 
         let tool_list = available_tools
             .iter()
@@ -2810,7 +2938,6 @@ impl Thread {
         let client = self.project.read(cx).client();
 
         let enabled_tool_names: Vec<String> = self
-            .profile
             .enabled_tools(cx)
             .iter()
             .map(|(name, _)| name.clone().into())
@@ -3093,9 +3220,57 @@ impl Thread {
             }
         }
 
+        let file_changes = self.file_changes(cx);
+        if !file_changes.is_empty() {
+            writeln!(markdown, "## Changes\n")?;
+            for file_change in file_changes {
+                writeln!(markdown, "### {}\n", file_change.path)?;
+                writeln!(markdown, "```diff\n{}\n```\n", file_change.diff.trim_end())?;
+            }
+        }
+
         Ok(String::from_utf8_lossy(&markdown).to_string())
     }
 
+    /// Returns the diffs of the files changed by the agent: the ones saved with the thread,
+    /// updated with the changes that haven't been reviewed yet.
+    pub fn file_changes(&self, cx: &App) -> Vec<ExportedFileChange> {
+        let mut file_changes = self.file_changes.clone();
+        for (buffer, diff) in self.action_log.read(cx).changed_buffers(cx) {
+            let buffer = buffer.read(cx);
+            let Some(file) = buffer.file() else {
+                continue;
+            };
+            let path = file.full_path(cx).to_string_lossy().into_owned();
+            let old_text = diff.read(cx).base_text_string().unwrap_or_default();
+            let diff = language::unified_diff(&old_text, &buffer.text());
+            match file_changes
+                .iter_mut()
+                .find(|file_change| file_change.path == path)
+            {
+                Some(file_change) => file_change.diff = diff,
+                None => file_changes.push(ExportedFileChange { path, diff }),
+            }
+        }
+        file_changes
+    }
+
+    /// Exports this thread with its file changes, to be shared outside of the threads database.
+    pub fn export(&self, cx: &mut Context<Self>) -> Task<Result<ExportedThread>> {
+        let serialized = self.serialize(cx);
+        cx.spawn(async move |_, _| {
+            let mut thread = serialized.await?;
+            // The file changes are exported next to the thread, to be read without the thread.
+            let file_changes = std::mem::take(&mut thread.file_changes);
+            Ok(ExportedThread {
+                version: ExportedThread::VERSION.to_string(),
+                exported_at: Utc::now(),
+                thread,
+                file_changes,
+            })
+        })
+    }
+
     pub fn keep_edits_in_range(
         &mut self,
         buffer: Entity<language::Buffer>,
@@ -3353,6 +3528,21 @@ pub enum ThreadEvent {
 
 impl EventEmitter<ThreadEvent> for Thread {}
 
+/// Returns the stricter of two permissions: denying over asking, over falling back to the tool's
+/// own confirmation when no rule matches, over allowing.
+fn stricter_permission(
+    a: Option<ToolPermission>,
+    b: Option<ToolPermission>,
+) -> Option<ToolPermission> {
+    let strictness = |permission: Option<ToolPermission>| match permission {
+        Some(ToolPermission::Allow) => 0,
+        None => 1,
+        Some(ToolPermission::Ask) => 2,
+        Some(ToolPermission::Deny) => 3,
+    };
+    if strictness(a) >= strictness(b) { a } else { b }
+}
+
 struct PendingCompletion {
     id: usize,
     queue_state: QueueState,
@@ -3363,7 +3553,10 @@ struct PendingCompletion {
 mod tests {
     use super::*;
     use crate::{
-        context::load_context, context_store::ContextStore, thread_store, thread_store::ThreadStore,
+        context::load_context,
+        context_store::ContextStore,
+        thread_store,
+        thread_store::{ThreadStore, ThreadsDatabase},
     };
 
     // Test-specific constants
@@ -3382,7 +3575,7 @@ mod tests {
         LanguageModelProviderName, LanguageModelToolChoice,
     };
     use parking_lot::Mutex;
-    use project::{FakeFs, Project};
+    use project::{FakeFs, Fs, Project};
     use prompt_store::PromptBuilder;
     use serde_json::json;
     use settings::{Settings, SettingsStore};
@@ -3854,6 +4047,56 @@ fn main() {{
         );
     }
 
+    #[gpui::test]
+    async fn test_subagent_thread(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+
+        let project = create_test_project(
+            cx,
+            json!({"code.rs": "fn main() {\n    println!(\"Hello, world!\");\n}"}),
+        )
+        .await;
+
+        let (_workspace, thread_store, thread, _context_store, _model) =
+            setup_test_environment(cx, project.clone()).await;
+
+        let subagent_thread = thread_store.update(cx, |store, cx| {
+            let allowed_tools = HashSet::from_iter(["read_file".to_string()]);
+            store.create_subagent_thread(&thread, allowed_tools, cx)
+        });
+        cx.read(|cx| {
+            let subagent_thread = subagent_thread.read(cx);
+            assert_eq!(subagent_thread.parent_id(), Some(thread.read(cx).id()));
+            assert_eq!(subagent_thread.action_log(), thread.read(cx).action_log());
+
+            let enabled_tool_names = |thread: &Thread| {
+                thread
+                    .enabled_tools(cx)
+                    .into_iter()
+                    .map(|(name, _)| name.to_string())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(enabled_tool_names(subagent_thread), ["read_file"]);
+            assert!(enabled_tool_names(thread.read(cx)).len() > 1);
+        });
+
+        subagent_thread.update(cx, |thread, cx| {
+            thread.insert_user_message("Hello!", ContextLoadResult::default(), None, vec![], cx);
+        });
+        thread_store
+            .update(cx, |store, cx| store.save_thread(&subagent_thread, cx))
+            .await
+            .unwrap();
+        let subagent_thread_id = cx.read(|cx| subagent_thread.read(cx).id().clone());
+        thread_store.read_with(cx, |store, _| {
+            assert!(
+                store
+                    .reverse_chronological_threads()
+                    .all(|thread| thread.id != subagent_thread_id)
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_serializing_thread_profile(cx: &mut TestAppContext) {
         init_test_settings(cx);
@@ -5430,6 +5673,189 @@ fn main() {{
         cx.run_until_parked();
     }
 
+    #[gpui::test]
+    async fn test_import_thread(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+        let threads_dir = tempfile::tempdir().unwrap();
+        cx.update(|cx| ThreadsDatabase::init_test(threads_dir.path().to_path_buf(), cx));
+        let project = create_test_project(cx, json!({})).await;
+        let (_, thread_store, thread, _, _) = setup_test_environment(cx, project.clone()).await;
+
+        thread.update(cx, |thread, cx| {
+            thread.insert_user_message(
+                "Please explain this code",
+                ContextLoadResult::default(),
+                None,
+                Vec::new(),
+                cx,
+            );
+        });
+        let exported_json = thread
+            .update(cx, |thread, cx| thread.export(cx))
+            .await
+            .unwrap()
+            .to_json()
+            .unwrap();
+        let file_change = ExportedFileChange {
+            path: "test/code.rs".into(),
+            diff: "@@ -1 +1 @@\n-a\n+b\n".into(),
+        };
+
+        let (_, cx) = cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        for read_only in [true, false] {
+            let mut exported_thread = ExportedThread::from_json(exported_json.as_bytes()).unwrap();
+            exported_thread.file_changes = vec![file_change.clone()];
+            let id = thread_store
+                .update(cx, |store, cx| {
+                    store.import_thread(exported_thread, read_only, cx)
+                })
+                .await
+                .unwrap();
+            let imported_thread = thread_store
+                .update_in(cx, |store, window, cx| store.open_thread(&id, window, cx))
+                .await
+                .unwrap();
+
+            imported_thread.update(cx, |thread, cx| {
+                assert_eq!(thread.is_read_only(), read_only);
+                assert_eq!(thread.text(), "User:\nPlease explain this code\n");
+                assert_eq!(thread.file_changes(cx), [file_change.clone()]);
+
+                let message_id = thread.messages().next().unwrap().id;
+                assert_eq!(thread.delete_message(message_id, cx), !read_only);
+            });
+
+            let reexported_thread = imported_thread
+                .update(cx, |thread, cx| thread.export(cx))
+                .await
+                .unwrap();
+            assert_eq!(reexported_thread.thread.read_only, read_only);
+            assert_eq!(reexported_thread.file_changes, [file_change.clone()]);
+        }
+    }
+
+    #[gpui::test]
+    async fn test_thread_branches(cx: &mut TestAppContext) {
+        init_test_settings(cx);
+        let threads_dir = tempfile::tempdir().unwrap();
+        cx.update(|cx| ThreadsDatabase::init_test(threads_dir.path().to_path_buf(), cx));
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/test"), json!({".git": {}, "code.rs": "one"}))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/test").as_ref()], cx).await;
+        cx.run_until_parked();
+        let (_, thread_store, thread, _, _) = setup_test_environment(cx, project.clone()).await;
+        let git_store = project.read_with(cx, |project, _| project.git_store().clone());
+        let (_, cx) = cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+
+        let code_path = path!("/test/code.rs");
+        let mut message_ids = Vec::new();
+        for (text, next_contents) in [("First", "two"), ("Second", "three")] {
+            let checkpoint = git_store
+                .update(cx, |git_store, cx| git_store.checkpoint(cx))
+                .await
+                .unwrap();
+            let message_id = thread.update(cx, |thread, cx| {
+                let message_id = thread.insert_user_message(
+                    text,
+                    ContextLoadResult::default(),
+                    None,
+                    vec![],
+                    cx,
+                );
+                thread.edit_message(
+                    message_id,
+                    Role::User,
+                    vec![MessageSegment::Text(text.into())],
+                    vec![],
+                    None,
+                    Some(checkpoint),
+                    cx,
+                );
+                message_id
+            });
+            message_ids.push(message_id);
+            fs.insert_file(code_path, next_contents.into()).await;
+        }
+        let first_checkpoint = thread.read_with(cx, |thread, _| {
+            thread.checkpoint_for_message(message_ids[0]).unwrap()
+        });
+        thread_store
+            .update(cx, |store, cx| store.save_thread(&thread, cx))
+            .await
+            .unwrap();
+        let parent_id = thread.read_with(cx, |thread, _| thread.id().clone());
+
+        // Branching before the second message restores the project to its checkpoint, and keeps
+        // the checkpoints of the messages before it.
+        let branch_id = thread_store
+            .update(cx, |store, cx| {
+                store.branch_thread(&thread, message_ids[1], cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(fs.load(code_path.as_ref()).await.unwrap(), "two");
+        let branch = thread_store
+            .update_in(cx, |store, window, cx| {
+                store.open_thread(&branch_id, window, cx)
+            })
+            .await
+            .unwrap();
+        branch.read_with(cx, |branch, _| {
+            assert_eq!(branch.text(), "User:\nFirst\n");
+            assert_eq!(
+                branch
+                    .checkpoint_for_message(message_ids[0])
+                    .map(|checkpoint| checkpoint.git_checkpoint),
+                Some(first_checkpoint.git_checkpoint)
+            );
+        });
+        thread_store.read_with(cx, |store, _| {
+            let tree = store
+                .branch_tree(&branch_id)
+                .into_iter()
+                .map(|(depth, thread)| (depth, thread.id.clone()))
+                .collect::<Vec<_>>();
+            assert_eq!(tree, [(0, parent_id.clone()), (1, branch_id.clone())]);
+            assert_eq!(store.branch_tree(&parent_id).len(), 2);
+        });
+
+        // Switching branches records the state of the branch being left, and restores the state of
+        // the other one.
+        fs.insert_file(code_path, "branch".into()).await;
+        thread_store
+            .update(cx, |store, cx| store.switch_branch(&branch, &parent_id, cx))
+            .await
+            .unwrap();
+        assert_eq!(fs.load(code_path.as_ref()).await.unwrap(), "three");
+        thread_store
+            .update(cx, |store, cx| store.switch_branch(&thread, &branch_id, cx))
+            .await
+            .unwrap();
+        assert_eq!(fs.load(code_path.as_ref()).await.unwrap(), "branch");
+
+        // The checkpoints are anchored by refs, which are deleted along with their thread.
+        let checkpoint_refs = |thread_id: &ThreadId| {
+            let prefix = format!("refs/zed/checkpoints/{thread_id}/");
+            fs.with_git_state(path!("/test/.git").as_ref(), false, |state| {
+                state
+                    .refs
+                    .keys()
+                    .filter(|name| name.starts_with(&prefix))
+                    .count()
+            })
+            .unwrap()
+        };
+        assert_eq!(checkpoint_refs(&parent_id), 4);
+        assert_eq!(checkpoint_refs(&branch_id), 3);
+        thread_store
+            .update(cx, |store, cx| store.delete_thread(&branch_id, cx))
+            .await
+            .unwrap();
+        assert_eq!(checkpoint_refs(&branch_id), 0);
+        assert_eq!(checkpoint_refs(&parent_id), 4);
+    }
+
     fn init_test_settings(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
@@ -0,0 +1,96 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::SerializedThread;
+
+/// A thread exported to a file, to be shared outside of the threads database, like attached to a
+/// code review, and imported back into a [`crate::ThreadStore`].
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ExportedThread {
+    pub version: String,
+    pub exported_at: DateTime<Utc>,
+    pub thread: SerializedThread,
+    /// The changes the agent made to files in the thread, which are informational only and aren't
+    /// applied back when importing.
+    #[serde(default)]
+    pub file_changes: Vec<ExportedFileChange>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct ExportedFileChange {
+    pub path: String,
+    /// The unified diff of the file's changes.
+    pub diff: String,
+}
+
+#[derive(Deserialize)]
+struct ExportedThreadJson {
+    version: String,
+    exported_at: DateTime<Utc>,
+    thread: serde_json::Value,
+    #[serde(default)]
+    file_changes: Vec<ExportedFileChange>,
+}
+
+impl ExportedThread {
+    pub const VERSION: &'static str = "1";
+
+    pub fn from_json(json: &[u8]) -> Result<Self> {
+        let exported_thread_json = serde_json::from_slice::<serde_json::Value>(json)?;
+        match exported_thread_json.get("version") {
+            Some(serde_json::Value::String(version)) if version == Self::VERSION => {
+                let exported_thread =
+                    serde_json::from_value::<ExportedThreadJson>(exported_thread_json)?;
+                // The thread has the serialization format of the Zed version that exported it.
+                let thread =
+                    SerializedThread::from_json(&serde_json::to_vec(&exported_thread.thread)?)?;
+                Ok(Self {
+                    version: exported_thread.version,
+                    exported_at: exported_thread.exported_at,
+                    thread,
+                    file_changes: exported_thread.file_changes,
+                })
+            }
+            version => anyhow::bail!("unrecognized exported thread version: {version:?}"),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_exported_thread_from_json() {
+        let exported_at = Utc::now();
+        let json = json!({
+            "version": ExportedThread::VERSION,
+            "exported_at": exported_at,
+            "thread": {
+                "summary": "Test conversation",
+                "updated_at": exported_at,
+                "messages": [],
+            },
+            "file_changes": [{ "path": "project/src/main.rs", "diff": "@@ -1 +1 @@\n-a\n+b\n" }],
+        });
+
+        let exported_thread =
+            ExportedThread::from_json(&serde_json::to_vec(&json).unwrap()).unwrap();
+        assert_eq!(exported_thread.thread.version, SerializedThread::VERSION);
+        assert_eq!(exported_thread.thread.summary.as_ref(), "Test conversation");
+        assert_eq!(exported_thread.file_changes.len(), 1);
+
+        let round_tripped =
+            ExportedThread::from_json(exported_thread.to_json().unwrap().as_bytes()).unwrap();
+        assert_eq!(round_tripped, exported_thread);
+
+        let json = json!({ "version": "0", "exported_at": exported_at, "thread": {} });
+        assert!(ExportedThread::from_json(&serde_json::to_vec(&json).unwrap()).is_err());
+    }
+}
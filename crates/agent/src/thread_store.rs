@@ -3,12 +3,13 @@ use crate::{
     thread::{
         DetailedSummaryState, ExceededWindowError, MessageId, ProjectSnapshot, Thread, ThreadId,
     },
+    thread_export::{ExportedFileChange, ExportedThread},
 };
 use agent_settings::{AgentProfileId, CompletionMode};
 use anyhow::{Context as _, Result, anyhow};
 use assistant_tool::{Tool, ToolId, ToolWorkingSet};
 use chrono::{DateTime, Utc};
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use context_server::ContextServerId;
 use futures::{
     FutureExt as _, StreamExt as _,
@@ -16,12 +17,13 @@ use futures::{
     future::{self, BoxFuture, Shared},
 };
 use gpui::{
-    App, BackgroundExecutor, Context, Entity, EventEmitter, Global, ReadGlobal, SharedString,
-    Subscription, Task, Window, prelude::*,
+    App, AsyncApp, BackgroundExecutor, Context, Entity, EventEmitter, Global, ReadGlobal,
+    SharedString, Subscription, Task, WeakEntity, Window, prelude::*,
 };
 use indoc::indoc;
 use language_model::{LanguageModelToolResultContent, LanguageModelToolUseId, Role, TokenUsage};
 use project::context_server_store::{ContextServerStatus, ContextServerStore};
+use project::git_store::{GitStore, GitStoreCheckpoint};
 use project::{Project, ProjectItem, ProjectPath, Worktree};
 use prompt_store::{
     ProjectContext, PromptBuilder, PromptId, PromptStore, PromptsUpdatedEvent, RulesFileContext,
@@ -29,12 +31,13 @@ use prompt_store::{
 };
 use serde::{Deserialize, Serialize};
 use sqlez::{
-    bindable::{Bind, Column},
+    bindable::{Bind, Column, StaticColumnCount},
     connection::Connection,
     statement::Statement,
 };
 use std::{
     cell::{Ref, RefCell},
+    fmt::Write as _,
     path::{Path, PathBuf},
     rc::Rc,
     sync::{Arc, Mutex},
@@ -109,6 +112,9 @@ pub struct ThreadStore {
     prompt_store: Option<Entity<PromptStore>>,
     context_server_tool_ids: HashMap<ContextServerId, Vec<ToolId>>,
     threads: Vec<SerializedThreadMetadata>,
+    branches: HashMap<ThreadId, ThreadBranch>,
+    /// The threads created or opened by the store that are still alive.
+    live_threads: HashMap<ThreadId, WeakEntity<Thread>>,
     project_context: SharedProjectContext,
     reload_system_prompt_tx: mpsc::Sender<()>,
     _reload_system_prompt_task: Task<()>,
@@ -195,6 +201,8 @@ impl ThreadStore {
             prompt_store,
             context_server_tool_ids: HashMap::default(),
             threads: Vec::new(),
+            branches: HashMap::default(),
+            live_threads: HashMap::default(),
             project_context: SharedProjectContext::default(),
             reload_system_prompt_tx,
             _reload_system_prompt_task: reload_system_prompt_task,
@@ -403,8 +411,21 @@ impl ThreadStore {
         self.threads.iter()
     }
 
+    /// Returns the thread with the `id` if it's open, like a thread delegating to a sub-agent.
+    pub fn live_thread(&self, id: &ThreadId) -> Option<Entity<Thread>> {
+        self.live_threads.get(id)?.upgrade()
+    }
+
+    fn register_thread(&mut self, thread: Entity<Thread>, cx: &App) -> Entity<Thread> {
+        self.live_threads
+            .retain(|_, thread| thread.upgrade().is_some());
+        self.live_threads
+            .insert(thread.read(cx).id().clone(), thread.downgrade());
+        thread
+    }
+
     pub fn create_thread(&mut self, cx: &mut Context<Self>) -> Entity<Thread> {
-        cx.new(|cx| {
+        let thread = cx.new(|cx| {
             Thread::new(
                 self.project.clone(),
                 self.tools.clone(),
@@ -412,7 +433,41 @@ impl ThreadStore {
                 self.project_context.clone(),
                 cx,
             )
-        })
+        });
+        self.register_thread(thread, cx)
+    }
+
+    /// Creates the thread of a sub-agent delegated a goal by the `parent` thread, which isn't
+    /// saved to the history, can only use the `allowed_tools` and is bound by the permission
+    /// rules of the parent's profile as well as its own.
+    pub fn create_subagent_thread(
+        &mut self,
+        parent: &Entity<Thread>,
+        allowed_tools: HashSet<String>,
+        cx: &mut Context<Self>,
+    ) -> Entity<Thread> {
+        let (parent_id, parent_profile_id, action_log) = {
+            let parent = parent.read(cx);
+            (
+                parent.id().clone(),
+                parent.profile().id().clone(),
+                parent.action_log().clone(),
+            )
+        };
+        let thread = cx.new(|cx| {
+            Thread::new_subagent(
+                parent_id,
+                parent_profile_id,
+                action_log,
+                allowed_tools,
+                self.project.clone(),
+                self.tools.clone(),
+                self.prompt_builder.clone(),
+                self.project_context.clone(),
+                cx,
+            )
+        });
+        self.register_thread(thread, cx)
     }
 
     pub fn create_thread_from_serialized(
@@ -420,7 +475,7 @@ impl ThreadStore {
         serialized: SerializedThread,
         cx: &mut Context<Self>,
     ) -> Entity<Thread> {
-        cx.new(|cx| {
+        let thread = cx.new(|cx| {
             Thread::deserialize(
                 ThreadId::new(),
                 serialized,
@@ -431,7 +486,8 @@ impl ThreadStore {
                 None,
                 cx,
             )
-        })
+        });
+        self.register_thread(thread, cx)
     }
 
     pub fn open_thread(
@@ -451,7 +507,7 @@ impl ThreadStore {
                 .with_context(|| format!("no thread found with ID: {id:?}"))?;
 
             let thread = this.update_in(cx, |this, window, cx| {
-                cx.new(|cx| {
+                let thread = cx.new(|cx| {
                     Thread::deserialize(
                         id.clone(),
                         thread,
@@ -462,7 +518,8 @@ impl ThreadStore {
                         Some(window),
                         cx,
                     )
-                })
+                });
+                this.register_thread(thread, cx)
             })?;
 
             Ok(thread)
@@ -470,6 +527,11 @@ impl ThreadStore {
     }
 
     pub fn save_thread(&self, thread: &Entity<Thread>, cx: &mut Context<Self>) -> Task<Result<()>> {
+        // Sub-agent transcripts only live in the tool call of their parent thread.
+        if thread.read(cx).parent_id().is_some() {
+            return Task::ready(Ok(()));
+        }
+
         let (metadata, serialized_thread) =
             thread.update(cx, |thread, cx| (thread.id().clone(), thread.serialize(cx)));
 
@@ -483,15 +545,50 @@ impl ThreadStore {
         })
     }
 
+    /// Saves an exported thread as a new thread, which can only be read when `read_only` is set
+    /// and can be continued otherwise.
+    pub fn import_thread(
+        &self,
+        exported_thread: ExportedThread,
+        read_only: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ThreadId>> {
+        let id = ThreadId::new();
+        let serialized_thread = SerializedThread {
+            read_only,
+            file_changes: exported_thread.file_changes,
+            ..exported_thread.thread
+        };
+
+        let database_future = ThreadsDatabase::global_future(cx);
+        cx.spawn(async move |this, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            database.save_thread(id.clone(), serialized_thread).await?;
+
+            this.update(cx, |this, cx| this.reload(cx))?.await?;
+            Ok(id)
+        })
+    }
+
     pub fn delete_thread(&mut self, id: &ThreadId, cx: &mut Context<Self>) -> Task<Result<()>> {
         let id = id.clone();
+        let delete_refs = self.branches.contains_key(&id).then(|| {
+            let git_store = self.project.read(cx).git_store().clone();
+            git_store.update(cx, |git_store, cx| {
+                git_store.delete_refs(checkpoint_refs_prefix(&id), cx)
+            })
+        });
         let database_future = ThreadsDatabase::global_future(cx);
         cx.spawn(async move |this, cx| {
             let database = database_future.await.map_err(|err| anyhow!(err))?;
             database.delete_thread(id.clone()).await?;
+            if let Some(delete_refs) = delete_refs {
+                delete_refs.await.log_err();
+            }
 
             this.update(cx, |this, cx| {
                 this.threads.retain(|thread| thread.id != id);
+                this.branches.remove(&id);
                 cx.notify();
             })
         })
@@ -500,19 +597,254 @@ impl ThreadStore {
     pub fn reload(&self, cx: &mut Context<Self>) -> Task<Result<()>> {
         let database_future = ThreadsDatabase::global_future(cx);
         cx.spawn(async move |this, cx| {
-            let threads = database_future
-                .await
-                .map_err(|err| anyhow!(err))?
-                .list_threads()
-                .await?;
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            let threads = database.list_threads().await?;
+            let branches = database.list_branches().await?;
 
             this.update(cx, |this, cx| {
                 this.threads = threads;
+                this.branches = branches
+                    .into_iter()
+                    .map(|branch| (branch.id.clone(), branch))
+                    .collect();
                 cx.notify();
             })
         })
     }
 
+    pub fn thread_branch(&self, id: &ThreadId) -> Option<&ThreadBranch> {
+        self.branches.get(id)
+    }
+
+    /// Returns the threads of the tree of branches containing the given one, depth-first from
+    /// its root, along with their depth in the tree.
+    pub fn branch_tree(&self, id: &ThreadId) -> Vec<(usize, &SerializedThreadMetadata)> {
+        let mut root_id = id;
+        let mut visited = HashSet::default();
+        while let Some(parent_id) = self
+            .branches
+            .get(root_id)
+            .and_then(|branch| branch.parent_id.as_ref())
+        {
+            if !visited.insert(parent_id) || !self.threads.iter().any(|t| &t.id == parent_id) {
+                break;
+            }
+            root_id = parent_id;
+        }
+
+        let mut tree = Vec::new();
+        let mut stack = vec![(0, root_id)];
+        while let Some((depth, id)) = stack.pop() {
+            let Some(thread) = self.threads.iter().find(|thread| &thread.id == id) else {
+                continue;
+            };
+            tree.push((depth, thread));
+
+            let mut children = self
+                .branches
+                .values()
+                .filter(|branch| branch.parent_id.as_ref() == Some(id))
+                .collect::<Vec<_>>();
+            children.sort_by_key(|branch| std::cmp::Reverse(branch.message_id));
+            stack.extend(children.into_iter().map(|branch| (depth + 1, &branch.id)));
+        }
+        tree
+    }
+
+    /// Creates a new thread with the messages of `thread` preceding `message_id`, and restores
+    /// the project to its checkpoint at that message.
+    pub fn branch_thread(
+        &self,
+        thread: &Entity<Thread>,
+        message_id: MessageId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ThreadId>> {
+        let Some(checkpoint) = thread.read(cx).checkpoint_for_message(message_id) else {
+            return Task::ready(Err(anyhow!("No checkpoint was taken at this message")));
+        };
+        let parent_id = thread.read(cx).id().clone();
+        let parent_branch = self.branches.get(&parent_id).cloned().unwrap_or_else(|| {
+            // The root of the tree starts from the project's state before the thread's first
+            // message, to compare every branch from the same point.
+            let thread = thread.read(cx);
+            let first_checkpoint = thread
+                .messages()
+                .find_map(|message| thread.checkpoint_for_message(message.id));
+            ThreadBranch {
+                id: parent_id.clone(),
+                parent_id: None,
+                message_id: None,
+                base_checkpoint: Some(
+                    first_checkpoint
+                        .unwrap_or_else(|| checkpoint.clone())
+                        .git_checkpoint,
+                ),
+                head_checkpoint: None,
+            }
+        });
+        let branch_number = self
+            .branches
+            .values()
+            .filter(|branch| branch.parent_id.as_ref() == Some(&parent_id))
+            .count()
+            + 1;
+
+        let serialized_thread = thread.update(cx, |thread, cx| thread.serialize(cx));
+        let git_store = self.project.read(cx).git_store().clone();
+        let head_checkpoint = git_store.update(cx, |git_store, cx| git_store.checkpoint(cx));
+        let database_future = ThreadsDatabase::global_future(cx);
+        cx.spawn(async move |this, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            let mut serialized_thread = serialized_thread.await?;
+            let parent_branch = ThreadBranch {
+                head_checkpoint: Some(head_checkpoint.await?),
+                ..parent_branch
+            };
+            let parent_checkpoints = branch_checkpoints(&parent_branch, &serialized_thread);
+            anchor_checkpoints(&git_store, &parent_id, parent_checkpoints, cx).await?;
+            database.save_branch(parent_branch).await?;
+
+            let id = ThreadId::new();
+            serialized_thread
+                .messages
+                .retain(|message| message.id < message_id);
+            serialized_thread.summary =
+                format!("{} (branch {branch_number})", serialized_thread.summary).into();
+            serialized_thread.updated_at = Utc::now();
+            serialized_thread.detailed_summary_state = DetailedSummaryState::default();
+            serialized_thread.tool_use_limit_reached = false;
+            let branch = ThreadBranch {
+                id: id.clone(),
+                parent_id: Some(parent_id),
+                message_id: Some(message_id),
+                base_checkpoint: Some(checkpoint.git_checkpoint.clone()),
+                head_checkpoint: None,
+            };
+            let checkpoints = branch_checkpoints(&branch, &serialized_thread);
+            anchor_checkpoints(&git_store, &id, checkpoints, cx).await?;
+            database.save_thread(id.clone(), serialized_thread).await?;
+            database.save_branch(branch).await?;
+
+            git_store
+                .update(cx, |git_store, cx| {
+                    git_store.restore_checkpoint(checkpoint.git_checkpoint, cx)
+                })?
+                .await?;
+            this.update(cx, |this, cx| this.reload(cx))?.await?;
+            Ok(id)
+        })
+    }
+
+    /// Records the project's state as the head of `thread`'s branch, and restores it to the
+    /// state it was in when the user last left the branch `to`.
+    pub fn switch_branch(
+        &self,
+        thread: &Entity<Thread>,
+        to: &ThreadId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let from = thread.read(cx).id();
+        let Some(from_branch) = self.branches.get(from).cloned() else {
+            return Task::ready(Err(anyhow!("The thread isn't part of a tree of branches")));
+        };
+        let Some(target_checkpoint) = self.branches.get(to).and_then(|branch| {
+            branch
+                .head_checkpoint
+                .clone()
+                .or_else(|| branch.base_checkpoint.clone())
+        }) else {
+            return Task::ready(Err(anyhow!("No checkpoint was recorded for the branch")));
+        };
+
+        let serialized_thread = thread.update(cx, |thread, cx| thread.serialize(cx));
+        let git_store = self.project.read(cx).git_store().clone();
+        let head_checkpoint = git_store.update(cx, |git_store, cx| git_store.checkpoint(cx));
+        let database_future = ThreadsDatabase::global_future(cx);
+        cx.spawn(async move |this, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            let from_branch = ThreadBranch {
+                head_checkpoint: Some(head_checkpoint.await?),
+                ..from_branch
+            };
+            let checkpoints = branch_checkpoints(&from_branch, &serialized_thread.await?);
+            anchor_checkpoints(&git_store, &from_branch.id, checkpoints, cx).await?;
+            database.save_branch(from_branch).await?;
+            git_store
+                .update(cx, |git_store, cx| {
+                    git_store.restore_checkpoint(target_checkpoint, cx)
+                })?
+                .await?;
+            this.update(cx, |this, cx| this.reload(cx))?.await
+        })
+    }
+
+    /// Returns a Markdown document with the diff of each branch in the tree of `thread`, from the
+    /// project's state before the tree's first message to the branch's last recorded state.
+    pub fn compare_branches(
+        &self,
+        thread: &Entity<Thread>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<String>> {
+        let active_id = thread.read(cx).id().clone();
+        let tree = self.branch_tree(&active_id);
+        let Some(base_checkpoint) = tree.first().and_then(|(_, root)| {
+            self.branches
+                .get(&root.id)
+                .and_then(|branch| branch.base_checkpoint.clone())
+        }) else {
+            return Task::ready(Err(anyhow!("The thread isn't part of a tree of branches")));
+        };
+        let branches = tree
+            .into_iter()
+            .map(|(depth, thread)| {
+                let head_checkpoint = self
+                    .branches
+                    .get(&thread.id)
+                    .and_then(|branch| branch.head_checkpoint.clone());
+                (
+                    depth,
+                    thread.id.clone(),
+                    thread.summary.clone(),
+                    head_checkpoint,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let git_store = self.project.read(cx).git_store().clone();
+        let current_checkpoint = git_store.update(cx, |git_store, cx| git_store.checkpoint(cx));
+        cx.spawn(async move |_, cx| {
+            let current_checkpoint = current_checkpoint.await?;
+            let mut markdown = String::new();
+            for (depth, id, summary, head_checkpoint) in branches {
+                let is_active = id == active_id;
+                let heading_level = "#".repeat((depth + 2).min(6));
+                let current = if is_active { " (current)" } else { "" };
+                writeln!(markdown, "{heading_level} {summary}{current}\n")?;
+
+                let head_checkpoint = if is_active {
+                    Some(current_checkpoint.clone())
+                } else {
+                    head_checkpoint
+                };
+                let Some(head_checkpoint) = head_checkpoint else {
+                    writeln!(markdown, "No checkpoint was recorded for this branch.\n")?;
+                    continue;
+                };
+                let diff = git_store
+                    .update(cx, |git_store, cx| {
+                        git_store.diff_checkpoints(base_checkpoint.clone(), head_checkpoint, cx)
+                    })?
+                    .await?;
+                if diff.trim().is_empty() {
+                    writeln!(markdown, "No changes.\n")?;
+                } else {
+                    writeln!(markdown, "```diff\n{}\n```\n", diff.trim_end())?;
+                }
+            }
+            Ok(markdown)
+        })
+    }
+
     fn register_context_server_handlers(&self, cx: &mut Context<Self>) {
         let context_server_store = self.project.read(cx).context_server_store();
         cx.subscribe(&context_server_store, Self::handle_context_server_event)
@@ -547,6 +879,7 @@ impl ThreadStore {
                     }
                 }
             }
+            _ => {}
         }
     }
 
@@ -599,6 +932,66 @@ impl ThreadStore {
     }
 }
 
+/// A thread's place in a tree of threads branched off each other, with the project's state at
+/// both ends of the branch.
+#[derive(Debug, Clone)]
+pub struct ThreadBranch {
+    pub id: ThreadId,
+    /// The thread this one branched off, or `None` for the root of the tree.
+    pub parent_id: Option<ThreadId>,
+    /// The message of the parent thread this one branched off before.
+    pub message_id: Option<MessageId>,
+    /// The project's checkpoint where the branch starts.
+    pub base_checkpoint: Option<GitStoreCheckpoint>,
+    /// The project's checkpoint when the user last left the branch.
+    pub head_checkpoint: Option<GitStoreCheckpoint>,
+}
+
+/// The namespace of the refs that keep git from garbage-collecting the checkpoints of a branched
+/// thread.
+fn checkpoint_refs_prefix(thread_id: &ThreadId) -> String {
+    format!("refs/zed/checkpoints/{thread_id}")
+}
+
+/// Returns the checkpoints a branch can be restored or compared to: the ends of the branch, and
+/// the checkpoints of its thread's messages.
+fn branch_checkpoints(branch: &ThreadBranch, thread: &SerializedThread) -> Vec<GitStoreCheckpoint> {
+    let mut checkpoints = Vec::new();
+    for checkpoint in branch
+        .base_checkpoint
+        .iter()
+        .chain(&branch.head_checkpoint)
+        .chain(
+            thread
+                .messages
+                .iter()
+                .filter_map(|message| message.checkpoint.as_ref()),
+        )
+    {
+        if !checkpoints.contains(checkpoint) {
+            checkpoints.push(checkpoint.clone());
+        }
+    }
+    checkpoints
+}
+
+async fn anchor_checkpoints(
+    git_store: &Entity<GitStore>,
+    thread_id: &ThreadId,
+    checkpoints: Vec<GitStoreCheckpoint>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    let prefix = checkpoint_refs_prefix(thread_id);
+    let tasks = git_store.update(cx, |git_store, cx| {
+        checkpoints
+            .into_iter()
+            .map(|checkpoint| git_store.anchor_checkpoint(checkpoint, &prefix, cx))
+            .collect::<Vec<_>>()
+    })?;
+    future::try_join_all(tasks).await?;
+    Ok(())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializedThreadMetadata {
     pub id: ThreadId,
@@ -630,6 +1023,13 @@ pub struct SerializedThread {
     pub tool_use_limit_reached: bool,
     #[serde(default)]
     pub profile: Option<AgentProfileId>,
+    /// Whether the thread was imported to be read without being continued.
+    #[serde(default)]
+    pub read_only: bool,
+    /// The diffs of the files the agent changed, kept for exporting the thread after its edits
+    /// are reviewed.
+    #[serde(default)]
+    pub file_changes: Vec<ExportedFileChange>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -717,6 +1117,9 @@ pub struct SerializedMessage {
     pub creases: Vec<SerializedCrease>,
     #[serde(default)]
     pub is_hidden: bool,
+    /// The project's checkpoint from before the message was sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<GitStoreCheckpoint>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -777,6 +1180,8 @@ impl LegacySerializedThread {
             completion_mode: None,
             tool_use_limit_reached: false,
             profile: None,
+            read_only: false,
+            file_changes: Vec::new(),
         }
     }
 }
@@ -803,6 +1208,7 @@ impl LegacySerializedMessage {
             context: String::new(),
             creases: Vec::new(),
             is_hidden: false,
+            checkpoint: None,
         }
     }
 }
@@ -834,6 +1240,8 @@ impl ThreadsDatabase {
     const COMPRESSION_LEVEL: i32 = 3;
 }
 
+impl StaticColumnCount for ThreadId {}
+
 impl Bind for ThreadId {
     fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
         self.to_string().bind(statement, start_index)
@@ -848,6 +1256,18 @@ impl Column for ThreadId {
 }
 
 impl ThreadsDatabase {
+    /// Replaces the global database with one in `threads_dir`, so tests don't save threads to
+    /// the user's database.
+    #[cfg(test)]
+    pub(crate) fn init_test(threads_dir: PathBuf, cx: &mut App) {
+        let database = ThreadsDatabase::new(threads_dir, cx.background_executor().clone())
+            .map(Arc::new)
+            .map_err(Arc::new);
+        cx.set_global(GlobalThreadsDatabase(
+            future::ready(database).boxed().shared(),
+        ));
+    }
+
     fn global_future(
         cx: &mut App,
     ) -> Shared<BoxFuture<'static, Result<Arc<ThreadsDatabase>, Arc<anyhow::Error>>>> {
@@ -894,6 +1314,17 @@ impl ThreadsDatabase {
             "})?()
         .map_err(|e| anyhow!("Failed to create threads table: {}", e))?;
 
+        connection.exec(indoc! {"
+                CREATE TABLE IF NOT EXISTS thread_branches (
+                    id TEXT PRIMARY KEY,
+                    parent_id TEXT,
+                    message_id INTEGER,
+                    base_checkpoint TEXT,
+                    head_checkpoint TEXT
+                )
+            "})?()
+        .map_err(|e| anyhow!("Failed to create thread branches table: {}", e))?;
+
         let db = Self {
             executor: executor.clone(),
             connection: Arc::new(Mutex::new(connection)),
@@ -1061,8 +1492,80 @@ impl ThreadsDatabase {
             let mut delete = connection.exec_bound::<ThreadId>(indoc! {"
                 DELETE FROM threads WHERE id = ?
             "})?;
+            delete(id.clone())?;
 
-            delete(id)?;
+            let mut delete_branch = connection.exec_bound::<ThreadId>(indoc! {"
+                DELETE FROM thread_branches WHERE id = ?
+            "})?;
+            delete_branch(id)?;
+
+            Ok(())
+        })
+    }
+
+    pub fn list_branches(&self) -> Task<Result<Vec<ThreadBranch>>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock().unwrap();
+            let mut select = connection.select_bound::<(), (
+                ThreadId,
+                Option<ThreadId>,
+                Option<usize>,
+                Option<String>,
+                Option<String>,
+            )>(indoc! {"
+                SELECT id, parent_id, message_id, base_checkpoint, head_checkpoint FROM thread_branches
+            "})?;
+
+            let parse_checkpoint = |checkpoint: Option<String>| {
+                checkpoint.and_then(|checkpoint| serde_json::from_str(&checkpoint).log_err())
+            };
+            Ok(select(())?
+                .into_iter()
+                .map(
+                    |(id, parent_id, message_id, base_checkpoint, head_checkpoint)| ThreadBranch {
+                        id,
+                        parent_id,
+                        message_id: message_id.map(MessageId),
+                        base_checkpoint: parse_checkpoint(base_checkpoint),
+                        head_checkpoint: parse_checkpoint(head_checkpoint),
+                    },
+                )
+                .collect())
+        })
+    }
+
+    pub fn save_branch(&self, branch: ThreadBranch) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let base_checkpoint = branch
+                .base_checkpoint
+                .map(|checkpoint| serde_json::to_string(&checkpoint))
+                .transpose()?;
+            let head_checkpoint = branch
+                .head_checkpoint
+                .map(|checkpoint| serde_json::to_string(&checkpoint))
+                .transpose()?;
+
+            let connection = connection.lock().unwrap();
+            let mut insert = connection.exec_bound::<(
+                ThreadId,
+                Option<ThreadId>,
+                Option<usize>,
+                Option<String>,
+                Option<String>,
+            )>(indoc! {"
+                INSERT OR REPLACE INTO thread_branches (id, parent_id, message_id, base_checkpoint, head_checkpoint) VALUES (?, ?, ?, ?, ?)
+            "})?;
+            insert((
+                branch.id,
+                branch.parent_id,
+                branch.message_id.map(|message_id| message_id.as_usize()),
+                base_checkpoint,
+                head_checkpoint,
+            ))?;
 
             Ok(())
         })
@@ -1110,7 +1613,8 @@ mod tests {
                     tool_results: vec![],
                     context: "".to_string(),
                     creases: vec![],
-                    is_hidden: false
+                    is_hidden: false,
+                    checkpoint: None,
                 }],
                 version: SerializedThread::VERSION.to_string(),
                 initial_project_snapshot: None,
@@ -1121,7 +1625,9 @@ mod tests {
                 model: None,
                 completion_mode: None,
                 tool_use_limit_reached: false,
-                profile: None
+                profile: None,
+                read_only: false,
+                file_changes: Vec::new()
             }
         )
     }
@@ -1144,6 +1650,7 @@ mod tests {
                     context: "".to_string(),
                     creases: vec![],
                     is_hidden: false,
+                    checkpoint: None,
                 },
                 SerializedMessage {
                     id: MessageId(2),
@@ -1160,6 +1667,7 @@ mod tests {
                     context: "".to_string(),
                     creases: vec![],
                     is_hidden: false,
+                    checkpoint: None,
                 },
                 SerializedMessage {
                     id: MessageId(1),
@@ -1177,6 +1685,7 @@ mod tests {
                     context: "".to_string(),
                     creases: vec![],
                     is_hidden: false,
+                    checkpoint: None,
                 },
             ],
             version: SerializedThreadV0_1_0::VERSION.to_string(),
@@ -1189,6 +1698,8 @@ mod tests {
             completion_mode: None,
             tool_use_limit_reached: false,
             profile: None,
+            read_only: false,
+            file_changes: Vec::new(),
         });
         let upgraded = thread_v0_1_0.upgrade();
 
@@ -1208,7 +1719,8 @@ mod tests {
                         tool_results: vec![],
                         context: "".to_string(),
                         creases: vec![],
-                        is_hidden: false
+                        is_hidden: false,
+                        checkpoint: None,
                     },
                     SerializedMessage {
                         id: MessageId(2),
@@ -1230,6 +1742,7 @@ mod tests {
                         context: "".to_string(),
                         creases: vec![],
                         is_hidden: false,
+                        checkpoint: None,
                     },
                 ],
                 version: SerializedThread::VERSION.to_string(),
@@ -1241,7 +1754,9 @@ mod tests {
                 model: None,
                 completion_mode: None,
                 tool_use_limit_reached: false,
-                profile: None
+                profile: None,
+                read_only: false,
+                file_changes: Vec::new()
             }
         )
     }
@@ -606,6 +606,7 @@ impl Thread {
             tool_choice: None,
             stop: Vec::new(),
             temperature: None,
+            max_tokens: None,
             thinking_allowed: true,
         };
 
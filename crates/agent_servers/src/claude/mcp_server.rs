@@ -49,12 +49,14 @@ impl ClaudeZedMcpServer {
         #[cfg(test)]
         let zed_path = crate::e2e_tests::get_zed_path();
 
+        let socket_path = self
+            .server
+            .socket_path()
+            .context("mcp server is not listening on a socket")?;
+
         Ok(McpServerConfig {
             command: zed_path,
-            args: vec![
-                "--nc".into(),
-                self.server.socket_path().display().to_string(),
-            ],
+            args: vec!["--nc".into(), socket_path.display().to_string()],
             env: None,
         })
     }
@@ -44,6 +44,8 @@ pub struct AgentProfileSettings {
     /// The name of the profile.
     pub name: SharedString,
     pub tools: IndexMap<Arc<str>, bool>,
+    /// Rules deciding the permission of tool calls, checked before the global ones.
+    pub tool_permissions: Vec<crate::ToolPermissionRule>,
     pub enable_all_context_servers: bool,
     pub context_servers: IndexMap<Arc<str>, ContextServerPreset>,
 }
@@ -1,4 +1,5 @@
 mod agent_profile;
+mod tool_permissions;
 
 use std::sync::Arc;
 
@@ -12,6 +13,7 @@ use settings::{Settings, SettingsSources};
 use std::borrow::Cow;
 
 pub use crate::agent_profile::*;
+pub use crate::tool_permissions::*;
 
 pub const SUMMARIZE_THREAD_PROMPT: &str =
     include_str!("../../agent/src/prompts/summarize_thread_prompt.txt");
@@ -63,6 +65,7 @@ pub struct AgentSettings {
     pub default_view: DefaultView,
     pub profiles: IndexMap<AgentProfileId, AgentProfileSettings>,
     pub always_allow_tool_actions: bool,
+    pub tool_permissions: Vec<ToolPermissionRule>,
     pub notify_when_agent_waiting: NotifyWhenAgentWaiting,
     pub play_sound_when_agent_done: bool,
     pub stream_edits: bool,
@@ -105,6 +108,26 @@ impl AgentSettings {
             model,
         });
     }
+
+    /// Returns the permission the rules give a tool call, checking the rules of the
+    /// profile before the global ones. Returns `None` when no rule matches.
+    pub fn tool_permission(
+        &self,
+        profile_id: &AgentProfileId,
+        tool_name: &str,
+        subject: Option<&ToolPermissionSubject>,
+    ) -> Option<ToolPermission> {
+        let profile_rules = self
+            .profiles
+            .get(profile_id)
+            .map(|profile| profile.tool_permissions.as_slice())
+            .unwrap_or_default();
+        tool_permission(
+            profile_rules.iter().chain(&self.tool_permissions),
+            tool_name,
+            subject,
+        )
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
@@ -170,6 +193,13 @@ impl AgentSettingsContent {
         self.always_allow_tool_actions = Some(allow);
     }
 
+    /// Adds a rule before all other global rules, so it takes precedence over them.
+    pub fn add_tool_permission_rule(&mut self, rule: ToolPermissionRule) {
+        self.tool_permissions
+            .get_or_insert_default()
+            .insert(0, rule);
+    }
+
     pub fn set_play_sound_when_agent_done(&mut self, allow: bool) {
         self.play_sound_when_agent_done = Some(allow);
     }
@@ -201,6 +231,7 @@ impl AgentSettingsContent {
             AgentProfileContent {
                 name: profile_settings.name.into(),
                 tools: profile_settings.tools,
+                tool_permissions: profile_settings.tool_permissions,
                 enable_all_context_servers: Some(profile_settings.enable_all_context_servers),
                 context_servers: profile_settings
                     .context_servers
@@ -268,6 +299,12 @@ pub struct AgentSettingsContent {
     ///
     /// Default: false
     always_allow_tool_actions: Option<bool>,
+    /// Rules deciding whether tool calls run without asking, ask for confirmation or are refused.
+    /// The first rule matching a call applies, after the rules of the profile in use.
+    /// Calls no rule matches follow `always_allow_tool_actions`.
+    ///
+    /// Default: []
+    tool_permissions: Option<Vec<ToolPermissionRule>>,
     /// Where to show a popup notification when the agent is waiting for user input.
     ///
     /// Default: "primary_screen"
@@ -384,6 +421,10 @@ pub struct AgentProfileContent {
     pub name: Arc<str>,
     #[serde(default)]
     pub tools: IndexMap<Arc<str>, bool>,
+    /// Rules deciding the permission of tool calls with this profile, checked before the global
+    /// `tool_permissions`.
+    #[serde(default)]
+    pub tool_permissions: Vec<ToolPermissionRule>,
     /// Whether all context servers are enabled by default.
     pub enable_all_context_servers: Option<bool>,
     #[serde(default)]
@@ -446,6 +487,10 @@ impl Settings for AgentSettings {
                 &mut settings.always_allow_tool_actions,
                 value.always_allow_tool_actions,
             );
+            merge(
+                &mut settings.tool_permissions,
+                value.tool_permissions.clone(),
+            );
             merge(
                 &mut settings.notify_when_agent_waiting,
                 value.notify_when_agent_waiting,
@@ -486,6 +531,7 @@ impl Settings for AgentSettings {
                             AgentProfileSettings {
                                 name: profile.name.clone().into(),
                                 tools: profile.tools.clone(),
+                                tool_permissions: profile.tool_permissions.clone(),
                                 enable_all_context_servers: profile
                                     .enable_all_context_servers
                                     .unwrap_or_default(),
@@ -507,6 +553,25 @@ impl Settings for AgentSettings {
             }
         }
 
+        // Compiling the patterns here also spares compiling them for every tool call.
+        let invalid_patterns = settings
+            .tool_permissions
+            .iter()
+            .chain(
+                settings
+                    .profiles
+                    .values()
+                    .flat_map(|profile| &profile.tool_permissions),
+            )
+            .filter_map(|rule| rule.validate().err())
+            .collect::<Vec<_>>();
+        if !invalid_patterns.is_empty() {
+            bail!(
+                "agent.tool_permissions has invalid patterns:\n{}",
+                invalid_patterns.join("\n")
+            );
+        }
+
         Ok(settings)
     }
 
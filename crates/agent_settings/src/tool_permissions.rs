@@ -0,0 +1,622 @@
+use std::sync::{Arc, OnceLock};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Whether a tool call runs right away, waits for the user's confirmation or is refused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolPermission {
+    Allow,
+    Ask,
+    Deny,
+}
+
+/// A rule that decides the permission of the calls to a tool whose input matches its pattern.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ToolPermissionRule {
+    /// The name of the tool, like "edit_file" or "terminal".
+    pub tool: Arc<str>,
+    /// What the tool's input must match for the rule to apply. The rule applies to every
+    /// call of the tool when omitted.
+    ///
+    /// - A glob for the path of "edit_file" and "delete_path", like "src/**/*.rs"
+    /// - A regex for the command of "terminal", like "^cargo (check|test)\\b". Commands
+    ///   chained with `;`, `&&`, `||`, `|`, `$(…)`, backticks or newlines are matched one by one.
+    /// - A domain for "fetch", which also matches its subdomains, like "docs.rs"
+    #[serde(default)]
+    pub pattern: Option<String>,
+    pub permission: ToolPermission,
+    #[serde(skip)]
+    #[schemars(skip)]
+    compiled_pattern: CompiledPatternCache,
+}
+
+/// The part of a tool call's input that [`ToolPermissionRule`] patterns are matched against.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ToolPermissionSubject {
+    Path(String),
+    Command(String),
+    Domain(String),
+}
+
+/// The kinds of [`ToolPermissionSubject`], which decide how a rule's pattern is interpreted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SubjectKind {
+    Path,
+    Command,
+    Domain,
+}
+
+impl SubjectKind {
+    /// Returns the kind of subject the calls to a built-in tool have.
+    fn for_tool(tool_name: &str) -> Option<Self> {
+        match tool_name {
+            "edit_file" | "delete_path" => Some(Self::Path),
+            "terminal" => Some(Self::Command),
+            "fetch" => Some(Self::Domain),
+            _ => None,
+        }
+    }
+}
+
+impl ToolPermissionSubject {
+    fn kind(&self) -> SubjectKind {
+        match self {
+            Self::Path(_) => SubjectKind::Path,
+            Self::Command(_) => SubjectKind::Command,
+            Self::Domain(_) => SubjectKind::Domain,
+        }
+    }
+
+    /// Splits a command subject into the commands chained in it, so rules are matched against
+    /// each of them. Other subjects are matched as they are, with paths normalized.
+    fn parts(&self) -> Vec<Self> {
+        match self {
+            Self::Command(command) => split_command(command)
+                .into_iter()
+                .map(|command| Self::Command(command.to_string()))
+                .collect(),
+            Self::Path(path) => vec![Self::Path(normalize_path(path))],
+            Self::Domain(_) => vec![self.clone()],
+        }
+    }
+}
+
+/// A rule's pattern, compiled for the kind of subject it's matched against.
+#[derive(Debug)]
+enum CompiledPattern {
+    Glob(globset::GlobMatcher),
+    Regex(regex::Regex),
+    Domain(String),
+}
+
+impl CompiledPattern {
+    fn new(pattern: &str, kind: SubjectKind) -> Result<Self, String> {
+        match kind {
+            SubjectKind::Path => globset::Glob::new(pattern)
+                .map(|glob| Self::Glob(glob.compile_matcher()))
+                .map_err(|error| error.to_string()),
+            SubjectKind::Command => regex::Regex::new(pattern)
+                .map(Self::Regex)
+                .map_err(|error| error.to_string()),
+            SubjectKind::Domain => Ok(Self::Domain(pattern.to_lowercase())),
+        }
+    }
+
+    fn is_match(&self, subject: &ToolPermissionSubject) -> bool {
+        match (self, subject) {
+            (Self::Glob(glob), ToolPermissionSubject::Path(path)) => glob.is_match(path),
+            (Self::Regex(regex), ToolPermissionSubject::Command(command)) => {
+                regex.is_match(command)
+            }
+            (Self::Domain(pattern), ToolPermissionSubject::Domain(domain)) => {
+                let domain = domain.to_lowercase();
+                domain == *pattern
+                    || domain
+                        .strip_suffix(pattern.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Holds a rule's compiled pattern once it's first needed, so it isn't compiled for every
+/// tool call. Ignored when comparing rules.
+#[derive(Clone, Debug, Default)]
+struct CompiledPatternCache(Arc<OnceLock<Result<CompiledPattern, String>>>);
+
+impl PartialEq for CompiledPatternCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for CompiledPatternCache {}
+
+impl ToolPermissionRule {
+    pub fn new(
+        tool: impl Into<Arc<str>>,
+        pattern: Option<String>,
+        permission: ToolPermission,
+    ) -> Self {
+        Self {
+            tool: tool.into(),
+            pattern,
+            permission,
+            compiled_pattern: CompiledPatternCache::default(),
+        }
+    }
+
+    /// Returns a rule allowing calls to `tool` with exactly this subject. For a chained
+    /// command, the rule allows each of its commands exactly.
+    pub fn allow_exactly(tool: impl Into<Arc<str>>, subject: &ToolPermissionSubject) -> Self {
+        let pattern = match subject {
+            ToolPermissionSubject::Path(path) => globset::escape(&normalize_path(path)),
+            ToolPermissionSubject::Command(command) => {
+                let commands = split_command(command)
+                    .into_iter()
+                    .map(regex::escape)
+                    .collect::<Vec<_>>();
+                format!("^(?:{})$", commands.join("|"))
+            }
+            ToolPermissionSubject::Domain(domain) => domain.clone(),
+        };
+        Self::new(tool, Some(pattern), ToolPermission::Allow)
+    }
+
+    /// Compiles the rule's pattern, returning a description of the error if it's invalid
+    /// for the rule's tool.
+    pub fn validate(&self) -> Result<(), String> {
+        let (Some(pattern), Some(kind)) =
+            (self.pattern.as_deref(), SubjectKind::for_tool(&self.tool))
+        else {
+            return Ok(());
+        };
+        match self.compiled_pattern(kind) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(format!(
+                "invalid pattern {pattern:?} for the {} tool: {error}",
+                self.tool
+            )),
+        }
+    }
+
+    fn compiled_pattern(&self, kind: SubjectKind) -> &Result<CompiledPattern, String> {
+        self.compiled_pattern
+            .0
+            .get_or_init(|| CompiledPattern::new(self.pattern.as_deref().unwrap_or_default(), kind))
+    }
+
+    /// Returns whether the rule applies to a call of the tool with this subject. Command
+    /// subjects are matched as a whole, see [`tool_permission`] for how chained commands are
+    /// decided.
+    pub fn matches(&self, tool_name: &str, subject: Option<&ToolPermissionSubject>) -> bool {
+        if self.tool.as_ref() != tool_name {
+            return false;
+        }
+        if self.pattern.is_none() {
+            return true;
+        }
+        let Some(subject) = subject else {
+            return false;
+        };
+        match self.compiled_pattern(subject.kind()) {
+            Ok(pattern) => pattern.is_match(subject),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Returns the permission the rules give a tool call, if any rule applies to it.
+///
+/// Each of the commands chained in a terminal command is decided by the first rule matching
+/// it: the call is denied when any command is denied, and only allowed when every command
+/// is allowed.
+pub fn tool_permission<'a>(
+    rules: impl IntoIterator<Item = &'a ToolPermissionRule>,
+    tool_name: &str,
+    subject: Option<&ToolPermissionSubject>,
+) -> Option<ToolPermission> {
+    let rules = rules.into_iter().collect::<Vec<_>>();
+    let permission_of = |subject: Option<&ToolPermissionSubject>| {
+        rules
+            .iter()
+            .find(|rule| rule.matches(tool_name, subject))
+            .map(|rule| rule.permission)
+    };
+
+    let Some(subject) = subject else {
+        return permission_of(None);
+    };
+    let parts = subject.parts();
+    if parts.is_empty() {
+        return permission_of(Some(subject));
+    }
+    let permissions = parts
+        .iter()
+        .map(|part| permission_of(Some(part)))
+        .collect::<Vec<_>>();
+    if permissions.contains(&Some(ToolPermission::Deny)) {
+        Some(ToolPermission::Deny)
+    } else if permissions.contains(&None) {
+        None
+    } else if permissions.contains(&Some(ToolPermission::Ask)) {
+        Some(ToolPermission::Ask)
+    } else {
+        Some(ToolPermission::Allow)
+    }
+}
+
+/// Splits a shell command into the commands chained in it with `;`, `&&`, `||`, `|`, `&`,
+/// newlines, or run by substitutions with `$(…)`, backticks, `<(…)` or `>(…)`. Redirections
+/// become parts of their own, like `> ~/.bashrc`, so the files they write need a rule too.
+fn split_command(command: &str) -> Vec<&str> {
+    const SEPARATORS: [&str; 10] = ["&&", "||", "$(", "<(", ">(", ";", "|", "&", "`", "\n"];
+    const REDIRECTIONS: [&str; 7] = ["&>", ">&", ">>", ">", "<&", "<<", "<"];
+
+    let mut commands = Vec::new();
+    let mut command_start = 0;
+    let mut search_start = 0;
+    while let Some((index, operator, is_redirection)) = SEPARATORS
+        .iter()
+        .map(|separator| (*separator, false))
+        .chain(REDIRECTIONS.iter().map(|redirection| (*redirection, true)))
+        .filter_map(|(operator, is_redirection)| {
+            let index = search_start + command[search_start..].find(operator)?;
+            Some((index, operator, is_redirection))
+        })
+        .min_by_key(|(index, operator, _)| (*index, std::cmp::Reverse(operator.len())))
+    {
+        search_start = index + operator.len();
+        if is_redirection {
+            // The file descriptor a redirection applies to, like the `2` of `2> errors.log`, belongs to it.
+            let preceding = &command[command_start..index];
+            let descriptor = preceding.len()
+                - preceding
+                    .trim_end_matches(|c: char| c.is_ascii_digit())
+                    .len();
+            let before_descriptor = &preceding[..preceding.len() - descriptor];
+            let redirection_start = if before_descriptor.is_empty()
+                || before_descriptor.ends_with(|c: char| c.is_whitespace())
+            {
+                index - descriptor
+            } else {
+                index
+            };
+            commands.push(&command[command_start..redirection_start]);
+            command_start = redirection_start;
+        } else {
+            commands.push(&command[command_start..index]);
+            command_start = search_start;
+        }
+    }
+    commands.push(&command[command_start..]);
+
+    commands
+        .into_iter()
+        .map(|command| command.trim_matches(|c: char| c.is_whitespace() || c == '(' || c == ')'))
+        .filter(|command| !command.is_empty() && !is_descriptor_duplication(command))
+        .collect()
+}
+
+/// Whether the redirection only duplicates or closes file descriptors, like `2>&1`, without touching any file.
+fn is_descriptor_duplication(redirection: &str) -> bool {
+    let redirection = redirection.trim_start_matches(|c: char| c.is_ascii_digit());
+    redirection
+        .strip_prefix(">&")
+        .or_else(|| redirection.strip_prefix("<&"))
+        .is_some_and(|target| {
+            let target = target.trim();
+            target == "-" || (!target.is_empty() && target.chars().all(|c| c.is_ascii_digit()))
+        })
+}
+
+/// Resolves the `.` and `..` components of a path, so a path can't escape the directories
+/// a glob allows, like `project/src/../../etc/passwd`.
+fn normalize_path(path: &str) -> String {
+    let mut components = Vec::new();
+    for component in path.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                if components.last().is_some_and(|last| *last != "..") {
+                    components.pop();
+                } else {
+                    components.push(component);
+                }
+            }
+            _ => components.push(component),
+        }
+    }
+    let normalized = components.join("/");
+    if path.starts_with('/') {
+        format!("/{normalized}")
+    } else {
+        normalized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(tool: &str, pattern: Option<&str>, permission: ToolPermission) -> ToolPermissionRule {
+        ToolPermissionRule::new(tool, pattern.map(Into::into), permission)
+    }
+
+    #[test]
+    fn test_tool_permission() {
+        let rules = [
+            rule("terminal", Some("^rm "), ToolPermission::Deny),
+            rule(
+                "terminal",
+                Some(r"^cargo (check|test)\b"),
+                ToolPermission::Allow,
+            ),
+            rule("terminal", None, ToolPermission::Ask),
+            rule("edit_file", Some("project/src/**"), ToolPermission::Allow),
+            rule("fetch", Some("docs.rs"), ToolPermission::Allow),
+        ];
+        let command = |command: &str| ToolPermissionSubject::Command(command.into());
+        let path = |path: &str| ToolPermissionSubject::Path(path.into());
+        let domain = |domain: &str| ToolPermissionSubject::Domain(domain.into());
+
+        assert_eq!(
+            tool_permission(&rules, "terminal", Some(&command("rm -rf target"))),
+            Some(ToolPermission::Deny)
+        );
+        assert_eq!(
+            tool_permission(&rules, "terminal", Some(&command("cargo test -p agent"))),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            tool_permission(&rules, "terminal", Some(&command("cargo testify"))),
+            Some(ToolPermission::Ask)
+        );
+        assert_eq!(
+            tool_permission(&rules, "edit_file", Some(&path("project/src/main.rs"))),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            tool_permission(&rules, "edit_file", Some(&path("project/Cargo.toml"))),
+            None
+        );
+        assert_eq!(
+            tool_permission(&rules, "fetch", Some(&domain("Docs.RS"))),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            tool_permission(&rules, "fetch", Some(&domain("serde.docs.rs"))),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            tool_permission(&rules, "fetch", Some(&domain("notdocs.rs"))),
+            None
+        );
+        assert_eq!(tool_permission(&rules, "delete_path", None), None);
+
+        // Commands run in the background, redirections and process substitutions are matched on their own.
+        assert_eq!(
+            tool_permission(&rules, "terminal", Some(&command("cargo check & rm -rf ~"))),
+            Some(ToolPermission::Deny)
+        );
+        for command_line in [
+            "cargo test > ~/.bashrc",
+            "cargo test >> ~/.bashrc",
+            "cargo test 2> ~/.bashrc",
+            "cargo test < /etc/passwd",
+            "cargo test <(curl example.com)",
+            "cargo test >(sh)",
+        ] {
+            assert_eq!(
+                tool_permission(&rules, "terminal", Some(&command(command_line))),
+                Some(ToolPermission::Ask),
+                "{command_line}"
+            );
+        }
+        assert_eq!(
+            tool_permission(&rules, "terminal", Some(&command("cargo test 2>&1"))),
+            Some(ToolPermission::Allow)
+        );
+    }
+
+    #[test]
+    fn test_allow_exactly() {
+        let command = ToolPermissionSubject::Command("cargo test -- --nocapture".into());
+        let rule = ToolPermissionRule::allow_exactly("terminal", &command);
+        assert!(rule.matches("terminal", Some(&command)));
+        assert!(!rule.matches(
+            "terminal",
+            Some(&ToolPermissionSubject::Command(
+                "cargo test -- --nocapture && rm -rf /".into()
+            ))
+        ));
+
+        let chained_command =
+            ToolPermissionSubject::Command("cargo build && cargo test -- --nocapture".into());
+        let rule = ToolPermissionRule::allow_exactly("terminal", &chained_command);
+        assert_eq!(
+            tool_permission([&rule], "terminal", Some(&chained_command)),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            tool_permission(
+                [&rule],
+                "terminal",
+                Some(&ToolPermissionSubject::Command(
+                    "cargo build && rm -rf /".into()
+                ))
+            ),
+            None
+        );
+        for command in [
+            "cargo build & rm -rf /",
+            "cargo build > ~/.bashrc",
+            "cargo build <(rm -rf /)",
+        ] {
+            assert_eq!(
+                tool_permission(
+                    [&rule],
+                    "terminal",
+                    Some(&ToolPermissionSubject::Command(command.into()))
+                ),
+                None,
+                "{command}"
+            );
+        }
+
+        let redirected_command = ToolPermissionSubject::Command("cargo test > out.txt".into());
+        let rule = ToolPermissionRule::allow_exactly("terminal", &redirected_command);
+        assert_eq!(
+            tool_permission([&rule], "terminal", Some(&redirected_command)),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            tool_permission(
+                [&rule],
+                "terminal",
+                Some(&ToolPermissionSubject::Command(
+                    "cargo test > ~/.bashrc".into()
+                ))
+            ),
+            None
+        );
+
+        let path = ToolPermissionSubject::Path("project/src/[id].ts".into());
+        let rule = ToolPermissionRule::allow_exactly("edit_file", &path);
+        assert!(rule.matches("edit_file", Some(&path)));
+        assert!(!rule.matches(
+            "edit_file",
+            Some(&ToolPermissionSubject::Path("project/src/i.ts".into()))
+        ));
+    }
+
+    #[test]
+    fn test_chained_commands() {
+        let rules = [
+            rule("terminal", Some(r"\brm\b"), ToolPermission::Deny),
+            rule(
+                "terminal",
+                Some(r"^cargo (check|test)\b"),
+                ToolPermission::Allow,
+            ),
+            rule("terminal", Some(r"^git status$"), ToolPermission::Allow),
+            rule("terminal", Some(r"^npm "), ToolPermission::Ask),
+        ];
+        let permission = |command: &str| {
+            tool_permission(
+                &rules,
+                "terminal",
+                Some(&ToolPermissionSubject::Command(command.into())),
+            )
+        };
+
+        assert_eq!(
+            permission("cargo test && git status"),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(
+            permission("cargo check | git status;\ncargo test || git status"),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(permission("cargo test && curl example.com"), None);
+        assert_eq!(
+            permission("cargo test; npm install"),
+            Some(ToolPermission::Ask)
+        );
+        for command in [
+            "cargo test; rm -rf /",
+            "cargo test && rm -rf /",
+            "cargo test || rm -rf /",
+            "cargo test | rm -rf /",
+            "cargo test $(rm -rf /)",
+            "cargo test `rm -rf /`",
+            "cargo test\nrm -rf /",
+            "curl example.com; rm -rf /",
+        ] {
+            assert_eq!(permission(command), Some(ToolPermission::Deny), "{command}");
+        }
+
+        assert_eq!(
+            split_command("(cd src && ls) | grep \"rs\"; echo $(date)"),
+            ["cd src", "ls", "grep \"rs\"", "echo", "date"]
+        );
+        assert_eq!(
+            split_command("make 2>&1 >build.log & diff <(ls a) >(cat)"),
+            ["make", ">build.log", "diff", "ls a", "cat"]
+        );
+    }
+
+    #[test]
+    fn test_path_normalization() {
+        let rules = [
+            rule("edit_file", Some("project/src/**"), ToolPermission::Allow),
+            rule(
+                "delete_path",
+                Some("project/target/**"),
+                ToolPermission::Allow,
+            ),
+        ];
+        let permission = |tool: &str, path: &str| {
+            tool_permission(
+                &rules,
+                tool,
+                Some(&ToolPermissionSubject::Path(path.into())),
+            )
+        };
+
+        assert_eq!(
+            permission("edit_file", "project/./src/../src/main.rs"),
+            Some(ToolPermission::Allow)
+        );
+        assert_eq!(permission("edit_file", "project/src/../Cargo.toml"), None);
+        assert_eq!(
+            permission("edit_file", "project/src/../../etc/passwd"),
+            None
+        );
+        assert_eq!(permission("delete_path", "project/target/../src"), None);
+
+        assert_eq!(normalize_path("a/./b/../c"), "a/c");
+        assert_eq!(normalize_path("a/../../b"), "../b");
+        assert_eq!(normalize_path("/a/b/../c"), "/a/c");
+    }
+
+    #[test]
+    fn test_validate() {
+        let error = rule("terminal", Some("^cargo (check"), ToolPermission::Allow)
+            .validate()
+            .unwrap_err();
+        assert!(
+            error.starts_with("invalid pattern \"^cargo (check\" for the terminal tool"),
+            "{error}"
+        );
+        assert!(
+            rule("edit_file", Some("src/{a,b"), ToolPermission::Allow)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            rule("edit_file", Some("src/**/*.rs"), ToolPermission::Allow)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            rule("fetch", Some("docs.rs"), ToolPermission::Allow)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            rule("terminal", None, ToolPermission::Ask)
+                .validate()
+                .is_ok()
+        );
+
+        let invalid_rule = rule("terminal", Some("(cargo"), ToolPermission::Allow);
+        assert!(!invalid_rule.matches(
+            "terminal",
+            Some(&ToolPermissionSubject::Command("(cargo".into()))
+        ));
+    }
+}
@@ -1465,6 +1465,8 @@ impl AcpThreadView {
                                 show_summary: true,
                                 show_command: true,
                                 show_rerun: false,
+                                problem_matchers: Vec::new(),
+                                background: None,
                             };
                             workspace
                                 .spawn_in_terminal(spawn_in_terminal, window, cx)
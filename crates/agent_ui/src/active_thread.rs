@@ -10,7 +10,7 @@ use agent::{
     thread_store::RulesLoadingError,
     tool_use::{PendingToolUseStatus, ToolUse},
 };
-use agent_settings::{AgentSettings, NotifyWhenAgentWaiting};
+use agent_settings::{AgentSettings, NotifyWhenAgentWaiting, ToolPermissionRule};
 use anyhow::Context as _;
 use assistant_tool::ToolUseStatus;
 use audio::{Audio, Sound};
@@ -749,6 +749,11 @@ fn open_markdown_link(
             }),
             cx,
         ),
+        Some(MentionLink::McpResource(_, uri)) => {
+            if matches!(uri.scheme(), "http" | "https") {
+                cx.open_url(uri.as_str());
+            }
+        }
         None => cx.open_url(&text),
     }
 }
@@ -992,6 +997,11 @@ impl ActiveThread {
             }
             ThreadEvent::Stopped(reason) => {
                 match reason {
+                    Ok(StopReason::EndTurn | StopReason::MaxTokens)
+                        if self.thread.read(cx).parent_id().is_some() =>
+                    {
+                        // The parent thread carries on with the sub-agent's summary.
+                    }
                     Ok(StopReason::EndTurn | StopReason::MaxTokens) => {
                         let used_tools = self.thread.read(cx).used_tools_since_last_user_message();
                         self.notify_with_sound(
@@ -1362,6 +1372,9 @@ impl ActiveThread {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if self.thread.read(cx).is_read_only() {
+            return;
+        }
         let editor = crate::message_editor::create_editor(
             self.workspace.clone(),
             self.context_store.downgrade(),
@@ -1503,6 +1516,7 @@ impl ActiveThread {
                             &configured_model.model,
                             cx,
                         ),
+                        max_tokens: None,
                         thinking_allowed: true,
                     };
 
@@ -1595,6 +1609,9 @@ impl ActiveThread {
         let Some((message_id, state)) = self.editing_message.take() else {
             return;
         };
+        if self.thread.read(cx).is_read_only() {
+            return;
+        }
 
         let Some(model) = self
             .thread
@@ -1859,6 +1876,7 @@ impl ActiveThread {
 
         let is_generating = thread.is_generating();
         let is_generating_stale = thread.is_generation_stale().unwrap_or(false);
+        let is_read_only = thread.is_read_only();
 
         let loading_dots = (is_generating && is_last_message).then(|| {
             h_flex()
@@ -2103,7 +2121,11 @@ impl ActiveThread {
                                 .shadow_md()
                                 .border_1()
                                 .border_color(colors.border)
-                                .hover(|hover| hover.border_color(colors.text_accent.opacity(0.5)))
+                                .when(!is_read_only, |this| {
+                                    this.hover(|hover| {
+                                        hover.border_color(colors.text_accent.opacity(0.5))
+                                    })
+                                })
                                 .child(
                                     v_flex()
                                         .p_2p5()
@@ -2190,7 +2212,7 @@ impl ActiveThread {
                                             )
                                         }),
                                 )
-                                .on_click(cx.listener({
+                                .when(!is_read_only, |this| this.on_click(cx.listener({
                                     let message_creases = message.creases.clone();
                                     move |this, _, window, cx| {
                                         if let Some(message_text) =
@@ -2216,7 +2238,7 @@ impl ActiveThread {
                                             );
                                         }
                                     }
-                                })),
+                                }))),
                         )
                 }
                 Role::Assistant => v_flex()
@@ -2319,6 +2341,20 @@ impl ActiveThread {
                         restore_checkpoint_button.into_any_element()
                     };
 
+                    let branch_button =
+                        Button::new(("branch-from-message", ix), "Branch From Here")
+                            .icon(IconName::GitBranch)
+                            .icon_size(IconSize::XSmall)
+                            .icon_position(IconPosition::Start)
+                            .label_size(LabelSize::XSmall)
+                            .disabled(is_pending)
+                            .tooltip(Tooltip::text(
+                                "Start a new branch of this thread from before this message",
+                            ))
+                            .on_click(cx.listener(move |_, _, _window, cx| {
+                                cx.emit(ActiveThreadEvent::BranchFromMessage(message_id));
+                            }));
+
                     parent.child(
                         h_flex()
                             .pt_2p5()
@@ -2327,6 +2363,7 @@ impl ActiveThread {
                             .gap_1()
                             .child(ui::Divider::horizontal())
                             .child(restore_checkpoint_button)
+                            .child(branch_button)
                             .child(ui::Divider::horizontal()),
                     )
                 } else {
@@ -2832,6 +2869,17 @@ impl ActiveThread {
             .map(|workspace| workspace.read(cx).app_state().fs.clone());
         let needs_confirmation = matches!(&tool_use.status, ToolUseStatus::NeedsConfirmation);
         let needs_confirmation_tools = tool_use.needs_confirmation;
+        let exact_permission_rule = needs_confirmation
+            .then(|| {
+                self.thread
+                    .read(cx)
+                    .tools()
+                    .read(cx)
+                    .tool(&tool_use.name, cx)
+            })
+            .flatten()
+            .and_then(|tool| tool.permission_subject(&tool_use.input))
+            .map(|subject| ToolPermissionRule::allow_exactly(tool_use.name.as_ref(), &subject));
 
         let status_icons = div().child(match &tool_use.status {
             ToolUseStatus::NeedsConfirmation => {
@@ -3209,6 +3257,51 @@ impl ActiveThread {
                                 .child(
                                     h_flex()
                                         .gap_0p5()
+                                        .when_some(exact_permission_rule, |this, rule| {
+                                            let tool_id = tool_use.id.clone();
+                                            let fs = fs.clone();
+                                            let pattern = rule.pattern.clone().unwrap_or_default();
+                                            this.child(
+                                                Button::new(
+                                                    "always-allow-tool-pattern",
+                                                    "Always Allow This",
+                                                )
+                                                .label_size(LabelSize::Small)
+                                                .icon(IconName::Check)
+                                                .icon_position(IconPosition::Start)
+                                                .icon_size(IconSize::Small)
+                                                .icon_color(Color::Success)
+                                                .tooltip(move |window, cx| {
+                                                    Tooltip::with_meta(
+                                                        "Never ask for permission for this exact call",
+                                                        None,
+                                                        format!("Adds a rule allowing `{pattern}` to your tool permissions"),
+                                                        window,
+                                                        cx,
+                                                    )
+                                                })
+                                                .on_click(cx.listener(
+                                                    move |this, event, window, cx| {
+                                                        if let Some(fs) = fs.clone() {
+                                                            let rule = rule.clone();
+                                                            update_settings_file::<AgentSettings>(
+                                                                fs,
+                                                                cx,
+                                                                move |settings, _| {
+                                                                    settings.add_tool_permission_rule(rule);
+                                                                },
+                                                            );
+                                                        }
+                                                        this.handle_allow_tool(
+                                                            tool_id.clone(),
+                                                            event,
+                                                            window,
+                                                            cx,
+                                                        )
+                                                    },
+                                                )),
+                                            )
+                                        })
                                         .child({
                                             let tool_id = tool_use.id.clone();
                                             Button::new(
@@ -3586,6 +3679,7 @@ impl ActiveThread {
 
 pub enum ActiveThreadEvent {
     EditingMessageTokenCountChanged,
+    BranchFromMessage(MessageId),
 }
 
 impl EventEmitter<ActiveThreadEvent> for ActiveThread {}
@@ -3624,6 +3718,23 @@ pub(crate) fn open_active_thread_as_markdown(
     workspace: Entity<Workspace>,
     window: &mut Window,
     cx: &mut App,
+) -> Task<anyhow::Result<()>> {
+    let thread = thread.read(cx);
+    let markdown = match thread.to_markdown(cx) {
+        Ok(markdown) => markdown,
+        Err(error) => return Task::ready(Err(error)),
+    };
+    let thread_summary = thread.summary().or_default().to_string();
+    open_markdown(markdown, thread_summary, workspace, window, cx)
+}
+
+/// Opens a Markdown document in a new editor of the workspace, which must be local.
+pub(crate) fn open_markdown(
+    markdown: String,
+    title: String,
+    workspace: Entity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
 ) -> Task<anyhow::Result<()>> {
     let markdown_language_task = workspace
         .read(cx)
@@ -3635,27 +3746,22 @@ pub(crate) fn open_active_thread_as_markdown(
         let markdown_language = markdown_language_task.await?;
 
         workspace.update_in(cx, |workspace, window, cx| {
-            let thread = thread.read(cx);
-            let markdown = thread.to_markdown(cx)?;
-            let thread_summary = thread.summary().or_default().to_string();
-
             let project = workspace.project().clone();
 
             if !project.read(cx).is_local() {
-                anyhow::bail!("failed to open active thread as markdown in remote project");
+                anyhow::bail!("failed to open markdown in remote project");
             }
 
             let buffer = project.update(cx, |project, cx| {
                 project.create_local_buffer(&markdown, Some(markdown_language), cx)
             });
-            let buffer =
-                cx.new(|cx| MultiBuffer::singleton(buffer, cx).with_title(thread_summary.clone()));
+            let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx).with_title(title.clone()));
 
             workspace.add_item_to_active_pane(
                 Box::new(cx.new(|cx| {
                     let mut editor =
                         Editor::for_multibuffer(buffer, Some(project.clone()), window, cx);
-                    editor.set_breadcrumb_header(thread_summary);
+                    editor.set_breadcrumb_header(title);
                     editor
                 })),
                 None,
@@ -3752,6 +3858,12 @@ pub(crate) fn open_context(
             cx,
         ),
 
+        AgentContextHandle::McpResource(mcp_resource_context) => {
+            if matches!(mcp_resource_context.uri.scheme(), "http" | "https") {
+                cx.open_url(mcp_resource_context.uri.as_str());
+            }
+        }
+
         AgentContextHandle::Image(_) => {}
     }
 }
@@ -1,5 +1,6 @@
 mod add_llm_provider_modal;
 mod configure_context_server_modal;
+mod context_server_sampling_modal;
 mod manage_profiles_modal;
 mod tool_picker;
 
@@ -36,6 +37,7 @@ use workspace::Workspace;
 use zed_actions::ExtensionCategoryFilter;
 
 pub(crate) use configure_context_server_modal::ConfigureContextServerModal;
+pub(crate) use context_server_sampling_modal::{ContextServerSamplingModal, SamplingStage};
 pub(crate) use manage_profiles_modal::ManageProfilesModal;
 
 use crate::{
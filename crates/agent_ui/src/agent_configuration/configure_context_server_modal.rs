@@ -5,6 +5,7 @@ use std::{
 };
 
 use anyhow::{Context as _, Result};
+use collections::HashMap;
 use context_server::{ContextServerCommand, ContextServerId};
 use editor::{Editor, EditorElement, EditorStyle};
 use gpui::{
@@ -36,6 +37,11 @@ enum ConfigurationTarget {
         id: ContextServerId,
         command: ContextServerCommand,
     },
+    ExistingHttp {
+        id: ContextServerId,
+        url: String,
+        headers: HashMap<String, String>,
+    },
     Extension {
         id: ContextServerId,
         repository_url: Option<SharedString>,
@@ -105,6 +111,16 @@ impl ConfigurationSource {
                     cx,
                 ),
             },
+            ConfigurationTarget::ExistingHttp { id, url, headers } => {
+                ConfigurationSource::Existing {
+                    editor: create_editor(
+                        context_server_http_input(id, url, headers),
+                        jsonc_language,
+                        window,
+                        cx,
+                    ),
+                }
+            }
             ConfigurationTarget::Extension {
                 id,
                 repository_url,
@@ -138,18 +154,14 @@ impl ConfigurationSource {
         }
     }
 
-    fn output(&self, cx: &mut App) -> Result<(ContextServerId, ContextServerSettings)> {
+    /// The configured context server, along with the bearer token to store for it, if any.
+    fn output(
+        &self,
+        cx: &mut App,
+    ) -> Result<(ContextServerId, ContextServerSettings, Option<String>)> {
         match self {
             ConfigurationSource::New { editor } | ConfigurationSource::Existing { editor } => {
-                parse_input(&editor.read(cx).text(cx)).map(|(id, command)| {
-                    (
-                        id,
-                        ContextServerSettings::Custom {
-                            enabled: true,
-                            command,
-                        },
-                    )
-                })
+                parse_input(&editor.read(cx).text(cx))
             }
             ConfigurationSource::Extension {
                 id,
@@ -174,6 +186,7 @@ impl ConfigurationSource {
                         enabled: true,
                         settings,
                     },
+                    None,
                 ))
             }
         }
@@ -211,6 +224,30 @@ fn context_server_input(existing: Option<(ContextServerId, ContextServerCommand)
     )
 }
 
+fn context_server_http_input(
+    id: ContextServerId,
+    url: String,
+    headers: HashMap<String, String>,
+) -> String {
+    let headers = serde_json::to_string(&headers).unwrap();
+    format!(
+        r#"{{
+  /// The name of your MCP server
+  "{}": {{
+    /// The URL the MCP server is served at
+    "url": {},
+    /// The headers to send with every request
+    "headers": {headers},
+    /// The bearer token to authenticate with, stored in the system keychain.
+    /// Leave it out to keep the stored one.
+    "bearer_token": null
+  }}
+}}"#,
+        id.0,
+        serde_json::to_string(&url).unwrap(),
+    )
+}
+
 fn resolve_context_server_extension(
     id: ContextServerId,
     worktree_store: Entity<WorktreeStore>,
@@ -310,6 +347,15 @@ impl ConfigureContextServerModal {
                     id: server_id,
                     command,
                 }),
+                ContextServerSettings::Http {
+                    enabled: _,
+                    url,
+                    headers,
+                } => Some(ConfigurationTarget::ExistingHttp {
+                    id: server_id,
+                    url,
+                    headers,
+                }),
                 ContextServerSettings::Extension { .. } => {
                     match workspace
                         .update(cx, |workspace, cx| {
@@ -372,7 +418,7 @@ impl ConfigureContextServerModal {
             return;
         };
 
-        let (id, settings) = match self.source.output(cx) {
+        let (id, settings, bearer_token) = match self.source.output(cx) {
             Ok(val) => val,
             Err(error) => {
                 self.set_error(error.to_string(), cx);
@@ -382,6 +428,16 @@ impl ConfigureContextServerModal {
 
         self.state = State::Waiting;
 
+        if let (ContextServerSettings::Http { url, .. }, Some(bearer_token)) =
+            (&settings, bearer_token)
+        {
+            self.context_server_store
+                .update(cx, |store, cx| {
+                    store.set_bearer_token(url.clone(), Some(bearer_token), cx)
+                })
+                .detach_and_log_err(cx);
+        }
+
         let existing_server = self.context_server_store.read(cx).get_running_server(&id);
         if existing_server.is_some() {
             self.context_server_store.update(cx, |store, cx| {
@@ -450,13 +506,38 @@ impl ConfigureContextServerModal {
     }
 }
 
-fn parse_input(text: &str) -> Result<(ContextServerId, ContextServerCommand)> {
+fn parse_input(text: &str) -> Result<(ContextServerId, ContextServerSettings, Option<String>)> {
     let value: serde_json::Value = serde_json_lenient::from_str(text)?;
     let object = value.as_object().context("Expected object")?;
     anyhow::ensure!(object.len() == 1, "Expected exactly one key-value pair");
     let (context_server_name, value) = object.into_iter().next().unwrap();
+    let id = ContextServerId(context_server_name.clone().into());
+
+    if value.get("url").is_some() {
+        #[derive(serde::Deserialize)]
+        struct HttpInput {
+            url: String,
+            #[serde(default)]
+            headers: HashMap<String, String>,
+            #[serde(default)]
+            bearer_token: Option<String>,
+        }
+
+        let input: HttpInput = serde_json::from_value(value.clone())?;
+        let settings = ContextServerSettings::Http {
+            enabled: true,
+            url: input.url,
+            headers: input.headers,
+        };
+        return Ok((id, settings, input.bearer_token));
+    }
+
     let command: ContextServerCommand = serde_json::from_value(value.clone())?;
-    Ok((ContextServerId(context_server_name.clone().into()), command))
+    let settings = ContextServerSettings::Custom {
+        enabled: true,
+        command,
+    };
+    Ok((id, settings, None))
 }
 
 impl ModalView for ConfigureContextServerModal {}
@@ -739,6 +820,7 @@ fn wait_for_context_server(
                 _ => {}
             }
         }
+        _ => {}
     });
 
     cx.spawn(async move |_cx| {
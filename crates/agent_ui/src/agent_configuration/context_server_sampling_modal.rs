@@ -0,0 +1,281 @@
+use std::collections::VecDeque;
+
+use context_server::types;
+use editor::{Editor, EditorElement, EditorStyle};
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, TextStyle, prelude::*};
+use project::context_server_store::sampling::{SamplingRequest, SamplingResponse};
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::{KeyBinding, Modal, ModalFooter, ModalHeader, Section, prelude::*};
+use workspace::{ModalView, Workspace};
+
+pub enum SamplingStage {
+    Request(SamplingRequest),
+    Response(SamplingResponse),
+}
+
+enum ActiveStage {
+    Request(SamplingRequest),
+    Response {
+        response: SamplingResponse,
+        editor: Entity<Editor>,
+    },
+}
+
+/// Asks the user to allow a context server to sample their language model, and then to
+/// review the model's answer before it's sent back to the server.
+///
+/// Stages that arrive while the modal is open are queued. Anything still pending when
+/// the modal is dismissed is rejected, because dropping it closes its response channel.
+pub struct ContextServerSamplingModal {
+    active_stage: ActiveStage,
+    pending_stages: VecDeque<SamplingStage>,
+    focus_handle: FocusHandle,
+}
+
+impl ContextServerSamplingModal {
+    pub fn show(
+        workspace: &mut Workspace,
+        stage: SamplingStage,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        if let Some(modal) = workspace.active_modal::<Self>(cx) {
+            modal.update(cx, |modal, _| modal.pending_stages.push_back(stage));
+            return;
+        }
+
+        workspace.toggle_modal(window, cx, |window, cx| {
+            let focus_handle = cx.focus_handle();
+            let active_stage = ActiveStage::new(stage, window, cx);
+            Self {
+                active_stage,
+                pending_stages: VecDeque::new(),
+                focus_handle,
+            }
+        });
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        match &self.active_stage {
+            ActiveStage::Request(request) => request.clone().respond(true),
+            ActiveStage::Response { response, editor } => {
+                response.clone().accept(editor.read(cx).text(cx));
+            }
+        }
+        self.advance(window, cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        match &self.active_stage {
+            ActiveStage::Request(request) => request.clone().respond(false),
+            ActiveStage::Response { response, .. } => response.clone().reject(),
+        }
+        self.advance(window, cx);
+    }
+
+    fn advance(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(stage) = self.pending_stages.pop_front() {
+            self.active_stage = ActiveStage::new(stage, window, cx);
+            self.focus_handle(cx).focus(window);
+            cx.notify();
+        } else {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn render_request(&self, request: &SamplingRequest, cx: &Context<Self>) -> AnyElement {
+        let create_message = &request.request;
+        v_flex()
+            .gap_2()
+            .when_some(
+                create_message.system_prompt.clone(),
+                |this, system_prompt| this.child(render_message("System", system_prompt, cx)),
+            )
+            .children(create_message.messages.iter().map(|message| {
+                let role = match message.role {
+                    types::Role::User => "User",
+                    types::Role::Assistant => "Assistant",
+                };
+                let text = match &message.content {
+                    types::MessageContent::Text { text, .. } => text.clone(),
+                    types::MessageContent::Image { mime_type, .. } => {
+                        format!("[Image ({mime_type})]")
+                    }
+                    types::MessageContent::Audio { mime_type, .. } => {
+                        format!("[Audio ({mime_type})]")
+                    }
+                    types::MessageContent::Resource { resource, .. } => {
+                        format!("[Resource {}]", resource.uri)
+                    }
+                };
+                render_message(role, text, cx)
+            }))
+            .into_any_element()
+    }
+
+    fn render_response(&self, editor: &Entity<Editor>, cx: &Context<Self>) -> AnyElement {
+        div()
+            .p_2()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().colors().border_variant)
+            .bg(cx.theme().colors().editor_background)
+            .child({
+                let settings = ThemeSettings::get_global(cx);
+                let text_style = TextStyle {
+                    color: cx.theme().colors().text,
+                    font_family: settings.buffer_font.family.clone(),
+                    font_fallbacks: settings.buffer_font.fallbacks.clone(),
+                    font_size: settings.buffer_font_size(cx).into(),
+                    font_weight: settings.buffer_font.weight,
+                    line_height: relative(settings.buffer_line_height.value()),
+                    ..Default::default()
+                };
+                EditorElement::new(
+                    editor,
+                    EditorStyle {
+                        background: cx.theme().colors().editor_background,
+                        local_player: cx.theme().players().local(),
+                        text: text_style,
+                        syntax: cx.theme().syntax().clone(),
+                        ..Default::default()
+                    },
+                )
+            })
+            .into_any_element()
+    }
+}
+
+impl ActiveStage {
+    fn new(
+        stage: SamplingStage,
+        window: &mut Window,
+        cx: &mut Context<ContextServerSamplingModal>,
+    ) -> Self {
+        match stage {
+            SamplingStage::Request(request) => ActiveStage::Request(request),
+            SamplingStage::Response(response) => {
+                let editor = cx.new(|cx| {
+                    let mut editor = Editor::auto_height(4, 16, window, cx);
+                    editor.set_text(response.text.clone(), window, cx);
+                    editor
+                });
+                ActiveStage::Response { response, editor }
+            }
+        }
+    }
+}
+
+fn render_message(role: &'static str, text: String, cx: &App) -> impl IntoElement {
+    v_flex()
+        .gap_0p5()
+        .child(Label::new(role).size(LabelSize::Small).color(Color::Muted))
+        .child(
+            div()
+                .text_ui_sm(cx)
+                .text_color(cx.theme().colors().text)
+                .child(text),
+        )
+}
+
+impl EventEmitter<DismissEvent> for ContextServerSamplingModal {}
+
+impl Focusable for ContextServerSamplingModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        match &self.active_stage {
+            ActiveStage::Request(_) => self.focus_handle.clone(),
+            ActiveStage::Response { editor, .. } => editor.focus_handle(cx),
+        }
+    }
+}
+
+impl ModalView for ContextServerSamplingModal {}
+
+impl Render for ContextServerSamplingModal {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle(cx);
+        let (headline, description, confirm_label, content) = match &self.active_stage {
+            ActiveStage::Request(request) => (
+                "Allow Sampling Request?",
+                format!(
+                    "The {} MCP server wants to send these messages to {}.",
+                    request.server_id, request.model_name
+                ),
+                "Allow",
+                self.render_request(request, cx),
+            ),
+            ActiveStage::Response { response, editor } => (
+                "Review Sampled Message",
+                format!(
+                    "{} answered the {} MCP server's request. You can edit the answer before sending it.",
+                    response.model_name, response.server_id
+                ),
+                "Send",
+                self.render_response(editor, cx),
+            ),
+        };
+
+        div()
+            .id("context-server-sampling-modal")
+            .key_context("ContextServerSamplingModal")
+            .track_focus(&self.focus_handle)
+            .w(rems(34.))
+            .elevation_3(cx)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .child(
+                Modal::new("context-server-sampling", None)
+                    .header(
+                        ModalHeader::new()
+                            .headline(headline)
+                            .description(description),
+                    )
+                    .section(
+                        Section::new().child(
+                            div()
+                                .id("sampling-content")
+                                .max_h_96()
+                                .overflow_y_scroll()
+                                .child(content),
+                        ),
+                    )
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Button::new("reject", "Reject")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Cancel,
+                                                &focus_handle,
+                                                window,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _event, window, cx| {
+                                            this.cancel(&menu::Cancel, window, cx)
+                                        })),
+                                )
+                                .child(
+                                    Button::new("confirm", confirm_label)
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Confirm,
+                                                &focus_handle,
+                                                window,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _event, window, cx| {
+                                            this.confirm(&menu::Confirm, window, cx)
+                                        })),
+                                ),
+                        ),
+                    ),
+            )
+    }
+}
@@ -278,6 +278,7 @@ impl PickerDelegate for ToolPickerDelegate {
                     .or_insert_with(|| AgentProfileContent {
                         name: default_profile.name.into(),
                         tools: default_profile.tools,
+                        tool_permissions: default_profile.tool_permissions,
                         enable_all_context_servers: Some(
                             default_profile.enable_all_context_servers,
                         ),
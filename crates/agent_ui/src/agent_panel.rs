@@ -14,14 +14,16 @@ use crate::agent_diff::AgentDiffThread;
 use crate::message_editor::{MAX_EDITOR_LINES, MIN_EDITOR_LINES};
 use crate::ui::NewThreadButton;
 use crate::{
-    AddContextServer, AgentDiffPane, ContinueThread, ContinueWithBurnMode,
-    DeleteRecentlyOpenThread, ExpandMessageEditor, Follow, InlineAssistant, NewTextThread,
-    NewThread, OpenActiveThreadAsMarkdown, OpenAgentDiff, OpenHistory, ResetTrialEndUpsell,
-    ResetTrialUpsell, ToggleBurnMode, ToggleContextPicker, ToggleNavigationMenu, ToggleOptionsMenu,
+    AddContextServer, AgentDiffPane, CompareThreadBranches, ContinueThread, ContinueWithBurnMode,
+    DeleteRecentlyOpenThread, ExpandMessageEditor, ExportThreadAsJson, ExportThreadAsMarkdown,
+    Follow, ImportThread, InlineAssistant, NewTextThread, NewThread, OpenActiveThreadAsMarkdown,
+    OpenAgentDiff, OpenHistory, ResetTrialEndUpsell, ResetTrialUpsell, ToggleBurnMode,
+    ToggleContextPicker, ToggleNavigationMenu, ToggleOptionsMenu,
     acp::AcpThreadView,
     active_thread::{self, ActiveThread, ActiveThreadEvent},
     agent_configuration::{AgentConfiguration, AssistantConfigurationEvent},
     agent_diff::AgentDiff,
+    delegate_tool::DelegateTool,
     message_editor::{MessageEditor, MessageEditorEvent},
     slash_command::SlashCommandCompletionProvider,
     text_thread_editor::{
@@ -32,14 +34,15 @@ use crate::{
     ui::{AgentOnboardingModal, EndTrialUpsell},
 };
 use agent::{
-    Thread, ThreadError, ThreadEvent, ThreadId, ThreadSummary, TokenUsageRatio,
+    ExportedThread, MessageId, Thread, ThreadError, ThreadEvent, ThreadId, ThreadSummary,
+    TokenUsageRatio,
     context_store::ContextStore,
     history_store::{HistoryEntryId, HistoryStore},
     thread_store::{TextThreadStore, ThreadStore},
 };
 use agent_settings::{AgentDockPosition, AgentSettings, CompletionMode, DefaultView};
 use ai_onboarding::AgentPanelOnboarding;
-use anyhow::{Result, anyhow};
+use anyhow::{Context as _, Result, anyhow};
 use assistant_context::{AssistantContext, ContextEvent, ContextSummary};
 use assistant_slash_command::SlashCommandWorkingSet;
 use assistant_tool::ToolWorkingSet;
@@ -51,8 +54,8 @@ use fs::Fs;
 use gpui::{
     Action, Animation, AnimationExt as _, AnyElement, App, AsyncWindowContext, ClipboardItem,
     Corner, DismissEvent, Entity, EventEmitter, ExternalPaths, FocusHandle, Focusable, Hsla,
-    KeyContext, Pixels, Subscription, Task, UpdateGlobal, WeakEntity, prelude::*,
-    pulsating_between,
+    KeyContext, PathPromptOptions, Pixels, PromptLevel, Subscription, Task, UpdateGlobal,
+    WeakEntity, prelude::*, pulsating_between,
 };
 use language::LanguageRegistry;
 use language_model::{
@@ -72,7 +75,8 @@ use ui::{
 };
 use util::ResultExt as _;
 use workspace::{
-    CollaboratorId, DraggedSelection, DraggedTab, ToggleZoom, ToolbarItemView, Workspace,
+    CollaboratorId, DetachAndPromptErr, DraggedSelection, DraggedTab, ToggleZoom, ToolbarItemView,
+    Workspace,
     dock::{DockPosition, Panel, PanelEvent},
 };
 use zed_actions::{
@@ -291,11 +295,18 @@ impl ActiveView {
                     }
                 }
             }),
-            cx.subscribe(&active_thread, |_, _, event, cx| match &event {
-                ActiveThreadEvent::EditingMessageTokenCountChanged => {
-                    cx.notify();
-                }
-            }),
+            cx.subscribe_in(
+                &active_thread,
+                window,
+                |this, _, event, window, cx| match &event {
+                    ActiveThreadEvent::EditingMessageTokenCountChanged => {
+                        cx.notify();
+                    }
+                    ActiveThreadEvent::BranchFromMessage(message_id) => {
+                        this.branch_thread(*message_id, window, cx);
+                    }
+                },
+            ),
             cx.subscribe_in(&active_thread.read(cx).thread().clone(), window, {
                 let editor = editor.clone();
                 move |_, thread, event, window, cx| match event {
@@ -558,6 +569,16 @@ impl AgentPanel {
         let workspace = workspace.weak_handle();
         let weak_self = cx.entity().downgrade();
 
+        let delegate_tool = Arc::new(DelegateTool::new(
+            thread_store.downgrade(),
+            context_store.downgrade(),
+            language_registry.clone(),
+        ));
+        thread_store
+            .read(cx)
+            .tools()
+            .update(cx, |tools, cx| tools.insert_native(delegate_tool, cx));
+
         let message_editor_context_store =
             cx.new(|_cx| ContextStore::new(project.downgrade(), Some(thread_store.downgrade())));
         let inline_assist_context_store =
@@ -1334,6 +1355,175 @@ impl AgentPanel {
         }
     }
 
+    fn export_thread_as_markdown(
+        &mut self,
+        _: &ExportThreadAsMarkdown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ActiveView::Thread { thread, .. } = &self.active_view else {
+            return;
+        };
+        let markdown = thread.read(cx).thread().read(cx).to_markdown(cx);
+        self.save_thread_export(Task::ready(markdown), window, cx);
+    }
+
+    fn export_thread_as_json(
+        &mut self,
+        _: &ExportThreadAsJson,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ActiveView::Thread { thread, .. } = &self.active_view else {
+            return;
+        };
+        let export = thread
+            .read(cx)
+            .thread()
+            .update(cx, |thread, cx| thread.export(cx));
+        let json = cx.spawn(async move |_, _| export.await?.to_json());
+        self.save_thread_export(json, window, cx);
+    }
+
+    fn save_thread_export(
+        &self,
+        contents: Task<Result<String>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let directory = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .find_map(|worktree| Some(worktree.read(cx).as_local()?.abs_path().to_path_buf()))
+            .unwrap_or_else(|| paths::home_dir().clone());
+        let path = cx.prompt_for_new_path(&directory);
+        let fs = self.fs.clone();
+        cx.spawn_in(window, async move |_, _| {
+            let Some(path) = path.await?? else {
+                return Ok(());
+            };
+            fs.atomic_write(path, contents.await?).await
+        })
+        .detach_and_prompt_err("Failed to export thread", window, cx, |_, _, _| None);
+    }
+
+    fn import_thread(&mut self, _: &ImportThread, window: &mut Window, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+        let fs = self.fs.clone();
+        let thread_store = self.thread_store.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+                return Ok(());
+            };
+            let exported_thread = ExportedThread::from_json(fs.load(&path).await?.as_bytes())?;
+
+            let answer = cx
+                .prompt(
+                    PromptLevel::Info,
+                    &format!("Import \"{}\"?", exported_thread.thread.summary),
+                    Some("A read-only thread can be read but not continued."),
+                    &["Import", "Import as Read-Only", "Cancel"],
+                )
+                .await?;
+            let read_only = match answer {
+                0 => false,
+                1 => true,
+                _ => return Ok(()),
+            };
+
+            let thread_id = thread_store
+                .update(cx, |thread_store, cx| {
+                    thread_store.import_thread(exported_thread, read_only, cx)
+                })?
+                .await?;
+            this.update_in(cx, |this, window, cx| {
+                this.open_thread_by_id(&thread_id, window, cx)
+            })?
+            .await
+        })
+        .detach_and_prompt_err("Failed to import thread", window, cx, |_, _, _| None);
+    }
+
+    fn branch_thread(
+        &mut self,
+        message_id: MessageId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ActiveView::Thread { thread, .. } = &self.active_view else {
+            return;
+        };
+        let thread = thread.read(cx).thread().clone();
+        let branch = self.thread_store.update(cx, |thread_store, cx| {
+            thread_store.branch_thread(&thread, message_id, cx)
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let thread_id = branch.await?;
+            this.update_in(cx, |this, window, cx| {
+                this.open_thread_by_id(&thread_id, window, cx)
+            })?
+            .await
+        })
+        .detach_and_prompt_err("Failed to branch thread", window, cx, |_, _, _| None);
+    }
+
+    fn switch_thread_branch(
+        &mut self,
+        thread_id: ThreadId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ActiveView::Thread { thread, .. } = &self.active_view else {
+            return;
+        };
+        let thread = thread.read(cx).thread().clone();
+        if thread.read(cx).id() == &thread_id {
+            return;
+        }
+        let switch = self.thread_store.update(cx, |thread_store, cx| {
+            thread_store.switch_branch(&thread, &thread_id, cx)
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            switch.await?;
+            this.update_in(cx, |this, window, cx| {
+                this.open_thread_by_id(&thread_id, window, cx)
+            })?
+            .await
+        })
+        .detach_and_prompt_err("Failed to switch branch", window, cx, |_, _, _| None);
+    }
+
+    fn compare_thread_branches(
+        &mut self,
+        _: &CompareThreadBranches,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ActiveView::Thread { thread, .. } = &self.active_view else {
+            return;
+        };
+        let thread = thread.read(cx).thread().clone();
+        let title = format!("Branches of {}", thread.read(cx).summary().or_default());
+        let comparison = self.thread_store.update(cx, |thread_store, cx| {
+            thread_store.compare_branches(&thread, cx)
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let markdown = comparison.await?;
+            let workspace = workspace.upgrade().context("workspace was dropped")?;
+            cx.update(|window, cx| {
+                active_thread::open_markdown(markdown, title, workspace, window, cx)
+            })?
+            .await
+        })
+        .detach_and_prompt_err("Failed to compare branches", window, cx, |_, _, _| None);
+    }
+
     fn handle_agent_configuration_event(
         &mut self,
         _entity: &Entity<AgentConfiguration>,
@@ -1887,6 +2077,25 @@ impl AgentPanel {
             | ActiveView::History
             | ActiveView::Configuration => None,
         };
+        let is_thread_active = active_thread.is_some();
+        let thread_branches = match &self.active_view {
+            ActiveView::Thread { thread, .. } => {
+                let thread_id = thread.read(cx).thread().read(cx).id().clone();
+                let tree = self.thread_store.read(cx).branch_tree(&thread_id);
+                if tree.len() > 1 {
+                    tree.into_iter()
+                        .map(|(depth, thread)| {
+                            let label = format!("{}{}", "  ".repeat(depth), thread.summary);
+                            (thread.id.clone(), label, thread.id == thread_id)
+                        })
+                        .collect()
+                } else {
+                    Vec::new()
+                }
+            }
+            _ => Vec::new(),
+        };
+        let panel = cx.entity().downgrade();
 
         let new_thread_menu = PopoverMenu::new("new_thread_menu")
             .trigger_with_tooltip(
@@ -2078,6 +2287,45 @@ impl AgentPanel {
                             .action("Add Custom Server…", Box::new(AddContextServer))
                             .separator();
 
+                        if !thread_branches.is_empty() {
+                            menu = menu.header("Branches");
+                            for (thread_id, label, is_active) in &thread_branches {
+                                let thread_id = thread_id.clone();
+                                let panel = panel.clone();
+                                menu = menu.toggleable_entry(
+                                    label.clone(),
+                                    *is_active,
+                                    IconPosition::Start,
+                                    None,
+                                    move |window, cx| {
+                                        panel
+                                            .update(cx, |panel, cx| {
+                                                panel.switch_thread_branch(
+                                                    thread_id.clone(),
+                                                    window,
+                                                    cx,
+                                                )
+                                            })
+                                            .ok();
+                                    },
+                                );
+                            }
+                            menu = menu
+                                .action("Compare Branches", Box::new(CompareThreadBranches))
+                                .separator();
+                        }
+                        if is_thread_active {
+                            menu = menu
+                                .action(
+                                    "Export Thread as Markdown…",
+                                    Box::new(ExportThreadAsMarkdown),
+                                )
+                                .action("Export Thread as JSON…", Box::new(ExportThreadAsJson));
+                        }
+                        menu = menu
+                            .action("Import Thread…", Box::new(ImportThread))
+                            .separator();
+
                         menu = menu
                             .action("Rules…", Box::new(OpenRulesLibrary::default()))
                             .action("Settings", Box::new(OpenSettings))
@@ -3224,6 +3472,10 @@ impl Render for AgentPanel {
                 this.open_configuration(window, cx);
             }))
             .on_action(cx.listener(Self::open_active_thread_as_markdown))
+            .on_action(cx.listener(Self::export_thread_as_markdown))
+            .on_action(cx.listener(Self::export_thread_as_json))
+            .on_action(cx.listener(Self::import_thread))
+            .on_action(cx.listener(Self::compare_thread_branches))
             .on_action(cx.listener(Self::deploy_rules_library))
             .on_action(cx.listener(Self::open_agent_diff))
             .on_action(cx.listener(Self::go_back))
@@ -10,6 +10,7 @@ mod context_picker;
 mod context_server_configuration;
 mod context_strip;
 mod debug;
+mod delegate_tool;
 mod inline_assistant;
 mod inline_prompt_editor;
 mod language_model_selector;
@@ -102,6 +103,14 @@ actions!(
         AcceptSuggestedContext,
         /// Opens the active thread as a markdown file.
         OpenActiveThreadAsMarkdown,
+        /// Exports the active thread and its file changes to a Markdown file.
+        ExportThreadAsMarkdown,
+        /// Exports the active thread and its file changes to a JSON file that can be imported back.
+        ExportThreadAsJson,
+        /// Imports a thread from a JSON file it was exported to.
+        ImportThread,
+        /// Opens the diffs of the active thread's branches in a Markdown document.
+        CompareThreadBranches,
         /// Opens the agent diff view to review changes.
         OpenAgentDiff,
         /// Keeps the current suggestion or change.
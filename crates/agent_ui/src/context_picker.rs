@@ -1,6 +1,7 @@
 mod completion_provider;
 mod fetch_context_picker;
 pub(crate) mod file_context_picker;
+mod mcp_resource_context_picker;
 mod rules_context_picker;
 mod symbol_context_picker;
 mod thread_context_picker;
@@ -11,6 +12,8 @@ use std::sync::Arc;
 
 use anyhow::{Result, anyhow};
 pub use completion_provider::ContextPickerCompletionProvider;
+use context_server::ContextServerId;
+use context_server::protocol::ServerCapability;
 use editor::display_map::{Crease, CreaseId, CreaseMetadata, FoldId};
 use editor::{Anchor, AnchorRangeExt as _, Editor, ExcerptId, FoldPlaceholder, ToOffset};
 use fetch_context_picker::FetchContextPicker;
@@ -20,7 +23,9 @@ use gpui::{
     App, DismissEvent, Empty, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task,
     WeakEntity,
 };
+use http_client::Url;
 use language::Buffer;
+use mcp_resource_context_picker::McpResourceContextPicker;
 use multi_buffer::MultiBufferRow;
 use paths::contexts_dir;
 use project::{Entry, ProjectPath};
@@ -39,7 +44,7 @@ use workspace::{Workspace, notifications::NotifyResultExt};
 use crate::AgentPanel;
 use agent::{
     ThreadId,
-    context::RULES_ICON,
+    context::{ContextKind, RULES_ICON},
     context_store::ContextStore,
     thread_store::{TextThreadStore, ThreadStore},
 };
@@ -80,6 +85,7 @@ enum ContextPickerMode {
     Fetch,
     Thread,
     Rules,
+    McpResource,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -117,6 +123,7 @@ impl TryFrom<&str> for ContextPickerMode {
             "fetch" => Ok(Self::Fetch),
             "thread" => Ok(Self::Thread),
             "rule" => Ok(Self::Rules),
+            "resource" => Ok(Self::McpResource),
             _ => Err(format!("Invalid context picker mode: {}", value)),
         }
     }
@@ -130,6 +137,7 @@ impl ContextPickerMode {
             Self::Fetch => "fetch",
             Self::Thread => "thread",
             Self::Rules => "rule",
+            Self::McpResource => "resource",
         }
     }
 
@@ -140,6 +148,7 @@ impl ContextPickerMode {
             Self::Fetch => "Fetch",
             Self::Thread => "Threads",
             Self::Rules => "Rules",
+            Self::McpResource => "MCP Resources",
         }
     }
 
@@ -150,6 +159,7 @@ impl ContextPickerMode {
             Self::Fetch => IconName::Globe,
             Self::Thread => IconName::Thread,
             Self::Rules => RULES_ICON,
+            Self::McpResource => ContextKind::McpResource.icon(),
         }
     }
 }
@@ -162,6 +172,7 @@ enum ContextPickerState {
     Fetch(Entity<FetchContextPicker>),
     Thread(Entity<ThreadContextPicker>),
     Rules(Entity<RulesContextPicker>),
+    McpResource(Entity<McpResourceContextPicker>),
 }
 
 pub(super) struct ContextPicker {
@@ -347,6 +358,17 @@ impl ContextPicker {
                         )
                     }));
                 }
+                ContextPickerMode::McpResource => {
+                    self.mode = ContextPickerState::McpResource(cx.new(|cx| {
+                        McpResourceContextPicker::new(
+                            context_picker.clone(),
+                            self.workspace.clone(),
+                            self.context_store.clone(),
+                            window,
+                            cx,
+                        )
+                    }));
+                }
                 ContextPickerMode::Thread => {
                     if let Some((thread_store, text_thread_store)) = self
                         .thread_store
@@ -549,6 +571,7 @@ impl ContextPicker {
             ContextPickerState::Fetch(entity) => entity.update(cx, |_, cx| cx.notify()),
             ContextPickerState::Thread(entity) => entity.update(cx, |_, cx| cx.notify()),
             ContextPickerState::Rules(entity) => entity.update(cx, |_, cx| cx.notify()),
+            ContextPickerState::McpResource(entity) => entity.update(cx, |_, cx| cx.notify()),
         }
     }
 }
@@ -564,6 +587,9 @@ impl Focusable for ContextPicker {
             ContextPickerState::Fetch(fetch_picker) => fetch_picker.focus_handle(cx),
             ContextPickerState::Thread(thread_picker) => thread_picker.focus_handle(cx),
             ContextPickerState::Rules(user_rules_picker) => user_rules_picker.focus_handle(cx),
+            ContextPickerState::McpResource(mcp_resource_picker) => {
+                mcp_resource_picker.focus_handle(cx)
+            }
         }
     }
 }
@@ -582,6 +608,9 @@ impl Render for ContextPicker {
                 ContextPickerState::Rules(user_rules_picker) => {
                     parent.child(user_rules_picker.clone())
                 }
+                ContextPickerState::McpResource(mcp_resource_picker) => {
+                    parent.child(mcp_resource_picker.clone())
+                }
             })
     }
 }
@@ -625,6 +654,23 @@ fn available_context_picker_entries(
         entries.push(ContextPickerEntry::Mode(ContextPickerMode::Rules));
     }
 
+    let has_resource_servers = workspace
+        .read(cx)
+        .project()
+        .read(cx)
+        .context_server_store()
+        .read(cx)
+        .running_servers()
+        .iter()
+        .any(|server| {
+            server
+                .client()
+                .is_some_and(|protocol| protocol.capable(ServerCapability::Resources))
+        });
+    if has_resource_servers {
+        entries.push(ContextPickerEntry::Mode(ContextPickerMode::McpResource));
+    }
+
     entries.push(ContextPickerEntry::Mode(ContextPickerMode::Fetch));
 
     entries
@@ -891,6 +937,7 @@ pub enum MentionLink {
     Thread(ThreadId),
     TextThread(Arc<Path>),
     Rule(UserPromptId),
+    McpResource(ContextServerId, Url),
 }
 
 impl MentionLink {
@@ -900,6 +947,7 @@ impl MentionLink {
     const THREAD: &str = "@thread";
     const FETCH: &str = "@fetch";
     const RULE: &str = "@rule";
+    const MCP_RESOURCE: &str = "@resource";
 
     const TEXT_THREAD_URL_PREFIX: &str = "text-thread://";
 
@@ -912,6 +960,7 @@ impl MentionLink {
             || url.starts_with(Self::SELECTION)
             || url.starts_with(Self::THREAD)
             || url.starts_with(Self::RULE)
+            || url.starts_with(Self::MCP_RESOURCE)
     }
 
     pub fn for_file(file_name: &str, full_path: &str) -> String {
@@ -968,6 +1017,10 @@ impl MentionLink {
         format!("[@{}]({}:{})", rule.title, Self::RULE, rule.prompt_id.0)
     }
 
+    pub fn for_mcp_resource(name: &str, server_id: &ContextServerId, uri: &Url) -> String {
+        format!("[@{}]({}:{}:{})", name, Self::MCP_RESOURCE, server_id, uri)
+    }
+
     pub fn try_parse(link: &str, workspace: &Entity<Workspace>, cx: &App) -> Option<Self> {
         fn extract_project_path_from_link(
             path: &str,
@@ -1034,6 +1087,14 @@ impl MentionLink {
                 let prompt_id = UserPromptId(Uuid::try_parse(argument).ok()?);
                 Some(MentionLink::Rule(prompt_id))
             }
+            Self::MCP_RESOURCE => {
+                let (server_id, uri) = argument.split_once(Self::SEPARATOR)?;
+                let uri = Url::parse(uri).ok()?;
+                Some(MentionLink::McpResource(
+                    ContextServerId(server_id.into()),
+                    uri,
+                ))
+            }
             _ => None,
         }
     }
@@ -23,12 +23,13 @@ use workspace::Workspace;
 
 use agent::{
     Thread,
-    context::{AgentContextHandle, AgentContextKey, RULES_ICON},
+    context::{AgentContextHandle, AgentContextKey, ContextKind, RULES_ICON},
     thread_store::{TextThreadStore, ThreadStore},
 };
 
 use super::fetch_context_picker::fetch_url_content;
 use super::file_context_picker::{FileMatch, search_files};
+use super::mcp_resource_context_picker::{McpResourceContextEntry, search_mcp_resources};
 use super::rules_context_picker::{RulesContextEntry, search_rules};
 use super::symbol_context_picker::SymbolMatch;
 use super::symbol_context_picker::search_symbols;
@@ -45,6 +46,7 @@ pub(crate) enum Match {
     Thread(ThreadMatch),
     Fetch(SharedString),
     Rules(RulesContextEntry),
+    McpResource(McpResourceContextEntry),
     Entry(EntryMatch),
 }
 
@@ -62,6 +64,7 @@ impl Match {
             Match::Symbol(_) => 1.,
             Match::Fetch(_) => 1.,
             Match::Rules(_) => 1.,
+            Match::McpResource(_) => 1.,
         }
     }
 }
@@ -151,6 +154,18 @@ fn search(
             }
         }
 
+        Some(ContextPickerMode::McpResource) => {
+            let search_resources_task =
+                search_mcp_resources(query.clone(), cancellation_flag.clone(), &workspace, cx);
+            cx.background_spawn(async move {
+                search_resources_task
+                    .await
+                    .into_iter()
+                    .map(Match::McpResource)
+                    .collect()
+            })
+        }
+
         None => {
             if query.is_empty() {
                 let mut matches = recent_entries
@@ -523,6 +538,60 @@ impl ContextPickerCompletionProvider {
         }
     }
 
+    fn completion_for_mcp_resource(
+        entry: McpResourceContextEntry,
+        excerpt_id: ExcerptId,
+        source_range: Range<Anchor>,
+        editor: Entity<Editor>,
+        context_store: Entity<ContextStore>,
+    ) -> Option<Completion> {
+        // Templates need their arguments filled in, which only the context picker supports.
+        let McpResourceContextEntry::Resource {
+            server_id,
+            uri,
+            name,
+        } = entry
+        else {
+            return None;
+        };
+        let new_text = format!(
+            "{} ",
+            MentionLink::for_mcp_resource(&name, &server_id, &uri)
+        );
+        let new_text_len = new_text.len();
+        let icon_path: SharedString = ContextKind::McpResource.icon().path().into();
+        Some(Completion {
+            replace_range: source_range.clone(),
+            new_text,
+            label: CodeLabel::plain(name.to_string(), None),
+            documentation: None,
+            insert_text_mode: None,
+            source: project::CompletionSource::Custom,
+            icon_path: Some(icon_path.clone()),
+            confirm: Some(confirm_completion_callback(
+                icon_path,
+                name.clone(),
+                excerpt_id,
+                source_range.start,
+                new_text_len - 1,
+                editor.clone(),
+                context_store.clone(),
+                move |_, cx| {
+                    let context = context_store.update(cx, |context_store, cx| {
+                        context_store.add_mcp_resource(
+                            server_id.clone(),
+                            uri.clone(),
+                            name.clone(),
+                            false,
+                            cx,
+                        )
+                    });
+                    Task::ready(context)
+                },
+            )),
+        })
+    }
+
     fn completion_for_fetch(
         source_range: Range<Anchor>,
         url_to_fetch: SharedString,
@@ -884,6 +953,14 @@ impl CompletionProvider for ContextPickerCompletionProvider {
                             context_store.clone(),
                         )),
 
+                        Match::McpResource(entry) => Self::completion_for_mcp_resource(
+                            entry,
+                            excerpt_id,
+                            source_range.clone(),
+                            editor.clone(),
+                            context_store.clone(),
+                        ),
+
                         Match::Fetch(url) => Some(Self::completion_for_fetch(
                             source_range.clone(),
                             url,
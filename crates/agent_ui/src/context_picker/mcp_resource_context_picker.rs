@@ -0,0 +1,690 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use context_server::ContextServerId;
+use context_server::protocol::ServerCapability;
+use context_server::types;
+use futures::FutureExt as _;
+use futures::future::{self, Shared};
+use fuzzy::StringMatchCandidate;
+use gpui::{
+    App, BackgroundExecutor, DismissEvent, Entity, FocusHandle, Focusable, Task, WeakEntity,
+};
+use http_client::Url;
+use picker::{Picker, PickerDelegate};
+use ui::{ListItem, prelude::*};
+use util::ResultExt as _;
+use workspace::Workspace;
+
+use crate::context_picker::ContextPicker;
+use agent::context::ContextKind;
+use agent::context_store::{self, ContextStore};
+
+pub struct McpResourceContextPicker {
+    picker: Entity<Picker<McpResourceContextPickerDelegate>>,
+}
+
+impl McpResourceContextPicker {
+    pub fn new(
+        context_picker: WeakEntity<ContextPicker>,
+        workspace: WeakEntity<Workspace>,
+        context_store: WeakEntity<context_store::ContextStore>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let entries_task = match workspace.upgrade() {
+            Some(workspace) => list_mcp_resources(&workspace, true, cx),
+            None => Task::ready(Vec::new()),
+        };
+        let entries_task = cx
+            .spawn(async move |_, _| Arc::new(entries_task.await))
+            .shared();
+        let delegate = McpResourceContextPickerDelegate::new(
+            entries_task,
+            context_picker,
+            workspace,
+            context_store,
+        );
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+
+        McpResourceContextPicker { picker }
+    }
+}
+
+impl Focusable for McpResourceContextPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for McpResourceContextPicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        self.picker.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum McpResourceContextEntry {
+    Resource {
+        server_id: ContextServerId,
+        uri: Url,
+        name: SharedString,
+    },
+    Template {
+        server_id: ContextServerId,
+        uri_template: SharedString,
+        name: SharedString,
+    },
+}
+
+impl McpResourceContextEntry {
+    pub fn name(&self) -> &SharedString {
+        match self {
+            Self::Resource { name, .. } => name,
+            Self::Template { name, .. } => name,
+        }
+    }
+}
+
+/// A resource template whose variables are being filled in, one at a time.
+struct TemplateArguments {
+    server_id: ContextServerId,
+    uri_template: SharedString,
+    name: SharedString,
+    variables: Vec<String>,
+    values: Vec<(String, String)>,
+}
+
+impl TemplateArguments {
+    fn current_variable(&self) -> Option<&String> {
+        self.variables.get(self.values.len())
+    }
+
+    fn is_last_variable(&self) -> bool {
+        self.values.len() + 1 >= self.variables.len()
+    }
+}
+
+enum McpResourceMatch {
+    Entry(McpResourceContextEntry),
+    ArgumentValue(String),
+}
+
+pub struct McpResourceContextPickerDelegate {
+    entries_task: Shared<Task<Arc<Vec<McpResourceContextEntry>>>>,
+    context_picker: WeakEntity<ContextPicker>,
+    workspace: WeakEntity<Workspace>,
+    context_store: WeakEntity<context_store::ContextStore>,
+    template_arguments: Option<TemplateArguments>,
+    matches: Vec<McpResourceMatch>,
+    selected_index: usize,
+}
+
+impl McpResourceContextPickerDelegate {
+    pub fn new(
+        entries_task: Shared<Task<Arc<Vec<McpResourceContextEntry>>>>,
+        context_picker: WeakEntity<ContextPicker>,
+        workspace: WeakEntity<Workspace>,
+        context_store: WeakEntity<context_store::ContextStore>,
+    ) -> Self {
+        McpResourceContextPickerDelegate {
+            entries_task,
+            context_picker,
+            workspace,
+            context_store,
+            template_arguments: None,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    fn add_resource(
+        &self,
+        server_id: ContextServerId,
+        uri: Url,
+        name: SharedString,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        self.context_store
+            .update(cx, |context_store, cx| {
+                context_store.add_mcp_resource(server_id, uri, name, true, cx)
+            })
+            .log_err();
+    }
+
+    fn reset_query(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.defer_in(window, |picker, window, cx| {
+            picker.set_query("", window, cx);
+            picker.refresh_placeholder(window, cx);
+            picker.refresh(window, cx);
+        });
+    }
+}
+
+impl PickerDelegate for McpResourceContextPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        match &self.template_arguments {
+            Some(arguments) => match arguments.current_variable() {
+                Some(variable) => format!("Value for `{variable}` in {}…", arguments.name).into(),
+                None => "Search MCP resources…".into(),
+            },
+            None => "Search MCP resources…".into(),
+        }
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        if self.template_arguments.is_some() {
+            Some("Type a value".into())
+        } else {
+            Some("No resources found".into())
+        }
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        if let Some(arguments) = &self.template_arguments {
+            let Some(variable) = arguments.current_variable().cloned() else {
+                return Task::ready(());
+            };
+            let completions = self
+                .workspace
+                .upgrade()
+                .and_then(|workspace| {
+                    let server = workspace
+                        .read(cx)
+                        .project()
+                        .read(cx)
+                        .context_server_store()
+                        .read(cx)
+                        .get_running_server(&arguments.server_id)?;
+                    Some(complete_template_argument(
+                        server.client()?,
+                        arguments.uri_template.to_string(),
+                        variable,
+                        query.clone(),
+                        cx,
+                    ))
+                })
+                .unwrap_or_else(|| Task::ready(Vec::new()));
+            return cx.spawn_in(window, async move |this, cx| {
+                let values = completions.await;
+                this.update(cx, |this, cx| {
+                    let mut matches = Vec::new();
+                    if !query.is_empty() && !values.contains(&query) {
+                        matches.push(McpResourceMatch::ArgumentValue(query));
+                    }
+                    matches.extend(values.into_iter().map(McpResourceMatch::ArgumentValue));
+                    this.delegate.matches = matches;
+                    this.delegate.selected_index = 0;
+                    cx.notify();
+                })
+                .ok();
+            });
+        }
+
+        let entries_task = self.entries_task.clone();
+        let executor = cx.background_executor().clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let entries = entries_task.await;
+            let matches =
+                filter_mcp_resources(&entries, query, Arc::new(AtomicBool::default()), executor)
+                    .await;
+            this.update(cx, |this, cx| {
+                this.delegate.matches = matches.into_iter().map(McpResourceMatch::Entry).collect();
+                this.delegate.selected_index = 0;
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    fn confirm_update_query(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<String> {
+        match self.matches.get(self.selected_index)? {
+            McpResourceMatch::Entry(McpResourceContextEntry::Template {
+                server_id,
+                uri_template,
+                name,
+            }) => {
+                let variables = template_variables(uri_template);
+                if variables.is_empty() {
+                    return None;
+                }
+                self.template_arguments = Some(TemplateArguments {
+                    server_id: server_id.clone(),
+                    uri_template: uri_template.clone(),
+                    name: name.clone(),
+                    variables,
+                    values: Vec::new(),
+                });
+            }
+            McpResourceMatch::ArgumentValue(value) => {
+                let arguments = self.template_arguments.as_mut()?;
+                if arguments.is_last_variable() {
+                    return None;
+                }
+                let variable = arguments.current_variable()?.clone();
+                arguments.values.push((variable, value.clone()));
+            }
+            McpResourceMatch::Entry(McpResourceContextEntry::Resource { .. }) => return None,
+        }
+
+        self.matches.clear();
+        self.reset_query(window, cx);
+        Some(String::new())
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(mat) = self.matches.get(self.selected_index) else {
+            return;
+        };
+
+        match mat {
+            McpResourceMatch::Entry(McpResourceContextEntry::Resource {
+                server_id,
+                uri,
+                name,
+            }) => {
+                self.add_resource(server_id.clone(), uri.clone(), name.clone(), cx);
+            }
+            McpResourceMatch::Entry(McpResourceContextEntry::Template {
+                server_id,
+                uri_template,
+                name,
+            }) => {
+                // Templates without variables are plain resources.
+                if let Some(uri) = Url::parse(uri_template).log_err() {
+                    self.add_resource(server_id.clone(), uri, name.clone(), cx);
+                }
+            }
+            McpResourceMatch::ArgumentValue(value) => {
+                let Some(mut arguments) = self.template_arguments.take() else {
+                    return;
+                };
+                if let Some(variable) = arguments.current_variable().cloned() {
+                    arguments.values.push((variable, value.clone()));
+                }
+                let uri = expand_uri_template(&arguments.uri_template, &arguments.values);
+                if let Some(uri) = Url::parse(&uri).log_err() {
+                    let values = arguments
+                        .values
+                        .iter()
+                        .map(|(_, value)| value.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let name = format!("{} ({values})", arguments.name);
+                    self.add_resource(arguments.server_id, uri, name.into(), cx);
+                }
+                self.matches.clear();
+                self.reset_query(window, cx);
+            }
+        }
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.context_picker
+            .update(cx, |_, cx| {
+                cx.emit(DismissEvent);
+            })
+            .ok();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let item = ListItem::new(ix).inset(true).toggle_state(selected);
+        Some(match &self.matches[ix] {
+            McpResourceMatch::Entry(entry) => item.child(render_mcp_resource_context_entry(
+                entry,
+                self.context_store.clone(),
+                cx,
+            )),
+            McpResourceMatch::ArgumentValue(value) => {
+                item.child(Label::new(value.clone()).truncate())
+            }
+        })
+    }
+}
+
+pub fn render_mcp_resource_context_entry(
+    entry: &McpResourceContextEntry,
+    context_store: WeakEntity<ContextStore>,
+    cx: &mut App,
+) -> Div {
+    let (detail, added) = match entry {
+        McpResourceContextEntry::Resource { server_id, uri, .. } => (
+            SharedString::from(server_id.0.clone()),
+            context_store.upgrade().map_or(false, |context_store| {
+                context_store.read(cx).includes_mcp_resource(server_id, uri)
+            }),
+        ),
+        McpResourceContextEntry::Template { uri_template, .. } => (uri_template.clone(), false),
+    };
+
+    h_flex()
+        .gap_1p5()
+        .w_full()
+        .justify_between()
+        .child(
+            h_flex()
+                .gap_1p5()
+                .max_w_72()
+                .child(
+                    Icon::new(ContextKind::McpResource.icon())
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                )
+                .child(Label::new(entry.name().clone()).truncate())
+                .child(
+                    Label::new(detail)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .truncate(),
+                ),
+        )
+        .when(added, |el| {
+            el.child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Icon::new(IconName::Check)
+                            .size(IconSize::Small)
+                            .color(Color::Success),
+                    )
+                    .child(Label::new("Added").size(LabelSize::Small)),
+            )
+        })
+}
+
+/// Lists the resources, and optionally the resource templates, of the running context servers
+/// that serve resources.
+pub(crate) fn list_mcp_resources(
+    workspace: &Entity<Workspace>,
+    include_templates: bool,
+    cx: &mut App,
+) -> Task<Vec<McpResourceContextEntry>> {
+    let servers = workspace
+        .read(cx)
+        .project()
+        .read(cx)
+        .context_server_store()
+        .read(cx)
+        .running_servers();
+    let list_tasks = servers
+        .into_iter()
+        .filter_map(|server| {
+            let protocol = server.client()?;
+            if !protocol.capable(ServerCapability::Resources) {
+                return None;
+            }
+            let server_id = server.id();
+            Some(async move {
+                let mut entries = Vec::new();
+                if let Some(response) = protocol
+                    .request::<types::requests::ResourcesList>(())
+                    .await
+                    .log_err()
+                {
+                    entries.extend(response.resources.into_iter().map(|resource| {
+                        McpResourceContextEntry::Resource {
+                            server_id: server_id.clone(),
+                            uri: resource.uri,
+                            name: resource.name.into(),
+                        }
+                    }));
+                }
+                if include_templates {
+                    // Not every server that serves resources serves templates.
+                    if let Ok(response) = protocol
+                        .request::<types::requests::ListResourceTemplates>(())
+                        .await
+                    {
+                        entries.extend(response.resource_templates.into_iter().map(|template| {
+                            McpResourceContextEntry::Template {
+                                server_id: server_id.clone(),
+                                uri_template: template.uri_template.into(),
+                                name: template.name.into(),
+                            }
+                        }));
+                    }
+                }
+                entries
+            })
+        })
+        .collect::<Vec<_>>();
+
+    cx.spawn(async move |_cx| {
+        future::join_all(list_tasks)
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    })
+}
+
+pub(crate) async fn filter_mcp_resources(
+    entries: &[McpResourceContextEntry],
+    query: String,
+    cancellation_flag: Arc<AtomicBool>,
+    executor: BackgroundExecutor,
+) -> Vec<McpResourceContextEntry> {
+    if query.is_empty() {
+        return entries.to_vec();
+    }
+
+    let candidates = entries
+        .iter()
+        .enumerate()
+        .map(|(id, entry)| StringMatchCandidate::new(id, entry.name()))
+        .collect::<Vec<_>>();
+    let matches = fuzzy::match_strings(
+        &candidates,
+        &query,
+        false,
+        true,
+        100,
+        &cancellation_flag,
+        executor,
+    )
+    .await;
+
+    matches
+        .into_iter()
+        .map(|mat| entries[mat.candidate_id].clone())
+        .collect()
+}
+
+pub(crate) fn search_mcp_resources(
+    query: String,
+    cancellation_flag: Arc<AtomicBool>,
+    workspace: &Entity<Workspace>,
+    cx: &mut App,
+) -> Task<Vec<McpResourceContextEntry>> {
+    let list_task = list_mcp_resources(workspace, false, cx);
+    let executor = cx.background_executor().clone();
+    cx.spawn(async move |_cx| {
+        let entries = list_task.await;
+        filter_mcp_resources(&entries, query, cancellation_flag, executor).await
+    })
+}
+
+fn complete_template_argument(
+    protocol: Arc<context_server::protocol::InitializedContextServerProtocol>,
+    uri_template: String,
+    name: String,
+    value: String,
+    cx: &App,
+) -> Task<Vec<String>> {
+    cx.spawn(async move |_cx| {
+        let response = protocol
+            .request::<types::requests::CompletionComplete>(types::CompletionCompleteParams {
+                reference: types::CompletionReference::Resource(types::ResourceReference {
+                    ty: types::PromptReferenceType::Resource,
+                    uri: uri_template,
+                }),
+                argument: types::CompletionArgument { name, value },
+                meta: None,
+            })
+            .await
+            // Servers without the completions capability reject the request.
+            .ok();
+        response
+            .map(|response| response.completion.values)
+            .unwrap_or_default()
+    })
+}
+
+/// Returns the names of the variables in a URI template, in the order they first appear.
+fn template_variables(uri_template: &str) -> Vec<String> {
+    let mut variables = Vec::<String>::new();
+    for expression in template_expressions(uri_template) {
+        let (_, names) = split_template_operator(expression);
+        for name in names.split(',').map(template_variable_name) {
+            if !name.is_empty() && !variables.iter().any(|variable| variable == name) {
+                variables.push(name.to_string());
+            }
+        }
+    }
+    variables
+}
+
+/// Expands a URI template (RFC 6570) with the given variable values. Only single values are
+/// supported, and the explode and prefix modifiers are ignored.
+fn expand_uri_template(uri_template: &str, values: &[(String, String)]) -> String {
+    let mut result = String::new();
+    let mut rest = uri_template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+
+        let (operator, names) = split_template_operator(&rest[start + 1..start + len]);
+        let (prefix, separator, named, encode) = match operator {
+            Some('+') => ("", ",", false, false),
+            Some('#') => ("#", ",", false, false),
+            Some('.') => (".", ".", false, true),
+            Some('/') => ("/", "/", false, true),
+            Some(';') => (";", ";", true, true),
+            Some('?') => ("?", "&", true, true),
+            Some('&') => ("&", "&", true, true),
+            _ => ("", ",", false, true),
+        };
+        let expanded = names
+            .split(',')
+            .map(template_variable_name)
+            .filter_map(|name| {
+                let (_, value) = values.iter().find(|(variable, _)| variable == name)?;
+                let value = if encode {
+                    urlencoding::encode(value).into_owned()
+                } else {
+                    value.clone()
+                };
+                Some(if named {
+                    format!("{name}={value}")
+                } else {
+                    value
+                })
+            })
+            .collect::<Vec<_>>();
+        if !expanded.is_empty() {
+            result.push_str(prefix);
+            result.push_str(&expanded.join(separator));
+        }
+
+        rest = &rest[start + len + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn template_expressions(uri_template: &str) -> impl Iterator<Item = &str> {
+    uri_template
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(expression, _)| expression))
+}
+
+fn split_template_operator(expression: &str) -> (Option<char>, &str) {
+    match expression.chars().next() {
+        Some(operator @ ('+' | '#' | '.' | '/' | ';' | '?' | '&')) => {
+            (Some(operator), &expression[1..])
+        }
+        _ => (None, expression),
+    }
+}
+
+fn template_variable_name(spec: &str) -> &str {
+    let spec = spec.trim_end_matches('*');
+    spec.split_once(':').map_or(spec, |(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_variables() {
+        assert_eq!(template_variables("file:///{path}"), vec!["path"]);
+        assert_eq!(
+            template_variables("repo://{owner}/{repo}{/path*}{?ref,owner}"),
+            vec!["owner", "repo", "path", "ref"]
+        );
+        assert!(template_variables("config://settings").is_empty());
+    }
+
+    #[test]
+    fn test_expand_uri_template() {
+        let values = [
+            ("owner".to_string(), "zed industries".to_string()),
+            ("repo".to_string(), "zed".to_string()),
+            ("path".to_string(), "crates/agent".to_string()),
+        ];
+        assert_eq!(
+            expand_uri_template("repo://{owner}/{repo}", &values),
+            "repo://zed%20industries/zed"
+        );
+        assert_eq!(
+            expand_uri_template("file:///{+path}", &values),
+            "file:///crates/agent"
+        );
+        assert_eq!(
+            expand_uri_template("repo://{repo}/tree{?path,missing}", &values),
+            "repo://zed/tree?path=crates%2Fagent"
+        );
+        assert_eq!(
+            expand_uri_template("repo://{repo}{/missing}", &values),
+            "repo://zed"
+        );
+    }
+}
@@ -5,20 +5,45 @@ use extension::ExtensionManifest;
 use fs::Fs;
 use gpui::WeakEntity;
 use language::LanguageRegistry;
-use project::project_settings::ProjectSettings;
-use settings::update_settings_file;
+use project::{DisableAiSettings, context_server_store, project_settings::ProjectSettings};
+use settings::{Settings as _, update_settings_file};
 use ui::prelude::*;
 use util::ResultExt;
 use workspace::Workspace;
 
-use crate::agent_configuration::ConfigureContextServerModal;
+use crate::agent_configuration::{
+    ConfigureContextServerModal, ContextServerSamplingModal, SamplingStage,
+};
 
 pub(crate) fn init(language_registry: Arc<LanguageRegistry>, fs: Arc<dyn Fs>, cx: &mut App) {
-    cx.observe_new(move |_: &mut Workspace, window, cx| {
+    cx.observe_new(move |workspace: &mut Workspace, window, cx| {
         let Some(window) = window else {
             return;
         };
 
+        let context_server_store = workspace.project().read(cx).context_server_store();
+        cx.subscribe_in(
+            &context_server_store,
+            window,
+            |workspace, _, event, window, cx| {
+                let stage = match event {
+                    context_server_store::Event::SamplingRequested(request) => {
+                        SamplingStage::Request(request.clone())
+                    }
+                    context_server_store::Event::SamplingCompleted(response) => {
+                        SamplingStage::Response(response.clone())
+                    }
+                    _ => return,
+                };
+                if DisableAiSettings::get_global(cx).disable_ai {
+                    // Dropping the stage rejects it.
+                    return;
+                }
+                ContextServerSamplingModal::show(workspace, stage, window, cx);
+            },
+        )
+        .detach();
+
         if let Some(extension_events) = extension::ExtensionEvents::try_global(cx).as_ref() {
             cx.subscribe_in(extension_events, window, {
                 let language_registry = language_registry.clone();
@@ -0,0 +1,778 @@
+use std::{
+    str::FromStr as _,
+    sync::{Arc, Mutex},
+};
+
+use crate::ActiveThread;
+use agent::{
+    ContextLoadResult, ContextStore, MessageSegment, TextThreadStore, Thread, ThreadEvent,
+    ThreadId, ThreadStore,
+};
+use agent_settings::{AgentProfileId, AgentSettings};
+use anyhow::{Context as _, Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolCard, ToolResult, ToolUseStatus};
+use cloud_llm_client::CompletionIntent;
+use collections::HashSet;
+use futures::channel::oneshot;
+use gpui::{
+    AnyWindowHandle, App, Context, Entity, SharedString, Subscription, Task, WeakEntity, Window,
+};
+use language::LanguageRegistry;
+use language_model::{
+    ConfiguredModel, LanguageModel, LanguageModelRegistry, LanguageModelRequest,
+    LanguageModelToolSchemaFormat, Role, SelectedModel, StopReason,
+};
+use project::Project;
+use serde::{Deserialize, Serialize};
+use settings::Settings as _;
+use ui::{Disclosure, Tooltip, prelude::*};
+use util::markdown::MarkdownInlineCode;
+use workspace::Workspace;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DelegateToolInput {
+    pub goal: String,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Delegates a goal to a sub-agent, which works on it in a thread of its own and returns a
+/// summary of its work, so that long tasks don't fill up the context window of the parent thread.
+pub struct DelegateTool {
+    thread_store: WeakEntity<ThreadStore>,
+    text_thread_store: WeakEntity<TextThreadStore>,
+    language_registry: Arc<LanguageRegistry>,
+    /// The threads of the running sub-agents, which can't delegate in turn.
+    subagent_thread_ids: Arc<Mutex<HashSet<ThreadId>>>,
+}
+
+impl DelegateTool {
+    pub const NAME: &str = "delegate";
+
+    pub fn new(
+        thread_store: WeakEntity<ThreadStore>,
+        text_thread_store: WeakEntity<TextThreadStore>,
+        language_registry: Arc<LanguageRegistry>,
+    ) -> Self {
+        Self {
+            thread_store,
+            text_thread_store,
+            language_registry,
+            subagent_thread_ids: Arc::default(),
+        }
+    }
+}
+
+impl Tool for DelegateTool {
+    fn name(&self) -> String {
+        Self::NAME.to_string()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &Entity<Project>, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        include_str!("./delegate_tool/description.md").to_string()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::ZedAssistant
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        let mut schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "goal": {
+                    "type": "string",
+                    "description": "The goal of the sub-agent. The sub-agent doesn't see this conversation, so describe everything it needs to know, and what it should report back."
+                },
+                "profile": {
+                    "type": "string",
+                    "description": "The ID of the profile whose tools the sub-agent can use, like `ask` to only read the project or `write` to also edit it. Defaults to the user's default profile."
+                },
+                "model": {
+                    "type": "string",
+                    "description": "The model of the sub-agent, as `provider/model`. Defaults to your own model."
+                }
+            },
+            "required": ["goal"]
+        });
+        assistant_tool::adapt_schema_to_format(&mut schema, format)?;
+        Ok(schema)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<DelegateToolInput>(input.clone()) {
+            Ok(input) => {
+                let goal = input.goal.lines().next().unwrap_or_default();
+                format!("Delegate {}", MarkdownInlineCode(goal))
+            }
+            Err(_) => "Delegate to a sub-agent".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        request: Arc<LanguageModelRequest>,
+        _project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        model: Arc<dyn LanguageModel>,
+        window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<DelegateToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        let Some(parent_id) = request.thread_id.as_deref().map(ThreadId::from) else {
+            return Task::ready(Err(anyhow!("Only threads can delegate to sub-agents"))).into();
+        };
+        if self
+            .subagent_thread_ids
+            .lock()
+            .unwrap()
+            .contains(&parent_id)
+        {
+            return Task::ready(Err(anyhow!(
+                "Sub-agents can't delegate to other sub-agents"
+            )))
+            .into();
+        }
+        let Some(thread_store) = self.thread_store.upgrade() else {
+            return Task::ready(Err(anyhow!("Thread store was dropped"))).into();
+        };
+        let Some(parent) = thread_store.read(cx).live_thread(&parent_id) else {
+            return Task::ready(Err(anyhow!("The delegating thread isn't open"))).into();
+        };
+        let (profile_id, configured_model, allowed_tools) =
+            match subagent_settings(&input, &request, model, cx) {
+                Ok(settings) => settings,
+                Err(err) => return Task::ready(Err(err)).into(),
+            };
+
+        let thread = thread_store.update(cx, |thread_store, cx| {
+            thread_store.create_subagent_thread(&parent, allowed_tools, cx)
+        });
+        thread.update(cx, |thread, cx| {
+            thread.set_profile(profile_id, cx);
+            thread.set_configured_model(Some(configured_model.clone()), cx);
+            thread.insert_user_message(
+                format!(
+                    "{}\n\n{}",
+                    include_str!("./delegate_tool/subagent_prompt.md").trim_end(),
+                    input.goal
+                ),
+                ContextLoadResult::default(),
+                None,
+                Vec::new(),
+                cx,
+            );
+            thread.send_to_model(
+                configured_model.model,
+                CompletionIntent::UserPrompt,
+                window,
+                cx,
+            );
+        });
+        let subagent_thread_id = thread.read(cx).id().clone();
+        self.subagent_thread_ids
+            .lock()
+            .unwrap()
+            .insert(subagent_thread_id.clone());
+
+        let (result_tx, result_rx) = oneshot::channel();
+        let card = cx.new(|cx| {
+            DelegateToolCard::new(
+                input.goal.into(),
+                thread,
+                result_tx,
+                self.thread_store.clone(),
+                self.text_thread_store.clone(),
+                self.language_registry.clone(),
+                cx,
+            )
+        });
+
+        // Canceling the parent thread drops the output task, which stops the sub-agent as well.
+        let stop_subagent = util::defer({
+            let cx = cx.to_async();
+            let card = card.downgrade();
+            let subagent_thread_ids = self.subagent_thread_ids.clone();
+            move || {
+                subagent_thread_ids
+                    .lock()
+                    .unwrap()
+                    .remove(&subagent_thread_id);
+                cx.spawn(async move |cx| {
+                    card.update(cx, |card, cx| card.cancel(cx)).ok();
+                })
+                .detach();
+            }
+        });
+        let output = cx.spawn(async move |_cx| {
+            let _stop_subagent = stop_subagent;
+            let summary = result_rx
+                .await
+                .map_err(|_| anyhow!("Sub-agent was canceled"))??;
+            Ok(summary.into())
+        });
+
+        ToolResult {
+            output,
+            card: Some(card.into()),
+        }
+    }
+}
+
+/// Returns the profile and model of a sub-agent, defaulting to the user's default profile and the
+/// model of the parent thread, along with the tools it may use: the ones of the parent thread's
+/// `request`, except for delegating in turn.
+fn subagent_settings(
+    input: &DelegateToolInput,
+    request: &LanguageModelRequest,
+    model: Arc<dyn LanguageModel>,
+    cx: &mut App,
+) -> Result<(AgentProfileId, ConfiguredModel, HashSet<String>)> {
+    let settings = AgentSettings::get_global(cx);
+    let profile_id = match input.profile.as_deref() {
+        Some(profile) => {
+            let profile_id = AgentProfileId(profile.into());
+            if !settings.profiles.contains_key(&profile_id) {
+                let available_profiles = settings
+                    .profiles
+                    .keys()
+                    .map(|id| format!("`{}`", id.0))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                anyhow::bail!(
+                    "Profile `{profile}` doesn't exist. Available profiles: {available_profiles}"
+                );
+            }
+            profile_id
+        }
+        None => settings.default_profile.clone(),
+    };
+
+    let configured_model = match input.model.as_deref() {
+        Some(model) => {
+            let selected_model = SelectedModel::from_str(model).map_err(|err| anyhow!(err))?;
+            LanguageModelRegistry::global(cx)
+                .update(cx, |registry, cx| {
+                    registry.select_model(&selected_model, cx)
+                })
+                .with_context(|| format!("Model `{model}` isn't available"))?
+        }
+        None => {
+            let provider = LanguageModelRegistry::read_global(cx)
+                .provider(&model.provider_id())
+                .context("Provider of the current model isn't available")?;
+            ConfiguredModel { provider, model }
+        }
+    };
+
+    let allowed_tools = request
+        .tools
+        .iter()
+        .map(|tool| tool.name.clone())
+        .filter(|name| name != DelegateTool::NAME)
+        .collect();
+
+    Ok((profile_id, configured_model, allowed_tools))
+}
+
+pub struct DelegateToolCard {
+    goal: SharedString,
+    thread: Entity<Thread>,
+    result_tx: Option<oneshot::Sender<Result<String>>>,
+    thread_store: WeakEntity<ThreadStore>,
+    text_thread_store: WeakEntity<TextThreadStore>,
+    language_registry: Arc<LanguageRegistry>,
+    active_thread: Option<Entity<ActiveThread>>,
+    expanded: bool,
+    _subscription: Subscription,
+}
+
+impl DelegateToolCard {
+    fn new(
+        goal: SharedString,
+        thread: Entity<Thread>,
+        result_tx: oneshot::Sender<Result<String>>,
+        thread_store: WeakEntity<ThreadStore>,
+        text_thread_store: WeakEntity<TextThreadStore>,
+        language_registry: Arc<LanguageRegistry>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&thread, Self::handle_thread_event);
+        Self {
+            goal,
+            thread,
+            result_tx: Some(result_tx),
+            thread_store,
+            text_thread_store,
+            language_registry,
+            active_thread: None,
+            expanded: false,
+            _subscription: subscription,
+        }
+    }
+
+    fn handle_thread_event(
+        &mut self,
+        thread: Entity<Thread>,
+        event: &ThreadEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            ThreadEvent::Stopped(Ok(StopReason::ToolUse)) => {}
+            ThreadEvent::Stopped(Ok(StopReason::Refusal)) => {
+                self.finish(Err(anyhow!(
+                    "Sub-agent's model refused to work on the goal"
+                )));
+            }
+            ThreadEvent::Stopped(Ok(StopReason::EndTurn | StopReason::MaxTokens)) => {
+                let summary = last_assistant_text(thread.read(cx));
+                self.finish(Ok(if summary.is_empty() {
+                    "Sub-agent finished without a summary.".to_string()
+                } else {
+                    summary
+                }));
+            }
+            ThreadEvent::Stopped(Err(error)) => {
+                self.finish(Err(anyhow!("Sub-agent stopped due to an error: {error:#}")));
+            }
+            ThreadEvent::ToolConfirmationNeeded => {
+                self.expanded = true;
+                cx.notify();
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(&mut self, result: Result<String>) {
+        if let Some(result_tx) = self.result_tx.take() {
+            result_tx.send(result).ok();
+        }
+    }
+
+    /// Stops the sub-agent, if it's still running.
+    pub fn cancel(&mut self, cx: &mut Context<Self>) {
+        if self.result_tx.is_some() {
+            self.finish(Err(anyhow!("Sub-agent was canceled by the user")));
+            self.thread.update(cx, |thread, cx| {
+                thread.cancel_last_completion(None, cx);
+            });
+            cx.notify();
+        }
+    }
+
+    fn active_thread(
+        &mut self,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<ActiveThread>> {
+        if self.active_thread.is_none() {
+            let thread_store = self.thread_store.upgrade()?;
+            let text_thread_store = self.text_thread_store.upgrade()?;
+            let project = self.thread.read(cx).project().downgrade();
+            let context_store =
+                cx.new(|_| ContextStore::new(project, Some(thread_store.downgrade())));
+            self.active_thread = Some(cx.new(|cx| {
+                ActiveThread::new(
+                    self.thread.clone(),
+                    thread_store,
+                    text_thread_store,
+                    context_store,
+                    self.language_registry.clone(),
+                    workspace,
+                    window,
+                    cx,
+                )
+            }));
+        }
+        self.active_thread.clone()
+    }
+}
+
+/// Returns the text of the last message of the agent, which is its summary once it's done.
+fn last_assistant_text(thread: &Thread) -> String {
+    thread
+        .messages()
+        .filter(|message| message.role == Role::Assistant && !message.ui_only)
+        .last()
+        .map(|message| {
+            message
+                .segments
+                .iter()
+                .filter_map(MessageSegment::text)
+                .collect::<String>()
+                .trim()
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+impl ToolCard for DelegateToolCard {
+    fn render(
+        &mut self,
+        status: &ToolUseStatus,
+        window: &mut Window,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let is_running = self.result_tx.is_some()
+            && matches!(status, ToolUseStatus::Pending | ToolUseStatus::Running);
+        let status_label = match status {
+            ToolUseStatus::Error(_) => Some(("Failed", Color::Error)),
+            ToolUseStatus::Finished(_) => Some(("Done", Color::Success)),
+            _ if is_running => Some(("Working…", Color::Muted)),
+            _ => None,
+        };
+
+        let header = h_flex()
+            .w_full()
+            .gap_1p5()
+            .justify_between()
+            .child(
+                h_flex()
+                    .min_w_0()
+                    .gap_1p5()
+                    .child(
+                        Icon::new(IconName::ZedAssistant)
+                            .size(IconSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(self.goal.lines().next().unwrap_or_default().to_string())
+                            .size(LabelSize::Small)
+                            .truncate(),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .flex_none()
+                    .gap_1()
+                    .when_some(status_label, |this, (label, color)| {
+                        this.child(Label::new(label).size(LabelSize::Small).color(color))
+                    })
+                    .when(is_running, |this| {
+                        this.child(
+                            IconButton::new("cancel-subagent", IconName::Stop)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Error)
+                                .tooltip(Tooltip::text("Stop Sub-agent"))
+                                .on_click(cx.listener(|this, _, _, cx| this.cancel(cx))),
+                        )
+                    })
+                    .child(
+                        Disclosure::new("subagent-disclosure", self.expanded)
+                            .opened_icon(IconName::ChevronUp)
+                            .closed_icon(IconName::ChevronDown)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.expanded = !this.expanded;
+                                cx.notify();
+                            })),
+                    ),
+            );
+
+        let transcript = if self.expanded {
+            self.active_thread(workspace, window, cx)
+        } else {
+            None
+        };
+
+        v_flex()
+            .mb_2()
+            .border_1()
+            .when(matches!(status, ToolUseStatus::Error(_)), |card| {
+                card.border_dashed()
+            })
+            .border_color(cx.theme().colors().border.opacity(0.6))
+            .rounded_lg()
+            .overflow_hidden()
+            .child(
+                div()
+                    .p_2()
+                    .bg(cx.theme().colors().element_background)
+                    .child(header),
+            )
+            .when_some(transcript, |card, active_thread| {
+                card.child(
+                    div()
+                        .h_96()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border.opacity(0.6))
+                        .bg(cx.theme().colors().panel_background)
+                        .child(active_thread),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent::thread_store;
+    use agent_settings::{ToolPermission, ToolPermissionRule};
+    use assistant_tool::{ToolRegistry, ToolResultContent, ToolWorkingSet};
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use language_model::{
+        LanguageModelCompletionEvent, LanguageModelRequestTool, LanguageModelToolUse,
+        LanguageModelToolUseId, fake_provider::FakeLanguageModel,
+    };
+    use prompt_store::PromptBuilder;
+    use serde_json::json;
+    use settings::{Settings as _, SettingsStore};
+    use util::path;
+
+    #[gpui::test]
+    async fn test_delegate_returns_summary(cx: &mut TestAppContext) {
+        let (tool, thread, model) = setup_test(cx).await;
+        let result = run_tool(
+            &tool,
+            &thread,
+            json!({"goal": "Find the bug", "profile": "ask"}),
+            &model,
+            cx,
+        );
+        cx.run_until_parked();
+
+        let subagent = subagent_thread(&result, cx);
+        subagent.read_with(cx, |subagent, cx| {
+            assert_eq!(subagent.parent_id(), Some(thread.read(cx).id()));
+            assert_eq!(enabled_tool_names(subagent, cx), ["read_file"]);
+        });
+        let request = model.pending_completions().pop().unwrap();
+        assert!(
+            request
+                .messages
+                .last()
+                .unwrap()
+                .string_contents()
+                .contains("Find the bug")
+        );
+
+        model.send_last_completion_stream_text_chunk("The bug is in `main`.");
+        model.send_last_completion_stream_event(LanguageModelCompletionEvent::Stop(
+            StopReason::EndTurn,
+        ));
+        model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        let output = result.output.await.unwrap();
+        assert_eq!(
+            output.content,
+            ToolResultContent::Text("The bug is in `main`.".into())
+        );
+    }
+
+    #[gpui::test]
+    async fn test_subagent_follows_parent_permission_rules(cx: &mut TestAppContext) {
+        let (tool, thread, model) = setup_test(cx).await;
+        cx.update(|cx| {
+            let parent_profile_id = thread.read(cx).profile().id().clone();
+            let mut settings = AgentSettings::get_global(cx).clone();
+            settings
+                .profiles
+                .get_mut(&parent_profile_id)
+                .unwrap()
+                .tool_permissions
+                .push(ToolPermissionRule::new(
+                    "read_file",
+                    None,
+                    ToolPermission::Deny,
+                ));
+            AgentSettings::override_global(settings, cx);
+        });
+
+        // The sub-agent's profile has no rules for reading files, but its parent denies it.
+        let result = run_tool(
+            &tool,
+            &thread,
+            json!({"goal": "Read the code", "profile": "ask"}),
+            &model,
+            cx,
+        );
+        cx.run_until_parked();
+        let subagent = subagent_thread(&result, cx);
+        let tool_use_id = LanguageModelToolUseId::from("read");
+        let input = json!({"path": "test/code.rs"});
+        model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+            LanguageModelToolUse {
+                id: tool_use_id.clone(),
+                name: "read_file".into(),
+                raw_input: input.to_string(),
+                input,
+                is_input_complete: true,
+            },
+        ));
+        model.send_last_completion_stream_event(LanguageModelCompletionEvent::Stop(
+            StopReason::ToolUse,
+        ));
+        model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        subagent.read_with(cx, |subagent, _| {
+            let tool_result = subagent.tool_result(&tool_use_id).unwrap();
+            assert!(tool_result.is_error);
+            assert!(
+                subagent
+                    .output_for_tool(&tool_use_id)
+                    .unwrap()
+                    .contains("denied by the user's tool permission rules")
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_canceling_parent_stops_subagent(cx: &mut TestAppContext) {
+        let (tool, thread, model) = setup_test(cx).await;
+        let result = run_tool(&tool, &thread, json!({"goal": "Fix the bug"}), &model, cx);
+        cx.run_until_parked();
+
+        let subagent = subagent_thread(&result, cx);
+        subagent.read_with(cx, |subagent, cx| {
+            assert!(subagent.is_generating());
+            // The sub-agent can't delegate, even though its profile and its parent can.
+            assert_eq!(enabled_tool_names(subagent, cx), ["edit_file", "read_file"]);
+        });
+
+        drop(result.output);
+        cx.run_until_parked();
+        subagent.read_with(cx, |subagent, _| assert!(!subagent.is_generating()));
+    }
+
+    async fn setup_test(
+        cx: &mut TestAppContext,
+    ) -> (Arc<DelegateTool>, Entity<Thread>, Arc<FakeLanguageModel>) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AgentSettings::register(cx);
+            prompt_store::init(cx);
+            thread_store::init(cx);
+            workspace::init_settings(cx);
+            language_model::init_settings(cx);
+            LanguageModelRegistry::test(cx);
+            ToolRegistry::default_global(cx);
+
+            let http_client = Arc::new(http_client::HttpClientWithUrl::new(
+                http_client::FakeHttpClient::with_200_response(),
+                "http://localhost".to_string(),
+                None,
+            ));
+            assistant_tools::init(http_client, cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/test"), json!({"code.rs": "fn main() {}"}))
+            .await;
+        let project = Project::test(fs, [path!("/test").as_ref()], cx).await;
+        let prompt_builder = Arc::new(PromptBuilder::new(None).unwrap());
+        let thread_store = cx
+            .update(|cx| {
+                ThreadStore::load(
+                    project.clone(),
+                    cx.new(|_| ToolWorkingSet::default()),
+                    None,
+                    prompt_builder.clone(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let text_thread_store = cx
+            .update(|cx| {
+                TextThreadStore::new(project.clone(), prompt_builder, Default::default(), cx)
+            })
+            .await
+            .unwrap();
+
+        let tool = Arc::new(DelegateTool::new(
+            thread_store.downgrade(),
+            text_thread_store.downgrade(),
+            Arc::new(LanguageRegistry::new(cx.executor())),
+        ));
+        cx.update(|cx| {
+            thread_store
+                .read(cx)
+                .tools()
+                .update(cx, |tools, cx| tools.insert_native(tool.clone(), cx));
+        });
+        let thread = thread_store.update(cx, |store, cx| store.create_thread(cx));
+
+        (tool, thread, Arc::new(FakeLanguageModel::default()))
+    }
+
+    /// Runs the tool the way `thread` would, with the tools the thread offers its model.
+    fn run_tool(
+        tool: &Arc<DelegateTool>,
+        thread: &Entity<Thread>,
+        input: serde_json::Value,
+        model: &Arc<FakeLanguageModel>,
+        cx: &mut TestAppContext,
+    ) -> ToolResult {
+        cx.update(|cx| {
+            let (thread_id, project, action_log) = {
+                let thread = thread.read(cx);
+                (
+                    thread.id().to_string(),
+                    thread.project().clone(),
+                    thread.action_log().clone(),
+                )
+            };
+            let request = LanguageModelRequest {
+                thread_id: Some(thread_id),
+                tools: ["read_file", "edit_file", DelegateTool::NAME]
+                    .into_iter()
+                    .map(|name| LanguageModelRequestTool {
+                        name: name.to_string(),
+                        description: String::new(),
+                        input_schema: json!({}),
+                    })
+                    .collect(),
+                ..Default::default()
+            };
+            tool.clone().run(
+                input,
+                Arc::new(request),
+                project,
+                action_log,
+                model.clone(),
+                None,
+                cx,
+            )
+        })
+    }
+
+    fn subagent_thread(result: &ToolResult, cx: &mut TestAppContext) -> Entity<Thread> {
+        let card = result
+            .card
+            .as_ref()
+            .and_then(|card| card.downcast::<DelegateToolCard>())
+            .unwrap();
+        card.read_with(cx, |card, _| card.thread.clone())
+    }
+
+    fn enabled_tool_names(thread: &Thread, cx: &App) -> Vec<String> {
+        let mut names = thread
+            .enabled_tools(cx)
+            .into_iter()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+}
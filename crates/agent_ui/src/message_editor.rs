@@ -358,6 +358,10 @@ impl MessageEditor {
     }
 
     fn send_to_model(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.thread.read(cx).is_read_only() {
+            return;
+        }
+
         let Some(ConfiguredModel { model, provider }) = self
             .thread
             .update(cx, |thread, cx| thread.get_or_init_configured_model(cx))
@@ -1358,6 +1362,31 @@ impl MessageEditor {
         )
     }
 
+    fn render_read_only_callout(&self, line_height: Pixels, cx: &mut Context<Self>) -> Div {
+        div()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Callout::new()
+                    .line_height(line_height)
+                    .icon(
+                        Icon::new(IconName::Info)
+                            .color(Color::Muted)
+                            .size(IconSize::XSmall),
+                    )
+                    .title("This thread was imported as read-only")
+                    .description("To continue it, start a new thread from a summary.")
+                    .primary_action(
+                        Button::new("start-new-thread-from-read-only", "Start New Thread")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                let from_thread_id = Some(this.thread.read(cx).id().clone());
+                                window.dispatch_action(Box::new(NewThread { from_thread_id }), cx);
+                            })),
+                    ),
+            )
+    }
+
     pub fn last_estimated_token_count(&self) -> Option<u64> {
         self.last_estimated_token_count
     }
@@ -1458,6 +1487,7 @@ impl MessageEditor {
                         tool_choice: None,
                         stop: vec![],
                         temperature: AgentSettings::temperature_for_model(&model.model, cx),
+                        max_tokens: None,
                         thinking_allowed: true,
                     };
 
@@ -1643,6 +1673,7 @@ impl Render for MessageEditor {
             });
 
         let burn_mode_enabled = thread.completion_mode() == CompletionMode::Burn;
+        let is_read_only = thread.is_read_only();
 
         let action_log = self.thread.read(cx).action_log();
         let changed_buffers = action_log.read(cx).changed_buffers(cx);
@@ -1684,7 +1715,13 @@ impl Render for MessageEditor {
             .when(changed_buffers.len() > 0, |parent| {
                 parent.child(self.render_edits_bar(&changed_buffers, window, cx))
             })
-            .child(self.render_editor(window, cx))
+            .map(|this| {
+                if is_read_only {
+                    this.child(self.render_read_only_callout(line_height, cx))
+                } else {
+                    this.child(self.render_editor(window, cx))
+                }
+            })
             .children({
                 let usage_callout = self.render_usage_callout(line_height, cx);
 
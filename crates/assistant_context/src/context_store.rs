@@ -855,6 +855,7 @@ impl ContextStore {
                     _ => {}
                 }
             }
+            _ => {}
         }
     }
 
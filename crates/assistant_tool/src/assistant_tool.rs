@@ -10,6 +10,7 @@ use std::fmt::Formatter;
 use std::ops::Deref;
 use std::sync::Arc;
 
+use agent_settings::ToolPermissionSubject;
 use anyhow::Result;
 use gpui::AnyElement;
 use gpui::AnyWindowHandle;
@@ -181,6 +182,11 @@ impl AnyToolCard {
     ) -> AnyElement {
         (self.render)(self.entity.clone(), status, window, workspace, cx)
     }
+
+    /// Returns the card's entity, if it's a `T`.
+    pub fn downcast<T: ToolCard>(&self) -> Option<Entity<T>> {
+        self.entity.clone().downcast::<T>().ok()
+    }
 }
 
 impl From<Task<Result<ToolResultOutput>>> for ToolResult {
@@ -226,6 +232,12 @@ pub trait Tool: 'static + Send + Sync {
     /// Returns true if the tool may perform edits.
     fn may_perform_edits(&self) -> bool;
 
+    /// Returns the part of the input that the patterns of the user's tool permission rules
+    /// are matched against, like the path the tool edits or the command it runs.
+    fn permission_subject(&self, _input: &serde_json::Value) -> Option<ToolPermissionSubject> {
+        None
+    }
+
     /// Returns the JSON schema that describes the tool's input.
     fn input_schema(&self, _: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
         Ok(serde_json::Value::Object(serde_json::Map::default()))
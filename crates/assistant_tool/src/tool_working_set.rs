@@ -45,6 +45,8 @@ impl std::fmt::Display for UniqueToolName {
 /// A working set of tools for use in one instance of the Assistant Panel.
 #[derive(Default)]
 pub struct ToolWorkingSet {
+    /// Native tools that depend on the owner of the working set, so can't be in the registry.
+    native_tools_by_name: HashMap<UniqueToolName, Arc<dyn Tool>>,
     context_server_tools_by_id: HashMap<ToolId, Arc<dyn Tool>>,
     context_server_tools_by_name: HashMap<UniqueToolName, Arc<dyn Tool>>,
     next_tool_id: ToolId,
@@ -52,8 +54,9 @@ pub struct ToolWorkingSet {
 
 impl ToolWorkingSet {
     pub fn tool(&self, name: &str, cx: &App) -> Option<Arc<dyn Tool>> {
-        self.context_server_tools_by_name
+        self.native_tools_by_name
             .get(name)
+            .or_else(|| self.context_server_tools_by_name.get(name))
             .cloned()
             .or_else(|| ToolRegistry::global(cx).tool(name))
     }
@@ -64,6 +67,7 @@ impl ToolWorkingSet {
             .into_iter()
             .map(|tool| (UniqueToolName(tool.name().into()), tool))
             .collect::<Vec<_>>();
+        tools.extend(self.native_tools_by_name.clone());
         tools.extend(self.context_server_tools_by_name.clone());
         tools
     }
@@ -87,6 +91,14 @@ impl ToolWorkingSet {
         tools_by_source
     }
 
+    /// Adds a native tool, which keeps its name like the ones in the registry.
+    pub fn insert_native(&mut self, tool: Arc<dyn Tool>, cx: &App) {
+        debug_assert_eq!(tool.source(), ToolSource::Native);
+        self.native_tools_by_name
+            .insert(UniqueToolName(tool.name().into()), tool);
+        self.tools_changed(cx);
+    }
+
     pub fn insert(&mut self, tool: Arc<dyn Tool>, cx: &App) -> ToolId {
         let tool_id = self.register_tool(tool);
         self.tools_changed(cx);
@@ -114,13 +126,15 @@ impl ToolWorkingSet {
     }
 
     fn tools_changed(&mut self, cx: &App) {
+        let mut native_tools = ToolRegistry::global(cx).tools();
+        native_tools.extend(self.native_tools_by_name.values().cloned());
         self.context_server_tools_by_name = resolve_context_server_tool_name_conflicts(
             &self
                 .context_server_tools_by_id
                 .values()
                 .cloned()
                 .collect::<Vec<_>>(),
-            &ToolRegistry::global(cx).tools(),
+            &native_tools,
         );
     }
 }
@@ -239,10 +253,16 @@ mod tests {
                         "tool2",
                         ToolSource::ContextServer { id: "mcp-2".into() },
                     )) as Arc<dyn Tool>,
+                    Arc::new(TestTool::new(
+                        "tool3",
+                        ToolSource::ContextServer { id: "mcp-1".into() },
+                    )) as Arc<dyn Tool>,
                 ]
                 .into_iter(),
                 cx,
             );
+            tool_working_set
+                .insert_native(Arc::new(TestTool::new("tool3", ToolSource::Native)), cx);
         });
 
         cx.update(|cx| {
@@ -262,6 +282,14 @@ mod tests {
                 ToolSource::ContextServer { id: "mcp-2".into() },
                 cx,
             );
+            assert_tool(&tool_working_set, "tool3", "tool3", ToolSource::Native, cx);
+            assert_tool(
+                &tool_working_set,
+                "mcp-1_tool3",
+                "tool3",
+                ToolSource::ContextServer { id: "mcp-1".into() },
+                cx,
+            );
         })
     }
 
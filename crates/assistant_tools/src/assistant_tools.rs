@@ -6,14 +6,21 @@ mod edit_agent;
 mod edit_file_tool;
 mod fetch_tool;
 mod find_path_tool;
+mod find_references_tool;
+mod go_to_definition_tool;
 mod grep_tool;
+mod hover_tool;
 mod list_directory_tool;
 mod move_path_tool;
 mod now_tool;
 mod open_tool;
 mod project_notifications_tool;
 mod read_file_tool;
+mod rename_symbol_tool;
 mod schema;
+mod semantic_search_tool;
+mod symbol_position;
+mod symbols_tool;
 mod templates;
 mod terminal_tool;
 mod thinking_tool;
@@ -26,6 +33,7 @@ use gpui::{App, Entity};
 use http_client::HttpClientWithUrl;
 use language_model::LanguageModelRegistry;
 use move_path_tool::MovePathTool;
+use semantic_index::SemanticDb;
 use std::sync::Arc;
 use web_search_tool::WebSearchTool;
 
@@ -37,8 +45,14 @@ use crate::diagnostics_tool::DiagnosticsTool;
 use crate::edit_file_tool::EditFileTool;
 use crate::fetch_tool::FetchTool;
 use crate::find_path_tool::FindPathTool;
+use crate::find_references_tool::FindReferencesTool;
+use crate::go_to_definition_tool::GoToDefinitionTool;
+use crate::hover_tool::HoverTool;
 use crate::list_directory_tool::ListDirectoryTool;
 use crate::now_tool::NowTool;
+use crate::rename_symbol_tool::RenameSymbolTool;
+use crate::semantic_search_tool::SemanticSearchTool;
+use crate::symbols_tool::SymbolsTool;
 use crate::thinking_tool::ThinkingTool;
 
 pub use edit_file_tool::{EditFileMode, EditFileToolInput};
@@ -69,6 +83,11 @@ pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut App) {
     registry.register_tool(ThinkingTool);
     registry.register_tool(FetchTool::new(http_client));
     registry.register_tool(EditFileTool);
+    registry.register_tool(GoToDefinitionTool);
+    registry.register_tool(FindReferencesTool);
+    registry.register_tool(SymbolsTool);
+    registry.register_tool(HoverTool);
+    registry.register_tool(RenameSymbolTool);
 
     register_web_search_tool(&LanguageModelRegistry::global(cx), cx);
     cx.subscribe(
@@ -81,6 +100,19 @@ pub fn init(http_client: Arc<HttpClientWithUrl>, cx: &mut App) {
         },
     )
     .detach();
+
+    register_semantic_search_tool(cx);
+    cx.observe_global::<SemanticDb>(register_semantic_search_tool)
+        .detach();
+}
+
+/// Semantic search is only offered while a semantic index is enabled.
+fn register_semantic_search_tool(cx: &mut App) {
+    if cx.has_global::<SemanticDb>() {
+        ToolRegistry::global(cx).register_tool(SemanticSearchTool);
+    } else {
+        ToolRegistry::global(cx).unregister_tool(SemanticSearchTool);
+    }
 }
 
 fn register_web_search_tool(registry: &Entity<LanguageModelRegistry>, cx: &mut App) {
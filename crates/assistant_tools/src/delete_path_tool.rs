@@ -1,4 +1,5 @@
 use crate::schema::json_schema_for;
+use agent_settings::ToolPermissionSubject;
 use anyhow::{Context as _, Result, anyhow};
 use assistant_tool::{ActionLog, Tool, ToolResult};
 use futures::{SinkExt, StreamExt, channel::mpsc};
@@ -41,6 +42,11 @@ impl Tool for DeletePathTool {
         true
     }
 
+    fn permission_subject(&self, input: &serde_json::Value) -> Option<ToolPermissionSubject> {
+        let input = serde_json::from_value::<DeletePathToolInput>(input.clone()).ok()?;
+        Some(ToolPermissionSubject::Path(input.path))
+    }
+
     fn description(&self) -> String {
         include_str!("./delete_path_tool/description.md").into()
     }
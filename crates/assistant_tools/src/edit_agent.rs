@@ -719,6 +719,7 @@ impl EditAgent {
             tools,
             stop: Vec::new(),
             temperature: None,
+            max_tokens: None,
             thinking_allowed: true,
         };
 
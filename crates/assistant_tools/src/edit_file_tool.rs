@@ -174,6 +174,16 @@ impl Tool for EditFileTool {
         true
     }
 
+    fn permission_subject(
+        &self,
+        input: &serde_json::Value,
+    ) -> Option<agent_settings::ToolPermissionSubject> {
+        let input = serde_json::from_value::<EditFileToolInput>(input.clone()).ok()?;
+        Some(agent_settings::ToolPermissionSubject::Path(
+            input.path.to_string_lossy().into_owned(),
+        ))
+    }
+
     fn description(&self) -> String {
         include_str!("edit_file_tool/description.md").to_string()
     }
@@ -3,12 +3,13 @@ use std::sync::Arc;
 use std::{borrow::Cow, cell::RefCell};
 
 use crate::schema::json_schema_for;
+use agent_settings::ToolPermissionSubject;
 use anyhow::{Context as _, Result, anyhow, bail};
 use assistant_tool::{ActionLog, Tool, ToolResult};
 use futures::AsyncReadExt as _;
 use gpui::{AnyWindowHandle, App, AppContext as _, Entity, Task};
 use html_to_markdown::{TagHandler, convert_html_to_markdown, markdown};
-use http_client::{AsyncBody, HttpClientWithUrl};
+use http_client::{AsyncBody, HttpClientWithUrl, Url};
 use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
 use project::Project;
 use schemars::JsonSchema;
@@ -124,6 +125,18 @@ impl Tool for FetchTool {
         false
     }
 
+    fn permission_subject(&self, input: &serde_json::Value) -> Option<ToolPermissionSubject> {
+        let input = serde_json::from_value::<FetchToolInput>(input.clone()).ok()?;
+        let url = if !input.url.starts_with("https://") && !input.url.starts_with("http://") {
+            Url::parse(&format!("https://{}", input.url))
+        } else {
+            Url::parse(&input.url)
+        };
+        Some(ToolPermissionSubject::Domain(
+            url.ok()?.host_str()?.to_string(),
+        ))
+    }
+
     fn description(&self) -> String {
         include_str!("./fetch_tool/description.md").to_string()
     }
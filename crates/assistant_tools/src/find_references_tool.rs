@@ -0,0 +1,76 @@
+use crate::schema::json_schema_for;
+use crate::symbol_position::{SymbolPosition, format_locations};
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use gpui::{AnyWindowHandle, App, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use std::sync::Arc;
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+pub struct FindReferencesTool;
+
+impl Tool for FindReferencesTool {
+    fn name(&self) -> String {
+        "find_references".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &Entity<Project>, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn description(&self) -> String {
+        include_str!("./find_references_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::ToolSearch
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<SymbolPosition>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<SymbolPosition>(input.clone()) {
+            Ok(input) => format!("Find references to {}", MarkdownInlineCode(&input.symbol)),
+            Err(_) => "Find references".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<SymbolPosition>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        let position = input.resolve(&project, cx);
+
+        cx.spawn(async move |cx| {
+            let (buffer, position) = position.await?;
+            let locations = project
+                .update(cx, |project, cx| project.references(&buffer, position, cx))?
+                .await?;
+            if locations.is_empty() {
+                return Ok(format!("No references found for `{}`.", input.symbol).into());
+            }
+            let mut output = format!("Found {} references:\n", locations.len());
+            output.push_str(&format_locations(&locations, cx)?);
+            Ok(output.into())
+        })
+        .into()
+    }
+}
@@ -0,0 +1,89 @@
+use crate::schema::json_schema_for;
+use crate::symbol_position::SymbolPosition;
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use gpui::{AnyWindowHandle, App, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::{HoverBlockKind, Project};
+use std::sync::Arc;
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+pub struct HoverTool;
+
+impl Tool for HoverTool {
+    fn name(&self) -> String {
+        "hover".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &Entity<Project>, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn description(&self) -> String {
+        include_str!("./hover_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::ToolBulb
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<SymbolPosition>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<SymbolPosition>(input.clone()) {
+            Ok(input) => format!("Get type info of {}", MarkdownInlineCode(&input.symbol)),
+            Err(_) => "Get type info".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<SymbolPosition>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        let position = input.resolve(&project, cx);
+
+        cx.spawn(async move |cx| {
+            let (buffer, position) = position.await?;
+            let hovers = project
+                .update(cx, |project, cx| project.hover(&buffer, position, cx))?
+                .await;
+            let output = hovers
+                .into_iter()
+                .flat_map(|hover| hover.contents)
+                .filter(|block| !block.text.trim().is_empty())
+                .map(|block| match block.kind {
+                    HoverBlockKind::Code { language } => {
+                        format!("```{language}\n{}\n```", block.text.trim())
+                    }
+                    HoverBlockKind::PlainText | HoverBlockKind::Markdown => {
+                        block.text.trim().to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            if output.is_empty() {
+                Ok(format!("No type info found for `{}`.", input.symbol).into())
+            } else {
+                Ok(output.into())
+            }
+        })
+        .into()
+    }
+}
@@ -0,0 +1,378 @@
+use crate::schema::json_schema_for;
+use crate::symbol_position::SymbolPosition;
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use collections::HashSet;
+use gpui::{AnyWindowHandle, App, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RenameSymbolToolInput {
+    /// The relative path of the file containing the symbol.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    ///
+    /// <example>
+    /// If the project has the following root directories:
+    ///
+    /// - /a/b/directory1
+    /// - /c/d/directory2
+    ///
+    /// If you want to access `file.txt` in `directory1`, you should use the path `directory1/file.txt`.
+    /// If you want to access `file.txt` in `directory2`, you should use the path `directory2/file.txt`.
+    /// </example>
+    pub path: String,
+
+    /// The line number of the symbol (1-based index)
+    pub line: u32,
+
+    /// The symbol as it is written on that line, like `parse_config` or `Config`
+    pub symbol: String,
+
+    /// The new name of the symbol
+    pub new_name: String,
+}
+
+pub struct RenameSymbolTool;
+
+impl Tool for RenameSymbolTool {
+    fn name(&self) -> String {
+        "rename_symbol".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &Entity<Project>, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        true
+    }
+
+    fn description(&self) -> String {
+        include_str!("./rename_symbol_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::ToolPencil
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<RenameSymbolToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<RenameSymbolToolInput>(input.clone()) {
+            Ok(input) => format!(
+                "Rename {} to {}",
+                MarkdownInlineCode(&input.symbol),
+                MarkdownInlineCode(&input.new_name)
+            ),
+            Err(_) => "Rename symbol".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<RenameSymbolToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        let position = SymbolPosition {
+            path: input.path,
+            line: input.line,
+            symbol: input.symbol.clone(),
+        }
+        .resolve(&project, cx);
+
+        cx.spawn(async move |cx| {
+            let (buffer, position) = position.await?;
+            let transaction = project
+                .update(cx, |project, cx| {
+                    project.perform_rename(buffer, position, input.new_name.clone(), cx)
+                })?
+                .await?;
+            anyhow::ensure!(
+                !transaction.0.is_empty(),
+                "The language server didn't rename `{}`",
+                input.symbol
+            );
+
+            // Roll back the whole rename rather than leave the symbol half renamed, when the
+            // language server also edits files outside the project, like dependencies.
+            let outside_paths = cx.update(|cx| {
+                transaction
+                    .0
+                    .keys()
+                    .filter_map(|buffer| {
+                        let file = buffer.read(cx).file();
+                        let in_project = file.is_some_and(|file| {
+                            project
+                                .read(cx)
+                                .worktree_for_id(file.worktree_id(cx), cx)
+                                .is_some_and(|worktree| worktree.read(cx).is_visible())
+                        });
+                        (!in_project).then(|| {
+                            file.map_or_else(
+                                || "an untitled buffer".to_string(),
+                                |file| file.full_path(cx).display().to_string(),
+                            )
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+            if !outside_paths.is_empty() {
+                cx.update(|cx| {
+                    for (buffer, transaction) in &transaction.0 {
+                        buffer.update(cx, |buffer, cx| buffer.undo_transaction(transaction.id, cx));
+                    }
+                })?;
+                anyhow::bail!(
+                    "Renaming `{}` would also edit files outside the project, so it was undone:\n- {}",
+                    input.symbol,
+                    outside_paths.join("\n- ")
+                );
+            }
+
+            // The language server already applied the edits, so for buffers the agent hasn't
+            // touched yet, track them from the text before the rename to make it reviewable.
+            cx.update(|cx| {
+                for (buffer, transaction) in &transaction.0 {
+                    buffer.update(cx, |buffer, cx| buffer.undo_transaction(transaction.id, cx));
+                    action_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx));
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.redo_to_transaction(transaction.id, cx)
+                    });
+                    action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+                }
+            })?;
+
+            let buffers = transaction.0.keys().cloned().collect::<HashSet<_>>();
+            project
+                .update(cx, |project, cx| project.save_buffers(buffers.clone(), cx))?
+                .await?;
+
+            cx.update(|cx| {
+                let mut output =
+                    format!("Renamed `{}` to `{}` in:\n", input.symbol, input.new_name);
+                for buffer in &buffers {
+                    if let Some(file) = buffer.read(cx).file() {
+                        writeln!(output, "- {}", file.full_path(cx).display()).ok();
+                    }
+                }
+                output.into()
+            })
+        })
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol_position::tests::{init_test, rust_project};
+    use buffer_diff::DiffHunkStatusKind;
+    use fs::Fs as _;
+    use gpui::{AppContext as _, TestAppContext};
+    use language_model::fake_provider::FakeLanguageModel;
+    use project::FakeFs;
+    use serde_json::json;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_rename_is_reviewable(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "main.rs": "fn greet() {}\n\nfn main() {\n    greet();\n}\n",
+                },
+            }),
+        )
+        .await;
+        let (project, fake_server, _handle) = rust_project(fs.clone(), cx).await;
+        fake_server.set_request_handler::<lsp::request::Rename, _, _>(|params, _| async move {
+            assert_eq!(params.new_name, "welcome");
+            let uri = params.text_document_position.text_document.uri;
+            Ok(Some(lsp::WorkspaceEdit {
+                changes: Some(
+                    [(
+                        uri,
+                        vec![
+                            lsp::TextEdit::new(
+                                lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 8)),
+                                "welcome".into(),
+                            ),
+                            lsp::TextEdit::new(
+                                lsp::Range::new(lsp::Position::new(3, 4), lsp::Position::new(3, 9)),
+                                "welcome".into(),
+                            ),
+                        ],
+                    )]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..Default::default()
+            }))
+        });
+
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let output = run_rename(project.clone(), action_log.clone(), cx)
+            .await
+            .unwrap();
+        assert_eq!(
+            output.content.as_str().unwrap(),
+            "Renamed `greet` to `welcome` in:\n- root/src/main.rs\n"
+        );
+        assert_eq!(
+            fs.load(path!("/root/src/main.rs").as_ref()).await.unwrap(),
+            "fn welcome() {}\n\nfn main() {\n    welcome();\n}\n"
+        );
+
+        let hunks = cx.read(|cx| {
+            action_log
+                .read(cx)
+                .changed_buffers(cx)
+                .into_iter()
+                .flat_map(|(buffer, diff)| {
+                    let snapshot = buffer.read(cx).snapshot();
+                    let diff = diff.read(cx);
+                    diff.hunks(&snapshot, cx)
+                        .map(|hunk| {
+                            (
+                                hunk.status().kind,
+                                diff.base_text()
+                                    .text_for_range(hunk.diff_base_byte_range)
+                                    .collect::<String>(),
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(
+            hunks,
+            [
+                (DiffHunkStatusKind::Modified, "fn greet() {}\n".to_string()),
+                (DiffHunkStatusKind::Modified, "    greet();\n".to_string()),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_rename_outside_project(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "main.rs": "fn greet() {}\n\nfn main() {\n    greet();\n}\n",
+                },
+            }),
+        )
+        .await;
+        fs.insert_tree(
+            path!("/dependency"),
+            json!({ "lib.rs": "pub fn greet() {}\n" }),
+        )
+        .await;
+        let (project, fake_server, _handle) = rust_project(fs.clone(), cx).await;
+        fake_server.set_request_handler::<lsp::request::Rename, _, _>(|params, _| async move {
+            let edit = || {
+                vec![lsp::TextEdit::new(
+                    lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 8)),
+                    "welcome".into(),
+                )]
+            };
+            Ok(Some(lsp::WorkspaceEdit {
+                changes: Some(
+                    [
+                        (params.text_document_position.text_document.uri, edit()),
+                        (
+                            lsp::Url::from_file_path(path!("/dependency/lib.rs")).unwrap(),
+                            vec![lsp::TextEdit::new(
+                                lsp::Range::new(
+                                    lsp::Position::new(0, 7),
+                                    lsp::Position::new(0, 12),
+                                ),
+                                "welcome".into(),
+                            )],
+                        ),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..Default::default()
+            }))
+        });
+
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let error = run_rename(project.clone(), action_log.clone(), cx)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Renaming `greet` would also edit files outside the project, so it was undone:\n- {}",
+                path!("/dependency/lib.rs")
+            )
+        );
+        let main_text = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/root/src/main.rs"), cx)
+            })
+            .await
+            .unwrap()
+            .read_with(cx, |buffer, _| buffer.text());
+        assert_eq!(main_text, "fn greet() {}\n\nfn main() {\n    greet();\n}\n");
+        assert_eq!(
+            fs.load(path!("/dependency/lib.rs").as_ref()).await.unwrap(),
+            "pub fn greet() {}\n"
+        );
+        assert!(action_log.read_with(cx, |log, cx| log.changed_buffers(cx).is_empty()));
+    }
+
+    fn run_rename(
+        project: Entity<Project>,
+        action_log: Entity<ActionLog>,
+        cx: &mut TestAppContext,
+    ) -> Task<Result<assistant_tool::ToolResultOutput>> {
+        let input = serde_json::to_value(RenameSymbolToolInput {
+            path: "root/src/main.rs".into(),
+            line: 1,
+            symbol: "greet".into(),
+            new_name: "welcome".into(),
+        })
+        .unwrap();
+        cx.update(|cx| {
+            Arc::new(RenameSymbolTool)
+                .run(
+                    input,
+                    Arc::default(),
+                    project,
+                    action_log,
+                    Arc::new(FakeLanguageModel::default()),
+                    None,
+                    cx,
+                )
+                .output
+        })
+    }
+}
@@ -0,0 +1,201 @@
+use crate::schema::json_schema_for;
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use gpui::{AnyWindowHandle, App, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::Project;
+use schemars::JsonSchema;
+use semantic_index::{SemanticDb, Status};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+/// The number of ranked chunks to return for a query.
+const RESULT_LIMIT: usize = 16;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SemanticSearchToolInput {
+    /// A natural-language description of the code to find.
+    ///
+    /// <example>
+    /// To find where failed HTTP requests are retried, use "retrying failed HTTP requests".
+    /// </example>
+    pub query: String,
+}
+
+pub struct SemanticSearchTool;
+
+impl Tool for SemanticSearchTool {
+    fn name(&self) -> String {
+        "semantic_search".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &Entity<Project>, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn description(&self) -> String {
+        include_str!("./semantic_search_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::ToolSearch
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<SemanticSearchToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<SemanticSearchToolInput>(input.clone()) {
+            Ok(input) => format!("Semantic search for {}", MarkdownInlineCode(&input.query)),
+            Err(_) => "Semantic search".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<SemanticSearchToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+        let Some(project_index) = cx
+            .try_global::<SemanticDb>()
+            .and_then(|semantic_db| semantic_db.project_index(project.clone(), cx))
+        else {
+            return Task::ready(Err(anyhow!(
+                "Semantic search is not available for this project"
+            )))
+            .into();
+        };
+
+        let project_index = project_index.read(cx);
+        let still_indexing = project_index.status() != Status::Idle;
+        let search = project_index.search(vec![input.query], RESULT_LIMIT, cx);
+        let fs = project.read(cx).fs().clone();
+        cx.spawn(async move |cx| {
+            let results = SemanticDb::load_results(search.await?, &fs, cx).await?;
+            if results.is_empty() {
+                return Ok("No results found".to_string().into());
+            }
+
+            let mut output = String::new();
+            if still_indexing {
+                writeln!(
+                    output,
+                    "The project is still being indexed, so these results may be incomplete."
+                )?;
+            }
+            for result in &results {
+                writeln!(output, "\n## Matches in {}", result.full_path.display())?;
+                let start_row = *result.row_range.start();
+                let end_row = *result.row_range.end();
+                if start_row == end_row {
+                    writeln!(output, "\n### L{}", start_row + 1)?;
+                } else {
+                    writeln!(output, "\n### L{}-{}", start_row + 1, end_row + 1)?;
+                }
+                output.push_str("```\n");
+                output.push_str(&result.excerpt_content);
+                if !result.excerpt_content.ends_with('\n') {
+                    output.push('\n');
+                }
+                output.push_str("```\n");
+            }
+            Ok(format!("Found {} results:\n{output}", results.len()).into())
+        })
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext, TestAppContext};
+    use language_model::fake_provider::FakeLanguageModel;
+    use project::FakeFs;
+    use semantic_index::FakeEmbeddingProvider;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_semantic_search_tool(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "retry.rs": "fn retry_request() {\n    // retry failed requests\n}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+
+        let tool = Arc::new(SemanticSearchTool);
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let model = Arc::new(FakeLanguageModel::default());
+        let input = serde_json::to_value(SemanticSearchToolInput {
+            query: "retrying requests".into(),
+        })
+        .unwrap();
+
+        // Without a semantic index, the tool reports that it isn't available.
+        let result = cx.update(|cx| {
+            tool.clone().run(
+                input.clone(),
+                Arc::default(),
+                project.clone(),
+                action_log.clone(),
+                model.clone(),
+                None,
+                cx,
+            )
+        });
+        assert!(result.output.await.is_err());
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut semantic_db = SemanticDb::new(
+            temp_dir.path().into(),
+            Arc::new(FakeEmbeddingProvider),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        let project_index = cx.update(|cx| semantic_db.create_project_index(project.clone(), cx));
+        cx.update(|cx| cx.set_global(semantic_db));
+        cx.run_until_parked();
+        while project_index.read_with(cx, |project_index, _| project_index.status()) != Status::Idle
+        {
+            cx.run_until_parked();
+        }
+
+        let result =
+            cx.update(|cx| tool.run(input, Arc::default(), project, action_log, model, None, cx));
+        let output = result.output.await.unwrap();
+        let output = output.content.as_str().unwrap();
+        assert!(output.contains("## Matches in root"), "{output}");
+        assert!(output.contains("retry.rs"), "{output}");
+        assert!(output.contains("fn retry_request()"), "{output}");
+    }
+}
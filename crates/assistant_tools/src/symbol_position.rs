@@ -0,0 +1,290 @@
+//! Shared by the tools that ask language servers about a symbol in a file.
+
+use anyhow::{Context as _, Result, anyhow};
+use gpui::{App, AsyncApp, Entity, Task};
+use language::{Buffer, Location, Point, ToPoint as _};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// The maximum number of locations listed in a tool's output.
+pub(crate) const MAX_LOCATIONS: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SymbolPosition {
+    /// The relative path of the file containing the symbol.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    ///
+    /// <example>
+    /// If the project has the following root directories:
+    ///
+    /// - /a/b/directory1
+    /// - /c/d/directory2
+    ///
+    /// If you want to access `file.txt` in `directory1`, you should use the path `directory1/file.txt`.
+    /// If you want to access `file.txt` in `directory2`, you should use the path `directory2/file.txt`.
+    /// </example>
+    pub path: String,
+
+    /// The line number of the symbol (1-based index)
+    pub line: u32,
+
+    /// The symbol as it is written on that line, like `parse_config` or `Config`
+    pub symbol: String,
+}
+
+impl SymbolPosition {
+    /// Opens the buffer of the file and finds the symbol on its line.
+    pub fn resolve(
+        &self,
+        project: &Entity<Project>,
+        cx: &mut App,
+    ) -> Task<Result<(Entity<Buffer>, Point)>> {
+        let Some(project_path) = project.read(cx).find_project_path(&self.path, cx) else {
+            return Task::ready(Err(anyhow!("Path {} not found in project", self.path)));
+        };
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+        let path = self.path.clone();
+        let line = self.line;
+        let symbol = self.symbol.clone();
+        cx.spawn(async move |cx| {
+            let buffer = open_buffer.await?;
+            let position = buffer.read_with(cx, |buffer, _| {
+                let row = line.saturating_sub(1);
+                anyhow::ensure!(
+                    row <= buffer.max_point().row,
+                    "Line {line} is past the end of {path}"
+                );
+                let line_text = buffer
+                    .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+                    .collect::<String>();
+                let column = symbol_column(&line_text, &symbol).with_context(|| {
+                    format!("`{symbol}` doesn't appear on line {line} of {path}")
+                })?;
+                Ok(Point::new(row, column as u32))
+            })??;
+            Ok((buffer, position))
+        })
+    }
+}
+
+/// Returns the byte offset of `symbol` in `line`, preferring an occurrence that isn't part of a
+/// longer identifier.
+fn symbol_column(line: &str, symbol: &str) -> Option<usize> {
+    if symbol.is_empty() {
+        return None;
+    }
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(symbol)
+        .map(|(ix, _)| ix)
+        .find(|&ix| {
+            let before = line[..ix].chars().next_back();
+            let after = line[ix + symbol.len()..].chars().next();
+            !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+        })
+        .or_else(|| line.find(symbol))
+}
+
+/// Formats locations as `path:line: text` lines, listing at most [`MAX_LOCATIONS`] of them.
+pub(crate) fn format_locations(locations: &[Location], cx: &AsyncApp) -> Result<String> {
+    cx.update(|cx| {
+        let mut output = String::new();
+        for location in locations.iter().take(MAX_LOCATIONS) {
+            let buffer = location.buffer.read(cx);
+            let Some(file) = buffer.file() else {
+                continue;
+            };
+            let row = location.range.start.to_point(buffer).row;
+            let line_text = buffer
+                .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+                .collect::<String>();
+            writeln!(
+                output,
+                "{}:{}: {}",
+                file.full_path(cx).display(),
+                row + 1,
+                line_text.trim()
+            )
+            .ok();
+        }
+        if locations.len() > MAX_LOCATIONS {
+            writeln!(output, "... and {} more", locations.len() - MAX_LOCATIONS).ok();
+        }
+        output
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        find_references_tool::FindReferencesTool, go_to_definition_tool::GoToDefinitionTool,
+    };
+    use assistant_tool::{ActionLog, Tool};
+    use futures::StreamExt as _;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::{Language, LanguageConfig, LanguageMatcher};
+    use language_model::fake_provider::FakeLanguageModel;
+    use project::{FakeFs, lsp_store::OpenLspBufferHandle};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::sync::Arc;
+    use util::path;
+
+    #[test]
+    fn test_symbol_column() {
+        assert_eq!(
+            symbol_column("let config = parse_config();", "config"),
+            Some(4)
+        );
+        assert_eq!(
+            symbol_column("parse_config(config_path)", "config"),
+            Some(6)
+        );
+        assert_eq!(symbol_column("fn main() {}", "main"), Some(3));
+        assert_eq!(symbol_column("fn main() {}", "other"), None);
+        assert_eq!(symbol_column("fn main() {}", ""), None);
+    }
+
+    #[gpui::test]
+    async fn test_definitions_and_references_output(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "main.rs": "mod config;\n\nfn main() {\n    config::parse_config();\n}\n",
+                    "config.rs": "/// Parses the config.\npub fn parse_config() {}\n",
+                },
+            }),
+        )
+        .await;
+        let (project, fake_server, _handle) = rust_project(fs, cx).await;
+        let location = |path: &str, line: u32, column: u32| lsp::Location {
+            uri: lsp::Url::from_file_path(path).unwrap(),
+            range: lsp::Range::new(
+                lsp::Position::new(line, column),
+                lsp::Position::new(line, column + "parse_config".len() as u32),
+            ),
+        };
+        fake_server.set_request_handler::<lsp::request::GotoDefinition, _, _>(
+            move |_, _| async move {
+                Ok(Some(lsp::GotoDefinitionResponse::Scalar(location(
+                    path!("/root/src/config.rs"),
+                    1,
+                    7,
+                ))))
+            },
+        );
+        fake_server.set_request_handler::<lsp::request::References, _, _>(move |_, _| async move {
+            Ok(Some(vec![
+                location(path!("/root/src/config.rs"), 1, 7),
+                location(path!("/root/src/main.rs"), 3, 12),
+            ]))
+        });
+
+        let input = json!({
+            "path": "root/src/main.rs",
+            "line": 4,
+            "symbol": "parse_config",
+        });
+        let definitions = run_tool(Arc::new(GoToDefinitionTool), input.clone(), &project, cx)
+            .await
+            .unwrap();
+        assert_eq!(
+            definitions,
+            "root/src/config.rs:2: pub fn parse_config() {}\n"
+        );
+        let references = run_tool(Arc::new(FindReferencesTool), input, &project, cx)
+            .await
+            .unwrap();
+        assert_eq!(
+            references,
+            "Found 2 references:\n\
+            root/src/config.rs:2: pub fn parse_config() {}\n\
+            root/src/main.rs:4: config::parse_config();\n"
+        );
+    }
+
+    pub(crate) fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+        });
+    }
+
+    /// Creates a project for `/root` with a fake Rust language server, which is started by
+    /// opening `/root/src/main.rs`.
+    pub(crate) async fn rust_project(
+        fs: Arc<FakeFs>,
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<Project>,
+        lsp::FakeLanguageServer,
+        OpenLspBufferHandle,
+    ) {
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            language::FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    definition_provider: Some(lsp::OneOf::Left(true)),
+                    references_provider: Some(lsp::OneOf::Left(true)),
+                    rename_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        let (_, handle) = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/root/src/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let fake_server = fake_servers.next().await.unwrap();
+        (project, fake_server, handle)
+    }
+
+    async fn run_tool(
+        tool: Arc<dyn Tool>,
+        input: serde_json::Value,
+        project: &Entity<Project>,
+        cx: &mut TestAppContext,
+    ) -> Result<String> {
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    input,
+                    Arc::default(),
+                    project.clone(),
+                    action_log,
+                    Arc::new(FakeLanguageModel::default()),
+                    None,
+                    cx,
+                )
+                .output
+            })
+            .await?;
+        Ok(output.content.as_str().unwrap_or_default().to_string())
+    }
+}
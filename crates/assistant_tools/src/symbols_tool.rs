@@ -0,0 +1,176 @@
+use crate::schema::json_schema_for;
+use crate::symbol_position::MAX_LOCATIONS;
+use anyhow::{Result, anyhow};
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use gpui::{AnyWindowHandle, App, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRequest, LanguageModelToolSchemaFormat};
+use project::{DocumentSymbol, Project};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, path::Path, sync::Arc};
+use ui::IconName;
+use util::markdown::MarkdownInlineCode;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SymbolsToolInput {
+    /// The relative path of a file to list the symbols of, as an outline.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    ///
+    /// <example>
+    /// If the project has the following root directories:
+    ///
+    /// - /a/b/directory1
+    /// - /c/d/directory2
+    ///
+    /// If you want to access `file.txt` in `directory1`, you should use the path `directory1/file.txt`.
+    /// If you want to access `file.txt` in `directory2`, you should use the path `directory2/file.txt`.
+    /// </example>
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// Text to search the symbols of the whole project for, when no path is given.
+    #[serde(default)]
+    pub query: Option<String>,
+}
+
+pub struct SymbolsTool;
+
+impl Tool for SymbolsTool {
+    fn name(&self) -> String {
+        "symbols".into()
+    }
+
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &Entity<Project>, _: &App) -> bool {
+        false
+    }
+
+    fn may_perform_edits(&self) -> bool {
+        false
+    }
+
+    fn description(&self) -> String {
+        include_str!("./symbols_tool/description.md").into()
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::ToolSearch
+    }
+
+    fn input_schema(&self, format: LanguageModelToolSchemaFormat) -> Result<serde_json::Value> {
+        json_schema_for::<SymbolsToolInput>(format)
+    }
+
+    fn ui_text(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<SymbolsToolInput>(input.clone()) {
+            Ok(SymbolsToolInput {
+                path: Some(path), ..
+            }) if !path.is_empty() => format!("List symbols in {}", MarkdownInlineCode(&path)),
+            Ok(SymbolsToolInput {
+                query: Some(query), ..
+            }) => format!("Search project symbols for {}", MarkdownInlineCode(&query)),
+            _ => "List symbols".to_string(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        _request: Arc<LanguageModelRequest>,
+        project: Entity<Project>,
+        _action_log: Entity<ActionLog>,
+        _model: Arc<dyn LanguageModel>,
+        _window: Option<AnyWindowHandle>,
+        cx: &mut App,
+    ) -> ToolResult {
+        let input = match serde_json::from_value::<SymbolsToolInput>(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
+        };
+
+        match input.path.filter(|path| !path.is_empty()) {
+            Some(path) => {
+                let Some(project_path) = project.read(cx).find_project_path(&path, cx) else {
+                    return Task::ready(Err(anyhow!("Path {path} not found in project"))).into();
+                };
+                let buffer =
+                    project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+
+                cx.spawn(async move |cx| {
+                    let buffer = buffer.await?;
+                    let symbols = project
+                        .update(cx, |project, cx| project.document_symbols(&buffer, cx))?
+                        .await?;
+                    if symbols.is_empty() {
+                        return Ok(format!("No symbols found in {path}.").into());
+                    }
+                    let mut output = String::new();
+                    write_outline(&symbols, 0, &mut output);
+                    Ok(output.into())
+                })
+                .into()
+            }
+            None => {
+                let Some(query) = input.query else {
+                    return Task::ready(Err(anyhow!("Either a path or a query is required")))
+                        .into();
+                };
+                let symbols = project.update(cx, |project, cx| project.symbols(&query, cx));
+
+                cx.spawn(async move |cx| {
+                    let symbols = symbols.await?;
+                    if symbols.is_empty() {
+                        return Ok(format!("No symbols found matching `{query}`.").into());
+                    }
+                    project.read_with(cx, |project, cx| {
+                        let mut output = String::new();
+                        for symbol in symbols.iter().take(MAX_LOCATIONS) {
+                            let Some(worktree) =
+                                project.worktree_for_id(symbol.path.worktree_id, cx)
+                            else {
+                                continue;
+                            };
+                            writeln!(
+                                output,
+                                "{} {} at {}:{}",
+                                symbol_kind(symbol.kind),
+                                symbol.name,
+                                Path::new(worktree.read(cx).root_name())
+                                    .join(&symbol.path.path)
+                                    .display(),
+                                symbol.range.start.0.row + 1
+                            )
+                            .ok();
+                        }
+                        if symbols.len() > MAX_LOCATIONS {
+                            writeln!(output, "... and {} more", symbols.len() - MAX_LOCATIONS).ok();
+                        }
+                        output.into()
+                    })
+                })
+                .into()
+            }
+        }
+    }
+}
+
+fn write_outline(symbols: &[DocumentSymbol], depth: usize, output: &mut String) {
+    for symbol in symbols {
+        writeln!(
+            output,
+            "{}{} {} [L{}-{}]",
+            "  ".repeat(depth),
+            symbol_kind(symbol.kind),
+            symbol.name,
+            symbol.range.start.0.row + 1,
+            symbol.range.end.0.row + 1
+        )
+        .ok();
+        write_outline(&symbol.children, depth + 1, output);
+    }
+}
+
+fn symbol_kind(kind: lsp::SymbolKind) -> String {
+    format!("{kind:?}").to_lowercase().replace('_', " ")
+}
@@ -85,6 +85,16 @@ impl Tool for TerminalTool {
         false
     }
 
+    fn permission_subject(
+        &self,
+        input: &serde_json::Value,
+    ) -> Option<agent_settings::ToolPermissionSubject> {
+        let input = serde_json::from_value::<TerminalToolInput>(input.clone()).ok()?;
+        Some(agent_settings::ToolPermissionSubject::Command(
+            input.command,
+        ))
+    }
+
     fn description(&self) -> String {
         include_str!("./terminal_tool/description.md").to_string()
     }
@@ -149,7 +159,7 @@ impl Tool for TerminalTool {
         } else {
             format!("({}) </dev/null", input.command)
         };
-        let args = vec!["-c".into(), command];
+        let args = vec!["-c".into(), command.clone()];
 
         let cwd = working_dir.clone();
         let env = match &working_dir {
@@ -214,6 +224,11 @@ impl Tool for TerminalTool {
             let project = project.downgrade();
             async move |cx| {
                 let program = program.await;
+                // Mark where the command output starts and ends, so that exactly that output is read from the terminal.
+                let args = match terminal::mark_command_output(&program, &command) {
+                    Some(command) if cfg!(not(windows)) => vec!["-c".into(), command],
+                    _ => args,
+                };
                 let env = env.await;
                 let terminal = project
                     .update(cx, |project, cx| {
@@ -286,7 +301,11 @@ impl Tool for TerminalTool {
                     .update(cx, |terminal, cx| terminal.wait_for_completed_task(cx))?
                     .await;
                 let (content, content_line_count) = terminal.read_with(cx, |terminal, _| {
-                    (terminal.get_content(), terminal.total_lines())
+                    let content = match terminal.last_finished_command() {
+                        Some(command) => command.output.clone(),
+                        None => terminal.get_content(),
+                    };
+                    (content, terminal.total_lines())
                 })?;
 
                 let previous_len = content.len();
@@ -443,6 +443,10 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::SetIndexText>)
             .add_request_handler(forward_mutating_project_request::<proto::ToggleBreakpoint>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BreakpointsForFile>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::ShareTerminal>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UnshareTerminal>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UpdateSharedTerminal>)
+            .add_request_handler(forward_mutating_project_request::<proto::SharedTerminalInput>)
             .add_request_handler(forward_mutating_project_request::<proto::OpenCommitMessageBuffer>)
             .add_request_handler(forward_mutating_project_request::<proto::GitDiff>)
             .add_request_handler(forward_mutating_project_request::<proto::GitCreateBranch>)
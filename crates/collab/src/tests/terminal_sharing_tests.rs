@@ -0,0 +1,158 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use call::ActiveCall;
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{BackgroundExecutor, Entity, TestAppContext};
+use project::{Event, terminals::TerminalAccess};
+use rpc::proto;
+use serde_json::json;
+use terminal::{Terminal, TerminalBuilder};
+
+use crate::tests::TestServer;
+
+#[gpui::test]
+async fn test_share_terminal(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    cx_a.update(terminal::init);
+    cx_b.update(terminal::init);
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "a.txt": "a-contents" }))
+        .await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+
+    let mirrored_terminals = Rc::new(RefCell::new(Vec::<Entity<Terminal>>::new()));
+    let _subscription = cx_b.update(|cx| {
+        let mirrored_terminals = mirrored_terminals.clone();
+        cx.subscribe(&project_b, move |_, event, _| {
+            if let Event::TerminalShared(terminal) = event {
+                mirrored_terminals.borrow_mut().push(terminal.clone());
+            }
+        })
+    });
+
+    let (input_tx, mut input_rx) = mpsc::unbounded();
+    let (completion_tx, _completion_rx) = smol::channel::bounded(1);
+    let terminal_a = cx_a
+        .new(|cx| TerminalBuilder::new_fake("shell".into(), input_tx, completion_tx).subscribe(cx));
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"$ echo hello\r\nhello\r\n$ ", cx)
+    });
+
+    // The guest mirrors the screen of the terminal shared read-only.
+    project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, TerminalAccess::ReadOnly, cx)
+        })
+        .unwrap();
+    executor.run_until_parked();
+    let terminal_b = {
+        let mirrored_terminals = mirrored_terminals.borrow();
+        assert_eq!(mirrored_terminals.len(), 1);
+        mirrored_terminals[0].clone()
+    };
+    assert_eq!(
+        project_b.read_with(cx_b, |project, _| project.terminal_access(&terminal_b)),
+        Some(TerminalAccess::ReadOnly)
+    );
+    assert_eq!(screen_text(&terminal_b, cx_b), "$ echo hello\nhello\n$");
+
+    // The later output is mirrored too.
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"ls\r\na.txt\r\n$ ", cx)
+    });
+    executor.advance_clock(Duration::from_millis(100));
+    executor.run_until_parked();
+    assert_eq!(
+        screen_text(&terminal_b, cx_b),
+        "$ echo hello\nhello\n$ ls\na.txt\n$"
+    );
+
+    // The guest's input is refused while the terminal is shared read-only, by the guest and by the host.
+    terminal_b.update(cx_b, |terminal, _| terminal.input(b"rm a.txt\r".to_vec()));
+    executor.run_until_parked();
+    let response = client_b
+        .client()
+        .request(proto::SharedTerminalInput {
+            project_id,
+            // The id of the first terminal shared by the host.
+            terminal_id: 0,
+            input: b"rm a.txt\r".to_vec(),
+        })
+        .await;
+    assert!(response.is_err(), "The host should refuse the input");
+    executor.run_until_parked();
+    assert!(
+        input_rx.try_next().is_err(),
+        "No input should reach the read-only terminal"
+    );
+
+    // Once shared read-write, the guest's input reaches the host's terminal.
+    project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, TerminalAccess::ReadWrite, cx)
+        })
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(mirrored_terminals.borrow().len(), 1);
+    assert_eq!(
+        project_b.read_with(cx_b, |project, _| project.terminal_access(&terminal_b)),
+        Some(TerminalAccess::ReadWrite)
+    );
+    terminal_b.update(cx_b, |terminal, _| terminal.input(b"pwd\r".to_vec()));
+    executor.run_until_parked();
+    assert_eq!(input_rx.next().await, Some(b"pwd\r".to_vec()));
+
+    // Unsharing the terminal closes the guest's mirror.
+    let closed = Rc::new(RefCell::new(false));
+    let _close_subscription = cx_b.update(|cx| {
+        let closed = closed.clone();
+        cx.subscribe(&terminal_b, move |_, event, _| {
+            if matches!(event, terminal::Event::CloseTerminal) {
+                *closed.borrow_mut() = true;
+            }
+        })
+    });
+    project_a.update(cx_a, |project, cx| {
+        project.unshare_terminal(&terminal_a, cx)
+    });
+    executor.run_until_parked();
+    assert!(*closed.borrow(), "The guest's mirror should be closed");
+    assert_eq!(
+        project_b.read_with(cx_b, |project, _| project.terminal_access(&terminal_b)),
+        None
+    );
+    assert_eq!(
+        project_a.read_with(cx_a, |project, _| project.terminal_access(&terminal_a)),
+        None
+    );
+}
+
+fn screen_text(terminal: &Entity<Terminal>, cx: &mut TestAppContext) -> String {
+    terminal.read_with(cx, |terminal, _| {
+        terminal
+            .get_content()
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim_end()
+            .to_string()
+    })
+}
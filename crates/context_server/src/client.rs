@@ -36,7 +36,8 @@ pub const INTERNAL_ERROR: i32 = -32603;
 
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
 type NotificationHandler = Box<dyn Send + FnMut(Value, AsyncApp)>;
-type RequestHandler = Box<dyn Send + FnMut(RequestId, &RawValue, AsyncApp)>;
+/// Handles a request sent by the context server, resolving to the result sent back to it.
+pub type RequestHandler = Box<dyn Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -51,6 +52,7 @@ pub(crate) struct Client {
     outbound_tx: channel::Sender<String>,
     name: Arc<str>,
     notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
@@ -197,6 +199,7 @@ impl Client {
             let notification_handlers = notification_handlers.clone();
             let response_handlers = response_handlers.clone();
             let request_handlers = request_handlers.clone();
+            let outbound_tx = outbound_tx.clone();
             let transport = transport.clone();
             async move |cx| {
                 Self::handle_input(
@@ -204,6 +207,7 @@ impl Client {
                     notification_handlers,
                     request_handlers,
                     response_handlers,
+                    outbound_tx,
                     cx,
                 )
                 .log_err()
@@ -233,6 +237,7 @@ impl Client {
         Ok(Self {
             server_id,
             notification_handlers,
+            request_handlers,
             response_handlers,
             name: server_name,
             next_id: Default::default(),
@@ -247,14 +252,16 @@ impl Client {
     /// Handles input from the server's stdout.
     ///
     /// This function continuously reads lines from the provided stdout stream,
-    /// parses them as JSON-RPC responses or notifications, and dispatches them
-    /// to the appropriate handlers. It processes both responses (which are matched
-    /// to pending requests) and notifications (which trigger registered handlers).
+    /// parses them as JSON-RPC requests, responses or notifications, and dispatches them
+    /// to the appropriate handlers. It processes requests (whose handlers' results are
+    /// sent back to the server), responses (which are matched to pending requests) and
+    /// notifications (which trigger registered handlers).
     async fn handle_input(
         transport: Arc<dyn Transport>,
         notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
         request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<()> {
         let mut receiver = transport.receive();
@@ -262,14 +269,48 @@ impl Client {
         while let Some(message) = receiver.next().await {
             log::trace!("recv: {}", &message);
             if let Ok(request) = serde_json::from_str::<AnyRequest>(&message) {
-                let mut request_handlers = request_handlers.lock();
-                if let Some(handler) = request_handlers.get_mut(request.method) {
-                    handler(
-                        request.id,
-                        request.params.unwrap_or(RawValue::NULL),
-                        cx.clone(),
-                    );
-                }
+                let id = request.id;
+                let method = request.method.to_string();
+                let response = request_handlers
+                    .lock()
+                    .get_mut(request.method)
+                    .map(|handler| {
+                        let params = request
+                            .params
+                            .and_then(|params| serde_json::from_str(params.get()).ok())
+                            .unwrap_or(Value::Null);
+                        handler(params, cx.clone())
+                    });
+                let outbound_tx = outbound_tx.clone();
+                cx.background_spawn(
+                    async move {
+                        let value = match response {
+                            Some(response) => match response.await {
+                                Ok(result) => CspResult::Ok(Some(result)),
+                                Err(error) => {
+                                    log::error!("failed to handle {method:?} request: {error:#}");
+                                    CspResult::Error(Some(Error {
+                                        message: error.to_string(),
+                                        code: INTERNAL_ERROR,
+                                    }))
+                                }
+                            },
+                            None => CspResult::Error(Some(Error {
+                                message: format!("Method not found: {method}"),
+                                code: METHOD_NOT_FOUND,
+                            })),
+                        };
+                        let response = serde_json::to_string(&Response {
+                            jsonrpc: JSON_RPC_VERSION,
+                            id,
+                            value,
+                        })?;
+                        outbound_tx.send(response).await?;
+                        anyhow::Ok(())
+                    }
+                    .log_err(),
+                )
+                .detach();
             } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut() {
                     if let Some(handler) = handlers.remove(&response.id) {
@@ -448,6 +489,12 @@ impl Client {
     ) {
         self.notification_handlers.lock().insert(method, f);
     }
+
+    /// Registers a handler for requests the context server sends to us.
+    /// The result of the task it returns is sent back to the server as the response.
+    pub fn on_request(&self, method: &'static str, f: RequestHandler) {
+        self.request_handlers.lock().insert(method, f);
+    }
 }
 
 #[derive(Debug)]
@@ -475,3 +522,109 @@ impl fmt::Debug for Client {
             .finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use futures::Stream;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    use super::*;
+
+    /// A transport the test sends the server's messages over, and reads the client's messages from.
+    struct TestTransport {
+        server_rx: channel::Receiver<String>,
+        client_tx: channel::Sender<String>,
+    }
+
+    #[async_trait::async_trait]
+    impl Transport for TestTransport {
+        async fn send(&self, message: String) -> Result<()> {
+            self.client_tx.send(message).await?;
+            Ok(())
+        }
+
+        fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+            Box::pin(self.server_rx.clone())
+        }
+
+        fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+            Box::pin(futures::stream::empty())
+        }
+    }
+
+    #[gpui::test]
+    async fn test_requests_from_the_server(cx: &mut TestAppContext) {
+        let (server_tx, server_rx) = channel::unbounded();
+        let (client_tx, client_rx) = channel::unbounded();
+        let client = Client::new(
+            ContextServerId("test".into()),
+            "test".into(),
+            Arc::new(TestTransport {
+                server_rx,
+                client_tx,
+            }),
+            cx.to_async(),
+        )
+        .unwrap();
+        client.on_request(
+            "roots/list",
+            Box::new(|params: Value, _: AsyncApp| Task::ready(Ok(json!({ "params": params })))),
+        );
+        client.on_request(
+            "sampling/createMessage",
+            Box::new(|_: Value, _: AsyncApp| {
+                Task::ready(Err(anyhow!("The user rejected the sampling request")))
+            }),
+        );
+
+        let mut responses = client_rx.map(|message| {
+            serde_json::from_str::<Value>(&message).expect("invalid response from the client")
+        });
+
+        // The result of the handler is sent back to the server.
+        server_tx
+            .send(
+                json!({"jsonrpc": "2.0", "id": 1, "method": "roots/list", "params": {"a": 1}})
+                    .to_string(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            responses.next().await.unwrap(),
+            json!({"jsonrpc": "2.0", "id": 1, "result": {"params": {"a": 1}}})
+        );
+
+        // The error of the handler is sent back as an internal error.
+        server_tx
+            .send(
+                json!({"jsonrpc": "2.0", "id": "sample", "method": "sampling/createMessage"})
+                    .to_string(),
+            )
+            .await
+            .unwrap();
+        let response = responses.next().await.unwrap();
+        assert_eq!(response["id"], json!("sample"));
+        assert_eq!(response["error"]["code"], json!(INTERNAL_ERROR));
+        assert_eq!(
+            response["error"]["message"],
+            json!("The user rejected the sampling request")
+        );
+
+        // The requests without a handler are answered with an error too, so the server doesn't wait for them.
+        server_tx
+            .send(json!({"jsonrpc": "2.0", "id": 2, "method": "elicitation/create"}).to_string())
+            .await
+            .unwrap();
+        let response = responses.next().await.unwrap();
+        assert_eq!(response["id"], json!(2));
+        assert_eq!(response["error"]["code"], json!(METHOD_NOT_FOUND));
+        assert_eq!(
+            response["error"]["message"],
+            json!("Method not found: elicitation/create")
+        );
+        assert!(response.get("result").is_none());
+    }
+}
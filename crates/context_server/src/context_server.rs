@@ -14,9 +14,11 @@ use anyhow::Result;
 use client::Client;
 use collections::HashMap;
 use gpui::AsyncApp;
+use http_client::{HttpClient, Url};
 use parking_lot::RwLock;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use types::Request as _;
 use util::redact::should_redact;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -52,8 +54,38 @@ impl std::fmt::Debug for ContextServerCommand {
     }
 }
 
+/// The URL a context server is served at over HTTP, and the headers sent with every request.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ContextServerEndpoint {
+    pub url: Url,
+    pub headers: HashMap<String, String>,
+}
+
+impl std::fmt::Debug for ContextServerEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let filtered_headers = self
+            .headers
+            .iter()
+            .map(|(k, v)| {
+                let name = k.to_uppercase();
+                if name == "AUTHORIZATION" || should_redact(&name) {
+                    (k, "[REDACTED]")
+                } else {
+                    (k, v.as_str())
+                }
+            })
+            .collect::<Vec<_>>();
+
+        f.debug_struct("ContextServerEndpoint")
+            .field("url", &self.url)
+            .field("headers", &filtered_headers)
+            .finish()
+    }
+}
+
 enum ContextServerTransport {
     Stdio(ContextServerCommand, Option<PathBuf>),
+    Http(ContextServerEndpoint, Arc<dyn HttpClient>),
     Custom(Arc<dyn crate::transport::Transport>),
 }
 
@@ -79,6 +111,18 @@ impl ContextServer {
         }
     }
 
+    pub fn http(
+        id: ContextServerId,
+        endpoint: ContextServerEndpoint,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Self {
+        Self {
+            id,
+            client: RwLock::new(None),
+            configuration: ContextServerTransport::Http(endpoint, http_client),
+        }
+    }
+
     pub fn new(id: ContextServerId, transport: Arc<dyn crate::transport::Transport>) -> Self {
         Self {
             id,
@@ -96,23 +140,49 @@ impl ContextServer {
     }
 
     pub async fn start(&self, cx: &AsyncApp) -> Result<()> {
-        self.initialize(self.new_client(cx)?).await
+        self.initialize(self.new_client(cx)?, Self::client_capabilities(&[]))
+            .await
     }
 
-    /// Starts the context server, making sure handlers are registered before initialization happens
+    /// Starts the context server, making sure handlers are registered before initialization happens.
+    ///
+    /// The capabilities advertised to the server are derived from the requests we can handle.
     pub async fn start_with_handlers(
         &self,
         notification_handlers: Vec<(
             &'static str,
             Box<dyn 'static + Send + FnMut(serde_json::Value, AsyncApp)>,
         )>,
+        request_handlers: Vec<(&'static str, client::RequestHandler)>,
         cx: &AsyncApp,
     ) -> Result<()> {
         let client = self.new_client(cx)?;
         for (method, handler) in notification_handlers {
             client.on_notification(method, handler);
         }
-        self.initialize(client).await
+        let methods = request_handlers
+            .iter()
+            .map(|(method, _)| *method)
+            .collect::<Vec<_>>();
+        for (method, handler) in request_handlers {
+            client.on_request(method, handler);
+        }
+        self.initialize(client, Self::client_capabilities(&methods))
+            .await
+    }
+
+    fn client_capabilities(request_methods: &[&str]) -> types::ClientCapabilities {
+        types::ClientCapabilities {
+            experimental: None,
+            sampling: request_methods
+                .contains(&types::requests::CreateMessage::METHOD)
+                .then(|| serde_json::json!({})),
+            roots: request_methods
+                .contains(&types::requests::ListRoots::METHOD)
+                .then_some(types::RootsCapabilities {
+                    list_changed: Some(true),
+                }),
+        }
     }
 
     fn new_client(&self, cx: &AsyncApp) -> Result<Client> {
@@ -127,6 +197,16 @@ impl ContextServer {
                 working_directory,
                 cx.clone(),
             )?,
+            ContextServerTransport::Http(endpoint, http_client) => Client::new(
+                client::ContextServerId(self.id.0.clone()),
+                endpoint.url.host_str().unwrap_or_default().into(),
+                Arc::new(crate::transport::HttpTransport::new(
+                    endpoint.clone(),
+                    http_client.clone(),
+                    cx,
+                )),
+                cx.clone(),
+            )?,
             ContextServerTransport::Custom(transport) => Client::new(
                 client::ContextServerId(self.id.0.clone()),
                 self.id().0,
@@ -136,14 +216,18 @@ impl ContextServer {
         })
     }
 
-    async fn initialize(&self, client: Client) -> Result<()> {
+    async fn initialize(
+        &self,
+        client: Client,
+        capabilities: types::ClientCapabilities,
+    ) -> Result<()> {
         log::info!("starting context server {}", self.id);
         let protocol = crate::protocol::ModelContextProtocol::new(client);
         let client_info = types::Implementation {
             name: "Zed".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
         };
-        let initialized_protocol = protocol.initialize(client_info).await?;
+        let initialized_protocol = protocol.initialize(client_info, capabilities).await?;
 
         log::debug!(
             "context server {} initialized: {:?}",
@@ -9,15 +9,19 @@ use futures::{
 };
 use gpui::{App, AppContext, AsyncApp, Task};
 use net::async_net::{UnixListener, UnixStream};
+use rand::Rng as _;
 use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde_json::{json, value::RawValue};
 use smol::stream::StreamExt;
 use std::{
     cell::RefCell,
+    io::Read as _,
     path::{Path, PathBuf},
     rc::Rc,
+    sync::Arc,
 };
+use url::Url;
 use util::ResultExt;
 
 use crate::{
@@ -29,11 +33,25 @@ use crate::{
     },
 };
 
+/// The length of the random bearer token HTTP requests must carry.
+const HTTP_TOKEN_LENGTH: usize = 32;
+
 pub struct McpServer {
-    socket_path: PathBuf,
+    address: McpServerAddress,
     tools: Rc<RefCell<HashMap<&'static str, RegisteredTool>>>,
     handlers: Rc<RefCell<HashMap<&'static str, RequestHandler>>>,
     _server_task: Task<()>,
+    _stop_listening: util::Deferred<Box<dyn FnOnce()>>,
+}
+
+/// Where an [`McpServer`] accepts connections.
+#[derive(Debug, Clone)]
+pub enum McpServerAddress {
+    /// A Unix socket speaking newline-delimited JSON-RPC, which clients can bridge to stdio with `nc -U`.
+    Socket(PathBuf),
+    /// A localhost URL accepting JSON-RPC messages in HTTP POST requests, which must carry the bearer
+    /// token in their `Authorization` header.
+    Http { url: Url, token: String },
 }
 
 struct RegisteredTool {
@@ -50,6 +68,7 @@ type ToolHandler = Box<
 type RequestHandler = Box<dyn Fn(RequestId, Option<Box<RawValue>>, &App) -> Task<String>>;
 
 impl McpServer {
+    /// Listens on a Unix socket in a new temporary directory.
     pub fn new(cx: &AsyncApp) -> Task<Result<Self>> {
         let task = cx.background_spawn(async move {
             let temp_dir = tempfile::Builder::new().prefix("zed-mcp").tempdir()?;
@@ -61,27 +80,219 @@ impl McpServer {
 
         cx.spawn(async move |cx| {
             let (temp_dir, socket_path, listener) = task.await?;
-            let tools = Rc::new(RefCell::new(HashMap::default()));
-            let handlers = Rc::new(RefCell::new(HashMap::default()));
-            let server_task = cx.spawn({
-                let tools = tools.clone();
-                let handlers = handlers.clone();
-                async move |cx| {
-                    while let Ok((stream, _)) = listener.accept().await {
-                        Self::serve_connection(stream, tools.clone(), handlers.clone(), cx);
+            Ok(Self::serve_socket(
+                socket_path,
+                listener,
+                Box::new(move || drop(temp_dir)),
+                cx,
+            ))
+        })
+    }
+
+    /// Listens on the Unix socket at `socket_path`, replacing a stale socket left there.
+    /// The socket is removed when the server is dropped.
+    pub fn bind_socket(socket_path: PathBuf, cx: &AsyncApp) -> Task<Result<Self>> {
+        let task = cx.background_spawn({
+            let socket_path = socket_path.clone();
+            async move {
+                if let Ok(metadata) = std::fs::symlink_metadata(&socket_path) {
+                    #[cfg(unix)]
+                    {
+                        use std::os::unix::fs::FileTypeExt as _;
+                        anyhow::ensure!(
+                            metadata.file_type().is_socket(),
+                            "{} exists and is not a socket",
+                            socket_path.display()
+                        );
                     }
-                    drop(temp_dir)
+                    #[cfg(not(unix))]
+                    let _ = metadata;
+                    std::fs::remove_file(&socket_path).context("removing stale mcp socket")?;
                 }
-            });
-            Ok(Self {
-                socket_path,
-                _server_task: server_task,
-                tools,
-                handlers: handlers,
-            })
+                if let Some(parent) = socket_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                UnixListener::bind(&socket_path).context("creating mcp socket")
+            }
+        });
+
+        cx.spawn(async move |cx| {
+            let listener = task.await?;
+            Ok(Self::serve_socket(
+                socket_path.clone(),
+                listener,
+                Box::new(move || {
+                    std::fs::remove_file(&socket_path).log_err();
+                }),
+                cx,
+            ))
         })
     }
 
+    fn serve_socket(
+        socket_path: PathBuf,
+        listener: UnixListener,
+        stop_listening: Box<dyn FnOnce()>,
+        cx: &mut AsyncApp,
+    ) -> Self {
+        let tools = Rc::new(RefCell::new(HashMap::default()));
+        let handlers = Rc::new(RefCell::new(HashMap::default()));
+        let server_task = cx.spawn({
+            let tools = tools.clone();
+            let handlers = handlers.clone();
+            async move |cx| {
+                while let Ok((stream, _)) = listener.accept().await {
+                    Self::serve_connection(stream, tools.clone(), handlers.clone(), cx);
+                }
+            }
+        });
+        Self {
+            address: McpServerAddress::Socket(socket_path),
+            _server_task: server_task,
+            _stop_listening: util::defer(stop_listening),
+            tools,
+            handlers,
+        }
+    }
+
+    /// Listens for HTTP POST requests on the given localhost port, or on a free port if it's 0.
+    ///
+    /// Each request carries one JSON-RPC message, and the response to it is sent back as the
+    /// HTTP response. Requests without the listener's random bearer token, and requests from
+    /// browser pages on other origins, are refused.
+    pub fn bind_http(port: u16, cx: &AsyncApp) -> Task<Result<Self>> {
+        let server = match tiny_http::Server::http(("127.0.0.1", port)) {
+            Ok(server) => Arc::new(server),
+            Err(error) => {
+                return Task::ready(Err(anyhow::anyhow!(
+                    "failed to listen on port {port}: {error}"
+                )));
+            }
+        };
+        let url = match Url::parse(&format!("http://{}/mcp", server.server_addr())) {
+            Ok(url) => url,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+
+        let token = rand::thread_rng()
+            .sample_iter(&rand::distributions::Alphanumeric)
+            .take(HTTP_TOKEN_LENGTH)
+            .map(char::from)
+            .collect::<String>();
+
+        let (request_tx, mut request_rx) = unbounded::<(tiny_http::Request, String)>();
+        std::thread::spawn({
+            let server = server.clone();
+            let token = token.clone();
+            move || {
+                for mut request in server.incoming_requests() {
+                    if let Some(status) = Self::refuse_http_request(&request, &token) {
+                        request
+                            .respond(tiny_http::Response::empty(status))
+                            .log_err();
+                        continue;
+                    }
+                    let mut body = String::new();
+                    if request.as_reader().read_to_string(&mut body).is_err() {
+                        request.respond(tiny_http::Response::empty(400)).log_err();
+                        continue;
+                    }
+                    if request_tx.unbounded_send((request, body)).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let tools = Rc::new(RefCell::new(HashMap::default()));
+        let handlers = Rc::new(RefCell::new(HashMap::default()));
+        let server_task = cx.spawn({
+            let tools = tools.clone();
+            let handlers = handlers.clone();
+            async move |cx| {
+                while let Some((http_request, body)) = request_rx.next().await {
+                    let (outgoing_tx, mut outgoing_rx) = unbounded();
+                    let is_notification = match serde_json::from_str::<RawRequest>(&body) {
+                        Ok(request) => {
+                            let is_notification = request.id.is_none();
+                            Self::handle_message(request, &tools, &handlers, &outgoing_tx, cx)
+                                .await;
+                            is_notification
+                        }
+                        Err(error) => {
+                            outgoing_tx
+                                .unbounded_send(
+                                    json!({
+                                        "jsonrpc": "2.0",
+                                        "error": {
+                                            "code": -32700,
+                                            "message": format!("Failed to parse: {error}"),
+                                        },
+                                    })
+                                    .to_string(),
+                                )
+                                .ok();
+                            false
+                        }
+                    };
+                    drop(outgoing_tx);
+
+                    cx.background_spawn(async move {
+                        let response = if is_notification {
+                            tiny_http::Response::from_string("").with_status_code(202)
+                        } else if let Some(message) = outgoing_rx.next().await {
+                            let content_type = "Content-Type: application/json"
+                                .parse::<tiny_http::Header>()
+                                .expect("valid header");
+                            tiny_http::Response::from_string(message).with_header(content_type)
+                        } else {
+                            tiny_http::Response::from_string("").with_status_code(500)
+                        };
+                        http_request.respond(response).log_err();
+                    })
+                    .detach();
+                }
+            }
+        });
+
+        Task::ready(Ok(Self {
+            address: McpServerAddress::Http { url, token },
+            _server_task: server_task,
+            _stop_listening: util::defer(Box::new(move || server.unblock())),
+            tools,
+            handlers,
+        }))
+    }
+
+    /// Returns the status code to refuse an HTTP request with, if it isn't a POST request
+    /// with the bearer token from a local client.
+    fn refuse_http_request(request: &tiny_http::Request, token: &str) -> Option<u16> {
+        if *request.method() != tiny_http::Method::Post {
+            return Some(405);
+        }
+        let is_authorized = request.headers().iter().any(|header| {
+            header.field.equiv("Authorization")
+                && header
+                    .value
+                    .as_str()
+                    .strip_prefix("Bearer ")
+                    .is_some_and(|request_token| tokens_match(request_token, token))
+        });
+        if !is_authorized {
+            return Some(401);
+        }
+        let origin = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Origin"))?;
+        let origin = Url::parse(origin.value.as_str()).ok();
+        let is_local = origin
+            .as_ref()
+            .and_then(|origin| origin.host_str())
+            .is_some_and(|host| matches!(host, "localhost" | "127.0.0.1" | "[::1]"));
+        if is_local { None } else { Some(403) }
+    }
+
     pub fn add_tool<T: McpServerTool + Clone + 'static>(&mut self, tool: T) {
         let mut settings = schemars::generate::SchemaSettings::draft07();
         settings.inline_subschemas = true;
@@ -183,8 +394,15 @@ impl McpServer {
         );
     }
 
-    pub fn socket_path(&self) -> &Path {
-        &self.socket_path
+    pub fn address(&self) -> &McpServerAddress {
+        &self.address
+    }
+
+    pub fn socket_path(&self) -> Option<&Path> {
+        match &self.address {
+            McpServerAddress::Socket(socket_path) => Some(socket_path),
+            McpServerAddress::Http { .. } => None,
+        }
     }
 
     fn serve_connection(
@@ -202,40 +420,49 @@ impl McpServer {
 
         cx.spawn(async move |cx| {
             while let Some(request) = incoming_rx.next().await {
-                let Some(request_id) = request.id.clone() else {
-                    continue;
-                };
-
-                if request.method == CallTool::METHOD {
-                    Self::handle_call_tool(request_id, request.params, &tools, &outgoing_tx, cx)
-                        .await;
-                } else if request.method == ListTools::METHOD {
-                    Self::handle_list_tools(request.id.unwrap(), &tools, &outgoing_tx);
-                } else if let Some(handler) = handlers.borrow().get(&request.method.as_ref()) {
-                    let outgoing_tx = outgoing_tx.clone();
-
-                    if let Some(task) = cx
-                        .update(|cx| handler(request_id, request.params, cx))
-                        .log_err()
-                    {
-                        cx.spawn(async move |_| {
-                            let response = task.await;
-                            outgoing_tx.unbounded_send(response).ok();
-                        })
-                        .detach();
-                    }
-                } else {
-                    Self::send_err(
-                        request_id,
-                        format!("unhandled method {}", request.method),
-                        &outgoing_tx,
-                    );
-                }
+                Self::handle_message(request, &tools, &handlers, &outgoing_tx, cx).await;
             }
         })
         .detach();
     }
 
+    async fn handle_message(
+        request: RawRequest,
+        tools: &Rc<RefCell<HashMap<&'static str, RegisteredTool>>>,
+        handlers: &Rc<RefCell<HashMap<&'static str, RequestHandler>>>,
+        outgoing_tx: &UnboundedSender<String>,
+        cx: &mut AsyncApp,
+    ) {
+        let Some(request_id) = request.id.clone() else {
+            return;
+        };
+
+        if request.method == CallTool::METHOD {
+            Self::handle_call_tool(request_id, request.params, tools, outgoing_tx, cx).await;
+        } else if request.method == ListTools::METHOD {
+            Self::handle_list_tools(request_id, tools, outgoing_tx);
+        } else if let Some(handler) = handlers.borrow().get(&request.method.as_ref()) {
+            let outgoing_tx = outgoing_tx.clone();
+
+            if let Some(task) = cx
+                .update(|cx| handler(request_id, request.params, cx))
+                .log_err()
+            {
+                cx.spawn(async move |_| {
+                    let response = task.await;
+                    outgoing_tx.unbounded_send(response).ok();
+                })
+                .detach();
+            }
+        } else {
+            Self::send_err(
+                request_id,
+                format!("unhandled method {}", request.method),
+                outgoing_tx,
+            );
+        }
+    }
+
     fn handle_list_tools(
         request_id: RequestId,
         tools: &Rc<RefCell<HashMap<&'static str, RegisteredTool>>>,
@@ -393,6 +620,15 @@ impl McpServer {
     }
 }
 
+/// Compares the tokens in a time that doesn't depend on where they differ.
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
 pub trait McpServerTool {
     type Input: DeserializeOwned + JsonSchema;
     type Output: Serialize + JsonSchema;
@@ -441,3 +677,103 @@ struct RawResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     result: Option<Box<serde_json::value::RawValue>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read as _, Write as _};
+
+    use gpui::TestAppContext;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_http_requests_require_the_token(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let server = McpServer::bind_http(0, &cx.to_async()).await.unwrap();
+        let McpServerAddress::Http { url, token } = server.address().clone() else {
+            panic!("expected an HTTP address");
+        };
+        assert_eq!(token.len(), HTTP_TOKEN_LENGTH);
+        let list_tools = r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#;
+
+        let (status, _) = post(&url, &[], list_tools).recv().await.unwrap();
+        assert_eq!(status, 401);
+
+        let wrong_token = format!("Bearer {}", "x".repeat(HTTP_TOKEN_LENGTH));
+        let (status, _) = post(&url, &[("Authorization", &wrong_token)], list_tools)
+            .recv()
+            .await
+            .unwrap();
+        assert_eq!(status, 401);
+
+        let authorization = format!("Bearer {token}");
+        let (status, body) = post(&url, &[("Authorization", &authorization)], list_tools)
+            .recv()
+            .await
+            .unwrap();
+        assert_eq!(status, 200);
+        let response = serde_json::from_str::<serde_json::Value>(&body).unwrap();
+        assert_eq!(response["id"], json!(1));
+        assert_eq!(response["result"]["tools"], json!([]));
+
+        let (status, _) = post(
+            &url,
+            &[
+                ("Authorization", &authorization),
+                ("Origin", "https://example.com"),
+            ],
+            list_tools,
+        )
+        .recv()
+        .await
+        .unwrap();
+        assert_eq!(status, 403);
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("abc", "abc"));
+        assert!(!tokens_match("abc", "abd"));
+        assert!(!tokens_match("abc", "abcd"));
+        assert!(!tokens_match("", "abc"));
+    }
+
+    /// Sends a POST request from another thread, as the server runs on the test's executor,
+    /// resolving to the response's status code and body.
+    fn post(
+        url: &Url,
+        headers: &[(&str, &str)],
+        body: &str,
+    ) -> smol::channel::Receiver<(u16, String)> {
+        let address = format!("{}:{}", url.host_str().unwrap(), url.port().unwrap());
+        let mut request = format!(
+            "POST {} HTTP/1.1\r\nHost: {address}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            url.path(),
+            body.len()
+        );
+        for (name, value) in headers {
+            request.push_str(&format!("{name}: {value}\r\n"));
+        }
+        request.push_str("\r\n");
+        request.push_str(body);
+
+        let (response_tx, response_rx) = smol::channel::bounded(1);
+        std::thread::spawn(move || {
+            let mut stream = std::net::TcpStream::connect(address).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            let status = response
+                .split(' ')
+                .nth(1)
+                .and_then(|status| status.parse().ok())
+                .unwrap();
+            let body = response
+                .split_once("\r\n\r\n")
+                .map(|(_, body)| body.to_string())
+                .unwrap_or_default();
+            response_tx.send_blocking((status, body)).ok();
+        });
+        response_rx
+    }
+}
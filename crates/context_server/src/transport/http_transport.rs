@@ -0,0 +1,752 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use futures::io::BufReader;
+use futures::{AsyncBufReadExt as _, AsyncReadExt as _, Stream};
+use gpui::{AsyncApp, BackgroundExecutor, Task};
+use http_client::{AsyncBody, HttpClient, Method, Request, Response, StatusCode, Url};
+use parking_lot::Mutex;
+use smol::channel;
+use util::ResultExt as _;
+
+use crate::ContextServerEndpoint;
+use crate::transport::Transport;
+
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+const INITIALIZED_NOTIFICATION: &str = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+const MAX_RECONNECTION_ATTEMPTS: u32 = 5;
+const RECONNECTION_DELAY: Duration = Duration::from_secs(1);
+
+/// Which of the HTTP transports of the Model Context Protocol the server speaks.
+#[derive(Clone, Debug, PartialEq)]
+enum Protocol {
+    /// Not known until the server answers the first request.
+    Unknown,
+    /// Every message is POSTed to the endpoint, and the server answers with JSON or an event stream.
+    StreamableHttp,
+    /// The legacy transport, where the server sends every message over a single event stream and
+    /// the messages are POSTed to the URL it announces in the stream's `endpoint` event.
+    Sse { message_url: Url },
+}
+
+struct ConnectionState {
+    protocol: Protocol,
+    session_id: Option<String>,
+    /// The initialize request, sent again to start a new session when the server forgets the current one.
+    initialize_request: Option<String>,
+    last_event_id: Option<String>,
+}
+
+struct Connection {
+    http_client: Arc<dyn HttpClient>,
+    endpoint: ContextServerEndpoint,
+    executor: BackgroundExecutor,
+    state: Mutex<ConnectionState>,
+    response_tx: channel::Sender<String>,
+    error_tx: channel::Sender<String>,
+}
+
+/// Connects to a context server over the Streamable HTTP transport, falling back to the legacy
+/// HTTP with SSE transport for the servers that don't support it.
+pub struct HttpTransport {
+    connection: Arc<Connection>,
+    /// Reads the event stream the server sends its own requests and notifications over.
+    stream_task: Mutex<Option<Task<()>>>,
+    response_rx: channel::Receiver<String>,
+    error_rx: channel::Receiver<String>,
+}
+
+impl HttpTransport {
+    pub fn new(
+        endpoint: ContextServerEndpoint,
+        http_client: Arc<dyn HttpClient>,
+        cx: &AsyncApp,
+    ) -> Self {
+        let (response_tx, response_rx) = channel::unbounded();
+        let (error_tx, error_rx) = channel::unbounded();
+        Self {
+            connection: Arc::new(Connection {
+                http_client,
+                endpoint,
+                executor: cx.background_executor().clone(),
+                state: Mutex::new(ConnectionState {
+                    protocol: Protocol::Unknown,
+                    session_id: None,
+                    initialize_request: None,
+                    last_event_id: None,
+                }),
+                response_tx,
+                error_tx,
+            }),
+            stream_task: Mutex::new(None),
+            response_rx,
+            error_rx,
+        }
+    }
+
+    async fn send_message(&self, message: String) -> Result<()> {
+        let connection = &self.connection;
+        let protocol = connection.state.lock().protocol.clone();
+        if let Protocol::Sse { message_url } = protocol {
+            return connection.post_to_sse_endpoint(&message_url, message).await;
+        }
+
+        let is_initialize_request = is_initialize_request(&message);
+        if is_initialize_request {
+            connection.state.lock().initialize_request = Some(message.clone());
+        }
+
+        let had_session = connection.state.lock().session_id.is_some();
+        let response = connection.post(&message).await?;
+        let status = response.status();
+        if protocol == Protocol::Unknown
+            && matches!(
+                status,
+                StatusCode::BAD_REQUEST | StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+            )
+        {
+            log::info!(
+                "context server at {} doesn't support Streamable HTTP ({status}), falling back to SSE",
+                connection.endpoint.url
+            );
+            let message_url = self.connect_sse().await?;
+            return connection.post_to_sse_endpoint(&message_url, message).await;
+        }
+
+        if status == StatusCode::NOT_FOUND && had_session && !is_initialize_request {
+            log::info!(
+                "context server at {} expired the session, starting a new one",
+                connection.endpoint.url
+            );
+            self.stream_task.lock().take();
+            connection.start_new_session().await?;
+            let response = connection.post(&message).await?;
+            connection.handle_post_response(response).await?;
+        } else {
+            connection.handle_post_response(response).await?;
+            connection.state.lock().protocol = Protocol::StreamableHttp;
+        }
+
+        if !is_initialize_request {
+            self.listen_for_server_messages();
+        }
+        Ok(())
+    }
+
+    /// Opens the event stream the server may send its own requests and notifications over,
+    /// once the session has been initialized.
+    fn listen_for_server_messages(&self) {
+        let mut stream_task = self.stream_task.lock();
+        if stream_task.is_none() {
+            let connection = self.connection.clone();
+            *stream_task = Some(self.connection.executor.spawn(async move {
+                match connection.open_stream().await {
+                    Ok(Some(events)) => connection.maintain_stream(events).await,
+                    Ok(None) => {}
+                    Err(error) => connection.report_error(error),
+                }
+            }));
+        }
+    }
+
+    /// Connects with the legacy SSE transport, returning the URL the messages are POSTed to.
+    async fn connect_sse(&self) -> Result<Url> {
+        let connection = &self.connection;
+        let mut events = connection.get_sse_stream().await?;
+        let message_url = connection.read_message_url(&mut events).await?;
+        connection.state.lock().protocol = Protocol::Sse {
+            message_url: message_url.clone(),
+        };
+
+        let task_connection = connection.clone();
+        *self.stream_task.lock() = Some(
+            connection
+                .executor
+                .spawn(async move { task_connection.maintain_stream(events).await }),
+        );
+        Ok(message_url)
+    }
+}
+
+impl Connection {
+    fn request(
+        &self,
+        method: Method,
+        url: &Url,
+        body: Option<String>,
+    ) -> Result<Request<AsyncBody>> {
+        let mut builder = Request::builder()
+            .method(method.clone())
+            .uri(url.as_str())
+            .header(
+                "Accept",
+                if method == Method::GET {
+                    "text/event-stream"
+                } else {
+                    "application/json, text/event-stream"
+                },
+            );
+        for (name, value) in &self.endpoint.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let state = self.state.lock();
+        if let Some(session_id) = &state.session_id {
+            builder = builder.header(SESSION_ID_HEADER, session_id.as_str());
+        }
+        if method == Method::GET {
+            if let Some(last_event_id) = &state.last_event_id {
+                builder = builder.header(LAST_EVENT_ID_HEADER, last_event_id.as_str());
+            }
+        }
+        drop(state);
+
+        let body = match body {
+            Some(body) => {
+                builder = builder.header("Content-Type", "application/json");
+                AsyncBody::from(body)
+            }
+            None => AsyncBody::empty(),
+        };
+        Ok(builder.body(body)?)
+    }
+
+    async fn post(&self, message: &str) -> Result<Response<AsyncBody>> {
+        log::trace!("outgoing message: {}", message);
+        let request = self.request(Method::POST, &self.endpoint.url, Some(message.to_string()))?;
+        self.http_client.send(request).await
+    }
+
+    async fn post_to_sse_endpoint(&self, message_url: &Url, message: String) -> Result<()> {
+        log::trace!("outgoing message: {}", message);
+        let request = self.request(Method::POST, message_url, Some(message))?;
+        let response = self.http_client.send(request).await?;
+        ensure_success(response).await.map(drop)
+    }
+
+    /// Reads the response to a POSTed message, which is empty for notifications and responses,
+    /// and carries the answer to requests either as JSON or as an event stream.
+    async fn handle_post_response(self: &Arc<Self>, response: Response<AsyncBody>) -> Result<()> {
+        let mut response = ensure_success(response).await?;
+        if let Some(session_id) = header(&response, SESSION_ID_HEADER) {
+            self.state.lock().session_id = Some(session_id);
+        }
+
+        let content_type = header(&response, "Content-Type").unwrap_or_default();
+        if content_type.starts_with("text/event-stream") {
+            let connection = self.clone();
+            let mut events = BufReader::new(response.into_body());
+            self.executor
+                .spawn(async move {
+                    if let Err(error) = connection.forward_events(&mut events).await {
+                        connection.report_error(error);
+                    }
+                })
+                .detach();
+        } else if content_type.starts_with("application/json") {
+            let mut body = String::new();
+            response.body_mut().read_to_string(&mut body).await?;
+            match serde_json::from_str::<serde_json::Value>(&body)? {
+                serde_json::Value::Array(messages) => {
+                    for message in messages {
+                        self.forward(message.to_string()).await;
+                    }
+                }
+                _ => self.forward(body).await,
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends the initialize request again for a new session. Its response is consumed here, as the client
+    /// already got the one for the session it initialized and doesn't expect another one.
+    async fn start_new_session(self: &Arc<Self>) -> Result<()> {
+        let initialize_request = {
+            let mut state = self.state.lock();
+            state.session_id = None;
+            state.last_event_id = None;
+            state
+                .initialize_request
+                .clone()
+                .context("no initialize request to start a new session with")?
+        };
+        let request_id = serde_json::from_str::<serde_json::Value>(&initialize_request)?
+            .get("id")
+            .cloned()
+            .context("the initialize request has no id")?;
+        let response = self.post(&initialize_request).await?;
+        self.read_initialize_response(response, &request_id).await?;
+        let response = self.post(INITIALIZED_NOTIFICATION).await?;
+        self.handle_post_response(response).await
+    }
+
+    /// Reads the response to an initialize request sent by the transport, forwarding the other messages the
+    /// server sends along with it.
+    async fn read_initialize_response(
+        &self,
+        response: Response<AsyncBody>,
+        request_id: &serde_json::Value,
+    ) -> Result<()> {
+        let mut response = ensure_success(response).await?;
+        if let Some(session_id) = header(&response, SESSION_ID_HEADER) {
+            self.state.lock().session_id = Some(session_id);
+        }
+
+        let content_type = header(&response, "Content-Type").unwrap_or_default();
+        if content_type.starts_with("text/event-stream") {
+            let mut events = BufReader::new(response.into_body());
+            while let Some(event) = next_event(&mut events).await? {
+                if event.event != "message" || event.data.is_empty() {
+                    continue;
+                }
+                let message = serde_json::from_str::<serde_json::Value>(&event.data)?;
+                if message.get("id") == Some(request_id) {
+                    return initialize_result(&message);
+                }
+                self.forward(event.data).await;
+            }
+        } else if content_type.starts_with("application/json") {
+            let mut body = String::new();
+            response.body_mut().read_to_string(&mut body).await?;
+            let messages = match serde_json::from_str::<serde_json::Value>(&body)? {
+                serde_json::Value::Array(messages) => messages,
+                message => vec![message],
+            };
+            let mut result = None;
+            for message in messages {
+                if message.get("id") == Some(request_id) {
+                    result = Some(initialize_result(&message));
+                } else {
+                    self.forward(message.to_string()).await;
+                }
+            }
+            if let Some(result) = result {
+                return result;
+            }
+        }
+        Err(anyhow!(
+            "context server at {} didn't answer the initialize request of the new session",
+            self.endpoint.url
+        ))
+    }
+
+    async fn get_sse_stream(&self) -> Result<BufReader<AsyncBody>> {
+        let request = self.request(Method::GET, &self.endpoint.url, None)?;
+        let response = ensure_success(self.http_client.send(request).await?).await?;
+        Ok(BufReader::new(response.into_body()))
+    }
+
+    async fn read_message_url(&self, events: &mut BufReader<AsyncBody>) -> Result<Url> {
+        loop {
+            let event = next_event(events)
+                .await?
+                .context("the event stream ended before the server sent its message endpoint")?;
+            if event.event == "endpoint" {
+                return self
+                    .endpoint
+                    .url
+                    .join(event.data.trim())
+                    .context("invalid message endpoint");
+            }
+        }
+    }
+
+    /// Opens the event stream again after it ended, resuming after the last event received.
+    /// Returns None when the server doesn't offer one.
+    async fn open_stream(&self) -> Result<Option<BufReader<AsyncBody>>> {
+        let protocol = self.state.lock().protocol.clone();
+        if let Protocol::Sse { .. } = protocol {
+            let mut events = self.get_sse_stream().await?;
+            let message_url = self.read_message_url(&mut events).await?;
+            self.state.lock().protocol = Protocol::Sse { message_url };
+            return Ok(Some(events));
+        }
+
+        let request = self.request(Method::GET, &self.endpoint.url, None)?;
+        let response = self.http_client.send(request).await?;
+        if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+            return Ok(None);
+        }
+        let response = ensure_success(response).await?;
+        Ok(Some(BufReader::new(response.into_body())))
+    }
+
+    /// Forwards the events of the stream, reconnecting whenever the stream ends.
+    async fn maintain_stream(self: Arc<Self>, mut events: BufReader<AsyncBody>) {
+        loop {
+            if let Err(error) = self.forward_events(&mut events).await {
+                self.report_error(error);
+            }
+            if self.response_tx.is_closed() {
+                return;
+            }
+
+            let mut attempt = 0;
+            events = loop {
+                if attempt == MAX_RECONNECTION_ATTEMPTS {
+                    self.report_error(anyhow!(
+                        "gave up reconnecting to {} after {attempt} attempts",
+                        self.endpoint.url
+                    ));
+                    return;
+                }
+                attempt += 1;
+                self.executor.timer(RECONNECTION_DELAY * attempt).await;
+                match self.open_stream().await {
+                    Ok(Some(events)) => break events,
+                    Ok(None) => return,
+                    Err(error) => self.report_error(error),
+                }
+            };
+            log::info!("reconnected to context server at {}", self.endpoint.url);
+        }
+    }
+
+    async fn forward_events(&self, events: &mut BufReader<AsyncBody>) -> Result<()> {
+        while let Some(event) = next_event(events).await? {
+            if let Some(id) = event.id {
+                self.state.lock().last_event_id = Some(id);
+            }
+            match event.event.as_str() {
+                "message" if !event.data.is_empty() => self.forward(event.data).await,
+                "endpoint" => {
+                    let message_url = self.endpoint.url.join(event.data.trim())?;
+                    self.state.lock().protocol = Protocol::Sse { message_url };
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    async fn forward(&self, message: String) {
+        self.response_tx.send(message).await.ok();
+    }
+
+    fn report_error(&self, error: anyhow::Error) {
+        self.error_tx.try_send(format!("{error:#}")).log_err();
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send(&self, message: String) -> Result<()> {
+        self.send_message(message).await
+    }
+
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.response_rx.clone())
+    }
+
+    fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.error_rx.clone())
+    }
+}
+
+impl Drop for HttpTransport {
+    fn drop(&mut self) {
+        let state = self.connection.state.lock();
+        if state.protocol != Protocol::StreamableHttp || state.session_id.is_none() {
+            return;
+        }
+        drop(state);
+
+        // Let the server release the session.
+        if let Some(request) = self
+            .connection
+            .request(Method::DELETE, &self.connection.endpoint.url, None)
+            .log_err()
+        {
+            let request = self.connection.http_client.send(request);
+            self.connection
+                .executor
+                .spawn(async move { request.await.log_err() })
+                .detach();
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct ServerSentEvent {
+    event: String,
+    data: String,
+    id: Option<String>,
+}
+
+/// Reads the next event of a `text/event-stream` body, returning None once the stream ends.
+async fn next_event(events: &mut BufReader<AsyncBody>) -> Result<Option<ServerSentEvent>> {
+    let mut event = ServerSentEvent::default();
+    let mut has_data = false;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if events.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            if has_data || !event.event.is_empty() {
+                if event.event.is_empty() {
+                    event.event = "message".to_string();
+                }
+                return Ok(Some(event));
+            }
+            continue;
+        }
+        if line.starts_with(':') {
+            continue;
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => event.event = value.to_string(),
+            "data" => {
+                if has_data {
+                    event.data.push('\n');
+                }
+                event.data.push_str(value);
+                has_data = true;
+            }
+            "id" => event.id = Some(value.to_string()),
+            _ => {}
+        }
+    }
+}
+
+async fn ensure_success(mut response: Response<AsyncBody>) -> Result<Response<AsyncBody>> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await.ok();
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        anyhow::bail!("context server refused the credentials ({status}): {body}");
+    }
+    anyhow::bail!("context server responded with {status}: {body}");
+}
+
+fn header(response: &Response<AsyncBody>, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(ToString::to_string)
+}
+
+fn initialize_result(response: &serde_json::Value) -> Result<()> {
+    match response.get("error") {
+        Some(error) => Err(anyhow!(
+            "context server refused to start a new session: {error}"
+        )),
+        None => Ok(()),
+    }
+}
+
+fn is_initialize_request(message: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(message).is_ok_and(|message| {
+        message.get("method").and_then(|method| method.as_str()) == Some("initialize")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{StreamExt as _, TryStreamExt as _, channel::mpsc};
+    use gpui::TestAppContext;
+    use http_client::FakeHttpClient;
+    use serde_json::json;
+
+    /// A server speaking Streamable HTTP, which forgets the session once and then answers with
+    /// an event stream.
+    #[gpui::test]
+    async fn test_streamable_http(cx: &mut TestAppContext) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let sessions = Arc::new(Mutex::new(0));
+        let http_client = FakeHttpClient::create({
+            let requests = requests.clone();
+            let sessions = sessions.clone();
+            move |request| {
+                let requests = requests.clone();
+                let sessions = sessions.clone();
+                async move {
+                    let session_id = request
+                        .headers()
+                        .get(SESSION_ID_HEADER)
+                        .map(|value| value.to_str().unwrap().to_string());
+                    let method = request.method().clone();
+                    let mut body = String::new();
+                    request.into_body().read_to_string(&mut body).await?;
+                    requests
+                        .lock()
+                        .push((method.clone(), session_id.clone(), body.clone()));
+
+                    let response = Response::builder();
+                    if method == Method::GET {
+                        return Ok(response.status(405).body(AsyncBody::empty())?);
+                    }
+                    if is_initialize_request(&body) {
+                        let mut sessions = sessions.lock();
+                        *sessions += 1;
+                        return Ok(response
+                            .status(200)
+                            .header("Content-Type", "application/json")
+                            .header(SESSION_ID_HEADER, format!("session-{sessions}"))
+                            .body(
+                                json!({"jsonrpc": "2.0", "id": 0, "result": {}})
+                                    .to_string()
+                                    .into(),
+                            )?);
+                    }
+                    if session_id.as_deref() == Some("session-1") && body.contains("ping") {
+                        return Ok(response.status(404).body(AsyncBody::empty())?);
+                    }
+                    if body.contains("\"id\"") {
+                        return Ok(response
+                            .status(200)
+                            .header("Content-Type", "text/event-stream")
+                            .body(
+                                "event: message\nid: 1\ndata: {\"jsonrpc\":\"2.0\",\"id\":1,\n\ndata: \"result\":{}}\n\n"
+                                    .into(),
+                            )?);
+                    }
+                    Ok(response.status(202).body(AsyncBody::empty())?)
+                }
+            }
+        });
+
+        let transport = HttpTransport::new(
+            ContextServerEndpoint {
+                url: "http://mcp.example/mcp".parse().unwrap(),
+                headers: [("X-Team".to_string(), "platform".to_string())]
+                    .into_iter()
+                    .collect(),
+            },
+            http_client,
+            &cx.to_async(),
+        );
+        let mut responses = transport.receive();
+
+        let initialize = json!({"jsonrpc": "2.0", "id": 0, "method": "initialize"}).to_string();
+        transport.send(initialize.clone()).await.unwrap();
+        assert_eq!(
+            responses.next().await.unwrap(),
+            json!({"jsonrpc": "2.0", "id": 0, "result": {}}).to_string()
+        );
+        transport
+            .send(INITIALIZED_NOTIFICATION.to_string())
+            .await
+            .unwrap();
+
+        let ping = json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}).to_string();
+        transport.send(ping.clone()).await.unwrap();
+        // The response to the initialize request sent again for the new session is consumed by the transport.
+        assert_eq!(
+            responses.next().await.unwrap(),
+            "{\"jsonrpc\":\"2.0\",\"id\":1,\n\"result\":{}}"
+        );
+        cx.run_until_parked();
+
+        let requests = requests.lock();
+        let posts = requests
+            .iter()
+            .filter(|(method, _, _)| *method == Method::POST)
+            .map(|(_, session_id, body)| (session_id.as_deref(), body.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            posts,
+            [
+                (None, initialize.as_str()),
+                (Some("session-1"), INITIALIZED_NOTIFICATION),
+                (Some("session-1"), ping.as_str()),
+                (None, initialize.as_str()),
+                (Some("session-2"), INITIALIZED_NOTIFICATION),
+                (Some("session-2"), ping.as_str()),
+            ]
+        );
+        assert!(
+            requests
+                .iter()
+                .any(|(method, session_id, _)| *method == Method::GET && session_id.is_some())
+        );
+    }
+
+    /// A server only speaking the legacy SSE transport, whose event stream drops once.
+    #[gpui::test]
+    async fn test_sse_fallback(cx: &mut TestAppContext) {
+        let streams = Arc::new(Mutex::new(Vec::<mpsc::UnboundedSender<String>>::new()));
+        let http_client = FakeHttpClient::create({
+            let streams = streams.clone();
+            move |request| {
+                let streams = streams.clone();
+                async move {
+                    let response = Response::builder();
+                    let path = request.uri().path().to_string();
+                    let query = request.uri().query().unwrap_or_default().to_string();
+                    let method = request.method().clone();
+                    let mut body = String::new();
+                    request.into_body().read_to_string(&mut body).await?;
+
+                    if method == Method::POST && path == "/sse" {
+                        return Ok(response.status(405).body(AsyncBody::empty())?);
+                    }
+                    if method == Method::GET {
+                        let (tx, rx) = mpsc::unbounded::<String>();
+                        let connection = streams.lock().len();
+                        tx.unbounded_send(format!(
+                            "event: endpoint\ndata: /messages?connection={connection}\n\n"
+                        ))
+                        .unwrap();
+                        streams.lock().push(tx);
+                        let reader = rx
+                            .map(|event| Ok::<_, std::io::Error>(event.into_bytes()))
+                            .into_async_read();
+                        return Ok(response
+                            .status(200)
+                            .header("Content-Type", "text/event-stream")
+                            .body(AsyncBody::from_reader(reader))?);
+                    }
+
+                    assert_eq!(path, "/messages");
+                    let connection = query
+                        .strip_prefix("connection=")
+                        .and_then(|connection| connection.parse::<usize>().ok())
+                        .unwrap();
+                    let stream = streams.lock()[connection].clone();
+                    stream
+                        .unbounded_send(format!("event: message\ndata: {body}\n\n"))
+                        .unwrap();
+                    Ok(response.status(202).body(AsyncBody::empty())?)
+                }
+            }
+        });
+
+        let transport = HttpTransport::new(
+            ContextServerEndpoint {
+                url: "http://mcp.example/sse".parse().unwrap(),
+                headers: Default::default(),
+            },
+            http_client,
+            &cx.to_async(),
+        );
+        let mut responses = transport.receive();
+
+        transport.send("{\"id\":0}".to_string()).await.unwrap();
+        assert_eq!(responses.next().await.unwrap(), "{\"id\":0}");
+
+        // Dropping the stream makes the transport connect again and use the new message endpoint.
+        streams.lock()[0].close_channel();
+        cx.run_until_parked();
+        cx.executor().advance_clock(RECONNECTION_DELAY);
+        cx.run_until_parked();
+        assert_eq!(streams.lock().len(), 2);
+
+        transport.send("{\"id\":1}".to_string()).await.unwrap();
+        assert_eq!(responses.next().await.unwrap(), "{\"id\":1}");
+    }
+}
@@ -77,6 +77,12 @@ pub mod requests {
         ListResourceTemplatesResponse
     );
     request!("roots/list", ListRoots, (), ListRootsResponse);
+    request!(
+        "sampling/createMessage",
+        CreateMessage,
+        CreateMessageRequest,
+        CreateMessageResult
+    );
 }
 
 pub trait Request {
@@ -233,7 +239,8 @@ pub struct PromptReference {
 pub struct ResourceReference {
     #[serde(rename = "type")]
     pub ty: PromptReferenceType,
-    pub uri: Url,
+    /// The URI or URI template of the resource.
+    pub uri: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -313,7 +320,7 @@ pub struct CreateMessageRequest {
     pub metadata: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateMessageResult {
     pub role: Role,
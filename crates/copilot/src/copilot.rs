@@ -1409,6 +1409,14 @@ mod tests {
         fn load_bytes(&self, _cx: &App) -> Task<Result<Vec<u8>>> {
             unimplemented!()
         }
+
+        fn load_with_encoding(
+            &self,
+            _: Option<fs::Encoding>,
+            _: &App,
+        ) -> Task<Result<(String, fs::Encoding)>> {
+            unimplemented!()
+        }
     }
 }
 
@@ -0,0 +1,346 @@
+//! Parsing of delimited text into records, keeping the byte range of every field so that the edits of a cell can
+//! be written back to the buffer.
+
+use std::{cmp::Ordering, ops::Range, path::Path};
+
+/// The delimiters tried when detecting the dialect of a file.
+const DELIMITERS: [char; 4] = [',', '\t', ';', '|'];
+/// How many records are looked at when detecting the delimiter.
+const DETECTION_RECORDS: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CsvDialect {
+    pub delimiter: char,
+    pub quote: char,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+        }
+    }
+}
+
+impl CsvDialect {
+    /// Guesses the dialect from the first records of the text, falling back to the one implied by the extension.
+    pub fn detect(text: &str, path: Option<&Path>) -> Self {
+        let is_tsv = path
+            .and_then(|path| path.extension())
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                extension.eq_ignore_ascii_case("tsv") || extension.eq_ignore_ascii_case("tab")
+            });
+        let fallback = if is_tsv { '\t' } else { ',' };
+        let quote = detect_quote(text);
+
+        let mut best: Option<(char, usize)> = None;
+        let mut most_frequent: Option<(char, usize)> = None;
+        // The fallback goes first so that it wins the ties.
+        let candidates = std::iter::once(fallback).chain(
+            DELIMITERS
+                .into_iter()
+                .filter(|delimiter| *delimiter != fallback),
+        );
+        for delimiter in candidates {
+            let counts = delimiter_counts(text, delimiter, quote);
+            let total = counts.iter().sum::<usize>();
+            if total > most_frequent.map_or(0, |(_, total)| total) {
+                most_frequent = Some((delimiter, total));
+            }
+            let consistent = counts
+                .first()
+                .is_some_and(|first| *first > 0 && counts.iter().all(|count| count == first));
+            if consistent && counts[0] > best.map_or(0, |(_, count)| count) {
+                best = Some((delimiter, counts[0]));
+            }
+        }
+
+        Self {
+            delimiter: best
+                .or(most_frequent)
+                .map_or(fallback, |(delimiter, _)| delimiter),
+            quote,
+        }
+    }
+
+    /// The text of a field with the given value, quoted when the value needs it.
+    pub fn encode_field(&self, value: &str) -> String {
+        let needs_quotes = value
+            .chars()
+            .any(|c| c == self.delimiter || c == self.quote || c == '\n' || c == '\r');
+        if needs_quotes {
+            let quote = self.quote.to_string();
+            let escaped = value.replace(self.quote, &quote.repeat(2));
+            format!("{quote}{escaped}{quote}")
+        } else {
+            value.to_string()
+        }
+    }
+
+    pub fn delimiter_label(&self) -> &'static str {
+        match self.delimiter {
+            ',' => "Comma",
+            '\t' => "Tab",
+            ';' => "Semicolon",
+            '|' => "Pipe",
+            _ => "Other",
+        }
+    }
+}
+
+pub fn delimiters() -> impl Iterator<Item = char> {
+    DELIMITERS.into_iter()
+}
+
+fn detect_quote(text: &str) -> char {
+    let single_quoted_field = text.lines().take(DETECTION_RECORDS).any(|line| {
+        line.starts_with('\'')
+            || DELIMITERS
+                .iter()
+                .any(|delimiter| line.contains(&format!("{delimiter}'")))
+    });
+    if !text.contains('"') && single_quoted_field {
+        '\''
+    } else {
+        '"'
+    }
+}
+
+/// The number of delimiters outside of the quotes on each of the first records.
+fn delimiter_counts(text: &str, delimiter: char, quote: char) -> Vec<usize> {
+    let mut counts = Vec::new();
+    let mut count = 0;
+    let mut in_quotes = false;
+    for c in text.chars() {
+        if c == quote {
+            in_quotes = !in_quotes;
+        } else if !in_quotes && c == delimiter {
+            count += 1;
+        } else if !in_quotes && c == '\n' {
+            counts.push(count);
+            count = 0;
+            if counts.len() == DETECTION_RECORDS {
+                break;
+            }
+        }
+    }
+    if count > 0 {
+        counts.push(count);
+    }
+    counts
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsvField {
+    /// The range of the field in the text, including its quotes.
+    pub range: Range<usize>,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsvRecord {
+    pub fields: Vec<CsvField>,
+    /// The offset right after the last field, where the missing fields are appended.
+    pub end: usize,
+}
+
+impl CsvRecord {
+    pub fn value(&self, column: usize) -> &str {
+        self.fields
+            .get(column)
+            .map_or("", |field| field.value.as_str())
+    }
+}
+
+/// Splits the text into records, skipping the blank lines.
+pub fn parse(text: &str, dialect: CsvDialect) -> Vec<CsvRecord> {
+    let bytes = text.as_bytes();
+    let delimiter = dialect.delimiter as u8;
+    let quote = dialect.quote as u8;
+    let field_end = |start: usize| {
+        bytes[start..]
+            .iter()
+            .position(|byte| *byte == delimiter || *byte == b'\n' || *byte == b'\r')
+            .map_or(bytes.len(), |offset| start + offset)
+    };
+
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut ix = 0;
+    loop {
+        let start = ix;
+        let mut value = String::new();
+        if bytes.get(ix) == Some(&quote) {
+            ix += 1;
+            let mut chunk_start = ix;
+            loop {
+                match bytes[ix..].iter().position(|byte| *byte == quote) {
+                    Some(offset) => {
+                        let quote_ix = ix + offset;
+                        value.push_str(&text[chunk_start..quote_ix]);
+                        if bytes.get(quote_ix + 1) == Some(&quote) {
+                            value.push(dialect.quote);
+                            ix = quote_ix + 2;
+                            chunk_start = ix;
+                        } else {
+                            ix = quote_ix + 1;
+                            break;
+                        }
+                    }
+                    None => {
+                        value.push_str(&text[chunk_start..]);
+                        ix = bytes.len();
+                        break;
+                    }
+                }
+            }
+        }
+        // The unquoted fields, and whatever follows the closing quote of the quoted ones.
+        let end = field_end(ix);
+        value.push_str(&text[ix..end]);
+        ix = end;
+        fields.push(CsvField {
+            range: start..ix,
+            value,
+        });
+
+        if bytes.get(ix) == Some(&delimiter) {
+            ix += 1;
+            continue;
+        }
+        let is_blank_line = fields.len() == 1 && fields[0].range.is_empty();
+        if is_blank_line {
+            fields.clear();
+        } else {
+            records.push(CsvRecord {
+                fields: std::mem::take(&mut fields),
+                end: ix,
+            });
+        }
+        if ix >= bytes.len() {
+            break;
+        }
+        ix += if bytes[ix..].starts_with(b"\r\n") {
+            2
+        } else {
+            1
+        };
+        if ix >= bytes.len() {
+            break;
+        }
+    }
+    records
+}
+
+/// Whether all the non-empty values are numbers, so that the column is aligned to the right.
+pub fn is_numeric_column<'a>(values: impl IntoIterator<Item = &'a str>) -> bool {
+    let mut any_number = false;
+    for value in values {
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        if value.parse::<f64>().is_err() {
+            return false;
+        }
+        any_number = true;
+    }
+    any_number
+}
+
+/// Orders the values numerically when both are numbers, and case-insensitively otherwise.
+pub fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a
+            .to_lowercase()
+            .cmp(&b.to_lowercase())
+            .then_with(|| a.cmp(b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(records: &[CsvRecord]) -> Vec<Vec<&str>> {
+        records
+            .iter()
+            .map(|record| {
+                record
+                    .fields
+                    .iter()
+                    .map(|field| field.value.as_str())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = "name,notes\r\n\"Smith, J\",\"said \"\"hi\"\"\nthen left\"\n\nplain,\n";
+        let records = parse(text, CsvDialect::default());
+        assert_eq!(
+            values(&records),
+            [
+                vec!["name", "notes"],
+                vec!["Smith, J", "said \"hi\"\nthen left"],
+                vec!["plain", ""],
+            ]
+        );
+        assert_eq!(&text[records[1].fields[0].range.clone()], "\"Smith, J\"");
+        assert_eq!(records[2].fields[1].range, records[2].end..records[2].end);
+        assert_eq!(&text[records[2].end..], "\n");
+
+        assert_eq!(
+            values(&parse("a\t\"b", CsvDialect::detect("a\tb", None))),
+            [vec!["a", "b"]]
+        );
+        assert!(parse("", CsvDialect::default()).is_empty());
+    }
+
+    #[test]
+    fn test_detect_dialect() {
+        let detect = |text: &str, path: &str| CsvDialect::detect(text, Some(Path::new(path)));
+        assert_eq!(detect("a,b,c\n1,2,3\n", "x.csv").delimiter, ',');
+        assert_eq!(detect("a;b;c\n1,5;2;3\n", "x.csv").delimiter, ';');
+        assert_eq!(detect("a\tb\n\"1,2\"\t3\n", "x.txt").delimiter, '\t');
+        assert_eq!(detect("a|b\n1|2\n", "x.csv").delimiter, '|');
+        assert_eq!(detect("single column\n", "x.tsv").delimiter, '\t');
+        assert_eq!(detect("single column\n", "x.csv").delimiter, ',');
+        assert_eq!(detect("'a,b',c\n", "x.csv").quote, '\'');
+        assert_eq!(detect("\"it's\",c\n", "x.csv").quote, '"');
+    }
+
+    #[test]
+    fn test_encode_field() {
+        let dialect = CsvDialect::default();
+        assert_eq!(dialect.encode_field("plain"), "plain");
+        assert_eq!(dialect.encode_field("a,b"), "\"a,b\"");
+        assert_eq!(dialect.encode_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        let tsv = CsvDialect {
+            delimiter: '\t',
+            ..dialect
+        };
+        assert_eq!(tsv.encode_field("a,b"), "a,b");
+        assert_eq!(
+            values(&parse(&dialect.encode_field("x\n\"y\","), dialect)),
+            [vec!["x\n\"y\","]]
+        );
+    }
+
+    #[test]
+    fn test_column_values() {
+        assert!(is_numeric_column(["1", " 2.5", "", "-3e2"]));
+        assert!(!is_numeric_column(["1", "n/a"]));
+        assert!(!is_numeric_column(["", ""]));
+
+        let mut values = vec!["b", "10", "A", "9", "a"];
+        values.sort_by(|a, b| compare_values(a, b));
+        assert_eq!(values, ["9", "10", "A", "a", "b"]);
+    }
+}
@@ -0,0 +1,29 @@
+use gpui::{App, actions};
+use workspace::Workspace;
+
+mod csv_document;
+pub mod csv_table_view;
+
+pub use csv_document::{CsvDialect, CsvField, CsvRecord};
+
+actions!(
+    csv_editor,
+    [
+        /// Toggles between the raw text and the table view of a CSV or TSV file.
+        ToggleTableView,
+        /// Selects the cell to the right of the selected one.
+        SelectNextColumn,
+        /// Selects the cell to the left of the selected one.
+        SelectPreviousColumn
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        crate::csv_table_view::CsvTableView::register(workspace, window, cx);
+    })
+    .detach();
+}
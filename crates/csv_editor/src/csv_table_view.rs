@@ -0,0 +1,928 @@
+use std::{ops::Range, path::PathBuf, sync::Arc};
+
+use collections::HashMap;
+use editor::{Editor, EditorEvent};
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, ClickEvent, Context, Entity, EventEmitter, FocusHandle, Focusable, FontWeight,
+    IntoElement, ParentElement, Render, ScrollStrategy, Styled, Subscription, Task,
+    UniformListScrollHandle, Window, uniform_list,
+};
+use language::{Anchor, Buffer, BufferEvent, BufferSnapshot};
+use menu::{Cancel, Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::Project;
+use ui::{ContextMenu, PopoverMenu, Tooltip, prelude::*, right_click_menu};
+use workspace::{
+    Workspace,
+    item::{Item, ItemEvent, SaveOptions},
+};
+
+use crate::{
+    SelectNextColumn, SelectPreviousColumn, ToggleTableView,
+    csv_document::{self, CsvDialect, CsvRecord},
+};
+
+/// The width of the column showing the record numbers.
+const ROW_NUMBER_WIDTH: Pixels = px(56.);
+/// How many records are measured when sizing the columns.
+const MEASURED_RECORDS: usize = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ColumnAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// The records of the buffer at the version they were parsed from.
+struct CsvTable {
+    snapshot: BufferSnapshot,
+    dialect: CsvDialect,
+    /// The records, the first of them being the header.
+    records: Vec<CsvRecord>,
+    column_count: usize,
+    numeric_columns: Vec<bool>,
+    column_widths: Vec<Pixels>,
+}
+
+impl CsvTable {
+    fn new(snapshot: BufferSnapshot, dialect: CsvDialect) -> Self {
+        let text = snapshot.text();
+        let records = csv_document::parse(&text, dialect);
+        let column_count = records
+            .iter()
+            .map(|record| record.fields.len())
+            .max()
+            .unwrap_or(0);
+        let numeric_columns = (0..column_count)
+            .map(|column| {
+                csv_document::is_numeric_column(
+                    records.iter().skip(1).map(|record| record.value(column)),
+                )
+            })
+            .collect();
+        let column_widths = (0..column_count)
+            .map(|column| {
+                let chars = records
+                    .iter()
+                    .take(MEASURED_RECORDS)
+                    .map(|record| record.value(column).chars().count())
+                    .max()
+                    .unwrap_or(0);
+                px(chars.clamp(4, 40) as f32 * 8. + 24.)
+            })
+            .collect();
+        Self {
+            snapshot,
+            dialect,
+            records,
+            column_count,
+            numeric_columns,
+            column_widths,
+        }
+    }
+}
+
+/// A cell being edited, and where its new text goes in the buffer.
+struct CellEdit {
+    record_ix: usize,
+    column: usize,
+    editor: Entity<Editor>,
+    original: String,
+    range: Range<Anchor>,
+    /// The delimiters inserted before the new text when the record is missing the edited field.
+    prefix: String,
+}
+
+/// Shows a CSV or TSV buffer as a table whose cells are edited through the buffer.
+pub struct CsvTableView {
+    buffer: Entity<Buffer>,
+    path: Option<PathBuf>,
+    focus_handle: FocusHandle,
+    filter_editor: Entity<Editor>,
+    /// The dialect the buffer is parsed with, None until it is detected.
+    dialect: Option<CsvDialect>,
+    table: Option<Arc<CsvTable>>,
+    /// The records shown, after the filter and the sort.
+    rows: Vec<usize>,
+    sort: Option<(usize, SortDirection)>,
+    alignments: HashMap<usize, ColumnAlignment>,
+    /// The record and the column of the selected cell.
+    selection: Option<(usize, usize)>,
+    editing: Option<CellEdit>,
+    scroll_handle: UniformListScrollHandle,
+    parse_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+pub enum CsvTableViewEvent {
+    TitleChanged,
+}
+
+impl CsvTableView {
+    pub fn register(workspace: &mut Workspace, _window: &mut Window, _cx: &mut Context<Workspace>) {
+        workspace.register_action(|workspace, _: &ToggleTableView, window, cx| {
+            let Some(active_item) = workspace.active_item(cx) else {
+                return;
+            };
+            let pane = workspace.active_pane().clone();
+
+            if let Some(table_view) = active_item.downcast::<CsvTableView>() {
+                let buffer = table_view.read(cx).buffer.clone();
+                let existing = pane.read(cx).items_of_type::<Editor>().find(|editor| {
+                    editor.read(cx).buffer().read(cx).as_singleton().as_ref() == Some(&buffer)
+                });
+                match existing {
+                    Some(editor) => {
+                        workspace.activate_item(&editor, true, true, window, cx);
+                    }
+                    None => {
+                        workspace.open_project_item::<Editor>(pane, buffer, true, true, window, cx);
+                    }
+                }
+                return;
+            }
+
+            let Some(editor) = active_item.act_as::<Editor>(cx) else {
+                return;
+            };
+            let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+                return;
+            };
+            let existing = pane
+                .read(cx)
+                .items_of_type::<CsvTableView>()
+                .find(|table_view| table_view.read(cx).buffer == buffer);
+            if let Some(existing) = existing {
+                workspace.activate_item(&existing, true, true, window, cx);
+                return;
+            }
+
+            let table_view = cx.new(|cx| CsvTableView::new(buffer, window, cx));
+            workspace.add_item_to_active_pane(Box::new(table_view), None, true, window, cx);
+        });
+    }
+
+    pub fn new(buffer: Entity<Buffer>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let path = buffer.read(cx).file().map(|file| file.path().to_path_buf());
+        let filter_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter rows…", cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe(&buffer, |this, _, event: &BufferEvent, cx| match event {
+                BufferEvent::Edited | BufferEvent::Reloaded => this.reparse(cx),
+                BufferEvent::DirtyChanged | BufferEvent::Saved | BufferEvent::FileHandleChanged => {
+                    cx.emit(CsvTableViewEvent::TitleChanged)
+                }
+                _ => {}
+            }),
+            cx.subscribe(&filter_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::Edited { .. } = event {
+                    this.update_rows(cx);
+                }
+            }),
+        ];
+
+        let mut this = Self {
+            buffer,
+            path,
+            focus_handle: cx.focus_handle(),
+            filter_editor,
+            dialect: None,
+            table: None,
+            rows: Vec::new(),
+            sort: None,
+            alignments: HashMap::default(),
+            selection: None,
+            editing: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            parse_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.reparse(cx);
+        this
+    }
+
+    fn reparse(&mut self, cx: &mut Context<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot();
+        let dialect = self.dialect;
+        let path = self.path.clone();
+        self.parse_task = cx.spawn(async move |this, cx| {
+            let table = cx
+                .background_spawn(async move {
+                    let dialect = dialect
+                        .unwrap_or_else(|| CsvDialect::detect(&snapshot.text(), path.as_deref()));
+                    CsvTable::new(snapshot, dialect)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.dialect.get_or_insert(table.dialect);
+                this.table = Some(Arc::new(table));
+                this.update_rows(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn set_delimiter(&mut self, delimiter: char, cx: &mut Context<Self>) {
+        let dialect = self.dialect.unwrap_or_default();
+        self.dialect = Some(CsvDialect {
+            delimiter,
+            ..dialect
+        });
+        self.editing = None;
+        self.selection = None;
+        self.sort = None;
+        self.alignments.clear();
+        self.reparse(cx);
+    }
+
+    /// Recomputes the records shown, after the filter or the records change.
+    fn update_rows(&mut self, cx: &mut Context<Self>) {
+        let Some(table) = self.table.clone() else {
+            return;
+        };
+        let query = self.filter_editor.read(cx).text(cx).to_lowercase();
+        let query = query.trim();
+        self.rows = (1..table.records.len())
+            .filter(|record_ix| {
+                query.is_empty()
+                    || table.records[*record_ix]
+                        .fields
+                        .iter()
+                        .any(|field| field.value.to_lowercase().contains(query))
+            })
+            .collect();
+        if let Some((column, direction)) = self.sort {
+            self.rows.sort_by(|a, b| {
+                let ordering = csv_document::compare_values(
+                    table.records[*a].value(column),
+                    table.records[*b].value(column),
+                );
+                match direction {
+                    SortDirection::Ascending => ordering,
+                    SortDirection::Descending => ordering.reverse(),
+                }
+            });
+        }
+        if self.selection.is_some_and(|(record_ix, column)| {
+            record_ix >= table.records.len() || column >= table.column_count
+        }) {
+            self.selection = None;
+        }
+        cx.notify();
+    }
+
+    fn set_sort(&mut self, sort: Option<(usize, SortDirection)>, cx: &mut Context<Self>) {
+        self.sort = sort;
+        self.update_rows(cx);
+    }
+
+    /// Sorts by the column ascending, then descending, then not at all.
+    fn cycle_sort(&mut self, column: usize, cx: &mut Context<Self>) {
+        let sort = match self.sort {
+            Some((sorted, SortDirection::Ascending)) if sorted == column => {
+                Some((column, SortDirection::Descending))
+            }
+            Some((sorted, SortDirection::Descending)) if sorted == column => None,
+            _ => Some((column, SortDirection::Ascending)),
+        };
+        self.set_sort(sort, cx);
+    }
+
+    fn alignment(&self, table: &CsvTable, column: usize) -> ColumnAlignment {
+        self.alignments.get(&column).copied().unwrap_or(
+            if table.numeric_columns.get(column).copied().unwrap_or(false) {
+                ColumnAlignment::Right
+            } else {
+                ColumnAlignment::Left
+            },
+        )
+    }
+
+    fn set_alignment(&mut self, column: usize, alignment: ColumnAlignment, cx: &mut Context<Self>) {
+        self.alignments.insert(column, alignment);
+        cx.notify();
+    }
+
+    fn select_cell(&mut self, record_ix: usize, column: usize, cx: &mut Context<Self>) {
+        self.selection = Some((record_ix, column));
+        if let Some(row_ix) = self.rows.iter().position(|row| *row == record_ix) {
+            self.scroll_handle
+                .scroll_to_item(row_ix, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn select_row(&mut self, row_ix: usize, cx: &mut Context<Self>) {
+        if let Some(record_ix) = self.rows.get(row_ix).copied() {
+            let column = self.selection.map_or(0, |(_, column)| column);
+            self.select_cell(record_ix, column, cx);
+        }
+    }
+
+    fn selected_row_ix(&self) -> Option<usize> {
+        let (record_ix, _) = self.selection?;
+        self.rows.iter().position(|row| *row == record_ix)
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let row_ix = self.selected_row_ix().map_or(0, |row_ix| row_ix + 1);
+        self.select_row(row_ix.min(self.rows.len().saturating_sub(1)), cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        let row_ix = self
+            .selected_row_ix()
+            .map_or(0, |row_ix| row_ix.saturating_sub(1));
+        self.select_row(row_ix, cx);
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_row(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_row(self.rows.len().saturating_sub(1), cx);
+    }
+
+    fn select_next_column(&mut self, _: &SelectNextColumn, _: &mut Window, cx: &mut Context<Self>) {
+        let column_count = self.table.as_ref().map_or(0, |table| table.column_count);
+        if let Some((record_ix, column)) = self.selection {
+            self.select_cell(
+                record_ix,
+                (column + 1).min(column_count.saturating_sub(1)),
+                cx,
+            );
+        }
+    }
+
+    fn select_previous_column(
+        &mut self,
+        _: &SelectPreviousColumn,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some((record_ix, column)) = self.selection {
+            self.select_cell(record_ix, column.saturating_sub(1), cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editing.is_some() {
+            self.commit_edit(window, cx);
+        } else if let Some((record_ix, column)) = self.selection {
+            self.start_edit(record_ix, column, window, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.editing.take().is_some() {
+            self.focus_handle.focus(window);
+        } else {
+            self.selection = None;
+        }
+        cx.notify();
+    }
+
+    fn undo(&mut self, _: &editor::actions::Undo, _: &mut Window, cx: &mut Context<Self>) {
+        self.editing = None;
+        self.buffer.update(cx, |buffer, cx| buffer.undo(cx));
+    }
+
+    fn redo(&mut self, _: &editor::actions::Redo, _: &mut Window, cx: &mut Context<Self>) {
+        self.editing = None;
+        self.buffer.update(cx, |buffer, cx| buffer.redo(cx));
+    }
+
+    fn start_edit(
+        &mut self,
+        record_ix: usize,
+        column: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.buffer.read(cx).read_only() {
+            return;
+        }
+        let Some(table) = self.table.clone() else {
+            return;
+        };
+        let Some(record) = table.records.get(record_ix) else {
+            return;
+        };
+
+        let snapshot = &table.snapshot;
+        let (range, prefix) = match record.fields.get(column) {
+            Some(field) => (field.range.clone(), String::new()),
+            None => (
+                record.end..record.end,
+                table
+                    .dialect
+                    .delimiter
+                    .to_string()
+                    .repeat(column + 1 - record.fields.len()),
+            ),
+        };
+        let range = snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+        let original = record.value(column).to_string();
+
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_text(original.as_str(), window, cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+            editor
+        });
+        editor.focus_handle(cx).focus(window);
+        self.selection = Some((record_ix, column));
+        self.editing = Some(CellEdit {
+            record_ix,
+            column,
+            editor,
+            original,
+            range,
+            prefix,
+        });
+        cx.notify();
+    }
+
+    /// Writes the value of the edited cell to the buffer, as a single undoable edit.
+    fn commit_edit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(edit) = self.editing.take() else {
+            return;
+        };
+        let value = edit.editor.read(cx).text(cx);
+        if value != edit.original {
+            let dialect = self.dialect.unwrap_or_default();
+            let text = format!("{}{}", edit.prefix, dialect.encode_field(&value));
+            self.buffer.update(cx, |buffer, cx| {
+                buffer.edit([(edit.range, text)], None, cx);
+            });
+        }
+        self.focus_handle.focus(window);
+        cx.notify();
+    }
+
+    fn on_cell_click(
+        &mut self,
+        record_ix: usize,
+        column: usize,
+        event: &ClickEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .editing
+            .as_ref()
+            .is_some_and(|edit| (edit.record_ix, edit.column) == (record_ix, column))
+        {
+            return;
+        }
+        self.commit_edit(window, cx);
+        if event.click_count() > 1 {
+            self.start_edit(record_ix, column, window, cx);
+        } else {
+            self.focus_handle.focus(window);
+            self.select_cell(record_ix, column, cx);
+        }
+    }
+
+    fn render_cell(
+        &self,
+        table: &CsvTable,
+        record_ix: usize,
+        column: usize,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let width = table.column_widths[column];
+        let selected = self.selection == Some((record_ix, column));
+        let editor = self
+            .editing
+            .as_ref()
+            .filter(|edit| (edit.record_ix, edit.column) == (record_ix, column))
+            .map(|edit| edit.editor.clone());
+
+        let cell = h_flex()
+            .id(("cell", record_ix * table.column_count + column))
+            .w(width)
+            .flex_none()
+            .h_full()
+            .px_1p5()
+            .overflow_hidden()
+            .border_1()
+            .border_color(if selected {
+                cx.theme().colors().border_focused
+            } else {
+                gpui::transparent_black()
+            })
+            .on_click(cx.listener(move |this, event, window, cx| {
+                this.on_cell_click(record_ix, column, event, window, cx)
+            }));
+
+        match editor {
+            Some(editor) => cell.child(editor).into_any_element(),
+            None => cell
+                .map(|cell| match self.alignment(table, column) {
+                    ColumnAlignment::Left => cell.justify_start(),
+                    ColumnAlignment::Center => cell.justify_center(),
+                    ColumnAlignment::Right => cell.justify_end(),
+                })
+                .child(
+                    Label::new(table.records[record_ix].value(column).to_string())
+                        .buffer_font(cx)
+                        .single_line()
+                        .truncate(),
+                )
+                .into_any_element(),
+        }
+    }
+
+    fn render_rows(&self, range: Range<usize>, cx: &Context<Self>) -> Vec<AnyElement> {
+        let Some(table) = self.table.as_ref() else {
+            return Vec::new();
+        };
+        self.rows[range.clone()]
+            .iter()
+            .zip(range)
+            .map(|(&record_ix, row_ix)| {
+                h_flex()
+                    .id(("row", record_ix))
+                    .h_7()
+                    .when(row_ix % 2 == 1, |row| {
+                        row.bg(cx.theme().colors().text.opacity(0.05))
+                    })
+                    .child(
+                        div().w(ROW_NUMBER_WIDTH).flex_none().px_1p5().child(
+                            Label::new(record_ix.to_string())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                    .children(
+                        (0..table.column_count)
+                            .map(|column| self.render_cell(table, record_ix, column, cx)),
+                    )
+                    .into_any_element()
+            })
+            .collect()
+    }
+
+    fn render_header(&self, table: &CsvTable, cx: &Context<Self>) -> impl IntoElement {
+        let this = cx.entity().downgrade();
+        h_flex()
+            .h_8()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(div().w(ROW_NUMBER_WIDTH).flex_none())
+            .children((0..table.column_count).map(|column| {
+                let sort = self
+                    .sort
+                    .filter(|(sorted, _)| *sorted == column)
+                    .map(|(_, direction)| direction);
+                let alignment = self.alignment(table, column);
+                let title = table
+                    .records
+                    .first()
+                    .map(|header| header.value(column).to_string())
+                    .unwrap_or_default();
+                let width = table.column_widths[column];
+                let on_click =
+                    cx.listener(move |this, _: &ClickEvent, _, cx| this.cycle_sort(column, cx));
+                let this = this.clone();
+
+                right_click_menu(("header-menu", column))
+                    .trigger(move |_, _, _| {
+                        h_flex()
+                            .id(("header", column))
+                            .w(width)
+                            .flex_none()
+                            .h_full()
+                            .px_1p5()
+                            .gap_1()
+                            .child(
+                                Label::new(title.clone())
+                                    .weight(FontWeight::SEMIBOLD)
+                                    .single_line()
+                                    .truncate(),
+                            )
+                            .when_some(sort, |this, direction| {
+                                this.child(
+                                    Icon::new(match direction {
+                                        SortDirection::Ascending => IconName::ArrowUp,
+                                        SortDirection::Descending => IconName::ArrowDown,
+                                    })
+                                    .size(IconSize::XSmall)
+                                    .color(Color::Muted),
+                                )
+                            })
+                            .on_click(on_click)
+                    })
+                    .menu(move |window, cx| {
+                        let this = this.clone();
+                        ContextMenu::build(window, cx, move |menu, _, _| {
+                            let sort_entry = |direction| {
+                                let this = this.clone();
+                                move |_: &mut Window, cx: &mut App| {
+                                    this.update(cx, |this, cx| {
+                                        this.set_sort(Some((column, direction)), cx)
+                                    })
+                                    .ok();
+                                }
+                            };
+                            let align_entry = |alignment| {
+                                let this = this.clone();
+                                move |_: &mut Window, cx: &mut App| {
+                                    this.update(cx, |this, cx| {
+                                        this.set_alignment(column, alignment, cx)
+                                    })
+                                    .ok();
+                                }
+                            };
+                            let clear_sort = {
+                                let this = this.clone();
+                                move |_: &mut Window, cx: &mut App| {
+                                    this.update(cx, |this, cx| this.set_sort(None, cx)).ok();
+                                }
+                            };
+                            menu.entry("Sort Ascending", None, sort_entry(SortDirection::Ascending))
+                                .entry(
+                                    "Sort Descending",
+                                    None,
+                                    sort_entry(SortDirection::Descending),
+                                )
+                                .when(sort.is_some(), |menu| {
+                                    menu.entry("Clear Sort", None, clear_sort)
+                                })
+                                .separator()
+                                .toggleable_entry(
+                                    "Align Left",
+                                    alignment == ColumnAlignment::Left,
+                                    IconPosition::Start,
+                                    None,
+                                    align_entry(ColumnAlignment::Left),
+                                )
+                                .toggleable_entry(
+                                    "Align Center",
+                                    alignment == ColumnAlignment::Center,
+                                    IconPosition::Start,
+                                    None,
+                                    align_entry(ColumnAlignment::Center),
+                                )
+                                .toggleable_entry(
+                                    "Align Right",
+                                    alignment == ColumnAlignment::Right,
+                                    IconPosition::Start,
+                                    None,
+                                    align_entry(ColumnAlignment::Right),
+                                )
+                        })
+                    })
+            }))
+    }
+}
+
+impl CsvTableView {
+    fn render_toolbar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let this = cx.entity().downgrade();
+        let dialect = self.dialect.unwrap_or_default();
+        let summary = match self.table.as_ref() {
+            Some(table) => format!(
+                "{} of {} rows, {} columns",
+                self.rows.len(),
+                table.records.len().saturating_sub(1),
+                table.column_count
+            ),
+            None => "Parsing…".to_string(),
+        };
+
+        h_flex()
+            .gap_2()
+            .p_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .flex_1()
+                    .h_8()
+                    .px_2()
+                    .gap_2()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Icon::new(IconName::Filter).color(Color::Muted))
+                    .child(self.filter_editor.clone()),
+            )
+            .child(
+                Label::new(summary)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                PopoverMenu::new("csv-delimiter")
+                    .trigger(
+                        Button::new(
+                            "csv-delimiter-trigger",
+                            format!("Delimiter: {}", dialect.delimiter_label()),
+                        )
+                        .style(ButtonStyle::Subtle)
+                        .label_size(LabelSize::Small),
+                    )
+                    .menu(move |window, cx| {
+                        let this = this.clone();
+                        Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                            for delimiter in csv_document::delimiters() {
+                                let this = this.clone();
+                                let label = CsvDialect {
+                                    delimiter,
+                                    ..dialect
+                                }
+                                .delimiter_label();
+                                menu = menu.toggleable_entry(
+                                    label,
+                                    delimiter == dialect.delimiter,
+                                    IconPosition::Start,
+                                    None,
+                                    move |_, cx| {
+                                        this.update(cx, |this, cx| {
+                                            this.set_delimiter(delimiter, cx)
+                                        })
+                                        .ok();
+                                    },
+                                );
+                            }
+                            menu
+                        }))
+                    }),
+            )
+            .child(
+                IconButton::new("csv-raw-text", IconName::FileText)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|window, cx| {
+                        Tooltip::for_action("Show Raw Text", &ToggleTableView, window, cx)
+                    })
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(Box::new(ToggleTableView), cx)
+                    }),
+            )
+    }
+}
+
+impl Render for CsvTableView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let body = match self.table.clone() {
+            Some(table) if table.column_count > 0 => {
+                let width = ROW_NUMBER_WIDTH + table.column_widths.iter().copied().sum::<Pixels>();
+                div()
+                    .id("csv-table")
+                    .size_full()
+                    .overflow_x_scroll()
+                    .child(
+                        v_flex()
+                            .w(width)
+                            .h_full()
+                            .child(self.render_header(&table, cx))
+                            .child(
+                                uniform_list(
+                                    "csv-table-rows",
+                                    self.rows.len(),
+                                    cx.processor(|this, range: Range<usize>, _, cx| {
+                                        this.render_rows(range, cx)
+                                    }),
+                                )
+                                .track_scroll(self.scroll_handle.clone())
+                                .flex_1(),
+                            ),
+                    )
+                    .into_any_element()
+            }
+            Some(_) => h_flex()
+                .size_full()
+                .justify_center()
+                .child(Label::new("The file has no rows.").color(Color::Muted))
+                .into_any_element(),
+            None => div().into_any_element(),
+        };
+
+        v_flex()
+            .key_context("CsvTable")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_next_column))
+            .on_action(cx.listener(Self::select_previous_column))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .child(div().flex_1().min_h_0().child(body))
+    }
+}
+
+impl Focusable for CsvTableView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<CsvTableViewEvent> for CsvTableView {}
+
+impl Item for CsvTableView {
+    type Event = CsvTableViewEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            CsvTableViewEvent::TitleChanged => {
+                f(ItemEvent::UpdateTab);
+                f(ItemEvent::UpdateBreadcrumbs);
+            }
+        }
+    }
+
+    fn tab_icon(&self, _window: &Window, cx: &App) -> Option<Icon> {
+        let path = self.path.as_ref()?;
+        FileIcons::get_icon(path, cx).map(Icon::from_path)
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| format!("Table {}", name.to_string_lossy()).into())
+            .unwrap_or_else(|| "Table".into())
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        let path = self.path.as_ref()?;
+        Some(path.to_string_lossy().into_owned().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("csv table: open")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.buffer.entity_id(), self.buffer.read(cx))
+    }
+
+    fn is_singleton(&self, _cx: &App) -> bool {
+        true
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, _cx: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.commit_edit(window, cx);
+        project.update(cx, |project, cx| {
+            project.save_buffer(self.buffer.clone(), cx)
+        })
+    }
+
+    fn reload(
+        &mut self,
+        project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.editing = None;
+        let reload = project.update(cx, |project, cx| {
+            project.reload_buffers([self.buffer.clone()].into_iter().collect(), true, cx)
+        });
+        cx.background_spawn(async move {
+            reload.await?;
+            Ok(())
+        })
+    }
+}
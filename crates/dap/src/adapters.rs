@@ -156,6 +156,7 @@ impl DebugTaskDefinition {
             adapter: self.adapter.clone().into(),
             build: None,
             tcp_connection: self.tcp_connection.clone(),
+            inputs: Vec::new(),
             config: self.config.clone(),
         }
     }
@@ -439,6 +440,7 @@ impl DebugAdapter for FakeAdapter {
             build: None,
             config,
             tcp_connection: None,
+            inputs: Vec::new(),
         })
     }
 
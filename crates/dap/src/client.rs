@@ -57,6 +57,29 @@ impl DebugAdapterClient {
         Ok(this)
     }
 
+    /// Starts a client that talks to a recorded [`DapTranscript`](crate::transcript::DapTranscript)
+    /// instead of a debug adapter.
+    #[cfg(any(test, feature = "test-support"))]
+    pub async fn start_replay(
+        id: SessionId,
+        binary: DebugAdapterBinary,
+        transcript: crate::transcript::DapTranscript,
+        message_handler: DapMessageHandler,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        let transport =
+            crate::transport::ReplayTransport::new(transcript, cx.background_executor().clone());
+        let this = Self {
+            id,
+            binary,
+            transport_delegate: TransportDelegate::with_transport(Box::new(transport)),
+            sequence_count: AtomicU64::new(1),
+        };
+        this.connect(message_handler, cx).await?;
+
+        Ok(this)
+    }
+
     pub fn should_reconnect_for_ssh(&self) -> bool {
         self.transport_delegate.tcp_arguments().is_some()
             && self.binary.command.as_deref() == Some("ssh")
@@ -253,7 +276,9 @@ impl DebugAdapterClient {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{client::DebugAdapterClient, debugger_settings::DebuggerSettings};
+    use crate::{
+        client::DebugAdapterClient, debugger_settings::DebuggerSettings, transcript::DapTranscript,
+    };
     use dap_types::{
         Capabilities, InitializeRequestArguments, InitializeRequestArgumentsPathFormat,
         RunInTerminalRequestArguments, StartDebuggingRequestArguments,
@@ -344,6 +369,115 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    pub async fn test_replay_transcript(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let mut transcript = DapTranscript::new(None);
+        transcript.push_rpc_message(
+            IoKind::StdIn,
+            &json!({
+                "seq": 7,
+                "type": "request",
+                "command": "initialize",
+                "arguments": { "adapterID": "recorded-adapter" },
+            })
+            .to_string(),
+        );
+        transcript.push_rpc_message(
+            IoKind::StdOut,
+            &json!({
+                "seq": 1,
+                "type": "response",
+                "request_seq": 7,
+                "success": true,
+                "command": "initialize",
+                "body": { "supportsConfigurationDoneRequest": true },
+            })
+            .to_string(),
+        );
+        transcript.push_rpc_message(
+            IoKind::StdOut,
+            &json!({ "seq": 2, "type": "event", "event": "initialized", "body": {} }).to_string(),
+        );
+        let transcript = DapTranscript::from_json(&transcript.to_json().unwrap()).unwrap();
+
+        let called_event_handler = Arc::new(AtomicBool::new(false));
+        let client = DebugAdapterClient::start_replay(
+            crate::client::SessionId(1),
+            DebugAdapterBinary {
+                command: None,
+                arguments: Default::default(),
+                envs: Default::default(),
+                connection: None,
+                cwd: None,
+                request_args: StartDebuggingRequestArguments {
+                    configuration: serde_json::Value::Null,
+                    request: dap_types::StartDebuggingRequestArgumentsRequest::Launch,
+                },
+            },
+            transcript,
+            Box::new({
+                let called_event_handler = called_event_handler.clone();
+                move |event| {
+                    called_event_handler.store(true, Ordering::SeqCst);
+                    assert_eq!(
+                        Message::Event(Box::new(Events::Initialized(
+                            Some(Capabilities::default())
+                        ))),
+                        event
+                    );
+                }
+            }),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        cx.run_until_parked();
+        assert!(
+            !called_event_handler.load(Ordering::SeqCst),
+            "Replay should wait for the recorded request"
+        );
+
+        let response = client
+            .request::<Initialize>(InitializeRequestArguments {
+                client_id: Some("zed".to_owned()),
+                client_name: Some("Zed".to_owned()),
+                adapter_id: "replayed-adapter".to_owned(),
+                locale: None,
+                path_format: None,
+                supports_variable_type: None,
+                supports_variable_paging: None,
+                supports_run_in_terminal_request: None,
+                supports_memory_references: None,
+                supports_progress_reporting: None,
+                supports_invalidated_event: None,
+                lines_start_at1: None,
+                columns_start_at1: None,
+                supports_memory_event: None,
+                supports_args_can_be_interpreted_by_shell: None,
+                supports_start_debugging_request: None,
+                supports_ansistyling: None,
+            })
+            .await
+            .unwrap();
+
+        cx.run_until_parked();
+
+        assert_eq!(
+            dap_types::Capabilities {
+                supports_configuration_done_request: Some(true),
+                ..Default::default()
+            },
+            response
+        );
+        assert!(
+            called_event_handler.load(Ordering::SeqCst),
+            "Event handler was not called"
+        );
+    }
+
     #[gpui::test]
     pub async fn test_calls_event_handler(cx: &mut TestAppContext) {
         init_test(cx);
@@ -4,6 +4,7 @@ pub mod debugger_settings;
 pub mod inline_value;
 pub mod proto_conversions;
 mod registry;
+pub mod transcript;
 pub mod transport;
 
 use std::net::Ipv4Addr;
@@ -0,0 +1,124 @@
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{adapters::DebugAdapterName, transport::IoKind};
+
+/// A recording of every DAP message exchanged between Zed and a debug adapter
+/// during a single session.
+///
+/// Transcripts are exported from the DAP log view to attach to bug reports. In tests, they can be
+/// replayed to a [`crate::client::DebugAdapterClient`] with `DebugAdapterClient::start_replay`,
+/// which makes it possible to reproduce debugger issues without the adapter installed. Replaying
+/// is a test-only tool, and isn't available in release builds.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DapTranscript {
+    pub version: u32,
+    pub adapter: Option<DebugAdapterName>,
+    pub entries: Vec<TranscriptEntry>,
+    /// Whether messages were dropped after the transcript reached [`DapTranscript::ENTRY_LIMIT`].
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    pub direction: TranscriptDirection,
+    /// The raw JSON body of the message, without the `Content-Length` header.
+    pub message: serde_json::Value,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptDirection {
+    /// A message Zed sent to the debug adapter.
+    Send,
+    /// A message Zed received from the debug adapter.
+    Receive,
+}
+
+impl DapTranscript {
+    pub const VERSION: u32 = 1;
+    /// The most messages a transcript records. The first messages are kept rather than the last
+    /// ones, since a replay has to start from the beginning of the session.
+    pub const ENTRY_LIMIT: usize = 10_000;
+
+    pub fn new(adapter: Option<DebugAdapterName>) -> Self {
+        Self {
+            version: Self::VERSION,
+            adapter,
+            entries: Vec::new(),
+            truncated: false,
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let transcript: Self =
+            serde_json::from_str(json).context("deserializing debug adapter transcript")?;
+        anyhow::ensure!(
+            transcript.version <= Self::VERSION,
+            "unsupported debug adapter transcript version {}",
+            transcript.version
+        );
+        Ok(transcript)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("serializing debug adapter transcript")
+    }
+
+    /// Records a message as reported to a [`crate::transport::LogKind::Rpc`] log handler.
+    ///
+    /// Messages that are not valid JSON are ignored, since they could not be replayed, and so are
+    /// the messages after the first [`DapTranscript::ENTRY_LIMIT`] ones.
+    pub fn push_rpc_message(&mut self, kind: IoKind, message: &str) {
+        if self.entries.len() >= Self::ENTRY_LIMIT {
+            self.truncated = true;
+            return;
+        }
+        let direction = match kind {
+            IoKind::StdIn => TranscriptDirection::Send,
+            IoKind::StdOut | IoKind::StdErr => TranscriptDirection::Receive,
+        };
+        match serde_json::from_str(message) {
+            Ok(message) => self.entries.push(TranscriptEntry { direction, message }),
+            Err(error) => log::debug!("Skipping non-JSON DAP message in transcript: {error}"),
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl TranscriptEntry {
+    pub(crate) fn message_type(&self) -> Option<&str> {
+        self.message.get("type")?.as_str()
+    }
+
+    pub(crate) fn command(&self) -> Option<&str> {
+        self.message.get("command")?.as_str()
+    }
+
+    pub(crate) fn seq(&self) -> Option<u64> {
+        self.message.get("seq")?.as_u64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_is_capped() {
+        let mut transcript = DapTranscript::new(None);
+        for seq in 0..DapTranscript::ENTRY_LIMIT + 2 {
+            transcript.push_rpc_message(
+                IoKind::StdIn,
+                &format!(r#"{{"seq":{seq},"type":"request","command":"threads"}}"#),
+            );
+        }
+        assert_eq!(transcript.entries.len(), DapTranscript::ENTRY_LIMIT);
+        assert_eq!(transcript.entries[0].message["seq"], 0);
+        assert!(transcript.truncated);
+
+        let json = transcript.to_json().unwrap();
+        assert_eq!(DapTranscript::from_json(&json).unwrap(), transcript);
+    }
+}
@@ -1,6 +1,4 @@
 use anyhow::{Context as _, Result, anyhow, bail};
-#[cfg(any(test, feature = "test-support"))]
-use async_pipe::{PipeReader, PipeWriter};
 use dap_types::{
     ErrorResponse,
     messages::{Message, Response},
@@ -26,6 +24,8 @@ use std::{
 use task::TcpArgumentsTemplate;
 use util::ConnectionResult;
 
+#[cfg(any(test, feature = "test-support"))]
+use crate::transcript::{DapTranscript, TranscriptDirection, TranscriptEntry};
 use crate::{
     adapters::{DebugAdapterBinary, TcpArguments},
     client::DapMessageHandler,
@@ -160,13 +160,22 @@ impl TransportDelegate {
     pub(crate) async fn start(binary: &DebugAdapterBinary, cx: &mut AsyncApp) -> Result<Self> {
         let log_handlers: LogHandlers = Default::default();
         let transport = start(binary, log_handlers.clone(), cx).await?;
-        Ok(Self {
+        Ok(Self::new(transport, log_handlers))
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    pub(crate) fn with_transport(transport: Box<dyn Transport>) -> Self {
+        Self::new(transport, Default::default())
+    }
+
+    fn new(transport: Box<dyn Transport>, log_handlers: LogHandlers) -> Self {
+        Self {
             transport: Mutex::new(transport),
             log_handlers,
             server_tx: Default::default(),
             pending_requests: Arc::new(Mutex::new(PendingRequests::new())),
             tasks: Default::default(),
-        })
+        }
     }
 
     pub async fn connect(
@@ -741,6 +750,178 @@ impl Drop for StdioTransport {
     }
 }
 
+/// Plays a recorded [`DapTranscript`] back to the client in place of a real debug adapter.
+///
+/// Messages the client sends are matched against the recorded outgoing messages by command,
+/// and once every message of a recorded batch has been seen, the messages the adapter sent in
+/// response are written back. Response sequence numbers are rewritten to match the requests the
+/// client actually sent, so the client doesn't need to reproduce the recorded sequence numbers.
+#[cfg(any(test, feature = "test-support"))]
+pub struct ReplayTransport {
+    transcript: Arc<DapTranscript>,
+    executor: BackgroundExecutor,
+    replay_task: Option<Task<()>>,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl ReplayTransport {
+    pub fn new(transcript: DapTranscript, executor: BackgroundExecutor) -> Self {
+        Self {
+            transcript: Arc::new(transcript),
+            executor,
+            replay_task: None,
+        }
+    }
+
+    async fn replay(
+        transcript: Arc<DapTranscript>,
+        client_reader: async_pipe::PipeReader,
+        mut adapter_writer: async_pipe::PipeWriter,
+    ) -> Result<()> {
+        let entries = &transcript.entries;
+        let mut reader = BufReader::new(client_reader);
+        let mut buffer = String::new();
+        let mut recorded_to_actual_seq = HashMap::default();
+        let mut pending_sends = Vec::new();
+
+        let mut cursor =
+            Self::write_received_messages(entries, 0, &recorded_to_actual_seq, &mut adapter_writer)
+                .await?;
+
+        loop {
+            if pending_sends.is_empty() {
+                while let Some(entry) = entries.get(cursor)
+                    && entry.direction == TranscriptDirection::Send
+                {
+                    pending_sends.push(cursor);
+                    cursor += 1;
+                }
+            }
+
+            let message =
+                match TransportDelegate::receive_server_message(&mut reader, &mut buffer, None)
+                    .await
+                {
+                    ConnectionResult::Timeout => bail!("Timed out when replaying transcript"),
+                    ConnectionResult::ConnectionReset => {
+                        log::info!("Client closed the replay connection");
+                        return Ok(());
+                    }
+                    ConnectionResult::Result(message) => serde_json::to_value(message?)?,
+                };
+            let command = message.get("command").and_then(|command| command.as_str());
+
+            if pending_sends.is_empty() {
+                log::warn!(
+                    "Client sent {:?} after the end of the debug adapter transcript",
+                    command
+                );
+                continue;
+            }
+
+            let ix = pending_sends
+                .iter()
+                .position(|ix| entries[*ix].command() == command)
+                .with_context(|| {
+                    format!(
+                        "Client sent {:?} but the transcript expected {:?}",
+                        command,
+                        entries[pending_sends[0]].command()
+                    )
+                })?;
+            let entry = &entries[pending_sends.remove(ix)];
+            if entry.message_type() == Some("request")
+                && let Some((recorded_seq, actual_seq)) = entry
+                    .seq()
+                    .zip(message.get("seq").and_then(|seq| seq.as_u64()))
+            {
+                recorded_to_actual_seq.insert(recorded_seq, actual_seq);
+            }
+
+            if pending_sends.is_empty() {
+                cursor = Self::write_received_messages(
+                    entries,
+                    cursor,
+                    &recorded_to_actual_seq,
+                    &mut adapter_writer,
+                )
+                .await?;
+            }
+        }
+    }
+
+    /// Writes the consecutive adapter messages starting at `cursor`, returning the index of the
+    /// next message the client is expected to send.
+    async fn write_received_messages(
+        entries: &[TranscriptEntry],
+        mut cursor: usize,
+        recorded_to_actual_seq: &HashMap<u64, u64>,
+        writer: &mut async_pipe::PipeWriter,
+    ) -> Result<usize> {
+        while let Some(entry) = entries.get(cursor)
+            && entry.direction == TranscriptDirection::Receive
+        {
+            cursor += 1;
+
+            let mut message = entry.message.clone();
+            if entry.message_type() == Some("response")
+                && let Some(actual_seq) = message
+                    .get("request_seq")
+                    .and_then(|seq| seq.as_u64())
+                    .and_then(|seq| recorded_to_actual_seq.get(&seq))
+            {
+                message["request_seq"] = (*actual_seq).into();
+            }
+
+            let message = serde_json::to_string(&message)?;
+            writer
+                .write_all(TransportDelegate::build_rpc_message(message).as_bytes())
+                .await?;
+            writer.flush().await?;
+        }
+        Ok(cursor)
+    }
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl Transport for ReplayTransport {
+    fn has_adapter_logs(&self) -> bool {
+        false
+    }
+
+    fn tcp_arguments(&self) -> Option<TcpArguments> {
+        None
+    }
+
+    fn connect(
+        &mut self,
+    ) -> Task<
+        Result<(
+            Box<dyn AsyncWrite + Unpin + Send + 'static>,
+            Box<dyn AsyncRead + Unpin + Send + 'static>,
+        )>,
+    > {
+        let (client_writer, client_reader) = async_pipe::pipe();
+        let (adapter_writer, adapter_reader) = async_pipe::pipe();
+
+        let transcript = self.transcript.clone();
+        self.replay_task = Some(self.executor.spawn(async move {
+            if let Err(error) = Self::replay(transcript, client_reader, adapter_writer).await {
+                log::error!("Error replaying debug adapter transcript: {error:#}");
+            }
+        }));
+
+        Task::ready(Ok((
+            Box::new(client_writer) as _,
+            Box::new(adapter_reader) as _,
+        )))
+    }
+
+    fn kill(&mut self) {
+        self.replay_task.take();
+    }
+}
+
 #[cfg(any(test, feature = "test-support"))]
 type RequestHandler = Box<dyn Send + FnMut(u64, serde_json::Value) -> RequestHandling<Response>>;
 
@@ -60,6 +60,7 @@ impl DebugAdapter for GdbDebugAdapter {
             build: None,
             config: serde_json::Value::Object(obj),
             tcp_connection: None,
+            inputs: Vec::new(),
         })
     }
 
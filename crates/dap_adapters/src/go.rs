@@ -400,6 +400,7 @@ impl DebugAdapter for GoDebugAdapter {
             build: None,
             config: args,
             tcp_connection: None,
+            inputs: Vec::new(),
         })
     }
 
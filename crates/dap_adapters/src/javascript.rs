@@ -222,6 +222,7 @@ impl DebugAdapter for JsDebugAdapter {
             build: None,
             config: args,
             tcp_connection: None,
+            inputs: Vec::new(),
         })
     }
 
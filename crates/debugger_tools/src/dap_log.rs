@@ -2,6 +2,7 @@ use dap::{
     adapters::DebugAdapterName,
     client::SessionId,
     debugger_settings::DebuggerSettings,
+    transcript::DapTranscript,
     transport::{IoKind, LogKind},
 };
 use editor::{Editor, EditorEvent};
@@ -14,7 +15,7 @@ use gpui::{
     ParentElement, Render, SharedString, Styled, Subscription, WeakEntity, Window, actions, div,
 };
 use project::{
-    Project,
+    DirectoryLister, Project,
     debugger::{dap_store, session::Session},
     search::SearchQuery,
 };
@@ -84,6 +85,7 @@ struct DebugAdapterState {
     id: SessionId,
     log_messages: VecDeque<SharedString>,
     rpc_messages: RpcMessages,
+    transcript: DapTranscript,
     session_label: SharedString,
     adapter_name: DebugAdapterName,
     has_adapter_logs: bool,
@@ -139,6 +141,7 @@ impl DebugAdapterState {
             id,
             log_messages: VecDeque::new(),
             rpc_messages: RpcMessages::new(),
+            transcript: DapTranscript::new(Some(adapter_name.clone())),
             adapter_name,
             session_label,
             has_adapter_logs,
@@ -267,6 +270,12 @@ impl LogStore {
             IoKind::StdIn => MessageKind::Send,
         };
 
+        // Unlike the displayed messages, the transcript keeps the messages from the start of the
+        // session, without reformatting them, so that it can be replayed.
+        debug_client_state
+            .transcript
+            .push_rpc_message(io_kind, &message);
+
         let rpc_messages = &mut debug_client_state.rpc_messages;
 
         // Push a separator if the kind has changed
@@ -481,6 +490,14 @@ impl LogStore {
             .map(|state| &mut state.rpc_messages.messages)
     }
 
+    fn transcript_for_session(
+        &mut self,
+        id: &LogStoreEntryIdentifier<'_>,
+    ) -> Option<&DapTranscript> {
+        self.get_debug_adapter_state(id)
+            .map(|state| &state.transcript)
+    }
+
     fn initialization_sequence_for_session(
         &mut self,
         id: &LogStoreEntryIdentifier<'_>,
@@ -648,6 +665,17 @@ impl Render for DapLogToolbarItemView {
                     )
                     .ml_2(),
             )
+            .child(
+                div()
+                    .child(
+                        Button::new("export_transcript_button", "Export Transcript").on_click(
+                            |_, window, cx| {
+                                window.dispatch_action(Box::new(ExportDebugAdapterTranscript), cx)
+                            },
+                        ),
+                    )
+                    .ml_2(),
+            )
             .into_any_element()
     }
 }
@@ -915,6 +943,46 @@ impl DapLogView {
 
         cx.focus_self(window);
     }
+
+    fn current_transcript_json(&self, cx: &mut App) -> Option<anyhow::Result<String>> {
+        let (session_id, _) = self.current_view?;
+        let id = LogStoreEntryIdentifier {
+            session_id,
+            project: Cow::Owned(self.project.downgrade()),
+        };
+        self.log_store.update(cx, |log_store, _| {
+            log_store
+                .transcript_for_session(&id)
+                .map(|transcript| transcript.to_json())
+        })
+    }
+}
+
+fn export_transcript(
+    workspace: &mut Workspace,
+    _: &ExportDebugAdapterTranscript,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(log_view) = workspace.active_item_as::<DapLogView>(cx) else {
+        return;
+    };
+    let Some(transcript) = log_view.update(cx, |log_view, cx| log_view.current_transcript_json(cx))
+    else {
+        return;
+    };
+
+    let fs = workspace.app_state().fs.clone();
+    let lister = DirectoryLister::Local(workspace.project().clone(), fs.clone());
+    let abs_path = workspace.prompt_for_new_path(lister, window, cx);
+    cx.background_spawn(async move {
+        let transcript = transcript?;
+        let Some(abs_path) = abs_path.await?.into_iter().flatten().next() else {
+            return Ok(());
+        };
+        fs.atomic_write(abs_path, transcript).await
+    })
+    .detach_and_log_err(cx);
 }
 
 fn log_contents(lines: impl Iterator<Item = SharedString>) -> String {
@@ -950,7 +1018,10 @@ actions!(
     dev,
     [
         /// Opens the debug adapter protocol logs viewer.
-        OpenDebugAdapterLogs
+        OpenDebugAdapterLogs,
+        /// Exports the message transcript of the debug session shown in the logs viewer, from the
+        /// start of the session up to its first 10,000 messages.
+        ExportDebugAdapterTranscript
     ]
 );
 
@@ -969,6 +1040,8 @@ pub fn init(cx: &mut App) {
             });
         }
 
+        workspace.register_action(export_transcript);
+
         let log_store = log_store.clone();
         workspace.register_action(move |workspace, _: &OpenDebugAdapterLogs, window, cx| {
             let project = workspace.project().read(cx);
@@ -1142,4 +1215,16 @@ impl LogStore {
                 .into()
         })
     }
+
+    pub fn transcript_for_session_id(
+        &self,
+        project: &WeakEntity<Project>,
+        session_id: SessionId,
+    ) -> Option<DapTranscript> {
+        self.projects
+            .get(project)?
+            .debug_sessions
+            .get(&session_id)
+            .map(|state| state.transcript.clone())
+    }
 }
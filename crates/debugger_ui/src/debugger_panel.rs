@@ -188,6 +188,31 @@ impl DebugPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if !scenario.referenced_inputs().is_empty() {
+            let Ok(scenario) = self.workspace.update(cx, |workspace, cx| {
+                workspace.resolve_debug_scenario_inputs(scenario, &task_context, window, cx)
+            }) else {
+                return;
+            };
+            cx.spawn_in(window, async move |this, cx| {
+                let Some(scenario) = scenario.await? else {
+                    return anyhow::Ok(());
+                };
+                this.update_in(cx, |this, window, cx| {
+                    this.start_session(
+                        scenario,
+                        task_context,
+                        active_buffer,
+                        worktree_id,
+                        window,
+                        cx,
+                    )
+                })
+            })
+            .detach_and_log_err(cx);
+            return;
+        }
+
         let dap_store = self.project.read(cx).dap_store();
         let Some(adapter) = DapRegistry::global(cx).adapter(&scenario.adapter) else {
             return;
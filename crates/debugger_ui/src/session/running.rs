@@ -927,6 +927,7 @@ impl RunningState {
                 build,
                 mut config,
                 tcp_connection,
+                inputs: _,
             } = scenario;
             Self::relativize_paths(None, &mut config, &task_context);
             Self::substitute_variables_in_config(&mut config, &task_context);
@@ -1013,42 +1014,72 @@ impl RunningState {
                     args,
                     ..task.resolved.clone()
                 };
-                let terminal = project
-                    .update_in(cx, |project, window, cx| {
-                        project.create_terminal(
-                            TerminalKind::Task(task_with_shell.clone()),
-                            window.window_handle(),
-                            cx,
-                        )
+
+                // A background task that is still running, e.g. from a previous session, is reused.
+                let running_background_terminal = if task.resolved.background.is_some() {
+                    project.read_with(cx, |project, cx| {
+                        project.local_terminal_handles().iter().find_map(|terminal| {
+                            let terminal = terminal.upgrade()?;
+                            let is_same_task = terminal.read(cx).background_task_status().is_some()
+                                && terminal.read(cx).task()?.full_label
+                                    == task.resolved.full_label;
+                            is_same_task.then_some(terminal)
+                        })
                     })?
-                    .await?;
+                } else {
+                    None
+                };
 
-                let terminal_view = cx.new_window_entity(|window, cx| {
-                    TerminalView::new(
-                        terminal.clone(),
-                        weak_workspace,
-                        None,
-                        weak_project,
-                        window,
-                        cx,
-                    )
-                })?;
-
-                this.update_in(cx, |this, window, cx| {
-                    this.ensure_pane_item(DebuggerPaneItem::Terminal, window, cx);
-                    this.debug_terminal.update(cx, |debug_terminal, cx| {
-                        debug_terminal.terminal = Some(terminal_view);
-                        cx.notify();
-                    });
-                })?;
-
-                let exit_status = terminal
-                    .read_with(cx, |terminal, cx| terminal.wait_for_completed_task(cx))?
-                    .await
-                    .context("Failed to wait for completed task")?;
+                let terminal = match running_background_terminal {
+                    Some(terminal) => terminal,
+                    None => {
+                        let terminal = project
+                            .update_in(cx, |project, window, cx| {
+                                project.create_terminal(
+                                    TerminalKind::Task(task_with_shell.clone()),
+                                    window.window_handle(),
+                                    cx,
+                                )
+                            })?
+                            .await?;
+
+                        let terminal_view = cx.new_window_entity(|window, cx| {
+                            TerminalView::new(
+                                terminal.clone(),
+                                weak_workspace,
+                                None,
+                                weak_project,
+                                window,
+                                cx,
+                            )
+                        })?;
+
+                        this.update_in(cx, |this, window, cx| {
+                            this.ensure_pane_item(DebuggerPaneItem::Terminal, window, cx);
+                            this.debug_terminal.update(cx, |debug_terminal, cx| {
+                                debug_terminal.terminal = Some(terminal_view);
+                                cx.notify();
+                            });
+                        })?;
+                        terminal
+                    }
+                };
 
-                if !exit_status.success() {
-                    anyhow::bail!("Build failed");
+                if task.resolved.background.is_some() {
+                    // Background tasks keep running, so start debugging as soon as they are ready.
+                    terminal
+                        .update(cx, |terminal, cx| terminal.wait_for_background_task_ready(cx))?
+                        .await
+                        .context("Failed to wait for the background task to become ready")?;
+                } else {
+                    let exit_status = terminal
+                        .read_with(cx, |terminal, cx| terminal.wait_for_completed_task(cx))?
+                        .await
+                        .context("Failed to wait for completed task")?;
+
+                    if !exit_status.success() {
+                        anyhow::bail!("Build failed");
+                    }
                 }
                 Some((task.resolved.clone(), locator_name, extra_config))
             } else {
@@ -1184,6 +1215,8 @@ impl RunningState {
             show_summary: false,
             show_command: false,
             show_rerun: false,
+            problem_matchers: Vec::new(),
+            background: None,
         });
 
         let workspace = self.workspace.clone();
@@ -82,6 +82,19 @@ async fn test_dap_logger_captures_all_session_rpc_messages(
         "We should have the initialization sequence in the log store"
     );
 
+    let transcript = log_store
+        .read_with(cx, |log_store, _| {
+            log_store.transcript_for_session_id(&project.downgrade(), session_id)
+        })
+        .expect("the started session should have a transcript");
+    assert!(
+        transcript.entries.iter().any(|entry| {
+            entry.direction == dap::transcript::TranscriptDirection::Send
+                && entry.message["command"] == "initialize"
+        }),
+        "The transcript should contain the initialize request"
+    );
+
     // Set up basic responses for common requests
     client.on_request::<Threads, _>(move |_, _| {
         Ok(dap::ThreadsResponse {
@@ -0,0 +1,96 @@
+use editor::Editor;
+use gpui::{
+    Context, Corner, Entity, IntoElement, ParentElement, Render, Subscription, WeakEntity, Window,
+    div,
+};
+use language::Buffer;
+use project::Encoding;
+use ui::{Button, ButtonCommon, ContextMenu, FluentBuilder, LabelSize, PopoverMenu, Tooltip};
+use workspace::{StatusItemView, Workspace, item::ItemHandle};
+
+use crate::{EncodingAction, EncodingSelector};
+
+pub struct ActiveBufferEncoding {
+    active_encoding: Option<Encoding>,
+    workspace: WeakEntity<Workspace>,
+    _observe_active_buffer: Option<Subscription>,
+}
+
+impl ActiveBufferEncoding {
+    pub fn new(workspace: &Workspace) -> Self {
+        Self {
+            active_encoding: None,
+            workspace: workspace.weak_handle(),
+            _observe_active_buffer: None,
+        }
+    }
+
+    fn update_encoding(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let encoding = Some(buffer.read(cx).encoding());
+        if self.active_encoding != encoding {
+            self.active_encoding = encoding;
+            cx.notify();
+        }
+    }
+}
+
+impl Render for ActiveBufferEncoding {
+    fn render(&mut self, _: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div().when_some(self.active_encoding, |el, active_encoding| {
+            let workspace = self.workspace.clone();
+            el.child(
+                PopoverMenu::new("encoding-menu")
+                    .anchor(Corner::BottomRight)
+                    .trigger_with_tooltip(
+                        Button::new("change-encoding", active_encoding.to_string())
+                            .label_size(LabelSize::Small),
+                        Tooltip::text("Select Encoding"),
+                    )
+                    .menu(move |window, cx| {
+                        let workspace = workspace.clone();
+                        Some(ContextMenu::build(window, cx, |menu, _, _| {
+                            [
+                                ("Reopen with Encoding", EncodingAction::Reopen),
+                                ("Save with Encoding", EncodingAction::Save),
+                            ]
+                            .into_iter()
+                            .fold(menu, |menu, (label, action)| {
+                                let workspace = workspace.clone();
+                                menu.entry(label, None, move |window, cx| {
+                                    workspace
+                                        .update(cx, |workspace, cx| {
+                                            EncodingSelector::toggle(workspace, action, window, cx);
+                                        })
+                                        .ok();
+                                })
+                            })
+                        }))
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ActiveBufferEncoding {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer = active_pane_item
+            .and_then(|item| item.downcast::<Editor>())
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+            .filter(|buffer| buffer.read(cx).file().is_some());
+        if let Some(buffer) = buffer {
+            self._observe_active_buffer =
+                Some(cx.observe(&buffer, |this, buffer, cx| this.update_encoding(buffer, cx)));
+            self.update_encoding(buffer, cx);
+        } else {
+            self.active_encoding = None;
+            self._observe_active_buffer = None;
+        }
+
+        cx.notify();
+    }
+}
@@ -0,0 +1,322 @@
+//! The text encodings that files are loaded from and saved to.
+
+use anyhow::{Result, anyhow};
+use std::fmt;
+
+/// How many leading bytes are looked at when checking for UTF-16 without a byte order mark.
+const UTF_16_DETECTION_BYTES: usize = 4096;
+
+/// The encodings that can be picked for reopening and saving files, in the order they're listed.
+const SELECTABLE_ENCODINGS: [&encoding_rs::Encoding; 16] = [
+    encoding_rs::UTF_8,
+    encoding_rs::UTF_16LE,
+    encoding_rs::UTF_16BE,
+    encoding_rs::WINDOWS_1252,
+    encoding_rs::ISO_8859_2,
+    encoding_rs::ISO_8859_15,
+    encoding_rs::WINDOWS_1250,
+    encoding_rs::WINDOWS_1251,
+    encoding_rs::KOI8_R,
+    encoding_rs::SHIFT_JIS,
+    encoding_rs::EUC_JP,
+    encoding_rs::ISO_2022_JP,
+    encoding_rs::GBK,
+    encoding_rs::GB18030,
+    encoding_rs::BIG5,
+    encoding_rs::EUC_KR,
+];
+
+/// A text encoding, along with whether the file starts with a byte order mark.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Encoding {
+    encoding: &'static encoding_rs::Encoding,
+    with_bom: bool,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self::UTF_8
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.with_bom {
+            write!(f, "{} with BOM", self.name())
+        } else {
+            write!(f, "{}", self.name())
+        }
+    }
+}
+
+impl Encoding {
+    pub const UTF_8: Self = Self {
+        encoding: encoding_rs::UTF_8,
+        with_bom: false,
+    };
+
+    /// Looks up an encoding by its name or one of its aliases, like `latin1` or `sjis`.
+    /// Only the Unicode encodings can have a byte order mark.
+    pub fn for_name(name: &str, with_bom: bool) -> Option<Self> {
+        let encoding = encoding_rs::Encoding::for_label(name.trim().as_bytes())?;
+        if encoding == encoding_rs::REPLACEMENT || encoding == encoding_rs::X_USER_DEFINED {
+            return None;
+        }
+        Some(Self {
+            encoding,
+            with_bom: with_bom && is_unicode(encoding),
+        })
+    }
+
+    /// The canonical name of the encoding, without the byte order mark.
+    pub fn name(&self) -> &'static str {
+        self.encoding.name()
+    }
+
+    pub fn has_bom(&self) -> bool {
+        self.with_bom
+    }
+
+    pub fn is_utf8(&self) -> bool {
+        self.encoding == encoding_rs::UTF_8
+    }
+
+    /// The encodings that can be picked for reopening and saving files.
+    pub fn all() -> impl Iterator<Item = Self> {
+        SELECTABLE_ENCODINGS.into_iter().flat_map(|encoding| {
+            let boms: &[bool] = if is_unicode(encoding) {
+                &[false, true]
+            } else {
+                &[false]
+            };
+            boms.iter().map(move |with_bom| Self {
+                encoding,
+                with_bom: *with_bom,
+            })
+        })
+    }
+
+    /// Guesses the encoding of a file from its byte order mark, or from the bytes themselves.
+    /// Returns None for the files that don't look like text at all.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if let Some((encoding, _)) = encoding_rs::Encoding::for_bom(bytes) {
+            return Some(Self {
+                encoding,
+                with_bom: true,
+            });
+        }
+        if let Some(encoding) = detect_utf16(bytes) {
+            return Some(Self {
+                encoding,
+                with_bom: false,
+            });
+        }
+        if std::str::from_utf8(bytes).is_ok() {
+            return Some(Self::UTF_8);
+        }
+        if bytes.contains(&0) {
+            return None;
+        }
+
+        let decodes_to = |encoding: &'static encoding_rs::Encoding, predicate: fn(&str) -> bool| {
+            encoding
+                .decode_without_bom_handling_and_without_replacement(bytes)
+                .is_some_and(|text| predicate(&text))
+        };
+        let encoding = if decodes_to(encoding_rs::SHIFT_JIS, is_japanese) {
+            encoding_rs::SHIFT_JIS
+        } else if decodes_to(encoding_rs::GBK, is_chinese) {
+            encoding_rs::GBK
+        } else {
+            encoding_rs::WINDOWS_1252
+        };
+        Some(Self {
+            encoding,
+            with_bom: false,
+        })
+    }
+
+    /// Decodes the bytes, dropping the byte order mark and replacing the malformed sequences.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        self.encoding.decode_with_bom_removal(bytes).0.into_owned()
+    }
+
+    /// Encodes the text, failing when some of its characters can't be represented in this encoding.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(text.len() + 3);
+        if self.encoding == encoding_rs::UTF_16LE || self.encoding == encoding_rs::UTF_16BE {
+            // `encoding_rs` only encodes into the encodings that are ASCII-compatible.
+            let big_endian = self.encoding == encoding_rs::UTF_16BE;
+            if self.with_bom {
+                bytes.extend(encode_utf16_unit(0xFEFF, big_endian));
+            }
+            for unit in text.encode_utf16() {
+                bytes.extend(encode_utf16_unit(unit, big_endian));
+            }
+            return Ok(bytes);
+        }
+
+        if self.with_bom {
+            bytes.extend_from_slice(b"\xEF\xBB\xBF");
+        }
+        let (encoded, _, had_unmappable_characters) = self.encoding.encode(text);
+        if had_unmappable_characters {
+            let character = text
+                .chars()
+                .find(|c| {
+                    let mut buffer = [0; 4];
+                    self.encoding.encode(c.encode_utf8(&mut buffer)).2
+                })
+                .unwrap_or(char::REPLACEMENT_CHARACTER);
+            return Err(anyhow!(
+                "{character:?} can't be represented in {}",
+                self.name()
+            ));
+        }
+        bytes.extend_from_slice(&encoded);
+        Ok(bytes)
+    }
+}
+
+fn is_unicode(encoding: &'static encoding_rs::Encoding) -> bool {
+    encoding == encoding_rs::UTF_8
+        || encoding == encoding_rs::UTF_16LE
+        || encoding == encoding_rs::UTF_16BE
+}
+
+fn encode_utf16_unit(unit: u16, big_endian: bool) -> [u8; 2] {
+    if big_endian {
+        unit.to_be_bytes()
+    } else {
+        unit.to_le_bytes()
+    }
+}
+
+/// Recognizes UTF-16 without a byte order mark from the zero bytes of the characters below U+0100, which are on
+/// the odd offsets in little endian and on the even ones in big endian.
+fn detect_utf16(bytes: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    let bytes = &bytes[..bytes.len().min(UTF_16_DETECTION_BYTES)];
+    let pairs = bytes.len() / 2;
+    if pairs == 0 {
+        return None;
+    }
+    let (mut even_zeros, mut odd_zeros) = (0, 0);
+    for pair in bytes.chunks_exact(2) {
+        even_zeros += (pair[0] == 0) as usize;
+        odd_zeros += (pair[1] == 0) as usize;
+    }
+    let mostly_zeros = |zeros: usize| zeros * 10 >= pairs * 4;
+    let hardly_zeros = |zeros: usize| zeros * 20 <= pairs;
+    if mostly_zeros(odd_zeros) && hardly_zeros(even_zeros) {
+        Some(encoding_rs::UTF_16LE)
+    } else if mostly_zeros(even_zeros) && hardly_zeros(odd_zeros) {
+        Some(encoding_rs::UTF_16BE)
+    } else {
+        None
+    }
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}')
+}
+
+fn is_east_asian(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3000}'..='\u{303F}' // CJK symbols and punctuation
+            | '\u{3040}'..='\u{30FF}' // Hiragana and Katakana
+            | '\u{3400}'..='\u{4DBF}' // CJK Unified Ideographs Extension A
+            | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+            | '\u{FF00}'..='\u{FFEF}' // Halfwidth and Fullwidth Forms
+    )
+}
+
+/// Japanese text is told apart from the text in other encodings by its hiragana and katakana.
+fn is_japanese(text: &str) -> bool {
+    text.chars().any(is_kana) && text.chars().all(|c| c.is_ascii() || is_east_asian(c))
+}
+
+/// Chinese text is told apart from Latin text by its characters being encoded with two bytes above 0xA0, as they are
+/// in GB2312, while the accented letters of Latin text are mostly followed by ASCII.
+fn is_chinese(text: &str) -> bool {
+    text.chars().all(|c| {
+        let mut buffer = [0; 4];
+        c.is_ascii()
+            || (is_east_asian(c)
+                && encoding_rs::GBK
+                    .encode(c.encode_utf8(&mut buffer))
+                    .0
+                    .iter()
+                    .all(|byte| *byte > 0xA0))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Encoding::detect(b"plain"), Some(Encoding::UTF_8));
+        assert_eq!(
+            Encoding::detect("h\u{e9}llo".as_bytes()),
+            Some(Encoding::UTF_8)
+        );
+        assert_eq!(Encoding::detect(b""), Some(Encoding::UTF_8));
+        assert_eq!(
+            Encoding::detect(b"\xEF\xBB\xBFbom"),
+            Encoding::for_name("utf-8", true)
+        );
+        assert_eq!(
+            Encoding::detect(b"\xFF\xFEa\x00"),
+            Encoding::for_name("utf-16le", true)
+        );
+        assert_eq!(
+            Encoding::detect(b"a\x00b\x00c\x00\n\x00"),
+            Encoding::for_name("utf-16le", false)
+        );
+        assert_eq!(
+            Encoding::detect(b"\x00a\x00b\x00c\x00\n"),
+            Encoding::for_name("utf-16be", false)
+        );
+        assert_eq!(
+            Encoding::detect(b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR"),
+            None
+        );
+
+        let (shift_jis, _, _) = encoding_rs::SHIFT_JIS.encode("これは日本語です。");
+        assert_eq!(
+            Encoding::detect(&shift_jis),
+            Encoding::for_name("sjis", false)
+        );
+        let (gbk, _, _) = encoding_rs::GBK.encode("这是中文文本。");
+        assert_eq!(Encoding::detect(&gbk), Encoding::for_name("gbk", false));
+        assert_eq!(
+            Encoding::detect(b"caf\xE9 cr\xE8me"),
+            Encoding::for_name("latin1", false)
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "h\u{e9}llo\n";
+        for encoding in [
+            Encoding::UTF_8,
+            Encoding::for_name("utf-8", true).unwrap(),
+            Encoding::for_name("utf-16le", true).unwrap(),
+            Encoding::for_name("utf-16be", false).unwrap(),
+            Encoding::for_name("latin1", false).unwrap(),
+        ] {
+            let bytes = encoding.encode(text).unwrap();
+            assert_eq!(Encoding::detect(&bytes), Some(encoding), "{encoding}");
+            assert_eq!(encoding.decode(&bytes), text, "{encoding}");
+        }
+
+        let shift_jis = Encoding::for_name("shift_jis", false).unwrap();
+        assert_eq!(shift_jis.decode(&shift_jis.encode("日本").unwrap()), "日本");
+        assert!(shift_jis.encode("emoji 😀").is_err());
+        // Only the Unicode encodings have a byte order mark.
+        assert!(!Encoding::for_name("gbk", true).unwrap().has_bom());
+        assert!(Encoding::all().any(|encoding| encoding == shift_jis));
+    }
+}
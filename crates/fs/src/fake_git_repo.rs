@@ -36,6 +36,8 @@ pub struct FakeGitRepositoryState {
     pub branches: HashSet<String>,
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
+    /// Work directory snapshots taken by `checkpoint`, keyed by their fake commit sha.
+    pub checkpoints: HashMap<String, Vec<(PathBuf, Vec<u8>)>>,
 }
 
 impl FakeGitRepositoryState {
@@ -50,6 +52,7 @@ impl FakeGitRepositoryState {
             branches: Default::default(),
             simulated_index_write_error_message: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
+            checkpoints: Default::default(),
         }
     }
 }
@@ -69,6 +72,19 @@ impl FakeGitRepository {
         }
         .boxed()
     }
+
+    fn work_directory_files(&self) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+        let work_directory = self
+            .dot_git_path
+            .parent()
+            .context("failed to read git work directory")?;
+        Ok(self
+            .fs
+            .files_with_contents(work_directory)
+            .into_iter()
+            .filter(|(path, _)| !path.starts_with(&self.dot_git_path))
+            .collect())
+    }
 }
 
 impl GitRepository for FakeGitRepository {
@@ -462,26 +478,59 @@ impl GitRepository for FakeGitRepository {
     }
 
     fn diff(&self, _diff: git::repository::DiffType) -> BoxFuture<'_, Result<String>> {
-        unimplemented!()
+        future::ready(Err(anyhow::anyhow!(
+            "diffs aren't simulated by the fake repository"
+        )))
+        .boxed()
     }
 
     fn checkpoint(&self) -> BoxFuture<'static, Result<GitRepositoryCheckpoint>> {
-        unimplemented!()
+        let files = self.work_directory_files();
+        self.with_state_async(false, move |state| {
+            let files = files?;
+            let sha = format!("{:040x}", state.checkpoints.len() + 1);
+            state.checkpoints.insert(sha.clone(), files);
+            Ok(GitRepositoryCheckpoint {
+                commit_sha: sha.parse()?,
+            })
+        })
     }
 
-    fn restore_checkpoint(
-        &self,
-        _checkpoint: GitRepositoryCheckpoint,
-    ) -> BoxFuture<'_, Result<()>> {
-        unimplemented!()
+    fn restore_checkpoint(&self, checkpoint: GitRepositoryCheckpoint) -> BoxFuture<'_, Result<()>> {
+        async move {
+            let files = self
+                .with_state_async(false, move |state| {
+                    state
+                        .checkpoints
+                        .get(&checkpoint.commit_sha.to_string())
+                        .cloned()
+                        .context("checkpoint not found")
+                })
+                .await?;
+            for (path, content) in files {
+                self.fs.insert_file(path, content).await;
+            }
+            Ok(())
+        }
+        .boxed()
     }
 
     fn compare_checkpoints(
         &self,
-        _left: GitRepositoryCheckpoint,
-        _right: GitRepositoryCheckpoint,
+        left: GitRepositoryCheckpoint,
+        right: GitRepositoryCheckpoint,
     ) -> BoxFuture<'_, Result<bool>> {
-        unimplemented!()
+        self.with_state_async(false, move |state| {
+            let left = state
+                .checkpoints
+                .get(&left.commit_sha.to_string())
+                .context("checkpoint not found")?;
+            let right = state
+                .checkpoints
+                .get(&right.commit_sha.to_string())
+                .context("checkpoint not found")?;
+            Ok(left == right)
+        })
     }
 
     fn diff_checkpoints(
@@ -492,6 +541,27 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn anchor_checkpoint(
+        &self,
+        name: String,
+        checkpoint: GitRepositoryCheckpoint,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            state.refs.insert(name, checkpoint.commit_sha.to_string());
+            Ok(())
+        })
+    }
+
+    fn delete_refs(&self, prefix: String) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let prefix = prefix.trim_end_matches('/');
+            state
+                .refs
+                .retain(|name, _| name != prefix && !name.starts_with(&format!("{prefix}/")));
+            Ok(())
+        })
+    }
+
     fn default_branch(&self) -> BoxFuture<'_, Result<Option<SharedString>>> {
         unimplemented!()
     }
@@ -1,3 +1,4 @@
+mod encoding;
 #[cfg(target_os = "macos")]
 mod mac_watcher;
 
@@ -14,6 +15,8 @@ use gpui::ReadGlobal as _;
 use std::borrow::Cow;
 use util::command::new_std_command;
 
+pub use encoding::Encoding;
+
 #[cfg(unix)]
 use std::os::fd::{AsFd, AsRawFd};
 
@@ -109,8 +112,37 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Loads the file as text, decoding it with the given encoding, or with the one detected from its contents.
+    async fn load_with_encoding(
+        &self,
+        path: &Path,
+        encoding: Option<Encoding>,
+    ) -> Result<(String, Encoding)> {
+        let bytes = self.load_bytes(path).await?;
+        let encoding = match encoding {
+            Some(encoding) => encoding,
+            None => {
+                Encoding::detect(&bytes).with_context(|| format!("{path:?} is not a text file"))?
+            }
+        };
+        Ok((encoding.decode(&bytes), encoding))
+    }
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
+    /// Saves the text like [`Fs::save`] does, encoding it with the given encoding.
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
+        if encoding == Encoding::UTF_8 {
+            return self.save(path, text, line_ending).await;
+        }
+        let text = chunks(text, line_ending).collect::<String>();
+        self.write(path, &encoding.encode(&text)?).await
+    }
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
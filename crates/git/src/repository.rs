@@ -10,7 +10,7 @@ use gpui::{AppContext as _, AsyncApp, BackgroundExecutor, SharedString};
 use parking_lot::Mutex;
 use rope::Rope;
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::borrow::{Borrow, Cow};
 use std::ffi::{OsStr, OsString};
 use std::io::prelude::*;
@@ -464,6 +464,16 @@ pub trait GitRepository: Send + Sync {
         target_checkpoint: GitRepositoryCheckpoint,
     ) -> BoxFuture<'_, Result<String>>;
 
+    /// Points the ref `name` at a checkpoint, so that `git gc` keeps its commit.
+    fn anchor_checkpoint(
+        &self,
+        name: String,
+        checkpoint: GitRepositoryCheckpoint,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Deletes every ref under `prefix`, e.g. `refs/zed/checkpoints/<thread>`.
+    fn delete_refs(&self, prefix: String) -> BoxFuture<'_, Result<()>>;
+
     fn default_branch(&self) -> BoxFuture<'_, Result<Option<SharedString>>>;
 }
 
@@ -514,7 +524,7 @@ impl RealGitRepository {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GitRepositoryCheckpoint {
     pub commit_sha: Oid,
 }
@@ -1610,6 +1620,46 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn anchor_checkpoint(
+        &self,
+        name: String,
+        checkpoint: GitRepositoryCheckpoint,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git = GitBinary::new(git_binary_path, working_directory, executor);
+                git.run(&["update-ref", &name, &checkpoint.commit_sha.to_string()])
+                    .await?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn delete_refs(&self, prefix: String) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git = GitBinary::new(git_binary_path, working_directory, executor);
+                let refs = git
+                    .run(&["for-each-ref", "--format=%(refname)", &prefix])
+                    .await?;
+                for name in refs.lines().filter(|name| !name.is_empty()) {
+                    git.run(&["update-ref", "-d", name]).await?;
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
     fn default_branch(&self) -> BoxFuture<'_, Result<Option<SharedString>>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.git_binary_path.clone();
@@ -1947,6 +1947,7 @@ impl GitPanel {
                     tool_choice: None,
                     stop: Vec::new(),
                     temperature,
+                    max_tokens: None,
                     thinking_allowed: false,
                 };
 
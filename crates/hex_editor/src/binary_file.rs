@@ -0,0 +1,166 @@
+use std::{ops::Range, path::PathBuf, sync::Arc};
+
+use anyhow::{Context as _, Result, anyhow};
+use fs::Fs;
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Task};
+use project::{Project, ProjectEntryId, ProjectItem, ProjectPath};
+
+use crate::hex_buffer::HexBuffer;
+
+pub enum BinaryFileEvent {
+    Edited(Range<usize>),
+    DirtyChanged,
+    Saved,
+    Reloaded,
+}
+
+/// A file of a local project loaded as raw bytes, written back as they are when saved.
+pub struct BinaryFile {
+    project_path: ProjectPath,
+    abs_path: PathBuf,
+    entry_id: Option<ProjectEntryId>,
+    fs: Arc<dyn Fs>,
+    buffer: HexBuffer,
+}
+
+impl EventEmitter<BinaryFileEvent> for BinaryFile {}
+
+impl BinaryFile {
+    /// Loads the file, failing when `require_binary` is set and the file is valid UTF-8 text.
+    pub fn open(
+        project: &Entity<Project>,
+        project_path: ProjectPath,
+        require_binary: bool,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let project = project.read(cx);
+        if !project.is_local() {
+            return Task::ready(Err(anyhow!(
+                "binary files can only be opened in local projects"
+            )));
+        }
+        let Some(abs_path) = project.absolute_path(&project_path, cx) else {
+            return Task::ready(Err(anyhow!("no worktree for {:?}", project_path.path)));
+        };
+        let entry_id = project
+            .entry_for_path(&project_path, cx)
+            .map(|entry| entry.id);
+        let fs = project.fs().clone();
+
+        cx.spawn(async move |cx| {
+            let bytes = fs
+                .load_bytes(&abs_path)
+                .await
+                .with_context(|| format!("loading {abs_path:?}"))?;
+            if require_binary && std::str::from_utf8(&bytes).is_ok() {
+                return Err(anyhow!("{abs_path:?} is a text file"));
+            }
+            cx.new(|_| Self {
+                project_path,
+                abs_path,
+                entry_id,
+                fs,
+                buffer: HexBuffer::new(bytes),
+            })
+        })
+    }
+
+    pub fn buffer(&self) -> &HexBuffer {
+        &self.buffer
+    }
+
+    pub fn abs_path(&self) -> &PathBuf {
+        &self.abs_path
+    }
+
+    pub fn overwrite(&mut self, offset: usize, bytes: &[u8], cx: &mut Context<Self>) {
+        let was_dirty = self.buffer.is_dirty();
+        let range = self.buffer.overwrite(offset, bytes);
+        if !range.is_empty() {
+            self.did_edit(range, was_dirty, cx);
+        }
+    }
+
+    pub fn undo(&mut self, cx: &mut Context<Self>) -> Option<Range<usize>> {
+        let was_dirty = self.buffer.is_dirty();
+        let range = self.buffer.undo()?;
+        self.did_edit(range.clone(), was_dirty, cx);
+        Some(range)
+    }
+
+    pub fn redo(&mut self, cx: &mut Context<Self>) -> Option<Range<usize>> {
+        let was_dirty = self.buffer.is_dirty();
+        let range = self.buffer.redo()?;
+        self.did_edit(range.clone(), was_dirty, cx);
+        Some(range)
+    }
+
+    fn did_edit(&mut self, range: Range<usize>, was_dirty: bool, cx: &mut Context<Self>) {
+        cx.emit(BinaryFileEvent::Edited(range));
+        if self.buffer.is_dirty() != was_dirty {
+            cx.emit(BinaryFileEvent::DirtyChanged);
+        }
+        cx.notify();
+    }
+
+    pub fn save(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let bytes = self.buffer.bytes().to_vec();
+        cx.spawn(async move |this, cx| {
+            fs.write(&abs_path, &bytes)
+                .await
+                .with_context(|| format!("writing {abs_path:?}"))?;
+            this.update(cx, |this, cx| {
+                // The bytes may have been edited while they were written.
+                if this.buffer.bytes() == bytes.as_slice() {
+                    this.buffer.did_save();
+                }
+                cx.emit(BinaryFileEvent::Saved);
+                cx.emit(BinaryFileEvent::DirtyChanged);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Loads the bytes from the disk again, discarding the edits.
+    pub fn reload(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            let bytes = fs
+                .load_bytes(&abs_path)
+                .await
+                .with_context(|| format!("loading {abs_path:?}"))?;
+            this.update(cx, |this, cx| {
+                this.buffer.reset(bytes);
+                cx.emit(BinaryFileEvent::Reloaded);
+                cx.emit(BinaryFileEvent::DirtyChanged);
+                cx.notify();
+            })
+        })
+    }
+}
+
+impl ProjectItem for BinaryFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        // Only the files that can't be opened as text buffers are opened as binary files.
+        Some(Self::open(project, path.clone(), true, cx))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.buffer.is_dirty()
+    }
+}
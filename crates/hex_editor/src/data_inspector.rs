@@ -0,0 +1,112 @@
+//! Decoding of the bytes at the cursor as numbers, for the data inspector.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endianness {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Endianness::Little => "Little Endian",
+            Endianness::Big => "Big Endian",
+        }
+    }
+
+    pub fn toggle(&self) -> Self {
+        match self {
+            Endianness::Little => Endianness::Big,
+            Endianness::Big => Endianness::Little,
+        }
+    }
+}
+
+/// The bytes at the start of the slice decoded as each of the types, None for the types that need more bytes
+/// than there are.
+pub fn inspect(bytes: &[u8], endianness: Endianness) -> Vec<(&'static str, Option<String>)> {
+    macro_rules! decode {
+        ($ty:ty) => {
+            bytes
+                .get(..std::mem::size_of::<$ty>())
+                .map(|bytes| {
+                    let bytes = bytes.try_into().unwrap();
+                    match endianness {
+                        Endianness::Little => <$ty>::from_le_bytes(bytes),
+                        Endianness::Big => <$ty>::from_be_bytes(bytes),
+                    }
+                })
+                .map(|value| value.to_string())
+        };
+    }
+
+    vec![
+        ("binary", bytes.first().map(|byte| format!("{byte:08b}"))),
+        ("u8", decode!(u8)),
+        ("i8", decode!(i8)),
+        ("u16", decode!(u16)),
+        ("i16", decode!(i16)),
+        ("u32", decode!(u32)),
+        ("i32", decode!(i32)),
+        ("u64", decode!(u64)),
+        ("i64", decode!(i64)),
+        ("f32", decode!(f32)),
+        ("f64", decode!(f64)),
+        ("UTF-8", decode_char(bytes)),
+    ]
+}
+
+fn decode_char(bytes: &[u8]) -> Option<String> {
+    let len = match bytes.first()? {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => return None,
+    };
+    let c = std::str::from_utf8(bytes.get(..len)?)
+        .ok()?
+        .chars()
+        .next()?;
+    Some(format!("{c:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(values: &[(&'static str, Option<String>)], label: &str) -> Option<String> {
+        values
+            .iter()
+            .find(|(value_label, _)| *value_label == label)
+            .and_then(|(_, value)| value.clone())
+    }
+
+    #[test]
+    fn test_inspect() {
+        let bytes = [0xFE, 0xFF, 0x00, 0x00];
+        let little = inspect(&bytes, Endianness::Little);
+        assert_eq!(value(&little, "binary").as_deref(), Some("11111110"));
+        assert_eq!(value(&little, "i8").as_deref(), Some("-2"));
+        assert_eq!(value(&little, "u16").as_deref(), Some("65534"));
+        assert_eq!(value(&little, "i32").as_deref(), Some("65534"));
+        assert_eq!(value(&little, "u64"), None);
+
+        let big = inspect(&bytes, Endianness::Big);
+        assert_eq!(value(&big, "u16").as_deref(), Some("65279"));
+        assert_eq!(value(&big, "u32").as_deref(), Some("4278124544"));
+
+        let float = inspect(&1.5f32.to_le_bytes(), Endianness::Little);
+        assert_eq!(value(&float, "f32").as_deref(), Some("1.5"));
+        assert_eq!(
+            value(&inspect("é".as_bytes(), Endianness::Little), "UTF-8").as_deref(),
+            Some("'é'")
+        );
+        assert!(
+            inspect(&[], Endianness::Big)
+                .iter()
+                .all(|(_, value)| value.is_none())
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! The bytes of a binary file, edited in place with an undo history.
+
+use std::ops::Range;
+
+use anyhow::{Context as _, Result, anyhow};
+
+struct ByteEdit {
+    offset: usize,
+    old: Vec<u8>,
+    new: Vec<u8>,
+}
+
+impl ByteEdit {
+    fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.new.len()
+    }
+}
+
+pub struct HexBuffer {
+    bytes: Vec<u8>,
+    undo_stack: Vec<ByteEdit>,
+    redo_stack: Vec<ByteEdit>,
+    /// The depth of the undo stack when the bytes were last saved, None when that state can't be reached anymore.
+    saved_depth: Option<usize>,
+}
+
+impl HexBuffer {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            saved_depth: Some(0),
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.saved_depth != Some(self.undo_stack.len())
+    }
+
+    pub fn did_save(&mut self) {
+        self.saved_depth = Some(self.undo_stack.len());
+    }
+
+    /// Replaces the bytes at the offset, without changing the length of the buffer. Returns the overwritten range.
+    pub fn overwrite(&mut self, offset: usize, new: &[u8]) -> Range<usize> {
+        let start = offset.min(self.bytes.len());
+        let end = (start + new.len()).min(self.bytes.len());
+        let new = &new[..end - start];
+        if self.bytes[start..end] == *new {
+            return start..start;
+        }
+
+        if self
+            .saved_depth
+            .is_some_and(|depth| depth > self.undo_stack.len())
+        {
+            self.saved_depth = None;
+        }
+        self.redo_stack.clear();
+        self.undo_stack.push(ByteEdit {
+            offset: start,
+            old: self.bytes[start..end].to_vec(),
+            new: new.to_vec(),
+        });
+        self.bytes[start..end].copy_from_slice(new);
+        start..end
+    }
+
+    /// Reverts the last edit, returning the range it changed.
+    pub fn undo(&mut self) -> Option<Range<usize>> {
+        let edit = self.undo_stack.pop()?;
+        let range = edit.range();
+        self.bytes[range.clone()].copy_from_slice(&edit.old);
+        self.redo_stack.push(edit);
+        Some(range)
+    }
+
+    /// Reapplies the last undone edit, returning the range it changed.
+    pub fn redo(&mut self) -> Option<Range<usize>> {
+        let edit = self.redo_stack.pop()?;
+        let range = edit.range();
+        self.bytes[range.clone()].copy_from_slice(&edit.new);
+        self.undo_stack.push(edit);
+        Some(range)
+    }
+
+    /// Replaces all the bytes, such as when the file is reloaded, forgetting the undo history.
+    pub fn reset(&mut self, bytes: Vec<u8>) {
+        *self = Self::new(bytes);
+    }
+
+    /// Finds the next occurrence of the pattern at or after the offset, wrapping around at the end.
+    pub fn find_next(&self, pattern: &[u8], from: usize) -> Option<usize> {
+        let from = from.min(self.bytes.len());
+        find(&self.bytes[from..], pattern)
+            .map(|ix| from + ix)
+            .or_else(|| find(&self.bytes, pattern).filter(|ix| *ix < from))
+    }
+
+    /// Finds the last occurrence of the pattern starting before the offset, wrapping around at the start.
+    pub fn find_previous(&self, pattern: &[u8], before: usize) -> Option<usize> {
+        let before = before.min(self.bytes.len());
+        let end = (before + pattern.len())
+            .saturating_sub(1)
+            .min(self.bytes.len());
+        rfind(&self.bytes[..end], pattern).or_else(|| rfind(&self.bytes, pattern))
+    }
+}
+
+fn find(haystack: &[u8], pattern: &[u8]) -> Option<usize> {
+    if pattern.is_empty() {
+        return None;
+    }
+    haystack
+        .windows(pattern.len())
+        .position(|window| window == pattern)
+}
+
+fn rfind(haystack: &[u8], pattern: &[u8]) -> Option<usize> {
+    if pattern.is_empty() {
+        return None;
+    }
+    haystack
+        .windows(pattern.len())
+        .rposition(|window| window == pattern)
+}
+
+/// Parses a search pattern: hex digits like `DE AD be ef`, or text between double quotes like `"PNG"`.
+pub fn parse_byte_pattern(query: &str) -> Result<Vec<u8>> {
+    let query = query.trim();
+    if let Some(text) = query
+        .strip_prefix('"')
+        .and_then(|query| query.strip_suffix('"'))
+    {
+        return Ok(text.as_bytes().to_vec());
+    }
+
+    let digits = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    if digits.len() % 2 != 0 {
+        return Err(anyhow!("hex patterns need two digits per byte"));
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let pair = pair.iter().collect::<String>();
+            u8::from_str_radix(&pair, 16).with_context(|| format!("{pair:?} is not a hex byte"))
+        })
+        .collect()
+}
+
+/// Parses an offset, hexadecimal when prefixed with `0x` and decimal otherwise.
+pub fn parse_offset(query: &str) -> Result<usize> {
+    let query = query.trim().replace('_', "");
+    match query
+        .strip_prefix("0x")
+        .or_else(|| query.strip_prefix("0X"))
+    {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => query.parse(),
+    }
+    .with_context(|| format!("{query:?} is not an offset"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overwrite_and_undo() {
+        let mut buffer = HexBuffer::new(vec![0, 1, 2, 3]);
+        assert!(!buffer.is_dirty());
+
+        assert_eq!(buffer.overwrite(1, &[0xAA, 0xBB]), 1..3);
+        assert_eq!(buffer.bytes(), [0, 0xAA, 0xBB, 3]);
+        assert!(buffer.is_dirty());
+        // The writes past the end are dropped, the length never changes.
+        assert_eq!(buffer.overwrite(3, &[0xCC, 0xDD]), 3..4);
+        assert_eq!(buffer.bytes(), [0, 0xAA, 0xBB, 0xCC]);
+        assert_eq!(buffer.overwrite(0, &[0]), 0..0);
+
+        assert_eq!(buffer.undo(), Some(3..4));
+        assert_eq!(buffer.undo(), Some(1..3));
+        assert_eq!(buffer.bytes(), [0, 1, 2, 3]);
+        assert!(!buffer.is_dirty());
+        assert_eq!(buffer.undo(), None);
+
+        assert_eq!(buffer.redo(), Some(1..3));
+        buffer.did_save();
+        assert!(!buffer.is_dirty());
+        buffer.undo();
+        assert!(buffer.is_dirty());
+        // Editing after the undo makes the saved state unreachable.
+        buffer.overwrite(0, &[9]);
+        assert_eq!(buffer.redo(), None);
+        buffer.undo();
+        assert!(buffer.is_dirty());
+    }
+
+    #[test]
+    fn test_find() {
+        let buffer = HexBuffer::new(b"abcabcab".to_vec());
+        assert_eq!(buffer.find_next(b"bc", 0), Some(1));
+        assert_eq!(buffer.find_next(b"bc", 2), Some(4));
+        assert_eq!(buffer.find_next(b"bc", 5), Some(1));
+        assert_eq!(buffer.find_next(b"x", 0), None);
+        assert_eq!(buffer.find_previous(b"bc", 4), Some(1));
+        assert_eq!(buffer.find_previous(b"bc", 5), Some(4));
+        assert_eq!(buffer.find_previous(b"bc", 1), Some(4));
+        assert_eq!(buffer.find_next(b"", 0), None);
+    }
+
+    #[test]
+    fn test_parse_queries() {
+        assert_eq!(
+            parse_byte_pattern("DE AD be ef").unwrap(),
+            [0xDE, 0xAD, 0xBE, 0xEF]
+        );
+        assert_eq!(parse_byte_pattern("\"PNG\"").unwrap(), b"PNG");
+        assert!(parse_byte_pattern("ABC").is_err());
+        assert!(parse_byte_pattern("zz").is_err());
+
+        assert_eq!(parse_offset("0x1F").unwrap(), 31);
+        assert_eq!(parse_offset(" 1_024 ").unwrap(), 1024);
+        assert!(parse_offset("0xZZ").is_err());
+    }
+}
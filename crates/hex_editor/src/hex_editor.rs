@@ -0,0 +1,57 @@
+use gpui::{App, actions};
+use workspace::Workspace;
+
+mod binary_file;
+mod data_inspector;
+mod hex_buffer;
+pub mod hex_editor_view;
+
+pub use binary_file::{BinaryFile, BinaryFileEvent};
+pub use data_inspector::Endianness;
+
+actions!(
+    hex_editor,
+    [
+        /// Opens the file of the active item in the hex editor, even when it is a text file.
+        OpenInHexEditor,
+        /// Moves the cursor to the previous byte.
+        MoveLeft,
+        /// Moves the cursor to the next byte.
+        MoveRight,
+        /// Moves the cursor to the same byte of the previous row.
+        MoveUp,
+        /// Moves the cursor to the same byte of the next row.
+        MoveDown,
+        /// Moves the cursor up by a page of rows.
+        MovePageUp,
+        /// Moves the cursor down by a page of rows.
+        MovePageDown,
+        /// Moves the cursor to the first byte of the file.
+        MoveToBeginning,
+        /// Moves the cursor to the last byte of the file.
+        MoveToEnd,
+        /// Switches the typing between the hex and the ASCII columns.
+        SwitchColumn,
+        /// Focuses the input for going to an offset.
+        FocusGoToOffset,
+        /// Focuses the input for searching byte patterns.
+        FocusSearch,
+        /// Selects the next match of the byte pattern.
+        FindNext,
+        /// Selects the previous match of the byte pattern.
+        FindPrevious,
+        /// Switches the data inspector between little and big endian.
+        ToggleEndianness
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    workspace::register_fallback_project_item::<hex_editor_view::HexEditor>(cx);
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        crate::hex_editor_view::HexEditor::register(workspace, window, cx);
+    })
+    .detach();
+}
@@ -0,0 +1,707 @@
+use std::ops::Range;
+
+use editor::Editor;
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, IntoElement,
+    KeyDownEvent, ParentElement, Render, ScrollStrategy, Styled, Subscription, Task,
+    UniformListScrollHandle, Window, uniform_list,
+};
+use menu::{Cancel, Confirm};
+use project::Project;
+use ui::{Tooltip, prelude::*};
+use workspace::{
+    Pane, Workspace,
+    item::{Item, ItemEvent, ProjectItem, SaveOptions},
+    notifications::DetachAndPromptErr,
+};
+
+use crate::{
+    FindNext, FindPrevious, FocusGoToOffset, FocusSearch, MoveDown, MoveLeft, MovePageDown,
+    MovePageUp, MoveRight, MoveToBeginning, MoveToEnd, MoveUp, OpenInHexEditor, SwitchColumn,
+    ToggleEndianness,
+    binary_file::{BinaryFile, BinaryFileEvent},
+    data_inspector::{self, Endianness},
+    hex_buffer,
+};
+
+const BYTES_PER_ROW: usize = 16;
+const ROWS_PER_PAGE: usize = 32;
+/// How many bytes the data inspector decodes at most.
+const INSPECTED_BYTES: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EditColumn {
+    Hex,
+    Ascii,
+}
+
+/// Shows the bytes of a [`BinaryFile`] as offset, hex and ASCII columns, and overwrites them as they are typed.
+pub struct HexEditor {
+    file: Entity<BinaryFile>,
+    focus_handle: FocusHandle,
+    cursor: usize,
+    column: EditColumn,
+    /// Whether the high nibble of the byte at the cursor was typed, and the next hex digit goes to the low one.
+    typed_high_nibble: bool,
+    goto_editor: Entity<Editor>,
+    search_editor: Entity<Editor>,
+    search_match: Option<Range<usize>>,
+    error: Option<SharedString>,
+    endianness: Endianness,
+    scroll_handle: UniformListScrollHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+pub enum HexEditorEvent {
+    TitleChanged,
+}
+
+impl HexEditor {
+    pub fn register(workspace: &mut Workspace, _window: &mut Window, _cx: &mut Context<Workspace>) {
+        workspace.register_action(|workspace, _: &OpenInHexEditor, window, cx| {
+            let Some(project_path) = workspace
+                .active_item(cx)
+                .and_then(|item| item.project_path(cx))
+            else {
+                return;
+            };
+            let project = workspace.project().clone();
+            let open = BinaryFile::open(&project, project_path, false, cx);
+            cx.spawn_in(window, async move |workspace, cx| {
+                let file = open.await?;
+                workspace.update_in(cx, |workspace, window, cx| {
+                    let hex_editor = cx.new(|cx| HexEditor::new(file, window, cx));
+                    workspace.add_item_to_active_pane(Box::new(hex_editor), None, true, window, cx);
+                })
+            })
+            .detach_and_prompt_err(
+                "Failed to open the file in the hex editor",
+                window,
+                cx,
+                |_, _, _| None,
+            );
+        });
+    }
+
+    pub fn new(file: Entity<BinaryFile>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let goto_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Go to offset, like 0x1F0", cx);
+            editor
+        });
+        let search_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Find bytes, like DE AD or \"PNG\"", cx);
+            editor
+        });
+        let subscriptions = vec![cx.subscribe(&file, |this, _, event, cx| match event {
+            BinaryFileEvent::Edited(_) => cx.notify(),
+            BinaryFileEvent::DirtyChanged | BinaryFileEvent::Saved => {
+                cx.emit(HexEditorEvent::TitleChanged)
+            }
+            BinaryFileEvent::Reloaded => {
+                this.search_match = None;
+                this.set_cursor(this.cursor, cx);
+            }
+        })];
+
+        Self {
+            file,
+            focus_handle: cx.focus_handle(),
+            cursor: 0,
+            column: EditColumn::Hex,
+            typed_high_nibble: false,
+            goto_editor,
+            search_editor,
+            search_match: None,
+            error: None,
+            endianness: Endianness::default(),
+            scroll_handle: UniformListScrollHandle::new(),
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn len(&self, cx: &App) -> usize {
+        self.file.read(cx).buffer().len()
+    }
+
+    fn set_cursor(&mut self, offset: usize, cx: &mut Context<Self>) {
+        self.cursor = offset.min(self.len(cx).saturating_sub(1));
+        self.typed_high_nibble = false;
+        self.scroll_handle
+            .scroll_to_item(self.cursor / BYTES_PER_ROW, ScrollStrategy::Top);
+        cx.notify();
+    }
+
+    fn move_by(&mut self, delta: isize, cx: &mut Context<Self>) {
+        let offset = self.cursor.saturating_add_signed(delta);
+        self.set_cursor(offset, cx);
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-1, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(1, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        if self.cursor >= BYTES_PER_ROW {
+            self.move_by(-(BYTES_PER_ROW as isize), cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        if self.cursor + BYTES_PER_ROW < self.len(cx) {
+            self.move_by(BYTES_PER_ROW as isize, cx);
+        }
+    }
+
+    fn move_page_up(&mut self, _: &MovePageUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-((BYTES_PER_ROW * ROWS_PER_PAGE) as isize), cx);
+    }
+
+    fn move_page_down(&mut self, _: &MovePageDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by((BYTES_PER_ROW * ROWS_PER_PAGE) as isize, cx);
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(0, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(usize::MAX, cx);
+    }
+
+    fn switch_column(&mut self, _: &SwitchColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            EditColumn::Hex => EditColumn::Ascii,
+            EditColumn::Ascii => EditColumn::Hex,
+        };
+        self.typed_high_nibble = false;
+        cx.notify();
+    }
+
+    fn toggle_endianness(&mut self, _: &ToggleEndianness, _: &mut Window, cx: &mut Context<Self>) {
+        self.endianness = self.endianness.toggle();
+        cx.notify();
+    }
+
+    fn focus_go_to_offset(
+        &mut self,
+        _: &FocusGoToOffset,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.goto_editor.update(cx, |editor, cx| {
+            editor.select_all(&editor::actions::SelectAll, window, cx)
+        });
+        window.focus(&self.goto_editor.focus_handle(cx));
+    }
+
+    fn focus_search(&mut self, _: &FocusSearch, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_editor.update(cx, |editor, cx| {
+            editor.select_all(&editor::actions::SelectAll, window, cx)
+        });
+        window.focus(&self.search_editor.focus_handle(cx));
+    }
+
+    fn undo(&mut self, _: &editor::actions::Undo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(range) = self.file.update(cx, |file, cx| file.undo(cx)) {
+            self.set_cursor(range.start, cx);
+        }
+    }
+
+    fn redo(&mut self, _: &editor::actions::Redo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(range) = self.file.update(cx, |file, cx| file.redo(cx)) {
+            self.set_cursor(range.start, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.goto_editor.focus_handle(cx).is_focused(window) {
+            self.go_to_offset(window, cx);
+        } else if self.search_editor.focus_handle(cx).is_focused(window) {
+            self.find(true, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        self.error = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn go_to_offset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let query = self.goto_editor.read(cx).text(cx);
+        match hex_buffer::parse_offset(&query) {
+            Ok(offset) if offset < self.len(cx) => {
+                self.error = None;
+                self.set_cursor(offset, cx);
+                window.focus(&self.focus_handle);
+            }
+            Ok(offset) => {
+                self.error = Some(format!("Offset {offset:#x} is past the end of the file").into())
+            }
+            Err(error) => self.error = Some(error.to_string().into()),
+        }
+        cx.notify();
+    }
+
+    fn find_next(&mut self, _: &FindNext, _: &mut Window, cx: &mut Context<Self>) {
+        self.find(true, cx);
+    }
+
+    fn find_previous(&mut self, _: &FindPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        self.find(false, cx);
+    }
+
+    fn find(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let query = self.search_editor.read(cx).text(cx);
+        let pattern = match hex_buffer::parse_byte_pattern(&query) {
+            Ok(pattern) if !pattern.is_empty() => pattern,
+            Ok(_) => return,
+            Err(error) => {
+                self.error = Some(error.to_string().into());
+                cx.notify();
+                return;
+            }
+        };
+
+        let buffer = self.file.read(cx).buffer();
+        let found = if forward {
+            let from = match &self.search_match {
+                Some(search_match) if search_match.start == self.cursor => self.cursor + 1,
+                _ => self.cursor,
+            };
+            buffer.find_next(&pattern, from)
+        } else {
+            buffer.find_previous(&pattern, self.cursor)
+        };
+        match found {
+            Some(offset) => {
+                self.error = None;
+                self.search_match = Some(offset..offset + pattern.len());
+                self.set_cursor(offset, cx);
+            }
+            None => {
+                self.error = Some("No matches".into());
+                self.search_match = None;
+                cx.notify();
+            }
+        }
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control || modifiers.platform || modifiers.alt || modifiers.function {
+            return;
+        }
+        let Some(typed) = event.keystroke.key_char.as_ref() else {
+            return;
+        };
+        let mut chars = typed.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return;
+        };
+        let Some(&byte) = self.file.read(cx).buffer().bytes().get(self.cursor) else {
+            return;
+        };
+
+        let new_byte = match self.column {
+            EditColumn::Hex => {
+                let Some(digit) = c.to_digit(16) else {
+                    return;
+                };
+                if self.typed_high_nibble {
+                    (byte & 0xF0) | digit as u8
+                } else {
+                    (byte & 0x0F) | (digit as u8) << 4
+                }
+            }
+            EditColumn::Ascii => {
+                if !c.is_ascii() || c.is_ascii_control() {
+                    return;
+                }
+                c as u8
+            }
+        };
+        cx.stop_propagation();
+
+        let offset = self.cursor;
+        self.file
+            .update(cx, |file, cx| file.overwrite(offset, &[new_byte], cx));
+        if self.column == EditColumn::Hex && !self.typed_high_nibble {
+            self.typed_high_nibble = true;
+            cx.notify();
+        } else {
+            self.move_by(1, cx);
+        }
+    }
+
+    fn click_byte(
+        &mut self,
+        offset: usize,
+        column: EditColumn,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.column = column;
+        window.focus(&self.focus_handle);
+        self.set_cursor(offset, cx);
+    }
+
+    fn render_rows(&self, range: Range<usize>, cx: &Context<Self>) -> Vec<AnyElement> {
+        let bytes = self.file.read(cx).buffer().bytes();
+        let colors = cx.theme().colors();
+        let focused_bg = colors.element_selected;
+        let unfocused_bg = colors.ghost_element_selected;
+        let match_bg = colors.search_match_background;
+
+        range
+            .map(|row| {
+                let row_start = row * BYTES_PER_ROW;
+                let byte_bg = |offset: usize, column: EditColumn| {
+                    if offset == self.cursor {
+                        Some(if column == self.column {
+                            focused_bg
+                        } else {
+                            unfocused_bg
+                        })
+                    } else if self
+                        .search_match
+                        .as_ref()
+                        .is_some_and(|search_match| search_match.contains(&offset))
+                    {
+                        Some(match_bg)
+                    } else {
+                        None
+                    }
+                };
+
+                let hex_cells = (row_start..row_start + BYTES_PER_ROW).map(|offset| {
+                    let byte = bytes.get(offset).copied();
+                    div()
+                        .id(("hex", offset))
+                        .w(px(22.))
+                        .when(offset % BYTES_PER_ROW == BYTES_PER_ROW / 2, |cell| {
+                            cell.ml_2()
+                        })
+                        .text_center()
+                        .when_some(byte_bg(offset, EditColumn::Hex), |cell, bg| cell.bg(bg))
+                        .when(byte == Some(0), |cell| {
+                            cell.text_color(Color::Muted.color(cx))
+                        })
+                        .children(byte.map(|byte| format!("{byte:02X}")))
+                        .when(byte.is_some(), |cell| {
+                            cell.on_click(cx.listener(move |this, _, window, cx| {
+                                this.click_byte(offset, EditColumn::Hex, window, cx)
+                            }))
+                        })
+                });
+                let ascii_cells = (row_start..row_start + BYTES_PER_ROW).filter_map(|offset| {
+                    let byte = bytes.get(offset).copied()?;
+                    let printable = byte.is_ascii_graphic() || byte == b' ';
+                    Some(
+                        div()
+                            .id(("ascii", offset))
+                            .w(px(9.))
+                            .when_some(byte_bg(offset, EditColumn::Ascii), |cell, bg| cell.bg(bg))
+                            .when(!printable, |cell| cell.text_color(Color::Muted.color(cx)))
+                            .child(if printable {
+                                (byte as char).to_string()
+                            } else {
+                                ".".to_string()
+                            })
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.click_byte(offset, EditColumn::Ascii, window, cx)
+                            })),
+                    )
+                });
+
+                h_flex()
+                    .gap_4()
+                    .font_buffer(cx)
+                    .child(
+                        div()
+                            .w(px(88.))
+                            .text_color(Color::Muted.color(cx))
+                            .child(format!("{row_start:08X}")),
+                    )
+                    .child(h_flex().children(hex_cells))
+                    .child(h_flex().children(ascii_cells))
+                    .into_any_element()
+            })
+            .collect()
+    }
+
+    fn render_inspector(&self, cx: &Context<Self>) -> impl IntoElement {
+        let bytes = self.file.read(cx).buffer().bytes();
+        let inspected = bytes
+            .get(self.cursor..)
+            .map(|bytes| &bytes[..bytes.len().min(INSPECTED_BYTES)])
+            .unwrap_or_default();
+
+        v_flex()
+            .w(px(260.))
+            .p_2()
+            .gap_1()
+            .border_l_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Label::new(format!("Offset {:#X}", self.cursor)).size(LabelSize::Small))
+                    .child(
+                        Button::new("hex-endianness", self.endianness.label())
+                            .style(ButtonStyle::Subtle)
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.toggle_endianness(&ToggleEndianness, window, cx)
+                            })),
+                    ),
+            )
+            .children(
+                data_inspector::inspect(inspected, self.endianness)
+                    .into_iter()
+                    .map(|(label, value)| {
+                        h_flex()
+                            .justify_between()
+                            .gap_2()
+                            .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+                            .child(
+                                Label::new(value.unwrap_or_else(|| "—".to_string()))
+                                    .size(LabelSize::Small)
+                                    .buffer_font(cx)
+                                    .single_line()
+                                    .truncate(),
+                            )
+                    }),
+            )
+    }
+
+    fn render_toolbar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let input = |editor: &Entity<Editor>, icon: IconName| {
+            h_flex()
+                .flex_1()
+                .h_8()
+                .px_2()
+                .gap_2()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .child(Icon::new(icon).color(Color::Muted))
+                .child(editor.clone())
+        };
+
+        h_flex()
+            .gap_2()
+            .p_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(input(&self.goto_editor, IconName::ArrowRight))
+            .child(input(&self.search_editor, IconName::MagnifyingGlass))
+            .child(
+                IconButton::new("hex-find-previous", IconName::ChevronLeft)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|window, cx| {
+                        Tooltip::for_action("Find Previous", &FindPrevious, window, cx)
+                    })
+                    .on_click(cx.listener(|this, _, _, cx| this.find(false, cx))),
+            )
+            .child(
+                IconButton::new("hex-find-next", IconName::ChevronRight)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|window, cx| Tooltip::for_action("Find Next", &FindNext, window, cx))
+                    .on_click(cx.listener(|this, _, _, cx| this.find(true, cx))),
+            )
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let row_count = self.len(cx).div_ceil(BYTES_PER_ROW);
+        let status = format!(
+            "{} bytes{}",
+            self.len(cx),
+            match self.column {
+                EditColumn::Hex => ", editing hex",
+                EditColumn::Ascii => ", editing ASCII",
+            }
+        );
+
+        v_flex()
+            .key_context("HexEditor")
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::move_page_up))
+            .on_action(cx.listener(Self::move_page_down))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::switch_column))
+            .on_action(cx.listener(Self::toggle_endianness))
+            .on_action(cx.listener(Self::focus_go_to_offset))
+            .on_action(cx.listener(Self::focus_search))
+            .on_action(cx.listener(Self::find_next))
+            .on_action(cx.listener(Self::find_previous))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .items_start()
+                    .child(
+                        div()
+                            .key_context("HexGrid")
+                            .track_focus(&self.focus_handle)
+                            .on_key_down(cx.listener(Self::key_down))
+                            .flex_1()
+                            .h_full()
+                            .p_2()
+                            .child(
+                                uniform_list(
+                                    "hex-rows",
+                                    row_count,
+                                    cx.processor(|this, range: Range<usize>, _, cx| {
+                                        this.render_rows(range, cx)
+                                    }),
+                                )
+                                .track_scroll(self.scroll_handle.clone())
+                                .size_full(),
+                            ),
+                    )
+                    .child(self.render_inspector(cx)),
+            )
+            .child(
+                h_flex()
+                    .gap_3()
+                    .px_2()
+                    .py_1()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        Label::new(status)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .when_some(self.error.clone(), |this, error| {
+                        this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+                    }),
+            )
+    }
+}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::TitleChanged => {
+                f(ItemEvent::UpdateTab);
+                f(ItemEvent::UpdateBreadcrumbs);
+            }
+        }
+    }
+
+    fn tab_icon(&self, _window: &Window, cx: &App) -> Option<Icon> {
+        FileIcons::get_icon(self.file.read(cx).abs_path(), cx).map(Icon::from_path)
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        self.file
+            .read(cx)
+            .abs_path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_else(|| "Hex Editor".into())
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        Some(
+            self.file
+                .read(cx)
+                .abs_path()
+                .to_string_lossy()
+                .into_owned()
+                .into(),
+        )
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("hex editor: open")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.file.entity_id(), self.file.read(cx))
+    }
+
+    fn is_singleton(&self, _cx: &App) -> bool {
+        true
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.file.read(cx).buffer().is_dirty()
+    }
+
+    fn can_save(&self, _cx: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _options: SaveOptions,
+        _project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.file.update(cx, |file, cx| file.save(cx))
+    }
+
+    fn reload(
+        &mut self,
+        _project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.file.update(cx, |file, cx| file.reload(cx))
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = BinaryFile;
+
+    fn for_project_item(
+        _project: Entity<Project>,
+        _pane: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, window, cx)
+    }
+}
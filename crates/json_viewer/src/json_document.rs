@@ -0,0 +1,230 @@
+//! Loading of JSON and NDJSON files into records, streamed from the disk in batches.
+
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use futures::{AsyncBufReadExt as _, StreamExt as _, channel::mpsc::UnboundedSender};
+use serde_json::Value;
+
+/// How many records are parsed before they are sent to the viewer.
+const BATCH_SIZE: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JsonFormat {
+    /// A single JSON document, whose elements are the records if it is an array.
+    Json,
+    /// One JSON document per line.
+    Ndjson,
+}
+
+impl JsonFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" | "geojson" => Some(Self::Json),
+            "ndjson" | "jsonl" | "jsonlines" => Some(Self::Ndjson),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonRecord {
+    /// The zero-based line of the file where the record starts.
+    pub line: u32,
+    pub value: Value,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LoadEvent {
+    Records(Vec<JsonRecord>),
+    /// A line of an NDJSON file that is not valid JSON.
+    InvalidLine {
+        line: u32,
+        error: String,
+    },
+}
+
+/// Reads the records of the file, sending them in batches as they are parsed.
+pub async fn load(path: &Path, format: JsonFormat, tx: UnboundedSender<LoadEvent>) -> Result<()> {
+    match format {
+        JsonFormat::Json => {
+            let source = smol::fs::read(path)
+                .await
+                .with_context(|| format!("reading {path:?}"))?;
+            let records = parse_json(&source).with_context(|| format!("parsing {path:?}"))?;
+            for batch in records.chunks(BATCH_SIZE) {
+                tx.unbounded_send(LoadEvent::Records(batch.to_vec())).ok();
+            }
+        }
+        JsonFormat::Ndjson => {
+            let file = smol::fs::File::open(path)
+                .await
+                .with_context(|| format!("opening {path:?}"))?;
+            let mut lines = smol::io::BufReader::new(file).lines();
+            let mut records = Vec::with_capacity(BATCH_SIZE);
+            let mut line = 0;
+            while let Some(text) = lines.next().await {
+                let text = text.with_context(|| format!("reading {path:?}"))?;
+                if !text.trim().is_empty() {
+                    match serde_json::from_str(&text) {
+                        Ok(value) => records.push(JsonRecord { line, value }),
+                        Err(error) => {
+                            let error = error.to_string();
+                            tx.unbounded_send(LoadEvent::InvalidLine { line, error })
+                                .ok();
+                        }
+                    }
+                }
+                if records.len() == BATCH_SIZE {
+                    tx.unbounded_send(LoadEvent::Records(std::mem::take(&mut records)))
+                        .ok();
+                }
+                line += 1;
+            }
+            if !records.is_empty() {
+                tx.unbounded_send(LoadEvent::Records(records)).ok();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The records of a JSON document: the elements of the top-level array, or the document itself.
+pub fn parse_json(source: &[u8]) -> Result<Vec<JsonRecord>> {
+    match serde_json::from_slice(source)? {
+        Value::Array(elements) => Ok(element_lines(source)
+            .into_iter()
+            .zip(elements)
+            .map(|(line, value)| JsonRecord { line, value })
+            .collect()),
+        value => Ok(vec![JsonRecord {
+            line: first_value_line(source),
+            value,
+        }]),
+    }
+}
+
+fn first_value_line(source: &[u8]) -> u32 {
+    let start = source
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(0);
+    source[..start]
+        .iter()
+        .filter(|byte| **byte == b'\n')
+        .count() as u32
+}
+
+/// The lines where the elements of the valid JSON array start.
+fn element_lines(source: &[u8]) -> Vec<u32> {
+    let mut lines = Vec::new();
+    let mut line = 0;
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut expecting_element = false;
+    for &byte in source {
+        // The strings can't contain raw line breaks.
+        if byte == b'\n' {
+            line += 1;
+        } else if in_string {
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+            } else if byte == b'"' {
+                in_string = false;
+            }
+        } else if !byte.is_ascii_whitespace() {
+            if depth == 1 && expecting_element && byte != b']' {
+                lines.push(line);
+                expecting_element = false;
+            }
+            match byte {
+                b'"' => in_string = true,
+                b'[' | b'{' => {
+                    depth += 1;
+                    if depth == 1 {
+                        expecting_element = true;
+                    }
+                }
+                b']' | b'}' => depth -= 1,
+                b',' if depth == 1 => expecting_element = true,
+                _ => {}
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::mpsc;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_json_array_records() {
+        let source = br#"[
+  {"msg": "a, [b]", "nested": [1, 2]},
+  "multi\"line\\",
+
+  {
+    "msg": "c"
+  }
+]"#;
+        assert_eq!(
+            parse_json(source).unwrap(),
+            [
+                JsonRecord {
+                    line: 1,
+                    value: json!({"msg": "a, [b]", "nested": [1, 2]}),
+                },
+                JsonRecord {
+                    line: 2,
+                    value: json!("multi\"line\\"),
+                },
+                JsonRecord {
+                    line: 4,
+                    value: json!({"msg": "c"}),
+                },
+            ]
+        );
+        assert_eq!(
+            parse_json(b"\n\n{\"a\": 1}").unwrap(),
+            [JsonRecord {
+                line: 2,
+                value: json!({"a": 1}),
+            }]
+        );
+        assert!(parse_json(b"[1,").is_err());
+    }
+
+    #[test]
+    fn test_ndjson_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.ndjson");
+        std::fs::write(&path, "{\"a\": 1}\n\nnot json\n{\"a\": 2}\n").unwrap();
+
+        let (tx, rx) = mpsc::unbounded();
+        smol::block_on(load(&path, JsonFormat::Ndjson, tx)).unwrap();
+        let events = smol::block_on(rx.collect::<Vec<_>>());
+        assert!(matches!(events[0], LoadEvent::InvalidLine { line: 2, .. }));
+        assert_eq!(
+            events[1],
+            LoadEvent::Records(vec![
+                JsonRecord {
+                    line: 0,
+                    value: json!({"a": 1}),
+                },
+                JsonRecord {
+                    line: 3,
+                    value: json!({"a": 2}),
+                },
+            ])
+        );
+        assert_eq!(events.len(), 2);
+    }
+}
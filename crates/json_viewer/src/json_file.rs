@@ -0,0 +1,65 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use gpui::{App, AppContext as _, Entity, Task};
+use project::{Project, ProjectEntryId, ProjectItem, ProjectPath};
+
+use crate::json_document::JsonFormat;
+
+/// An NDJSON file of a local project, whose records are streamed from the disk by the viewer instead of being
+/// loaded into a buffer.
+pub struct JsonFile {
+    project_path: ProjectPath,
+    abs_path: PathBuf,
+    entry_id: Option<ProjectEntryId>,
+}
+
+impl JsonFile {
+    pub fn abs_path(&self) -> &Path {
+        &self.abs_path
+    }
+}
+
+impl ProjectItem for JsonFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        // The single JSON documents are edited as text, and opened in the viewer with `OpenViewer`.
+        if JsonFormat::from_path(&path.path) != Some(JsonFormat::Ndjson) {
+            return None;
+        }
+        let project = project.read(cx);
+        if !project.is_local() {
+            return None;
+        }
+        let project_path = path.clone();
+        let Some(abs_path) = project.absolute_path(&project_path, cx) else {
+            return Some(Task::ready(Err(anyhow!(
+                "no worktree for {:?}",
+                project_path.path
+            ))));
+        };
+        let entry_id = project
+            .entry_for_path(&project_path, cx)
+            .map(|entry| entry.id);
+        Some(Task::ready(Ok(cx.new(|_| Self {
+            project_path,
+            abs_path,
+            entry_id,
+        }))))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+}
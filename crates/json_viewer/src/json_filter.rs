@@ -0,0 +1,505 @@
+//! A subset of the jq filter language, to narrow down the JSON records shown in the viewer:
+//!
+//! - paths like `.`, `.level`, `.request.headers["user-agent"]`, `.items[0]` and `.items[]`,
+//! - pipes, like `.items[] | .name`,
+//! - `select(...)` with the `==`, `!=`, `<`, `<=`, `>` and `>=` comparisons, `contains(...)`, `and`, `or`
+//!   and `not`, like `select(.level == "error" and (.status >= 500 or .msg | contains("timeout")))`.
+
+use std::{cmp::Ordering, iter::Peekable, str::CharIndices};
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use serde_json::Value;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonFilter {
+    stages: Vec<Stage>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Stage {
+    Path(Vec<Segment>),
+    Select(Condition),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(i64),
+    Iterate,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Condition {
+    /// Whether any value of the path is truthy, or passes the test.
+    Test {
+        path: Vec<Segment>,
+        test: Option<Test>,
+    },
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Test {
+    Compare(Comparison, Value),
+    Contains(Value),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl JsonFilter {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?.into_iter().peekable(),
+        };
+        let mut stages = vec![parser.stage()?];
+        while parser.eat(&Token::Pipe) {
+            stages.push(parser.stage()?);
+        }
+        if let Some(token) = parser.tokens.next() {
+            bail!("unexpected {token}");
+        }
+        Ok(Self { stages })
+    }
+
+    /// The values the filter produces for the input, none when the input is filtered out.
+    pub fn apply(&self, input: &Value) -> Vec<Value> {
+        let mut values = vec![input.clone()];
+        for stage in &self.stages {
+            values = match stage {
+                Stage::Path(path) => values
+                    .iter()
+                    .flat_map(|value| select_path(value, path))
+                    .collect(),
+                Stage::Select(condition) => values
+                    .into_iter()
+                    .filter(|value| condition.matches(value))
+                    .collect(),
+            };
+        }
+        values
+    }
+}
+
+impl Condition {
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            Condition::Test { path, test } => {
+                select_path(value, path).iter().any(|selected| match test {
+                    None => !matches!(selected, Value::Null | Value::Bool(false)),
+                    Some(Test::Compare(comparison, operand)) => comparison.holds(selected, operand),
+                    Some(Test::Contains(operand)) => contains(selected, operand),
+                })
+            }
+            Condition::Not(condition) => !condition.matches(value),
+            Condition::And(left, right) => left.matches(value) && right.matches(value),
+            Condition::Or(left, right) => left.matches(value) || right.matches(value),
+        }
+    }
+}
+
+impl Comparison {
+    fn holds(self, left: &Value, right: &Value) -> bool {
+        let ordering = match (left, right) {
+            (Value::Number(left), Value::Number(right)) => left
+                .as_f64()
+                .zip(right.as_f64())
+                .and_then(|(left, right)| left.partial_cmp(&right)),
+            (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+            (Value::Bool(left), Value::Bool(right)) => Some(left.cmp(right)),
+            (left, right) => (left == right).then_some(Ordering::Equal),
+        };
+        match self {
+            Comparison::Equal => ordering == Some(Ordering::Equal),
+            Comparison::NotEqual => ordering != Some(Ordering::Equal),
+            Comparison::Less => ordering == Some(Ordering::Less),
+            Comparison::LessOrEqual => ordering.is_some_and(|ordering| ordering.is_le()),
+            Comparison::Greater => ordering == Some(Ordering::Greater),
+            Comparison::GreaterOrEqual => ordering.is_some_and(|ordering| ordering.is_ge()),
+        }
+    }
+}
+
+/// Same as jq's `contains`: substrings of strings, elements of arrays and entries of objects are contained.
+fn contains(value: &Value, operand: &Value) -> bool {
+    match (value, operand) {
+        (Value::String(value), Value::String(operand)) => value.contains(operand.as_str()),
+        (Value::Array(values), Value::Array(operands)) => operands
+            .iter()
+            .all(|operand| values.iter().any(|value| contains(value, operand))),
+        (Value::Object(values), Value::Object(operands)) => {
+            operands.iter().all(|(key, operand)| {
+                values
+                    .get(key)
+                    .is_some_and(|value| contains(value, operand))
+            })
+        }
+        (value, operand) => value == operand,
+    }
+}
+
+fn select_path(value: &Value, path: &[Segment]) -> Vec<Value> {
+    let Some((segment, rest)) = path.split_first() else {
+        return vec![value.clone()];
+    };
+    let selected = match (segment, value) {
+        (Segment::Key(key), Value::Object(object)) => {
+            vec![object.get(key).cloned().unwrap_or(Value::Null)]
+        }
+        (Segment::Index(index), Value::Array(array)) => {
+            let index = if *index < 0 {
+                array.len() as i64 + index
+            } else {
+                *index
+            };
+            vec![
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|index| array.get(index))
+                    .cloned()
+                    .unwrap_or(Value::Null),
+            ]
+        }
+        (Segment::Iterate, Value::Array(array)) => array.clone(),
+        (Segment::Iterate, Value::Object(object)) => object.values().cloned().collect(),
+        (Segment::Key(_) | Segment::Index(_), Value::Null) => vec![Value::Null],
+        _ => Vec::new(),
+    };
+    selected
+        .iter()
+        .flat_map(|value| select_path(value, rest))
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Dot,
+    LeftBracket,
+    RightBracket,
+    LeftParen,
+    RightParen,
+    Pipe,
+    Operator(Comparison),
+    Identifier(String),
+    Literal(Value),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Dot => write!(f, "`.`"),
+            Token::LeftBracket => write!(f, "`[`"),
+            Token::RightBracket => write!(f, "`]`"),
+            Token::LeftParen => write!(f, "`(`"),
+            Token::RightParen => write!(f, "`)`"),
+            Token::Pipe => write!(f, "`|`"),
+            Token::Operator(comparison) => write!(f, "`{comparison:?}` comparison"),
+            Token::Identifier(identifier) => write!(f, "`{identifier}`"),
+            Token::Literal(literal) => write!(f, "`{literal}`"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '.' => Token::Dot,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '|' => Token::Pipe,
+            '=' if next_is(&mut chars, '=') => Token::Operator(Comparison::Equal),
+            '!' if next_is(&mut chars, '=') => Token::Operator(Comparison::NotEqual),
+            '<' if next_is(&mut chars, '=') => Token::Operator(Comparison::LessOrEqual),
+            '<' => Token::Operator(Comparison::Less),
+            '>' if next_is(&mut chars, '=') => Token::Operator(Comparison::GreaterOrEqual),
+            '>' => Token::Operator(Comparison::Greater),
+            '"' => {
+                let mut escaped = false;
+                let end = chars
+                    .by_ref()
+                    .find(|(_, c)| {
+                        let is_end = *c == '"' && !escaped;
+                        escaped = *c == '\\' && !escaped;
+                        is_end
+                    })
+                    .map(|(end, _)| end)
+                    .context("unterminated string")?;
+                let string = serde_json::from_str(&source[start..=end])
+                    .with_context(|| format!("invalid string {}", &source[start..=end]))?;
+                Token::Literal(Value::String(string))
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                let end = take_while(&mut chars, start, |c| {
+                    c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-')
+                });
+                let number = &source[start..end];
+                Token::Literal(
+                    serde_json::from_str(number)
+                        .ok()
+                        .filter(Value::is_number)
+                        .with_context(|| format!("invalid number {number}"))?,
+                )
+            }
+            c if c == '_' || c.is_alphabetic() => {
+                let end = take_while(&mut chars, start, |c| c == '_' || c.is_alphanumeric());
+                match &source[start..end] {
+                    "true" => Token::Literal(Value::Bool(true)),
+                    "false" => Token::Literal(Value::Bool(false)),
+                    "null" => Token::Literal(Value::Null),
+                    identifier => Token::Identifier(identifier.to_string()),
+                }
+            }
+            c => bail!("unexpected `{c}`"),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn next_is(chars: &mut Peekable<CharIndices>, expected: char) -> bool {
+    chars.next_if(|(_, c)| *c == expected).is_some()
+}
+
+/// Consumes the characters matching the predicate, returning the end of the token starting at `start`.
+fn take_while(
+    chars: &mut Peekable<CharIndices>,
+    start: usize,
+    predicate: impl Fn(char) -> bool,
+) -> usize {
+    let mut end = start + 1;
+    while let Some((index, c)) = chars.next_if(|(_, c)| predicate(*c)) {
+        end = index + c.len_utf8();
+    }
+    end
+}
+
+struct Parser {
+    tokens: Peekable<std::vec::IntoIter<Token>>,
+}
+
+impl Parser {
+    fn eat(&mut self, token: &Token) -> bool {
+        self.tokens.next_if_eq(token).is_some()
+    }
+
+    fn expect(&mut self, token: Token) -> Result<()> {
+        match self.tokens.next() {
+            Some(next) if next == token => Ok(()),
+            Some(next) => Err(anyhow!("expected {token}, found {next}")),
+            None => Err(anyhow!("expected {token}")),
+        }
+    }
+
+    fn eat_identifier(&mut self, identifier: &str) -> bool {
+        self.tokens
+            .next_if(|token| matches!(token, Token::Identifier(next) if next == identifier))
+            .is_some()
+    }
+
+    fn stage(&mut self) -> Result<Stage> {
+        if self.eat_identifier("select") {
+            self.expect(Token::LeftParen)?;
+            let condition = self.or_condition()?;
+            self.expect(Token::RightParen)?;
+            Ok(Stage::Select(condition))
+        } else {
+            Ok(Stage::Path(self.path()?))
+        }
+    }
+
+    fn or_condition(&mut self) -> Result<Condition> {
+        let mut condition = self.and_condition()?;
+        while self.eat_identifier("or") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.and_condition()?));
+        }
+        Ok(condition)
+    }
+
+    fn and_condition(&mut self) -> Result<Condition> {
+        let mut condition = self.condition()?;
+        while self.eat_identifier("and") {
+            condition = Condition::And(Box::new(condition), Box::new(self.condition()?));
+        }
+        Ok(condition)
+    }
+
+    fn condition(&mut self) -> Result<Condition> {
+        if self.eat_identifier("not") {
+            return Ok(Condition::Not(Box::new(self.condition()?)));
+        }
+        if self.eat(&Token::LeftParen) {
+            let condition = self.or_condition()?;
+            self.expect(Token::RightParen)?;
+            return Ok(condition);
+        }
+
+        let path = self.path()?;
+        let test = if let Some(Token::Operator(comparison)) = self.tokens.peek().cloned() {
+            self.tokens.next();
+            Some(Test::Compare(comparison, self.literal()?))
+        } else if self.eat(&Token::Pipe) {
+            if !self.eat_identifier("contains") {
+                bail!("expected `contains` after `|` in `select`");
+            }
+            self.expect(Token::LeftParen)?;
+            let operand = self.literal()?;
+            self.expect(Token::RightParen)?;
+            Some(Test::Contains(operand))
+        } else {
+            None
+        };
+        Ok(Condition::Test { path, test })
+    }
+
+    fn literal(&mut self) -> Result<Value> {
+        match self.tokens.next() {
+            Some(Token::Literal(value)) => Ok(value),
+            Some(token) => Err(anyhow!("expected a literal, found {token}")),
+            None => Err(anyhow!("expected a literal")),
+        }
+    }
+
+    fn path(&mut self) -> Result<Vec<Segment>> {
+        self.expect(Token::Dot)?;
+        let mut segments = Vec::new();
+        if let Some(key) = self.identifier() {
+            segments.push(Segment::Key(key));
+        }
+        loop {
+            if self.eat(&Token::LeftBracket) {
+                segments.push(self.bracket_segment()?);
+            } else if self.eat(&Token::Dot) {
+                if let Some(key) = self.identifier() {
+                    segments.push(Segment::Key(key));
+                } else if self.eat(&Token::LeftBracket) {
+                    segments.push(self.bracket_segment()?);
+                } else {
+                    bail!("expected a key after `.`");
+                }
+            } else {
+                return Ok(segments);
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> Option<String> {
+        match self
+            .tokens
+            .next_if(|token| matches!(token, Token::Identifier(_)))
+        {
+            Some(Token::Identifier(identifier)) => Some(identifier),
+            _ => None,
+        }
+    }
+
+    /// The segment in brackets, after the opening one.
+    fn bracket_segment(&mut self) -> Result<Segment> {
+        let segment = match self.tokens.next() {
+            Some(Token::RightBracket) => return Ok(Segment::Iterate),
+            Some(Token::Literal(Value::String(key))) => Segment::Key(key),
+            Some(Token::Literal(Value::Number(index))) => {
+                Segment::Index(index.as_i64().context("array index must be an integer")?)
+            }
+            Some(token) => bail!("unexpected {token} in brackets"),
+            None => bail!("expected `]`"),
+        };
+        self.expect(Token::RightBracket)?;
+        Ok(segment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn apply(filter: &str, input: Value) -> Vec<Value> {
+        JsonFilter::parse(filter).unwrap().apply(&input)
+    }
+
+    #[test]
+    fn test_paths() {
+        let input = json!({
+            "level": "info",
+            "request": {"headers": {"user-agent": "curl"}},
+            "items": [{"name": "a"}, {"name": "b"}],
+        });
+        assert_eq!(apply(".", input.clone()), vec![input.clone()]);
+        assert_eq!(apply(".level", input.clone()), vec![json!("info")]);
+        assert_eq!(
+            apply(".request.headers[\"user-agent\"]", input.clone()),
+            vec![json!("curl")]
+        );
+        assert_eq!(apply(".items[1].name", input.clone()), vec![json!("b")]);
+        assert_eq!(apply(".items[-1].name", input.clone()), vec![json!("b")]);
+        assert_eq!(
+            apply(".items[] | .name", input.clone()),
+            vec![json!("a"), json!("b")]
+        );
+        assert_eq!(apply(".missing.key", input.clone()), vec![Value::Null]);
+        assert_eq!(apply(".level[]", input), Vec::<Value>::new());
+    }
+
+    #[test]
+    fn test_select() {
+        let records = [
+            json!({"level": "error", "status": 503, "msg": "upstream timeout"}),
+            json!({"level": "error", "status": 404, "msg": "not found"}),
+            json!({"level": "info", "status": 200, "msg": "ok"}),
+        ];
+        let matching = |filter: &str| {
+            let filter = JsonFilter::parse(filter).unwrap();
+            records
+                .iter()
+                .enumerate()
+                .filter(|(_, record)| !filter.apply(record).is_empty())
+                .map(|(ix, _)| ix)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matching("select(.level == \"error\")"), [0, 1]);
+        assert_eq!(matching("select(.status >= 500)"), [0]);
+        assert_eq!(matching("select(.status != 200)"), [0, 1]);
+        assert_eq!(matching("select(.msg | contains(\"time\"))"), [0]);
+        assert_eq!(
+            matching("select(.level == \"info\" or .status < 500 and .status > 400)"),
+            [1, 2]
+        );
+        assert_eq!(matching("select(not (.level == \"error\"))"), [2]);
+        assert_eq!(matching("select(.missing)"), Vec::<usize>::new());
+        assert_eq!(
+            apply("select(.status == 503) | .msg", records[0].clone()),
+            vec![json!("upstream timeout")]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for filter in [
+            "",
+            "level",
+            ".a ==",
+            "select(.a",
+            ".a[\"b\"",
+            ".a | contains",
+            "\"x",
+        ] {
+            assert!(JsonFilter::parse(filter).is_err(), "{filter}");
+        }
+    }
+}
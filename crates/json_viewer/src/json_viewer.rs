@@ -0,0 +1,30 @@
+use gpui::{App, actions};
+use workspace::Workspace;
+
+mod json_document;
+mod json_file;
+mod json_filter;
+pub mod json_viewer_view;
+
+pub use json_document::{JsonFormat, JsonRecord};
+pub use json_file::JsonFile;
+pub use json_filter::JsonFilter;
+
+actions!(
+    json_viewer,
+    [
+        /// Opens the current JSON or NDJSON file in a structured viewer.
+        OpenViewer
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<json_viewer_view::JsonViewer>(cx);
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        crate::json_viewer_view::JsonViewer::register(workspace, window, cx);
+    })
+    .detach();
+}
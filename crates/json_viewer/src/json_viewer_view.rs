@@ -0,0 +1,819 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use collections::HashSet;
+use editor::{Editor, EditorEvent};
+use file_icons::FileIcons;
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, FontWeight,
+    IntoElement, ParentElement, Render, Styled, Subscription, Task, UniformListScrollHandle,
+    WeakEntity, Window, uniform_list,
+};
+use project::Project;
+use serde_json::Value;
+use ui::{ButtonLike, ContextMenu, ListItem, PopoverMenu, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Pane, Workspace,
+    item::{Item, ProjectItem},
+};
+
+use crate::{
+    OpenViewer,
+    json_document::{self, JsonFormat, JsonRecord, LoadEvent},
+    json_file::JsonFile,
+    json_filter::JsonFilter,
+};
+
+/// The width of a table column.
+const COLUMN_WIDTH: Pixels = px(180.);
+/// How many characters of a value are shown on its row.
+const MAX_PREVIEW_CHARS: usize = 240;
+/// The column of the table showing the records that are not objects.
+const VALUE_COLUMN: &str = ".";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ViewMode {
+    Tree,
+    Table,
+}
+
+/// A value produced by the filter for one of the records.
+struct JsonRow {
+    record_ix: usize,
+    /// None when there is no filter, and the row shows the record itself.
+    value: Option<Value>,
+}
+
+/// A row of the tree, showing a value of a [`JsonRow`] at the given JSON pointer.
+struct TreeRow {
+    row_ix: usize,
+    depth: usize,
+    pointer: String,
+    key: Option<SharedString>,
+}
+
+/// Shows the records of a JSON or NDJSON file as a tree or as a table, narrowed down with a [`JsonFilter`].
+pub struct JsonViewer {
+    path: PathBuf,
+    /// The project item, when the file was opened from the project rather than from its editor.
+    file: Option<Entity<JsonFile>>,
+    format: JsonFormat,
+    workspace: WeakEntity<Workspace>,
+    filter_editor: Entity<Editor>,
+    filter: Option<JsonFilter>,
+    filter_error: Option<SharedString>,
+    mode: ViewMode,
+    records: Vec<Arc<JsonRecord>>,
+    invalid_lines: usize,
+    loading: bool,
+    load_error: Option<SharedString>,
+    rows: Vec<JsonRow>,
+    /// How many of the records were passed through the filter.
+    filtered_records: usize,
+    tree_rows: Vec<TreeRow>,
+    /// The expanded values, by their row and JSON pointer.
+    expanded: HashSet<(usize, String)>,
+    columns: Vec<String>,
+    hidden_columns: HashSet<String>,
+    scroll_handle: UniformListScrollHandle,
+    _load_task: Task<()>,
+    filter_task: Option<Task<()>>,
+    _filter_editor_subscription: Subscription,
+}
+
+impl JsonViewer {
+    pub fn register(workspace: &mut Workspace, _window: &mut Window, _cx: &mut Context<Workspace>) {
+        workspace.register_action(|workspace, _: &OpenViewer, window, cx| {
+            let Some(path) = Self::active_editor_path(workspace, cx) else {
+                workspace.show_error(&anyhow::anyhow!("no local file is open in the editor"), cx);
+                return;
+            };
+            let existing = workspace
+                .active_pane()
+                .read(cx)
+                .items_of_type::<JsonViewer>()
+                .find(|viewer| viewer.read(cx).path == path);
+            if let Some(existing) = existing {
+                workspace.activate_item(&existing, true, true, window, cx);
+                return;
+            }
+
+            let workspace_handle = workspace.weak_handle();
+            let viewer = cx.new(|cx| JsonViewer::new(path, workspace_handle, window, cx));
+            workspace.add_item_to_active_pane(Box::new(viewer), None, true, window, cx);
+        });
+    }
+
+    fn active_editor_path(workspace: &Workspace, cx: &App) -> Option<PathBuf> {
+        let editor = workspace.active_item(cx)?.act_as::<Editor>(cx)?;
+        let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+        let file = buffer.read(cx).file()?;
+        Some(file.as_local()?.abs_path(cx))
+    }
+
+    pub fn new(
+        path: PathBuf,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let filter_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter, like select(.level == \"error\") | .msg", cx);
+            editor
+        });
+        let filter_editor_subscription =
+            cx.subscribe(&filter_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::Edited { .. } = event {
+                    this.update_filter(cx);
+                }
+            });
+        // The files with other extensions are mostly logs.
+        let format = JsonFormat::from_path(&path).unwrap_or(JsonFormat::Ndjson);
+
+        let mut this = Self {
+            path,
+            file: None,
+            format,
+            workspace,
+            filter_editor,
+            filter: None,
+            filter_error: None,
+            mode: ViewMode::Tree,
+            records: Vec::new(),
+            invalid_lines: 0,
+            loading: false,
+            load_error: None,
+            rows: Vec::new(),
+            filtered_records: 0,
+            tree_rows: Vec::new(),
+            expanded: HashSet::default(),
+            columns: Vec::new(),
+            hidden_columns: HashSet::default(),
+            scroll_handle: UniformListScrollHandle::new(),
+            _load_task: Task::ready(()),
+            filter_task: None,
+            _filter_editor_subscription: filter_editor_subscription,
+        };
+        this.reload(cx);
+        this
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the file again, streaming its records into the viewer.
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.records.clear();
+        self.invalid_lines = 0;
+        self.loading = true;
+        self.load_error = None;
+        self.columns.clear();
+        self.refilter(cx);
+
+        let (tx, mut rx) = mpsc::unbounded();
+        let path = self.path.clone();
+        let format = self.format;
+        let load = cx.background_spawn(async move { json_document::load(&path, format, tx).await });
+        self._load_task = cx.spawn(async move |this, cx| {
+            while let Some(event) = rx.next().await {
+                let updated = this.update(cx, |this, cx| {
+                    match event {
+                        LoadEvent::Records(records) => {
+                            this.records.extend(records.into_iter().map(Arc::new));
+                            this.filter_new_records(cx);
+                        }
+                        LoadEvent::InvalidLine { line, error } => {
+                            log::debug!("invalid JSON on line {}: {error}", line + 1);
+                            this.invalid_lines += 1;
+                        }
+                    }
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    return;
+                }
+            }
+            let result = load.await;
+            this.update(cx, |this, cx| {
+                this.loading = false;
+                this.load_error = result.err().map(|error| format!("{error:#}").into());
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
+
+    fn update_filter(&mut self, cx: &mut Context<Self>) {
+        let source = self.filter_editor.read(cx).text(cx);
+        let filter = if source.trim().is_empty() {
+            Ok(None)
+        } else {
+            JsonFilter::parse(&source).map(Some)
+        };
+        match filter {
+            Ok(filter) => {
+                self.filter_error = None;
+                if self.filter != filter {
+                    self.filter = filter;
+                    self.refilter(cx);
+                }
+            }
+            Err(error) => self.filter_error = Some(error.to_string().into()),
+        }
+        cx.notify();
+    }
+
+    fn refilter(&mut self, cx: &mut Context<Self>) {
+        self.filter_task = None;
+        self.filtered_records = 0;
+        self.rows.clear();
+        self.tree_rows.clear();
+        self.expanded.clear();
+        self.filter_new_records(cx);
+    }
+
+    /// Passes the records that were loaded since the last time through the filter, in the background.
+    fn filter_new_records(&mut self, cx: &mut Context<Self>) {
+        if self.filter_task.is_some() || self.filtered_records == self.records.len() {
+            return;
+        }
+        let start = self.filtered_records;
+        let records = self.records[start..].to_vec();
+        let filter = self.filter.clone();
+        self.filter_task = Some(cx.spawn(async move |this, cx| {
+            let end = start + records.len();
+            let rows = cx
+                .background_spawn(async move {
+                    let mut rows = Vec::new();
+                    for (record_ix, record) in (start..).zip(&records) {
+                        match &filter {
+                            Some(filter) => {
+                                rows.extend(filter.apply(&record.value).into_iter().map(|value| {
+                                    JsonRow {
+                                        record_ix,
+                                        value: Some(value),
+                                    }
+                                }))
+                            }
+                            None => rows.push(JsonRow {
+                                record_ix,
+                                value: None,
+                            }),
+                        }
+                    }
+                    rows
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.filter_task = None;
+                this.filtered_records = end;
+                this.append_rows(rows);
+                this.filter_new_records(cx);
+                cx.notify();
+            })
+            .log_err();
+        }));
+    }
+
+    fn append_rows(&mut self, rows: Vec<JsonRow>) {
+        let start = self.rows.len();
+        for row in &rows {
+            match self.row_value(row) {
+                Value::Object(object) => {
+                    for key in object.keys() {
+                        if !self.columns.contains(key) {
+                            self.columns.push(key.clone());
+                        }
+                    }
+                }
+                _ => {
+                    if !self.columns.iter().any(|column| column == VALUE_COLUMN) {
+                        self.columns.push(VALUE_COLUMN.to_string());
+                    }
+                }
+            }
+        }
+        self.rows.extend(rows);
+        self.tree_rows
+            .extend((start..self.rows.len()).map(|row_ix| TreeRow {
+                row_ix,
+                depth: 0,
+                pointer: String::new(),
+                key: None,
+            }));
+    }
+
+    fn row_value<'a>(&'a self, row: &'a JsonRow) -> &'a Value {
+        row.value
+            .as_ref()
+            .unwrap_or(&self.records[row.record_ix].value)
+    }
+
+    fn toggle_expanded(&mut self, row_ix: usize, pointer: String, cx: &mut Context<Self>) {
+        let key = (row_ix, pointer);
+        if !self.expanded.remove(&key) {
+            self.expanded.insert(key);
+        }
+        self.rebuild_tree_rows();
+        cx.notify();
+    }
+
+    fn rebuild_tree_rows(&mut self) {
+        let mut tree_rows = Vec::with_capacity(self.rows.len());
+        for (row_ix, row) in self.rows.iter().enumerate() {
+            self.push_tree_rows(
+                row_ix,
+                self.row_value(row),
+                0,
+                String::new(),
+                None,
+                &mut tree_rows,
+            );
+        }
+        self.tree_rows = tree_rows;
+    }
+
+    fn push_tree_rows(
+        &self,
+        row_ix: usize,
+        value: &Value,
+        depth: usize,
+        pointer: String,
+        key: Option<SharedString>,
+        tree_rows: &mut Vec<TreeRow>,
+    ) {
+        let expanded = self.expanded.contains(&(row_ix, pointer.clone()));
+        tree_rows.push(TreeRow {
+            row_ix,
+            depth,
+            pointer: pointer.clone(),
+            key,
+        });
+        if !expanded {
+            return;
+        }
+        match value {
+            Value::Object(object) => {
+                for (key, child) in object {
+                    let child_pointer = format!("{pointer}/{}", escape_pointer_token(key));
+                    let key = Some(key.clone().into());
+                    self.push_tree_rows(row_ix, child, depth + 1, child_pointer, key, tree_rows);
+                }
+            }
+            Value::Array(array) => {
+                for (index, child) in array.iter().enumerate() {
+                    let child_pointer = format!("{pointer}/{index}");
+                    let key = Some(index.to_string().into());
+                    self.push_tree_rows(row_ix, child, depth + 1, child_pointer, key, tree_rows);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn toggle_column(&mut self, column: String, cx: &mut Context<Self>) {
+        if !self.hidden_columns.remove(&column) {
+            self.hidden_columns.insert(column);
+        }
+        cx.notify();
+    }
+
+    fn visible_columns(&self) -> impl Iterator<Item = &String> {
+        self.columns
+            .iter()
+            .filter(|column| !self.hidden_columns.contains(*column))
+    }
+
+    /// Opens the file in an editor, at the line where the record starts.
+    fn open_source_line(&mut self, line: u32, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        // The NDJSON files open in the viewer, so the editor is opened for the buffer of the file explicitly.
+        let open_buffer = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.open_local_buffer(self.path.clone(), cx)
+            })
+        });
+        cx.spawn_in(window, async move |_, cx| {
+            let buffer = open_buffer.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let pane = workspace.active_pane().clone();
+                let editor =
+                    workspace.open_project_item::<Editor>(pane, buffer, true, true, window, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.go_to_singleton_buffer_point(language::Point::new(line, 0), window, cx);
+                });
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_source_line(&self, id: usize, row: &JsonRow, cx: &Context<Self>) -> AnyElement {
+        let line = self.records[row.record_ix].line;
+        Button::new(("source-line", id), (line + 1).to_string())
+            .style(ButtonStyle::Transparent)
+            .label_size(LabelSize::Small)
+            .color(Color::Muted)
+            .tooltip(Tooltip::text("Go to Line in File"))
+            .on_click(
+                cx.listener(move |this, _, window, cx| this.open_source_line(line, window, cx)),
+            )
+            .into_any_element()
+    }
+
+    fn render_tree_rows(&self, range: Range<usize>, cx: &Context<Self>) -> Vec<AnyElement> {
+        self.tree_rows[range.clone()]
+            .iter()
+            .zip(range)
+            .map(|(tree_row, ix)| {
+                let row = &self.rows[tree_row.row_ix];
+                let value = self
+                    .row_value(row)
+                    .pointer(&tree_row.pointer)
+                    .unwrap_or(&Value::Null);
+                let expandable = match value {
+                    Value::Object(object) => !object.is_empty(),
+                    Value::Array(array) => !array.is_empty(),
+                    _ => false,
+                };
+                let expanded = self
+                    .expanded
+                    .contains(&(tree_row.row_ix, tree_row.pointer.clone()));
+                let (row_ix, pointer) = (tree_row.row_ix, tree_row.pointer.clone());
+
+                ListItem::new(ix)
+                    .indent_level(tree_row.depth)
+                    .indent_step_size(px(16.))
+                    .toggle(expandable.then_some(expanded))
+                    .on_toggle(cx.listener(move |this, _, _, cx| {
+                        this.toggle_expanded(row_ix, pointer.clone(), cx)
+                    }))
+                    .start_slot((tree_row.depth == 0).then(|| self.render_source_line(ix, row, cx)))
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .when_some(tree_row.key.clone(), |this, key| {
+                                this.child(
+                                    Label::new(format!("{key}:"))
+                                        .color(Color::Accent)
+                                        .buffer_font(cx),
+                                )
+                            })
+                            .child(
+                                Label::new(value_preview(value, expanded))
+                                    .color(value_color(value))
+                                    .buffer_font(cx)
+                                    .single_line()
+                                    .truncate(),
+                            ),
+                    )
+                    .into_any_element()
+            })
+            .collect()
+    }
+
+    fn render_table_rows(&self, range: Range<usize>, cx: &Context<Self>) -> Vec<AnyElement> {
+        self.rows[range.clone()]
+            .iter()
+            .zip(range)
+            .map(|(row, ix)| {
+                let value = self.row_value(row);
+                h_flex()
+                    .id(ix)
+                    .w_full()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        div()
+                            .w(px(56.))
+                            .flex_none()
+                            .child(self.render_source_line(ix, row, cx)),
+                    )
+                    .children(self.visible_columns().map(|column| {
+                        let cell = if column == VALUE_COLUMN {
+                            (!value.is_object()).then_some(value)
+                        } else {
+                            value.get(column)
+                        };
+                        div()
+                            .w(COLUMN_WIDTH)
+                            .flex_none()
+                            .px_1()
+                            .overflow_hidden()
+                            .child(
+                                Label::new(cell.map(cell_text).unwrap_or_default())
+                                    .color(cell.map_or(Color::Default, value_color))
+                                    .buffer_font(cx)
+                                    .single_line()
+                                    .truncate(),
+                            )
+                    }))
+                    .into_any_element()
+            })
+            .collect()
+    }
+
+    fn render_table_header(&self, cx: &Context<Self>) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                div()
+                    .w(px(56.))
+                    .flex_none()
+                    .child(Label::new("Line").color(Color::Muted)),
+            )
+            .children(self.visible_columns().map(|column| {
+                div().w(COLUMN_WIDTH).flex_none().px_1().child(
+                    Label::new(column.clone())
+                        .weight(FontWeight::SEMIBOLD)
+                        .single_line()
+                        .truncate(),
+                )
+            }))
+    }
+
+    fn render_columns_menu(&self, cx: &Context<Self>) -> impl IntoElement {
+        let this = cx.entity().downgrade();
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| (column.clone(), !self.hidden_columns.contains(column)))
+            .collect::<Vec<_>>();
+        PopoverMenu::new("json-viewer-columns")
+            .trigger(
+                ButtonLike::new("json-viewer-columns-trigger")
+                    .child(Label::new("Columns").size(LabelSize::Small))
+                    .child(Icon::new(IconName::ChevronDown).size(IconSize::XSmall)),
+            )
+            .menu(move |window, cx| {
+                let this = this.clone();
+                let columns = columns.clone();
+                Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                    for (column, visible) in columns {
+                        let this = this.clone();
+                        menu = menu.toggleable_entry(
+                            column.clone(),
+                            visible,
+                            IconPosition::Start,
+                            None,
+                            move |_, cx| {
+                                this.update(cx, |this, cx| this.toggle_column(column.clone(), cx))
+                                    .ok();
+                            },
+                        );
+                    }
+                    menu
+                }))
+            })
+    }
+
+    fn render_toolbar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let mode_button = |id: &'static str, label: &'static str, mode: ViewMode| {
+            Button::new(id, label)
+                .style(ButtonStyle::Subtle)
+                .label_size(LabelSize::Small)
+                .toggle_state(self.mode == mode)
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.mode = mode;
+                    cx.notify();
+                }))
+        };
+
+        h_flex()
+            .gap_2()
+            .p_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .flex_1()
+                    .h_8()
+                    .px_2()
+                    .gap_2()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(if self.filter_error.is_some() {
+                        Color::Error.color(cx)
+                    } else {
+                        cx.theme().colors().border
+                    })
+                    .child(Icon::new(IconName::Filter).color(Color::Muted))
+                    .child(self.filter_editor.clone()),
+            )
+            .child(mode_button("json-viewer-tree", "Tree", ViewMode::Tree))
+            .child(mode_button("json-viewer-table", "Table", ViewMode::Table))
+            .when(self.mode == ViewMode::Table, |this| {
+                this.child(self.render_columns_menu(cx))
+            })
+            .child(
+                IconButton::new("json-viewer-reload", IconName::RotateCw)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Reload File"))
+                    .on_click(cx.listener(|this, _, _, cx| this.reload(cx))),
+            )
+    }
+
+    fn render_status(&self, cx: &Context<Self>) -> impl IntoElement {
+        let summary = if self.filter.is_some() {
+            format!(
+                "{} values from {} records",
+                self.rows.len(),
+                self.filtered_records
+            )
+        } else {
+            format!("{} records", self.records.len())
+        };
+        h_flex()
+            .gap_3()
+            .px_2()
+            .py_1()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new(summary)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .when(self.loading, |this| {
+                this.child(
+                    Label::new("Loading…")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .when(self.invalid_lines > 0, |this| {
+                this.child(
+                    Label::new(format!("{} invalid lines skipped", self.invalid_lines))
+                        .size(LabelSize::Small)
+                        .color(Color::Warning),
+                )
+            })
+            .when_some(self.filter_error.clone(), |this, error| {
+                this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            })
+            .when_some(self.load_error.clone(), |this, error| {
+                this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            })
+    }
+}
+
+fn escape_pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn value_preview(value: &Value, expanded: bool) -> String {
+    match value {
+        Value::Object(object) if expanded => format!("{{ {} keys }}", object.len()),
+        Value::Array(array) if expanded => format!("[ {} items ]", array.len()),
+        value => truncate(value.to_string()),
+    }
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::String(string) => truncate(string.clone()),
+        Value::Null => String::new(),
+        value => truncate(value.to_string()),
+    }
+}
+
+fn truncate(mut text: String) -> String {
+    if let Some((end, _)) = text.char_indices().nth(MAX_PREVIEW_CHARS) {
+        text.truncate(end);
+        text.push('…');
+    }
+    text
+}
+
+fn value_color(value: &Value) -> Color {
+    match value {
+        Value::String(_) => Color::Success,
+        Value::Number(_) => Color::Info,
+        Value::Bool(_) => Color::Warning,
+        Value::Null => Color::Muted,
+        Value::Object(_) | Value::Array(_) => Color::Default,
+    }
+}
+
+impl Render for JsonViewer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let body = match self.mode {
+            ViewMode::Tree => uniform_list(
+                "json-viewer-tree-rows",
+                self.tree_rows.len(),
+                cx.processor(|this, range: Range<usize>, _, cx| this.render_tree_rows(range, cx)),
+            )
+            .track_scroll(self.scroll_handle.clone())
+            .size_full()
+            .into_any_element(),
+            ViewMode::Table => v_flex()
+                .size_full()
+                .child(self.render_table_header(cx))
+                .child(
+                    uniform_list(
+                        "json-viewer-table-rows",
+                        self.rows.len(),
+                        cx.processor(|this, range: Range<usize>, _, cx| {
+                            this.render_table_rows(range, cx)
+                        }),
+                    )
+                    .track_scroll(self.scroll_handle.clone())
+                    .flex_1(),
+                )
+                .into_any_element(),
+        };
+
+        v_flex()
+            .id("JsonViewer")
+            .key_context("JsonViewer")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .child(div().flex_1().min_h_0().px_1().child(body))
+            .child(self.render_status(cx))
+    }
+}
+
+impl Focusable for JsonViewer {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.filter_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<()> for JsonViewer {}
+
+impl Item for JsonViewer {
+    type Event = ();
+
+    fn tab_icon(&self, _window: &Window, cx: &App) -> Option<Icon> {
+        FileIcons::get_icon(&self.path, cx).map(Icon::from_path)
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.path
+            .file_name()
+            .map(|name| format!("View {}", name.to_string_lossy()).into())
+            .unwrap_or_else(|| "JSON Viewer".into())
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(self.path.to_string_lossy().into_owned().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("json viewer: open")
+    }
+
+    fn to_item_events(_event: &Self::Event, _f: impl FnMut(workspace::item::ItemEvent)) {}
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        if let Some(file) = &self.file {
+            f(file.entity_id(), file.read(cx))
+        }
+    }
+
+    fn is_singleton(&self, _cx: &App) -> bool {
+        true
+    }
+}
+
+impl ProjectItem for JsonViewer {
+    type Item = JsonFile;
+
+    fn for_project_item(
+        _project: Entity<Project>,
+        _pane: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        let path = item.read(cx).abs_path().to_path_buf();
+        let workspace = window
+            .root::<Workspace>()
+            .flatten()
+            .map(|workspace| workspace.downgrade())
+            .unwrap_or_else(WeakEntity::new_invalid);
+        let mut this = Self::new(path, workspace, window, cx);
+        this.file = Some(item);
+        this
+    }
+}
@@ -21,7 +21,7 @@ use anyhow::{Context as _, Result};
 pub use clock::ReplicaId;
 use clock::{AGENT_REPLICA_ID, Lamport};
 use collections::HashMap;
-use fs::MTime;
+use fs::{Encoding, MTime};
 use futures::channel::oneshot;
 use gpui::{
     App, AppContext as _, Context, Entity, EventEmitter, HighlightStyle, SharedString, StyledText,
@@ -100,6 +100,8 @@ pub struct Buffer {
     /// The version vector when this buffer was last loaded from
     /// or saved to disk.
     saved_version: clock::Global,
+    /// The encoding the buffer's file was loaded with, and is saved with.
+    encoding: Encoding,
     preview_version: clock::Global,
     transaction_depth: usize,
     was_dirty_before_starting_transaction: Option<bool>,
@@ -400,6 +402,14 @@ pub trait LocalFile: File {
 
     /// Loads the file's contents from disk.
     fn load_bytes(&self, cx: &App) -> Task<Result<Vec<u8>>>;
+
+    /// Loads the file contents from disk and decodes them with the given encoding, or with the encoding
+    /// detected from them.
+    fn load_with_encoding(
+        &self,
+        encoding: Option<Encoding>,
+        cx: &App,
+    ) -> Task<Result<(String, Encoding)>>;
 }
 
 /// The auto-indent behavior associated with an editing operation.
@@ -841,6 +851,9 @@ impl Buffer {
         ));
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
+        if let Some(encoding) = message.encoding {
+            this.encoding = proto::deserialize_encoding(encoding)?;
+        }
         Ok(this)
     }
 
@@ -853,6 +866,7 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            encoding: Some(proto::serialize_encoding(self.encoding)),
         }
     }
 
@@ -930,6 +944,7 @@ impl Buffer {
         Self {
             saved_mtime,
             saved_version: buffer.version(),
+            encoding: Encoding::default(),
             preview_version: buffer.version(),
             reload_task: None,
             transaction_depth: 0,
@@ -1065,6 +1080,7 @@ impl Buffer {
                 }),
                 language: self.language.clone(),
                 has_conflict: self.has_conflict,
+                encoding: self.encoding,
                 has_unsaved_edits: Cell::new(self.has_unsaved_edits.get_mut().clone()),
                 _subscriptions: vec![cx.subscribe(&this, Self::on_base_buffer_event)],
                 ..Self::build(self.text.branch(), self.file.clone(), self.capability())
@@ -1226,6 +1242,19 @@ impl Buffer {
         self.saved_mtime
     }
 
+    /// The encoding of the buffer's file.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Sets the encoding that the buffer is saved with from now on, leaving its text unchanged.
+    pub fn set_encoding(&mut self, encoding: Encoding, cx: &mut Context<Self>) {
+        if self.encoding != encoding {
+            self.encoding = encoding;
+            cx.notify();
+        }
+    }
+
     /// Assign a language to the buffer.
     pub fn set_language(&mut self, language: Option<Arc<Language>>, cx: &mut Context<Self>) {
         self.non_text_state_update_count += 1;
@@ -1279,19 +1308,31 @@ impl Buffer {
 
     /// Reloads the contents of the buffer from disk.
     pub fn reload(&mut self, cx: &Context<Self>) -> oneshot::Receiver<Option<Transaction>> {
+        self.reload_with_encoding(self.encoding, cx)
+    }
+
+    /// Reloads the contents of the buffer from disk, decoding them with the given encoding.
+    pub fn reload_with_encoding(
+        &mut self,
+        encoding: Encoding,
+        cx: &Context<Self>,
+    ) -> oneshot::Receiver<Option<Transaction>> {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
         self.reload_task = Some(cx.spawn(async move |this, cx| {
             let Some((new_mtime, new_text)) = this.update(cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
 
-                Some((file.disk_state().mtime(), file.load(cx)))
+                Some((
+                    file.disk_state().mtime(),
+                    file.load_with_encoding(Some(encoding), cx),
+                ))
             })?
             else {
                 return Ok(());
             };
 
-            let new_text = new_text.await?;
+            let (new_text, encoding) = new_text.await?;
             let diff = this
                 .update(cx, |this, cx| this.diff(new_text.clone(), cx))?
                 .await;
@@ -1301,6 +1342,7 @@ impl Buffer {
                     this.apply_diff(diff, cx);
                     tx.send(this.finalize_last_transaction().cloned()).ok();
                     this.has_conflict = false;
+                    this.encoding = encoding;
                     this.did_reload(this.version(), this.line_ending(), new_mtime, cx);
                 } else {
                     if !diff.edits.is_empty()
@@ -4948,6 +4990,14 @@ impl LocalFile for TestFile {
     fn load_bytes(&self, _cx: &App) -> Task<Result<Vec<u8>>> {
         unimplemented!()
     }
+
+    fn load_with_encoding(
+        &self,
+        _encoding: Option<Encoding>,
+        _cx: &App,
+    ) -> Task<Result<(String, Encoding)>> {
+        unimplemented!()
+    }
 }
 
 pub(crate) fn contiguous_ranges(
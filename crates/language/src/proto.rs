@@ -29,6 +29,20 @@ pub fn serialize_line_ending(message: text::LineEnding) -> proto::LineEnding {
     }
 }
 
+/// Serializes a [`fs::Encoding`] to be sent over RPC.
+pub fn serialize_encoding(encoding: fs::Encoding) -> proto::Encoding {
+    proto::Encoding {
+        name: encoding.name().to_string(),
+        has_bom: encoding.has_bom(),
+    }
+}
+
+/// Deserializes a [`fs::Encoding`] from the RPC representation.
+pub fn deserialize_encoding(message: proto::Encoding) -> Result<fs::Encoding> {
+    fs::Encoding::for_name(&message.name, message.has_bom)
+        .with_context(|| format!("unknown encoding {:?}", message.name))
+}
+
 /// Serializes a [`crate::Operation`] to be sent over RPC.
 pub fn serialize_operation(operation: &crate::Operation) -> proto::Operation {
     proto::Operation {
@@ -392,9 +392,21 @@ pub struct LanguageModelRequest {
     pub tool_choice: Option<LanguageModelToolChoice>,
     pub stop: Vec<String>,
     pub temperature: Option<f32>,
+    /// The most tokens the model may generate, when lower than the model's own limit.
+    pub max_tokens: Option<u64>,
     pub thinking_allowed: bool,
 }
 
+impl LanguageModelRequest {
+    /// The most tokens the model may generate for the request, given the model's own limit.
+    pub fn max_output_tokens(&self, model_limit: Option<u64>) -> Option<u64> {
+        match (self.max_tokens, model_limit) {
+            (Some(max_tokens), Some(model_limit)) => Some(max_tokens.min(model_limit)),
+            (max_tokens, model_limit) => max_tokens.or(model_limit),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct LanguageModelResponseMessage {
     pub role: Option<Role>,
@@ -517,6 +517,9 @@ pub fn into_anthropic(
     max_output_tokens: u64,
     mode: AnthropicModelMode,
 ) -> anthropic::Request {
+    let max_output_tokens = request
+        .max_tokens
+        .map_or(max_output_tokens, |max_tokens| max_tokens.min(max_output_tokens));
     let mut new_messages: Vec<anthropic::Message> = Vec::new();
     let mut system_message = String::new();
 
@@ -1110,6 +1113,7 @@ mod tests {
             temperature: None,
             tools: vec![],
             tool_choice: None,
+            max_tokens: None,
             thinking_allowed: true,
         };
 
@@ -616,6 +616,9 @@ pub fn into_bedrock(
     mode: BedrockModelMode,
     supports_caching: bool,
 ) -> Result<bedrock::Request> {
+    let max_output_tokens = request
+        .max_tokens
+        .map_or(max_output_tokens, |max_tokens| max_tokens.min(max_output_tokens));
     let mut new_messages: Vec<BedrockMessage> = Vec::new();
     let mut system_message = String::new();
 
@@ -387,6 +387,7 @@ pub fn into_mistral(
     model: String,
     max_output_tokens: Option<u64>,
 ) -> mistral::Request {
+    let max_output_tokens = request.max_output_tokens(max_output_tokens);
     let stream = true;
 
     let mut messages = Vec::new();
@@ -905,6 +906,7 @@ mod tests {
             intent: None,
             mode: None,
             stop: vec![],
+            max_tokens: None,
             thinking_allowed: true,
         };
 
@@ -938,6 +940,7 @@ mod tests {
             intent: None,
             mode: None,
             stop: vec![],
+            max_tokens: None,
             thinking_allowed: true,
         };
 
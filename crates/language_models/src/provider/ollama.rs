@@ -334,6 +334,7 @@ impl OllamaLanguageModel {
             stream: true,
             options: Some(ChatOptions {
                 num_ctx: Some(self.model.max_tokens),
+                num_predict: request.max_tokens.map(|max_tokens| max_tokens as isize),
                 stop: Some(request.stop),
                 temperature: request.temperature.or(Some(1.0)),
                 ..Default::default()
@@ -7,7 +7,7 @@ use crate::{
 use anyhow::{Context as _, Result, anyhow};
 use client::Client;
 use collections::{HashMap, HashSet, hash_map};
-use fs::Fs;
+use fs::{Encoding, Fs};
 use futures::{Future, FutureExt as _, StreamExt, channel::oneshot, future::Shared};
 use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity,
@@ -15,8 +15,8 @@ use gpui::{
 use language::{
     Buffer, BufferEvent, Capability, DiskState, File as _, Language, Operation,
     proto::{
-        deserialize_line_ending, deserialize_version, serialize_line_ending, serialize_version,
-        split_operations,
+        deserialize_encoding, deserialize_line_ending, deserialize_version, serialize_encoding,
+        serialize_line_ending, serialize_version, split_operations,
     },
 };
 use rpc::{
@@ -124,6 +124,7 @@ impl RemoteBufferStore {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id().into();
         let version = buffer.version();
+        let encoding = buffer.encoding();
         let rpc = self.upstream_client.clone();
         let project_id = self.project_id;
         cx.spawn(async move |_, cx| {
@@ -133,12 +134,17 @@ impl RemoteBufferStore {
                     buffer_id,
                     new_path,
                     version: serialize_version(&version),
+                    encoding: Some(serialize_encoding(encoding)),
                 })
                 .await?;
             let version = deserialize_version(&response.version);
             let mtime = response.mtime.map(|mtime| mtime.into());
+            let encoding = response.encoding.map(deserialize_encoding).transpose()?;
 
             buffer_handle.update(cx, |buffer, cx| {
+                if let Some(encoding) = encoding {
+                    buffer.set_encoding(encoding, cx);
+                }
                 buffer.did_save(version.clone(), mtime, cx);
             })?;
 
@@ -326,6 +332,7 @@ impl RemoteBufferStore {
     fn reload_buffers(
         &self,
         buffers: HashSet<Entity<Buffer>>,
+        encoding: Option<Encoding>,
         push_to_history: bool,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<ProjectTransaction>> {
@@ -335,6 +342,7 @@ impl RemoteBufferStore {
                 .iter()
                 .map(|buffer| buffer.read(cx).remote_id().to_proto())
                 .collect(),
+            encoding: encoding.map(serialize_encoding),
         });
 
         cx.spawn(async move |this, cx| {
@@ -360,6 +368,7 @@ impl LocalBufferStore {
 
         let text = buffer.as_rope().clone();
         let line_ending = buffer.line_ending();
+        let encoding = buffer.encoding();
         let version = buffer.version();
         let buffer_id = buffer.remote_id();
         let file = buffer.file().cloned();
@@ -371,7 +380,7 @@ impl LocalBufferStore {
         }
 
         let save = worktree.update(cx, |worktree, cx| {
-            worktree.write_file(path.as_ref(), text, line_ending, cx)
+            worktree.write_file(path.as_ref(), text, line_ending, encoding, cx)
         });
 
         cx.spawn(async move |this, cx| {
@@ -394,6 +403,7 @@ impl LocalBufferStore {
                             buffer_id: buffer_id.to_proto(),
                             version: serialize_version(&version),
                             mtime: mtime.map(|time| time.into()),
+                            encoding: Some(serialize_encoding(encoding)),
                         })
                         .log_err();
                 }
@@ -612,8 +622,11 @@ impl LocalBufferStore {
                 let text_buffer = cx
                     .background_spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
                     .await;
-                cx.insert_entity(reservation, |_| {
-                    Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite)
+                cx.insert_entity(reservation, |cx| {
+                    let mut buffer =
+                        Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite);
+                    buffer.set_encoding(loaded.encoding, cx);
+                    buffer
                 })
             })
         });
@@ -678,13 +691,19 @@ impl LocalBufferStore {
     fn reload_buffers(
         &self,
         buffers: HashSet<Entity<Buffer>>,
+        encoding: Option<Encoding>,
         push_to_history: bool,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<ProjectTransaction>> {
         cx.spawn(async move |_, cx| {
             let mut project_transaction = ProjectTransaction::default();
             for buffer in buffers {
-                let transaction = buffer.update(cx, |buffer, cx| buffer.reload(cx))?.await?;
+                let transaction = buffer
+                    .update(cx, |buffer, cx| match encoding {
+                        Some(encoding) => buffer.reload_with_encoding(encoding, cx),
+                        None => buffer.reload(cx),
+                    })?
+                    .await?;
                 buffer.update(cx, |buffer, cx| {
                     if let Some(transaction) = transaction {
                         if !push_to_history {
@@ -1129,6 +1148,7 @@ impl BufferStore {
                         version: serialize_version(&buffer.version()),
                         mtime: buffer.saved_mtime().map(|t| t.into()),
                         line_ending: serialize_line_ending(buffer.line_ending()) as i32,
+                        encoding: Some(serialize_encoding(buffer.encoding())),
                     })
                     .log_err();
             }
@@ -1245,6 +1265,7 @@ impl BufferStore {
                         mtime: buffer.saved_mtime().map(|time| time.into()),
                         line_ending: language::proto::serialize_line_ending(buffer.line_ending())
                             as i32,
+                        encoding: Some(serialize_encoding(buffer.encoding())),
                     })
                     .log_err();
 
@@ -1360,6 +1381,10 @@ impl BufferStore {
             })?
             .await?;
         let buffer_id = buffer.read_with(&mut cx, |buffer, _| buffer.remote_id())?;
+        if let Some(encoding) = envelope.payload.encoding {
+            let encoding = deserialize_encoding(encoding)?;
+            buffer.update(&mut cx, |buffer, cx| buffer.set_encoding(encoding, cx))?;
+        }
 
         if let Some(new_path) = envelope.payload.new_path {
             let new_path = ProjectPath::from_proto(new_path);
@@ -1377,6 +1402,7 @@ impl BufferStore {
             buffer_id: buffer_id.into(),
             version: serialize_version(buffer.saved_version()),
             mtime: buffer.saved_mtime().map(|time| time.into()),
+            encoding: Some(serialize_encoding(buffer.encoding())),
         })
     }
 
@@ -1413,9 +1439,18 @@ impl BufferStore {
         let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
         let version = deserialize_version(&envelope.payload.version);
         let mtime = envelope.payload.mtime.clone().map(|time| time.into());
+        let encoding = envelope
+            .payload
+            .encoding
+            .clone()
+            .map(deserialize_encoding)
+            .transpose()?;
         this.update(&mut cx, move |this, cx| {
             if let Some(buffer) = this.get_possibly_incomplete(buffer_id) {
                 buffer.update(cx, |buffer, cx| {
+                    if let Some(encoding) = encoding {
+                        buffer.set_encoding(encoding, cx);
+                    }
                     buffer.did_save(version, mtime, cx);
                 });
             }
@@ -1427,6 +1462,7 @@ impl BufferStore {
                         buffer_id: buffer_id.into(),
                         mtime: envelope.payload.mtime,
                         version: envelope.payload.version,
+                        encoding: envelope.payload.encoding,
                     })
                     .log_err();
             }
@@ -1445,9 +1481,18 @@ impl BufferStore {
             proto::LineEnding::from_i32(envelope.payload.line_ending)
                 .context("missing line ending")?,
         );
+        let encoding = envelope
+            .payload
+            .encoding
+            .clone()
+            .map(deserialize_encoding)
+            .transpose()?;
         this.update(&mut cx, |this, cx| {
             if let Some(buffer) = this.get_possibly_incomplete(buffer_id) {
                 buffer.update(cx, |buffer, cx| {
+                    if let Some(encoding) = encoding {
+                        buffer.set_encoding(encoding, cx);
+                    }
                     buffer.did_reload(version, line_ending, mtime, cx);
                 });
             }
@@ -1460,15 +1505,18 @@ impl BufferStore {
                         mtime: envelope.payload.mtime,
                         version: envelope.payload.version,
                         line_ending: envelope.payload.line_ending,
+                        encoding: envelope.payload.encoding,
                     })
                     .log_err();
             }
         })
     }
 
+    /// Reloads the buffers from disk, decoding them with the given encoding, or with their own.
     pub fn reload_buffers(
         &self,
         buffers: HashSet<Entity<Buffer>>,
+        encoding: Option<Encoding>,
         push_to_history: bool,
         cx: &mut Context<Self>,
     ) -> Task<Result<ProjectTransaction>> {
@@ -1476,8 +1524,12 @@ impl BufferStore {
             return Task::ready(Ok(ProjectTransaction::default()));
         }
         match &self.state {
-            BufferStoreState::Local(this) => this.reload_buffers(buffers, push_to_history, cx),
-            BufferStoreState::Remote(this) => this.reload_buffers(buffers, push_to_history, cx),
+            BufferStoreState::Local(this) => {
+                this.reload_buffers(buffers, encoding, push_to_history, cx)
+            }
+            BufferStoreState::Remote(this) => {
+                this.reload_buffers(buffers, encoding, push_to_history, cx)
+            }
         }
     }
 
@@ -1487,13 +1539,18 @@ impl BufferStore {
         mut cx: AsyncApp,
     ) -> Result<proto::ReloadBuffersResponse> {
         let sender_id = envelope.original_sender_id().unwrap_or_default();
+        let encoding = envelope
+            .payload
+            .encoding
+            .map(deserialize_encoding)
+            .transpose()?;
         let reload = this.update(&mut cx, |this, cx| {
             let mut buffers = HashSet::default();
             for buffer_id in &envelope.payload.buffer_ids {
                 let buffer_id = BufferId::new(*buffer_id)?;
                 buffers.insert(this.get_existing(buffer_id)?);
             }
-            anyhow::Ok(this.reload_buffers(buffers, false, cx))
+            anyhow::Ok(this.reload_buffers(buffers, encoding, false, cx))
         })??;
 
         let project_transaction = reload.await?;
@@ -1,21 +1,29 @@
 pub mod extension;
 pub mod registry;
+pub mod sampling;
 
 use std::{path::Path, sync::Arc};
 
 use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet};
-use context_server::{ContextServer, ContextServerCommand, ContextServerId};
+use context_server::types::{self, Notification as _, Request as _};
+use context_server::{
+    ContextServer, ContextServerCommand, ContextServerEndpoint, ContextServerId,
+    client::RequestHandler,
+};
+use credentials_provider::CredentialsProvider;
 use futures::{FutureExt as _, future::join_all};
 use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity, actions};
 use registry::ContextServerDescriptorRegistry;
+use sampling::{SamplingRequest, SamplingResponse};
 use settings::{Settings as _, SettingsStore};
+use url::Url;
 use util::ResultExt as _;
 
 use crate::{
     Project,
     project_settings::{ContextServerSettings, ProjectSettings},
-    worktree_store::WorktreeStore,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
 
 pub fn init(cx: &mut App) {
@@ -99,13 +107,18 @@ pub enum ContextServerConfiguration {
         command: ContextServerCommand,
         settings: serde_json::Value,
     },
+    Http {
+        endpoint: ContextServerEndpoint,
+    },
 }
 
 impl ContextServerConfiguration {
-    pub fn command(&self) -> &ContextServerCommand {
+    /// The command that runs the context server, unless it's served over HTTP.
+    pub fn command(&self) -> Option<&ContextServerCommand> {
         match self {
-            ContextServerConfiguration::Custom { command } => command,
-            ContextServerConfiguration::Extension { command, .. } => command,
+            ContextServerConfiguration::Custom { command } => Some(command),
+            ContextServerConfiguration::Extension { command, .. } => Some(command),
+            ContextServerConfiguration::Http { .. } => None,
         }
     }
 
@@ -134,6 +147,36 @@ impl ContextServerConfiguration {
 
                 Some(ContextServerConfiguration::Extension { command, settings })
             }
+            ContextServerSettings::Http {
+                enabled: _,
+                url,
+                mut headers,
+            } => {
+                let parsed_url = url
+                    .parse()
+                    .with_context(|| format!("invalid URL for context server {id}: {url:?}"))
+                    .log_err()?;
+                let credentials_provider =
+                    cx.update(|cx| <dyn CredentialsProvider>::global(cx)).ok()?;
+                if let Some((_, token)) = credentials_provider
+                    .read_credentials(&url, cx)
+                    .await
+                    .log_err()
+                    .flatten()
+                {
+                    let token = String::from_utf8(token)
+                        .context("invalid context server bearer token")
+                        .log_err()?;
+                    headers.insert("Authorization".to_string(), format!("Bearer {token}"));
+                }
+
+                Some(ContextServerConfiguration::Http {
+                    endpoint: ContextServerEndpoint {
+                        url: parsed_url,
+                        headers,
+                    },
+                })
+            }
         }
     }
 }
@@ -158,6 +201,15 @@ pub enum Event {
         server_id: ContextServerId,
         status: ContextServerStatus,
     },
+    /// The server reported that the contents of a resource it serves have changed.
+    ResourceUpdated {
+        server_id: ContextServerId,
+        uri: Url,
+    },
+    /// The server asked to sample the user's language model and needs their consent.
+    SamplingRequested(SamplingRequest),
+    /// The model answered a sampling request, and the user needs to review the answer.
+    SamplingCompleted(SamplingResponse),
 }
 
 impl EventEmitter<Event> for ContextServerStore {}
@@ -236,6 +288,11 @@ impl ContextServerStore {
                     this.context_server_settings = settings.clone();
                     this.available_context_servers_changed(cx);
                 }),
+                cx.subscribe(&worktree_store, |this, _, event, _| match event {
+                    WorktreeStoreEvent::WorktreeAdded(_)
+                    | WorktreeStoreEvent::WorktreeRemoved(..) => this.roots_changed(),
+                    _ => {}
+                }),
             ]
         } else {
             Vec::new()
@@ -299,6 +356,31 @@ impl ContextServerStore {
             .collect()
     }
 
+    /// The visible worktrees of the project, which servers are allowed to operate on.
+    fn roots(&self, cx: &App) -> Vec<types::Root> {
+        self.worktree_store
+            .read(cx)
+            .visible_worktrees(cx)
+            .filter_map(|worktree| {
+                let worktree = worktree.read(cx);
+                Some(types::Root {
+                    uri: Url::from_file_path(worktree.abs_path()).ok()?,
+                    name: Some(worktree.root_name().to_string()),
+                })
+            })
+            .collect()
+    }
+
+    fn roots_changed(&self) {
+        for server in self.running_servers() {
+            if let Some(client) = server.client() {
+                client
+                    .notify::<types::notifications::RootsListChanged>(())
+                    .log_err();
+            }
+        }
+    }
+
     pub fn start_server(&mut self, server: Arc<ContextServer>, cx: &mut Context<Self>) {
         cx.spawn(async move |this, cx| {
             let this = this.upgrade().context("Context server store dropped")?;
@@ -397,7 +479,67 @@ impl ContextServerStore {
             let server = server.clone();
             let configuration = configuration.clone();
             async move |this, cx| {
-                match server.clone().start(&cx).await {
+                let resources_updated_handler = {
+                    let this = this.clone();
+                    let id = id.clone();
+                    Box::new(move |params, mut cx: AsyncApp| {
+                        let Some(params) =
+                            serde_json::from_value::<types::ResourcesUpdatedParams>(params)
+                                .log_err()
+                        else {
+                            return;
+                        };
+                        let Some(uri) = Url::parse(&params.uri).log_err() else {
+                            return;
+                        };
+                        this.update(&mut cx, |_, cx| {
+                            cx.emit(Event::ResourceUpdated {
+                                server_id: id.clone(),
+                                uri,
+                            })
+                        })
+                        .ok();
+                    })
+                        as Box<dyn 'static + Send + FnMut(serde_json::Value, AsyncApp)>
+                };
+                let create_message_handler = {
+                    let this = this.clone();
+                    let id = id.clone();
+                    Box::new(move |params, cx: AsyncApp| {
+                        let this = this.clone();
+                        let id = id.clone();
+                        cx.spawn(async move |cx| {
+                            sampling::create_message(this, id, params, cx).await
+                        })
+                    }) as RequestHandler
+                };
+                let list_roots_handler = {
+                    let this = this.clone();
+                    Box::new(move |_, cx: AsyncApp| {
+                        let roots = this.read_with(&cx, |this, cx| this.roots(cx));
+                        Task::ready(roots.and_then(|roots| {
+                            Ok(serde_json::to_value(types::ListRootsResponse {
+                                roots,
+                                meta: None,
+                            })?)
+                        }))
+                    }) as RequestHandler
+                };
+                let start = server.clone().start_with_handlers(
+                    vec![(
+                        types::notifications::ResourcesUpdated::METHOD,
+                        resources_updated_handler,
+                    )],
+                    vec![
+                        (
+                            types::requests::CreateMessage::METHOD,
+                            create_message_handler,
+                        ),
+                        (types::requests::ListRoots::METHOD, list_roots_handler),
+                    ],
+                    &cx,
+                );
+                match start.await {
                     Ok(_) => {
                         log::info!("Started {} context server", id);
                         debug_assert!(server.client().is_some());
@@ -481,16 +623,41 @@ impl ContextServerStore {
             });
 
         if let Some(factory) = self.context_server_factory.as_ref() {
-            factory(id, configuration)
-        } else {
-            Arc::new(ContextServer::stdio(
-                id,
-                configuration.command().clone(),
-                root_path,
-            ))
+            return factory(id, configuration);
+        }
+        match configuration.as_ref() {
+            ContextServerConfiguration::Http { endpoint } => {
+                Arc::new(ContextServer::http(id, endpoint.clone(), cx.http_client()))
+            }
+            ContextServerConfiguration::Custom { command }
+            | ContextServerConfiguration::Extension { command, .. } => {
+                Arc::new(ContextServer::stdio(id, command.clone(), root_path))
+            }
         }
     }
 
+    /// Stores the bearer token sent to the context servers served at the URL in the system keychain,
+    /// or deletes it when None, restarting the servers using it.
+    pub fn set_bearer_token(
+        &mut self,
+        url: String,
+        token: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+        cx.spawn(async move |this, cx| {
+            match token {
+                Some(token) => {
+                    credentials_provider
+                        .write_credentials(&url, "Bearer", token.as_bytes(), cx)
+                        .await?
+                }
+                None => credentials_provider.delete_credentials(&url, cx).await?,
+            }
+            this.update(cx, |this, cx| this.available_context_servers_changed(cx))
+        })
+    }
+
     fn resolve_context_server_settings<'a>(
         worktree_store: &'a Entity<WorktreeStore>,
         cx: &'a App,
@@ -636,6 +803,9 @@ mod tests {
     };
     use context_server::test::create_fake_transport;
     use gpui::{AppContext, TestAppContext, UpdateGlobal as _};
+    use language_model::{
+        ConfiguredModel, LanguageModelRegistry, fake_provider::FakeLanguageModel,
+    };
     use serde_json::json;
     use std::{cell::RefCell, path::PathBuf, rc::Rc};
     use util::path;
@@ -1266,6 +1436,7 @@ mod tests {
                         ix += 1;
                         *received_event_count.borrow_mut() += 1;
                     }
+                    _ => {}
                 }
             });
             ServerEvents {
@@ -1276,6 +1447,189 @@ mod tests {
         })
     }
 
+    #[gpui::test]
+    async fn test_context_server_roots(cx: &mut TestAppContext) {
+        let (_fs, project) = setup_context_server_test(cx, json!({"code.rs": ""}), vec![]).await;
+
+        let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+        let store = cx.new(|cx| {
+            ContextServerStore::test(
+                registry.clone(),
+                project.read(cx).worktree_store(),
+                project.downgrade(),
+                cx,
+            )
+        });
+
+        cx.update(|cx| {
+            let roots = store.read(cx).roots(cx);
+            assert_eq!(roots.len(), 1);
+            assert_eq!(roots[0].uri, Url::from_file_path(path!("/test")).unwrap());
+            assert_eq!(roots[0].name.as_deref(), Some("test"));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_sampling_request_allowed(cx: &mut TestAppContext) {
+        let (store, model) = setup_sampling_test(cx).await;
+        let (requests, responses, _subscription) = sampling_events(&store, cx);
+        let result = create_message(&store, cx);
+        cx.run_until_parked();
+
+        let request = requests.borrow_mut().pop().unwrap();
+        assert_eq!(request.model_name.as_ref(), "Fake");
+        assert_eq!(request.request.max_tokens, 100);
+        assert_eq!(model.completion_count(), 0);
+        request.respond(true);
+        cx.run_until_parked();
+
+        let completion = model.pending_completions().pop().unwrap();
+        assert_eq!(completion.max_tokens, Some(100));
+        assert_eq!(completion.messages.len(), 2);
+        assert_eq!(completion.messages[0].role, language_model::Role::System);
+        assert_eq!(completion.messages[0].string_contents(), "Be brief.");
+        assert_eq!(
+            completion.messages[1].string_contents(),
+            "Summarize the logs."
+        );
+        model.send_last_completion_stream_text_chunk("The build ");
+        model.send_last_completion_stream_text_chunk("failed.");
+        model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        let response = responses.borrow_mut().pop().unwrap();
+        assert_eq!(response.text, "The build failed.");
+        let text = response.text.clone();
+        response.accept(text);
+        let result = result.await.unwrap();
+        assert_eq!(result["role"], json!("assistant"));
+        assert_eq!(
+            result["content"],
+            json!({"type": "text", "text": "The build failed."})
+        );
+        assert_eq!(result["model"], json!("fake"));
+    }
+
+    #[gpui::test]
+    async fn test_sampling_response_edited(cx: &mut TestAppContext) {
+        let (store, model) = setup_sampling_test(cx).await;
+        let (requests, responses, _subscription) = sampling_events(&store, cx);
+        let result = create_message(&store, cx);
+        cx.run_until_parked();
+
+        requests.borrow_mut().pop().unwrap().respond(true);
+        cx.run_until_parked();
+        model.send_last_completion_stream_text_chunk("The build failed.");
+        model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        responses
+            .borrow_mut()
+            .pop()
+            .unwrap()
+            .accept("The build failed on Windows.".to_string());
+        let result = result.await.unwrap();
+        assert_eq!(
+            result["content"]["text"],
+            json!("The build failed on Windows.")
+        );
+    }
+
+    #[gpui::test]
+    async fn test_sampling_rejected(cx: &mut TestAppContext) {
+        let (store, model) = setup_sampling_test(cx).await;
+        let (requests, responses, _subscription) = sampling_events(&store, cx);
+
+        // The request is rejected before it's sent to the model.
+        let result = create_message(&store, cx);
+        cx.run_until_parked();
+        requests.borrow_mut().pop().unwrap().respond(false);
+        let error = result.await.unwrap_err();
+        assert_eq!(error.to_string(), "The user rejected the sampling request");
+        assert_eq!(model.completion_count(), 0);
+
+        // The model's answer is rejected, and isn't returned to the server.
+        let result = create_message(&store, cx);
+        cx.run_until_parked();
+        requests.borrow_mut().pop().unwrap().respond(true);
+        cx.run_until_parked();
+        model.send_last_completion_stream_text_chunk("The build failed.");
+        model.end_last_completion_stream();
+        cx.run_until_parked();
+        responses.borrow_mut().pop().unwrap().reject();
+        let error = result.await.unwrap_err();
+        assert_eq!(error.to_string(), "The user rejected the sampled message");
+    }
+
+    async fn setup_sampling_test(
+        cx: &mut TestAppContext,
+    ) -> (Entity<ContextServerStore>, Arc<FakeLanguageModel>) {
+        let (_fs, project) = setup_context_server_test(cx, json!({"code.rs": ""}), vec![]).await;
+        let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+        let store = cx.new(|cx| {
+            ContextServerStore::test(
+                registry.clone(),
+                project.read(cx).worktree_store(),
+                project.downgrade(),
+                cx,
+            )
+        });
+
+        let model = Arc::new(FakeLanguageModel::default());
+        cx.update(|cx| {
+            let provider = LanguageModelRegistry::test(cx);
+            LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+                registry.set_default_model(
+                    Some(ConfiguredModel {
+                        provider: Arc::new(provider),
+                        model: model.clone(),
+                    }),
+                    cx,
+                )
+            });
+        });
+        (store, model)
+    }
+
+    fn sampling_events(
+        store: &Entity<ContextServerStore>,
+        cx: &mut TestAppContext,
+    ) -> (
+        Rc<RefCell<Vec<SamplingRequest>>>,
+        Rc<RefCell<Vec<SamplingResponse>>>,
+        Subscription,
+    ) {
+        let requests = Rc::new(RefCell::new(Vec::new()));
+        let responses = Rc::new(RefCell::new(Vec::new()));
+        let subscription = cx.update(|cx| {
+            let requests = requests.clone();
+            let responses = responses.clone();
+            cx.subscribe(store, move |_, event, _| match event {
+                Event::SamplingRequested(request) => requests.borrow_mut().push(request.clone()),
+                Event::SamplingCompleted(response) => responses.borrow_mut().push(response.clone()),
+                _ => {}
+            })
+        });
+        (requests, responses, subscription)
+    }
+
+    fn create_message(
+        store: &Entity<ContextServerStore>,
+        cx: &mut TestAppContext,
+    ) -> Task<Result<serde_json::Value>> {
+        let store = store.downgrade();
+        cx.spawn(|mut cx| async move {
+            let params = json!({
+                "messages": [
+                    {"role": "user", "content": {"type": "text", "text": "Summarize the logs."}}
+                ],
+                "systemPrompt": "Be brief.",
+                "maxTokens": 100,
+            });
+            sampling::create_message(store, ContextServerId("mcp-1".into()), params, &mut cx).await
+        })
+    }
+
     async fn setup_context_server_test(
         cx: &mut TestAppContext,
         files: serde_json::Value,
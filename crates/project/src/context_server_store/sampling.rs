@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use base64::Engine as _;
+use context_server::{ContextServerId, types};
+use futures::StreamExt as _;
+use gpui::{AsyncApp, Image, ImageFormat, SharedString, WeakEntity};
+use language_model::{
+    LanguageModel, LanguageModelImage, LanguageModelRegistry, LanguageModelRequest,
+    LanguageModelRequestMessage, MessageContent, Role,
+};
+use smol::channel::Sender;
+
+use super::{ContextServerStore, Event};
+
+/// A `sampling/createMessage` request from a context server, waiting for the user to allow it.
+#[derive(Clone, Debug)]
+pub struct SamplingRequest {
+    pub server_id: ContextServerId,
+    /// The name of the model the request will be sent to.
+    pub model_name: SharedString,
+    pub request: Arc<types::CreateMessageRequest>,
+    response_tx: Sender<bool>,
+}
+
+impl SamplingRequest {
+    pub fn respond(self, allow: bool) {
+        self.response_tx.try_send(allow).ok();
+    }
+}
+
+/// The model's answer to a [`SamplingRequest`], waiting for the user to review it
+/// before it's returned to the context server.
+#[derive(Clone, Debug)]
+pub struct SamplingResponse {
+    pub server_id: ContextServerId,
+    pub model_name: SharedString,
+    pub text: String,
+    response_tx: Sender<Option<String>>,
+}
+
+impl SamplingResponse {
+    /// Returns `text`, which the user may have edited, to the context server.
+    pub fn accept(self, text: String) {
+        self.response_tx.try_send(Some(text)).ok();
+    }
+
+    pub fn reject(self) {
+        self.response_tx.try_send(None).ok();
+    }
+}
+
+/// Handles a `sampling/createMessage` request by asking the user for consent, running it
+/// against the default language model and letting the user review the result.
+///
+/// Requests are rejected when nobody responds to the emitted events, e.g. because no
+/// workspace is showing the project.
+pub(super) async fn create_message(
+    this: WeakEntity<ContextServerStore>,
+    server_id: ContextServerId,
+    params: serde_json::Value,
+    cx: &mut AsyncApp,
+) -> Result<serde_json::Value> {
+    let request = Arc::new(serde_json::from_value::<types::CreateMessageRequest>(
+        params,
+    )?);
+    let model = cx
+        .update(|cx| LanguageModelRegistry::read_global(cx).default_model())?
+        .context("No language model is configured")?
+        .model;
+    let model_name = model.name().0;
+
+    let (response_tx, response_rx) = smol::channel::bounded(1);
+    this.update(cx, |_, cx| {
+        cx.emit(Event::SamplingRequested(SamplingRequest {
+            server_id: server_id.clone(),
+            model_name: model_name.clone(),
+            request: request.clone(),
+            response_tx,
+        }))
+    })?;
+    anyhow::ensure!(
+        response_rx.recv().await.unwrap_or(false),
+        "The user rejected the sampling request"
+    );
+
+    let text = complete(model.clone(), &request, cx).await?;
+
+    let (response_tx, response_rx) = smol::channel::bounded(1);
+    this.update(cx, |_, cx| {
+        cx.emit(Event::SamplingCompleted(SamplingResponse {
+            server_id,
+            model_name,
+            text,
+            response_tx,
+        }))
+    })?;
+    let text = response_rx
+        .recv()
+        .await
+        .ok()
+        .flatten()
+        .context("The user rejected the sampled message")?;
+
+    Ok(serde_json::to_value(types::CreateMessageResult {
+        role: types::Role::Assistant,
+        content: types::MessageContent::Text {
+            text,
+            annotations: None,
+        },
+        model: model.id().0.to_string(),
+        stop_reason: Some("endTurn".to_string()),
+    })?)
+}
+
+async fn complete(
+    model: Arc<dyn LanguageModel>,
+    request: &types::CreateMessageRequest,
+    cx: &mut AsyncApp,
+) -> Result<String> {
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = request.system_prompt.as_ref() {
+        messages.push(LanguageModelRequestMessage {
+            role: Role::System,
+            content: vec![system_prompt.clone().into()],
+            cache: false,
+        });
+    }
+    for message in &request.messages {
+        let role = match message.role {
+            types::Role::User => Role::User,
+            types::Role::Assistant => Role::Assistant,
+        };
+        let content = match &message.content {
+            types::MessageContent::Text { text, .. } => MessageContent::Text(text.clone()),
+            types::MessageContent::Image {
+                data, mime_type, ..
+            } => {
+                let format = ImageFormat::from_mime_type(mime_type)
+                    .with_context(|| format!("Unsupported image type {mime_type}"))?;
+                let bytes = base64::engine::general_purpose::STANDARD.decode(data)?;
+                let image = Arc::new(Image::from_bytes(format, bytes));
+                let image = cx
+                    .update(|cx| LanguageModelImage::from_image(image, cx))?
+                    .await
+                    .context("Failed to process image")?;
+                MessageContent::Image(image)
+            }
+            types::MessageContent::Audio { .. } => {
+                anyhow::bail!("Audio content is not supported")
+            }
+            types::MessageContent::Resource { resource, .. } => {
+                MessageContent::Text(format!("[Resource {}]", resource.uri))
+            }
+        };
+        messages.push(LanguageModelRequestMessage {
+            role,
+            content: vec![content],
+            cache: false,
+        });
+    }
+
+    let request = LanguageModelRequest {
+        thread_id: None,
+        prompt_id: None,
+        intent: None,
+        mode: None,
+        messages,
+        tools: Vec::new(),
+        tool_choice: None,
+        stop: request.stop_sequences.clone().unwrap_or_default(),
+        temperature: request.temperature.map(|temperature| temperature as f32),
+        max_tokens: Some(request.max_tokens as u64),
+        thinking_allowed: false,
+    };
+
+    let mut stream = model.stream_completion_text(request, cx).await?.stream;
+    let mut text = String::new();
+    while let Some(chunk) = stream.next().await {
+        text.push_str(&chunk?);
+    }
+    Ok(text)
+}
@@ -187,6 +187,7 @@ impl DapLocator for GoLocator {
                     build: None,
                     config: config,
                     tcp_connection: None,
+                    inputs: Vec::new(),
                 })
             }
             "run" => {
@@ -231,6 +232,7 @@ impl DapLocator for GoLocator {
                     build: None,
                     config,
                     tcp_connection: None,
+                    inputs: Vec::new(),
                 })
             }
             _ => None,
@@ -246,7 +248,10 @@ impl DapLocator for GoLocator {
 mod tests {
     use super::*;
     use gpui::TestAppContext;
-    use task::{HideStrategy, RevealStrategy, RevealTarget, Shell, TaskTemplate};
+    use task::{
+        BackgroundPatterns, DependsOrder, HideStrategy, RevealStrategy, RevealTarget, Shell,
+        TaskTemplate,
+    };
 
     #[gpui::test]
     async fn test_create_scenario_for_go_build(_: &mut TestAppContext) {
@@ -266,6 +271,12 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: DependsOrder::Parallel,
+            problem_matcher: vec![],
+            is_background: false,
+            background_patterns: BackgroundPatterns::default(),
+            inputs: Vec::new(),
         };
 
         let scenario = locator
@@ -293,6 +304,12 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: DependsOrder::Parallel,
+            problem_matcher: vec![],
+            is_background: false,
+            background_patterns: BackgroundPatterns::default(),
+            inputs: Vec::new(),
         };
 
         let scenario = locator
@@ -431,6 +448,12 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: DependsOrder::Parallel,
+            problem_matcher: vec![],
+            is_background: false,
+            background_patterns: BackgroundPatterns::default(),
+            inputs: Vec::new(),
         };
 
         let scenario = locator
@@ -97,6 +97,7 @@ impl DapLocator for PythonLocator {
             build: None,
             config,
             tcp_connection: None,
+            inputs: Vec::new(),
         })
     }
 
@@ -46,7 +46,7 @@ use rpc::{
     AnyProtoClient, TypedEnvelope,
     proto::{self, FromProto, SSH_PROJECT_ID, ToProto, git_reset, split_repository_update},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
     collections::{BTreeSet, VecDeque},
@@ -164,7 +164,7 @@ struct LocalDownstreamState {
     _task: Task<Result<()>>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GitStoreCheckpoint {
     checkpoints_by_work_dir_abs_path: HashMap<Arc<Path>, GitRepositoryCheckpoint>,
 }
@@ -901,6 +901,82 @@ impl GitStore {
         })
     }
 
+    /// Returns the diff of the changes from one checkpoint to another, in the repositories they
+    /// both include.
+    pub fn diff_checkpoints(
+        &self,
+        base: GitStoreCheckpoint,
+        mut target: GitStoreCheckpoint,
+        cx: &mut App,
+    ) -> Task<Result<String>> {
+        let repositories_by_work_dir_abs_path = self
+            .repositories
+            .values()
+            .map(|repo| (repo.read(cx).snapshot.work_directory_abs_path.clone(), repo))
+            .collect::<HashMap<_, _>>();
+
+        let mut tasks = Vec::new();
+        for (work_dir_abs_path, base_checkpoint) in base.checkpoints_by_work_dir_abs_path {
+            let Some(target_checkpoint) = target
+                .checkpoints_by_work_dir_abs_path
+                .remove(&work_dir_abs_path)
+            else {
+                continue;
+            };
+            if let Some(repository) = repositories_by_work_dir_abs_path.get(&work_dir_abs_path) {
+                let diff = repository.update(cx, |repository, _| {
+                    repository.diff_checkpoints(base_checkpoint, target_checkpoint)
+                });
+                tasks.push(async move { diff.await? });
+            }
+        }
+        cx.background_spawn(async move { Ok(future::try_join_all(tasks).await?.concat()) })
+    }
+
+    /// Points the ref `{ref_prefix}/{sha}` at the checkpoint in each of its repositories, so that
+    /// git doesn't garbage-collect it.
+    pub fn anchor_checkpoint(
+        &self,
+        checkpoint: GitStoreCheckpoint,
+        ref_prefix: &str,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let repositories_by_work_dir_abs_path = self
+            .repositories
+            .values()
+            .map(|repo| (repo.read(cx).snapshot.work_directory_abs_path.clone(), repo))
+            .collect::<HashMap<_, _>>();
+
+        let mut tasks = Vec::new();
+        for (work_dir_abs_path, checkpoint) in checkpoint.checkpoints_by_work_dir_abs_path {
+            if let Some(repository) = repositories_by_work_dir_abs_path.get(&work_dir_abs_path) {
+                let name = format!("{ref_prefix}/{}", checkpoint.commit_sha);
+                let anchor = repository.update(cx, |repository, _| {
+                    repository.anchor_checkpoint(name, checkpoint)
+                });
+                tasks.push(async move { anchor.await? });
+            }
+        }
+        cx.background_spawn(async move {
+            future::try_join_all(tasks).await?;
+            Ok(())
+        })
+    }
+
+    /// Deletes the refs under `prefix` in every repository.
+    pub fn delete_refs(&self, prefix: String, cx: &mut App) -> Task<Result<()>> {
+        let mut tasks = Vec::new();
+        for repository in self.repositories.values() {
+            let delete =
+                repository.update(cx, |repository, _| repository.delete_refs(prefix.clone()));
+            tasks.push(async move { delete.await? });
+        }
+        cx.background_spawn(async move {
+            future::try_join_all(tasks).await?;
+            Ok(())
+        })
+    }
+
     /// Blames a buffer.
     pub fn blame_buffer(
         &self,
@@ -4245,6 +4321,30 @@ impl Repository {
         })
     }
 
+    pub fn anchor_checkpoint(
+        &mut self,
+        name: String,
+        checkpoint: GitRepositoryCheckpoint,
+    ) -> oneshot::Receiver<Result<()>> {
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => {
+                    backend.anchor_checkpoint(name, checkpoint).await
+                }
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
+    pub fn delete_refs(&mut self, prefix: String) -> oneshot::Receiver<Result<()>> {
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.delete_refs(prefix).await,
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
     fn schedule_scan(
         &mut self,
         updates_tx: Option<mpsc::UnboundedSender<DownstreamUpdate>>,
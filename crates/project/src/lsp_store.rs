@@ -97,6 +97,7 @@ use std::{
     time::{Duration, Instant},
 };
 use sum_tree::Dimensions;
+use task::{ProblemSeverity, TaskProblem};
 use text::{Anchor, BufferId, LineEnding, OffsetRangeExt};
 use url::Url;
 use util::{
@@ -178,6 +179,15 @@ pub struct LocalLspStore {
     registered_buffers: HashMap<BufferId, usize>,
     buffers_opened_in_servers: HashMap<BufferId, HashSet<LanguageServerId>>,
     buffer_pull_diagnostics_result_ids: HashMap<LanguageServerId, HashMap<PathBuf, Option<String>>>,
+    /// Diagnostics, found by the problem matchers in the output of the tasks, keyed by the task label.
+    task_diagnostics: HashMap<String, TaskDiagnostics>,
+}
+
+/// Problem matchers of a task are not a language server, yet every task gets a server id of its own,
+/// so that its diagnostics are stored and replaced separately from others.
+struct TaskDiagnostics {
+    server_id: LanguageServerId,
+    paths: HashSet<PathBuf>,
 }
 
 impl LocalLspStore {
@@ -3808,6 +3818,7 @@ impl LspStore {
                 registered_buffers: HashMap::default(),
                 buffers_opened_in_servers: HashMap::default(),
                 buffer_pull_diagnostics_result_ids: HashMap::default(),
+                task_diagnostics: HashMap::default(),
             }),
             last_formatting_failure: None,
             downstream_client: None,
@@ -10649,6 +10660,89 @@ impl LspStore {
         )
     }
 
+    /// Replaces all diagnostics, reported for the task with the given label earlier, with the new problems found in its output.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_label: &str,
+        problems: Vec<(PathBuf, TaskProblem)>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let languages = self.languages.clone();
+        let local = self
+            .as_local_mut()
+            .context("task diagnostics are only reported in local projects")?;
+        let task_diagnostics = local
+            .task_diagnostics
+            .entry(task_label.to_string())
+            .or_insert_with(|| TaskDiagnostics {
+                server_id: languages.next_language_server_id(),
+                paths: HashSet::default(),
+            });
+        let server_id = task_diagnostics.server_id;
+        let old_paths = mem::take(&mut task_diagnostics.paths);
+
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<_>>::default();
+        for (abs_path, problem) in problems {
+            let start = PointUtf16::new(
+                problem.line.saturating_sub(1),
+                problem.column.unwrap_or(1).saturating_sub(1),
+            );
+            let end = match (problem.end_line, problem.end_column, problem.column) {
+                (end_line, Some(end_column), _) => PointUtf16::new(
+                    end_line.unwrap_or(problem.line).saturating_sub(1),
+                    end_column.saturating_sub(1),
+                ),
+                (_, None, Some(_)) => start,
+                // Without a column, highlight the entire line; the end gets clipped to the line length.
+                (_, None, None) => PointUtf16::new(start.row, u32::MAX),
+            };
+            let group_id = post_inc(&mut local.next_diagnostic_group_id);
+            diagnostics_by_path
+                .entry(abs_path)
+                .or_default()
+                .push(DiagnosticEntry {
+                    range: Unclipped(start)..Unclipped(end),
+                    diagnostic: Diagnostic {
+                        // Tell apart the diagnostics of the tasks using the same problem matcher.
+                        source: Some(format!("{} ({task_label})", problem.source)),
+                        source_kind: DiagnosticSourceKind::Other,
+                        code: problem.code.map(lsp::NumberOrString::String),
+                        severity: match problem.severity {
+                            ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                            ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                            ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                            ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+                        },
+                        message: problem.message,
+                        group_id,
+                        is_primary: true,
+                        underline: true,
+                        ..Diagnostic::default()
+                    },
+                });
+        }
+
+        let local = self.as_local_mut().context("checked above")?;
+        if let Some(task_diagnostics) = local.task_diagnostics.get_mut(task_label) {
+            task_diagnostics.paths = diagnostics_by_path.keys().cloned().collect();
+        }
+        for old_path in old_paths {
+            diagnostics_by_path.entry(old_path).or_default();
+        }
+        for (abs_path, diagnostics) in diagnostics_by_path {
+            self.merge_diagnostic_entries(
+                server_id,
+                abs_path,
+                None,
+                None,
+                diagnostics,
+                |_, _, _| false,
+                cx,
+            )?;
+        }
+        Ok(())
+    }
+
     pub fn update_diagnostics(
         &mut self,
         language_server_id: LanguageServerId,
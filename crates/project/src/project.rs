@@ -132,7 +132,7 @@ pub use language::Location;
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, DebugScenarioContext, Inventory, TaskContexts,
-    TaskSourceKind,
+    TaskDependencyGraph, TaskDependencyNode, TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
@@ -329,6 +329,10 @@ pub enum Event {
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
     AgentLocationChanged,
+    /// A background task was started, has finished or changed its status, see [`Project::background_tasks`].
+    BackgroundTasksChanged,
+    /// The host has shared a terminal, mirrored by the given terminal, see [`Project::share_terminal`].
+    TerminalShared(Entity<terminal::Terminal>),
 }
 
 pub struct AgentLocationChanged;
@@ -1002,6 +1006,10 @@ impl Project {
         client.add_entity_request_handler(Self::handle_open_buffer_by_path);
         client.add_entity_request_handler(Self::handle_open_new_buffer);
         client.add_entity_message_handler(Self::handle_create_buffer_for_peer);
+        client.add_entity_message_handler(Self::handle_share_terminal);
+        client.add_entity_message_handler(Self::handle_unshare_terminal);
+        client.add_entity_message_handler(Self::handle_update_shared_terminal);
+        client.add_entity_request_handler(Self::handle_shared_terminal_input);
 
         WorktreeStore::init(&client);
         BufferStore::init(&client);
@@ -1160,9 +1168,7 @@ impl Project {
 
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
                 node: Some(node),
                 search_history: Self::new_search_history(),
                 environment,
@@ -1327,9 +1333,7 @@ impl Project {
                 ssh_client: Some(ssh.clone()),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
                 node: Some(node),
                 search_history: Self::new_search_history(),
                 environment,
@@ -1585,9 +1589,7 @@ impl Project {
                 git_store: git_store.clone(),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
                 node: None,
                 search_history: Self::new_search_history(),
                 search_included_history: Self::new_search_history(),
@@ -2365,6 +2367,7 @@ impl Project {
             self.git_store.update(cx, |git_store, cx| {
                 git_store.unshared(cx);
             });
+            self.unshare_terminals();
 
             self.client
                 .send(proto::UnshareProject {
@@ -2420,6 +2423,7 @@ impl Project {
             });
             self.lsp_store
                 .update(cx, |lsp_store, _cx| lsp_store.disconnected_from_host());
+            self.close_mirrored_terminals(cx);
         }
     }
 
@@ -3393,7 +3397,19 @@ impl Project {
         cx: &mut Context<Self>,
     ) -> Task<Result<ProjectTransaction>> {
         self.buffer_store.update(cx, |buffer_store, cx| {
-            buffer_store.reload_buffers(buffers, push_to_history, cx)
+            buffer_store.reload_buffers(buffers, None, push_to_history, cx)
+        })
+    }
+
+    /// Reloads the buffer from disk, decoding its file with the given encoding, which it's then saved with.
+    pub fn reload_buffer_with_encoding(
+        &self,
+        buffer: Entity<Buffer>,
+        encoding: Encoding,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        self.buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.reload_buffers([buffer].into_iter().collect(), Some(encoding), true, cx)
         })
     }
 
@@ -4489,6 +4505,7 @@ impl Project {
                 buffer_store.forget_shared_buffers_for(&collaborator.peer_id);
             });
             this.breakpoint_store.read(cx).broadcast();
+            this.reshare_terminals(cx);
             cx.emit(Event::CollaboratorJoined(collaborator.peer_id));
             this.collaborators
                 .insert(collaborator.peer_id, collaborator);
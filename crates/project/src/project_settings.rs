@@ -110,6 +110,18 @@ pub enum ContextServerSettings {
         /// are supported.
         settings: serde_json::Value,
     },
+    Http {
+        /// Whether the context server is enabled.
+        #[serde(default = "default_true")]
+        enabled: bool,
+        /// The URL of the context server's Streamable HTTP or SSE endpoint.
+        url: String,
+        /// The headers to send with every request.
+        ///
+        /// A bearer token for the URL, stored in the system keychain, is sent as well.
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
 }
 
 /// Common language server settings.
@@ -134,6 +146,7 @@ impl ContextServerSettings {
         match self {
             ContextServerSettings::Custom { enabled, .. } => *enabled,
             ContextServerSettings::Extension { enabled, .. } => *enabled,
+            ContextServerSettings::Http { enabled, .. } => *enabled,
         }
     }
 
@@ -141,6 +154,7 @@ impl ContextServerSettings {
         match self {
             ContextServerSettings::Custom { enabled: e, .. } => *e = enabled,
             ContextServerSettings::Extension { enabled: e, .. } => *e = enabled,
+            ContextServerSettings::Http { enabled: e, .. } => *e = enabled,
         }
     }
 }
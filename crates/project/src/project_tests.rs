@@ -8,7 +8,7 @@ use buffer_diff::{
     BufferDiffEvent, CALCULATE_DIFF_TASK, DiffHunkSecondaryStatus, DiffHunkStatus,
     DiffHunkStatusKind, assert_hunks,
 };
-use fs::FakeFs;
+use fs::{Encoding, FakeFs};
 use futures::{StreamExt, future};
 use git::{
     GitHostingProviderRegistry,
@@ -2512,6 +2512,130 @@ async fn test_diagnostics_from_multiple_language_servers(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_task_problems_as_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({ "src": { "main.rs": "fn main() {\n    let x: u32 = \"\";\n}\n" } }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/src/main.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let run_task = |task_label: &str, output: &[u8], cx: &mut gpui::TestAppContext| {
+        let (input_tx, _input_rx) = futures::channel::mpsc::unbounded();
+        let (completion_tx, _completion_rx) = smol::channel::bounded(1);
+        let terminal = cx.new(|cx| {
+            terminal::TerminalBuilder::new_fake(
+                task_label.to_string().into(),
+                input_tx,
+                completion_tx,
+            )
+            .with_problem_matchers(vec![task::ProblemMatcher::preset("$rustc").unwrap()])
+            .subscribe(cx)
+        });
+        project.update(cx, |project, cx| {
+            project.report_task_problems(
+                task_label.to_string(),
+                Some(Path::new(path!("/dir")).into()),
+                &terminal,
+                cx,
+            )
+        });
+        terminal.update(cx, |terminal, cx| terminal.write_output(output, cx));
+        cx.run_until_parked();
+        terminal
+    };
+    let diagnostics = |cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .map(|entry| {
+                    (
+                        entry.range,
+                        entry.diagnostic.severity,
+                        entry.diagnostic.source,
+                        entry.diagnostic.message,
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let _check = run_task(
+        "cargo check",
+        b"error[E0308]: mismatched types\r\n  --> src/main.rs:2:18\r\n",
+        cx,
+    );
+    assert_eq!(
+        diagnostics(cx),
+        [(
+            Point::new(1, 17)..Point::new(1, 17),
+            DiagnosticSeverity::ERROR,
+            Some("rustc (cargo check)".to_string()),
+            "mismatched types".to_string(),
+        )]
+    );
+
+    // Another task using the same problem matcher reports its own diagnostics.
+    let _clippy = run_task(
+        "cargo clippy",
+        b"warning: unused variable: `x`\r\n --> src/main.rs:2:9\r\n",
+        cx,
+    );
+    assert_eq!(
+        diagnostics(cx),
+        [
+            (
+                Point::new(1, 8)..Point::new(1, 8),
+                DiagnosticSeverity::WARNING,
+                Some("rustc (cargo clippy)".to_string()),
+                "unused variable: `x`".to_string(),
+            ),
+            (
+                Point::new(1, 17)..Point::new(1, 17),
+                DiagnosticSeverity::ERROR,
+                Some("rustc (cargo check)".to_string()),
+                "mismatched types".to_string(),
+            ),
+        ]
+    );
+
+    // Rerunning a task replaces the diagnostics of its previous run.
+    let _check = run_task(
+        "cargo check",
+        b"error[E0425]: cannot find value `y` in this scope\r\n --> src/main.rs:3:1\r\n",
+        cx,
+    );
+    assert_eq!(
+        diagnostics(cx),
+        [
+            (
+                Point::new(1, 8)..Point::new(1, 8),
+                DiagnosticSeverity::WARNING,
+                Some("rustc (cargo clippy)".to_string()),
+                "unused variable: `x`".to_string(),
+            ),
+            (
+                Point::new(2, 0)..Point::new(2, 0),
+                DiagnosticSeverity::ERROR,
+                Some("rustc (cargo check)".to_string()),
+                "cannot find value `y` in this scope".to_string(),
+            ),
+        ]
+    );
+}
+
 #[gpui::test]
 async fn test_edits_from_lsp2_with_past_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3627,6 +3751,74 @@ async fn test_save_file(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_save_file_with_encoding(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({})).await;
+    fs.insert_file(path!("/dir/latin1.txt"), b"caf\xE9 cr\xE8me\n".to_vec())
+        .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer(path!("/dir/latin1.txt"), cx)
+        })
+        .await
+        .unwrap();
+    let latin1 = Encoding::for_name("latin1", false).unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "caf\u{e9} cr\u{e8}me\n");
+        assert_eq!(buffer.encoding(), latin1);
+        buffer.edit([(0..0, "\u{e0} la ")], None, cx);
+    });
+
+    // The file keeps its encoding when saved.
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new(path!("/dir/latin1.txt")))
+            .await
+            .unwrap(),
+        b"\xE0 la caf\xE9 cr\xE8me\n"
+    );
+
+    // Saving with another encoding re-encodes the same text.
+    let utf16 = Encoding::for_name("utf-16le", true).unwrap();
+    buffer.update(cx, |buffer, cx| buffer.set_encoding(utf16, cx));
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    let bytes = fs
+        .load_bytes(Path::new(path!("/dir/latin1.txt")))
+        .await
+        .unwrap();
+    assert_eq!(
+        bytes,
+        utf16.encode("\u{e0} la caf\u{e9} cr\u{e8}me\n").unwrap()
+    );
+
+    // Reopening with the wrong encoding shows the file as decoded with it.
+    fs.insert_file(path!("/dir/latin1.txt"), "caf\u{e9}\n".as_bytes().to_vec())
+        .await;
+    cx.run_until_parked();
+    project
+        .update(cx, |project, cx| {
+            project.reload_buffer_with_encoding(buffer.clone(), latin1, cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "caf\u{c3}\u{a9}\n");
+        assert_eq!(buffer.encoding(), latin1);
+        assert!(!buffer.is_dirty());
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_save_file_spawns_language_server(cx: &mut gpui::TestAppContext) {
     // Issue: #24349
@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result, bail};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use fs::Fs;
@@ -43,6 +43,8 @@ pub struct Inventory {
     fs: Arc<dyn Fs>,
     last_scheduled_tasks: VecDeque<(TaskSourceKind, ResolvedTask)>,
     last_scheduled_scenarios: VecDeque<(DebugScenario, DebugScenarioContext)>,
+    /// Values of the task inputs, entered by the user for the task templates with the corresponding labels.
+    last_task_input_values: HashMap<String, HashMap<String, String>>,
     templates_from_settings: InventoryFor<TaskTemplate>,
     scenarios_from_settings: InventoryFor<DebugScenario>,
 }
@@ -247,6 +249,7 @@ impl Inventory {
             fs,
             last_scheduled_tasks: VecDeque::default(),
             last_scheduled_scenarios: VecDeque::default(),
+            last_task_input_values: HashMap::default(),
             templates_from_settings: InventoryFor::default(),
             scenarios_from_settings: InventoryFor::default(),
         })
@@ -376,6 +379,21 @@ impl Inventory {
         })
     }
 
+    /// Resolves all tasks the given task transitively depends on, see [`TaskDependencyGraph::new`].
+    /// Dependencies are looked up among the worktree and global tasks.
+    pub fn task_dependency_graph(
+        &self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        worktree_id: Option<WorktreeId>,
+        cx: &App,
+    ) -> Task<Result<TaskDependencyGraph>> {
+        let tasks = self.list_tasks(None, None, worktree_id, cx);
+        cx.background_spawn(async move {
+            TaskDependencyGraph::new(task_source_kind, resolved_task, &tasks.await)
+        })
+    }
+
     /// Pulls its task sources relevant to the worktree and the language given,
     /// returns all task templates with their source kinds, worktree tasks first, language tasks second
     /// and global tasks last. No specific order inside source kinds groups.
@@ -607,6 +625,23 @@ impl Inventory {
         }
     }
 
+    /// Values of the inputs, last entered for the task template with the label given.
+    pub fn last_task_input_values(&self, task_label: &str) -> Option<&HashMap<String, String>> {
+        self.last_task_input_values.get(task_label)
+    }
+
+    /// Remembers the values of the inputs, to suggest them when the same task template is spawned again.
+    pub fn task_input_values_entered(
+        &mut self,
+        task_label: String,
+        input_values: HashMap<String, String>,
+    ) {
+        self.last_task_input_values
+            .entry(task_label)
+            .or_default()
+            .extend(input_values);
+    }
+
     /// Deletes a resolved task from history, using its id.
     /// A similar may still resurface in `used_and_current_resolved_tasks` when its [`TaskTemplate`] is resolved again.
     pub fn delete_previously_used(&mut self, id: &TaskId) {
@@ -804,6 +839,117 @@ impl Inventory {
     }
 }
 
+/// A task together with all tasks it transitively depends on, see [`TaskTemplate::depends_on`].
+#[derive(Debug, Clone)]
+pub struct TaskDependencyGraph {
+    /// All tasks of the graph, ordered so that every task comes after its dependencies.
+    /// Every task is present once, even if multiple tasks depend on it.
+    /// The last task is the one the graph was created for.
+    pub tasks: Vec<TaskDependencyNode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskDependencyNode {
+    pub source_kind: TaskSourceKind,
+    pub task: ResolvedTask,
+    /// Indices of the task's dependencies in [`TaskDependencyGraph::tasks`], in the order they are listed in the template.
+    pub dependencies: Vec<usize>,
+}
+
+impl TaskDependencyGraph {
+    /// Looks up the dependencies of the task given among the `templates` by their labels,
+    /// and resolves them with the same [`TaskContext`] as the task itself.
+    /// Fails if any dependency is missing or cannot be resolved, or if the dependencies form a cycle.
+    pub fn new(
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        templates: &[(TaskSourceKind, TaskTemplate)],
+    ) -> Result<Self> {
+        let mut builder = TaskDependencyGraphBuilder {
+            templates,
+            tasks: Vec::new(),
+            indices_by_label: HashMap::default(),
+            labels_in_progress: Vec::new(),
+        };
+        builder.add_task(task_source_kind, resolved_task)?;
+        Ok(Self {
+            tasks: builder.tasks,
+        })
+    }
+
+    /// The task the graph was created for.
+    pub fn root(&self) -> &TaskDependencyNode {
+        self.tasks
+            .last()
+            .expect("dependency graph always contains its root task")
+    }
+}
+
+struct TaskDependencyGraphBuilder<'a> {
+    templates: &'a [(TaskSourceKind, TaskTemplate)],
+    tasks: Vec<TaskDependencyNode>,
+    indices_by_label: HashMap<String, usize>,
+    labels_in_progress: Vec<String>,
+}
+
+impl TaskDependencyGraphBuilder<'_> {
+    fn add_task(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+    ) -> Result<usize> {
+        let label = resolved_task.original_task().label.clone();
+        if let Some(ix) = self.indices_by_label.get(&label) {
+            return Ok(*ix);
+        }
+        if let Some(cycle_start) = self
+            .labels_in_progress
+            .iter()
+            .position(|in_progress| in_progress == &label)
+        {
+            let cycle = self.labels_in_progress[cycle_start..]
+                .iter()
+                .chain(Some(&label))
+                .map(|label| format!("`{label}`"))
+                .join(" -> ");
+            bail!("Task dependencies form a cycle: {cycle}");
+        }
+
+        self.labels_in_progress.push(label.clone());
+        let mut dependencies = Vec::new();
+        for dependency_label in &resolved_task.original_task().depends_on {
+            let (dependency_source_kind, dependency_template) = self
+                .templates
+                .iter()
+                .find(|(_, template)| &template.label == dependency_label)
+                .with_context(|| {
+                    format!("Task `{label}` depends on an unknown task `{dependency_label}`")
+                })?;
+            let dependency = dependency_template
+                .resolve_task(
+                    &dependency_source_kind.to_id_base(),
+                    resolved_task.task_context(),
+                )
+                .with_context(|| {
+                    format!(
+                        "Failed to resolve task `{dependency_label}`, a dependency of `{label}`"
+                    )
+                })?;
+            dependencies.push(self.add_task(dependency_source_kind.clone(), dependency)?);
+        }
+        self.labels_in_progress.pop();
+
+        let ix = self.tasks.len();
+        self.tasks.push(TaskDependencyNode {
+            source_kind: task_source_kind,
+            task: resolved_task,
+            dependencies,
+        });
+        self.indices_by_label.insert(label, ix);
+        Ok(ix)
+    }
+}
+
 fn task_lru_comparator(
     (kind_a, task_a, lru_score_a): &(TaskSourceKind, ResolvedTask, u32),
     (kind_b, task_b, lru_score_b): &(TaskSourceKind, ResolvedTask, u32),
@@ -1560,6 +1706,95 @@ mod tests {
         TaskStore::init(None);
     }
 
+    #[gpui::test]
+    async fn test_task_dependency_graph(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let inventory = cx.update(|cx| Inventory::new(fs, cx));
+        inventory.update(cx, |inventory, _| {
+            inventory
+                .update_file_based_tasks(
+                    TaskSettingsLocation::Global(tasks_file()),
+                    Some(
+                        &json!([
+                            {
+                                "label": "all",
+                                "depends_on": ["server", "client"],
+                                "depends_order": "sequence"
+                            },
+                            { "label": "server", "command": "echo", "depends_on": ["install"] },
+                            { "label": "client", "command": "echo", "depends_on": ["install"] },
+                            { "label": "install", "command": "echo" },
+                            { "label": "cycle_a", "command": "echo", "depends_on": ["cycle_b"] },
+                            { "label": "cycle_b", "command": "echo", "depends_on": ["cycle_a"] },
+                            { "label": "broken", "command": "echo", "depends_on": ["missing"] },
+                        ])
+                        .to_string(),
+                    ),
+                )
+                .unwrap();
+        });
+
+        let graph = task_dependency_graph(&inventory, "all", cx).await.unwrap();
+        assert_eq!(
+            graph
+                .tasks
+                .iter()
+                .map(|node| (
+                    node.task.original_task().label.as_str(),
+                    node.dependencies.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("install", vec![]),
+                ("server", vec![0]),
+                ("client", vec![0]),
+                ("all", vec![1, 2]),
+            ],
+            "Shared dependencies should be resolved once, before all tasks depending on them"
+        );
+        assert!(graph.root().task.original_task().is_compound());
+
+        let error = task_dependency_graph(&inventory, "cycle_a", cx)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task dependencies form a cycle: `cycle_a` -> `cycle_b` -> `cycle_a`"
+        );
+
+        let error = task_dependency_graph(&inventory, "broken", cx)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task `broken` depends on an unknown task `missing`"
+        );
+    }
+
+    async fn task_dependency_graph(
+        inventory: &Entity<Inventory>,
+        task_name: &str,
+        cx: &mut TestAppContext,
+    ) -> Result<TaskDependencyGraph> {
+        let (task_source_kind, template) = inventory
+            .update(cx, |inventory, cx| {
+                inventory.list_tasks(None, None, None, cx)
+            })
+            .await
+            .into_iter()
+            .find(|(_, template)| template.label == task_name)
+            .unwrap_or_else(|| panic!("Failed to find task with name {task_name}"));
+        let resolved_task = template
+            .resolve_task(&task_source_kind.to_id_base(), &TaskContext::default())
+            .unwrap_or_else(|| panic!("Failed to resolve task with name {task_name}"));
+        inventory
+            .update(cx, |inventory, cx| {
+                inventory.task_dependency_graph(task_source_kind, resolved_task, None, cx)
+            })
+            .await
+    }
+
     fn resolved_task_names(
         inventory: &Entity<Inventory>,
         worktree: Option<WorktreeId>,
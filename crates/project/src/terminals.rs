@@ -1,10 +1,16 @@
-use crate::{Project, ProjectPath};
+use crate::{Event, Project, ProjectPath};
 use anyhow::{Context as _, Result};
+use client::TypedEnvelope;
 use collections::HashMap;
-use gpui::{AnyWindowHandle, App, AppContext as _, Context, Entity, Task, WeakEntity};
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{
+    AnyWindowHandle, App, AppContext as _, AsyncApp, Context, Entity, Subscription, Task,
+    WeakEntity,
+};
 use itertools::Itertools;
 use language::LanguageName;
 use remote::ssh_session::SshArgs;
+use rpc::proto;
 use settings::{Settings, SettingsLocation};
 use smol::channel::bounded;
 use std::{
@@ -12,10 +18,13 @@ use std::{
     env::{self},
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 use task::{DEFAULT_REMOTE_SHELL, Shell, ShellBuilder, SpawnInTerminal};
 use terminal::{
-    TaskState, TaskStatus, Terminal, TerminalBuilder,
+    PersistentSession, ScreenCellRun, ScreenSnapshot, TaskState, TaskStatus, Terminal,
+    TerminalBuilder,
+    alacritty_terminal::term::cell::Flags,
     terminal_settings::{self, ActivateScript, TerminalSettings, VenvSettings},
 };
 use util::{
@@ -23,8 +32,39 @@ use util::{
     paths::{PathStyle, RemotePathBuf},
 };
 
+pub use terminal::BackgroundTaskStatus;
+
+#[derive(Default)]
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakEntity<terminal::Terminal>>,
+    /// The terminals the host shares with the guests, by their ids.
+    shared: HashMap<u64, SharedTerminal>,
+    next_shared_terminal_id: u64,
+    /// The terminals of the host mirrored by the guest, by their ids.
+    mirrored: HashMap<u64, MirroredTerminal>,
+}
+
+/// How the guests may access a terminal shared with them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerminalAccess {
+    /// The guests see the terminal's screen.
+    ReadOnly,
+    /// The guests see the terminal's screen, and may type into it.
+    ReadWrite,
+}
+
+struct SharedTerminal {
+    terminal: WeakEntity<Terminal>,
+    access: TerminalAccess,
+    last_snapshot: Option<ScreenSnapshot>,
+    _subscriptions: [Subscription; 2],
+    _send_updates: Task<()>,
+}
+
+struct MirroredTerminal {
+    terminal: WeakEntity<Terminal>,
+    access: TerminalAccess,
+    _forward_input: Task<()>,
 }
 
 /// Terminals are opened either for the users shell, or to run a task.
@@ -33,6 +73,11 @@ pub struct Terminals {
 pub enum TerminalKind {
     /// Run a shell at the given path (or $HOME if None)
     Shell(Option<PathBuf>),
+    /// Reattach to a persistent shell session, which is started again at the given path if it has ended.
+    PersistentSession {
+        session_id: String,
+        working_directory: Option<PathBuf>,
+    },
     /// Run a task.
     Task(SpawnInTerminal),
 }
@@ -56,6 +101,7 @@ pub struct SshDetails {
     pub ssh_command: SshCommand,
     pub envs: Option<HashMap<String, String>>,
     pub path_style: PathStyle,
+    pub remote_binary_path: Option<RemotePathBuf>,
 }
 
 impl Project {
@@ -88,6 +134,7 @@ impl Project {
                     ssh_command: SshCommand { arguments },
                     envs,
                     path_style,
+                    remote_binary_path: ssh_client.remote_binary_path(),
                 });
             }
         }
@@ -102,7 +149,11 @@ impl Project {
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<Terminal>>> {
         let path: Option<Arc<Path>> = match &kind {
-            TerminalKind::Shell(path) => path.as_ref().map(|path| Arc::from(path.as_ref())),
+            TerminalKind::Shell(path)
+            | TerminalKind::PersistentSession {
+                working_directory: path,
+                ..
+            } => path.as_ref().map(|path| Arc::from(path.as_ref())),
             TerminalKind::Task(spawn_task) => {
                 if let Some(cwd) = &spawn_task.cwd {
                     Some(Arc::from(cwd.as_ref()))
@@ -215,7 +266,11 @@ impl Project {
         let this = &mut *self;
         let ssh_details = this.ssh_details(cx);
         let path: Option<Arc<Path>> = match &kind {
-            TerminalKind::Shell(path) => path.as_ref().map(|path| Arc::from(path.as_ref())),
+            TerminalKind::Shell(path)
+            | TerminalKind::PersistentSession {
+                working_directory: path,
+                ..
+            } => path.as_ref().map(|path| Arc::from(path.as_ref())),
             TerminalKind::Task(spawn_task) => {
                 if let Some(cwd) = &spawn_task.cwd {
                     if ssh_details.is_some() {
@@ -257,12 +312,36 @@ impl Project {
         env.extend(settings.env);
 
         let local_path = if is_ssh_terminal { None } else { path.clone() };
+        // Task diagnostics are stored in the local language server store, hence only reported for local tasks.
+        let task_with_problem_matchers = match &kind {
+            TerminalKind::Task(spawn_task)
+                if !is_ssh_terminal && !spawn_task.problem_matchers.is_empty() =>
+            {
+                Some(spawn_task.full_label.clone())
+            }
+            _ => None,
+        };
+        let task_cwd = local_path.clone();
+        let is_background_task =
+            matches!(&kind, TerminalKind::Task(spawn_task) if spawn_task.background.is_some());
 
         let mut python_venv_activate_command = None;
 
+        let persistent_session_id = match &kind {
+            TerminalKind::Shell(_) if settings.persistent_sessions => new_persistent_session_id(),
+            TerminalKind::PersistentSession { session_id, .. } => Some(session_id.clone()),
+            _ => None,
+        };
+        let persistent_session = persistent_session_id
+            .and_then(|session_id| persistent_session(session_id, ssh_details.as_ref()).log_err());
+        let is_new_shell = matches!(&kind, TerminalKind::Shell(_));
+
         let (spawn_task, shell) = match kind {
-            TerminalKind::Shell(_) => {
-                if let Some(python_venv_directory) = &python_venv_directory {
+            TerminalKind::Shell(_) | TerminalKind::PersistentSession { .. } => {
+                // A reattached shell has had its virtual environment activated already.
+                if let Some(python_venv_directory) = &python_venv_directory
+                    && is_new_shell
+                {
                     python_venv_activate_command = this.python_activate_command(
                         python_venv_directory,
                         &settings.detect_venv,
@@ -276,6 +355,7 @@ impl Project {
                         ssh_command,
                         envs,
                         path_style,
+                        remote_binary_path,
                     }) => {
                         log::debug!("Connecting to a remote server: {ssh_command:?}");
 
@@ -286,9 +366,21 @@ impl Project {
                         env.entry("TERM".to_string())
                             .or_insert_with(|| "xterm-256color".to_string());
 
+                        // The remote server hosts the persistent sessions of the remote terminals.
+                        let attach_command = persistent_session
+                            .as_ref()
+                            .zip(remote_binary_path)
+                            .map(|(session, remote_binary_path)| {
+                                (
+                                    home_relative_remote_path(&remote_binary_path),
+                                    vec!["--terminal-attach".to_string(), session.id.clone()],
+                                )
+                            });
                         let (program, args) = wrap_for_ssh(
                             &ssh_command,
-                            None,
+                            attach_command
+                                .as_ref()
+                                .map(|(program, args)| (program, args)),
                             path.as_deref(),
                             env,
                             None,
@@ -321,6 +413,8 @@ impl Project {
                     show_summary: spawn_task.show_summary,
                     show_command: spawn_task.show_command,
                     show_rerun: spawn_task.show_rerun,
+                    problem_matchers: spawn_task.problem_matchers,
+                    background: spawn_task.background,
                     completion_rx,
                 });
 
@@ -339,6 +433,7 @@ impl Project {
                         ssh_command,
                         envs,
                         path_style,
+                        ..
                     }) => {
                         log::debug!("Connecting to a remote server: {ssh_command:?}");
                         env.entry("TERM".to_string())
@@ -396,6 +491,9 @@ impl Project {
             settings.alternate_scroll,
             settings.max_scroll_history_lines,
             is_ssh_terminal,
+            settings.shell_integration,
+            settings.max_image_memory_mb,
+            persistent_session,
             window,
             completion_tx,
             cx,
@@ -424,10 +522,56 @@ impl Project {
             if let Some(activate_command) = python_venv_activate_command {
                 this.activate_python_virtual_environment(activate_command, &terminal_handle, cx);
             }
+            if let Some(task_label) = task_with_problem_matchers {
+                this.report_task_problems(task_label, task_cwd, &terminal_handle, cx);
+            }
+            if is_background_task {
+                cx.subscribe(&terminal_handle, |_, _, event, cx| {
+                    if let terminal::Event::BackgroundTaskStatusChanged = event {
+                        cx.emit(Event::BackgroundTasksChanged);
+                    }
+                })
+                .detach();
+                cx.emit(Event::BackgroundTasksChanged);
+            }
             terminal_handle
         })
     }
 
+    /// Publishes the problems found in the task output as diagnostics,
+    /// replacing the ones reported by the previous runs of the same task.
+    pub(crate) fn report_task_problems(
+        &mut self,
+        task_label: String,
+        task_cwd: Option<Arc<Path>>,
+        terminal_handle: &Entity<Terminal>,
+        cx: &mut Context<Self>,
+    ) {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .update_task_diagnostics(&task_label, Vec::new(), cx)
+                .log_err();
+        });
+        cx.subscribe(terminal_handle, move |project, terminal, event, cx| {
+            if let terminal::Event::TaskProblemsChanged = event {
+                let problems = terminal
+                    .read(cx)
+                    .task_problems()
+                    .iter()
+                    .filter_map(|problem| {
+                        Some((problem.abs_path(task_cwd.as_deref())?, problem.clone()))
+                    })
+                    .collect();
+                project.lsp_store.update(cx, |lsp_store, cx| {
+                    lsp_store
+                        .update_task_diagnostics(&task_label, problems, cx)
+                        .log_err();
+                });
+            }
+        })
+        .detach();
+    }
+
     fn python_venv_directory(
         &self,
         abs_path: Arc<Path>,
@@ -620,6 +764,380 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakEntity<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    /// Labels and statuses of the background tasks that are still running in the local terminals.
+    pub fn background_tasks(&self, cx: &App) -> Vec<(String, BackgroundTaskStatus)> {
+        self.terminals
+            .local_handles
+            .iter()
+            .filter_map(|terminal| {
+                let terminal = terminal.upgrade()?;
+                let terminal = terminal.read(cx);
+                let status = terminal.background_task_status()?;
+                Some((terminal.task()?.full_label.clone(), status))
+            })
+            .collect()
+    }
+
+    /// Shares the terminal with the guests of the shared project, or changes how they may access it.
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Entity<Terminal>,
+        access: TerminalAccess,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        anyhow::ensure!(
+            self.is_shared() && !self.is_via_collab() && !terminal.read(cx).is_mirror(),
+            "only the host of a shared project can share its terminals"
+        );
+
+        if let Some((&terminal_id, shared)) = self
+            .terminals
+            .shared
+            .iter_mut()
+            .find(|(_, shared)| shared.terminal == terminal.downgrade())
+        {
+            shared.access = access;
+            return self.send_share_terminal(terminal_id, cx);
+        }
+
+        let terminal_id = self.terminals.next_shared_terminal_id;
+        self.terminals.next_shared_terminal_id += 1;
+
+        // The screen is sent after the terminal's output settles a bit, rather than on every update.
+        let (updates_tx, updates_rx) = bounded(1);
+        let send_updates = cx.spawn(async move |project, cx| {
+            while updates_rx.recv().await.is_ok() {
+                if project
+                    .update(cx, |project, cx| {
+                        project.send_shared_terminal_update(terminal_id, cx)
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+                cx.background_executor()
+                    .timer(Duration::from_millis(50))
+                    .await;
+            }
+        });
+        let subscriptions = [
+            cx.subscribe(terminal, move |project, _, event, cx| match event {
+                terminal::Event::Wakeup => {
+                    updates_tx.try_send(()).ok();
+                }
+                terminal::Event::TitleChanged => {
+                    project.send_share_terminal(terminal_id, cx).log_err();
+                }
+                _ => {}
+            }),
+            cx.observe_release(terminal, move |project, _, cx| {
+                project.stop_sharing_terminal(terminal_id, cx);
+            }),
+        ];
+
+        self.terminals.shared.insert(
+            terminal_id,
+            SharedTerminal {
+                terminal: terminal.downgrade(),
+                access,
+                last_snapshot: None,
+                _subscriptions: subscriptions,
+                _send_updates: send_updates,
+            },
+        );
+        self.send_share_terminal(terminal_id, cx)
+    }
+
+    /// Stops sharing the terminal with the guests, closing its mirrors.
+    pub fn unshare_terminal(&mut self, terminal: &Entity<Terminal>, cx: &mut Context<Self>) {
+        let terminal_id = self
+            .terminals
+            .shared
+            .iter()
+            .find(|(_, shared)| shared.terminal == terminal.downgrade())
+            .map(|(terminal_id, _)| *terminal_id);
+        if let Some(terminal_id) = terminal_id {
+            self.stop_sharing_terminal(terminal_id, cx);
+        }
+    }
+
+    /// How the guests may access the terminal: the one shared by the host, or its mirror on the guest.
+    pub fn terminal_access(&self, terminal: &Entity<Terminal>) -> Option<TerminalAccess> {
+        let terminal = terminal.downgrade();
+        self.terminals
+            .shared
+            .values()
+            .map(|shared| (&shared.terminal, shared.access))
+            .chain(
+                self.terminals
+                    .mirrored
+                    .values()
+                    .map(|mirrored| (&mirrored.terminal, mirrored.access)),
+            )
+            .find(|(shared_terminal, _)| **shared_terminal == terminal)
+            .map(|(_, access)| access)
+    }
+
+    fn stop_sharing_terminal(&mut self, terminal_id: u64, cx: &mut Context<Self>) {
+        if self.terminals.shared.remove(&terminal_id).is_some() {
+            if let Some(project_id) = self.remote_id() {
+                self.client
+                    .send(proto::UnshareTerminal {
+                        project_id,
+                        terminal_id,
+                    })
+                    .log_err();
+            }
+            cx.notify();
+        }
+    }
+
+    fn send_share_terminal(&mut self, terminal_id: u64, cx: &mut Context<Self>) -> Result<()> {
+        let project_id = self.remote_id().context("project is not shared")?;
+        let shared = self
+            .terminals
+            .shared
+            .get_mut(&terminal_id)
+            .context("terminal is not shared")?;
+        let terminal = shared.terminal.upgrade().context("terminal was dropped")?;
+        self.client.send(proto::ShareTerminal {
+            project_id,
+            terminal_id,
+            title: terminal.read(cx).title(false),
+            read_write: shared.access == TerminalAccess::ReadWrite,
+        })?;
+        // Send the whole screen again, for the guests that have just joined.
+        shared.last_snapshot = None;
+        self.send_shared_terminal_update(terminal_id, cx);
+        cx.notify();
+        Ok(())
+    }
+
+    fn send_shared_terminal_update(&mut self, terminal_id: u64, cx: &App) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let Some(shared) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        let Some(terminal) = shared.terminal.upgrade() else {
+            return;
+        };
+        let snapshot = terminal.read(cx).screen_snapshot();
+        if shared.last_snapshot.as_ref() != Some(&snapshot) {
+            self.client
+                .send(screen_snapshot_to_proto(project_id, terminal_id, &snapshot))
+                .log_err();
+            shared.last_snapshot = Some(snapshot);
+        }
+    }
+
+    /// Shares the shared terminals again, with the collaborators that have joined the project since.
+    pub(crate) fn reshare_terminals(&mut self, cx: &mut Context<Self>) {
+        let terminal_ids = self.terminals.shared.keys().copied().collect::<Vec<_>>();
+        for terminal_id in terminal_ids {
+            self.send_share_terminal(terminal_id, cx).log_err();
+        }
+    }
+
+    pub(crate) fn unshare_terminals(&mut self) {
+        self.terminals.shared.clear();
+    }
+
+    /// Closes the mirrors of the host's terminals, after the host has stopped sharing the project.
+    pub(crate) fn close_mirrored_terminals(&mut self, cx: &mut App) {
+        for (_, mirrored) in self.terminals.mirrored.drain() {
+            if let Some(terminal) = mirrored.terminal.upgrade() {
+                terminal.update(cx, |_, cx| cx.emit(terminal::Event::CloseTerminal));
+            }
+        }
+    }
+
+    pub(crate) async fn handle_share_terminal(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::ShareTerminal>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let terminal_id = envelope.payload.terminal_id;
+            let access = if envelope.payload.read_write {
+                TerminalAccess::ReadWrite
+            } else {
+                TerminalAccess::ReadOnly
+            };
+            let title = envelope.payload.title.into();
+
+            if let Some(mirrored) = this.terminals.mirrored.get_mut(&terminal_id) {
+                mirrored.access = access;
+                if let Some(terminal) = mirrored.terminal.upgrade() {
+                    terminal.update(cx, |terminal, cx| {
+                        terminal.set_title_override(Some(title), cx)
+                    });
+                }
+                cx.notify();
+                return;
+            }
+
+            let (input_tx, mut input_rx) = mpsc::unbounded();
+            let (completion_tx, _) = bounded(1);
+            let cursor_shape = TerminalSettings::get_global(cx)
+                .cursor_shape
+                .unwrap_or_default();
+            let builder = TerminalBuilder::new_mirror(title, cursor_shape, input_tx, completion_tx);
+            let terminal = cx.new(|cx| builder.subscribe(cx));
+
+            // The host checks the access too, the guest only avoids sending the input that would be rejected.
+            let forward_input = cx.spawn(async move |project, cx| {
+                while let Some(input) = input_rx.next().await {
+                    let Ok(Some((client, request))) = project.update(cx, |project, _| {
+                        let project_id = project.remote_id()?;
+                        let mirrored = project.terminals.mirrored.get(&terminal_id)?;
+                        (mirrored.access == TerminalAccess::ReadWrite).then(|| {
+                            let request = proto::SharedTerminalInput {
+                                project_id,
+                                terminal_id,
+                                input,
+                            };
+                            (project.client.clone(), request)
+                        })
+                    }) else {
+                        continue;
+                    };
+                    client.request(request).await.log_err();
+                }
+            });
+
+            this.terminals.mirrored.insert(
+                terminal_id,
+                MirroredTerminal {
+                    terminal: terminal.downgrade(),
+                    access,
+                    _forward_input: forward_input,
+                },
+            );
+            cx.emit(Event::TerminalShared(terminal));
+        })
+    }
+
+    pub(crate) async fn handle_unshare_terminal(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::UnshareTerminal>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            if let Some(mirrored) = this
+                .terminals
+                .mirrored
+                .remove(&envelope.payload.terminal_id)
+            {
+                if let Some(terminal) = mirrored.terminal.upgrade() {
+                    terminal.update(cx, |_, cx| cx.emit(terminal::Event::CloseTerminal));
+                }
+            }
+        })
+    }
+
+    pub(crate) async fn handle_update_shared_terminal(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::UpdateSharedTerminal>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            // The guests that have just joined get the updates before the terminal is shared with them again.
+            let Some(terminal) = this
+                .terminals
+                .mirrored
+                .get(&envelope.payload.terminal_id)
+                .and_then(|mirrored| mirrored.terminal.upgrade())
+            else {
+                return;
+            };
+            let snapshot = screen_snapshot_from_proto(envelope.payload);
+            terminal.update(cx, |terminal, cx| {
+                terminal.apply_screen_snapshot(&snapshot, cx)
+            });
+        })
+    }
+
+    pub(crate) async fn handle_shared_terminal_input(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::SharedTerminalInput>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |this, cx| {
+            let shared = this
+                .terminals
+                .shared
+                .get(&envelope.payload.terminal_id)
+                .context("terminal is not shared")?;
+            anyhow::ensure!(
+                shared.access == TerminalAccess::ReadWrite,
+                "terminal is shared read-only"
+            );
+            let terminal = shared.terminal.upgrade().context("terminal was dropped")?;
+            terminal.update(cx, |terminal, _| terminal.input(envelope.payload.input));
+            Ok(proto::Ack {})
+        })?
+    }
+}
+
+fn screen_snapshot_to_proto(
+    project_id: u64,
+    terminal_id: u64,
+    snapshot: &ScreenSnapshot,
+) -> proto::UpdateSharedTerminal {
+    proto::UpdateSharedTerminal {
+        project_id,
+        terminal_id,
+        columns: snapshot.columns as u32,
+        rows: snapshot.rows as u32,
+        lines: snapshot
+            .lines
+            .iter()
+            .map(|line| proto::TerminalLine {
+                runs: line
+                    .iter()
+                    .map(|run| proto::TerminalCellRun {
+                        text: run.text.clone(),
+                        fg: terminal::encode_color(run.fg),
+                        bg: terminal::encode_color(run.bg),
+                        flags: run.flags.bits() as u32,
+                    })
+                    .collect(),
+            })
+            .collect(),
+        cursor: snapshot.cursor.map(|(row, column)| proto::TerminalCursor {
+            row: row as u32,
+            column: column as u32,
+        }),
+    }
+}
+
+fn screen_snapshot_from_proto(update: proto::UpdateSharedTerminal) -> ScreenSnapshot {
+    let default_cell = terminal::alacritty_terminal::term::cell::Cell::default();
+    ScreenSnapshot {
+        columns: update.columns as usize,
+        rows: update.rows as usize,
+        lines: update
+            .lines
+            .into_iter()
+            .map(|line| {
+                line.runs
+                    .into_iter()
+                    .map(|run| ScreenCellRun {
+                        text: run.text,
+                        fg: terminal::decode_color(run.fg).unwrap_or(default_cell.fg),
+                        bg: terminal::decode_color(run.bg).unwrap_or(default_cell.bg),
+                        flags: Flags::from_bits_truncate(run.flags as u16),
+                    })
+                    .collect()
+            })
+            .collect(),
+        cursor: update
+            .cursor
+            .map(|cursor| (cursor.row as usize, cursor.column as usize)),
+    }
 }
 
 pub fn wrap_for_ssh(
@@ -634,6 +1152,11 @@ pub fn wrap_for_ssh(
         // DEFAULT_REMOTE_SHELL is '"${SHELL:-sh}"' so must not be escaped
         let command: Option<Cow<str>> = if command == DEFAULT_REMOTE_SHELL {
             Some(command.into())
+        } else if let Some(home_relative) = command.strip_prefix("~/") {
+            // Quoting would disable the ~ expansion, same as for the working directory below.
+            shlex::try_quote(home_relative)
+                .ok()
+                .map(|path| format!("\"$HOME\"/{path}").into())
         } else {
             shlex::try_quote(command).ok()
         };
@@ -684,6 +1207,66 @@ pub fn wrap_for_ssh(
     (program, args)
 }
 
+#[cfg(unix)]
+fn new_persistent_session_id() -> Option<String> {
+    Some(terminal::mux::new_session_id())
+}
+
+#[cfg(not(unix))]
+fn new_persistent_session_id() -> Option<String> {
+    None
+}
+
+/// The session to attach a terminal to, with the command ending it: the local sessions are kept by Zed
+/// itself, and the remote ones by the remote server.
+fn persistent_session(
+    session_id: String,
+    ssh_details: Option<&SshDetails>,
+) -> Result<PersistentSession> {
+    let end_args = vec!["--terminal-end".to_string(), session_id.clone()];
+    let end_command = match ssh_details {
+        Some(ssh_details) => {
+            let remote_binary_path = ssh_details
+                .remote_binary_path
+                .as_ref()
+                .context("the remote server binary is not known")?;
+            let (program, args) = wrap_for_ssh(
+                &ssh_details.ssh_command,
+                Some((&home_relative_remote_path(remote_binary_path), &end_args)),
+                None,
+                HashMap::default(),
+                None,
+                ssh_details.path_style,
+            );
+            let mut command = std::process::Command::new(program);
+            command.args(args);
+            if let Some(envs) = &ssh_details.envs {
+                command.envs(envs);
+            }
+            command
+        }
+        None => {
+            let mut command = std::process::Command::new(env::current_exe()?);
+            command.args(end_args);
+            command
+        }
+    };
+    Ok(PersistentSession {
+        id: session_id,
+        end_command,
+    })
+}
+
+/// The remote server binary path is relative to the home directory, which the ssh commands do not start in.
+fn home_relative_remote_path(path: &RemotePathBuf) -> String {
+    let path = path.to_string();
+    if path.starts_with('/') {
+        path
+    } else {
+        format!("~/{path}")
+    }
+}
+
 fn add_environment_path(env: &mut HashMap<String, String>, new_path: &Path) -> Result<()> {
     let mut env_paths = vec![new_path.to_path_buf()];
     if let Some(path) = env.get("PATH").or(env::var("PATH").ok().as_ref()) {
@@ -736,4 +1319,65 @@ mod tests {
         }
         assert_eq!(env.get("OTHER").unwrap(), "aaa");
     }
+
+    #[test]
+    fn test_wrap_for_ssh_expands_home_relative_command() {
+        let (program, args) = super::wrap_for_ssh(
+            &super::SshCommand {
+                arguments: vec!["host".to_string()],
+            },
+            Some((
+                &"~/.zed_server/zed-remote-server".to_string(),
+                &vec!["--terminal-attach".to_string(), "abc".to_string()],
+            )),
+            None,
+            HashMap::default(),
+            None,
+            util::paths::PathStyle::Posix,
+        );
+        assert_eq!(program, "ssh");
+        assert_eq!(&args[..2], ["host", "-t"]);
+        assert!(
+            args[2].contains("\"$HOME\"/.zed_server/zed-remote-server --terminal-attach abc"),
+            "{}",
+            args[2]
+        );
+    }
+
+    #[test]
+    fn test_screen_snapshot_proto_round_trip() {
+        use terminal::{
+            ScreenCellRun, ScreenSnapshot,
+            alacritty_terminal::{
+                term::cell::Flags,
+                vte::ansi::{Color, NamedColor, Rgb},
+            },
+        };
+
+        let snapshot = ScreenSnapshot {
+            columns: 20,
+            rows: 2,
+            lines: vec![
+                vec![
+                    ScreenCellRun {
+                        text: "$ ".to_string(),
+                        fg: Color::Named(NamedColor::Foreground),
+                        bg: Color::Named(NamedColor::Background),
+                        flags: Flags::empty(),
+                    },
+                    ScreenCellRun {
+                        text: "ls".to_string(),
+                        fg: Color::Spec(Rgb { r: 1, g: 2, b: 3 }),
+                        bg: Color::Indexed(17),
+                        flags: Flags::BOLD | Flags::UNDERLINE,
+                    },
+                ],
+                Vec::new(),
+            ],
+            cursor: Some((0, 4)),
+        };
+        let message = super::screen_snapshot_to_proto(1, 2, &snapshot);
+        assert_eq!((message.project_id, message.terminal_id), (1, 2));
+        assert_eq!(super::screen_snapshot_from_proto(message), snapshot);
+    }
 }
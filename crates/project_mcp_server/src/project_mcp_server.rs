@@ -0,0 +1,170 @@
+use std::{cell::RefCell, rc::Rc};
+
+use context_server::{
+    listener::{McpServer, McpServerAddress},
+    types::{
+        Implementation, InitializeParams, InitializeResponse, ProtocolVersion, ServerCapabilities,
+        ToolsCapabilities, requests,
+    },
+};
+use gpui::{App, ClipboardItem, Context, Task, Window, actions};
+use serde_json::json;
+use settings::Settings as _;
+use workspace::{
+    Toast, Workspace,
+    notifications::{DetachAndPromptErr as _, NotificationId},
+};
+
+mod project_mcp_server_settings;
+mod tools;
+
+pub use project_mcp_server_settings::{
+    ProjectMcpServerSettings, ProjectMcpServerTransport, ToolPermission,
+};
+
+actions!(
+    project_mcp_server,
+    [
+        /// Publishes the open project as an MCP server that external agents can connect to.
+        Start,
+        /// Stops publishing the open project as an MCP server.
+        Stop
+    ]
+);
+
+const SERVER_NAME: &str = "zed";
+
+struct ProjectMcpServerToast;
+
+pub fn init(cx: &mut App) {
+    ProjectMcpServerSettings::register(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        let server = Rc::new(RefCell::new(None));
+        workspace
+            .register_action({
+                let server = server.clone();
+                move |workspace, _: &Start, window, cx| {
+                    start(workspace, server.clone(), window, cx);
+                }
+            })
+            .register_action(move |workspace, _: &Stop, _, cx| {
+                if server.borrow_mut().take().is_some() {
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<ProjectMcpServerToast>(),
+                            "Stopped the project MCP server",
+                        )
+                        .autohide(),
+                        cx,
+                    );
+                }
+            });
+    })
+    .detach();
+}
+
+fn start(
+    workspace: &mut Workspace,
+    server: Rc<RefCell<Option<McpServer>>>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if let Some(server) = server.borrow().as_ref() {
+        show_connection_info(workspace, server.address(), cx);
+        return;
+    }
+
+    let settings = ProjectMcpServerSettings::get_global(cx).clone();
+    let project_context = tools::ProjectContext::new(cx.entity().downgrade(), window);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let mut mcp_server = match (settings.transport, settings.socket_path.clone()) {
+            (ProjectMcpServerTransport::Socket, Some(socket_path)) => {
+                McpServer::bind_socket(socket_path, cx).await?
+            }
+            (ProjectMcpServerTransport::Socket, None) => McpServer::new(cx).await?,
+            (ProjectMcpServerTransport::Http, _) => McpServer::bind_http(settings.port, cx).await?,
+        };
+        mcp_server.handle_request::<requests::Initialize>(handle_initialize);
+        tools::register(&mut mcp_server, project_context, &settings);
+
+        workspace.update(cx, |workspace, cx| {
+            show_connection_info(workspace, mcp_server.address(), cx);
+        })?;
+        server.replace(Some(mcp_server));
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err(
+        "Failed to start the project MCP server",
+        window,
+        cx,
+        |_, _, _| None,
+    );
+}
+
+fn show_connection_info(
+    workspace: &mut Workspace,
+    address: &McpServerAddress,
+    cx: &mut Context<Workspace>,
+) {
+    let (message, connection) = match address {
+        McpServerAddress::Socket(socket_path) => {
+            let command = match std::env::current_exe() {
+                Ok(zed_path) => format!("{} --nc {}", zed_path.display(), socket_path.display()),
+                Err(_) => format!("nc -U {}", socket_path.display()),
+            };
+            (
+                format!("Agents can connect to this project's MCP server with `{command}`"),
+                command,
+            )
+        }
+        McpServerAddress::Http { url, token } => {
+            let config = json!({
+                "mcpServers": {
+                    SERVER_NAME: {
+                        "type": "http",
+                        "url": url,
+                        "headers": { "Authorization": format!("Bearer {token}") },
+                    }
+                }
+            });
+            (
+                format!(
+                    "Agents can connect to this project's MCP server at {url} with the header `Authorization: Bearer {token}`"
+                ),
+                serde_json::to_string_pretty(&config).unwrap_or_default(),
+            )
+        }
+    };
+
+    workspace.show_toast(
+        Toast::new(NotificationId::unique::<ProjectMcpServerToast>(), message)
+            .on_click("Copy", move |_, cx| {
+                cx.write_to_clipboard(ClipboardItem::new_string(connection.clone()))
+            }),
+        cx,
+    );
+}
+
+fn handle_initialize(_: InitializeParams, cx: &App) -> Task<anyhow::Result<InitializeResponse>> {
+    cx.foreground_executor().spawn(async move {
+        Ok(InitializeResponse {
+            protocol_version: ProtocolVersion("2025-06-18".into()),
+            capabilities: ServerCapabilities {
+                experimental: None,
+                logging: None,
+                completions: None,
+                prompts: None,
+                resources: None,
+                tools: Some(ToolsCapabilities {
+                    list_changed: Some(false),
+                }),
+            },
+            server_info: Implementation {
+                name: SERVER_NAME.into(),
+                version: "0.1.0".into(),
+            },
+            meta: None,
+        })
+    })
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use collections::HashMap;
+use gpui::App;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+/// The settings for publishing the open project as an MCP server.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, Default)]
+pub struct ProjectMcpServerSettings {
+    /// How external agents connect to the server.
+    ///
+    /// Default: "socket"
+    #[serde(default)]
+    pub transport: ProjectMcpServerTransport,
+    /// The path of the Unix socket to listen on. A temporary path is used when unset.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub socket_path: Option<PathBuf>,
+    /// The localhost port to listen on with the HTTP transport. 0 picks a free port.
+    ///
+    /// Default: 0
+    #[serde(default)]
+    pub port: u16,
+    /// Whether external agents may use each tool, keyed by tool name.
+    /// Tools that aren't listed ask for confirmation.
+    #[serde(default)]
+    pub tools: HashMap<String, ToolPermission>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectMcpServerTransport {
+    /// Listens on a Unix socket, which agents can bridge to stdio with `nc -U`.
+    #[default]
+    Socket,
+    /// Listens for HTTP POST requests on localhost.
+    Http,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolPermission {
+    /// Runs the tool without asking.
+    Allow,
+    /// Asks for confirmation every time the tool is called.
+    #[default]
+    Confirm,
+    /// Doesn't offer the tool to agents.
+    Deny,
+}
+
+impl ProjectMcpServerSettings {
+    pub fn tool_permission(&self, tool_name: &str) -> ToolPermission {
+        self.tools.get(tool_name).copied().unwrap_or_default()
+    }
+}
+
+impl Settings for ProjectMcpServerSettings {
+    const KEY: Option<&'static str> = Some("project_mcp_server");
+
+    type FileContent = Self;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> anyhow::Result<Self> {
+        SettingsSources::<Self::FileContent>::json_merge_with(
+            [sources.default]
+                .into_iter()
+                .chain(sources.user)
+                .chain(sources.server),
+        )
+    }
+
+    fn import_from_vscode(_vscode: &settings::VsCodeSettings, _current: &mut Self::FileContent) {}
+}
@@ -0,0 +1,939 @@
+//! The tools the project MCP server offers to external agents.
+//!
+//! Tools work on Zed's live state: buffers include unsaved edits, and diagnostics and
+//! navigation come from the language servers Zed is running for the project.
+
+use std::{fmt::Write as _, path::PathBuf};
+
+use anyhow::{Context as _, Result};
+use context_server::{
+    listener::{McpServer, McpServerTool, ToolResponse},
+    types::{ToolAnnotations, ToolResponseContent},
+};
+use futures::StreamExt as _;
+use gpui::{
+    AnyWindowHandle, App, AppContext as _, AsyncApp, Entity, PromptLevel, WeakEntity, Window,
+};
+use language::{
+    Bias, Buffer, DiagnosticSeverity, Location, OffsetRangeExt as _, Point, ToPoint as _,
+};
+use project::{
+    Project, TaskContexts, TaskSourceKind, WorktreeSettings,
+    search::{SearchQuery, SearchResult},
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use settings::Settings as _;
+use task::TaskTemplate;
+use util::paths::PathMatcher;
+use workspace::Workspace;
+
+use crate::{ProjectMcpServerSettings, ToolPermission};
+
+/// The maximum number of matches returned by [`SearchTool`].
+const MAX_SEARCH_MATCHES: usize = 200;
+
+pub(crate) fn register(
+    server: &mut McpServer,
+    context: ProjectContext,
+    settings: &ProjectMcpServerSettings,
+) {
+    fn add_tool<T: McpServerTool + Clone + 'static>(
+        server: &mut McpServer,
+        settings: &ProjectMcpServerSettings,
+        tool: T,
+    ) {
+        if settings.tool_permission(T::NAME) != ToolPermission::Deny {
+            server.add_tool(tool);
+        }
+    }
+
+    add_tool(server, settings, ReadBufferTool(context.clone()));
+    add_tool(server, settings, WriteBufferTool(context.clone()));
+    add_tool(server, settings, DiagnosticsTool(context.clone()));
+    add_tool(server, settings, GoToDefinitionTool(context.clone()));
+    add_tool(server, settings, FindReferencesTool(context.clone()));
+    add_tool(server, settings, SearchTool(context.clone()));
+    add_tool(server, settings, ListTasksTool(context.clone()));
+    add_tool(server, settings, RunTaskTool(context));
+}
+
+/// The workspace whose project is published, shared by all tools.
+#[derive(Clone)]
+pub(crate) struct ProjectContext {
+    workspace: WeakEntity<Workspace>,
+    window: AnyWindowHandle,
+}
+
+impl ProjectContext {
+    pub(crate) fn new(workspace: WeakEntity<Workspace>, window: &Window) -> Self {
+        Self {
+            workspace,
+            window: window.window_handle(),
+        }
+    }
+
+    fn project(&self, cx: &mut AsyncApp) -> Result<Entity<Project>> {
+        self.workspace
+            .read_with(cx, |workspace, _| workspace.project().clone())
+    }
+
+    fn update_workspace<R>(
+        &self,
+        cx: &mut AsyncApp,
+        f: impl FnOnce(&mut Workspace, &mut Window, &mut gpui::Context<Workspace>) -> R,
+    ) -> Result<R> {
+        cx.update_window(self.window, |_, window, cx| {
+            self.workspace
+                .update(cx, |workspace, cx| f(workspace, window, cx))
+        })?
+    }
+
+    /// Checks the tool's permission setting, asking the user when it requires confirmation.
+    async fn authorize(&self, tool_name: &str, action: String, cx: &mut AsyncApp) -> Result<()> {
+        let permission =
+            cx.update(|cx| ProjectMcpServerSettings::get_global(cx).tool_permission(tool_name))?;
+        match permission {
+            ToolPermission::Allow => Ok(()),
+            ToolPermission::Deny => anyhow::bail!("The {tool_name} tool is disabled in Zed"),
+            ToolPermission::Confirm => {
+                let detail =
+                    format!("The agent called the {tool_name} tool of this project's MCP server.");
+                let answer = cx.update_window(self.window, |_, window, cx| {
+                    window.prompt(
+                        PromptLevel::Warning,
+                        &format!("Allow an external agent to {action}?"),
+                        Some(&detail),
+                        &["Allow", "Deny"],
+                        cx,
+                    )
+                })?;
+                anyhow::ensure!(
+                    answer.await? == 0,
+                    "The user denied the {tool_name} tool call"
+                );
+                Ok(())
+            }
+        }
+    }
+
+    async fn open_buffer(&self, path: &str, cx: &mut AsyncApp) -> Result<Entity<Buffer>> {
+        let project = self.project(cx)?;
+        let project_path = project
+            .read_with(cx, |project, cx| project.find_project_path(path, cx))?
+            .with_context(|| format!("Could not find path {path} in project"))?;
+        project
+            .update(cx, |project, cx| project.open_buffer(project_path, cx))?
+            .await
+    }
+
+    /// Lists the tasks available in the workspace, with the context they resolve in.
+    async fn tasks(
+        &self,
+        cx: &mut AsyncApp,
+    ) -> Result<(Vec<(TaskSourceKind, TaskTemplate)>, TaskContexts)> {
+        let task_contexts = self
+            .update_workspace(cx, |workspace, window, cx| {
+                tasks_ui::task_contexts(workspace, window, cx)
+            })?
+            .await;
+        let tasks = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                let Some(task_inventory) = workspace
+                    .project()
+                    .read(cx)
+                    .task_store()
+                    .read(cx)
+                    .task_inventory()
+                    .cloned()
+                else {
+                    return gpui::Task::ready(Vec::new());
+                };
+                let (file, language) = task_contexts
+                    .location()
+                    .map(|location| {
+                        let buffer = location.buffer.read(cx);
+                        (
+                            buffer.file().cloned(),
+                            buffer.language_at(location.range.start),
+                        )
+                    })
+                    .unwrap_or_default();
+                task_inventory
+                    .read(cx)
+                    .list_tasks(file, language, task_contexts.worktree(), cx)
+            })?
+            .await;
+        Ok((tasks, task_contexts))
+    }
+}
+
+fn text_response(text: String) -> Result<ToolResponse<()>> {
+    Ok(ToolResponse {
+        content: vec![ToolResponseContent::Text { text }],
+        structured_content: (),
+    })
+}
+
+fn annotations(title: &str, read_only: bool) -> ToolAnnotations {
+    ToolAnnotations {
+        title: Some(title.to_string()),
+        read_only_hint: Some(read_only),
+        destructive_hint: Some(!read_only),
+        idempotent_hint: None,
+        open_world_hint: Some(false),
+    }
+}
+
+/// Formats a location as `path:line:column`, with 1-based lines and columns.
+fn format_location(location: &Location, cx: &App) -> Option<String> {
+    let buffer = location.buffer.read(cx);
+    let path = buffer.file()?.full_path(cx);
+    let point = location.range.start.to_point(buffer);
+    Some(format!(
+        "{}:{}:{}",
+        path.display(),
+        point.row + 1,
+        point.column + 1
+    ))
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ReadBufferInput {
+    /// The path of the file, relative to the project, starting with the name of its root directory.
+    path: String,
+    /// The 1-based line to start reading from. Defaults to the first line.
+    start_line: Option<u32>,
+    /// The 1-based line to stop reading at, inclusive. Defaults to the last line.
+    end_line: Option<u32>,
+}
+
+#[derive(Clone)]
+struct ReadBufferTool(ProjectContext);
+
+impl McpServerTool for ReadBufferTool {
+    type Input = ReadBufferInput;
+    type Output = ();
+
+    const NAME: &'static str = "read_buffer";
+
+    fn description(&self) -> &'static str {
+        "Reads a file as it is open in Zed, including edits that haven't been saved yet."
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        annotations("Read Buffer", true)
+    }
+
+    async fn run(&self, input: Self::Input, cx: &mut AsyncApp) -> Result<ToolResponse<()>> {
+        self.0
+            .authorize(Self::NAME, format!("read {}", input.path), cx)
+            .await?;
+        let buffer = self.0.open_buffer(&input.path, cx).await?;
+        let text = buffer.read_with(cx, |buffer, _| {
+            let snapshot = buffer.snapshot();
+            let start_row = input.start_line.unwrap_or(1).saturating_sub(1);
+            let end_row = input
+                .end_line
+                .map_or(snapshot.max_point().row, |line| line.saturating_sub(1))
+                .min(snapshot.max_point().row);
+            let start = Point::new(start_row.min(end_row), 0);
+            let end = Point::new(end_row, snapshot.line_len(end_row));
+            snapshot.text_for_range(start..end).collect::<String>()
+        })?;
+        text_response(text)
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct WriteBufferInput {
+    /// The path of the file, relative to the project, starting with the name of its root directory.
+    path: String,
+    /// The new contents of the file.
+    text: String,
+}
+
+#[derive(Clone)]
+struct WriteBufferTool(ProjectContext);
+
+impl McpServerTool for WriteBufferTool {
+    type Input = WriteBufferInput;
+    type Output = ();
+
+    const NAME: &'static str = "write_buffer";
+
+    fn description(&self) -> &'static str {
+        "Replaces the contents of a file open in Zed and saves it."
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        annotations("Write Buffer", false)
+    }
+
+    async fn run(&self, input: Self::Input, cx: &mut AsyncApp) -> Result<ToolResponse<()>> {
+        self.0
+            .authorize(Self::NAME, format!("overwrite {}", input.path), cx)
+            .await?;
+        let buffer = self.0.open_buffer(&input.path, cx).await?;
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_text(input.text, cx);
+        })?;
+        self.0
+            .project(cx)?
+            .update(cx, |project, cx| project.save_buffer(buffer, cx))?
+            .await?;
+        text_response(format!("Wrote {}", input.path))
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct DiagnosticsInput {
+    /// The path of a file to list the errors and warnings of. When omitted, returns the number
+    /// of errors and warnings in each file of the project.
+    path: Option<String>,
+}
+
+#[derive(Clone)]
+struct DiagnosticsTool(ProjectContext);
+
+impl McpServerTool for DiagnosticsTool {
+    type Input = DiagnosticsInput;
+    type Output = ();
+
+    const NAME: &'static str = "diagnostics";
+
+    fn description(&self) -> &'static str {
+        "Lists the errors and warnings the language servers report for a file or the whole project."
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        annotations("Diagnostics", true)
+    }
+
+    async fn run(&self, input: Self::Input, cx: &mut AsyncApp) -> Result<ToolResponse<()>> {
+        self.0
+            .authorize(Self::NAME, "read the project's diagnostics".into(), cx)
+            .await?;
+        let mut output = String::new();
+
+        if let Some(path) = input.path.filter(|path| !path.is_empty()) {
+            let buffer = self.0.open_buffer(&path, cx).await?;
+            let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+            for (_, group) in snapshot.diagnostic_groups(None) {
+                let entry = &group.entries[group.primary_ix];
+                let range = entry.range.to_point(&snapshot);
+                let severity = match entry.diagnostic.severity {
+                    DiagnosticSeverity::ERROR => "error",
+                    DiagnosticSeverity::WARNING => "warning",
+                    _ => continue,
+                };
+                writeln!(
+                    output,
+                    "{severity} at line {}: {}",
+                    range.start.row + 1,
+                    entry.diagnostic.message
+                )?;
+            }
+            if output.is_empty() {
+                output = "File doesn't have errors or warnings!".into();
+            }
+        } else {
+            self.0.project(cx)?.read_with(cx, |project, cx| {
+                for (project_path, _, summary) in project.diagnostic_summaries(true, cx) {
+                    if summary.error_count == 0 && summary.warning_count == 0 {
+                        continue;
+                    }
+                    let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
+                    else {
+                        continue;
+                    };
+                    writeln!(
+                        output,
+                        "{}: {} error(s), {} warning(s)",
+                        PathBuf::from(worktree.read(cx).root_name())
+                            .join(&project_path.path)
+                            .display(),
+                        summary.error_count,
+                        summary.warning_count
+                    )
+                    .ok();
+                }
+            })?;
+            if output.is_empty() {
+                output = "No errors or warnings found in the project.".into();
+            }
+        }
+
+        text_response(output)
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SymbolPositionInput {
+    /// The path of the file, relative to the project, starting with the name of its root directory.
+    path: String,
+    /// The 1-based line of the symbol.
+    line: u32,
+    /// The 1-based column of the symbol, in bytes.
+    column: u32,
+}
+
+impl SymbolPositionInput {
+    fn point(&self, buffer: &Buffer) -> Point {
+        buffer.clip_point(
+            Point::new(self.line.saturating_sub(1), self.column.saturating_sub(1)),
+            Bias::Left,
+        )
+    }
+}
+
+#[derive(Clone)]
+struct GoToDefinitionTool(ProjectContext);
+
+impl McpServerTool for GoToDefinitionTool {
+    type Input = SymbolPositionInput;
+    type Output = ();
+
+    const NAME: &'static str = "go_to_definition";
+
+    fn description(&self) -> &'static str {
+        "Finds where the symbol at a position is defined, using the project's language servers."
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        annotations("Go to Definition", true)
+    }
+
+    async fn run(&self, input: Self::Input, cx: &mut AsyncApp) -> Result<ToolResponse<()>> {
+        self.0
+            .authorize(
+                Self::NAME,
+                format!("look up definitions in {}", input.path),
+                cx,
+            )
+            .await?;
+        let buffer = self.0.open_buffer(&input.path, cx).await?;
+        let position = buffer.read_with(cx, |buffer, _| input.point(buffer))?;
+        let definitions = self
+            .0
+            .project(cx)?
+            .update(cx, |project, cx| project.definitions(&buffer, position, cx))?
+            .await?;
+        let locations = cx.update(|cx| {
+            definitions
+                .iter()
+                .filter_map(|definition| format_location(&definition.target, cx))
+                .collect::<Vec<_>>()
+        })?;
+        if locations.is_empty() {
+            text_response("No definitions found".into())
+        } else {
+            text_response(locations.join("\n"))
+        }
+    }
+}
+
+#[derive(Clone)]
+struct FindReferencesTool(ProjectContext);
+
+impl McpServerTool for FindReferencesTool {
+    type Input = SymbolPositionInput;
+    type Output = ();
+
+    const NAME: &'static str = "find_references";
+
+    fn description(&self) -> &'static str {
+        "Finds all references to the symbol at a position, using the project's language servers."
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        annotations("Find References", true)
+    }
+
+    async fn run(&self, input: Self::Input, cx: &mut AsyncApp) -> Result<ToolResponse<()>> {
+        self.0
+            .authorize(
+                Self::NAME,
+                format!("look up references in {}", input.path),
+                cx,
+            )
+            .await?;
+        let buffer = self.0.open_buffer(&input.path, cx).await?;
+        let position = buffer.read_with(cx, |buffer, _| input.point(buffer))?;
+        let references = self
+            .0
+            .project(cx)?
+            .update(cx, |project, cx| project.references(&buffer, position, cx))?
+            .await?;
+        let locations = cx.update(|cx| {
+            references
+                .iter()
+                .filter_map(|reference| format_location(reference, cx))
+                .collect::<Vec<_>>()
+        })?;
+        if locations.is_empty() {
+            text_response("No references found".into())
+        } else {
+            text_response(locations.join("\n"))
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SearchInput {
+    /// A regex to search for in the contents of the project's files.
+    regex: String,
+    /// A glob pattern for the paths of files to search, like "**/*.rs".
+    include_pattern: Option<String>,
+    /// Whether the regex is case-sensitive. Defaults to false.
+    #[serde(default)]
+    case_sensitive: bool,
+}
+
+#[derive(Clone)]
+struct SearchTool(ProjectContext);
+
+impl McpServerTool for SearchTool {
+    type Input = SearchInput;
+    type Output = ();
+
+    const NAME: &'static str = "search";
+
+    fn description(&self) -> &'static str {
+        "Searches the contents of the project's files with a regex, including unsaved edits."
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        annotations("Search Project", true)
+    }
+
+    async fn run(&self, input: Self::Input, cx: &mut AsyncApp) -> Result<ToolResponse<()>> {
+        self.0
+            .authorize(
+                Self::NAME,
+                format!("search the project for `{}`", input.regex),
+                cx,
+            )
+            .await?;
+        let include_matcher = PathMatcher::new(input.include_pattern.as_slice())
+            .context("invalid include glob pattern")?;
+        // Keep excluded and private files out of the results, like the agent's own search does.
+        let exclude_matcher = cx.update(|cx| {
+            let settings = WorktreeSettings::get_global(cx);
+            PathMatcher::new(
+                settings
+                    .file_scan_exclusions
+                    .sources()
+                    .iter()
+                    .chain(settings.private_files.sources().iter()),
+            )
+        })??;
+        let query = SearchQuery::regex(
+            &input.regex,
+            false,
+            input.case_sensitive,
+            false,
+            true,
+            include_matcher,
+            exclude_matcher,
+            true,
+            None,
+        )?;
+
+        let results = self
+            .0
+            .project(cx)?
+            .update(cx, |project, cx| project.search(query, cx))?;
+        futures::pin_mut!(results);
+
+        let mut output = String::new();
+        let mut match_count = 0;
+        'outer: while let Some(SearchResult::Buffer { buffer, ranges }) = results.next().await {
+            let matches = buffer.read_with(cx, |buffer, cx| {
+                let Some(file) = buffer.file() else {
+                    return Vec::new();
+                };
+                let path = file.full_path(cx);
+                ranges
+                    .iter()
+                    .map(|range| {
+                        let row = range.start.to_point(buffer).row;
+                        let line = buffer
+                            .text_for_range(
+                                Point::new(row, 0)..Point::new(row, buffer.line_len(row)),
+                            )
+                            .collect::<String>();
+                        format!("{}:{}: {}", path.display(), row + 1, line.trim())
+                    })
+                    .collect()
+            })?;
+            for line in matches {
+                if match_count == MAX_SEARCH_MATCHES {
+                    writeln!(output, "Stopped after {MAX_SEARCH_MATCHES} matches.")?;
+                    break 'outer;
+                }
+                writeln!(output, "{line}")?;
+                match_count += 1;
+            }
+        }
+
+        if output.is_empty() {
+            text_response("No matches found".into())
+        } else {
+            text_response(output)
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ListTasksInput {}
+
+#[derive(Clone)]
+struct ListTasksTool(ProjectContext);
+
+impl McpServerTool for ListTasksTool {
+    type Input = ListTasksInput;
+    type Output = ();
+
+    const NAME: &'static str = "list_tasks";
+
+    fn description(&self) -> &'static str {
+        "Lists the tasks configured for the project, which can be started with the run_task tool."
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        annotations("List Tasks", true)
+    }
+
+    async fn run(&self, _: Self::Input, cx: &mut AsyncApp) -> Result<ToolResponse<()>> {
+        self.0
+            .authorize(Self::NAME, "list the project's tasks".into(), cx)
+            .await?;
+        let (tasks, _) = self.0.tasks(cx).await?;
+        let mut output = String::new();
+        for (_, task) in &tasks {
+            writeln!(
+                output,
+                "{}: {} {}",
+                task.label,
+                task.command,
+                task.args.join(" ")
+            )?;
+        }
+        if output.is_empty() {
+            text_response("The project has no tasks".into())
+        } else {
+            text_response(output)
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RunTaskInput {
+    /// The label of the task, as returned by the list_tasks tool.
+    label: String,
+}
+
+#[derive(Clone)]
+struct RunTaskTool(ProjectContext);
+
+impl McpServerTool for RunTaskTool {
+    type Input = RunTaskInput;
+    type Output = ();
+
+    const NAME: &'static str = "run_task";
+
+    fn description(&self) -> &'static str {
+        "Runs one of the project's tasks in Zed's terminal and waits for it to exit."
+    }
+
+    fn annotations(&self) -> ToolAnnotations {
+        annotations("Run Task", false)
+    }
+
+    async fn run(&self, input: Self::Input, cx: &mut AsyncApp) -> Result<ToolResponse<()>> {
+        self.0
+            .authorize(Self::NAME, format!("run the `{}` task", input.label), cx)
+            .await?;
+        let (tasks, task_contexts) = self.0.tasks(cx).await?;
+        let (task_source_kind, template) = tasks
+            .into_iter()
+            .find(|(_, task)| task.label == input.label)
+            .with_context(|| format!("No task is labeled {}", input.label))?;
+        let task_context = task_contexts.active_context().cloned().unwrap_or_default();
+        let resolved_task = template
+            .resolve_task(&task_source_kind.to_id_base(), &task_context)
+            .with_context(|| format!("Failed to resolve task {}", input.label))?;
+
+        // Dependencies run as a graph of their own, which doesn't report back when it's done.
+        if !resolved_task.original_task().depends_on.is_empty() {
+            self.0.update_workspace(cx, |workspace, window, cx| {
+                workspace.schedule_resolved_task(task_source_kind, resolved_task, false, window, cx)
+            })?;
+            return text_response(format!(
+                "Started task {} after its dependencies. Check Zed's terminal for its output.",
+                input.label
+            ));
+        }
+
+        let resolved_task = self
+            .0
+            .update_workspace(cx, |workspace, window, cx| {
+                workspace.resolve_task_inputs(resolved_task, window, cx)
+            })?
+            .await?
+            .context("The user dismissed the task's inputs")?;
+        let status = self
+            .0
+            .update_workspace(cx, |workspace, window, cx| {
+                workspace.spawn_in_terminal(resolved_task.resolved.clone(), window, cx)
+            })?
+            .await
+            .context("The task was cancelled")??;
+
+        match status.code() {
+            Some(code) => text_response(format!("Task {} exited with code {code}", input.label)),
+            None => text_response(format!("Task {} was terminated", input.label)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use fs::{FakeFs, Fs as _};
+    use gpui::{TestAppContext, VisualTestContext};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+    use workspace::AppState;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_read_buffer_includes_unsaved_edits(cx: &mut TestAppContext) {
+        let (fs, project, context, cx) = init_test(cx).await;
+        set_permission(ReadBufferTool::NAME, ToolPermission::Allow, cx);
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/root/lines.txt"), cx)
+            })
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| buffer.edit([(4..7, "TWO")], None, cx));
+
+        let text = run_tool(
+            ReadBufferTool(context.clone()),
+            ReadBufferInput {
+                path: "root/lines.txt".into(),
+                start_line: None,
+                end_line: None,
+            },
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(text, "one\nTWO\nthree\n");
+
+        let text = run_tool(
+            ReadBufferTool(context),
+            ReadBufferInput {
+                path: "root/lines.txt".into(),
+                start_line: Some(2),
+                end_line: Some(3),
+            },
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(text, "TWO\nthree");
+
+        assert_eq!(
+            fs.load(path!("/root/lines.txt").as_ref()).await.unwrap(),
+            "one\ntwo\nthree\n"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_write_buffer_saves_the_file(cx: &mut TestAppContext) {
+        let (fs, _, context, cx) = init_test(cx).await;
+        set_permission(WriteBufferTool::NAME, ToolPermission::Allow, cx);
+
+        let text = run_tool(
+            WriteBufferTool(context),
+            WriteBufferInput {
+                path: "root/lines.txt".into(),
+                text: "four\n".into(),
+            },
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(text, "Wrote root/lines.txt");
+        assert_eq!(
+            fs.load(path!("/root/lines.txt").as_ref()).await.unwrap(),
+            "four\n"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_search(cx: &mut TestAppContext) {
+        let (_, _, context, cx) = init_test(cx).await;
+        set_permission(SearchTool::NAME, ToolPermission::Allow, cx);
+
+        let text = run_tool(
+            SearchTool(context.clone()),
+            SearchInput {
+                regex: "fn ma.n".into(),
+                include_pattern: Some("**/*.rs".into()),
+                case_sensitive: false,
+            },
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(text, "root/src/main.rs:2: fn main() {}\n");
+
+        let text = run_tool(
+            SearchTool(context),
+            SearchInput {
+                regex: "FN MAIN".into(),
+                include_pattern: None,
+                case_sensitive: true,
+            },
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(text, "No matches found");
+    }
+
+    #[gpui::test]
+    async fn test_diagnostics_without_language_servers(cx: &mut TestAppContext) {
+        let (_, _, context, cx) = init_test(cx).await;
+        set_permission(DiagnosticsTool::NAME, ToolPermission::Allow, cx);
+
+        let text = run_tool(
+            DiagnosticsTool(context.clone()),
+            DiagnosticsInput { path: None },
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(text, "No errors or warnings found in the project.");
+
+        let text = run_tool(
+            DiagnosticsTool(context),
+            DiagnosticsInput {
+                path: Some("root/src/main.rs".into()),
+            },
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(text, "File doesn't have errors or warnings!");
+    }
+
+    #[gpui::test]
+    async fn test_tool_permissions(cx: &mut TestAppContext) {
+        let (fs, _, context, cx) = init_test(cx).await;
+        let input = || WriteBufferInput {
+            path: "root/lines.txt".into(),
+            text: "four\n".into(),
+        };
+
+        set_permission(WriteBufferTool::NAME, ToolPermission::Deny, cx);
+        let error = run_tool(WriteBufferTool(context.clone()), input(), cx)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The write_buffer tool is disabled in Zed"
+        );
+        assert!(!cx.has_pending_prompt());
+
+        set_permission(WriteBufferTool::NAME, ToolPermission::Confirm, cx);
+        let task = run_tool(WriteBufferTool(context.clone()), input(), cx);
+        cx.run_until_parked();
+        cx.simulate_prompt_answer("Deny");
+        let error = task.await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The user denied the write_buffer tool call"
+        );
+        assert_eq!(
+            fs.load(path!("/root/lines.txt").as_ref()).await.unwrap(),
+            "one\ntwo\nthree\n"
+        );
+
+        let task = run_tool(WriteBufferTool(context), input(), cx);
+        cx.run_until_parked();
+        cx.simulate_prompt_answer("Allow");
+        task.await.unwrap();
+        assert_eq!(
+            fs.load(path!("/root/lines.txt").as_ref()).await.unwrap(),
+            "four\n"
+        );
+    }
+
+    async fn init_test(
+        cx: &mut TestAppContext,
+    ) -> (
+        Arc<FakeFs>,
+        Entity<Project>,
+        ProjectContext,
+        &mut VisualTestContext,
+    ) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "lines.txt": "one\ntwo\nthree\n",
+                "notes.md": "Call main to start.\n",
+                "src": {
+                    "main.rs": "// The entry point.\nfn main() {}\n",
+                },
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let context = cx.update(|window, _| ProjectContext::new(workspace.downgrade(), window));
+        (fs, project, context, cx)
+    }
+
+    fn set_permission(tool_name: &str, permission: ToolPermission, cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<ProjectMcpServerSettings>(cx, |settings| {
+                    settings.tools.insert(tool_name.into(), permission);
+                });
+            });
+        });
+    }
+
+    /// Runs the tool, resolving to the text of its response.
+    fn run_tool<T: McpServerTool<Output = ()> + 'static>(
+        tool: T,
+        input: T::Input,
+        cx: &mut TestAppContext,
+    ) -> gpui::Task<Result<String>> {
+        cx.spawn(|mut cx| async move {
+            let response = tool.run(input, &mut cx).await?;
+            match response.content.as_slice() {
+                [ToolResponseContent::Text { text }] => Ok(text.clone()),
+                _ => anyhow::bail!("expected a single text response"),
+            }
+        })
+    }
+}
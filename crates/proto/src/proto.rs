@@ -248,6 +248,10 @@ messages!(
     (SyncExtensionsResponse, Background),
     (BreakpointsForFile, Background),
     (ToggleBreakpoint, Foreground),
+    (ShareTerminal, Foreground),
+    (UnshareTerminal, Foreground),
+    (UpdateSharedTerminal, Foreground),
+    (SharedTerminalInput, Foreground),
     (SynchronizeBuffers, Foreground),
     (SynchronizeBuffersResponse, Foreground),
     (SynchronizeContexts, Foreground),
@@ -484,6 +488,7 @@ request_messages!(
     (GetDocumentDiagnostics, GetDocumentDiagnosticsResponse),
     (PullWorkspaceDiagnostics, Ack),
     (GetDefaultBranch, GetDefaultBranchResponse),
+    (SharedTerminalInput, Ack),
 );
 
 entity_messages!(
@@ -615,7 +620,11 @@ entity_messages!(
     LogToDebugConsole,
     GetDocumentDiagnostics,
     PullWorkspaceDiagnostics,
-    GetDefaultBranch
+    GetDefaultBranch,
+    ShareTerminal,
+    UnshareTerminal,
+    UpdateSharedTerminal,
+    SharedTerminalInput
 );
 
 entity_messages!(
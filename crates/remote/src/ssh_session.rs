@@ -1151,6 +1151,15 @@ impl SshRemoteClient {
             .map(|ssh_connection| (ssh_connection.ssh_args(), ssh_connection.path_style()))
     }
 
+    /// The path of the server binary on the remote host, relative to the home directory unless absolute.
+    pub fn remote_binary_path(&self) -> Option<RemotePathBuf> {
+        self.state
+            .lock()
+            .as_ref()
+            .and_then(|state| state.ssh_connection())
+            .and_then(|ssh_connection| ssh_connection.remote_binary_path())
+    }
+
     pub fn upload_directory(
         &self,
         src_path: PathBuf,
@@ -1383,6 +1392,7 @@ trait RemoteConnection: Send + Sync {
     fn ssh_args(&self) -> SshArgs;
     fn connection_options(&self) -> SshConnectionOptions;
     fn path_style(&self) -> PathStyle;
+    fn remote_binary_path(&self) -> Option<RemotePathBuf>;
 
     #[cfg(any(test, feature = "test-support"))]
     fn simulate_disconnect(&self, _: &AsyncApp) {}
@@ -1527,6 +1537,10 @@ impl RemoteConnection for SshRemoteConnection {
     fn path_style(&self) -> PathStyle {
         self.ssh_path_style
     }
+
+    fn remote_binary_path(&self) -> Option<RemotePathBuf> {
+        self.remote_binary_path.clone()
+    }
 }
 
 impl SshRemoteConnection {
@@ -2692,6 +2706,10 @@ mod fake {
         fn path_style(&self) -> PathStyle {
             PathStyle::current()
         }
+
+        fn remote_binary_path(&self) -> Option<RemotePathBuf> {
+            None
+        }
     }
 
     pub(super) struct Delegate;
@@ -19,6 +19,16 @@ struct Cli {
     /// Used for loading the environment from the project.
     #[arg(long, hide = true)]
     printenv: bool,
+    /// Used for keeping the persistent terminal sessions alive, by having the server run a separate
+    /// daemon process owning the sessions' shells, communicating over a socket.
+    #[arg(long, hide = true)]
+    terminal_mux: Option<PathBuf>,
+    /// Used for attaching a terminal to its persistent session.
+    #[arg(long, hide = true)]
+    terminal_attach: Option<String>,
+    /// Used for ending the persistent session of a closed terminal.
+    #[arg(long, hide = true)]
+    terminal_end: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -72,6 +82,32 @@ fn main() {
         return;
     }
 
+    if let Some(socket) = &cli.terminal_mux {
+        if let Err(error) = terminal::mux::run_daemon(socket) {
+            eprintln!("Error: {error:#}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(session_id) = &cli.terminal_attach {
+        match terminal::mux::attach(session_id) {
+            Ok(exit_code) => std::process::exit(exit_code),
+            Err(error) => {
+                eprintln!("Error: {error:#}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(session_id) = &cli.terminal_end {
+        if let Err(error) = terminal::mux::end_session(session_id) {
+            eprintln!("Error: {error:#}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let result = match cli.command {
         Some(Commands::Run {
             log_file,
@@ -981,6 +981,7 @@ impl RulesLibrary {
                                     tool_choice: None,
                                     stop: Vec::new(),
                                     temperature: None,
+                                    max_tokens: None,
                                     thinking_allowed: true,
                                 },
                                 cx,
@@ -1,20 +1,19 @@
 use gpui::{Action, SharedString};
 
-use crate::{ActivateRegexMode, ActivateTextMode};
+use crate::{ActivateSemanticMode, ActivateTextMode};
 
-// TODO: Update the default search mode to get from config
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub enum SearchMode {
     #[default]
     Text,
-    Regex,
+    Semantic,
 }
 
 impl SearchMode {
     pub(crate) fn label(&self) -> &'static str {
         match self {
             SearchMode::Text => "Text",
-            SearchMode::Regex => "Regex",
+            SearchMode::Semantic => "Semantic",
         }
     }
     pub(crate) fn tooltip(&self) -> SharedString {
@@ -23,14 +22,15 @@ impl SearchMode {
     pub(crate) fn action(&self) -> Box<dyn Action> {
         match self {
             SearchMode::Text => ActivateTextMode.boxed_clone(),
-            SearchMode::Regex => ActivateRegexMode.boxed_clone(),
+            SearchMode::Semantic => ActivateSemanticMode.boxed_clone(),
         }
     }
 }
 
-pub(crate) fn next_mode(mode: &SearchMode) -> SearchMode {
+/// Semantic mode is skipped when the project has no semantic index.
+pub(crate) fn next_mode(mode: &SearchMode, semantic_available: bool) -> SearchMode {
     match mode {
-        SearchMode::Text => SearchMode::Regex,
-        SearchMode::Regex => SearchMode::Text,
+        SearchMode::Text if semantic_available => SearchMode::Semantic,
+        SearchMode::Text | SearchMode::Semantic => SearchMode::Text,
     }
 }
@@ -1,13 +1,17 @@
 use crate::{
-    BufferSearchBar, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext,
-    SearchOptions, SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
-    ToggleRegex, ToggleReplace, ToggleWholeWord, buffer_search::Deploy,
+    ActivateSemanticMode, ActivateTextMode, BufferSearchBar, CycleMode, FocusSearch,
+    NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
+    SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex,
+    ToggleReplace, ToggleWholeWord,
+    buffer_search::Deploy,
+    mode::{SearchMode, next_mode},
 };
 use anyhow::Context as _;
 use collections::{HashMap, HashSet};
 use editor::{
-    Anchor, Editor, EditorElement, EditorEvent, EditorSettings, EditorStyle, MAX_TAB_TITLE_LEN,
-    MultiBuffer, SelectionEffects, actions::SelectAll, items::active_match_index,
+    Anchor, Bias, Editor, EditorElement, EditorEvent, EditorSettings, EditorStyle, ExcerptRange,
+    MAX_TAB_TITLE_LEN, MultiBuffer, SelectionEffects, actions::SelectAll,
+    items::active_match_index,
 };
 use futures::{StreamExt, stream::FuturesOrdered};
 use gpui::{
@@ -23,6 +27,7 @@ use project::{
     search::{SearchInputKind, SearchQuery},
     search_history::SearchHistoryCursor,
 };
+use semantic_index::{ProjectIndex, SemanticDb};
 use settings::Settings;
 use std::{
     any::{Any, TypeId},
@@ -94,6 +99,24 @@ pub fn init(cx: &mut App) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, window, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, window, cx);
         });
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ActivateTextMode, window, cx| {
+                search_bar.activate_search_mode(SearchMode::Text, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ActivateSemanticMode, window, cx| {
+                search_bar.activate_search_mode(SearchMode::Semantic, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, action: &CycleMode, window, cx| {
+                search_bar.cycle_mode(action, window, cx);
+            },
+        );
         register_workspace_action(
             workspace,
             move |search_bar, action: &ToggleReplace, window, cx| {
@@ -173,6 +196,9 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+/// The number of ranked chunks shown for a semantic search.
+const SEMANTIC_SEARCH_LIMIT: usize = 64;
+
 fn contains_uppercase(str: &str) -> bool {
     str.chars().any(|c| c.is_uppercase())
 }
@@ -208,6 +234,7 @@ pub struct ProjectSearchView {
     replacement_editor: Entity<Editor>,
     results_editor: Entity<Editor>,
     search_options: SearchOptions,
+    current_mode: SearchMode,
     panels_with_errors: HashSet<InputPanel>,
     active_match_index: Option<usize>,
     search_id: usize,
@@ -225,6 +252,7 @@ pub struct ProjectSearchView {
 pub struct ProjectSearchSettings {
     search_options: SearchOptions,
     filters_enabled: bool,
+    current_mode: SearchMode,
 }
 
 pub struct ProjectSearchBar {
@@ -285,8 +313,8 @@ impl ProjectSearch {
         }
     }
 
-    fn search(&mut self, query: SearchQuery, cx: &mut Context<Self>) {
-        let search = self.project.update(cx, |project, cx| {
+    fn add_to_search_history(&mut self, query: &SearchQuery, cx: &mut Context<Self>) {
+        self.project.update(cx, |project, _| {
             project
                 .search_history_mut(SearchInputKind::Query)
                 .add(&mut self.search_history_cursor, query.as_str().to_string());
@@ -302,8 +330,19 @@ impl ProjectSearch {
                     .search_history_mut(SearchInputKind::Exclude)
                     .add(&mut self.search_excluded_history_cursor, excluded);
             }
-            project.search(query.clone(), cx)
         });
+    }
+
+    fn project_index(&self, cx: &App) -> Option<Entity<ProjectIndex>> {
+        cx.try_global::<SemanticDb>()?
+            .project_index(self.project.clone(), cx)
+    }
+
+    fn search(&mut self, query: SearchQuery, cx: &mut Context<Self>) {
+        self.add_to_search_history(&query, cx);
+        let search = self
+            .project
+            .update(cx, |project, cx| project.search(query.clone(), cx));
         self.last_search_query_text = Some(query.as_str().to_string());
         self.search_id += 1;
         self.active_query = Some(query);
@@ -378,6 +417,115 @@ impl ProjectSearch {
         }));
         cx.notify();
     }
+
+    /// Searches the project's semantic index, showing each matching chunk as
+    /// its own excerpt, most relevant first. The query's include and exclude
+    /// filters are applied to the results.
+    fn semantic_search(
+        &mut self,
+        project_index: Entity<ProjectIndex>,
+        query: SearchQuery,
+        cx: &mut Context<Self>,
+    ) {
+        self.add_to_search_history(&query, cx);
+        let search = project_index.read(cx).search(
+            vec![query.as_str().to_string()],
+            SEMANTIC_SEARCH_LIMIT,
+            cx,
+        );
+        self.last_search_query_text = Some(query.as_str().to_string());
+        self.search_id += 1;
+        // Chunks aren't text matches, so there is nothing to replace.
+        self.active_query = None;
+        self.match_ranges.clear();
+        self.pending_search = Some(cx.spawn(async move |project_search, cx| {
+            let results = search.await.log_err();
+            let project = project_search
+                .update(cx, |project_search, cx| {
+                    project_search.match_ranges.clear();
+                    project_search
+                        .excerpts
+                        .update(cx, |excerpts, cx| excerpts.clear(cx));
+                    project_search.no_results = Some(true);
+                    project_search.limit_reached = false;
+                    project_search.project.clone()
+                })
+                .ok()?;
+
+            for result in results.into_iter().flatten() {
+                let (worktree_id, full_path) = result
+                    .worktree
+                    .read_with(cx, |worktree, _| {
+                        (
+                            worktree.id(),
+                            Path::new(worktree.root_name()).join(&result.path),
+                        )
+                    })
+                    .ok()?;
+                if query.filters_path() {
+                    let path = if query.match_full_paths() {
+                        full_path.as_path()
+                    } else {
+                        result.path.as_ref()
+                    };
+                    if !query.match_path(path) {
+                        continue;
+                    }
+                }
+
+                let Some(buffer) = project
+                    .update(cx, |project, cx| {
+                        project.open_buffer(
+                            ProjectPath {
+                                worktree_id,
+                                path: result.path.clone(),
+                            },
+                            cx,
+                        )
+                    })
+                    .ok()?
+                    .await
+                    .log_err()
+                else {
+                    continue;
+                };
+
+                project_search
+                    .update(cx, |project_search, cx| {
+                        let match_range = project_search.excerpts.update(cx, |excerpts, cx| {
+                            let snapshot = buffer.read(cx).snapshot();
+                            let start = snapshot.clip_offset(result.range.start, Bias::Left);
+                            let end = snapshot.clip_offset(result.range.end, Bias::Right);
+                            let range = snapshot.anchor_after(start)..snapshot.anchor_before(end);
+                            let excerpt_id = excerpts
+                                .push_excerpts(buffer, [ExcerptRange::new(range.clone())], cx)
+                                .pop()?;
+                            let snapshot = excerpts.snapshot(cx);
+                            Some(
+                                snapshot.anchor_in_excerpt(excerpt_id, range.start)?
+                                    ..snapshot.anchor_in_excerpt(excerpt_id, range.end)?,
+                            )
+                        });
+                        project_search.match_ranges.extend(match_range);
+                        cx.notify();
+                    })
+                    .ok()?;
+            }
+
+            project_search
+                .update(cx, |project_search, cx| {
+                    if !project_search.match_ranges.is_empty() {
+                        project_search.no_results = Some(false);
+                    }
+                    project_search.pending_search.take();
+                    cx.notify();
+                })
+                .ok()?;
+
+            None
+        }));
+        cx.notify();
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -662,6 +810,7 @@ impl ProjectSearchView {
         ProjectSearchSettings {
             search_options: self.search_options,
             filters_enabled: self.filters_enabled,
+            current_mode: self.current_mode,
         }
     }
 
@@ -743,12 +892,21 @@ impl ProjectSearchView {
         let mut subscriptions = Vec::new();
 
         // Read in settings if available
-        let (mut options, filters_enabled) = if let Some(settings) = settings {
-            (settings.search_options, settings.filters_enabled)
+        let (mut options, filters_enabled, current_mode) = if let Some(settings) = settings {
+            (
+                settings.search_options,
+                settings.filters_enabled,
+                settings.current_mode,
+            )
         } else {
             let search_options =
                 SearchOptions::from_settings(&EditorSettings::get_global(cx).search);
-            (search_options, false)
+            (search_options, false, SearchMode::Text)
+        };
+        let current_mode = if entity.read(cx).project_index(cx).is_some() {
+            current_mode
+        } else {
+            SearchMode::Text
         };
 
         {
@@ -767,7 +925,7 @@ impl ProjectSearchView {
 
         let query_editor = cx.new(|cx| {
             let mut editor = Editor::single_line(window, cx);
-            editor.set_placeholder_text("Search all files…", cx);
+            editor.set_placeholder_text(Self::query_placeholder(current_mode), cx);
             editor.set_text(query_text, window, cx);
             editor
         });
@@ -879,6 +1037,7 @@ impl ProjectSearchView {
             query_editor,
             results_editor,
             search_options: options,
+            current_mode,
             panels_with_errors: HashSet::default(),
             active_match_index: None,
             included_files_editor,
@@ -1129,10 +1288,56 @@ impl ProjectSearchView {
 
     fn search(&mut self, cx: &mut Context<Self>) {
         if let Some(query) = self.build_search_query(cx) {
-            self.entity.update(cx, |model, cx| model.search(query, cx));
+            let project_index = match self.current_mode {
+                SearchMode::Text => None,
+                SearchMode::Semantic => self.entity.read(cx).project_index(cx),
+            };
+            self.entity.update(cx, |model, cx| {
+                if let Some(project_index) = project_index {
+                    model.semantic_search(project_index, query, cx)
+                } else {
+                    model.search(query, cx)
+                }
+            });
         }
     }
 
+    fn semantic_search_available(&self, cx: &App) -> bool {
+        self.entity.read(cx).project_index(cx).is_some()
+    }
+
+    fn query_placeholder(mode: SearchMode) -> &'static str {
+        match mode {
+            SearchMode::Text => "Search all files…",
+            SearchMode::Semantic => "Describe the code you're looking for…",
+        }
+    }
+
+    fn activate_search_mode(
+        &mut self,
+        mode: SearchMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.current_mode == mode
+            || (mode == SearchMode::Semantic && !self.semantic_search_available(cx))
+        {
+            return;
+        }
+        self.current_mode = mode;
+        self.query_editor.update(cx, |editor, cx| {
+            editor.set_placeholder_text(Self::query_placeholder(mode), cx);
+        });
+        ActiveSettings::update_global(cx, |settings, cx| {
+            settings.0.insert(
+                self.entity.read(cx).project.downgrade(),
+                self.current_settings(),
+            );
+        });
+        self.query_editor.focus_handle(cx).focus(window);
+        cx.notify();
+    }
+
     pub fn search_query_text(&self, cx: &App) -> String {
         self.query_editor.read(cx).text(cx)
     }
@@ -1706,6 +1911,33 @@ impl ProjectSearchBar {
         true
     }
 
+    fn activate_search_mode(
+        &mut self,
+        mode: SearchMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| {
+                search_view.activate_search_mode(mode, window, cx);
+            });
+            cx.notify();
+        }
+    }
+
+    fn cycle_mode(&mut self, _: &CycleMode, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| {
+                let next_mode = next_mode(
+                    &search_view.current_mode,
+                    search_view.semantic_search_available(cx),
+                );
+                search_view.activate_search_mode(next_mode, window, cx);
+            });
+            cx.notify();
+        }
+    }
+
     fn toggle_replace(&mut self, _: &ToggleReplace, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(search) = &self.active_project_search {
             search.update(cx, |this, cx| {
@@ -1989,34 +2221,63 @@ impl Render for ProjectSearchBar {
                 cx.listener(|this, action, window, cx| this.next_history_query(action, window, cx)),
             )
             .child(self.render_text_input(&search.query_editor, cx))
-            .child(
-                h_flex()
-                    .gap_1()
-                    .child(SearchOptions::CASE_SENSITIVE.as_button(
-                        self.is_option_enabled(SearchOptions::CASE_SENSITIVE, cx),
-                        focus_handle.clone(),
-                        cx.listener(|this, _, window, cx| {
-                            this.toggle_search_option(SearchOptions::CASE_SENSITIVE, window, cx);
-                        }),
-                    ))
-                    .child(SearchOptions::WHOLE_WORD.as_button(
-                        self.is_option_enabled(SearchOptions::WHOLE_WORD, cx),
-                        focus_handle.clone(),
-                        cx.listener(|this, _, window, cx| {
-                            this.toggle_search_option(SearchOptions::WHOLE_WORD, window, cx);
-                        }),
-                    ))
-                    .child(SearchOptions::REGEX.as_button(
-                        self.is_option_enabled(SearchOptions::REGEX, cx),
-                        focus_handle.clone(),
-                        cx.listener(|this, _, window, cx| {
-                            this.toggle_search_option(SearchOptions::REGEX, window, cx);
-                        }),
-                    )),
-            );
+            .when(search.current_mode == SearchMode::Text, |this| {
+                this.child(
+                    h_flex()
+                        .gap_1()
+                        .child(SearchOptions::CASE_SENSITIVE.as_button(
+                            self.is_option_enabled(SearchOptions::CASE_SENSITIVE, cx),
+                            focus_handle.clone(),
+                            cx.listener(|this, _, window, cx| {
+                                this.toggle_search_option(
+                                    SearchOptions::CASE_SENSITIVE,
+                                    window,
+                                    cx,
+                                );
+                            }),
+                        ))
+                        .child(SearchOptions::WHOLE_WORD.as_button(
+                            self.is_option_enabled(SearchOptions::WHOLE_WORD, cx),
+                            focus_handle.clone(),
+                            cx.listener(|this, _, window, cx| {
+                                this.toggle_search_option(SearchOptions::WHOLE_WORD, window, cx);
+                            }),
+                        ))
+                        .child(SearchOptions::REGEX.as_button(
+                            self.is_option_enabled(SearchOptions::REGEX, cx),
+                            focus_handle.clone(),
+                            cx.listener(|this, _, window, cx| {
+                                this.toggle_search_option(SearchOptions::REGEX, window, cx);
+                            }),
+                        )),
+                )
+            });
 
+        let toggled_mode = next_mode(&search.current_mode, true);
         let mode_column = h_flex()
             .gap_1()
+            .when(search.semantic_search_available(cx), |this| {
+                this.child(
+                    IconButton::new("project-search-semantic-mode", IconName::Sparkle)
+                        .shape(IconButtonShape::Square)
+                        .toggle_state(search.current_mode == SearchMode::Semantic)
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.cycle_mode(&CycleMode, window, cx);
+                        }))
+                        .tooltip({
+                            let focus_handle = focus_handle.clone();
+                            move |window, cx| {
+                                Tooltip::for_action_in(
+                                    toggled_mode.tooltip(),
+                                    &*toggled_mode.action(),
+                                    &focus_handle,
+                                    window,
+                                    cx,
+                                )
+                            }
+                        }),
+                )
+            })
             .child(
                 IconButton::new("project-search-filter-button", IconName::Filter)
                     .shape(IconButtonShape::Square)
@@ -2341,6 +2602,7 @@ impl Render for ProjectSearchBar {
             .on_action(cx.listener(|this, _: &ToggleCaseSensitive, window, cx| {
                 this.toggle_search_option(SearchOptions::CASE_SENSITIVE, window, cx);
             }))
+            .on_action(cx.listener(Self::cycle_mode))
             .on_action(cx.listener(|this, action, window, cx| {
                 if let Some(search) = this.active_project_search.as_ref() {
                     search.update(cx, |this, cx| {
@@ -2471,6 +2733,7 @@ pub mod tests {
     use editor::{DisplayPoint, display_map::DisplayRow};
     use gpui::{Action, TestAppContext, VisualTestContext, WindowHandle};
     use project::FakeFs;
+    use semantic_index::{FakeEmbeddingProvider, Status};
     use serde_json::json;
     use settings::SettingsStore;
     use util::path;
@@ -2603,6 +2866,68 @@ pub mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_semantic_project_search(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = 2;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut semantic_db = SemanticDb::new(
+            temp_dir.path().into(),
+            Arc::new(FakeEmbeddingProvider),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        let project_index = cx.update(|cx| semantic_db.create_project_index(project.clone(), cx));
+        cx.update(|cx| cx.set_global(semantic_db));
+        cx.run_until_parked();
+        while project_index.read_with(cx, |project_index, _| project_index.status()) != Status::Idle
+        {
+            cx.run_until_parked();
+        }
+
+        let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let workspace = window.root(cx).unwrap();
+        let search = cx.new(|cx| ProjectSearch::new(project.clone(), cx));
+        let search_view = cx.add_window(|window, cx| {
+            ProjectSearchView::new(workspace.downgrade(), search.clone(), window, cx, None)
+        });
+
+        search_view
+            .update(cx, |search_view, window, cx| {
+                assert!(search_view.semantic_search_available(cx));
+                search_view.activate_search_mode(SearchMode::Semantic, window, cx);
+                assert_eq!(search_view.current_mode, SearchMode::Semantic);
+            })
+            .unwrap();
+
+        perform_search(search_view, "numeric constants", cx);
+        search_view
+            .update(cx, |search_view, _, cx| {
+                let search = search_view.entity.read(cx);
+                assert!(search.active_query.is_none());
+                assert_eq!(search.match_ranges.len(), 2);
+                let results_text = search_view
+                    .results_editor
+                    .update(cx, |editor, cx| editor.display_text(cx));
+                assert!(results_text.contains("const ONE: usize = 1;"));
+                assert!(results_text.contains("const TWO: usize = 2;"));
+            })
+            .unwrap();
+    }
+
     #[gpui::test]
     async fn test_deploy_project_search_focus(cx: &mut TestAppContext) {
         init_test(cx);
@@ -10,6 +10,7 @@ use workspace::notifications::NotificationId;
 use workspace::{Toast, Workspace};
 
 pub mod buffer_search;
+pub mod mode;
 pub mod project_search;
 pub(crate) mod search_bar;
 pub mod search_status_button;
@@ -45,6 +46,10 @@ actions!(
         SelectAllMatches,
         /// Cycles through search modes.
         CycleMode,
+        /// Switches project search to plain text and regex matching.
+        ActivateTextMode,
+        /// Switches project search to semantic matching against the project's index.
+        ActivateSemanticMode,
         /// Navigates to the next query in search history.
         NextHistoryQuery,
         /// Navigates to the previous query in search history.
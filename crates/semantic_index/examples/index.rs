@@ -76,8 +76,7 @@ fn main() {
             .unwrap();
 
             let project_index = cx
-                .update(|cx| semantic_index.project_index(project.clone(), cx))
-                .unwrap()
+                .update(|cx| semantic_index.create_project_index(project.clone(), cx))
                 .unwrap();
 
             let (tx, rx) = oneshot::channel();
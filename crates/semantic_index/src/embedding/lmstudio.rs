@@ -13,6 +13,7 @@ pub enum LmStudioEmbeddingModel {
 pub struct LmStudioEmbeddingProvider {
     client: Arc<dyn HttpClient>,
     model: LmStudioEmbeddingModel,
+    api_url: String,
 }
 
 #[derive(Serialize)]
@@ -27,8 +28,16 @@ struct LmStudioEmbeddingResponse {
 }
 
 impl LmStudioEmbeddingProvider {
-    pub fn new(client: Arc<dyn HttpClient>, model: LmStudioEmbeddingModel) -> Self {
-        Self { client, model }
+    pub fn new(
+        client: Arc<dyn HttpClient>,
+        model: LmStudioEmbeddingModel,
+        api_url: String,
+    ) -> Self {
+        Self {
+            client,
+            model,
+            api_url,
+        }
     }
 }
 
@@ -49,7 +58,7 @@ impl EmbeddingProvider for LmStudioEmbeddingProvider {
             async {
                 let response = self
                     .client
-                    .post_json("http://localhost:1234/api/v0/embeddings", request.into())
+                    .post_json(&format!("{}/embeddings", self.api_url), request.into())
                     .await?;
 
                 let mut body = String::new();
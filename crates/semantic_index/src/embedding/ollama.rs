@@ -14,6 +14,7 @@ pub enum OllamaEmbeddingModel {
 pub struct OllamaEmbeddingProvider {
     client: Arc<dyn HttpClient>,
     model: OllamaEmbeddingModel,
+    api_url: String,
 }
 
 #[derive(Serialize)]
@@ -28,8 +29,12 @@ struct OllamaEmbeddingResponse {
 }
 
 impl OllamaEmbeddingProvider {
-    pub fn new(client: Arc<dyn HttpClient>, model: OllamaEmbeddingModel) -> Self {
-        Self { client, model }
+    pub fn new(client: Arc<dyn HttpClient>, model: OllamaEmbeddingModel, api_url: String) -> Self {
+        Self {
+            client,
+            model,
+            api_url,
+        }
     }
 }
 
@@ -52,7 +57,7 @@ impl EmbeddingProvider for OllamaEmbeddingProvider {
             async {
                 let response = self
                     .client
-                    .post_json("http://localhost:11434/api/embeddings", request.into())
+                    .post_json(&format!("{}/api/embeddings", self.api_url), request.into())
                     .await?;
 
                 let mut body = String::new();
@@ -4,6 +4,7 @@ mod embedding_index;
 mod indexing;
 mod project_index;
 mod project_index_debug_view;
+mod semantic_index_settings;
 mod summary_backlog;
 mod summary_index;
 mod worktree_index;
@@ -12,8 +13,10 @@ use anyhow::{Context as _, Result};
 use collections::HashMap;
 use fs::Fs;
 use gpui::{App, AppContext as _, AsyncApp, BorrowAppContext, Context, Entity, Global, WeakEntity};
+use http_client::HttpClient;
 use language::LineEnding;
 use project::{Project, Worktree};
+use settings::{Settings as _, SettingsStore};
 use std::{
     cmp::Ordering,
     path::{Path, PathBuf},
@@ -25,6 +28,7 @@ use workspace::Workspace;
 pub use embedding::*;
 pub use project_index::{LoadedSearchResult, ProjectIndex, SearchResult, Status};
 pub use project_index_debug_view::ProjectIndexDebugView;
+pub use semantic_index_settings::{EmbeddingProviderName, SemanticIndexSettings};
 pub use summary_index::FileSummary;
 
 pub struct SemanticDb {
@@ -35,6 +39,69 @@ pub struct SemanticDb {
 
 impl Global for SemanticDb {}
 
+pub fn init(client: Arc<dyn HttpClient>, cx: &mut App) {
+    SemanticIndexSettings::register(cx);
+
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, cx: &mut Context<Workspace>| {
+            let project = workspace.project().clone();
+            if cx.has_global::<SemanticDb>() {
+                cx.update_global::<SemanticDb, _>(|this, cx| {
+                    this.create_project_index(project, cx);
+                })
+            } else {
+                log::info!("No SemanticDb, skipping project index")
+            }
+        },
+    )
+    .detach();
+
+    let mut settings = SemanticIndexSettings::get_global(cx).clone();
+    load_semantic_db(settings.clone(), client.clone(), cx);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let new_settings = SemanticIndexSettings::get_global(cx);
+        if *new_settings != settings {
+            settings = new_settings.clone();
+            load_semantic_db(settings.clone(), client.clone(), cx);
+        }
+    })
+    .detach();
+}
+
+/// Replaces the global [`SemanticDb`] with one matching the given settings,
+/// and indexes the projects of any workspaces that are already open.
+fn load_semantic_db(settings: SemanticIndexSettings, client: Arc<dyn HttpClient>, cx: &mut App) {
+    if cx.has_global::<SemanticDb>() {
+        cx.remove_global::<SemanticDb>();
+    }
+    if !settings.enabled {
+        return;
+    }
+
+    cx.spawn(async move |cx| {
+        let embedding_provider = settings.embedding_provider(client, cx).await?;
+        let semantic_db = SemanticDb::new(settings.db_path(), embedding_provider, cx).await?;
+        cx.update(|cx| {
+            if cx.has_global::<SemanticDb>() || *SemanticIndexSettings::get_global(cx) != settings {
+                return;
+            }
+            cx.set_global(semantic_db);
+            for workspace in workspace::local_workspace_windows(cx) {
+                let Ok(project) = workspace
+                    .read(cx)
+                    .map(|workspace| workspace.project().clone())
+                else {
+                    continue;
+                };
+                cx.update_global::<SemanticDb, _>(|this, cx| {
+                    this.create_project_index(project, cx);
+                });
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
 impl SemanticDb {
     pub async fn new(
         db_path: PathBuf,
@@ -54,24 +121,6 @@ impl SemanticDb {
             .await
             .context("opening database connection")?;
 
-        cx.update(|cx| {
-            cx.observe_new(
-                |workspace: &mut Workspace, _window, cx: &mut Context<Workspace>| {
-                    let project = workspace.project().clone();
-
-                    if cx.has_global::<SemanticDb>() {
-                        cx.update_global::<SemanticDb, _>(|this, cx| {
-                            this.create_project_index(project, cx);
-                        })
-                    } else {
-                        log::info!("No SemanticDb, skipping project index")
-                    }
-                },
-            )
-            .detach();
-        })
-        .ok();
-
         Ok(SemanticDb {
             db_connection: Some(db_connection),
             embedding_provider,
@@ -205,9 +254,9 @@ impl SemanticDb {
     }
 
     pub fn project_index(
-        &mut self,
+        &self,
         project: Entity<Project>,
-        _cx: &mut App,
+        _cx: &App,
     ) -> Option<Entity<ProjectIndex>> {
         self.project_indices.get(&project.downgrade()).cloned()
     }
@@ -268,8 +317,9 @@ mod tests {
     use embedding_index::{ChunkedFile, EmbeddingIndex};
     use feature_flags::FeatureFlagAppExt;
     use fs::FakeFs;
-    use futures::{FutureExt, future::BoxFuture};
+    use futures::{AsyncReadExt as _, FutureExt, future::BoxFuture};
     use gpui::TestAppContext;
+    use http_client::{FakeHttpClient, Response};
     use indexing::IndexingEntrySet;
     use language::language_settings::AllLanguageSettings;
     use project::{Project, ProjectEntryId};
@@ -288,6 +338,7 @@ mod tests {
             language::init(cx);
             cx.update_flags(false, vec![]);
             Project::init_settings(cx);
+            SemanticIndexSettings::register(cx);
             SettingsStore::update(cx, |store, cx| {
                 store.update_user_settings::<AllLanguageSettings>(cx, |_| {});
             });
@@ -334,11 +385,6 @@ mod tests {
 
         init_test(cx);
 
-        cx.update(|cx| {
-            // This functionality is staff-flagged.
-            cx.update_flags(true, vec![]);
-        });
-
         let temp_dir = tempfile::tempdir().unwrap();
 
         let mut semantic_index = SemanticDb::new(
@@ -440,6 +486,101 @@ mod tests {
         assert!(content.contains("garbage in, garbage out"));
     }
 
+    #[gpui::test]
+    async fn test_search_with_ollama(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        init_test(cx);
+
+        // Stand in for a local Ollama server, embedding prompts that mention
+        // "garbage in" close to each other.
+        let http_client = FakeHttpClient::create(|request| async move {
+            anyhow::ensure!(
+                request.uri().to_string() == "http://localhost:11434/api/embeddings",
+                "unexpected request to {}",
+                request.uri()
+            );
+            let mut body = String::new();
+            request.into_body().read_to_string(&mut body).await?;
+            let body: serde_json::Value = serde_json::from_str(&body)?;
+            anyhow::ensure!(body["model"] == "nomic-embed-text");
+            let prompt = body["prompt"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("missing prompt"))?;
+            let embedding = if prompt.contains("garbage in") {
+                [0.9, -0.9]
+            } else {
+                [-0.9, 0.9]
+            };
+            Ok(Response::builder()
+                .status(200)
+                .body(json!({ "embedding": embedding }).to_string().into())?)
+        });
+
+        cx.update(|cx| {
+            SettingsStore::update(cx, |store, cx| {
+                store.update_user_settings::<SemanticIndexSettings>(cx, |settings| {
+                    settings.enabled = true;
+                    settings.provider = EmbeddingProviderName::Ollama;
+                });
+            });
+        });
+        let settings = cx.update(|cx| SemanticIndexSettings::get_global(cx).clone());
+        let embedding_provider = settings
+            .embedding_provider(http_client, &cx.to_async())
+            .await
+            .unwrap();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut semantic_index = SemanticDb::new(
+            temp_dir.path().into(),
+            embedding_provider,
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        let fs = FakeFs::new(cx.executor());
+        let project_path = Path::new("/fake_project");
+        fs.insert_tree(
+            project_path,
+            json!({
+                "fixture": {
+                    "main.rs": include_str!("../fixture/main.rs"),
+                    "needle.md": include_str!("../fixture/needle.md"),
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [project_path], cx).await;
+
+        let project_index = cx.update(|cx| {
+            let language_registry = project.read(cx).languages().clone();
+            let node_runtime = project.read(cx).node_runtime().unwrap().clone();
+            languages::init(language_registry, node_runtime, cx);
+            semantic_index.create_project_index(project.clone(), cx)
+        });
+
+        cx.run_until_parked();
+        while project_index.read_with(cx, |project_index, _| project_index.status()) != Status::Idle
+        {
+            cx.run_until_parked();
+        }
+
+        let results = cx
+            .update(|cx| {
+                project_index
+                    .read(cx)
+                    .search(vec!["garbage in, garbage out".into()], 4, cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            results.first().unwrap().path.to_string_lossy(),
+            path!("fixture/needle.md")
+        );
+    }
+
     #[gpui::test]
     async fn test_embed_files(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
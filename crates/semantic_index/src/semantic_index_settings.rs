@@ -0,0 +1,141 @@
+use crate::{
+    EmbeddingProvider, LmStudioEmbeddingModel, LmStudioEmbeddingProvider, OllamaEmbeddingModel,
+    OllamaEmbeddingProvider, OpenAiEmbeddingModel, OpenAiEmbeddingProvider,
+};
+use anyhow::{Context as _, Result};
+use credentials_provider::CredentialsProvider;
+use gpui::{App, AsyncApp};
+use http_client::HttpClient;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use std::{path::PathBuf, sync::Arc};
+
+const OLLAMA_API_URL: &str = "http://localhost:11434";
+const LMSTUDIO_API_URL: &str = "http://localhost:1234/api/v0";
+
+/// Settings for semantic code search.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SemanticIndexSettings {
+    /// Whether to index projects so they can be searched semantically.
+    ///
+    /// Default: false
+    pub enabled: bool,
+    /// The service used to compute embeddings.
+    ///
+    /// Default: ollama
+    pub provider: EmbeddingProviderName,
+    /// The embedding model to use. When unset, the provider's default model is used.
+    ///
+    /// Default: null
+    pub model: Option<String>,
+    /// The URL of the provider's API. When unset, the provider's default URL is used.
+    ///
+    /// Default: null
+    pub api_url: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingProviderName {
+    #[default]
+    Ollama,
+    OpenAi,
+    LmStudio,
+}
+
+impl SemanticIndexSettings {
+    /// The model used to compute embeddings, falling back to the provider's default.
+    pub fn model(&self) -> &str {
+        self.model.as_deref().unwrap_or(match self.provider {
+            EmbeddingProviderName::Ollama => "nomic-embed-text",
+            EmbeddingProviderName::OpenAi => "text-embedding-3-small",
+            EmbeddingProviderName::LmStudio => "nomic-embed-text",
+        })
+    }
+
+    /// The provider's API URL, falling back to the provider's default.
+    pub fn api_url(&self) -> &str {
+        self.api_url.as_deref().unwrap_or(match self.provider {
+            EmbeddingProviderName::Ollama => OLLAMA_API_URL,
+            EmbeddingProviderName::OpenAi => open_ai::OPEN_AI_API_URL,
+            EmbeddingProviderName::LmStudio => LMSTUDIO_API_URL,
+        })
+    }
+
+    /// Embeddings from different models aren't comparable, so each provider and
+    /// model pair gets its own database.
+    pub(crate) fn db_path(&self) -> PathBuf {
+        let provider = match self.provider {
+            EmbeddingProviderName::Ollama => "ollama",
+            EmbeddingProviderName::OpenAi => "open_ai",
+            EmbeddingProviderName::LmStudio => "lm_studio",
+        };
+        let model = self
+            .model()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect::<String>();
+        paths::embeddings_dir().join(format!("semantic-index-{provider}-{model}"))
+    }
+
+    pub(crate) async fn embedding_provider(
+        &self,
+        client: Arc<dyn HttpClient>,
+        cx: &AsyncApp,
+    ) -> Result<Arc<dyn EmbeddingProvider>> {
+        let api_url = self.api_url().to_string();
+        let model = self.model();
+        Ok(match self.provider {
+            EmbeddingProviderName::Ollama => {
+                let model = match model {
+                    "nomic-embed-text" => OllamaEmbeddingModel::NomicEmbedText,
+                    "mxbai-embed-large" => OllamaEmbeddingModel::MxbaiEmbedLarge,
+                    _ => anyhow::bail!("unsupported Ollama embedding model {model:?}"),
+                };
+                Arc::new(OllamaEmbeddingProvider::new(client, model, api_url))
+            }
+            EmbeddingProviderName::OpenAi => {
+                let model = match model {
+                    "text-embedding-3-small" => OpenAiEmbeddingModel::TextEmbedding3Small,
+                    "text-embedding-3-large" => OpenAiEmbeddingModel::TextEmbedding3Large,
+                    _ => anyhow::bail!("unsupported OpenAI embedding model {model:?}"),
+                };
+                let api_key = if let Ok(api_key) = std::env::var("OPENAI_API_KEY") {
+                    api_key
+                } else {
+                    let credentials_provider =
+                        cx.update(|cx| <dyn CredentialsProvider>::global(cx))?;
+                    let (_, api_key) = credentials_provider
+                        .read_credentials(&api_url, cx)
+                        .await?
+                        .context("no OpenAI API key, set OPENAI_API_KEY")?;
+                    String::from_utf8(api_key).context("invalid OpenAI API key")?
+                };
+                Arc::new(OpenAiEmbeddingProvider::new(
+                    client, model, api_url, api_key,
+                ))
+            }
+            EmbeddingProviderName::LmStudio => {
+                let model = match model {
+                    "nomic-embed-text" => LmStudioEmbeddingModel::NomicEmbedText,
+                    _ => anyhow::bail!("unsupported LM Studio embedding model {model:?}"),
+                };
+                Arc::new(LmStudioEmbeddingProvider::new(client, model, api_url))
+            }
+        })
+    }
+}
+
+impl Settings for SemanticIndexSettings {
+    const KEY: Option<&'static str> = Some("semantic_index");
+
+    type FileContent = Self;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> Result<Self> {
+        sources.json_merge()
+    }
+
+    fn import_from_vscode(_vscode: &settings::VsCodeSettings, _current: &mut Self::FileContent) {}
+}
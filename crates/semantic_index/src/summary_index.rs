@@ -570,6 +570,7 @@ impl SummaryIndex {
             tool_choice: None,
             stop: Vec::new(),
             temperature: None,
+            max_tokens: None,
             thinking_allowed: true,
         };
 
@@ -1,5 +1,5 @@
 use anyhow::{Context as _, Result};
-use collections::FxHashMap;
+use collections::{FxHashMap, HashMap};
 use gpui::SharedString;
 use log as _;
 use schemars::JsonSchema;
@@ -8,7 +8,11 @@ use std::net::Ipv4Addr;
 use std::path::PathBuf;
 use util::{debug_panic, schemars::add_new_subschema};
 
-use crate::{TaskTemplate, adapter_schema::AdapterSchemas};
+use crate::{
+    TaskInput, TaskTemplate,
+    adapter_schema::AdapterSchemas,
+    task_input::{json_references_input, substitute_input_values, substitute_input_values_in_json},
+};
 
 /// Represents the host information of the debug adapter
 #[derive(Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, Clone, Debug)]
@@ -266,6 +270,7 @@ pub struct DebugScenario {
     /// Name of the debug task
     pub label: SharedString,
     /// A task to run prior to spawning the debuggee.
+    /// The debuggee is spawned after the task finishes successfully, or, for background tasks, once the task reports being ready.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub build: Option<BuildTaskDefinition>,
     /// The main arguments to be sent to the debug adapter
@@ -278,6 +283,32 @@ pub struct DebugScenario {
     /// that is already running or is started by another process.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tcp_connection: Option<TcpArgumentsTemplate>,
+    /// Inputs to ask the user for when the scenario is started, referenced in its label and configuration as `${input:id}`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<TaskInput>,
+}
+
+impl DebugScenario {
+    /// Inputs of the scenario that are referenced in its label or configuration,
+    /// and need to be asked for before the scenario is started.
+    pub fn referenced_inputs(&self) -> Vec<&TaskInput> {
+        self.inputs
+            .iter()
+            .filter(|input| {
+                self.label.contains(&input.reference())
+                    || json_references_input(&self.config, input)
+            })
+            .collect()
+    }
+
+    /// Replaces the `${input:id}` references in the scenario's label and configuration with the input values given.
+    pub fn with_input_values(&self, values: &HashMap<String, String>) -> Self {
+        Self {
+            label: substitute_input_values(&self.label, values).into(),
+            config: substitute_input_values_in_json(self.config.clone(), values),
+            ..self.clone()
+        }
+    }
 }
 
 /// A group of Debug Tasks defined in a JSON file.
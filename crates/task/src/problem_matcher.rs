@@ -0,0 +1,609 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result};
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A way to find problems (errors, warnings, etc.) in the output of a task,
+/// either by referencing one of the predefined matchers, or by defining a custom one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcherTemplate {
+    /// A name of a predefined problem matcher, e.g. `$rustc`, see [`ProblemMatcher::preset`].
+    Preset(String),
+    /// A custom problem matcher.
+    Custom(ProblemMatcher),
+}
+
+impl ProblemMatcherTemplate {
+    /// Turns the template into a problem matcher, `None` if the preset referenced is unknown.
+    pub fn resolve(&self) -> Option<ProblemMatcher> {
+        match self {
+            Self::Preset(name) => ProblemMatcher::preset(name),
+            Self::Custom(matcher) => Some(matcher.clone()),
+        }
+    }
+}
+
+/// Describes how to find problems in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemMatcher {
+    /// A name of the tool that produces the problems, displayed as the source of the diagnostics.
+    pub source: String,
+    /// A severity to use for the problems, when the pattern does not capture any.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+    /// How to interpret the file paths captured by the pattern.
+    #[serde(default)]
+    pub file_location: FileLocation,
+    /// Regular expressions to match the lines of the output with.
+    /// Multiple patterns describe a problem that spans multiple consecutive lines.
+    #[serde(deserialize_with = "one_or_many")]
+    #[schemars(with = "Vec<ProblemPattern>")]
+    pub pattern: Vec<ProblemPattern>,
+}
+
+/// A regular expression to match a single line of a task output with,
+/// with indices of the capture groups that contain the problem's data.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match the line with.
+    pub regexp: String,
+    /// The capture group with the path of the file.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// The capture group with the 1-based line of the problem.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// The capture group with the 1-based column of the problem.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// The capture group with the 1-based line the problem ends at.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// The capture group with the 1-based column the problem ends at.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// The capture group with the severity of the problem.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// The capture group with the code of the problem.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// The capture group with the message of the problem.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether the pattern, being the last one in the matcher, may match multiple consecutive lines,
+    /// each producing a separate problem.
+    #[serde(default, rename = "loop")]
+    pub repeat: bool,
+}
+
+/// A severity of the problem, mapped to the corresponding diagnostic severity.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+/// How to interpret the file paths found in the task output.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileLocation {
+    /// Absolute paths are used as is, relative ones are resolved against the task's working directory.
+    #[default]
+    AutoDetect,
+    /// All paths are absolute.
+    Absolute,
+    /// All paths are relative to the task's working directory.
+    Relative,
+}
+
+/// A problem found in the task output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskProblem {
+    /// The path of the file, as printed by the task.
+    pub path: PathBuf,
+    /// 1-based line of the problem.
+    pub line: u32,
+    /// 1-based column of the problem, if reported.
+    pub column: Option<u32>,
+    pub end_line: Option<u32>,
+    pub end_column: Option<u32>,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+    /// The [`ProblemMatcher::source`] of the matcher that found the problem.
+    pub source: String,
+    pub file_location: FileLocation,
+}
+
+impl TaskProblem {
+    /// An absolute path of the file with the problem, if it can be determined.
+    pub fn abs_path(&self, task_cwd: Option<&Path>) -> Option<PathBuf> {
+        match self.file_location {
+            FileLocation::Absolute => Some(self.path.clone()),
+            FileLocation::Relative => Some(task_cwd?.join(&self.path)),
+            FileLocation::AutoDetect if self.path.is_absolute() => Some(self.path.clone()),
+            FileLocation::AutoDetect => Some(task_cwd?.join(&self.path)),
+        }
+    }
+}
+
+/// The predefined problem matchers, by name.
+const PRESETS: &[(&str, fn() -> ProblemMatcher)] = &[
+    ("$rustc", ProblemMatcher::rustc),
+    ("$tsc", ProblemMatcher::tsc),
+    // Only the problems are matched here, the watch mode is detected with the task's background patterns.
+    ("$tsc-watch", ProblemMatcher::tsc),
+    ("$gcc", ProblemMatcher::gcc),
+    ("$eslint-compact", ProblemMatcher::eslint_compact),
+    ("$eslint-stylish", ProblemMatcher::eslint_stylish),
+];
+
+impl ProblemMatcher {
+    /// Names of the predefined problem matchers.
+    pub fn preset_names() -> impl Iterator<Item = &'static str> {
+        PRESETS.iter().map(|(name, _)| *name)
+    }
+
+    /// Returns a predefined problem matcher by its name.
+    pub fn preset(name: &str) -> Option<Self> {
+        PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .map(|(_, matcher)| matcher())
+    }
+
+    fn rustc() -> Self {
+        Self {
+            source: "rustc".to_string(),
+            severity: ProblemSeverity::Error,
+            file_location: FileLocation::AutoDetect,
+            pattern: vec![
+                ProblemPattern {
+                    regexp: r"^(warning|error)(?:\[(\w+)\])?: (.*)$".to_string(),
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s*--> (.*?):(\d+):(\d+)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..ProblemPattern::default()
+                },
+            ],
+        }
+    }
+
+    fn tsc() -> Self {
+        Self {
+            source: "tsc".to_string(),
+            severity: ProblemSeverity::Error,
+            file_location: FileLocation::Relative,
+            pattern: vec![ProblemPattern {
+                regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.*)$".to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..ProblemPattern::default()
+            }],
+        }
+    }
+
+    fn gcc() -> Self {
+        Self {
+            source: "gcc".to_string(),
+            severity: ProblemSeverity::Error,
+            file_location: FileLocation::AutoDetect,
+            pattern: vec![ProblemPattern {
+                regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error):\s+(.*)$".to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..ProblemPattern::default()
+            }],
+        }
+    }
+
+    fn eslint_compact() -> Self {
+        Self {
+            source: "eslint".to_string(),
+            severity: ProblemSeverity::Error,
+            file_location: FileLocation::AutoDetect,
+            pattern: vec![ProblemPattern {
+                regexp:
+                    r"^(.+):\sline\s(\d+),\scol\s(\d+),\s(Error|Warning|Info)\s-\s(.+)\s\((.+)\)$"
+                        .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                code: Some(6),
+                ..ProblemPattern::default()
+            }],
+        }
+    }
+
+    fn eslint_stylish() -> Self {
+        Self {
+            source: "eslint".to_string(),
+            severity: ProblemSeverity::Error,
+            file_location: FileLocation::AutoDetect,
+            pattern: vec![
+                ProblemPattern {
+                    regexp: r"^((?:[a-zA-Z]:)*[./\\]+.*?)$".to_string(),
+                    file: Some(1),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.*?)(?:\s\s+(.*))?$"
+                        .to_string(),
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    repeat: true,
+                    ..ProblemPattern::default()
+                },
+            ],
+        }
+    }
+}
+/// Matches task output lines, one at a time, against a [`ProblemMatcher`].
+pub struct ProblemMatcherParser {
+    matcher: ProblemMatcher,
+    regexes: Vec<Regex>,
+    next_pattern: usize,
+    pending: PendingProblem,
+}
+
+#[derive(Clone, Default)]
+struct PendingProblem {
+    path: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl ProblemMatcherParser {
+    pub fn new(matcher: ProblemMatcher) -> Result<Self> {
+        anyhow::ensure!(
+            !matcher.pattern.is_empty(),
+            "problem matcher `{}` has no patterns",
+            matcher.source
+        );
+        let regexes = matcher
+            .pattern
+            .iter()
+            .map(|pattern| {
+                Regex::new(&pattern.regexp).with_context(|| {
+                    format!(
+                        "compiling problem matcher `{}` pattern {:?}",
+                        matcher.source, pattern.regexp
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            matcher,
+            regexes,
+            next_pattern: 0,
+            pending: PendingProblem::default(),
+        })
+    }
+
+    /// Processes the next line of the task output, without the line ending.
+    /// Returns a problem, if the line completes one.
+    pub fn push_line(&mut self, line: &str) -> Option<TaskProblem> {
+        if self.next_pattern > 0 {
+            if let Some(captures) = self.regexes[self.next_pattern].captures(line) {
+                return self.apply(self.next_pattern, &captures);
+            }
+            self.next_pattern = 0;
+            self.pending = PendingProblem::default();
+        }
+        let captures = self.regexes[0].captures(line)?;
+        self.apply(0, &captures)
+    }
+
+    fn apply(&mut self, pattern_ix: usize, captures: &Captures) -> Option<TaskProblem> {
+        let pattern = &self.matcher.pattern[pattern_ix];
+        if pattern_ix == 0 {
+            self.pending = PendingProblem::default();
+        }
+
+        let is_last = pattern_ix + 1 == self.matcher.pattern.len();
+        if !is_last {
+            self.pending.fill(pattern, captures);
+            self.next_pattern = pattern_ix + 1;
+            return None;
+        }
+
+        // The last pattern may repeat, so keep the data of the previous lines intact for the next match.
+        let mut problem = self.pending.clone();
+        problem.fill(pattern, captures);
+        if pattern.repeat && pattern_ix > 0 {
+            self.next_pattern = pattern_ix;
+        } else {
+            self.next_pattern = 0;
+            self.pending = PendingProblem::default();
+        }
+
+        Some(TaskProblem {
+            path: PathBuf::from(problem.path?),
+            line: problem.line?,
+            column: problem.column,
+            end_line: problem.end_line,
+            end_column: problem.end_column,
+            severity: problem.severity.unwrap_or(self.matcher.severity),
+            code: problem.code,
+            message: problem.message?,
+            source: self.matcher.source.clone(),
+            file_location: self.matcher.file_location,
+        })
+    }
+}
+
+impl PendingProblem {
+    fn fill(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let group = |ix: Option<usize>| {
+            captures
+                .get(ix?)
+                .map(|group| group.as_str().trim())
+                .filter(|group| !group.is_empty())
+        };
+        let number = |ix: Option<usize>| group(ix)?.parse::<u32>().ok();
+
+        if let Some(path) = group(pattern.file) {
+            self.path = Some(path.to_string());
+        }
+        if let Some(line) = number(pattern.line) {
+            self.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            self.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) = group(pattern.severity).and_then(parse_severity) {
+            self.severity = Some(severity);
+        }
+        if let Some(code) = group(pattern.code) {
+            self.code = Some(code.to_string());
+        }
+        if let Some(message) = group(pattern.message) {
+            self.message = Some(message.to_string());
+        }
+    }
+}
+
+fn parse_severity(severity: &str) -> Option<ProblemSeverity> {
+    match severity.to_lowercase().as_str() {
+        "error" | "fatal" | "e" => Some(ProblemSeverity::Error),
+        "warning" | "warn" | "w" => Some(ProblemSeverity::Warning),
+        "info" | "information" | "note" | "i" => Some(ProblemSeverity::Info),
+        "hint" | "help" | "h" => Some(ProblemSeverity::Hint),
+        _ => None,
+    }
+}
+
+/// Deserializes either a single value or a list of them, as VS Code allows for matchers and their patterns.
+pub(crate) fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(matcher: &str, output: &str) -> Vec<TaskProblem> {
+        let mut parser = ProblemMatcherParser::new(ProblemMatcher::preset(matcher).unwrap())
+            .expect("presets should compile");
+        output
+            .lines()
+            .filter_map(|line| parser.push_line(line))
+            .collect()
+    }
+
+    #[test]
+    fn test_presets_compile() {
+        for preset in ProblemMatcher::preset_names() {
+            let matcher = ProblemMatcher::preset(preset)
+                .unwrap_or_else(|| panic!("preset {preset} is not defined"));
+            assert!(
+                ProblemMatcherParser::new(matcher).is_ok(),
+                "preset {preset} should compile"
+            );
+        }
+        assert_eq!(ProblemMatcher::preset("$unknown"), None);
+    }
+
+    #[test]
+    fn test_rustc_multiline_problems() {
+        let output = r#"   Compiling zed v0.1.0
+error[E0308]: mismatched types
+  --> src/main.rs:4:18
+   |
+4  |     let x: u32 = "1";
+   |            ---   ^^^ expected `u32`, found `&str`
+warning: unused variable: `y`
+ --> crates/task/src/lib.rs:10:9
+error: aborting due to 1 previous error
+"#;
+        let problems = parse("$rustc", output);
+        assert_eq!(
+            problems,
+            vec![
+                TaskProblem {
+                    path: PathBuf::from("src/main.rs"),
+                    line: 4,
+                    column: Some(18),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0308".to_string()),
+                    message: "mismatched types".to_string(),
+                    source: "rustc".to_string(),
+                    file_location: FileLocation::AutoDetect,
+                },
+                TaskProblem {
+                    path: PathBuf::from("crates/task/src/lib.rs"),
+                    line: 10,
+                    column: Some(9),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `y`".to_string(),
+                    source: "rustc".to_string(),
+                    file_location: FileLocation::AutoDetect,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_presets() {
+        let problems = parse(
+            "$tsc",
+            "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.",
+        );
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, PathBuf::from("src/index.ts"));
+        assert_eq!((problems[0].line, problems[0].column), (3, Some(7)));
+        assert_eq!(problems[0].code.as_deref(), Some("TS2322"));
+
+        let problems = parse(
+            "$gcc",
+            "main.c:12:5: warning: implicit declaration of function 'foo'\nmain.c:20: error: expected ';'",
+        );
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].severity, ProblemSeverity::Warning);
+        assert_eq!(problems[1].column, None);
+        assert_eq!(problems[1].message, "expected ';'");
+
+        let problems = parse(
+            "$eslint-compact",
+            "/app/src/a.js: line 1, col 10, Error - 'x' is defined but never used. (no-unused-vars)",
+        );
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].code.as_deref(), Some("no-unused-vars"));
+        assert_eq!(problems[0].severity, ProblemSeverity::Error);
+    }
+
+    #[test]
+    fn test_looping_pattern() {
+        let output = r#"
+./src/a.js
+   1:10  error    'x' is defined but never used  no-unused-vars
+   3:1   warning  Unexpected console statement   no-console
+
+./src/b.js
+  7:3  error  Missing semicolon  semi
+"#;
+        let problems = parse("$eslint-stylish", output);
+        let summary = problems
+            .iter()
+            .map(|problem| {
+                (
+                    problem.path.to_string_lossy().into_owned(),
+                    problem.line,
+                    problem.severity,
+                    problem.code.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "./src/a.js".to_string(),
+                    1,
+                    ProblemSeverity::Error,
+                    Some("no-unused-vars".to_string())
+                ),
+                (
+                    "./src/a.js".to_string(),
+                    3,
+                    ProblemSeverity::Warning,
+                    Some("no-console".to_string())
+                ),
+                (
+                    "./src/b.js".to_string(),
+                    7,
+                    ProblemSeverity::Error,
+                    Some("semi".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_matcher_deserialization() {
+        let template: ProblemMatcherTemplate = serde_json::from_value(serde_json::json!({
+            "source": "mytool",
+            "severity": "warning",
+            "file_location": "relative",
+            "pattern": {
+                "regexp": "^(.*):(\\d+): (.*)$",
+                "file": 1,
+                "line": 2,
+                "message": 3
+            }
+        }))
+        .unwrap();
+        let matcher = template.resolve().unwrap();
+        assert_eq!(matcher.pattern.len(), 1);
+
+        let mut parser = ProblemMatcherParser::new(matcher).unwrap();
+        let problem = parser.push_line("lib/x.py:5: something is off").unwrap();
+        assert_eq!(problem.severity, ProblemSeverity::Warning);
+        assert_eq!(
+            problem.abs_path(Some(Path::new("/work"))),
+            Some(PathBuf::from("/work/lib/x.py"))
+        );
+
+        let preset: ProblemMatcherTemplate = serde_json::from_str("\"$rustc\"").unwrap();
+        assert_eq!(preset, ProblemMatcherTemplate::Preset("$rustc".to_string()));
+    }
+}
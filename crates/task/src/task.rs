@@ -2,9 +2,11 @@
 
 mod adapter_schema;
 mod debug_format;
+mod problem_matcher;
 mod serde_helpers;
 mod shell_builder;
 pub mod static_source;
+mod task_input;
 mod task_template;
 mod vscode_debug_format;
 mod vscode_format;
@@ -22,10 +24,15 @@ pub use debug_format::{
     AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
     Request, TcpArgumentsTemplate, ZedDebugConfig,
 };
+pub use problem_matcher::{
+    FileLocation, ProblemMatcher, ProblemMatcherParser, ProblemMatcherTemplate, ProblemPattern,
+    ProblemSeverity, TaskProblem,
+};
 pub use shell_builder::{DEFAULT_REMOTE_SHELL, ShellBuilder};
+pub use task_input::{PickStringOption, TaskInput};
 pub use task_template::{
-    DebugArgsRequest, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
-    substitute_variables_in_map, substitute_variables_in_str,
+    BackgroundPatterns, DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, TaskTemplate,
+    TaskTemplates, substitute_variables_in_map, substitute_variables_in_str,
 };
 pub use vscode_debug_format::VsCodeDebugTaskFile;
 pub use vscode_format::VsCodeTaskFile;
@@ -74,6 +81,10 @@ pub struct SpawnInTerminal {
     pub show_command: bool,
     /// Whether to show the rerun button in the terminal tab.
     pub show_rerun: bool,
+    /// Problem matchers to find diagnostics in the task output with.
+    pub problem_matchers: Vec<ProblemMatcher>,
+    /// Patterns to detect the readiness of the task with, if it is a background task.
+    pub background: Option<BackgroundPatterns>,
 }
 
 impl SpawnInTerminal {
@@ -117,6 +128,8 @@ pub struct ResolvedTask {
     pub id: TaskId,
     /// A template the task got resolved from.
     original_task: TaskTemplate,
+    /// A context the task got resolved with, used to resolve the task's dependencies.
+    task_context: TaskContext,
     /// Full, unshortened label of the task after all resolutions are made.
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
@@ -132,6 +145,11 @@ impl ResolvedTask {
         &self.original_task
     }
 
+    /// A context the task got resolved with.
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
+
     /// Variables that were substituted during the task template resolution.
     pub fn substituted_variables(&self) -> &HashSet<VariableName> {
         &self.substituted_variables
@@ -141,6 +159,40 @@ impl ResolvedTask {
     pub fn display_label(&self) -> &str {
         self.resolved.label.as_str()
     }
+
+    /// Substitutes the values of the task inputs, see [`TaskTemplate::referenced_inputs`],
+    /// into the resolved task and its template, so that reruns reuse the same values.
+    pub fn with_input_values(&self, values: &HashMap<String, String>) -> Self {
+        let substitute = |string: &str| task_input::substitute_input_values(string, values);
+        let values_hash = task_template::to_hex_hash(values).unwrap_or_default();
+        let id = TaskId(format!("{}_{values_hash}", self.id.0));
+        let resolved = &self.resolved;
+        Self {
+            id: id.clone(),
+            original_task: self.original_task.with_input_values(values),
+            task_context: self.task_context.clone(),
+            resolved_label: substitute(&self.resolved_label),
+            substituted_variables: self.substituted_variables.clone(),
+            resolved: SpawnInTerminal {
+                id,
+                full_label: substitute(&resolved.full_label),
+                label: substitute(&resolved.label),
+                command: resolved.command.as_deref().map(substitute),
+                args: resolved.args.iter().map(|arg| substitute(arg)).collect(),
+                command_label: substitute(&resolved.command_label),
+                cwd: resolved
+                    .cwd
+                    .as_ref()
+                    .map(|cwd| PathBuf::from(substitute(&cwd.to_string_lossy()))),
+                env: resolved
+                    .env
+                    .iter()
+                    .map(|(key, value)| (key.clone(), substitute(value)))
+                    .collect(),
+                ..resolved.clone()
+            },
+        }
+    }
 }
 
 /// Variables, available for use in [`TaskContext`] when a Zed's [`TaskTemplate`] gets resolved into a [`ResolvedTask`].
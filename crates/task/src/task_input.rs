@@ -0,0 +1,200 @@
+use collections::HashMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// An input of a task, which value is asked from the user when the task is spawned.
+/// Task fields reference the input as `${input:id}`, and get the value substituted instead.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskInput {
+    /// Asks the user to type the value in.
+    PromptString {
+        /// A name to reference the input with, as `${input:id}`.
+        id: String,
+        /// A text to show in the prompt.
+        #[serde(default)]
+        description: Option<String>,
+        /// A value to prefill the prompt with.
+        #[serde(default)]
+        default: Option<String>,
+    },
+    /// Asks the user to pick the value from a list of options.
+    PickString {
+        /// A name to reference the input with, as `${input:id}`.
+        id: String,
+        /// A text to show in the prompt.
+        #[serde(default)]
+        description: Option<String>,
+        /// Options to pick the value from.
+        options: Vec<PickStringOption>,
+        /// A value of the option to select initially.
+        #[serde(default)]
+        default: Option<String>,
+    },
+    /// Runs a command without asking the user, and uses its trimmed standard output as the value.
+    Command {
+        /// A name to reference the input with, as `${input:id}`.
+        id: String,
+        /// A command to run in the task's working directory.
+        command: String,
+        /// Arguments to the command.
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+/// An option of a [`TaskInput::PickString`] input.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum PickStringOption {
+    /// An option that is displayed as its value.
+    Value(String),
+    /// An option with a label to display instead of its value.
+    Labeled {
+        /// A text to display in the list.
+        label: String,
+        /// A value to use when the option is picked.
+        value: String,
+    },
+}
+
+impl PickStringOption {
+    /// A text to display the option with.
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Value(value) => value,
+            Self::Labeled { label, .. } => label,
+        }
+    }
+
+    /// A value to substitute when the option is picked.
+    pub fn value(&self) -> &str {
+        match self {
+            Self::Value(value) => value,
+            Self::Labeled { value, .. } => value,
+        }
+    }
+}
+
+impl TaskInput {
+    /// A name to reference the input with, as `${input:id}`.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::PromptString { id, .. }
+            | Self::PickString { id, .. }
+            | Self::Command { id, .. } => id,
+        }
+    }
+
+    /// A text to show in the prompt for the input.
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            Self::PromptString { description, .. } | Self::PickString { description, .. } => {
+                description.as_deref()
+            }
+            Self::Command { .. } => None,
+        }
+    }
+
+    /// A value to use when the user does not provide another one.
+    pub fn default_value(&self) -> Option<&str> {
+        match self {
+            Self::PromptString { default, .. } => default.as_deref(),
+            Self::PickString {
+                default, options, ..
+            } => default
+                .as_deref()
+                .or_else(|| options.first().map(PickStringOption::value)),
+            Self::Command { .. } => None,
+        }
+    }
+
+    /// A string that references the input in the task fields.
+    pub fn reference(&self) -> String {
+        format!("${{input:{}}}", self.id())
+    }
+}
+
+/// Replaces all `${input:id}` references in the string with the values of the corresponding inputs.
+/// References to the inputs without values are left as is.
+pub(crate) fn substitute_input_values(template: &str, values: &HashMap<String, String>) -> String {
+    if !template.contains("${input:") {
+        return template.to_owned();
+    }
+    values
+        .iter()
+        .fold(template.to_owned(), |string, (id, value)| {
+            string.replace(&format!("${{input:{id}}}"), value)
+        })
+}
+
+/// Replaces all `${input:id}` references in the strings of the JSON value, see [`substitute_input_values`].
+pub(crate) fn substitute_input_values_in_json(
+    value: serde_json::Value,
+    values: &HashMap<String, String>,
+) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(s) => {
+            serde_json::Value::String(substitute_input_values(&s, values))
+        }
+        serde_json::Value::Array(arr) => serde_json::Value::Array(
+            arr.into_iter()
+                .map(|v| substitute_input_values_in_json(v, values))
+                .collect(),
+        ),
+        serde_json::Value::Object(obj) => serde_json::Value::Object(
+            obj.into_iter()
+                .map(|(k, v)| (k, substitute_input_values_in_json(v, values)))
+                .collect(),
+        ),
+        _ => value,
+    }
+}
+
+/// Whether any of the strings in the JSON value references the input.
+pub(crate) fn json_references_input(value: &serde_json::Value, input: &TaskInput) -> bool {
+    match value {
+        serde_json::Value::String(s) => s.contains(&input.reference()),
+        serde_json::Value::Array(arr) => arr.iter().any(|v| json_references_input(v, input)),
+        serde_json::Value::Object(obj) => obj.values().any(|v| json_references_input(v, input)),
+        _ => false,
+    }
+}
+
+/// Ids of the inputs that the strings in the JSON value reference as `${input:id}`, but that are not among the `inputs`.
+pub(crate) fn unknown_input_references(
+    value: &serde_json::Value,
+    inputs: &[TaskInput],
+) -> Vec<String> {
+    fn collect(value: &serde_json::Value, inputs: &[TaskInput], unknown: &mut Vec<String>) {
+        match value {
+            serde_json::Value::String(s) => {
+                let mut s = s.as_str();
+                while let Some(start) = s.find("${input:") {
+                    s = &s[start + "${input:".len()..];
+                    let Some(end) = s.find('}') else {
+                        break;
+                    };
+                    let id = &s[..end];
+                    if inputs.iter().all(|input| input.id() != id)
+                        && !unknown.iter().any(|unknown| unknown == id)
+                    {
+                        unknown.push(id.to_owned());
+                    }
+                    s = &s[end..];
+                }
+            }
+            serde_json::Value::Array(arr) => {
+                arr.iter().for_each(|v| collect(v, inputs, unknown));
+            }
+            serde_json::Value::Object(obj) => {
+                obj.values().for_each(|v| collect(v, inputs, unknown));
+            }
+            _ => {}
+        }
+    }
+
+    let mut unknown = Vec::new();
+    collect(value, inputs, &mut unknown);
+    unknown
+}
@@ -9,8 +9,9 @@ use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
+    AttachRequest, ProblemMatcherTemplate, ResolvedTask, RevealTarget, Shell, SpawnInTerminal,
+    TaskContext, TaskId, TaskInput, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    serde_helpers::non_empty_string_vec, task_input::substitute_input_values,
 };
 
 /// A template definition of a Zed task to run.
@@ -72,6 +73,30 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks that have to finish successfully before this task starts.
+    /// A task with dependencies but without a `command` is a compound task, that only runs its dependencies.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks from `depends_on`:
+    /// * `parallel` — start all dependencies at once (default)
+    /// * `sequence` — start each dependency after the previous one has succeeded
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Problem matchers to find errors and warnings in the task output with, reported as project diagnostics.
+    /// Either names of the predefined matchers (`$rustc`, `$tsc`, `$tsc-watch`, `$gcc`, `$eslint-compact`, `$eslint-stylish`),
+    /// or custom matchers with regular expressions.
+    #[serde(default, deserialize_with = "crate::problem_matcher::one_or_many")]
+    pub problem_matcher: Vec<ProblemMatcherTemplate>,
+    /// Whether the task keeps running in the background, e.g. a file watcher or a development server.
+    /// Instead of finishing, such tasks become ready, as detected with `background_patterns`.
+    #[serde(default)]
+    pub is_background: bool,
+    /// Patterns to detect the state of a background task in its output.
+    #[serde(default)]
+    pub background_patterns: BackgroundPatterns,
+    /// Inputs to ask the user for when the task is spawned, referenced in the task fields as `${input:id}`.
+    #[serde(default)]
+    pub inputs: Vec<TaskInput>,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -109,6 +134,30 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// How to run the dependencies of a task.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Start all dependencies at once.
+    #[default]
+    Parallel,
+    /// Start each dependency after the previous one has finished successfully.
+    Sequence,
+}
+
+/// Regular expressions, matched against every line of a background task output, to detect whether the task is ready.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BackgroundPatterns {
+    /// A line matching this pattern marks the task as busy, e.g. when a watcher starts a rebuild.
+    #[serde(default)]
+    pub begins: Option<String>,
+    /// A line matching this pattern marks the task as ready, e.g. when a watcher finishes a rebuild.
+    /// When not set, the task is considered ready as soon as it starts, and after every line that doesn't match `begins`.
+    #[serde(default)]
+    pub ends: Option<String>,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -126,6 +175,52 @@ impl TaskTemplates {
 }
 
 impl TaskTemplate {
+    /// Whether the task only runs its dependencies, without a command of its own.
+    pub fn is_compound(&self) -> bool {
+        self.command.trim().is_empty() && !self.depends_on.is_empty()
+    }
+
+    /// Inputs of the task that are referenced in its fields, and need to be asked for before the task is spawned.
+    pub fn referenced_inputs(&self) -> Vec<&TaskInput> {
+        self.inputs
+            .iter()
+            .filter(|input| {
+                let reference = input.reference();
+                self.label.contains(&reference)
+                    || self.command.contains(&reference)
+                    || self.args.iter().any(|arg| arg.contains(&reference))
+                    || self
+                        .cwd
+                        .as_ref()
+                        .is_some_and(|cwd| cwd.contains(&reference))
+                    || self.env.values().any(|value| value.contains(&reference))
+            })
+            .collect()
+    }
+
+    /// Replaces the `${input:id}` references in the task template string fields with the input values given.
+    pub fn with_input_values(&self, values: &HashMap<String, String>) -> Self {
+        Self {
+            label: substitute_input_values(&self.label, values),
+            command: substitute_input_values(&self.command, values),
+            args: self
+                .args
+                .iter()
+                .map(|arg| substitute_input_values(arg, values))
+                .collect(),
+            cwd: self
+                .cwd
+                .as_ref()
+                .map(|cwd| substitute_input_values(cwd, values)),
+            env: self
+                .env
+                .iter()
+                .map(|(key, value)| (key.clone(), substitute_input_values(value, values)))
+                .collect(),
+            ..self.clone()
+        }
+    }
+
     /// Replaces all `VariableName` task variables in the task template string fields.
     /// If any replacement fails or the new string substitutions still have [`ZED_VARIABLE_NAME_PREFIX`],
     /// `None` is returned.
@@ -133,7 +228,7 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty() || (self.command.trim().is_empty() && !self.is_compound()) {
             return None;
         }
 
@@ -237,10 +332,23 @@ impl TaskTemplate {
             env
         };
 
+        let problem_matchers = self
+            .problem_matcher
+            .iter()
+            .filter_map(|matcher| {
+                let resolved = matcher.resolve();
+                if resolved.is_none() {
+                    log::warn!("Unknown problem matcher {matcher:?} in task {full_label:?}");
+                }
+                resolved
+            })
+            .collect();
+
         Some(ResolvedTask {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            task_context: cx.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
                 id,
@@ -267,6 +375,8 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matchers,
+                background: self.is_background.then(|| self.background_patterns.clone()),
             },
         })
     }
@@ -286,7 +396,7 @@ fn truncate_variables(task_variables: &HashMap<String, &str>) -> HashMap<String,
         .collect()
 }
 
-fn to_hex_hash(object: impl Serialize) -> anyhow::Result<String> {
+pub(crate) fn to_hex_hash(object: impl Serialize) -> anyhow::Result<String> {
     let json = serde_json_lenient::to_string(&object).context("serializing the object")?;
     let mut hasher = Sha256::new();
     hasher.update(json.as_bytes());
@@ -425,7 +535,7 @@ fn substitute_all_template_variables_in_map(
 mod tests {
     use std::{borrow::Cow, path::Path};
 
-    use crate::{TaskVariables, VariableName};
+    use crate::{PickStringOption, TaskVariables, VariableName};
 
     use super::*;
 
@@ -464,6 +574,119 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolving_compound_templates() {
+        let compound_task = TaskTemplate {
+            label: "build all".to_string(),
+            depends_on: vec!["build server".to_string(), "build client".to_string()],
+            depends_order: DependsOrder::Sequence,
+            ..TaskTemplate::default()
+        };
+        assert!(compound_task.is_compound());
+        let resolved_task = compound_task
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("compound tasks without a command should resolve");
+        assert_eq!(resolved_task.original_task(), &compound_task);
+        assert_eq!(resolved_task.task_context(), &TaskContext::default());
+
+        let task_with_command_and_dependencies = TaskTemplate {
+            command: "test_command".to_string(),
+            ..compound_task.clone()
+        };
+        assert!(!task_with_command_and_dependencies.is_compound());
+        assert!(
+            task_with_command_and_dependencies
+                .resolve_task(TEST_ID_BASE, &TaskContext::default())
+                .is_some()
+        );
+
+        let compound_task_without_label = TaskTemplate {
+            label: "".to_string(),
+            ..compound_task
+        };
+        assert_eq!(
+            compound_task_without_label.resolve_task(TEST_ID_BASE, &TaskContext::default()),
+            None,
+        );
+    }
+
+    #[test]
+    fn test_resolving_background_templates() {
+        let background_patterns = BackgroundPatterns {
+            begins: Some("^Rebuilding".to_string()),
+            ends: Some("^Watching for changes".to_string()),
+        };
+        let watcher = TaskTemplate {
+            label: "watch".to_string(),
+            command: "cargo watch".to_string(),
+            is_background: true,
+            background_patterns: background_patterns.clone(),
+            ..TaskTemplate::default()
+        };
+        let resolved = watcher
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .unwrap();
+        assert_eq!(resolved.resolved.background, Some(background_patterns));
+
+        let regular_task = TaskTemplate {
+            is_background: false,
+            ..watcher
+        };
+        let resolved = regular_task
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .unwrap();
+        assert_eq!(
+            resolved.resolved.background, None,
+            "Background patterns should be ignored for regular tasks"
+        );
+    }
+
+    #[test]
+    fn test_resolving_task_inputs() {
+        let configuration = TaskInput::PickString {
+            id: "configuration".to_string(),
+            description: Some("Build configuration".to_string()),
+            options: vec![
+                PickStringOption::Value("debug".to_string()),
+                PickStringOption::Value("release".to_string()),
+            ],
+            default: None,
+        };
+        let unused = TaskInput::PromptString {
+            id: "unused".to_string(),
+            description: None,
+            default: None,
+        };
+        let template = TaskTemplate {
+            label: "build ${input:configuration}".to_string(),
+            command: "make".to_string(),
+            args: vec!["CONFIG=${input:configuration}".to_string()],
+            inputs: vec![configuration.clone(), unused],
+            ..TaskTemplate::default()
+        };
+        assert_eq!(template.referenced_inputs(), vec![&configuration]);
+
+        let resolved = template
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .unwrap();
+        assert_eq!(resolved.resolved.label, "build ${input:configuration}");
+        assert_eq!(
+            resolved.resolved.args,
+            vec!["CONFIG=${input:configuration}"]
+        );
+
+        let values = HashMap::from_iter([("configuration".to_string(), "release".to_string())]);
+        let with_inputs = resolved.with_input_values(&values);
+        assert_eq!(with_inputs.resolved.label, "build release");
+        assert_eq!(with_inputs.resolved.command_label, "make CONFIG=release");
+        assert_eq!(with_inputs.resolved.args, vec!["CONFIG=release"]);
+        assert_ne!(with_inputs.id, resolved.id);
+        assert!(
+            with_inputs.original_task().referenced_inputs().is_empty(),
+            "Input values should be kept for the reruns"
+        );
+    }
+
     #[test]
     fn test_template_cwd_resolution() {
         let task_without_cwd = TaskTemplate {
@@ -888,4 +1111,26 @@ mod tests {
             "overwritten"
         );
     }
+
+    #[test]
+    fn test_problem_matcher_accepts_single_value() {
+        let template: TaskTemplate = serde_json::from_value(serde_json::json!({
+            "label": "build",
+            "command": "cargo",
+            "problem_matcher": "$rustc",
+        }))
+        .unwrap();
+        assert_eq!(
+            template.problem_matcher,
+            vec![ProblemMatcherTemplate::Preset("$rustc".to_string())]
+        );
+
+        let template: TaskTemplate = serde_json::from_value(serde_json::json!({
+            "label": "build",
+            "command": "cargo",
+            "problem_matcher": ["$rustc", "$gcc"],
+        }))
+        .unwrap();
+        assert_eq!(template.problem_matcher.len(), 2);
+    }
 }
@@ -4,6 +4,7 @@ use util::ResultExt as _;
 
 use crate::{
     DebugScenario, DebugTaskFile, EnvVariableReplacer, TcpArgumentsTemplate, VariableName,
+    task_input::unknown_input_references, vscode_format::VsCodeInput,
 };
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
@@ -48,6 +49,7 @@ impl VsCodeDebugTaskDefinition {
                 timeout: None,
             }),
             config,
+            inputs: Vec::new(),
         };
         Ok(definition)
     }
@@ -59,6 +61,8 @@ pub struct VsCodeDebugTaskFile {
     #[serde(default)]
     version: Option<String>,
     configurations: Vec<VsCodeDebugTaskDefinition>,
+    #[serde(default)]
+    inputs: Vec<VsCodeInput>,
 }
 
 impl TryFrom<VsCodeDebugTaskFile> for DebugTaskFile {
@@ -76,10 +80,33 @@ impl TryFrom<VsCodeDebugTaskFile> for DebugTaskFile {
             ),
             ("file".to_owned(), VariableName::File.to_string()),
         ]));
+        let inputs = file
+            .inputs
+            .into_iter()
+            .filter_map(|input| input.into_zed_format(&replacer))
+            .collect::<Vec<_>>();
         let templates = file
             .configurations
             .into_iter()
             .filter_map(|config| config.try_to_zed(&replacer).log_err())
+            .map(|mut scenario| {
+                scenario.inputs = inputs.clone();
+                scenario.inputs = scenario.referenced_inputs().into_iter().cloned().collect();
+                scenario
+            })
+            .filter(|scenario| {
+                let unknown_inputs = serde_json::to_value(scenario)
+                    .map(|value| unknown_input_references(&value, &scenario.inputs))
+                    .unwrap_or_default();
+                if let Some(input) = unknown_inputs.first() {
+                    log::warn!(
+                        "Skipping debug configuration `{}` that references the unsupported input `{input}`",
+                        scenario.label
+                    );
+                    return false;
+                }
+                true
+            })
             .collect::<Vec<_>>();
         Ok(DebugTaskFile(templates))
     }
@@ -103,7 +130,9 @@ fn task_type_to_adapter_name(task_type: &str) -> String {
 mod tests {
     use serde_json::json;
 
-    use crate::{DebugScenario, DebugTaskFile};
+    use collections::HashMap;
+
+    use crate::{DebugScenario, DebugTaskFile, PickStringOption, TaskInput};
 
     use super::VsCodeDebugTaskFile;
 
@@ -155,8 +184,94 @@ mod tests {
                     "port": 17,
                 }),
                 tcp_connection: None,
-                build: None
+                build: None,
+                inputs: Vec::new(),
             }])
         );
     }
+
+    #[test]
+    fn test_launch_json_inputs_are_prompted_for() {
+        let raw = r#"
+            {
+                "version": "0.2.0",
+                "configurations": [
+                    {
+                        "name": "Debug ${input:binary}",
+                        "request": "launch",
+                        "type": "lldb",
+                        "program": "${workspaceFolder}/target/${input:binary}",
+                        "args": ["${input:arguments}"]
+                    },
+                    {
+                        "name": "Attach",
+                        "request": "attach",
+                        "type": "lldb",
+                        "pid": "${input:process}"
+                    }
+                ],
+                "inputs": [
+                    {
+                        "id": "binary",
+                        "type": "pickString",
+                        "options": ["server", "client"]
+                    },
+                    {
+                        "id": "arguments",
+                        "type": "promptString"
+                    },
+                    {
+                        "id": "process",
+                        "type": "command",
+                        "command": "extension.pickProcess"
+                    }
+                ]
+            }
+        "#;
+        let parsed: VsCodeDebugTaskFile =
+            serde_json_lenient::from_str(&raw).expect("deserializing launch.json");
+        let zed = DebugTaskFile::try_from(parsed).expect("converting to Zed debug templates");
+        pretty_assertions::assert_eq!(
+            zed,
+            DebugTaskFile(vec![DebugScenario {
+                label: "Debug ${input:binary}".into(),
+                adapter: "CodeLLDB".into(),
+                config: json!({
+                    "request": "launch",
+                    "program": "${ZED_WORKTREE_ROOT}/target/${input:binary}",
+                    "args": ["${input:arguments}"],
+                }),
+                tcp_connection: None,
+                build: None,
+                inputs: vec![
+                    TaskInput::PickString {
+                        id: "binary".to_string(),
+                        description: None,
+                        options: vec![
+                            PickStringOption::Value("server".to_string()),
+                            PickStringOption::Value("client".to_string()),
+                        ],
+                        default: None,
+                    },
+                    TaskInput::PromptString {
+                        id: "arguments".to_string(),
+                        description: None,
+                        default: None,
+                    },
+                ],
+            }]),
+            "Inputs are asked for when the scenario starts, and configurations with unsupported inputs are skipped"
+        );
+
+        let scenario = zed.0[0].with_input_values(&HashMap::from_iter([
+            ("binary".to_string(), "server".to_string()),
+            ("arguments".to_string(), "--verbose".to_string()),
+        ]));
+        assert_eq!(scenario.label, "Debug server");
+        assert_eq!(
+            scenario.config["program"],
+            "${ZED_WORKTREE_ROOT}/target/server"
+        );
+        assert_eq!(scenario.config["args"], json!(["--verbose"]));
+    }
 }
@@ -3,7 +3,11 @@ use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    BackgroundPatterns, DependsOrder, EnvVariableReplacer, FileLocation, PickStringOption,
+    ProblemMatcher, ProblemMatcherTemplate, ProblemPattern, ProblemSeverity, TaskInput,
+    TaskTemplate, TaskTemplates, VariableName, task_input::unknown_input_references,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +26,236 @@ struct VsCodeTaskDefinition {
     #[serde(flatten)]
     other_attributes: HashMap<String, serde_json_lenient::Value>,
     options: Option<TaskOptions>,
+    depends_on: Option<DependsOn>,
+    depends_order: Option<VsCodeDependsOrder>,
+    problem_matcher: Option<VsCodeProblemMatchers>,
+    #[serde(default)]
+    is_background: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum DependsOn {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl DependsOn {
+    fn into_labels(self) -> Vec<String> {
+        match self {
+            Self::Single(label) => vec![label],
+            Self::Multiple(labels) => labels,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+enum VsCodeDependsOrder {
+    Parallel,
+    Sequence,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatchers {
+    Single(VsCodeProblemMatcher),
+    Multiple(Vec<VsCodeProblemMatcher>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    Named(String),
+    Custom(VsCodeCustomProblemMatcher),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeCustomProblemMatcher {
+    base: Option<String>,
+    owner: Option<String>,
+    source: Option<String>,
+    severity: Option<ProblemSeverity>,
+    file_location: Option<VsCodeFileLocation>,
+    pattern: Option<VsCodeProblemPatterns>,
+    background: Option<VsCodeBackgroundMatcher>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeBackgroundMatcher {
+    begins_pattern: Option<VsCodeBackgroundPattern>,
+    ends_pattern: Option<VsCodeBackgroundPattern>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeBackgroundPattern {
+    Regexp(String),
+    Object { regexp: String },
+}
+
+impl VsCodeBackgroundPattern {
+    fn into_regexp(self) -> String {
+        match self {
+            Self::Regexp(regexp) | Self::Object { regexp } => regexp,
+        }
+    }
+}
+
+/// Background patterns of the `$tsc-watch` problem matcher, as defined by the VS Code TypeScript extension.
+const TSC_WATCH_BEGINS_PATTERN: &str = r"^\s*(?:message TS6032:|\[?\D*.{1,2}[:.].{1,2}[:.].{1,2}\D*(├\D*\d{1,2}\D+┤)?(?:\]| -)) (Starting compilation in watch mode|File change detected\. Starting incremental compilation)\.\.\.";
+const TSC_WATCH_ENDS_PATTERN: &str = r"^\s*(?:message TS6042:|\[?\D*.{1,2}[:.].{1,2}[:.].{1,2}\D*(├\D*\d{1,2}\D+┤)?(?:\]| -)) (?:Compilation complete\.|Found \d+ errors?\.) Watching for file changes\.";
+
+/// Either a kind of the location (`absolute`, `relative`, `autoDetect`, `search`),
+/// or an array of the kind and the directory to resolve relative paths against.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeFileLocation {
+    Kind(String),
+    KindWithDirectory(Vec<String>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemPatterns {
+    Named(String),
+    Single(VsCodeProblemPattern),
+    Multiple(Vec<VsCodeProblemPattern>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default, rename = "loop")]
+    repeat: bool,
+}
+
+impl VsCodeProblemMatchers {
+    fn into_zed_format(self) -> Vec<ProblemMatcherTemplate> {
+        let matchers = match self {
+            Self::Single(matcher) => vec![matcher],
+            Self::Multiple(matchers) => matchers,
+        };
+        matchers
+            .into_iter()
+            .filter_map(|matcher| match matcher {
+                VsCodeProblemMatcher::Named(name) => Some(ProblemMatcherTemplate::Preset(name)),
+                VsCodeProblemMatcher::Custom(matcher) => matcher.into_zed_format().log_err(),
+            })
+            .collect()
+    }
+}
+
+impl VsCodeProblemMatchers {
+    /// Patterns to detect the readiness of a background task with, taken from the first matcher that defines them.
+    fn background_patterns(&self) -> BackgroundPatterns {
+        let matchers = match self {
+            Self::Single(matcher) => std::slice::from_ref(matcher),
+            Self::Multiple(matchers) => matchers.as_slice(),
+        };
+        matchers
+            .iter()
+            .find_map(|matcher| match matcher {
+                VsCodeProblemMatcher::Named(name) => tsc_watch_patterns(name),
+                VsCodeProblemMatcher::Custom(matcher) => match &matcher.background {
+                    Some(background) => Some(BackgroundPatterns {
+                        begins: background
+                            .begins_pattern
+                            .clone()
+                            .map(VsCodeBackgroundPattern::into_regexp),
+                        ends: background
+                            .ends_pattern
+                            .clone()
+                            .map(VsCodeBackgroundPattern::into_regexp),
+                    }),
+                    None => tsc_watch_patterns(matcher.base.as_deref()?),
+                },
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn tsc_watch_patterns(problem_matcher_name: &str) -> Option<BackgroundPatterns> {
+    (problem_matcher_name == "$tsc-watch").then(|| BackgroundPatterns {
+        begins: Some(TSC_WATCH_BEGINS_PATTERN.to_string()),
+        ends: Some(TSC_WATCH_ENDS_PATTERN.to_string()),
+    })
+}
+
+impl VsCodeCustomProblemMatcher {
+    fn into_zed_format(self) -> anyhow::Result<ProblemMatcherTemplate> {
+        let base = self.base.as_deref().and_then(ProblemMatcher::preset);
+        let pattern = match self.pattern {
+            Some(VsCodeProblemPatterns::Named(name)) => match ProblemMatcher::preset(&name) {
+                Some(preset) => preset.pattern,
+                None => bail!("Unknown problem pattern `{name}`"),
+            },
+            Some(VsCodeProblemPatterns::Single(pattern)) => vec![pattern.into()],
+            Some(VsCodeProblemPatterns::Multiple(patterns)) => {
+                patterns.into_iter().map(ProblemPattern::from).collect()
+            }
+            None => match (&base, self.base) {
+                (Some(base), _) => base.pattern.clone(),
+                // Leave the unknown matchers as is, the task resolution will report them.
+                (None, Some(name)) => return Ok(ProblemMatcherTemplate::Preset(name)),
+                (None, None) => bail!("Problem matcher has neither `base` nor `pattern`"),
+            },
+        };
+        let file_location = match self.file_location {
+            Some(VsCodeFileLocation::Kind(kind)) => Some(kind),
+            Some(VsCodeFileLocation::KindWithDirectory(kind)) => kind.into_iter().next(),
+            None => None,
+        }
+        .map(|kind| match kind.as_str() {
+            "absolute" => FileLocation::Absolute,
+            "relative" => FileLocation::Relative,
+            _ => FileLocation::AutoDetect,
+        });
+
+        Ok(ProblemMatcherTemplate::Custom(ProblemMatcher {
+            source: self
+                .source
+                .or(self.owner)
+                .or_else(|| Some(base.as_ref()?.source.clone()))
+                .unwrap_or_else(|| "task".to_string()),
+            severity: self
+                .severity
+                .or_else(|| Some(base.as_ref()?.severity))
+                .unwrap_or_default(),
+            file_location: file_location
+                .or_else(|| Some(base.as_ref()?.file_location))
+                .unwrap_or_default(),
+            pattern,
+        }))
+    }
+}
+
+impl From<VsCodeProblemPattern> for ProblemPattern {
+    fn from(pattern: VsCodeProblemPattern) -> Self {
+        Self {
+            regexp: pattern.regexp,
+            file: pattern.file,
+            line: pattern.line,
+            column: pattern.column,
+            end_line: pattern.end_line,
+            end_column: pattern.end_column,
+            severity: pattern.severity,
+            code: pattern.code,
+            message: pattern.message,
+            repeat: pattern.repeat,
+        }
+    }
 }
 
 #[derive(Clone, Deserialize, PartialEq, Debug)]
@@ -42,29 +276,32 @@ enum Command {
 }
 
 impl VsCodeTaskDefinition {
-    fn into_zed_format(
-        self,
-        replacer: &EnvVariableReplacer,
-    ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
-                self.label
-            );
-            return Ok(None);
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
+        let depends_on = self
+            .depends_on
+            .map(DependsOn::into_labels)
+            .unwrap_or_default();
+        let background_patterns = match &self.problem_matcher {
+            Some(problem_matcher) if self.is_background => problem_matcher.background_patterns(),
+            _ => BackgroundPatterns::default(),
+        };
+        let problem_matcher = self
+            .problem_matcher
+            .map(VsCodeProblemMatchers::into_zed_format)
+            .unwrap_or_default();
+        let depends_order = match self.depends_order {
+            Some(VsCodeDependsOrder::Sequence) => DependsOrder::Sequence,
+            Some(VsCodeDependsOrder::Parallel) | None => DependsOrder::Parallel,
         };
 
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        // `type` is not set in compound tasks, that only run their `dependsOn` tasks;
+        // we still want to deserialize the whole object though (hence command is an Option).
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
@@ -73,13 +310,90 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
+            problem_matcher,
+            is_background: self.is_background,
+            background_patterns,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
             template.cwd = options.cwd.map(|cwd| replacer.replace(&cwd));
             template.env = options.env;
         }
-        Ok(Some(template))
+        Ok(template)
+    }
+}
+
+/// An input of Code's tasks and launch configurations, referenced as `${input:id}`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum VsCodeInput {
+    PromptString {
+        id: String,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        default: Option<String>,
+    },
+    PickString {
+        id: String,
+        #[serde(default)]
+        description: Option<String>,
+        options: Vec<PickStringOption>,
+        #[serde(default)]
+        default: Option<String>,
+    },
+    Command {
+        id: String,
+        command: String,
+        #[serde(default)]
+        args: Option<serde_json_lenient::Value>,
+    },
+}
+
+impl VsCodeInput {
+    /// Code's `command` inputs run commands of extensions, of which only the shell command one has a Zed counterpart.
+    pub(crate) fn into_zed_format(self, replacer: &EnvVariableReplacer) -> Option<TaskInput> {
+        match self {
+            Self::PromptString {
+                id,
+                description,
+                default,
+            } => Some(TaskInput::PromptString {
+                id,
+                description,
+                default,
+            }),
+            Self::PickString {
+                id,
+                description,
+                options,
+                default,
+            } => Some(TaskInput::PickString {
+                id,
+                description,
+                options,
+                default,
+            }),
+            Self::Command { id, command, args } => {
+                let shell_command = args
+                    .as_ref()
+                    .and_then(|args| args.get("command")?.as_str())
+                    .filter(|_| command == "shellCommand.execute");
+                match shell_command {
+                    Some(shell_command) => Some(TaskInput::Command {
+                        id,
+                        command: replacer.replace(shell_command),
+                        args: Vec::new(),
+                    }),
+                    None => {
+                        log::warn!("Skipping input `{id}` with unsupported command `{command}`");
+                        None
+                    }
+                }
+            }
+        }
     }
 }
 
@@ -87,6 +401,8 @@ impl VsCodeTaskDefinition {
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct VsCodeTaskFile {
     tasks: Vec<VsCodeTaskDefinition>,
+    #[serde(default)]
+    inputs: Vec<VsCodeInput>,
 }
 
 impl TryFrom<VsCodeTaskFile> for TaskTemplates {
@@ -105,14 +421,32 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                 VariableName::SelectedText.to_string(),
             ),
         ]));
+        let inputs = value
+            .inputs
+            .into_iter()
+            .filter_map(|input| input.into_zed_format(&replacer))
+            .collect::<Vec<_>>();
         let templates = value
             .tasks
             .into_iter()
-            .filter_map(|vscode_definition| {
-                vscode_definition
-                    .into_zed_format(&replacer)
-                    .log_err()
-                    .flatten()
+            .filter_map(|vscode_definition| vscode_definition.into_zed_format(&replacer).log_err())
+            .map(|mut template| {
+                template.inputs = inputs.clone();
+                template.inputs = template.referenced_inputs().into_iter().cloned().collect();
+                template
+            })
+            .filter(|template| {
+                let unknown_inputs = serde_json::to_value(template)
+                    .map(|value| unknown_input_references(&value, &template.inputs))
+                    .unwrap_or_default();
+                if let Some(input) = unknown_inputs.first() {
+                    log::warn!(
+                        "Skipping task `{}` that references the unsupported input `{input}`",
+                        template.label
+                    );
+                    return false;
+                }
+                true
             })
             .collect();
         Ok(Self(templates))
@@ -124,8 +458,9 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
-        vscode_format::{Command, VsCodeTaskDefinition},
+        DependsOrder, FileLocation, PickStringOption, ProblemMatcher, ProblemMatcherTemplate,
+        ProblemPattern, ProblemSeverity, TaskInput, TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        vscode_format::{Command, DependsOn, VsCodeTaskDefinition},
     };
 
     use super::EnvVariableReplacer;
@@ -134,10 +469,12 @@ mod tests {
         assert_eq!(
             VsCodeTaskDefinition {
                 other_attributes: Default::default(),
+                problem_matcher: None,
                 ..lhs
             },
             VsCodeTaskDefinition {
                 other_attributes: Default::default(),
+                problem_matcher: None,
                 ..rhs
             },
         );
@@ -182,6 +519,10 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: None,
+                is_background: false,
             },
             VsCodeTaskDefinition {
                 label: "tsc: watch ./src".to_string(),
@@ -196,6 +537,10 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: None,
+                is_background: true,
             },
             VsCodeTaskDefinition {
                 label: "npm: build:compiler".to_string(),
@@ -204,6 +549,10 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: None,
+                is_background: false,
             },
             VsCodeTaskDefinition {
                 label: "npm: build:tests".to_string(),
@@ -212,6 +561,10 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: None,
+                is_background: false,
             },
         ];
 
@@ -227,6 +580,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: vec![ProblemMatcherTemplate::Preset("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -238,18 +592,23 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matcher: vec![ProblemMatcherTemplate::Preset("$tsc-watch".to_string())],
+                is_background: true,
+                background_patterns: super::tsc_watch_patterns("$tsc-watch").unwrap(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matcher: vec![ProblemMatcherTemplate::Preset("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: vec![ProblemMatcherTemplate::Preset("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -271,6 +630,10 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: None,
+                is_background: true,
             },
             VsCodeTaskDefinition {
                 label: "Build Extension".to_string(),
@@ -279,6 +642,10 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: None,
+                is_background: false,
             },
             VsCodeTaskDefinition {
                 label: "Build Server".to_string(),
@@ -288,6 +655,10 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: None,
+                is_background: false,
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release)".to_string(),
@@ -297,6 +668,10 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: None,
+                is_background: false,
             },
             VsCodeTaskDefinition {
                 label: "Pretest".to_string(),
@@ -305,18 +680,36 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: None,
+                is_background: false,
             },
             VsCodeTaskDefinition {
                 label: "Build Server and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Some(DependsOn::Multiple(vec![
+                    "Build Server".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: None,
+                problem_matcher: None,
+                is_background: false,
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release) and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Some(DependsOn::Multiple(vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: None,
+                problem_matcher: None,
+                is_background: false,
             },
         ];
         assert_eq!(vscode_definitions.tasks.len(), expected.len());
@@ -330,32 +723,268 @@ mod tests {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matcher: vec![ProblemMatcherTemplate::Preset("$tsc-watch".to_string())],
+                is_background: true,
+                background_patterns: super::tsc_watch_patterns("$tsc-watch").unwrap(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matcher: vec![ProblemMatcherTemplate::Custom(
+                    ProblemMatcher::preset("$tsc").unwrap(),
+                )],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matcher: vec![ProblemMatcherTemplate::Preset("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matcher: vec![ProblemMatcherTemplate::Preset("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matcher: vec![ProblemMatcherTemplate::Custom(
+                    ProblemMatcher::preset("$tsc").unwrap(),
+                )],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                problem_matcher: vec![ProblemMatcherTemplate::Preset("$rustc".to_string())],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                problem_matcher: vec![ProblemMatcherTemplate::Preset("$rustc".to_string())],
                 ..Default::default()
             },
         ];
         let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
         assert_eq!(tasks.0, expected);
     }
+
+    #[test]
+    fn can_deserialize_depends_on() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "lint",
+                        "type": "shell",
+                        "command": "eslint",
+                        "dependsOn": "install"
+                    },
+                    {
+                        "label": "ci",
+                        "dependsOn": ["install", "lint", "test"],
+                        "dependsOrder": "sequence"
+                    },
+                    {
+                        "label": "broken"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "lint".to_string(),
+                    command: "eslint".to_string(),
+                    depends_on: vec!["install".to_string()],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "ci".to_string(),
+                    depends_on: vec![
+                        "install".to_string(),
+                        "lint".to_string(),
+                        "test".to_string()
+                    ],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_deserialize_problem_matchers() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "make",
+                        "type": "shell",
+                        "command": "make",
+                        "problemMatcher": {
+                            "owner": "cpp",
+                            "severity": "warning",
+                            "fileLocation": ["absolute"],
+                            "pattern": [
+                                { "regexp": "^In file (.*):$", "file": 1 },
+                                {
+                                    "regexp": "^(\\d+):(\\d+): (.*)$",
+                                    "line": 1,
+                                    "column": 2,
+                                    "message": 3,
+                                    "loop": true
+                                }
+                            ]
+                        }
+                    },
+                    {
+                        "label": "build",
+                        "type": "shell",
+                        "command": "cargo build",
+                        "problemMatcher": ["$rustc", { "base": "$gcc", "source": "cc" }]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0[0].problem_matcher,
+            vec![ProblemMatcherTemplate::Custom(ProblemMatcher {
+                source: "cpp".to_string(),
+                severity: ProblemSeverity::Warning,
+                file_location: FileLocation::Absolute,
+                pattern: vec![
+                    ProblemPattern {
+                        regexp: "^In file (.*):$".to_string(),
+                        file: Some(1),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: "^(\\d+):(\\d+): (.*)$".to_string(),
+                        line: Some(1),
+                        column: Some(2),
+                        message: Some(3),
+                        repeat: true,
+                        ..ProblemPattern::default()
+                    },
+                ],
+            })]
+        );
+        assert_eq!(
+            tasks.0[1].problem_matcher,
+            vec![
+                ProblemMatcherTemplate::Preset("$rustc".to_string()),
+                ProblemMatcherTemplate::Custom(ProblemMatcher {
+                    source: "cc".to_string(),
+                    ..ProblemMatcher::preset("$gcc").unwrap()
+                }),
+            ]
+        );
+    }
+    #[test]
+    fn can_deserialize_task_inputs() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "deploy",
+                        "type": "shell",
+                        "command": "./deploy.sh",
+                        "args": ["${input:environment}", "${input:revision}"]
+                    },
+                    {
+                        "label": "greet",
+                        "type": "shell",
+                        "command": "echo ${input:name}"
+                    },
+                    {
+                        "label": "kill",
+                        "type": "shell",
+                        "command": "kill ${input:process}"
+                    }
+                ],
+                "inputs": [
+                    {
+                        "id": "environment",
+                        "type": "pickString",
+                        "description": "Where to deploy",
+                        "options": ["staging", { "label": "Production", "value": "prod" }],
+                        "default": "staging"
+                    },
+                    {
+                        "id": "revision",
+                        "type": "command",
+                        "command": "shellCommand.execute",
+                        "args": { "command": "git rev-parse HEAD" }
+                    },
+                    {
+                        "id": "name",
+                        "type": "promptString",
+                        "description": "Your name",
+                        "password": false
+                    },
+                    {
+                        "id": "process",
+                        "type": "command",
+                        "command": "extension.pickProcess"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0.len(),
+            2,
+            "The task referencing the unsupported command input should be skipped"
+        );
+        assert_eq!(
+            tasks.0[0].inputs,
+            vec![
+                TaskInput::PickString {
+                    id: "environment".to_string(),
+                    description: Some("Where to deploy".to_string()),
+                    options: vec![
+                        PickStringOption::Value("staging".to_string()),
+                        PickStringOption::Labeled {
+                            label: "Production".to_string(),
+                            value: "prod".to_string(),
+                        },
+                    ],
+                    default: Some("staging".to_string()),
+                },
+                TaskInput::Command {
+                    id: "revision".to_string(),
+                    command: "git rev-parse HEAD".to_string(),
+                    args: Vec::new(),
+                },
+            ]
+        );
+        assert_eq!(
+            tasks.0[1].inputs,
+            vec![TaskInput::PromptString {
+                id: "name".to_string(),
+                description: Some("Your name".to_string()),
+                default: None,
+            }],
+            "Only the inputs referenced by the task should be imported"
+        );
+    }
 }
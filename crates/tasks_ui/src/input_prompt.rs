@@ -0,0 +1,327 @@
+use std::{cmp, path::PathBuf, sync::Arc};
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use futures::channel::oneshot;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    App, AppContext as _, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, ParentElement, Render, SharedString, Styled, Subscription, Task, Window,
+    rems,
+};
+use picker::{Picker, PickerDelegate};
+use task::{PickStringOption, Shell, ShellBuilder, TaskInput};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, TaskInputContext, TaskInputProvider, Workspace};
+
+/// Asks for the values of the task inputs one by one, with a [`TaskInputPrompt`] modal per input,
+/// and runs the command inputs.
+pub(crate) struct TaskInputPrompter;
+
+impl TaskInputProvider for TaskInputPrompter {
+    fn prompt(
+        &self,
+        context: TaskInputContext,
+        inputs: Vec<TaskInput>,
+        last_values: HashMap<String, String>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<Option<HashMap<String, String>>>> {
+        let task_label: SharedString = context.label.into();
+        let is_local = workspace.project().read(cx).is_local();
+        let TaskInputContext {
+            cwd, env, shell, ..
+        } = context;
+        cx.spawn_in(window, async move |workspace, cx| {
+            let mut values = HashMap::default();
+            for input in inputs {
+                let value = match &input {
+                    TaskInput::Command { command, args, .. } => {
+                        anyhow::ensure!(
+                            is_local,
+                            "Command input `{}` can only be run in local projects",
+                            input.id()
+                        );
+                        run_input_command(command, args, &shell, cwd.clone(), env.clone())
+                            .await
+                            .with_context(|| format!("running command input `{}`", input.id()))?
+                    }
+                    TaskInput::PromptString { .. } | TaskInput::PickString { .. } => {
+                        let initial_value = last_values
+                            .get(input.id())
+                            .map(String::as_str)
+                            .or(input.default_value())
+                            .map(ToOwned::to_owned);
+                        let (tx, rx) = oneshot::channel();
+                        workspace.update_in(cx, |workspace, window, cx| {
+                            let task_label = task_label.clone();
+                            workspace.toggle_modal(window, cx, |window, cx| {
+                                TaskInputPrompt::new(
+                                    task_label,
+                                    &input,
+                                    initial_value,
+                                    tx,
+                                    window,
+                                    cx,
+                                )
+                            })
+                        })?;
+                        match rx.await {
+                            Ok(value) => value,
+                            Err(_) => return Ok(None),
+                        }
+                    }
+                };
+                values.insert(input.id().to_owned(), value);
+            }
+            Ok(Some(values))
+        })
+    }
+}
+
+async fn run_input_command(
+    command: &str,
+    args: &Vec<String>,
+    shell: &Shell,
+    cwd: Option<PathBuf>,
+    env: HashMap<String, String>,
+) -> Result<String> {
+    let (program, args) = ShellBuilder::new(true, shell)
+        .non_interactive()
+        .build(Some(command.to_owned()), args);
+    let mut command = util::command::new_smol_command(program);
+    command.args(args).envs(env);
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    let output = command.output().await?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "command failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// A modal to type in or pick the value of a single task input.
+pub struct TaskInputPrompt {
+    picker: Entity<Picker<TaskInputPromptDelegate>>,
+    _subscription: Subscription,
+}
+
+impl TaskInputPrompt {
+    fn new(
+        task_label: SharedString,
+        input: &TaskInput,
+        initial_value: Option<String>,
+        tx: oneshot::Sender<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let prompt = match input.description() {
+            Some(description) => format!("{task_label}: {description}"),
+            None => format!("{task_label}: {}", input.id()),
+        };
+        let options = match input {
+            TaskInput::PickString { options, .. } => options.clone(),
+            TaskInput::PromptString { .. } | TaskInput::Command { .. } => Vec::new(),
+        };
+        let is_pick = !options.is_empty();
+        let delegate = TaskInputPromptDelegate {
+            prompt: prompt.into(),
+            options,
+            initial_value: initial_value.clone(),
+            matches: Vec::new(),
+            selected_index: 0,
+            query: String::new(),
+            tx: Some(tx),
+        };
+        let picker = cx.new(|cx| {
+            let picker = Picker::uniform_list(delegate, window, cx);
+            if !is_pick && let Some(initial_value) = initial_value {
+                picker.set_query(initial_value, window, cx);
+            }
+            picker
+        });
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for TaskInputPrompt {}
+impl EventEmitter<DismissEvent> for TaskInputPrompt {}
+
+impl Focusable for TaskInputPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for TaskInputPrompt {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TaskInputPrompt")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+pub struct TaskInputPromptDelegate {
+    prompt: Arc<str>,
+    /// Options of the pick string inputs, empty for the prompt string ones.
+    options: Vec<PickStringOption>,
+    initial_value: Option<String>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    query: String,
+    tx: Option<oneshot::Sender<String>>,
+}
+
+impl PickerDelegate for TaskInputPromptDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        self.prompt.clone()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        if self.options.is_empty() {
+            Some("Type the value and press enter to confirm".into())
+        } else {
+            None
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        self.query = query.clone();
+        if self.options.is_empty() {
+            return Task::ready(());
+        }
+
+        let candidates = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(ix, option)| StringMatchCandidate::new(ix, option.label()))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    1000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    let initial_index = query
+                        .is_empty()
+                        .then(|| {
+                            let initial_value = delegate.initial_value.as_deref()?;
+                            delegate
+                                .options
+                                .iter()
+                                .position(|option| option.value() == initial_value)
+                        })
+                        .flatten();
+                    delegate.selected_index = match initial_index {
+                        Some(ix) => ix,
+                        None => cmp::min(
+                            delegate.selected_index,
+                            delegate.matches.len().saturating_sub(1),
+                        ),
+                    };
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let value = if self.options.is_empty() {
+            self.query.clone()
+        } else {
+            let Some(option) = self
+                .matches
+                .get(self.selected_index)
+                .and_then(|hit| self.options.get(hit.candidate_id))
+            else {
+                return;
+            };
+            option.value().to_owned()
+        };
+        if let Some(tx) = self.tx.take() {
+            tx.send(value).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        Some(
+            ListItem::new(SharedString::from(format!("task-input-option-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    hit.string.clone(),
+                    hit.positions.clone(),
+                )),
+        )
+    }
+}
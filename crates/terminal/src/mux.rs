@@ -0,0 +1,672 @@
+//! Persistent terminal sessions: a per-user daemon owns the shells and their ptys, so that they outlive
+//! Zed, and the terminals run a small client that attaches to a session by its id, bridging the terminal's
+//! own pty to the daemon. When the terminal is gone, the session stays around detached, keeping its output,
+//! and the next client attaching to it gets that output replayed.
+//!
+//! The client starts the daemon when there is none, and the daemon exits once its last session has ended;
+//! the daemons starting and exiting take a lock on a file next to the socket, so that they never replace
+//! each other's socket. When the client loses the daemon while the session might still be running, it exits
+//! with [`DETACHED_EXIT_CODE`], and the terminal attaches to the session again. On the wire, the client sends a JSON [`Request`] line, then both sides exchange frames of
+//! `[kind: u8][length: u32 BE][payload]`.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Read, Write},
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+        unix::{
+            net::{UnixListener, UnixStream},
+            process::{CommandExt, ExitStatusExt as _},
+        },
+    },
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use util::ResultExt as _;
+
+/// The environment variable the shell to run in a new session is passed through, as a JSON array of the
+/// program and its arguments. The user's shell is started as a login shell when it is not set.
+pub const SHELL_ENV_VAR: &str = "ZED_TERMINAL_MUX_SHELL";
+
+/// The exit code of the client when it got disconnected from the session without the session ending,
+/// `EX_TEMPFAIL` from `sysexits.h`: attaching to the session again might succeed.
+pub const DETACHED_EXIT_CODE: i32 = 75;
+
+/// How much of the output of every session is kept for replaying it to the next client.
+const MAX_SCROLLBACK_BYTES: usize = 4 * 1024 * 1024;
+/// The largest frame that is read, the scrollback replayed on attach being the largest one that is sent.
+const MAX_FRAME_BYTES: usize = MAX_SCROLLBACK_BYTES + 64 * 1024;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const RESIZE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const FRAME_INPUT: u8 = 0;
+const FRAME_RESIZE: u8 = 1;
+const FRAME_OUTPUT: u8 = 2;
+const FRAME_EXIT: u8 = 3;
+/// Another client has attached to the session.
+const FRAME_TAKEN_OVER: u8 = 4;
+
+/// The path of the socket the daemon listens on.
+pub fn socket_path() -> PathBuf {
+    paths::temp_dir().join("terminal-mux.sock")
+}
+
+/// Takes the lock that serializes the daemons starting and exiting, until the returned file is closed.
+fn lock_daemon_startup(socket_path: &Path) -> Result<File> {
+    if let Some(parent) = socket_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let lock_path = socket_path.with_extension("lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("opening the terminal mux lock file {lock_path:?}"))?;
+    loop {
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == 0 {
+            return Ok(file);
+        }
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error).context("locking the terminal mux lock file");
+        }
+    }
+}
+
+/// A new session id, unique among the sessions of the user.
+pub fn new_session_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("{:x}-{nanos:x}", std::process::id())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Request {
+    /// Attaches to the session, starting it first when it does not exist.
+    Attach(SessionRequest),
+    /// Ends the session, hanging up its shell.
+    End { session_id: String },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionRequest {
+    session_id: String,
+    shell: Option<(String, Vec<String>)>,
+    working_directory: Option<PathBuf>,
+    env: HashMap<String, String>,
+    columns: u16,
+    rows: u16,
+}
+
+fn write_frame(writer: &mut impl Write, kind: u8, payload: &[u8]) -> io::Result<()> {
+    let mut frame = Vec::with_capacity(5 + payload.len());
+    frame.push(kind);
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    writer.write_all(&frame)
+}
+
+/// Reads the next frame, or `None` when the other side has closed the connection.
+fn read_frame(reader: &mut impl Read) -> io::Result<Option<(u8, Vec<u8>)>> {
+    let mut header = [0; 5];
+    match reader.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+    let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if len > MAX_FRAME_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("terminal mux frame of {len} bytes is too large"),
+        ));
+    }
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload)?;
+    Ok(Some((header[0], payload)))
+}
+
+fn encode_size(columns: u16, rows: u16) -> [u8; 4] {
+    let [c0, c1] = columns.to_be_bytes();
+    let [r0, r1] = rows.to_be_bytes();
+    [c0, c1, r0, r1]
+}
+
+fn decode_size(payload: &[u8]) -> Option<(u16, u16)> {
+    let [c0, c1, r0, r1] = payload.try_into().ok()?;
+    Some((u16::from_be_bytes([c0, c1]), u16::from_be_bytes([r0, r1])))
+}
+
+fn window_size(fd: RawFd) -> Option<(u16, u16)> {
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    if unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) } < 0 || size.ws_col == 0 {
+        return None;
+    }
+    Some((size.ws_col, size.ws_row))
+}
+
+fn set_window_size(fd: RawFd, columns: u16, rows: u16) {
+    let size = libc::winsize {
+        ws_row: rows,
+        ws_col: columns,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    unsafe { libc::ioctl(fd, libc::TIOCSWINSZ, &size) };
+}
+
+/// Runs the daemon, until its last session has ended.
+pub fn run_daemon(socket_path: &Path) -> Result<()> {
+    let startup_lock = lock_daemon_startup(socket_path)?;
+    if UnixStream::connect(socket_path).is_ok() {
+        // Another daemon is already serving the sessions.
+        return Ok(());
+    }
+    // The socket is left behind by a daemon that has crashed.
+    std::fs::remove_file(socket_path).ok();
+    let listener = UnixListener::bind(socket_path)
+        .with_context(|| format!("binding the terminal mux socket {socket_path:?}"))?;
+    drop(startup_lock);
+    let daemon = Arc::new(Daemon {
+        socket_path: socket_path.to_path_buf(),
+        sessions: Mutex::new(HashMap::default()),
+        next_client_id: AtomicUsize::new(0),
+    });
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let daemon = daemon.clone();
+        thread::spawn(move || {
+            if let Err(error) = daemon.handle_connection(stream) {
+                log::error!("terminal mux connection failed: {error:#}");
+            }
+        });
+    }
+    Ok(())
+}
+
+struct Daemon {
+    socket_path: PathBuf,
+    sessions: Mutex<HashMap<String, Arc<Session>>>,
+    next_client_id: AtomicUsize,
+}
+
+struct Session {
+    master: File,
+    /// The process group of the shell, which is the leader of its own session.
+    shell_pid: libc::pid_t,
+    state: Mutex<SessionState>,
+}
+
+#[derive(Default)]
+struct SessionState {
+    scrollback: Vec<u8>,
+    /// The attached client, with its id.
+    client: Option<(usize, UnixStream)>,
+}
+
+impl SessionState {
+    fn push_output(&mut self, output: &[u8]) {
+        self.scrollback.extend_from_slice(output);
+        if self.scrollback.len() > MAX_SCROLLBACK_BYTES {
+            // Cut at a line boundary, to avoid replaying half of an escape sequence.
+            let excess = self.scrollback.len() - MAX_SCROLLBACK_BYTES;
+            let cut = self.scrollback[excess..]
+                .iter()
+                .position(|&byte| byte == b'\n')
+                .map_or(excess, |newline| excess + newline + 1);
+            self.scrollback.drain(..cut);
+        }
+        if let Some((_, client)) = &mut self.client
+            && write_frame(client, FRAME_OUTPUT, output).is_err()
+        {
+            self.client = None;
+        }
+    }
+}
+
+impl Daemon {
+    fn handle_connection(self: &Arc<Self>, stream: UnixStream) -> Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let request = serde_json::from_str(&line).context("parsing the terminal mux request")?;
+        let request = match request {
+            Request::Attach(request) => request,
+            Request::End { session_id } => {
+                if let Some(session) = self.sessions.lock().get(&session_id) {
+                    unsafe { libc::kill(-session.shell_pid, libc::SIGHUP) };
+                }
+                return Ok(());
+            }
+        };
+
+        let session = self.sessions.lock().get(&request.session_id).cloned();
+        let session = match session {
+            Some(session) => session,
+            None => self.start_session(&request)?,
+        };
+        let client_id = self.next_client_id.fetch_add(1, Ordering::Relaxed);
+        {
+            let mut state = session.state.lock();
+            if let Some((_, mut previous_client)) = state.client.take() {
+                write_frame(&mut previous_client, FRAME_TAKEN_OVER, &[]).ok();
+                previous_client.shutdown(std::net::Shutdown::Both).ok();
+            }
+            let mut client = stream.try_clone()?;
+            write_frame(&mut client, FRAME_OUTPUT, &state.scrollback)?;
+            state.client = Some((client_id, client));
+        }
+        session.resize(request.columns, request.rows);
+
+        let result = (|| -> io::Result<()> {
+            while let Some((kind, payload)) = read_frame(&mut reader)? {
+                match kind {
+                    FRAME_INPUT => (&session.master).write_all(&payload)?,
+                    FRAME_RESIZE => {
+                        if let Some((columns, rows)) = decode_size(&payload) {
+                            session.resize(columns, rows);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(())
+        })();
+        // Detach, unless another client has taken over the session already.
+        let mut state = session.state.lock();
+        if state
+            .client
+            .as_ref()
+            .is_some_and(|(id, _)| *id == client_id)
+        {
+            state.client = None;
+        }
+        Ok(result?)
+    }
+
+    fn start_session(self: &Arc<Self>, request: &SessionRequest) -> Result<Arc<Session>> {
+        let mut master = -1;
+        let mut slave = -1;
+        let size = libc::winsize {
+            ws_row: request.rows,
+            ws_col: request.columns,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        if unsafe {
+            libc::openpty(
+                &mut master,
+                &mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                &size,
+            )
+        } < 0
+        {
+            return Err(io::Error::last_os_error()).context("opening a pty");
+        }
+        let master = unsafe { File::from_raw_fd(master) };
+        let slave = unsafe { OwnedFd::from_raw_fd(slave) };
+
+        let (program, args) = request.shell.clone().unwrap_or_else(|| {
+            let shell = request
+                .env
+                .get("SHELL")
+                .cloned()
+                .unwrap_or_else(util::get_system_shell);
+            (shell, vec!["-l".to_string()])
+        });
+        let mut command = Command::new(&program);
+        command
+            .args(&args)
+            .env_clear()
+            .envs(&request.env)
+            .stdin(Stdio::from(slave.try_clone()?))
+            .stdout(Stdio::from(slave.try_clone()?))
+            .stderr(Stdio::from(slave));
+        if let Some(working_directory) = &request.working_directory {
+            command.current_dir(working_directory);
+        }
+        unsafe {
+            command.pre_exec(|| {
+                // Make the pty the controlling terminal of the shell.
+                if libc::setsid() < 0 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("spawning {program:?} for a terminal session"))?;
+
+        let session = Arc::new(Session {
+            master,
+            shell_pid: child.id() as libc::pid_t,
+            state: Mutex::new(SessionState::default()),
+        });
+        self.sessions
+            .lock()
+            .insert(request.session_id.clone(), session.clone());
+
+        let daemon = self.clone();
+        let session_id = request.session_id.clone();
+        let reader_session = session.clone();
+        thread::spawn(move || {
+            let mut buffer = vec![0; 64 * 1024];
+            loop {
+                match (&reader_session.master).read(&mut buffer) {
+                    Ok(0) => break,
+                    Ok(read) => reader_session.state.lock().push_output(&buffer[..read]),
+                    Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                    // Reading fails with EIO once the shell has exited.
+                    Err(_) => break,
+                }
+            }
+            let exit_code = child
+                .wait()
+                .map(|status| {
+                    status
+                        .code()
+                        .or_else(|| status.signal().map(|signal| 128 + signal))
+                        .unwrap_or(1)
+                })
+                .unwrap_or(1);
+            if let Some((_, mut client)) = reader_session.state.lock().client.take() {
+                write_frame(&mut client, FRAME_EXIT, &exit_code.to_be_bytes()).ok();
+            }
+            let is_last_session = {
+                let mut sessions = daemon.sessions.lock();
+                sessions.remove(&session_id);
+                sessions.is_empty()
+            };
+            if is_last_session {
+                // Keep a new daemon from starting until this one is gone, as it would find the socket in use.
+                let _startup_lock = lock_daemon_startup(&daemon.socket_path).log_err();
+                let sessions = daemon.sessions.lock();
+                if sessions.is_empty() {
+                    std::fs::remove_file(&daemon.socket_path).ok();
+                    std::process::exit(0);
+                }
+            }
+        });
+        Ok(session)
+    }
+}
+
+impl Session {
+    fn resize(&self, columns: u16, rows: u16) {
+        let fd = self.master.as_raw_fd();
+        set_window_size(fd, columns, rows);
+        // Make the programs redraw for the new client even when the size has not changed.
+        let foreground = unsafe { libc::tcgetpgrp(fd) };
+        if foreground > 0 {
+            unsafe { libc::kill(-foreground, libc::SIGWINCH) };
+        }
+    }
+}
+
+/// Connects to the daemon, starting it first when it is not running, with the current executable's
+/// `--terminal-mux <SOCKET>` mode.
+fn connect(socket_path: &Path) -> Result<UnixStream> {
+    if let Ok(stream) = UnixStream::connect(socket_path) {
+        return Ok(stream);
+    }
+    // A daemon might be starting or exiting, check again once it's done.
+    let startup_lock = lock_daemon_startup(socket_path)?;
+    if let Ok(stream) = UnixStream::connect(socket_path) {
+        return Ok(stream);
+    }
+    let mut daemon_command = Command::new(std::env::current_exe()?);
+    daemon_command
+        .arg("--terminal-mux")
+        .arg(socket_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    unsafe {
+        // Detach the daemon from the terminal, so that it is not hung up with it.
+        daemon_command.pre_exec(|| {
+            libc::setsid();
+            Ok(())
+        });
+    }
+    daemon_command
+        .spawn()
+        .context("starting the terminal mux daemon")?;
+    // The daemon takes the lock to bind the socket.
+    drop(startup_lock);
+    let started = std::time::Instant::now();
+    loop {
+        match UnixStream::connect(socket_path) {
+            Ok(stream) => return Ok(stream),
+            Err(error) if started.elapsed() > CONNECT_TIMEOUT => {
+                return Err(error).context("connecting to the terminal mux daemon");
+            }
+            Err(_) => thread::sleep(Duration::from_millis(20)),
+        }
+    }
+}
+
+fn send_request(stream: &mut UnixStream, request: &Request) -> Result<()> {
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Puts the terminal into raw mode for as long as it lives, so that the input is passed to the session as typed.
+struct RawMode {
+    fd: RawFd,
+    original: libc::termios,
+}
+
+impl RawMode {
+    fn enable(fd: RawFd) -> Option<Self> {
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(fd, &mut original) } < 0 {
+            return None;
+        }
+        let mut raw = original;
+        unsafe {
+            libc::cfmakeraw(&mut raw);
+            libc::tcsetattr(fd, libc::TCSANOW, &raw);
+        }
+        Some(Self { fd, original })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe { libc::tcsetattr(self.fd, libc::TCSANOW, &self.original) };
+    }
+}
+
+/// Attaches the current terminal to the session, starting the session and the daemon as needed.
+/// Returns the exit code of the session's shell once it exits, 0 when another client takes the session over,
+/// or [`DETACHED_EXIT_CODE`] when the daemon goes away.
+pub fn attach(session_id: &str) -> Result<i32> {
+    let stdin = io::stdin();
+    let stdin_fd = stdin.as_raw_fd();
+    let (columns, rows) = window_size(stdin_fd).unwrap_or((80, 24));
+    let shell = match std::env::var(SHELL_ENV_VAR) {
+        Ok(shell) => {
+            let mut shell: Vec<String> =
+                serde_json::from_str(&shell).with_context(|| format!("parsing {SHELL_ENV_VAR}"))?;
+            if shell.is_empty() {
+                None
+            } else {
+                let program = shell.remove(0);
+                Some((program, shell))
+            }
+        }
+        Err(_) => None,
+    };
+    let env = std::env::vars()
+        .filter(|(name, _)| name != SHELL_ENV_VAR)
+        .collect();
+
+    let mut stream = connect(&socket_path())?;
+    send_request(
+        &mut stream,
+        &Request::Attach(SessionRequest {
+            session_id: session_id.to_string(),
+            shell,
+            working_directory: std::env::current_dir().ok(),
+            env,
+            columns,
+            rows,
+        }),
+    )?;
+
+    let _raw_mode = RawMode::enable(stdin_fd);
+    let writer = Arc::new(Mutex::new(stream.try_clone()?));
+    thread::spawn({
+        let writer = writer.clone();
+        move || {
+            let mut stdin = stdin.lock();
+            let mut buffer = [0; 4096];
+            loop {
+                let read = match stdin.read(&mut buffer) {
+                    Ok(0) => break,
+                    Ok(read) => read,
+                    Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(_) => break,
+                };
+                if write_frame(&mut *writer.lock(), FRAME_INPUT, &buffer[..read]).is_err() {
+                    break;
+                }
+            }
+            // Losing the terminal detaches from the session.
+            writer.lock().shutdown(std::net::Shutdown::Both).ok();
+        }
+    });
+    thread::spawn(move || {
+        let mut size = (columns, rows);
+        loop {
+            thread::sleep(RESIZE_POLL_INTERVAL);
+            let Some(new_size) = window_size(stdin_fd) else {
+                continue;
+            };
+            if new_size != size {
+                size = new_size;
+                let payload = encode_size(size.0, size.1);
+                if write_frame(&mut *writer.lock(), FRAME_RESIZE, &payload).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    let mut stdout = io::stdout().lock();
+    while let Some((kind, payload)) = read_frame(&mut stream)? {
+        match kind {
+            FRAME_OUTPUT => {
+                stdout.write_all(&payload)?;
+                stdout.flush()?;
+            }
+            FRAME_EXIT => {
+                let code = payload
+                    .try_into()
+                    .map(i32::from_be_bytes)
+                    .map_err(|_| anyhow!("invalid exit frame"))?;
+                return Ok(code);
+            }
+            FRAME_TAKEN_OVER => return Ok(0),
+            _ => {}
+        }
+    }
+    Ok(DETACHED_EXIT_CODE)
+}
+
+/// Ends the session, if it is still running.
+pub fn end_session(session_id: &str) -> Result<()> {
+    let Ok(mut stream) = UnixStream::connect(socket_path()) else {
+        return Ok(());
+    };
+    send_request(
+        &mut stream,
+        &Request::End {
+            session_id: session_id.to_string(),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames() {
+        let mut wire = Vec::new();
+        write_frame(&mut wire, FRAME_OUTPUT, b"hello").unwrap();
+        write_frame(&mut wire, FRAME_RESIZE, &encode_size(120, 40)).unwrap();
+
+        let mut reader = wire.as_slice();
+        assert_eq!(
+            read_frame(&mut reader).unwrap(),
+            Some((FRAME_OUTPUT, b"hello".to_vec()))
+        );
+        let (kind, payload) = read_frame(&mut reader).unwrap().unwrap();
+        assert_eq!(kind, FRAME_RESIZE);
+        assert_eq!(decode_size(&payload), Some((120, 40)));
+        assert_eq!(read_frame(&mut reader).unwrap(), None);
+
+        let mut oversized = vec![FRAME_OUTPUT];
+        oversized.extend_from_slice(&(MAX_FRAME_BYTES as u32 + 1).to_be_bytes());
+        assert_eq!(
+            read_frame(&mut oversized.as_slice()).unwrap_err().kind(),
+            io::ErrorKind::InvalidData,
+            "A frame over the size limit should be rejected before allocating its payload"
+        );
+    }
+
+    #[test]
+    fn test_daemon_startup_lock_is_exclusive() {
+        let socket_path =
+            std::env::temp_dir().join(format!("terminal-mux-test-{}.sock", new_session_id()));
+        let lock = lock_daemon_startup(&socket_path).unwrap();
+
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let waiter = thread::spawn({
+            let socket_path = socket_path.clone();
+            move || {
+                let _lock = lock_daemon_startup(&socket_path).unwrap();
+                locked_tx.send(()).unwrap();
+            }
+        });
+        assert!(
+            locked_rx.recv_timeout(Duration::from_millis(100)).is_err(),
+            "The lock should be held until the file is closed"
+        );
+        drop(lock);
+        locked_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        waiter.join().unwrap();
+        std::fs::remove_file(socket_path.with_extension("lock")).ok();
+    }
+
+    #[test]
+    fn test_scrollback_is_cut_at_lines() {
+        let mut state = SessionState::default();
+        let line = format!("{}\n", "x".repeat(1023));
+        for _ in 0..MAX_SCROLLBACK_BYTES / line.len() + 10 {
+            state.push_output(line.as_bytes());
+        }
+        assert!(state.scrollback.len() <= MAX_SCROLLBACK_BYTES);
+        assert!(state.scrollback.starts_with(line.as_bytes()));
+    }
+}
@@ -83,12 +83,21 @@ pub struct ProcessInfo {
 pub struct PtyProcessInfo {
     system: System,
     refresh_kind: ProcessRefreshKind,
-    pid_getter: ProcessIdGetter,
+    /// None when the terminal mirrors a terminal shared by another collaborator, and has no process of its own.
+    pid_getter: Option<ProcessIdGetter>,
     pub current: Option<ProcessInfo>,
 }
 
 impl PtyProcessInfo {
     pub fn new(pty: &Pty) -> PtyProcessInfo {
+        Self::with_pid_getter(Some(ProcessIdGetter::new(pty)))
+    }
+
+    pub(crate) fn detached() -> PtyProcessInfo {
+        Self::with_pid_getter(None)
+    }
+
+    fn with_pid_getter(pid_getter: Option<ProcessIdGetter>) -> PtyProcessInfo {
         let process_refresh_kind = ProcessRefreshKind::new()
             .with_cmd(UpdateKind::Always)
             .with_cwd(UpdateKind::Always)
@@ -99,17 +108,17 @@ impl PtyProcessInfo {
         PtyProcessInfo {
             system,
             refresh_kind: process_refresh_kind,
-            pid_getter: ProcessIdGetter::new(pty),
+            pid_getter,
             current: None,
         }
     }
 
-    pub fn pid_getter(&self) -> &ProcessIdGetter {
-        &self.pid_getter
+    pub fn pid_getter(&self) -> Option<&ProcessIdGetter> {
+        self.pid_getter.as_ref()
     }
 
     fn refresh(&mut self) -> Option<&Process> {
-        let pid = self.pid_getter.pid()?;
+        let pid = self.pid()?;
         if self.system.refresh_processes_specifics(
             sysinfo::ProcessesToUpdate::Some(&[pid]),
             self.refresh_kind,
@@ -157,6 +166,6 @@ impl PtyProcessInfo {
     }
 
     pub fn pid(&self) -> Option<Pid> {
-        self.pid_getter.pid()
+        self.pid_getter.as_ref()?.pid()
     }
 }
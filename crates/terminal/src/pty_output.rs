@@ -0,0 +1,186 @@
+//! The processing of the pty output on its way to the terminal emulator, on the terminal's I/O thread:
+//! it is recorded, inline images are cut from it and the shell integration marks are parsed out of it.
+// The pty output is only filtered on Unix, where the pty's file can be read directly.
+#![cfg_attr(not(unix), allow(dead_code))]
+
+use std::{
+    io::{self, Read},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use alacritty_terminal::event::WindowSize;
+use futures::channel::mpsc::UnboundedSender;
+use parking_lot::Mutex;
+
+use crate::{
+    shell_integration::{ShellMarkEvent, ShellMarkParser},
+    terminal_images::ImageParser,
+    terminal_recording::TerminalRecorder,
+};
+
+pub(crate) struct PtyOutputReader<R> {
+    reader: R,
+    /// Absent when the inline images are disabled.
+    images: Option<ImageParser>,
+    shell_marks: ShellMarkParser,
+    line_feeds: Arc<AtomicUsize>,
+    marks_tx: UnboundedSender<ShellMarkEvent>,
+    /// Records the output as it was read, while the terminal session is being recorded.
+    recorder: Arc<Mutex<Option<TerminalRecorder>>>,
+    /// The filtered output that did not fit into the last read.
+    pending: Vec<u8>,
+    pending_offset: usize,
+}
+
+impl<R: Read> PtyOutputReader<R> {
+    pub fn new(
+        reader: R,
+        images: Option<ImageParser>,
+        line_feeds: Arc<AtomicUsize>,
+        marks_tx: UnboundedSender<ShellMarkEvent>,
+        recorder: Arc<Mutex<Option<TerminalRecorder>>>,
+    ) -> Self {
+        Self {
+            reader,
+            images,
+            shell_marks: ShellMarkParser::default(),
+            line_feeds,
+            marks_tx,
+            recorder,
+            pending: Vec::new(),
+            pending_offset: 0,
+        }
+    }
+
+    pub fn set_window_size(&mut self, window_size: WindowSize) {
+        if let Some(images) = &mut self.images {
+            images.set_window_size(window_size);
+        }
+    }
+
+    fn parse_shell_marks(&mut self, output: &[u8]) {
+        let marks_tx = &self.marks_tx;
+        self.shell_marks.advance(output, |mark| {
+            marks_tx.unbounded_send(mark).ok();
+        });
+        self.line_feeds
+            .store(self.shell_marks.line_feeds(), Ordering::Release);
+    }
+}
+
+impl<R: Read> Read for PtyOutputReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.pending_offset < self.pending.len() {
+                let pending = &self.pending[self.pending_offset..];
+                let len = pending.len().min(buf.len());
+                buf[..len].copy_from_slice(&pending[..len]);
+                self.pending_offset += len;
+                self.parse_shell_marks(&buf[..len]);
+                return Ok(len);
+            }
+
+            let read = self.reader.read(buf)?;
+            if let Some(recorder) = self.recorder.lock().as_mut() {
+                recorder.record_output(&buf[..read]);
+            }
+            let Some(images) = &mut self.images else {
+                self.parse_shell_marks(&buf[..read]);
+                return Ok(read);
+            };
+            if read == 0 {
+                return Ok(0);
+            }
+            // An image sequence can take many reads, so keep reading until there is output to pass on.
+            self.pending.clear();
+            self.pending_offset = 0;
+            images.advance(&buf[..read], &mut self.pending);
+        }
+    }
+}
+
+#[cfg(unix)]
+pub(crate) use unix::FilteredPty;
+
+#[cfg(unix)]
+mod unix {
+    use std::{fs::File, io, sync::Arc};
+
+    use alacritty_terminal::{
+        event::{OnResize, WindowSize},
+        tty::{ChildEvent, EventedPty, EventedReadWrite, Pty},
+    };
+    use polling::{Event, PollMode, Poller};
+
+    use super::PtyOutputReader;
+
+    /// A pty that passes its output through the [`PtyOutputReader`] on the way to the terminal emulator.
+    pub(crate) struct FilteredPty {
+        pty: Pty,
+        reader: PtyOutputReader<File>,
+    }
+
+    impl FilteredPty {
+        pub fn new(
+            pty: Pty,
+            make_reader: impl FnOnce(File) -> PtyOutputReader<File>,
+        ) -> io::Result<Self> {
+            let file = pty.file().try_clone()?;
+            Ok(Self {
+                pty,
+                reader: make_reader(file),
+            })
+        }
+    }
+
+    impl EventedReadWrite for FilteredPty {
+        type Reader = PtyOutputReader<File>;
+        type Writer = File;
+
+        unsafe fn register(
+            &mut self,
+            poll: &Arc<Poller>,
+            interest: Event,
+            poll_opts: PollMode,
+        ) -> io::Result<()> {
+            unsafe { self.pty.register(poll, interest, poll_opts) }
+        }
+
+        fn reregister(
+            &mut self,
+            poll: &Arc<Poller>,
+            interest: Event,
+            poll_opts: PollMode,
+        ) -> io::Result<()> {
+            self.pty.reregister(poll, interest, poll_opts)
+        }
+
+        fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+            self.pty.deregister(poll)
+        }
+
+        fn reader(&mut self) -> &mut Self::Reader {
+            &mut self.reader
+        }
+
+        fn writer(&mut self) -> &mut Self::Writer {
+            self.pty.writer()
+        }
+    }
+
+    impl EventedPty for FilteredPty {
+        fn next_child_event(&mut self) -> Option<ChildEvent> {
+            self.pty.next_child_event()
+        }
+    }
+
+    impl OnResize for FilteredPty {
+        fn on_resize(&mut self, window_size: WindowSize) {
+            self.reader.set_window_size(window_size);
+            self.pty.on_resize(window_size)
+        }
+    }
+}
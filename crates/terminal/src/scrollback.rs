@@ -0,0 +1,149 @@
+use alacritty_terminal::{
+    Term,
+    grid::{Dimensions, Row},
+    index::{Column, Line},
+    term::{TermMode, cell::Cell},
+};
+
+use crate::row_to_string;
+
+/// How many lines above the cursor are remembered to find them again after new output.
+const ANCHOR_LINES: usize = 3;
+
+/// Counts the lines dropped from the top of the scrollback, so that the lines of the terminal output
+/// can be indexed from the first line of the output, without the indices drifting once the scrollback is full.
+///
+/// Alacritty doesn't report how many lines it drops, so a few lines above the cursor are remembered
+/// on each update and looked up on the next one: new output only moves the existing lines up.
+#[derive(Default)]
+pub(crate) struct ScrollbackTracker {
+    dropped_lines: usize,
+    anchor: Option<Anchor>,
+}
+
+struct Anchor {
+    /// The index of the first remembered line, counting from the top of the scrollback.
+    line: usize,
+    text: Vec<String>,
+}
+
+impl ScrollbackTracker {
+    /// Accounts for the output that the terminal processed since the last update.
+    pub fn update<T>(&mut self, term: &Term<T>) {
+        // The alternate screen has no scrollback, and the main screen is left intact while it is shown.
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+        if let Some(anchor) = self.anchor.take() {
+            // If the remembered lines are gone, they were either rewritten, e.g. when the terminal was resized,
+            // or so much output was processed at once that they were dropped too. Either way the count can't be
+            // recovered, so it's kept as is.
+            let moved_by = (0..=anchor.line)
+                .find(|moved_by| anchor_matches(term, anchor.line - moved_by, &anchor.text));
+            self.dropped_lines += moved_by.unwrap_or(0);
+        }
+        self.anchor = new_anchor(term);
+    }
+
+    /// Accounts for the given number of lines being removed from the top of the scrollback,
+    /// e.g. when the terminal is cleared.
+    pub fn drop_lines<T>(&mut self, count: usize, term: &Term<T>) {
+        self.dropped_lines += count;
+        self.anchor = new_anchor(term);
+    }
+
+    /// The index of the grid line, counting from the first line of the terminal output.
+    pub fn output_line<T>(&self, term: &Term<T>, line: Line) -> usize {
+        (self.dropped_lines + term.history_size()).saturating_add_signed(line.0 as isize)
+    }
+
+    /// The grid line that shows the output line, above the top of the grid if the line was dropped.
+    pub fn grid_line<T>(&self, term: &Term<T>, output_line: usize) -> Line {
+        Line(output_line as i32 - (self.dropped_lines + term.history_size()) as i32)
+    }
+}
+
+fn new_anchor<T>(term: &Term<T>) -> Option<Anchor> {
+    let cursor_line = term.grid().cursor.point.line;
+    let first_line = (cursor_line - ANCHOR_LINES).max(term.topmost_line());
+    let text = (first_line.0..cursor_line.0)
+        .map(|line| row_to_string(&term.grid()[Line(line)]))
+        .collect::<Vec<_>>();
+    if text.iter().all(|line| line.trim().is_empty()) {
+        return None;
+    }
+    Some(Anchor {
+        line: (first_line.0 + term.history_size() as i32) as usize,
+        text,
+    })
+}
+
+fn anchor_matches<T>(term: &Term<T>, first_line: usize, text: &[String]) -> bool {
+    let history_size = term.history_size() as i32;
+    text.iter().enumerate().all(|(ix, text)| {
+        let line = Line((first_line + ix) as i32 - history_size);
+        line <= term.bottommost_line() && row_matches(&term.grid()[line], text)
+    })
+}
+
+fn row_matches(row: &Row<Cell>, text: &str) -> bool {
+    row[..Column(row.len())]
+        .iter()
+        .map(|cell| cell.c)
+        .eq(text.chars())
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{
+        Term,
+        event::VoidListener,
+        grid::Dimensions,
+        index::Line,
+        term::Config,
+        vte::ansi::{Processor, StdSyncHandler},
+    };
+    use gpui::{bounds, point, px, size};
+
+    use super::ScrollbackTracker;
+    use crate::{TerminalBounds, row_to_string};
+
+    #[test]
+    fn test_output_lines_stay_stable_when_scrollback_is_full() {
+        let terminal_bounds = TerminalBounds::new(
+            px(10.),
+            px(5.),
+            bounds(point(px(0.), px(0.)), size(px(50.), px(30.))),
+        );
+        let config = Config {
+            scrolling_history: 5,
+            ..Config::default()
+        };
+        let mut term = Term::new(config, &terminal_bounds, VoidListener);
+        let mut parser = Processor::<StdSyncHandler>::new();
+        let mut tracker = ScrollbackTracker::default();
+
+        let mut next_line = 0;
+        // Each batch moves the lines by less than the scrollback keeps, so that they can be found again.
+        for batch in [2, 4, 3, 1, 4, 2] {
+            for _ in 0..batch {
+                parser.advance(&mut term, format!("line {next_line}\r\n").as_bytes());
+                next_line += 1;
+            }
+            tracker.update(&term);
+
+            assert_eq!(
+                tracker.output_line(&term, term.grid().cursor.point.line),
+                next_line
+            );
+            let last_line = tracker.grid_line(&term, next_line - 1);
+            assert_eq!(
+                row_to_string(&term.grid()[last_line]).trim_end(),
+                format!("line {}", next_line - 1)
+            );
+        }
+        assert_eq!(term.history_size(), 5);
+        assert!(tracker.grid_line(&term, 0) < term.topmost_line());
+        assert_eq!(tracker.grid_line(&term, 0), Line(2 - next_line as i32));
+    }
+}
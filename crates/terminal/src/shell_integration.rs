@@ -0,0 +1,620 @@
+//! Shell integration: scripts that make bash, zsh and fish report their prompts and commands
+//! with the OSC 133 and OSC 633 escape sequences, and the parsing of these sequences from the pty output.
+// The pty output is only passed through the parser on Unix, where the scripts' shells are.
+#![cfg_attr(not(unix), allow(dead_code))]
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+
+const BASH_SCRIPT: &str = include_str!("./shell_integration/zed.bash");
+const ZSH_SCRIPT: &str = include_str!("./shell_integration/zed.zsh");
+const ZSHENV_SCRIPT: &str = include_str!("./shell_integration/zshenv.zsh");
+const FISH_SCRIPT: &str = include_str!("./shell_integration/zed.fish");
+
+/// How many bytes of a single command's output are kept, the rest is dropped.
+const MAX_COMMAND_OUTPUT_BYTES: usize = 1024 * 1024;
+/// How many of the latest command blocks are kept.
+const MAX_COMMAND_BLOCKS: usize = 1000;
+/// OSC sequences longer than this are not shell integration marks, and are not buffered.
+const MAX_OSC_LENGTH: usize = 4096;
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+/// A prompt and the command run from it, as reported by the shell integration marks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandBlock {
+    /// The line where the prompt starts, counting from the first line of the terminal output,
+    /// so that it stays the same when the lines above are dropped from the full scrollback.
+    pub prompt_line: usize,
+    /// The line where the command output starts, counting from the first line of the terminal output.
+    pub output_line: Option<usize>,
+    /// The command line, if the shell reported it.
+    pub command: Option<String>,
+    /// The command output, with the escape sequences stripped.
+    pub output: String,
+    /// Whether the output was too long and only its beginning is kept.
+    pub output_truncated: bool,
+    pub status: CommandStatus,
+}
+
+/// A state of the [`CommandBlock`]'s command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandStatus {
+    /// The prompt is shown, and the command is being typed in.
+    Prompt,
+    /// The command is running.
+    Running,
+    /// The command has finished, with the exit code if the shell reported it.
+    Finished { exit_code: Option<i32> },
+}
+
+impl CommandStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Finished { .. })
+    }
+}
+
+/// A [`CommandBlock`]'s prompt that is visible in the terminal viewport.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommandMark {
+    /// The line of the prompt, counting from the top of the viewport.
+    pub line: usize,
+    pub status: CommandStatus,
+}
+
+/// Updates the command blocks with the mark that the shell reported at the given line.
+pub(crate) fn apply_shell_mark(blocks: &mut Vec<CommandBlock>, mark: ShellMark, line: usize) {
+    let new_block = |status| CommandBlock {
+        prompt_line: line,
+        output_line: None,
+        command: None,
+        output: String::new(),
+        output_truncated: false,
+        status,
+    };
+    let last_status = blocks.last().map(|block| block.status);
+    match mark {
+        ShellMark::PromptStart => match blocks.last_mut() {
+            // Some shells report the prompt on their own, in addition to the scripts.
+            Some(block) if block.status == CommandStatus::Prompt => block.prompt_line = line,
+            _ => blocks.push(new_block(CommandStatus::Prompt)),
+        },
+        ShellMark::CommandLine(command) => {
+            if last_status.is_none_or(|status| status.is_finished()) {
+                blocks.push(new_block(CommandStatus::Prompt));
+            }
+            if let Some(block) = blocks.last_mut() {
+                block.command = Some(command);
+            }
+        }
+        ShellMark::CommandExecuted => match blocks.last_mut() {
+            Some(block) if block.status == CommandStatus::Prompt => {
+                block.status = CommandStatus::Running;
+                block.output_line = Some(line);
+            }
+            Some(block) if block.status == CommandStatus::Running => {}
+            // Commands that are run without a prompt, e.g. in tasks, start their blocks right away.
+            _ => {
+                let mut block = new_block(CommandStatus::Running);
+                block.output_line = Some(line);
+                blocks.push(block);
+            }
+        },
+        ShellMark::CommandFinished {
+            exit_code,
+            output,
+            output_truncated,
+        } => {
+            if let Some(block) = blocks.last_mut()
+                && block.status == CommandStatus::Running
+            {
+                block.status = CommandStatus::Finished { exit_code };
+                block.output = output;
+                block.output_truncated = output_truncated;
+            }
+        }
+    }
+    if blocks.len() > MAX_COMMAND_BLOCKS {
+        blocks.drain(..blocks.len() - MAX_COMMAND_BLOCKS);
+    }
+}
+
+/// A mark reported by the shell, along with the number of line feeds in the pty output before it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ShellMarkEvent {
+    pub mark: ShellMark,
+    pub line_feeds: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ShellMark {
+    /// `OSC 133 ; A`: the prompt starts.
+    PromptStart,
+    /// `OSC 633 ; E ; <command line>`: the command line that is about to run.
+    CommandLine(String),
+    /// `OSC 133 ; C`: the command starts running, its output follows.
+    CommandExecuted,
+    /// `OSC 133 ; D [; <exit code>]`: the command has finished.
+    CommandFinished {
+        exit_code: Option<i32>,
+        output: String,
+        output_truncated: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ParserState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+}
+
+/// Finds the shell integration marks in the pty output, and captures the output of the running commands.
+/// Everything else in the output is left for the terminal emulator to handle.
+#[derive(Default)]
+pub(crate) struct ShellMarkParser {
+    state: ParserState,
+    osc: Vec<u8>,
+    osc_overflow: bool,
+    line_feeds: usize,
+    /// The raw output of the running command, `None` when no command runs.
+    output: Option<Vec<u8>>,
+    output_truncated: bool,
+    /// Where the escape sequence that is being parsed starts in the captured output.
+    escape_start: usize,
+}
+
+impl ShellMarkParser {
+    pub fn line_feeds(&self) -> usize {
+        self.line_feeds
+    }
+
+    pub fn advance(&mut self, bytes: &[u8], mut on_mark: impl FnMut(ShellMarkEvent)) {
+        for &byte in bytes {
+            if let Some(output) = &mut self.output {
+                if output.len() < MAX_COMMAND_OUTPUT_BYTES {
+                    output.push(byte);
+                } else {
+                    self.output_truncated = true;
+                }
+            }
+            match self.state {
+                ParserState::Ground => match byte {
+                    ESC => {
+                        self.escape_start = self
+                            .output
+                            .as_ref()
+                            .map_or(0, |output| output.len().saturating_sub(1));
+                        self.state = ParserState::Escape;
+                    }
+                    b'\n' => self.line_feeds += 1,
+                    _ => {}
+                },
+                ParserState::Escape => {
+                    if byte == b']' {
+                        self.osc.clear();
+                        self.osc_overflow = false;
+                        self.state = ParserState::Osc;
+                    } else {
+                        self.state = ParserState::Ground;
+                    }
+                }
+                ParserState::Osc => match byte {
+                    BEL => {
+                        self.dispatch(&mut on_mark);
+                        self.state = ParserState::Ground;
+                    }
+                    ESC => self.state = ParserState::OscEscape,
+                    _ => {
+                        if self.osc.len() < MAX_OSC_LENGTH {
+                            self.osc.push(byte);
+                        } else {
+                            self.osc_overflow = true;
+                        }
+                    }
+                },
+                ParserState::OscEscape => {
+                    if byte == b'\\' {
+                        self.dispatch(&mut on_mark);
+                    }
+                    self.state = ParserState::Ground;
+                }
+            }
+        }
+    }
+
+    fn dispatch(&mut self, on_mark: &mut impl FnMut(ShellMarkEvent)) {
+        if self.osc_overflow {
+            return;
+        }
+        let osc = String::from_utf8_lossy(&self.osc);
+        let mut params = osc.split(';');
+        if !matches!(params.next(), Some("133" | "633")) {
+            return;
+        }
+        let mark = match params.next() {
+            Some("A") => ShellMark::PromptStart,
+            Some("E") => {
+                ShellMark::CommandLine(unescape_osc_633_value(params.next().unwrap_or_default()))
+            }
+            Some("C") => {
+                if self.output.is_some() {
+                    return;
+                }
+                self.output = Some(Vec::new());
+                self.output_truncated = false;
+                ShellMark::CommandExecuted
+            }
+            Some("D") => {
+                let Some(mut output) = self.output.take() else {
+                    return;
+                };
+                output.truncate(self.escape_start);
+                ShellMark::CommandFinished {
+                    exit_code: params.next().and_then(|code| code.trim().parse().ok()),
+                    output: output_text(&output),
+                    output_truncated: self.output_truncated,
+                }
+            }
+            _ => return,
+        };
+        on_mark(ShellMarkEvent {
+            mark,
+            line_feeds: self.line_feeds,
+        });
+    }
+}
+
+/// Reverts the escaping of `\` and `;` as `\\` and `\xAB` in the OSC 633 values.
+/// The escaped bytes are decoded as UTF-8 along with the rest, as shells escape the bytes of multibyte characters one by one.
+fn unescape_osc_633_value(value: &str) -> String {
+    let mut result = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some(ix) = rest.iter().position(|&byte| byte == b'\\') {
+        result.extend_from_slice(&rest[..ix]);
+        rest = &rest[ix + 1..];
+        if let Some(stripped) = rest.strip_prefix(b"\\") {
+            result.push(b'\\');
+            rest = stripped;
+        } else if let Some(byte) = rest
+            .strip_prefix(b"x")
+            .and_then(|hex| hex.get(..2))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            result.push(byte);
+            rest = &rest[3..];
+        } else {
+            result.push(b'\\');
+        }
+    }
+    result.extend_from_slice(rest);
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+/// Converts the raw pty output into plain text: strips the escape sequences,
+/// and applies the carriage returns and backspaces to the lines.
+pub(crate) fn output_text(bytes: &[u8]) -> String {
+    let mut stripped = Vec::with_capacity(bytes.len());
+    let mut ix = 0;
+    while ix < bytes.len() {
+        if bytes[ix] != ESC {
+            stripped.push(bytes[ix]);
+            ix += 1;
+            continue;
+        }
+        ix += 1;
+        match bytes.get(ix) {
+            // CSI: parameters and intermediates, up to the final byte.
+            Some(b'[') => {
+                ix += 1;
+                while ix < bytes.len() && !(0x40..=0x7e).contains(&bytes[ix]) {
+                    ix += 1;
+                }
+                ix += 1;
+            }
+            // OSC, DCS and the like: a string, up to the string terminator.
+            Some(b']' | b'P' | b'X' | b'^' | b'_') => {
+                ix += 1;
+                while ix < bytes.len() {
+                    if bytes[ix] == BEL {
+                        ix += 1;
+                        break;
+                    }
+                    if bytes[ix] == ESC && bytes.get(ix + 1) == Some(&b'\\') {
+                        ix += 2;
+                        break;
+                    }
+                    ix += 1;
+                }
+            }
+            // Character set designation and the like: an intermediate and a final byte.
+            Some(b'(' | b')' | b'*' | b'+' | b'#' | b'%') => ix += 2,
+            Some(_) => ix += 1,
+            None => {}
+        }
+    }
+
+    let text = String::from_utf8_lossy(&stripped);
+    let mut lines = Vec::new();
+    for line in text.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let line = line.rsplit('\r').next().unwrap_or_default();
+        let mut result = String::with_capacity(line.len());
+        for c in line.chars() {
+            match c {
+                '\x08' => {
+                    result.pop();
+                }
+                '\t' => result.push(c),
+                c if c.is_control() => {}
+                c => result.push(c),
+            }
+        }
+        lines.push(result);
+    }
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// Wraps a command to run with the shell's `-c`, so that it reports the start and the end of its output with the OSC 133 marks,
+/// and the output can be read with [`crate::Terminal::last_finished_command`] once it finishes.
+///
+/// The wrapper is written in the POSIX shell syntax, so `None` is returned for the other shells, e.g. fish and nu.
+pub fn mark_command_output(shell: &str, command: &str) -> Option<String> {
+    let name = Path::new(shell).file_name()?.to_str()?;
+    if !matches!(
+        name.trim_start_matches('-'),
+        "sh" | "bash" | "zsh" | "dash" | "ash" | "ksh" | "mksh" | "yash"
+    ) {
+        return None;
+    }
+    Some(format!(
+        "printf '\\033]133;C\\007'; {command}; __zed_exit_code=$?; \
+        printf '\\033]133;D;%d\\007' \"$__zed_exit_code\"; exit $__zed_exit_code"
+    ))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ShellKind {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl ShellKind {
+    fn new(program: &str) -> Option<Self> {
+        let name = Path::new(program).file_name()?.to_str()?;
+        match name.trim_start_matches('-') {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            _ => None,
+        }
+    }
+}
+
+/// Prepares the shell integration scripts for the shell program, if it is supported,
+/// and returns the arguments to start the shell with, adjusting its environment.
+pub(crate) fn shell_arguments(
+    program: &str,
+    login: bool,
+    env: &mut HashMap<String, String>,
+) -> Result<Option<Vec<String>>> {
+    let Some(kind) = ShellKind::new(program) else {
+        return Ok(None);
+    };
+    let dir = paths::temp_dir().join("shell_integration");
+    let login_args = || {
+        if login {
+            vec!["-l".to_string()]
+        } else {
+            Vec::new()
+        }
+    };
+    let args = match kind {
+        ShellKind::Bash => {
+            let script = write_script(&dir.join("zed.bash"), BASH_SCRIPT)?;
+            if login {
+                env.insert("ZED_SHELL_INTEGRATION_LOGIN".to_string(), "1".to_string());
+            }
+            vec![
+                "--init-file".to_string(),
+                script.to_string_lossy().into_owned(),
+            ]
+        }
+        ShellKind::Zsh => {
+            write_script(&dir.join("zed.zsh"), ZSH_SCRIPT)?;
+            write_script(&dir.join("zsh").join(".zshenv"), ZSHENV_SCRIPT)?;
+            let user_zdotdir = env
+                .get("ZDOTDIR")
+                .cloned()
+                .or_else(|| std::env::var("ZDOTDIR").ok());
+            if let Some(user_zdotdir) = user_zdotdir {
+                env.insert("ZED_USER_ZDOTDIR".to_string(), user_zdotdir);
+            }
+            env.insert(
+                "ZDOTDIR".to_string(),
+                dir.join("zsh").to_string_lossy().into_owned(),
+            );
+            env.insert(
+                "ZED_SHELL_INTEGRATION_DIR".to_string(),
+                dir.to_string_lossy().into_owned(),
+            );
+            login_args()
+        }
+        ShellKind::Fish => {
+            let data_dir = dir.join("fish_data");
+            write_script(
+                &data_dir.join("fish").join("vendor_conf.d").join("zed.fish"),
+                FISH_SCRIPT,
+            )?;
+            let data_dir = data_dir.to_string_lossy().into_owned();
+            let data_dirs = env
+                .get("XDG_DATA_DIRS")
+                .cloned()
+                .or_else(|| std::env::var("XDG_DATA_DIRS").ok())
+                .filter(|data_dirs| !data_dirs.is_empty())
+                .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+            env.insert(
+                "XDG_DATA_DIRS".to_string(),
+                format!("{data_dir}:{data_dirs}"),
+            );
+            env.insert("ZED_SHELL_INTEGRATION_DIR".to_string(), data_dir);
+            login_args()
+        }
+    };
+    Ok(Some(args))
+}
+
+fn write_script(path: &Path, contents: &str) -> Result<PathBuf> {
+    if std::fs::read_to_string(path).is_ok_and(|existing| existing == contents) {
+        return Ok(path.to_path_buf());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating shell integration directory {parent:?}"))?;
+    }
+    std::fs::write(path, contents)
+        .with_context(|| format!("writing shell integration script {path:?}"))?;
+    Ok(path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(chunks: &[&[u8]]) -> Vec<ShellMarkEvent> {
+        let mut parser = ShellMarkParser::default();
+        let mut marks = Vec::new();
+        for chunk in chunks {
+            parser.advance(chunk, |mark| marks.push(mark));
+        }
+        marks
+    }
+
+    #[test]
+    fn test_parse_shell_marks() {
+        let marks = parse(&[
+            b"\x1b]133;A\x07$ ",
+            b"\x1b]633;E;echo hi\\x3b ls \\\\\x07\x1b]13",
+            b"3;C\x1b\\h\x1b[1mi\x1b[0m\r\n",
+            b"50%\r100%\r\n\x1b]133;D;1\x07",
+            b"\x1b]133;D;0\x07\x1b]2;title\x07\x1b]133;A\x07$ ",
+        ]);
+        assert_eq!(
+            marks,
+            vec![
+                ShellMarkEvent {
+                    mark: ShellMark::PromptStart,
+                    line_feeds: 0,
+                },
+                ShellMarkEvent {
+                    mark: ShellMark::CommandLine("echo hi; ls \\".to_string()),
+                    line_feeds: 0,
+                },
+                ShellMarkEvent {
+                    mark: ShellMark::CommandExecuted,
+                    line_feeds: 0,
+                },
+                ShellMarkEvent {
+                    mark: ShellMark::CommandFinished {
+                        exit_code: Some(1),
+                        output: "hi\n100%".to_string(),
+                        output_truncated: false,
+                    },
+                    line_feeds: 2,
+                },
+                ShellMarkEvent {
+                    mark: ShellMark::PromptStart,
+                    line_feeds: 2,
+                },
+            ],
+            "Marks should be found across the chunks, and a finish without a running command should be ignored"
+        );
+    }
+
+    #[test]
+    fn test_apply_shell_marks() {
+        let mut blocks = Vec::new();
+        apply_shell_mark(&mut blocks, ShellMark::PromptStart, 0);
+        apply_shell_mark(&mut blocks, ShellMark::PromptStart, 1);
+        apply_shell_mark(&mut blocks, ShellMark::CommandLine("ls".to_string()), 1);
+        apply_shell_mark(&mut blocks, ShellMark::CommandExecuted, 2);
+        apply_shell_mark(
+            &mut blocks,
+            ShellMark::CommandFinished {
+                exit_code: Some(0),
+                output: "file".to_string(),
+                output_truncated: false,
+            },
+            3,
+        );
+        apply_shell_mark(&mut blocks, ShellMark::PromptStart, 3);
+        apply_shell_mark(
+            &mut blocks,
+            ShellMark::CommandFinished {
+                exit_code: Some(1),
+                output: String::new(),
+                output_truncated: false,
+            },
+            3,
+        );
+        assert_eq!(
+            blocks,
+            vec![
+                CommandBlock {
+                    prompt_line: 1,
+                    output_line: Some(2),
+                    command: Some("ls".to_string()),
+                    output: "file".to_string(),
+                    output_truncated: false,
+                    status: CommandStatus::Finished { exit_code: Some(0) },
+                },
+                CommandBlock {
+                    prompt_line: 3,
+                    output_line: None,
+                    command: None,
+                    output: String::new(),
+                    output_truncated: false,
+                    status: CommandStatus::Prompt,
+                },
+            ],
+            "Repeated prompt marks should update the prompt line, and a finish without a running command should be ignored"
+        );
+    }
+
+    #[test]
+    fn test_mark_command_output() {
+        assert!(
+            mark_command_output("/bin/bash", "ls")
+                .is_some_and(|wrapped| wrapped.contains("; ls; __zed_exit_code=$?;"))
+        );
+        assert!(mark_command_output("-zsh", "ls").is_some());
+        assert_eq!(mark_command_output("/usr/bin/fish", "ls"), None);
+        assert_eq!(mark_command_output("nu", "ls"), None);
+    }
+
+    #[test]
+    fn test_unescape_osc_633_value() {
+        assert_eq!(
+            unescape_osc_633_value("echo caf\\xc3\\xa9\\x3b ls ü \\\\ \\q"),
+            "echo café; ls ü \\ \\q"
+        );
+    }
+
+    #[test]
+    fn test_output_text() {
+        assert_eq!(
+            output_text(b"\x1b[32mok\x1b[0m\r\n\x1b]8;;https://zed.dev\x1b\\link\x1b]8;;\x1b\\\r\nab\x08c\r\n\r\n"),
+            "ok\nlink\nac"
+        );
+    }
+}
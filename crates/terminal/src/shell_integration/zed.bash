@@ -0,0 +1,43 @@
+# Zed shell integration for bash, sourced with `--init-file` instead of `~/.bashrc`.
+# Reports prompts, commands and their exit codes with the OSC 133 and OSC 633 sequences.
+
+if [ -n "${ZED_SHELL_INTEGRATION_LOGIN-}" ]; then
+    unset ZED_SHELL_INTEGRATION_LOGIN
+    [ -r /etc/profile ] && . /etc/profile
+    if [ -r ~/.bash_profile ]; then
+        . ~/.bash_profile
+    elif [ -r ~/.bash_login ]; then
+        . ~/.bash_login
+    elif [ -r ~/.profile ]; then
+        . ~/.profile
+    fi
+else
+    [ -r ~/.bashrc ] && . ~/.bashrc
+fi
+
+if [ -z "${__zed_shell_integration-}" ]; then
+    __zed_shell_integration=1
+
+    __zed_escape() {
+        local value="$1"
+        value=${value//'\'/'\\'}
+        value=${value//';'/'\x3b'}
+        value=${value//$'\n'/'\x0a'}
+        printf '%s' "$value"
+    }
+
+    __zed_preexec() {
+        local command
+        command="$(HISTTIMEFORMAT= builtin history 1 | sed 's/^ *[0-9]*[* ] *//')"
+        printf '\e]633;E;%s\a\e]133;C\a' "$(__zed_escape "$command")"
+    }
+
+    __zed_prompt_command() {
+        local exit_code=$?
+        printf '\e]133;D;%s\a\e]133;A\a' "$exit_code"
+        return $exit_code
+    }
+
+    PS0="${PS0-}"'$(__zed_preexec)'
+    PROMPT_COMMAND="__zed_prompt_command${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi
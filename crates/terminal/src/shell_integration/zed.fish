@@ -0,0 +1,33 @@
+# Zed shell integration for fish, loaded from the `vendor_conf.d` directory in `XDG_DATA_DIRS`.
+# Reports prompts, commands and their exit codes with the OSC 133 and OSC 633 sequences.
+
+status is-interactive; or exit
+
+# Hide our directory from the programs started in the shell.
+if set -q ZED_SHELL_INTEGRATION_DIR
+    set -l data_dirs (string split : -- "$XDG_DATA_DIRS")
+    set -l data_dirs (string match -v -- "$ZED_SHELL_INTEGRATION_DIR" $data_dirs)
+    if test (count $data_dirs) -gt 0
+        set -gx XDG_DATA_DIRS (string join : -- $data_dirs)
+    else
+        set -e XDG_DATA_DIRS
+    end
+    set -e ZED_SHELL_INTEGRATION_DIR
+end
+
+if not set -q __zed_shell_integration
+    set -g __zed_shell_integration 1
+
+    function __zed_prompt_start --on-event fish_prompt
+        printf '\e]133;A\a'
+    end
+
+    function __zed_preexec --on-event fish_preexec
+        set -l command (string replace -a -- '\\' '\\\\' $argv | string replace -a -- ';' '\\x3b' | string join -- '\x0a')
+        printf '\e]633;E;%s\a\e]133;C\a' "$command"
+    end
+
+    function __zed_postexec --on-event fish_postexec
+        printf '\e]133;D;%s\a' $status
+    end
+end
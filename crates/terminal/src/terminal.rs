@@ -1,10 +1,18 @@
 pub mod mappings;
+#[cfg(unix)]
+pub mod mux;
 
 pub use alacritty_terminal;
 
 mod pty_info;
+mod pty_output;
+mod scrollback;
+mod shell_integration;
 mod terminal_hyperlinks;
+mod terminal_images;
+mod terminal_recording;
 pub mod terminal_settings;
+mod terminal_sharing;
 
 use alacritty_terminal::{
     Term,
@@ -25,11 +33,14 @@ use alacritty_terminal::{
         ClearMode, CursorStyle as AlacCursorStyle, Handler, NamedPrivateMode, PrivateMode,
     },
 };
-use anyhow::{Result, bail};
+use anyhow::{Result, anyhow, bail};
 
 use futures::{
     FutureExt,
-    channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded},
+    channel::{
+        mpsc::{UnboundedReceiver, UnboundedSender, unbounded},
+        oneshot,
+    },
 };
 
 use mappings::mouse::{
@@ -39,16 +50,32 @@ use mappings::mouse::{
 
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+use parking_lot::Mutex;
 use pty_info::PtyProcessInfo;
+use regex::Regex;
+use scrollback::ScrollbackTracker;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::ShellMarkEvent;
 use smol::channel::{Receiver, Sender};
-use task::{HideStrategy, Shell, TaskId};
+use task::{
+    BackgroundPatterns, HideStrategy, ProblemMatcher, ProblemMatcherParser, Shell, TaskId,
+    TaskProblem,
+};
 use terminal_hyperlinks::RegexSearches;
+use terminal_images::{ImageEvent, ImageStore};
+use terminal_recording::TerminalRecorder;
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
 use theme::{ActiveTheme, Theme};
 use urlencoding;
-use util::{paths::home_dir, truncate_and_trailoff};
+use util::{ResultExt, paths::home_dir, truncate_and_trailoff};
+
+pub use shell_integration::{CommandBlock, CommandMark, CommandStatus, mark_command_output};
+pub use terminal_images::{ImagePlacement, is_image_placeholder};
+pub use terminal_recording::{
+    Asciicast, AsciicastEvent, AsciicastEventKind, AsciicastHeader, AsciicastReplay,
+};
+pub use terminal_sharing::{ScreenCellRun, ScreenSnapshot, decode_color, encode_color};
 
 use std::{
     borrow::Cow,
@@ -57,7 +84,10 @@ use std::{
     ops::{Deref, RangeInclusive},
     path::PathBuf,
     process::ExitStatus,
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
     time::{Duration, Instant},
 };
 use thiserror::Error;
@@ -103,6 +133,12 @@ actions!(
         ToggleViMode,
         /// Selects all text in the terminal.
         SelectAll,
+        /// Scrolls up to the previous command's prompt.
+        ScrollToPreviousCommand,
+        /// Scrolls down to the next command's prompt.
+        ScrollToNextCommand,
+        /// Copies the output of the last finished command to the clipboard.
+        CopyLastCommandOutput,
     ]
 );
 
@@ -130,6 +166,15 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    /// The task's problem matchers found new problems in its output, see [`Terminal::task_problems`].
+    TaskProblemsChanged,
+    /// The background task became busy or ready, or has finished, see [`Terminal::background_task_status`].
+    BackgroundTaskStatusChanged,
+    /// The shell reported a new prompt, or a command that started or finished, see [`Terminal::command_blocks`].
+    CommandBlocksChanged,
+    /// The terminal got disconnected from its persistent session, which might still be running,
+    /// and should be attached to it again, see [`Terminal::persistent_session_id`].
+    PersistentSessionDetached,
 }
 
 #[derive(Clone, Debug)]
@@ -351,6 +396,9 @@ impl TerminalBuilder {
         alternate_scroll: AlternateScroll,
         max_scroll_history_lines: Option<usize>,
         is_ssh_terminal: bool,
+        shell_integration: bool,
+        max_image_memory_mb: usize,
+        persistent_session: Option<PersistentSession>,
         window: AnyWindowHandle,
         completion_tx: Sender<Option<ExitStatus>>,
         cx: &App,
@@ -405,8 +453,59 @@ impl TerminalBuilder {
                 title_override,
             }),
         };
+
+        // Shell integration scripts are injected into the interactive shells started with their default arguments.
+        #[cfg(unix)]
+        let shell_params = match shell_params {
+            Some(ShellParams { args: None, .. }) | None
+                if shell_integration && task.is_none() && !is_ssh_terminal =>
+            {
+                let (program, login) = match &shell_params {
+                    Some(params) => (params.program.clone(), false),
+                    // Alacritty starts the system shell as a login shell on macOS.
+                    None => (util::get_system_shell(), cfg!(target_os = "macos")),
+                };
+                match shell_integration::shell_arguments(&program, login, &mut env)
+                    .log_err()
+                    .flatten()
+                {
+                    Some(args) => Some(ShellParams {
+                        program,
+                        args: Some(args),
+                        title_override: None,
+                    }),
+                    None => shell_params,
+                }
+            }
+            shell_params => shell_params,
+        };
         let terminal_title_override = shell_params.as_ref().and_then(|e| e.title_override.clone());
 
+        // The shell of a persistent session runs in the mux daemon, and the terminal runs the client attaching to it.
+        // The remote terminals are wrapped into the remote server's client already.
+        #[cfg(unix)]
+        let shell_params = match &persistent_session {
+            Some(session) if !is_ssh_terminal => {
+                let session_shell = shell_params
+                    .map(|params| {
+                        std::iter::once(params.program)
+                            .chain(params.args.unwrap_or_default())
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                env.insert(
+                    mux::SHELL_ENV_VAR.to_string(),
+                    serde_json::to_string(&session_shell)?,
+                );
+                Some(ShellParams {
+                    program: std::env::current_exe()?.to_string_lossy().into_owned(),
+                    args: Some(vec!["--terminal-attach".to_string(), session.id.clone()]),
+                    title_override: terminal_title_override.clone(),
+                })
+            }
+            _ => shell_params,
+        };
+
         #[cfg(windows)]
         let shell_program = shell_params.as_ref().map(|params| params.program.clone());
 
@@ -480,6 +579,29 @@ impl TerminalBuilder {
 
         let pty_info = PtyProcessInfo::new(&pty);
 
+        let shell_line_feeds = Arc::new(AtomicUsize::new(0));
+        let (shell_marks_tx, shell_marks_rx) = unbounded();
+        let (image_events_tx, image_events_rx) = unbounded();
+        let image_memory_limit = max_image_memory_mb.saturating_mul(1024 * 1024);
+        let recorder = Arc::new(Mutex::new(None));
+        // Pass the output through the image parser and the shell integration mark parser, so that the images
+        // are replaced with their placeholders, and the marks from the injected scripts, as well as from the
+        // user's own ones, are found.
+        #[cfg(unix)]
+        let pty = pty_output::FilteredPty::new(pty, |reader| {
+            let images = (image_memory_limit > 0)
+                .then(|| terminal_images::ImageParser::new(image_memory_limit, image_events_tx));
+            pty_output::PtyOutputReader::new(
+                reader,
+                images,
+                shell_line_feeds.clone(),
+                shell_marks_tx,
+                recorder.clone(),
+            )
+        })?;
+        #[cfg(not(unix))]
+        let _ = (shell_integration, shell_marks_tx, image_events_tx);
+
         //And connect them together
         let event_loop = EventLoop::new(
             term.clone(),
@@ -493,9 +615,32 @@ impl TerminalBuilder {
         let pty_tx = event_loop.channel();
         let _io_thread = event_loop.spawn(); // DANGER
 
+        let problem_matcher_parsers = task
+            .iter()
+            .flat_map(|task| task.problem_matchers.iter().cloned())
+            .filter_map(|matcher| ProblemMatcherParser::new(matcher).log_err())
+            .collect();
+
+        let background_task = task
+            .as_ref()
+            .and_then(|task| task.background.as_ref())
+            .map(BackgroundTaskState::new);
+
         let terminal = Terminal {
             task,
-            pty_tx: Notifier(pty_tx),
+            problem_matcher_parsers,
+            scanned_task_output_lines: 0,
+            scrollback: ScrollbackTracker::default(),
+            task_problems: Vec::new(),
+            background_task,
+            shell_marks_rx,
+            shell_line_feeds,
+            command_blocks: Vec::new(),
+            image_events_rx,
+            images: ImageStore::new(image_memory_limit),
+            recorder,
+            persistent_session,
+            pty_tx: PtySender::Pty(Notifier(pty_tx)),
             completion_tx,
             term,
             term_config: config,
@@ -527,7 +672,122 @@ impl TerminalBuilder {
         })
     }
 
+    /// Creates a terminal without a process of its own, mirroring the screen of a terminal shared by another
+    /// collaborator, see [`Terminal::apply_screen_snapshot`]. The input written to it is sent to `input_tx`.
+    pub fn new_mirror(
+        title: SharedString,
+        cursor_shape: CursorShape,
+        input_tx: UnboundedSender<Vec<u8>>,
+        completion_tx: Sender<Option<ExitStatus>>,
+    ) -> TerminalBuilder {
+        Self::new_without_process(
+            title,
+            cursor_shape,
+            PtySender::Mirror(input_tx),
+            completion_tx,
+        )
+    }
+
+    /// Creates a terminal without a process, for tests: the input written to it is sent to `input_tx`,
+    /// and its output is written with [`Terminal::write_output`].
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn new_fake(
+        title: SharedString,
+        input_tx: UnboundedSender<Vec<u8>>,
+        completion_tx: Sender<Option<ExitStatus>>,
+    ) -> TerminalBuilder {
+        Self::new_without_process(
+            title,
+            CursorShape::default(),
+            PtySender::Fake(input_tx),
+            completion_tx,
+        )
+    }
+
+    /// Matches the output of the terminal created with [`TerminalBuilder::new_fake`] with the problem matchers, as in a task terminal.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn with_problem_matchers(mut self, problem_matchers: Vec<ProblemMatcher>) -> Self {
+        self.terminal.problem_matcher_parsers = problem_matchers
+            .into_iter()
+            .filter_map(|matcher| ProblemMatcherParser::new(matcher).log_err())
+            .collect();
+        self
+    }
+
+    fn new_without_process(
+        title: SharedString,
+        cursor_shape: CursorShape,
+        pty_tx: PtySender,
+        completion_tx: Sender<Option<ExitStatus>>,
+    ) -> TerminalBuilder {
+        let config = Config {
+            scrolling_history: 0,
+            default_cursor_style: AlacCursorStyle::from(cursor_shape),
+            ..Config::default()
+        };
+        let (events_tx, events_rx) = unbounded();
+        let term = Term::new(
+            config.clone(),
+            &TerminalBounds::default(),
+            ZedListener(events_tx),
+        );
+        let (_, shell_marks_rx) = unbounded();
+        let (_, image_events_rx) = unbounded();
+
+        let terminal = Terminal {
+            task: None,
+            problem_matcher_parsers: Vec::new(),
+            scanned_task_output_lines: 0,
+            scrollback: ScrollbackTracker::default(),
+            task_problems: Vec::new(),
+            background_task: None,
+            shell_marks_rx,
+            shell_line_feeds: Arc::new(AtomicUsize::new(0)),
+            command_blocks: Vec::new(),
+            image_events_rx,
+            images: ImageStore::new(0),
+            recorder: Arc::new(Mutex::new(None)),
+            persistent_session: None,
+            pty_tx,
+            completion_tx,
+            term: Arc::new(FairMutex::new(term)),
+            term_config: config,
+            title_override: Some(title),
+            events: VecDeque::with_capacity(10),
+            last_content: Default::default(),
+            last_mouse: None,
+            matches: Vec::new(),
+            selection_head: None,
+            pty_info: PtyProcessInfo::detached(),
+            breadcrumb_text: String::new(),
+            scroll_px: px(0.),
+            next_link_id: 0,
+            selection_phase: SelectionPhase::Ended,
+            hyperlink_regex_searches: RegexSearches::new(),
+            vi_mode_enabled: false,
+            is_ssh_terminal: false,
+            python_venv_directory: None,
+            last_mouse_move_time: Instant::now(),
+            last_hyperlink_search_position: None,
+            #[cfg(windows)]
+            shell_program: None,
+        };
+
+        TerminalBuilder {
+            terminal,
+            events_rx,
+        }
+    }
+
     pub fn subscribe(mut self, cx: &Context<Terminal>) -> Terminal {
+        // Remove the images from the GPU atlas along with the terminal.
+        cx.on_release(|terminal, cx| {
+            for image in terminal.images.delete(None) {
+                cx.drop_image(image, None);
+            }
+        })
+        .detach();
+
         //Event loop
         cx.spawn(async move |terminal, cx| {
             while let Some(event) = self.events_rx.next().await {
@@ -619,6 +879,10 @@ pub struct TerminalContent {
     pub last_hovered_word: Option<HoveredWord>,
     pub scrolled_to_top: bool,
     pub scrolled_to_bottom: bool,
+    /// The command prompts visible in the viewport, as reported by the shell integration.
+    pub command_marks: Vec<CommandMark>,
+    /// The inline images with placeholders in the viewport.
+    pub images: Vec<ImagePlacement>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -645,6 +909,8 @@ impl Default for TerminalContent {
             last_hovered_word: None,
             scrolled_to_top: false,
             scrolled_to_bottom: false,
+            command_marks: Vec::new(),
+            images: Vec::new(),
         }
     }
 }
@@ -656,7 +922,7 @@ pub enum SelectionPhase {
 }
 
 pub struct Terminal {
-    pty_tx: Notifier,
+    pty_tx: PtySender,
     completion_tx: Sender<Option<ExitStatus>>,
     term: Arc<FairMutex<Term<ZedListener>>>,
     term_config: Config,
@@ -675,6 +941,21 @@ pub struct Terminal {
     selection_phase: SelectionPhase,
     hyperlink_regex_searches: RegexSearches,
     task: Option<TaskState>,
+    problem_matcher_parsers: Vec<ProblemMatcherParser>,
+    /// How many lines of the task output, counting from its first line, were passed to the problem matchers.
+    scanned_task_output_lines: usize,
+    scrollback: ScrollbackTracker,
+    task_problems: Vec<TaskProblem>,
+    background_task: Option<BackgroundTaskState>,
+    shell_marks_rx: UnboundedReceiver<ShellMarkEvent>,
+    /// How many line feeds the shell mark parser has seen in the pty output.
+    shell_line_feeds: Arc<AtomicUsize>,
+    command_blocks: Vec<CommandBlock>,
+    image_events_rx: UnboundedReceiver<ImageEvent>,
+    images: ImageStore,
+    /// The recording of the session, shared with the I/O thread that records the output.
+    recorder: Arc<Mutex<Option<TerminalRecorder>>>,
+    persistent_session: Option<PersistentSession>,
     vi_mode_enabled: bool,
     is_ssh_terminal: bool,
     last_mouse_move_time: Instant,
@@ -683,6 +964,45 @@ pub struct Terminal {
     shell_program: Option<String>,
 }
 
+/// Where the input written to the terminal goes.
+enum PtySender {
+    Pty(Notifier),
+    /// The terminal mirrors a terminal shared by another collaborator, and the input is forwarded to it.
+    Mirror(UnboundedSender<Vec<u8>>),
+    /// The terminal has no process, and the input is sent to the test.
+    #[cfg(any(test, feature = "test-support"))]
+    Fake(UnboundedSender<Vec<u8>>),
+}
+
+impl PtySender {
+    fn notify(&self, input: impl Into<Cow<'static, [u8]>>) {
+        match self {
+            PtySender::Pty(notifier) => notifier.notify(input),
+            PtySender::Mirror(input_tx) => {
+                input_tx.unbounded_send(input.into().into_owned()).ok();
+            }
+            #[cfg(any(test, feature = "test-support"))]
+            PtySender::Fake(input_tx) => {
+                input_tx.unbounded_send(input.into().into_owned()).ok();
+            }
+        }
+    }
+
+    fn send(&self, message: Msg) {
+        if let PtySender::Pty(notifier) = self {
+            notifier.0.send(message).ok();
+        }
+    }
+}
+
+/// A shell session kept alive by the terminal mux daemon, which the terminal is attached to, see [`mux`].
+#[derive(Debug)]
+pub struct PersistentSession {
+    pub id: String,
+    /// The command ending the session, when the terminal is closed for good rather than along with Zed.
+    pub end_command: std::process::Command,
+}
+
 pub struct TaskState {
     pub id: TaskId,
     pub full_label: String,
@@ -694,6 +1014,8 @@ pub struct TaskState {
     pub show_summary: bool,
     pub show_command: bool,
     pub show_rerun: bool,
+    pub problem_matchers: Vec<ProblemMatcher>,
+    pub background: Option<BackgroundPatterns>,
 }
 
 /// A status of the current terminal tab's task.
@@ -708,6 +1030,69 @@ pub enum TaskStatus {
     Completed { success: bool },
 }
 
+/// A status of a background task that is still running, as detected with its [`BackgroundPatterns`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackgroundTaskStatus {
+    /// The task is starting, or is processing new changes.
+    Busy,
+    /// The task has processed all changes and waits for new ones.
+    Ready,
+}
+
+struct BackgroundTaskState {
+    begins: Option<Regex>,
+    ends: Option<Regex>,
+    status: BackgroundTaskStatus,
+    ready_waiters: Vec<oneshot::Sender<()>>,
+}
+
+impl BackgroundTaskState {
+    fn new(patterns: &BackgroundPatterns) -> Self {
+        let begins = patterns
+            .begins
+            .as_deref()
+            .and_then(|pattern| Regex::new(pattern).log_err());
+        let ends = patterns
+            .ends
+            .as_deref()
+            .and_then(|pattern| Regex::new(pattern).log_err());
+        // Without a way to tell when the task is ready, consider it ready right away.
+        let status = if ends.is_some() {
+            BackgroundTaskStatus::Busy
+        } else {
+            BackgroundTaskStatus::Ready
+        };
+        Self {
+            begins,
+            ends,
+            status,
+            ready_waiters: Vec::new(),
+        }
+    }
+
+    fn match_line(&mut self, line: &str) {
+        if self
+            .begins
+            .as_ref()
+            .is_some_and(|begins| begins.is_match(line))
+        {
+            self.status = BackgroundTaskStatus::Busy;
+            return;
+        }
+        let is_ready = match &self.ends {
+            Some(ends) => ends.is_match(line),
+            // Without an end pattern, the task is only busy on the lines that match the begin pattern.
+            None => self.status == BackgroundTaskStatus::Busy,
+        };
+        if is_ready {
+            self.status = BackgroundTaskStatus::Ready;
+            for waiter in self.ready_waiters.drain(..) {
+                waiter.send(()).ok();
+            }
+        }
+    }
+}
+
 impl TaskStatus {
     fn register_terminal_exit(&mut self) {
         if self == &Self::Running {
@@ -778,6 +1163,10 @@ impl Terminal {
             }
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
+                self.scrollback.update(&self.term.lock_unfair());
+                self.process_shell_marks(cx);
+                self.process_image_events(cx);
+                self.scan_task_output(false, cx);
 
                 if self.pty_info.has_changed() {
                     cx.emit(Event::TitleChanged);
@@ -797,6 +1186,12 @@ impl Terminal {
                 self.write_to_pty(format(color).into_bytes());
             }
             AlacTermEvent::ChildExit(error_code) => {
+                if self.persistent_session.is_some() && self.is_detached_exit_code(error_code) {
+                    cx.emit(Event::PersistentSessionDetached);
+                    return;
+                }
+                // The session has ended, or another terminal has attached to it.
+                self.persistent_session = None;
                 self.register_task_finished(Some(error_code), cx);
             }
         }
@@ -821,11 +1216,20 @@ impl Terminal {
 
                 self.last_content.terminal_bounds = new_bounds;
 
-                self.pty_tx.0.send(Msg::Resize(new_bounds.into())).ok();
+                // The mirrors keep the size of the shared terminal.
+                if !self.is_mirror() {
+                    self.pty_tx.send(Msg::Resize(new_bounds.into()));
+                    if let Some(recorder) = self.recorder.lock().as_mut() {
+                        recorder.record_resize(new_bounds.num_columns(), new_bounds.num_lines());
+                    }
 
-                term.resize(new_bounds);
+                    term.resize(new_bounds);
+                }
             }
             InternalEvent::Clear => {
+                let lines_above_cursor =
+                    term.history_size() + term.grid().cursor.point.line.0.max(0) as usize;
+
                 // Clear back buffer
                 term.clear_screen(ClearMode::Saved);
 
@@ -854,6 +1258,20 @@ impl Terminal {
                 if (new_cursor.line.0 as usize) < term.screen_lines() - 1 {
                     term.grid_mut().reset_region((new_cursor.line + 1)..);
                 }
+                self.scrollback.drop_lines(lines_above_cursor, term);
+
+                // Only the current line is left, keep the command block that it belongs to
+                let current_block = self
+                    .command_blocks
+                    .pop()
+                    .filter(|block| !block.status.is_finished());
+                self.command_blocks.clear();
+                if let Some(mut block) = current_block {
+                    let line = self.scrollback.output_line(term, Line(0));
+                    block.prompt_line = line;
+                    block.output_line = block.output_line.map(|_| line);
+                    self.command_blocks.push(block);
+                }
 
                 cx.emit(Event::Wakeup);
             }
@@ -1157,6 +1575,46 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
     }
 
+    /// Scrolls the viewport up, so that the nearest command prompt above it is at its top.
+    pub fn scroll_to_previous_command(&mut self) {
+        let viewport_top = self.viewport_top_line();
+        if let Some(block) = self
+            .command_blocks
+            .iter()
+            .rev()
+            .find(|block| block.prompt_line < viewport_top)
+        {
+            let delta = (viewport_top - block.prompt_line) as i32;
+            self.events
+                .push_back(InternalEvent::Scroll(AlacScroll::Delta(delta)));
+        }
+    }
+
+    /// Scrolls the viewport down, so that the nearest command prompt below its top is at its top.
+    pub fn scroll_to_next_command(&mut self) {
+        let viewport_top = self.viewport_top_line();
+        match self
+            .command_blocks
+            .iter()
+            .find(|block| block.prompt_line > viewport_top)
+        {
+            Some(block) => {
+                let delta = (block.prompt_line - viewport_top) as i32;
+                self.events
+                    .push_back(InternalEvent::Scroll(AlacScroll::Delta(-delta)));
+            }
+            None => self.scroll_to_bottom(),
+        }
+    }
+
+    /// The line at the top of the viewport, counting from the first line of the terminal output.
+    fn viewport_top_line(&self) -> usize {
+        let term = self.term.lock_unfair();
+        let display_offset = term.grid().display_offset();
+        self.scrollback
+            .output_line(&term, Line(-(display_offset as i32)))
+    }
+
     pub fn scrolled_to_top(&self) -> bool {
         self.last_content.scrolled_to_top
     }
@@ -1182,6 +1640,10 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
         self.events.push_back(InternalEvent::SetSelection(None));
 
+        let input = input.into();
+        if let Some(recorder) = self.recorder.lock().as_mut() {
+            recorder.record_input(&input);
+        }
         self.write_to_pty(input);
     }
 
@@ -1337,10 +1799,22 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, window, cx)
         }
 
-        self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content = Self::make_content(
+            &terminal,
+            &self.last_content,
+            &self.command_blocks,
+            &self.scrollback,
+            &self.images,
+        );
     }
 
-    fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
+    fn make_content(
+        term: &Term<ZedListener>,
+        last_content: &TerminalContent,
+        command_blocks: &[CommandBlock],
+        scrollback: &ScrollbackTracker,
+        images: &ImageStore,
+    ) -> TerminalContent {
         let content = term.renderable_content();
 
         // Pre-allocate with estimated size to reduce reallocations
@@ -1358,6 +1832,18 @@ impl Terminal {
             None
         };
 
+        let viewport_top = scrollback.output_line(term, Line(-(content.display_offset as i32)));
+        let command_marks = command_blocks
+            .iter()
+            .filter_map(|block| {
+                let line = block.prompt_line.checked_sub(viewport_top)?;
+                (line < term.screen_lines()).then_some(CommandMark {
+                    line,
+                    status: block.status,
+                })
+            })
+            .collect();
+
         TerminalContent {
             cells,
             mode: content.mode,
@@ -1370,6 +1856,8 @@ impl Terminal {
             last_hovered_word: last_content.last_hovered_word.clone(),
             scrolled_to_top: content.display_offset == term.history_size(),
             scrolled_to_bottom: content.display_offset == 0,
+            command_marks,
+            images: images.placements(term),
         }
     }
 
@@ -1750,6 +2238,19 @@ impl Terminal {
         })
     }
 
+    /// Finds the lines of the whole scrollback with a match, for showing only those, see [`matching_lines`].
+    pub fn find_matching_lines(
+        &self,
+        mut searcher: RegexSearch,
+        cx: &Context<Self>,
+    ) -> Task<Vec<MatchingLine>> {
+        let term = self.term.clone();
+        cx.background_spawn(async move {
+            let term = term.lock();
+            matching_lines(&term, &mut searcher)
+        })
+    }
+
     pub fn working_directory(&self) -> Option<PathBuf> {
         if self.is_ssh_terminal {
             // We can't yet reliably detect the working directory of a shell on the
@@ -1836,6 +2337,95 @@ impl Terminal {
         self.task.as_ref()
     }
 
+    /// Problems found in the task output by the task's problem matchers so far.
+    pub fn task_problems(&self) -> &[TaskProblem] {
+        &self.task_problems
+    }
+
+    /// Passes the task output lines that were not processed yet to the problem matchers and the background task patterns.
+    /// Only the lines above the cursor are complete, unless the task has finished.
+    fn scan_task_output(&mut self, task_finished: bool, cx: &mut Context<Self>) {
+        if self.problem_matcher_parsers.is_empty() && self.background_task.is_none() {
+            return;
+        }
+
+        let lines = {
+            let term = self.term.lock_unfair();
+            let grid = term.grid();
+            let last_column = Column(grid.columns() - 1);
+            let cursor_line = grid.cursor.point.line.0;
+            let end_line = if task_finished {
+                cursor_line + 1
+            } else {
+                cursor_line
+            };
+
+            let mut lines = Vec::new();
+            let mut logical_line = String::new();
+            let mut line = self
+                .scrollback
+                .grid_line(&term, self.scanned_task_output_lines)
+                .0
+                .max(grid.topmost_line().0);
+            while line < end_line {
+                let row = &grid[Line(line)];
+                logical_line.push_str(&row_to_string(row));
+                line += 1;
+                if !row[last_column].flags.contains(Flags::WRAPLINE) {
+                    lines.push(logical_line.trim_end().to_string());
+                    logical_line.clear();
+                    self.scanned_task_output_lines = self.scrollback.output_line(&term, Line(line));
+                }
+            }
+            lines
+        };
+
+        let problems_before = self.task_problems.len();
+        for line in &lines {
+            for parser in &mut self.problem_matcher_parsers {
+                if let Some(problem) = parser.push_line(line) {
+                    self.task_problems.push(problem);
+                }
+            }
+        }
+        if self.task_problems.len() > problems_before {
+            cx.emit(Event::TaskProblemsChanged);
+        }
+
+        if let Some(background_task) = &mut self.background_task {
+            let previous_status = background_task.status;
+            for line in &lines {
+                background_task.match_line(line);
+            }
+            if background_task.status != previous_status {
+                cx.emit(Event::BackgroundTaskStatusChanged);
+            }
+        }
+    }
+
+    /// A status of the terminal's task, if it is a background task that is still running.
+    pub fn background_task_status(&self) -> Option<BackgroundTaskStatus> {
+        Some(self.background_task.as_ref()?.status)
+    }
+
+    /// Resolves once the background task of the terminal becomes ready,
+    /// fails if the task exits before that or is not a background task.
+    pub fn wait_for_background_task_ready(&mut self, cx: &App) -> Task<Result<()>> {
+        let Some(background_task) = self.background_task.as_mut() else {
+            return Task::ready(Err(anyhow!("Terminal has no running background task")));
+        };
+        if background_task.status == BackgroundTaskStatus::Ready {
+            return Task::ready(Ok(()));
+        }
+        let (ready_tx, ready_rx) = oneshot::channel();
+        background_task.ready_waiters.push(ready_tx);
+        cx.background_spawn(async move {
+            ready_rx
+                .await
+                .map_err(|_| anyhow!("Background task exited before becoming ready"))
+        })
+    }
+
     pub fn wait_for_completed_task(&self, cx: &App) -> Task<Option<ExitStatus>> {
         if let Some(task) = self.task() {
             if task.status == TaskStatus::Running {
@@ -1848,7 +2438,178 @@ impl Terminal {
         Task::ready(None)
     }
 
+    /// The prompts and commands that the shell reported with the shell integration marks, oldest first.
+    pub fn command_blocks(&self) -> &[CommandBlock] {
+        &self.command_blocks
+    }
+
+    /// The last command that has finished running, if the shell reported any.
+    pub fn last_finished_command(&self) -> Option<&CommandBlock> {
+        self.command_blocks
+            .iter()
+            .rev()
+            .find(|block| block.status.is_finished())
+    }
+
+    pub fn copy_last_command_output(&self, cx: &mut App) {
+        if let Some(block) = self.last_finished_command() {
+            cx.write_to_clipboard(ClipboardItem::new_string(block.output.clone()));
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.lock().is_some()
+    }
+
+    /// Starts recording the terminal output, input and resizes, see [`Terminal::stop_recording`].
+    pub fn start_recording(&mut self) -> Result<()> {
+        if cfg!(not(unix)) {
+            bail!("Recording terminal sessions is only supported on Unix");
+        }
+        let mut recorder = self.recorder.lock();
+        if recorder.is_none() {
+            let bounds = self.last_content.terminal_bounds;
+            let mut env = HashMap::default();
+            env.insert("TERM".to_string(), "xterm-256color".to_string());
+            if let Ok(shell) = std::env::var("SHELL") {
+                env.insert("SHELL".to_string(), shell);
+            }
+            *recorder = Some(TerminalRecorder::new(
+                bounds.num_columns(),
+                bounds.num_lines(),
+                Some(self.title(false)),
+                env,
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn stop_recording(&mut self) -> Option<Asciicast> {
+        Some(self.recorder.lock().take()?.finish())
+    }
+
+    /// Whether the terminal mirrors a terminal shared by another collaborator, see [`TerminalBuilder::new_mirror`].
+    pub fn is_mirror(&self) -> bool {
+        matches!(self.pty_tx, PtySender::Mirror(_))
+    }
+
+    /// Writes the output to the terminal created with [`TerminalBuilder::new_fake`], as if its process printed it.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn write_output(&mut self, output: &[u8], cx: &mut Context<Self>) {
+        let mut parser = alacritty_terminal::vte::ansi::Processor::<
+            alacritty_terminal::vte::ansi::StdSyncHandler,
+        >::new();
+        parser.advance(&mut *self.term.lock(), output);
+        cx.emit(Event::Wakeup);
+        self.scrollback.update(&self.term.lock_unfair());
+        self.scan_task_output(false, cx);
+    }
+
+    /// The visible screen of the terminal, to send to the collaborators it is shared with.
+    pub fn screen_snapshot(&self) -> ScreenSnapshot {
+        ScreenSnapshot::new(&self.term.lock())
+    }
+
+    /// Shows the screen of the shared terminal in its mirror.
+    pub fn apply_screen_snapshot(&mut self, snapshot: &ScreenSnapshot, cx: &mut Context<Self>) {
+        snapshot.apply(&mut self.term.lock());
+        cx.emit(Event::Wakeup);
+    }
+
+    pub fn set_title_override(&mut self, title: Option<SharedString>, cx: &mut Context<Self>) {
+        if self.title_override != title {
+            self.title_override = title;
+            cx.emit(Event::TitleChanged);
+        }
+    }
+
+    /// The id of the persistent session the terminal is attached to, to reattach to it after a restart.
+    pub fn persistent_session_id(&self) -> Option<&str> {
+        Some(self.persistent_session.as_ref()?.id.as_str())
+    }
+
+    /// Whether the persistent session client exited because it lost the connection to the session,
+    /// rather than because the session ended.
+    fn is_detached_exit_code(&self, exit_code: i32) -> bool {
+        #[cfg(unix)]
+        {
+            // The remote sessions are attached to over ssh, which exits with 255 when the connection is lost.
+            exit_code == mux::DETACHED_EXIT_CODE || (self.is_ssh_terminal && exit_code == 255)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = exit_code;
+            false
+        }
+    }
+
+    /// Ends the persistent session the terminal is attached to, instead of leaving it running detached.
+    pub fn end_persistent_session(&mut self, cx: &App) {
+        let Some(session) = self.persistent_session.take() else {
+            return;
+        };
+        let mut end_command = smol::process::Command::from(session.end_command);
+        end_command
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null());
+        cx.background_spawn(async move { end_command.status().await.log_err() })
+            .detach();
+    }
+
+    fn process_shell_marks(&mut self, cx: &mut Context<Self>) {
+        let mut marks = Vec::new();
+        while let Ok(Some(mark)) = self.shell_marks_rx.try_next() {
+            marks.push(mark);
+        }
+        if marks.is_empty() {
+            return;
+        }
+
+        let (cursor_line, line_feeds) = {
+            let term = self.term.lock();
+            (
+                self.scrollback
+                    .output_line(&term, term.grid().cursor.point.line),
+                self.shell_line_feeds.load(Ordering::Acquire),
+            )
+        };
+        for ShellMarkEvent {
+            mark,
+            line_feeds: mark_line_feeds,
+        } in marks
+        {
+            // The terminal has already processed the output after the mark,
+            // so count the lines back from the cursor to find where the mark was.
+            let line = cursor_line.saturating_sub(line_feeds.saturating_sub(mark_line_feeds));
+            shell_integration::apply_shell_mark(&mut self.command_blocks, mark, line);
+        }
+        cx.emit(Event::CommandBlocksChanged);
+    }
+
+    fn process_image_events(&mut self, cx: &mut Context<Self>) {
+        while let Ok(Some(event)) = self.image_events_rx.try_next() {
+            let removed = match event {
+                ImageEvent::Placed {
+                    placement_id,
+                    image,
+                } => self.images.insert(placement_id, image),
+                ImageEvent::Deleted { kitty_id } => self.images.delete(kitty_id),
+                ImageEvent::Reply(reply) => {
+                    self.write_to_pty(reply.into_bytes());
+                    Vec::new()
+                }
+            };
+            for image in removed {
+                cx.drop_image(image, None);
+            }
+        }
+    }
+
     fn register_task_finished(&mut self, error_code: Option<i32>, cx: &mut Context<Terminal>) {
+        // The command output marks arrive before the exit, make sure they are processed first.
+        self.scrollback.update(&self.term.lock_unfair());
+        self.process_shell_marks(cx);
         let e: Option<ExitStatus> = error_code.map(|code| {
             #[cfg(unix)]
             {
@@ -1873,6 +2634,13 @@ impl Terminal {
         if task.status != TaskStatus::Running {
             return;
         }
+        self.scan_task_output(true, cx);
+        if self.background_task.take().is_some() {
+            cx.emit(Event::BackgroundTaskStatusChanged);
+        }
+        let Some(task) = self.task.as_mut() else {
+            return;
+        };
         match error_code {
             Some(error_code) => {
                 task.status.register_task_exit(error_code);
@@ -1988,7 +2756,7 @@ unsafe fn append_text_to_term(term: &mut Term<ZedListener>, text_lines: &[&str])
 
 impl Drop for Terminal {
     fn drop(&mut self) {
-        self.pty_tx.0.send(Msg::Shutdown).ok();
+        self.pty_tx.send(Msg::Shutdown);
     }
 }
 
@@ -2009,6 +2777,37 @@ fn all_search_matches<'a, T>(
     RegexIter::new(start, end, AlacDirection::Right, term, regex)
 }
 
+/// A line of the terminal output containing a search match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchingLine {
+    /// The number of the line, counting from 1 at the top of the scrollback.
+    pub line_number: usize,
+    pub text: String,
+}
+
+/// The lines with a match, top to bottom, where the lines wrapped by the terminal count as one.
+fn matching_lines<T>(term: &Term<T>, regex: &mut RegexSearch) -> Vec<MatchingLine> {
+    let mut lines = Vec::new();
+    let mut last_line_start = None;
+    let matches = all_search_matches(term, regex).collect::<Vec<_>>();
+    for search_match in matches {
+        let line_start = term.line_search_left(*search_match.start());
+        if last_line_start == Some(line_start) {
+            continue;
+        }
+        last_line_start = Some(line_start);
+        let line_end = term.line_search_right(*search_match.start());
+        lines.push(MatchingLine {
+            line_number: (line_start.line.0 + term.history_size() as i32) as usize + 1,
+            text: term
+                .bounds_to_string(line_start, line_end)
+                .trim_end()
+                .to_string(),
+        });
+    }
+    lines
+}
+
 fn content_index_for_mouse(pos: Point<Pixels>, terminal_bounds: &TerminalBounds) -> usize {
     let col = (pos.x / terminal_bounds.cell_width()).round() as usize;
     let clamped_col = min(col, terminal_bounds.columns() - 1);
@@ -2112,12 +2911,99 @@ mod tests {
         index::{Column, Line, Point as AlacPoint},
         term::cell::Cell,
     };
-    use gpui::{Pixels, Point, bounds, point, size};
+    use gpui::{Pixels, Point, bounds, point, px, size};
     use rand::{Rng, distributions::Alphanumeric, rngs::ThreadRng, thread_rng};
 
     use crate::{
-        IndexedCell, TerminalBounds, TerminalContent, content_index_for_mouse, rgb_for_index,
+        BackgroundTaskState, BackgroundTaskStatus, IndexedCell, MatchingLine, TerminalBounds,
+        TerminalContent, content_index_for_mouse, matching_lines, rgb_for_index,
     };
+    use futures::channel::oneshot;
+    use task::BackgroundPatterns;
+
+    #[test]
+    fn test_background_task_status() {
+        let mut state = BackgroundTaskState::new(&BackgroundPatterns {
+            begins: Some("^Starting compilation".to_string()),
+            ends: Some(r"^Found \d+ errors".to_string()),
+        });
+        assert_eq!(state.status, BackgroundTaskStatus::Busy);
+        let (ready_tx, mut ready_rx) = oneshot::channel();
+        state.ready_waiters.push(ready_tx);
+
+        state.match_line("src/main.ts(1,1): error TS2304: Cannot find name 'foo'.");
+        assert_eq!(state.status, BackgroundTaskStatus::Busy);
+        state.match_line("Found 1 errors. Watching for file changes.");
+        assert_eq!(state.status, BackgroundTaskStatus::Ready);
+        assert_eq!(ready_rx.try_recv(), Ok(Some(())));
+
+        state.match_line("Starting compilation in watch mode...");
+        assert_eq!(state.status, BackgroundTaskStatus::Busy);
+
+        let state = BackgroundTaskState::new(&BackgroundPatterns {
+            begins: None,
+            ends: None,
+        });
+        assert_eq!(state.status, BackgroundTaskStatus::Ready);
+
+        let mut state = BackgroundTaskState::new(&BackgroundPatterns {
+            begins: Some("^Rebuilding".to_string()),
+            ends: None,
+        });
+        assert_eq!(state.status, BackgroundTaskStatus::Ready);
+        state.match_line("Rebuilding...");
+        assert_eq!(state.status, BackgroundTaskStatus::Busy);
+        let (ready_tx, mut ready_rx) = oneshot::channel();
+        state.ready_waiters.push(ready_tx);
+        state.match_line("Rebuilt in 120ms");
+        assert_eq!(state.status, BackgroundTaskStatus::Ready);
+        assert_eq!(ready_rx.try_recv(), Ok(Some(())));
+    }
+
+    #[test]
+    fn test_matching_lines() {
+        use alacritty_terminal::{
+            Term,
+            event::VoidListener,
+            term::{Config, search::RegexSearch},
+            vte::ansi::{Processor, StdSyncHandler},
+        };
+
+        let terminal_bounds = TerminalBounds::new(
+            px(10.),
+            px(5.),
+            bounds(point(px(0.), px(0.)), size(px(50.), px(30.))),
+        );
+        let config = Config {
+            scrolling_history: 100,
+            ..Config::default()
+        };
+        let mut term = Term::new(config, &terminal_bounds, VoidListener);
+        let mut parser = Processor::<StdSyncHandler>::new();
+        parser.advance(
+            &mut term,
+            b"error: one\r\nok\r\nwarning\r\nan error wraps around\r\nok\r\nerror: two error\r\n",
+        );
+
+        let mut regex = RegexSearch::new("error").unwrap();
+        assert_eq!(
+            matching_lines(&term, &mut regex),
+            vec![
+                MatchingLine {
+                    line_number: 1,
+                    text: "error: one".to_string(),
+                },
+                MatchingLine {
+                    line_number: 4,
+                    text: "an error wraps around".to_string(),
+                },
+                MatchingLine {
+                    line_number: 8,
+                    text: "error: two error".to_string(),
+                },
+            ]
+        );
+    }
 
     #[test]
     fn test_rgb_for_index() {
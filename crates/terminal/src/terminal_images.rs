@@ -0,0 +1,980 @@
+//! Inline images, sent by the programs with the Kitty graphics protocol or as Sixel graphics.
+//!
+//! The terminal emulator does not know about images, so their escape sequences are cut from the pty output
+//! before it reaches the emulator, and are replaced with placeholder cells that reserve the space for them.
+//! The first cell of each image row holds a private use character that encodes the row number, and has
+//! the underline color that encodes the image placement. The placeholders scroll and get cleared along
+//! with the rest of the grid, and the images are drawn over them.
+
+use std::{collections::VecDeque, sync::Arc};
+
+use alacritty_terminal::{
+    Term,
+    event::{EventListener, WindowSize},
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    vte::ansi::{Color, Rgb},
+};
+use anyhow::{Context as _, Result, anyhow, bail};
+use base64::Engine as _;
+use collections::HashMap;
+use futures::channel::mpsc::UnboundedSender;
+use gpui::{Pixels, RenderImage, Size, px, size};
+use image::RgbaImage;
+
+/// The placeholder of the image's first row, the next rows use the next characters.
+const PLACEHOLDER_BASE: u32 = 0x100000;
+const MAX_IMAGE_ROWS: usize = 0xfffd;
+/// The escape sequences longer than this are not decoded.
+const MAX_SEQUENCE_BYTES: usize = 64 * 1024 * 1024;
+const MAX_IMAGE_DIMENSION: u32 = 10_000;
+const ESC: u8 = 0x1b;
+
+/// Whether the character is a placeholder of an image row, which is not displayed as text.
+pub fn is_image_placeholder(c: char) -> bool {
+    (PLACEHOLDER_BASE..PLACEHOLDER_BASE + MAX_IMAGE_ROWS as u32).contains(&(c as u32))
+}
+
+/// An image displayed in the terminal.
+#[derive(Clone)]
+pub(crate) struct TerminalImage {
+    image: Arc<RenderImage>,
+    /// The size to draw the image with.
+    size: Size<Pixels>,
+    /// The id that the program has given to the image with the Kitty graphics protocol.
+    kitty_id: Option<u32>,
+}
+
+impl TerminalImage {
+    fn memory(&self) -> usize {
+        let size = self.image.size(0);
+        size.width.0 as usize * size.height.0 as usize * 4
+    }
+}
+
+/// An image that is anchored to the grid, as of the last sync of the terminal content.
+#[derive(Clone)]
+pub struct ImagePlacement {
+    pub image: Arc<RenderImage>,
+    /// The grid point of the image's top left corner, which might be above the viewport.
+    pub point: AlacPoint,
+    pub size: Size<Pixels>,
+}
+
+pub(crate) enum ImageEvent {
+    Placed {
+        placement_id: u32,
+        image: TerminalImage,
+    },
+    /// The program deleted the images with the Kitty id, or all images.
+    Deleted { kitty_id: Option<u32> },
+    /// A response to the program's Kitty graphics command, to write to the pty.
+    Reply(String),
+}
+
+/// Images that are displayed in the terminal, limited in the total memory they take:
+/// once over the limit, the oldest images are dropped and their placeholders stay blank.
+pub(crate) struct ImageStore {
+    images: HashMap<u32, TerminalImage>,
+    order: VecDeque<u32>,
+    memory: usize,
+    memory_limit: usize,
+}
+
+impl ImageStore {
+    pub fn new(memory_limit: usize) -> Self {
+        Self {
+            images: HashMap::default(),
+            order: VecDeque::new(),
+            memory: 0,
+            memory_limit,
+        }
+    }
+
+    /// Adds the image, returning the images it replaces or evicts, to be removed from the GPU atlas.
+    #[must_use]
+    pub fn insert(&mut self, placement_id: u32, image: TerminalImage) -> Vec<Arc<RenderImage>> {
+        let mut removed = Vec::new();
+        self.memory += image.memory();
+        if let Some(replaced) = self.images.insert(placement_id, image) {
+            self.memory -= replaced.memory();
+            self.order.retain(|id| *id != placement_id);
+            removed.push(replaced.image);
+        }
+        self.order.push_back(placement_id);
+        while self.memory > self.memory_limit
+            && let Some(oldest) = self.order.pop_front()
+        {
+            if let Some(image) = self.images.remove(&oldest) {
+                self.memory -= image.memory();
+                removed.push(image.image);
+            }
+        }
+        removed
+    }
+
+    /// Deletes the images with the Kitty id, or all images, returning them to be removed from the GPU atlas.
+    #[must_use]
+    pub fn delete(&mut self, kitty_id: Option<u32>) -> Vec<Arc<RenderImage>> {
+        let mut removed = Vec::new();
+        self.images.retain(|_, image| {
+            let keep = kitty_id.is_some_and(|kitty_id| image.kitty_id != Some(kitty_id));
+            if !keep {
+                removed.push(image.image.clone());
+            }
+            keep
+        });
+        self.order.retain(|id| self.images.contains_key(id));
+        self.memory = self.images.values().map(TerminalImage::memory).sum();
+        removed
+    }
+
+    /// Finds the images whose placeholders are in the viewport.
+    pub fn placements<T: EventListener>(&self, term: &Term<T>) -> Vec<ImagePlacement> {
+        if self.images.is_empty() {
+            return Vec::new();
+        }
+
+        let display_offset = term.grid().display_offset() as i32;
+        let mut placements = HashMap::<u32, AlacPoint>::default();
+        for line in 0..term.screen_lines() as i32 {
+            let line = Line(line - display_offset);
+            let row = &term.grid()[line];
+            for column in 0..term.columns() {
+                let cell = &row[Column(column)];
+                if !is_image_placeholder(cell.c) {
+                    continue;
+                }
+                let Some(Color::Spec(Rgb { r, g, b })) = cell.underline_color() else {
+                    continue;
+                };
+                let placement_id = u32::from_be_bytes([0, r, g, b]);
+                let image_row = (cell.c as u32 - PLACEHOLDER_BASE) as i32;
+                placements
+                    .entry(placement_id)
+                    .or_insert_with(|| AlacPoint::new(line - image_row, Column(column)));
+            }
+        }
+
+        placements
+            .into_iter()
+            .filter_map(|(placement_id, point)| {
+                let image = self.images.get(&placement_id)?;
+                Some(ImagePlacement {
+                    image: image.image.clone(),
+                    point,
+                    size: image.size,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ParserState {
+    Ground,
+    Escape,
+    DcsParams,
+    Sixel,
+    SixelEscape,
+    ApcStart,
+    Kitty,
+    KittyEscape,
+}
+
+/// Where to leave the cursor after the image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CursorMovement {
+    /// After the image's last column, on its last row.
+    AfterImage,
+    /// At the start of the line below the image.
+    NextLine,
+    /// Where the image starts.
+    Stay,
+}
+
+/// Cuts the image escape sequences from the pty output, decodes the images, and puts placeholders instead.
+pub(crate) struct ImageParser {
+    state: ParserState,
+    buffer: Vec<u8>,
+    dcs_params: Vec<u8>,
+    sequence_too_long: bool,
+    /// The chunks of the Kitty graphics command that is being transmitted.
+    kitty_command: Option<KittyCommand>,
+    /// Images transmitted with the Kitty graphics protocol, to display later by their ids.
+    transmitted: HashMap<u32, (Arc<RenderImage>, u32, u32)>,
+    transmitted_order: VecDeque<u32>,
+    transmitted_memory: usize,
+    memory_limit: usize,
+    next_placement_id: u32,
+    window_size: Option<WindowSize>,
+    events_tx: UnboundedSender<ImageEvent>,
+}
+
+impl ImageParser {
+    pub fn new(memory_limit: usize, events_tx: UnboundedSender<ImageEvent>) -> Self {
+        Self {
+            state: ParserState::Ground,
+            buffer: Vec::new(),
+            dcs_params: Vec::new(),
+            sequence_too_long: false,
+            kitty_command: None,
+            transmitted: HashMap::default(),
+            transmitted_order: VecDeque::new(),
+            transmitted_memory: 0,
+            memory_limit,
+            next_placement_id: 1,
+            window_size: None,
+            events_tx,
+        }
+    }
+
+    pub fn set_window_size(&mut self, window_size: WindowSize) {
+        self.window_size = Some(window_size);
+    }
+
+    /// Passes the output to the terminal emulator, except for the image escape sequences.
+    pub fn advance(&mut self, bytes: &[u8], output: &mut Vec<u8>) {
+        for &byte in bytes {
+            match self.state {
+                ParserState::Ground => {
+                    if byte == ESC {
+                        self.state = ParserState::Escape;
+                    } else {
+                        output.push(byte);
+                    }
+                }
+                ParserState::Escape => match byte {
+                    b'P' => {
+                        self.dcs_params.clear();
+                        self.state = ParserState::DcsParams;
+                    }
+                    b'_' => self.state = ParserState::ApcStart,
+                    ESC => output.push(ESC),
+                    _ => {
+                        output.extend_from_slice(&[ESC, byte]);
+                        self.state = ParserState::Ground;
+                    }
+                },
+                ParserState::DcsParams => match byte {
+                    b'0'..=b'9' | b';' if self.dcs_params.len() < 64 => self.dcs_params.push(byte),
+                    b'q' => self.start_sequence(ParserState::Sixel),
+                    _ => {
+                        // Not a Sixel image, leave the sequence to the terminal emulator.
+                        output.extend_from_slice(&[ESC, b'P']);
+                        output.extend_from_slice(&self.dcs_params);
+                        output.push(byte);
+                        self.state = ParserState::Ground;
+                    }
+                },
+                ParserState::ApcStart => {
+                    if byte == b'G' {
+                        self.start_sequence(ParserState::Kitty);
+                    } else {
+                        output.extend_from_slice(&[ESC, b'_', byte]);
+                        self.state = ParserState::Ground;
+                    }
+                }
+                ParserState::Sixel | ParserState::Kitty => {
+                    if byte == ESC {
+                        self.state = if self.state == ParserState::Sixel {
+                            ParserState::SixelEscape
+                        } else {
+                            ParserState::KittyEscape
+                        };
+                    } else if byte == 0x18 || byte == 0x1a {
+                        // CAN and SUB cancel the sequence.
+                        self.state = ParserState::Ground;
+                    } else {
+                        self.push_sequence_byte(byte);
+                    }
+                }
+                ParserState::SixelEscape | ParserState::KittyEscape => {
+                    let is_sixel = self.state == ParserState::SixelEscape;
+                    if byte == b'\\' {
+                        self.state = ParserState::Ground;
+                        if !self.sequence_too_long {
+                            let data = std::mem::take(&mut self.buffer);
+                            let result = if is_sixel {
+                                self.finish_sixel(&data, output)
+                            } else {
+                                self.finish_kitty(&data, output)
+                            };
+                            if let Err(error) = result {
+                                log::debug!("failed to display a terminal image: {error:#}");
+                            }
+                        }
+                        self.buffer.clear();
+                    } else {
+                        self.push_sequence_byte(ESC);
+                        self.push_sequence_byte(byte);
+                        self.state = if is_sixel {
+                            ParserState::Sixel
+                        } else {
+                            ParserState::Kitty
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    fn start_sequence(&mut self, state: ParserState) {
+        self.buffer.clear();
+        self.sequence_too_long = false;
+        self.state = state;
+    }
+
+    fn push_sequence_byte(&mut self, byte: u8) {
+        if self.buffer.len() < MAX_SEQUENCE_BYTES {
+            self.buffer.push(byte);
+        } else {
+            self.sequence_too_long = true;
+        }
+    }
+
+    fn finish_sixel(&mut self, data: &[u8], output: &mut Vec<u8>) -> Result<()> {
+        let image = decode_sixel(data)?;
+        let (width, height) = image.dimensions();
+        let image = render_image(image);
+        self.place(
+            image,
+            width,
+            height,
+            None,
+            None,
+            None,
+            CursorMovement::NextLine,
+            output,
+        );
+        Ok(())
+    }
+
+    fn finish_kitty(&mut self, data: &[u8], output: &mut Vec<u8>) -> Result<()> {
+        let (control, payload) = match data.iter().position(|&byte| byte == b';') {
+            Some(ix) => (&data[..ix], &data[ix + 1..]),
+            None => (data, &data[data.len()..]),
+        };
+        let chunk = KittyCommand::parse(control)?;
+        let more = chunk.more;
+        let mut command = match self.kitty_command.take() {
+            // The following chunks only have the `m` key, the rest is taken from the first chunk.
+            Some(mut command) => {
+                command.more = more;
+                command
+            }
+            None => chunk,
+        };
+        command.payload.extend_from_slice(payload);
+        if more {
+            self.kitty_command = Some(command);
+            return Ok(());
+        }
+
+        let result = self.run_kitty_command(&command, output);
+        if let Some(image_id) = command.image_id {
+            let reply = match &result {
+                Ok(()) if command.quiet == 0 && command.action != b'd' => Some("OK".to_string()),
+                Ok(()) => None,
+                Err(error) if command.quiet < 2 => Some(format!("{error}")),
+                Err(_) => None,
+            };
+            if let Some(reply) = reply {
+                self.events_tx
+                    .unbounded_send(ImageEvent::Reply(format!(
+                        "\x1b_Gi={image_id};{reply}\x1b\\"
+                    )))
+                    .ok();
+            }
+        }
+        result
+    }
+
+    fn run_kitty_command(&mut self, command: &KittyCommand, output: &mut Vec<u8>) -> Result<()> {
+        match command.action {
+            b'q' => {
+                decode_kitty_image(command)?;
+                Ok(())
+            }
+            b't' | b'T' => {
+                let image = decode_kitty_image(command)?;
+                let (width, height) = image.dimensions();
+                let image = render_image(image);
+                if let Some(image_id) = command.image_id {
+                    self.store_transmitted(image_id, image.clone(), width, height);
+                }
+                if command.action == b'T' {
+                    self.place_kitty(command, image, width, height, output);
+                }
+                Ok(())
+            }
+            b'p' => {
+                let image_id = command
+                    .image_id
+                    .context("EINVAL:the image id is required to display an image")?;
+                let (image, width, height) = self
+                    .transmitted
+                    .get(&image_id)
+                    .cloned()
+                    .with_context(|| format!("ENOENT:no image with id {image_id}"))?;
+                self.place_kitty(command, image, width, height, output);
+                Ok(())
+            }
+            b'd' => {
+                let kitty_id = match command.delete {
+                    b'i' | b'I' => Some(
+                        command
+                            .image_id
+                            .context("EINVAL:the image id is required to delete an image")?,
+                    ),
+                    b'a' | b'A' => None,
+                    _ => return Ok(()),
+                };
+                if command.delete.is_ascii_uppercase() {
+                    match kitty_id {
+                        Some(kitty_id) => self.remove_transmitted(kitty_id),
+                        None => {
+                            self.transmitted.clear();
+                            self.transmitted_order.clear();
+                            self.transmitted_memory = 0;
+                        }
+                    }
+                }
+                self.events_tx
+                    .unbounded_send(ImageEvent::Deleted { kitty_id })
+                    .ok();
+                Ok(())
+            }
+            action => bail!("EINVAL:unsupported action {}", action as char),
+        }
+    }
+
+    fn place_kitty(
+        &mut self,
+        command: &KittyCommand,
+        image: Arc<RenderImage>,
+        width: u32,
+        height: u32,
+        output: &mut Vec<u8>,
+    ) {
+        let cursor_movement = if command.keep_cursor {
+            CursorMovement::Stay
+        } else {
+            CursorMovement::AfterImage
+        };
+        self.place(
+            image,
+            width,
+            height,
+            command.columns,
+            command.rows,
+            command.image_id,
+            cursor_movement,
+            output,
+        );
+    }
+
+    fn store_transmitted(
+        &mut self,
+        image_id: u32,
+        image: Arc<RenderImage>,
+        width: u32,
+        height: u32,
+    ) {
+        self.remove_transmitted(image_id);
+        self.transmitted_memory += width as usize * height as usize * 4;
+        self.transmitted.insert(image_id, (image, width, height));
+        self.transmitted_order.push_back(image_id);
+        while self.transmitted_memory > self.memory_limit
+            && let Some(oldest) = self.transmitted_order.front().copied()
+        {
+            self.remove_transmitted(oldest);
+        }
+    }
+
+    fn remove_transmitted(&mut self, image_id: u32) {
+        if let Some((_, width, height)) = self.transmitted.remove(&image_id) {
+            self.transmitted_memory -= width as usize * height as usize * 4;
+            self.transmitted_order.retain(|id| *id != image_id);
+        }
+    }
+
+    /// Sends the image to display, and puts its placeholders into the output.
+    fn place(
+        &mut self,
+        image: Arc<RenderImage>,
+        width: u32,
+        height: u32,
+        columns: Option<usize>,
+        rows: Option<usize>,
+        kitty_id: Option<u32>,
+        cursor_movement: CursorMovement,
+        output: &mut Vec<u8>,
+    ) {
+        let (cell_width, cell_height, terminal_columns) = match self.window_size {
+            Some(window_size) if window_size.cell_width > 0 && window_size.cell_height > 0 => (
+                window_size.cell_width as f32,
+                window_size.cell_height as f32,
+                window_size.num_cols as usize,
+            ),
+            _ => (8., 16., usize::MAX),
+        };
+        let (width, height) = (width as f32, height as f32);
+        // Scale the image to the cells asked for, keeping the aspect ratio when only one dimension is given.
+        let display_size = match (columns, rows) {
+            (Some(columns), Some(rows)) => (columns as f32 * cell_width, rows as f32 * cell_height),
+            (Some(columns), None) => {
+                let display_width = columns as f32 * cell_width;
+                (display_width, height * display_width / width)
+            }
+            (None, Some(rows)) => {
+                let display_height = rows as f32 * cell_height;
+                (width * display_height / height, display_height)
+            }
+            (None, None) => (width, height),
+        };
+        let columns = columns
+            .unwrap_or_else(|| (display_size.0 / cell_width).ceil() as usize)
+            .clamp(1, terminal_columns.max(1));
+        let rows = rows
+            .unwrap_or_else(|| (display_size.1 / cell_height).ceil() as usize)
+            .clamp(1, MAX_IMAGE_ROWS);
+
+        let placement_id = self.next_placement_id;
+        self.next_placement_id = (self.next_placement_id + 1) & 0xff_ffff;
+        if self.next_placement_id == 0 {
+            self.next_placement_id = 1;
+        }
+        self.events_tx
+            .unbounded_send(ImageEvent::Placed {
+                placement_id,
+                image: TerminalImage {
+                    image,
+                    size: size(px(display_size.0), px(display_size.1)),
+                    kitty_id,
+                },
+            })
+            .ok();
+
+        write_placeholders(output, placement_id, columns, rows, cursor_movement);
+    }
+}
+
+/// Writes the placeholder of each image row into its first cell, and moves the cursor over the rest of the image.
+fn write_placeholders(
+    output: &mut Vec<u8>,
+    placement_id: u32,
+    columns: usize,
+    rows: usize,
+    cursor_movement: CursorMovement,
+) {
+    use std::io::Write as _;
+
+    let [_, r, g, b] = placement_id.to_be_bytes();
+    write!(output, "\x1b[58;2;{r};{g};{b}m").ok();
+    for row in 0..rows {
+        let placeholder = char::from_u32(PLACEHOLDER_BASE + row as u32).unwrap_or(' ');
+        write!(output, "{placeholder}").ok();
+        if columns > 1 {
+            write!(output, "\x1b[{}C", columns - 1).ok();
+        }
+        if row + 1 < rows {
+            write!(output, "\x1b[{columns}D\n").ok();
+        }
+    }
+    write!(output, "\x1b[59m").ok();
+    match cursor_movement {
+        CursorMovement::AfterImage => {}
+        CursorMovement::NextLine => output.extend_from_slice(b"\r\n"),
+        CursorMovement::Stay => {
+            write!(output, "\x1b[{columns}D").ok();
+            if rows > 1 {
+                write!(output, "\x1b[{}A", rows - 1).ok();
+            }
+        }
+    }
+}
+
+fn render_image(mut image: RgbaImage) -> Arc<RenderImage> {
+    // Convert from RGBA to BGRA.
+    for pixel in image.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    Arc::new(RenderImage::new(vec![image::Frame::new(image)]))
+}
+
+/// A command of the Kitty graphics protocol, see <https://sw.kovidgoyal.net/kitty/graphics-protocol/>.
+#[derive(Debug, Default)]
+struct KittyCommand {
+    action: u8,
+    format: u32,
+    medium: u8,
+    compression: Option<u8>,
+    width: u32,
+    height: u32,
+    image_id: Option<u32>,
+    columns: Option<usize>,
+    rows: Option<usize>,
+    keep_cursor: bool,
+    delete: u8,
+    quiet: u32,
+    more: bool,
+    payload: Vec<u8>,
+}
+
+impl KittyCommand {
+    fn parse(control: &[u8]) -> Result<Self> {
+        let mut command = Self {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            delete: b'a',
+            ..Self::default()
+        };
+        let control = std::str::from_utf8(control).context("EINVAL:invalid control data")?;
+        for pair in control.split(',').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .with_context(|| format!("EINVAL:invalid key {pair}"))?;
+            let number = || {
+                value
+                    .parse::<u32>()
+                    .with_context(|| format!("EINVAL:invalid value of {key}"))
+            };
+            let character = || value.bytes().next().unwrap_or_default();
+            match key {
+                "a" => command.action = character(),
+                "f" => command.format = number()?,
+                "t" => command.medium = character(),
+                "o" => command.compression = Some(character()),
+                "s" => command.width = number()?,
+                "v" => command.height = number()?,
+                "i" => command.image_id = Some(number()?).filter(|id| *id != 0),
+                "c" => command.columns = Some(number()? as usize).filter(|columns| *columns > 0),
+                "r" => command.rows = Some(number()? as usize).filter(|rows| *rows > 0),
+                "C" => command.keep_cursor = number()? == 1,
+                "d" => command.delete = character(),
+                "q" => command.quiet = number()?,
+                "m" => command.more = number()? == 1,
+                _ => {}
+            }
+        }
+        Ok(command)
+    }
+}
+
+fn decode_kitty_image(command: &KittyCommand) -> Result<RgbaImage> {
+    if command.medium != b'd' {
+        bail!("ENOTSUPPORTED:only the direct transmission is supported");
+    }
+    if command.compression.is_some() {
+        bail!("ENOTSUPPORTED:compression is not supported");
+    }
+    let payload: Vec<u8> = command
+        .payload
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(&payload)
+        .or_else(|_| base64::engine::general_purpose::STANDARD_NO_PAD.decode(&payload))
+        .map_err(|error| anyhow!("EINVAL:invalid base64 data: {error}"))?;
+    let image = match command.format {
+        100 => image::load_from_memory_with_format(&data, image::ImageFormat::Png)
+            .map_err(|error| anyhow!("EBADPNG:{error}"))?
+            .into_rgba8(),
+        24 | 32 => {
+            let (width, height) = (command.width, command.height);
+            if width == 0 || height == 0 {
+                bail!("EINVAL:the width and the height are required");
+            }
+            if width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+                bail!("EFBIG:the image is too large");
+            }
+            let bytes_per_pixel = if command.format == 24 { 3 } else { 4 };
+            let expected = (width as usize)
+                .checked_mul(height as usize)
+                .and_then(|pixels| pixels.checked_mul(bytes_per_pixel))
+                .context("EFBIG:the image is too large")?;
+            if data.len() < expected {
+                bail!("ENODATA:expected {expected} bytes, got {}", data.len());
+            }
+            let rgba = if command.format == 24 {
+                data[..expected]
+                    .chunks_exact(3)
+                    .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255])
+                    .collect()
+            } else {
+                data[..expected].to_vec()
+            };
+            RgbaImage::from_raw(width, height, rgba).context("EINVAL:invalid image size")?
+        }
+        format => bail!("EINVAL:unsupported format {format}"),
+    };
+    if image.width() > MAX_IMAGE_DIMENSION || image.height() > MAX_IMAGE_DIMENSION {
+        bail!("EFBIG:the image is too large");
+    }
+    Ok(image)
+}
+
+/// Decodes the Sixel data, which follows the `q` of the `DCS` sequence.
+fn decode_sixel(data: &[u8]) -> Result<RgbaImage> {
+    let mut palette = default_sixel_palette();
+    let mut color = 0;
+    let (mut x, mut y) = (0u32, 0u32);
+    let (mut width, mut height) = (0u32, 0u32);
+    // Columns of six pixels: the position, the bits of the pixels to paint, how many times to repeat, and the color.
+    let mut sixels = Vec::new();
+
+    let mut ix = 0;
+    let read_numbers = |ix: &mut usize| {
+        let mut numbers = Vec::new();
+        let mut current = None;
+        while let Some(&byte) = data.get(*ix) {
+            match byte {
+                b'0'..=b'9' => {
+                    current = Some(
+                        current
+                            .unwrap_or(0u32)
+                            .saturating_mul(10)
+                            .saturating_add((byte - b'0') as u32),
+                    )
+                }
+                b';' => numbers.push(current.take().unwrap_or(0)),
+                _ => break,
+            }
+            *ix += 1;
+        }
+        numbers.extend(current);
+        numbers
+    };
+
+    while let Some(&byte) = data.get(ix) {
+        ix += 1;
+        match byte {
+            b'"' => {
+                let numbers = read_numbers(&mut ix);
+                if let [_, _, raster_width, raster_height] = numbers[..] {
+                    width = width.max(raster_width);
+                    height = height.max(raster_height);
+                }
+            }
+            b'#' => {
+                let numbers = read_numbers(&mut ix);
+                match numbers[..] {
+                    [index] => color = index as usize % palette.len(),
+                    [index, system, a, b, c] => {
+                        color = index as usize % palette.len();
+                        palette[color] = match system {
+                            1 => hls_to_rgb(a, b, c),
+                            _ => [percent(a), percent(b), percent(c)],
+                        };
+                    }
+                    _ => {}
+                }
+            }
+            b'!' => {
+                let count = read_numbers(&mut ix).first().copied().unwrap_or(1).max(1);
+                if let Some(&sixel @ 0x3f..=0x7e) = data.get(ix) {
+                    ix += 1;
+                    sixels.push((x, y, sixel - 0x3f, count, palette[color]));
+                    x = x.saturating_add(count);
+                }
+            }
+            b'$' => x = 0,
+            b'-' => {
+                x = 0;
+                y = y.saturating_add(6);
+            }
+            0x3f..=0x7e => {
+                sixels.push((x, y, byte - 0x3f, 1, palette[color]));
+                x += 1;
+            }
+            _ => {}
+        }
+        if x > MAX_IMAGE_DIMENSION || y > MAX_IMAGE_DIMENSION {
+            bail!("the sixel image is too large");
+        }
+    }
+
+    for &(x, y, bits, count, _) in &sixels {
+        if bits != 0 {
+            width = width.max(x + count);
+            height = height.max(y + 6 - bits.leading_zeros().saturating_sub(2));
+        }
+    }
+    width = width.min(MAX_IMAGE_DIMENSION);
+    height = height.min(MAX_IMAGE_DIMENSION);
+    if width == 0 || height == 0 {
+        bail!("the sixel image is empty");
+    }
+
+    let mut image = RgbaImage::new(width, height);
+    for (x, y, bits, count, [r, g, b]) in sixels {
+        for bit in 0..6 {
+            if bits & (1 << bit) == 0 || y + bit >= height {
+                continue;
+            }
+            for x in x..(x + count).min(width) {
+                image.put_pixel(x, y + bit, image::Rgba([r, g, b, 255]));
+            }
+        }
+    }
+    Ok(image)
+}
+
+fn percent(value: u32) -> u8 {
+    (value.min(100) * 255 / 100) as u8
+}
+
+/// Converts the Sixel HLS color, where the hue 0 is blue, into RGB.
+fn hls_to_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 3] {
+    let hue = ((hue % 360 + 240) % 360) as f32 / 360.;
+    let lightness = lightness.min(100) as f32 / 100.;
+    let saturation = saturation.min(100) as f32 / 100.;
+    let rgb = gpui::hsla(hue, saturation, lightness, 1.).to_rgb();
+    [
+        (rgb.r * 255.).round() as u8,
+        (rgb.g * 255.).round() as u8,
+        (rgb.b * 255.).round() as u8,
+    ]
+}
+
+/// The VT340 colors that Sixel images start with.
+fn default_sixel_palette() -> Vec<[u8; 3]> {
+    let mut palette = [
+        [0, 0, 0],
+        [20, 20, 80],
+        [80, 13, 13],
+        [20, 80, 20],
+        [80, 20, 80],
+        [20, 80, 80],
+        [80, 80, 20],
+        [53, 53, 53],
+        [26, 26, 26],
+        [33, 33, 60],
+        [60, 26, 26],
+        [33, 60, 33],
+        [60, 33, 60],
+        [33, 60, 60],
+        [60, 60, 33],
+        [80, 80, 80],
+    ]
+    .map(|[r, g, b]| [percent(r), percent(g), percent(b)])
+    .to_vec();
+    palette.resize(256, [0, 0, 0]);
+    palette
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::mpsc::unbounded;
+
+    #[test]
+    fn test_image_sequences_are_replaced_with_placeholders() {
+        let (events_tx, mut events_rx) = unbounded();
+        let mut parser = ImageParser::new(usize::MAX, events_tx);
+        parser.set_window_size(WindowSize {
+            num_lines: 24,
+            num_cols: 80,
+            cell_width: 10,
+            cell_height: 20,
+        });
+
+        // A 2x3 red sixel image, split between the reads.
+        let mut output = Vec::new();
+        parser.advance(b"a\x1bPq#0;2;100;0;0!2~", &mut output);
+        parser.advance(b"\x1b\\b\x1b[1mc", &mut output);
+        let expected = format!(
+            "a\x1b[58;2;0;0;1m{}\x1b[59m\r\nb\x1b[1mc",
+            char::from_u32(PLACEHOLDER_BASE).unwrap()
+        );
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+        let Ok(Some(ImageEvent::Placed {
+            placement_id,
+            image,
+        })) = events_rx.try_next()
+        else {
+            panic!("expected the sixel image to be placed");
+        };
+        assert_eq!(placement_id, 1);
+        assert_eq!(image.size, size(px(2.), px(6.)));
+
+        // A 1x1 RGB Kitty image with an id, shown over 2 columns and 2 rows, with the other escape sequences kept.
+        let mut output = Vec::new();
+        parser.advance(
+            b"\x1bP$q\x1b\\\x1b_Ga=T,f=24,s=1,v=1,i=7,c=2,r=2;AAD/\x1b\\",
+            &mut output,
+        );
+        let expected = format!(
+            "\x1bP$q\x1b\\\x1b[58;2;0;0;2m{}\x1b[1C\x1b[2D\n{}\x1b[1C\x1b[59m",
+            char::from_u32(PLACEHOLDER_BASE).unwrap(),
+            char::from_u32(PLACEHOLDER_BASE + 1).unwrap(),
+        );
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+        let Ok(Some(ImageEvent::Placed { image, .. })) = events_rx.try_next() else {
+            panic!("expected the kitty image to be placed");
+        };
+        assert_eq!(image.kitty_id, Some(7));
+        assert_eq!(image.size, size(px(20.), px(40.)));
+        let Ok(Some(ImageEvent::Reply(reply))) = events_rx.try_next() else {
+            panic!("expected a reply to the kitty command");
+        };
+        assert_eq!(reply, "\x1b_Gi=7;OK\x1b\\");
+    }
+
+    #[test]
+    fn test_untrusted_image_parameters() {
+        let command = KittyCommand::parse(b"f=32,s=4294967295,v=4294967295").unwrap();
+        let error = decode_kitty_image(&KittyCommand {
+            payload: b"AAAA".to_vec(),
+            ..command
+        })
+        .unwrap_err();
+        assert!(error.to_string().starts_with("EFBIG:"), "{error}");
+
+        assert_eq!(
+            hls_to_rgb(u32::MAX, 50, 100),
+            hls_to_rgb(u32::MAX % 360, 50, 100)
+        );
+        assert_eq!(hls_to_rgb(360 + 120, 50, 100), hls_to_rgb(120, 50, 100));
+    }
+
+    #[test]
+    fn test_removed_images_are_returned_for_release() {
+        let image = |kitty_id| TerminalImage {
+            image: render_image(RgbaImage::new(2, 2)),
+            size: size(px(2.), px(2.)),
+            kitty_id,
+        };
+        let ids = |images: Vec<Arc<RenderImage>>| {
+            images.into_iter().map(|image| image.id).collect::<Vec<_>>()
+        };
+        // Room for two 2x2 images.
+        let mut store = ImageStore::new(32);
+
+        let first = image(Some(1));
+        let second = image(Some(2));
+        let third = image(None);
+        assert!(store.insert(1, first.clone()).is_empty());
+        assert!(store.insert(2, second.clone()).is_empty());
+        assert_eq!(
+            ids(store.insert(3, third.clone())),
+            vec![first.image.id],
+            "The oldest image should be evicted once over the memory limit"
+        );
+
+        let replacement = image(None);
+        assert_eq!(
+            ids(store.insert(3, replacement.clone())),
+            vec![third.image.id],
+            "A replaced image should be released too"
+        );
+        assert_eq!(ids(store.delete(Some(2))), vec![second.image.id]);
+        assert_eq!(ids(store.delete(None)), vec![replacement.image.id]);
+        assert!(store.images.is_empty());
+        assert_eq!(store.memory, 0);
+    }
+}
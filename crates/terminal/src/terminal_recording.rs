@@ -0,0 +1,486 @@
+//! Recording of the terminal sessions into the asciicast v2 format, see
+//! <https://docs.asciinema.org/manual/asciicast/v2/>, and their replay.
+
+use std::{
+    fmt::Write as _,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use alacritty_terminal::{
+    Term,
+    event::VoidListener,
+    grid::Dimensions,
+    term::{Config, RenderableCursor},
+    vte::ansi::Processor,
+};
+use anyhow::{Context as _, Result, bail};
+use collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::IndexedCell;
+
+/// How many bytes of event data a recording keeps, the events after that are dropped.
+const MAX_RECORDING_BYTES: usize = 64 * 1024 * 1024;
+/// The label of the marker that ends a recording that hit [`MAX_RECORDING_BYTES`].
+const TRUNCATED_MARKER: &str = "recording truncated";
+
+/// The first line of an asciicast file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AsciicastHeader {
+    pub version: u32,
+    pub width: usize,
+    pub height: usize,
+    /// When the recording started, in seconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsciicastEventKind {
+    /// Data written by the program to the terminal.
+    Output,
+    /// Data typed by the user.
+    Input,
+    /// The terminal was resized, the data is `{columns}x{rows}`.
+    Resize,
+    /// A marker, the data is its label.
+    Marker,
+}
+
+impl AsciicastEventKind {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Output => "o",
+            Self::Input => "i",
+            Self::Resize => "r",
+            Self::Marker => "m",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "o" => Some(Self::Output),
+            "i" => Some(Self::Input),
+            "r" => Some(Self::Resize),
+            "m" => Some(Self::Marker),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AsciicastEvent {
+    /// The time since the start of the recording.
+    pub time: Duration,
+    pub kind: AsciicastEventKind,
+    pub data: String,
+}
+
+/// A recorded terminal session.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Asciicast {
+    pub header: AsciicastHeader,
+    pub events: Vec<AsciicastEvent>,
+}
+
+impl Asciicast {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        let header = lines.next().context("the asciicast file is empty")?;
+        let header: AsciicastHeader =
+            serde_json::from_str(header).context("invalid asciicast header")?;
+        if header.version != 2 {
+            bail!("unsupported asciicast version {}", header.version);
+        }
+
+        let mut events = Vec::new();
+        for (ix, line) in lines.enumerate() {
+            let (time, code, data): (f64, String, String) = serde_json::from_str(line)
+                .with_context(|| format!("invalid asciicast event on line {}", ix + 2))?;
+            // Unknown event kinds are left for the newer players.
+            let Some(kind) = AsciicastEventKind::from_code(&code) else {
+                continue;
+            };
+            if !time.is_finite() || time < 0. {
+                bail!("invalid asciicast event time {time} on line {}", ix + 2);
+            }
+            events.push(AsciicastEvent {
+                time: Duration::from_secs_f64(time),
+                kind,
+                data,
+            });
+        }
+        Ok(Self { header, events })
+    }
+
+    /// Serializes the recording, one JSON value per line.
+    pub fn to_json_lines(&self) -> Result<String> {
+        let mut text = serde_json::to_string(&self.header)?;
+        text.push('\n');
+        for event in &self.events {
+            let time = (event.time.as_secs_f64() * 1_000_000.).round() / 1_000_000.;
+            let event = serde_json::to_string(&(time, event.kind.code(), &event.data))?;
+            writeln!(text, "{event}")?;
+        }
+        Ok(text)
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.events
+            .last()
+            .map_or(Duration::ZERO, |event| event.time)
+    }
+}
+
+/// Collects the terminal output and input as they happen, up to [`MAX_RECORDING_BYTES`] of them.
+pub(crate) struct TerminalRecorder {
+    started_at: Instant,
+    header: AsciicastHeader,
+    events: Vec<AsciicastEvent>,
+    recorded_bytes: usize,
+    max_bytes: usize,
+    /// Whether the recording hit the size limit, and the later events are dropped.
+    truncated: bool,
+    /// The end of the output that is not a complete UTF-8 character yet.
+    incomplete_output: Vec<u8>,
+}
+
+impl TerminalRecorder {
+    pub fn new(
+        width: usize,
+        height: usize,
+        title: Option<String>,
+        env: HashMap<String, String>,
+    ) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|duration| duration.as_secs());
+        Self {
+            started_at: Instant::now(),
+            header: AsciicastHeader {
+                version: 2,
+                width,
+                height,
+                timestamp,
+                title,
+                env,
+            },
+            events: Vec::new(),
+            recorded_bytes: 0,
+            max_bytes: MAX_RECORDING_BYTES,
+            truncated: false,
+            incomplete_output: Vec::new(),
+        }
+    }
+
+    pub fn record_output(&mut self, output: &[u8]) {
+        if self.truncated {
+            return;
+        }
+        self.incomplete_output.extend_from_slice(output);
+        let data = take_utf8(&mut self.incomplete_output);
+        self.record(AsciicastEventKind::Output, data);
+    }
+
+    pub fn record_input(&mut self, input: &[u8]) {
+        self.record(
+            AsciicastEventKind::Input,
+            String::from_utf8_lossy(input).into_owned(),
+        );
+    }
+
+    pub fn record_resize(&mut self, columns: usize, rows: usize) {
+        self.record(AsciicastEventKind::Resize, format!("{columns}x{rows}"));
+    }
+
+    fn record(&mut self, kind: AsciicastEventKind, data: String) {
+        if data.is_empty() || self.truncated {
+            return;
+        }
+        self.recorded_bytes += data.len();
+        if self.recorded_bytes > self.max_bytes {
+            log::warn!(
+                "Terminal recording is over {} bytes, dropping the rest of the session",
+                self.max_bytes
+            );
+            self.truncated = true;
+            self.incomplete_output.clear();
+            self.events.push(AsciicastEvent {
+                time: self.started_at.elapsed(),
+                kind: AsciicastEventKind::Marker,
+                data: TRUNCATED_MARKER.to_string(),
+            });
+            return;
+        }
+        self.events.push(AsciicastEvent {
+            time: self.started_at.elapsed(),
+            kind,
+            data,
+        });
+    }
+
+    pub fn finish(mut self) -> Asciicast {
+        if !self.incomplete_output.is_empty() {
+            let data = String::from_utf8_lossy(&self.incomplete_output).into_owned();
+            self.record(AsciicastEventKind::Output, data);
+        }
+        Asciicast {
+            header: self.header,
+            events: self.events,
+        }
+    }
+}
+
+/// Takes the complete UTF-8 characters from the bytes, replacing the invalid ones,
+/// and leaves the incomplete character at their end.
+fn take_utf8(bytes: &mut Vec<u8>) -> String {
+    let mut text = String::new();
+    let mut rest = bytes.as_slice();
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(valid) => {
+                text.push_str(valid);
+                rest = &[];
+                break;
+            }
+            Err(error) => {
+                let (valid, invalid) = rest.split_at(error.valid_up_to());
+                text.push_str(&String::from_utf8_lossy(valid));
+                match error.error_len() {
+                    Some(len) => {
+                        text.push(char::REPLACEMENT_CHARACTER);
+                        rest = &invalid[len..];
+                    }
+                    None => {
+                        rest = invalid;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    let incomplete = rest.to_vec();
+    *bytes = incomplete;
+    text
+}
+
+#[derive(Clone, Copy)]
+struct ReplaySize {
+    columns: usize,
+    lines: usize,
+}
+
+impl Dimensions for ReplaySize {
+    fn total_lines(&self) -> usize {
+        self.lines
+    }
+
+    fn screen_lines(&self) -> usize {
+        self.lines
+    }
+
+    fn columns(&self) -> usize {
+        self.columns
+    }
+}
+
+/// Replays a recording into a terminal grid, up to a position in time.
+pub struct AsciicastReplay {
+    asciicast: Asciicast,
+    term: Term<VoidListener>,
+    parser: Processor,
+    next_event: usize,
+    position: Duration,
+}
+
+impl AsciicastReplay {
+    pub fn new(asciicast: Asciicast) -> Self {
+        let term = Self::new_term(&asciicast.header);
+        Self {
+            asciicast,
+            term,
+            parser: Processor::new(),
+            next_event: 0,
+            position: Duration::ZERO,
+        }
+    }
+
+    fn new_term(header: &AsciicastHeader) -> Term<VoidListener> {
+        let size = ReplaySize {
+            columns: header.width.max(1),
+            lines: header.height.max(1),
+        };
+        Term::new(Config::default(), &size, VoidListener)
+    }
+
+    pub fn asciicast(&self) -> &Asciicast {
+        &self.asciicast
+    }
+
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_event >= self.asciicast.events.len()
+    }
+
+    /// Moves to the position, replaying the recording from its start when moving backwards.
+    pub fn seek(&mut self, position: Duration) {
+        let position = position.min(self.asciicast.duration());
+        if position < self.position {
+            self.term = Self::new_term(&self.asciicast.header);
+            self.parser = Processor::new();
+            self.next_event = 0;
+        }
+        while let Some(event) = self.asciicast.events.get(self.next_event)
+            && event.time <= position
+        {
+            match event.kind {
+                AsciicastEventKind::Output => {
+                    self.parser.advance(&mut self.term, event.data.as_bytes())
+                }
+                AsciicastEventKind::Resize => {
+                    if let Some((columns, lines)) = event.data.split_once('x')
+                        && let (Ok(columns), Ok(lines)) = (columns.parse(), lines.parse())
+                    {
+                        self.term.resize(ReplaySize {
+                            columns: usize::max(columns, 1),
+                            lines: usize::max(lines, 1),
+                        });
+                    }
+                }
+                AsciicastEventKind::Input | AsciicastEventKind::Marker => {}
+            }
+            self.next_event += 1;
+        }
+        self.position = position;
+    }
+
+    /// The columns and the rows of the replayed terminal.
+    pub fn size(&self) -> (usize, usize) {
+        (self.term.columns(), self.term.screen_lines())
+    }
+
+    pub fn cells(&self) -> Vec<IndexedCell> {
+        self.term
+            .renderable_content()
+            .display_iter
+            .map(|ic| IndexedCell {
+                point: ic.point,
+                cell: ic.cell.clone(),
+            })
+            .collect()
+    }
+
+    pub fn cursor(&self) -> RenderableCursor {
+        self.term.renderable_content().cursor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asciicast_round_trip() {
+        let mut recorder = TerminalRecorder::new(80, 24, Some("demo".into()), HashMap::default());
+        recorder.record_output(b"$ ls\r\n\xe2\x9c");
+        recorder.record_output(b"\x93 done\xff\r\n");
+        recorder.record_input(b"q");
+        recorder.record_resize(100, 30);
+        let asciicast = recorder.finish();
+
+        let kinds_and_data = asciicast
+            .events
+            .iter()
+            .map(|event| (event.kind, event.data.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds_and_data,
+            [
+                (AsciicastEventKind::Output, "$ ls\r\n"),
+                (AsciicastEventKind::Output, "✓ done\u{fffd}\r\n"),
+                (AsciicastEventKind::Input, "q"),
+                (AsciicastEventKind::Resize, "100x30"),
+            ]
+        );
+
+        let text = asciicast.to_json_lines().unwrap();
+        assert!(text.starts_with(r#"{"version":2,"width":80,"height":24,"timestamp":"#));
+        let parsed = Asciicast::parse(&text).unwrap();
+        assert_eq!(parsed.header, asciicast.header);
+        assert_eq!(parsed.events.len(), asciicast.events.len());
+    }
+
+    #[test]
+    fn test_recording_is_truncated_over_the_size_limit() {
+        let mut recorder = TerminalRecorder::new(80, 24, None, HashMap::default());
+        recorder.max_bytes = 10;
+        recorder.record_output(b"12345");
+        recorder.record_input(b"67");
+        recorder.record_output(b"890ab");
+        recorder.record_output(b"more");
+        recorder.record_resize(100, 30);
+        let asciicast = recorder.finish();
+
+        let kinds_and_data = asciicast
+            .events
+            .iter()
+            .map(|event| (event.kind, event.data.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds_and_data,
+            [
+                (AsciicastEventKind::Output, "12345"),
+                (AsciicastEventKind::Input, "67"),
+                (AsciicastEventKind::Marker, TRUNCATED_MARKER),
+            ],
+            "The events over the limit should be dropped, and the truncation marked"
+        );
+    }
+
+    #[test]
+    fn test_asciicast_replay() {
+        let asciicast = Asciicast::parse(concat!(
+            r#"{"version": 2, "width": 10, "height": 3}"#,
+            "\n",
+            r#"[0.5, "o", "one\r\n"]"#,
+            "\n",
+            r#"[1.0, "x", "unknown"]"#,
+            "\n",
+            r#"[1.5, "o", "two"]"#,
+            "\n",
+            r#"[2.0, "r", "20x5"]"#,
+            "\n",
+        ))
+        .unwrap();
+        assert_eq!(asciicast.duration(), Duration::from_secs(2));
+
+        let text = |replay: &AsciicastReplay| {
+            replay
+                .cells()
+                .iter()
+                .map(|cell| cell.c)
+                .collect::<String>()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let mut replay = AsciicastReplay::new(asciicast);
+        replay.seek(Duration::from_secs(1));
+        assert_eq!(text(&replay), "one");
+        replay.seek(Duration::from_secs(3));
+        assert_eq!(text(&replay), "one two");
+        assert_eq!(replay.size(), (20, 5));
+        assert!(replay.is_finished());
+        replay.seek(Duration::from_millis(100));
+        assert_eq!(text(&replay), "");
+        assert_eq!(replay.size(), (10, 3));
+    }
+}
@@ -47,6 +47,9 @@ pub struct TerminalSettings {
     pub default_height: Pixels,
     pub detect_venv: VenvSettings,
     pub max_scroll_history_lines: Option<usize>,
+    pub shell_integration: bool,
+    pub max_image_memory_mb: usize,
+    pub persistent_sessions: bool,
     pub toolbar: Toolbar,
     pub scrollbar: ScrollbarSettings,
     pub minimum_contrast: f32,
@@ -231,6 +234,25 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: 10_000
     pub max_scroll_history_lines: Option<usize>,
+    /// Whether to inject the shell integration scripts into bash, zsh and fish, so that they report
+    /// where the prompts, the commands and their output are, and how the commands have exited.
+    /// Existing terminals will not pick up this change until they are recreated.
+    ///
+    /// Default: true
+    pub shell_integration: Option<bool>,
+    /// How many megabytes of memory the inline images, displayed with the Kitty graphics protocol
+    /// or as Sixel graphics, can take in a terminal. Once over it, the oldest images are dropped.
+    /// Set to 0 to disable the inline images.
+    /// Existing terminals will not pick up this change until they are recreated.
+    ///
+    /// Default: 320
+    pub max_image_memory_mb: Option<usize>,
+    /// Whether the shells of the new terminals run in sessions kept alive by a per-user daemon, so that
+    /// the terminals reattach to them, with their output, after Zed restarts. For remote projects, the
+    /// sessions are kept on the remote host. Only supported on Unix.
+    ///
+    /// Default: false
+    pub persistent_sessions: Option<bool>,
     /// Toolbar related settings
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar-related settings
@@ -282,6 +304,10 @@ impl settings::Settings for TerminalSettings {
         vscode.bool_setting(&name("copyOnSelection"), &mut current.copy_on_select);
         vscode.bool_setting("macOptionIsMeta", &mut current.option_as_meta);
         vscode.usize_setting("scrollback", &mut current.max_scroll_history_lines);
+        vscode.bool_setting(
+            &name("shellIntegration.enabled"),
+            &mut current.shell_integration,
+        );
         match vscode.read_bool(&name("cursorBlinking")) {
             Some(true) => current.blinking = Some(TerminalBlink::On),
             Some(false) => current.blinking = Some(TerminalBlink::Off),
@@ -0,0 +1,244 @@
+//! Snapshots of the visible screen of a terminal shared with the collaborators, and their application to
+//! the terminals mirroring it on the guests' side.
+
+use alacritty_terminal::{
+    Term,
+    event::EventListener,
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::{
+        TermMode,
+        cell::{Cell, Flags},
+        test::TermSize,
+    },
+    vte::ansi::{Color as AnsiColor, Handler, NamedColor, NamedPrivateMode, PrivateMode, Rgb},
+};
+
+/// The visible screen of a shared terminal.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScreenSnapshot {
+    pub columns: usize,
+    pub rows: usize,
+    pub lines: Vec<Vec<ScreenCellRun>>,
+    /// The row and the column of the cursor, None when the cursor is hidden.
+    pub cursor: Option<(usize, usize)>,
+}
+
+/// Consecutive cells of a line with the same colors and flags, with one character per cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScreenCellRun {
+    pub text: String,
+    pub fg: AnsiColor,
+    pub bg: AnsiColor,
+    pub flags: Flags,
+}
+
+impl ScreenSnapshot {
+    pub(crate) fn new<T: EventListener>(term: &Term<T>) -> Self {
+        let grid = term.grid();
+        let columns = grid.columns();
+        let rows = grid.screen_lines();
+        let blank = Cell::default();
+
+        let lines = (0..rows)
+            .map(|row| {
+                let line = &grid[Line(row as i32)];
+                let mut runs = Vec::<ScreenCellRun>::new();
+                for column in 0..columns {
+                    let cell = &line[Column(column)];
+                    match runs.last_mut() {
+                        Some(run)
+                            if run.fg == cell.fg
+                                && run.bg == cell.bg
+                                && run.flags == cell.flags =>
+                        {
+                            run.text.push(cell.c)
+                        }
+                        _ => runs.push(ScreenCellRun {
+                            text: cell.c.to_string(),
+                            fg: cell.fg,
+                            bg: cell.bg,
+                            flags: cell.flags,
+                        }),
+                    }
+                }
+                // The trailing blank cells are filled in when the snapshot is applied.
+                if runs.last().is_some_and(|run| {
+                    run.fg == blank.fg
+                        && run.bg == blank.bg
+                        && run.flags == blank.flags
+                        && run.text.chars().all(|c| c == ' ')
+                }) {
+                    runs.pop();
+                }
+                runs
+            })
+            .collect();
+
+        let cursor = term.mode().contains(TermMode::SHOW_CURSOR).then(|| {
+            let point = grid.cursor.point;
+            (point.line.0.max(0) as usize, point.column.0)
+        });
+
+        Self {
+            columns,
+            rows,
+            lines,
+            cursor,
+        }
+    }
+
+    /// Replaces the screen of the mirroring terminal with the snapshot, resizing it to the size of the shared one.
+    pub(crate) fn apply<T: EventListener>(&self, term: &mut Term<T>) {
+        if self.columns == 0 || self.rows == 0 {
+            return;
+        }
+        if term.columns() != self.columns || term.screen_lines() != self.rows {
+            term.resize(TermSize::new(self.columns, self.rows));
+        }
+
+        let grid = term.grid_mut();
+        for row in 0..self.rows {
+            let line = &mut grid[Line(row as i32)];
+            let mut cells = self.lines.get(row).into_iter().flatten().flat_map(|run| {
+                run.text.chars().map(|c| Cell {
+                    c,
+                    fg: run.fg,
+                    bg: run.bg,
+                    flags: run.flags,
+                    ..Cell::default()
+                })
+            });
+            for column in 0..self.columns {
+                line[Column(column)] = cells.next().unwrap_or_default();
+            }
+        }
+
+        let mode = PrivateMode::Named(NamedPrivateMode::ShowCursor);
+        match self.cursor {
+            Some((row, column)) => {
+                grid.cursor.point = AlacPoint::new(
+                    Line(row.min(self.rows - 1) as i32),
+                    Column(column.min(self.columns - 1)),
+                );
+                term.set_private_mode(mode);
+            }
+            None => term.unset_private_mode(mode),
+        }
+    }
+}
+
+const RGB_COLOR: u32 = 0x01 << 24;
+const INDEXED_COLOR: u32 = 0x02 << 24;
+const NAMED_COLOR: u32 = 0x03 << 24;
+
+const NAMED_COLORS: [NamedColor; 29] = [
+    NamedColor::Black,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Yellow,
+    NamedColor::Blue,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::White,
+    NamedColor::BrightBlack,
+    NamedColor::BrightRed,
+    NamedColor::BrightGreen,
+    NamedColor::BrightYellow,
+    NamedColor::BrightBlue,
+    NamedColor::BrightMagenta,
+    NamedColor::BrightCyan,
+    NamedColor::BrightWhite,
+    NamedColor::Foreground,
+    NamedColor::Background,
+    NamedColor::Cursor,
+    NamedColor::DimBlack,
+    NamedColor::DimRed,
+    NamedColor::DimGreen,
+    NamedColor::DimYellow,
+    NamedColor::DimBlue,
+    NamedColor::DimMagenta,
+    NamedColor::DimCyan,
+    NamedColor::DimWhite,
+    NamedColor::BrightForeground,
+    NamedColor::DimForeground,
+];
+
+/// Encodes the color as `0x01RRGGBB` for an RGB color, `0x020000II` for an indexed one and `0x0300NNNN` for a
+/// named one.
+pub fn encode_color(color: AnsiColor) -> u32 {
+    match color {
+        AnsiColor::Spec(Rgb { r, g, b }) => {
+            RGB_COLOR | (r as u32) << 16 | (g as u32) << 8 | b as u32
+        }
+        AnsiColor::Indexed(index) => INDEXED_COLOR | index as u32,
+        AnsiColor::Named(name) => NAMED_COLOR | name as u32,
+    }
+}
+
+/// Decodes a color encoded with [`encode_color`].
+pub fn decode_color(encoded: u32) -> Option<AnsiColor> {
+    let value = encoded & 0xFF_FFFF;
+    match encoded & 0xFF00_0000 {
+        RGB_COLOR => Some(AnsiColor::Spec(Rgb {
+            r: (value >> 16) as u8,
+            g: (value >> 8) as u8,
+            b: value as u8,
+        })),
+        INDEXED_COLOR => Some(AnsiColor::Indexed(u8::try_from(value).ok()?)),
+        NAMED_COLOR => NAMED_COLORS
+            .into_iter()
+            .find(|name| *name as u32 == value)
+            .map(AnsiColor::Named),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{
+        event::VoidListener,
+        term::Config,
+        vte::ansi::{Processor, StdSyncHandler},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_color_encoding() {
+        for color in [
+            AnsiColor::Spec(Rgb {
+                r: 0x12,
+                g: 0x34,
+                b: 0x56,
+            }),
+            AnsiColor::Indexed(200),
+            AnsiColor::Named(NamedColor::Red),
+            AnsiColor::Named(NamedColor::DimForeground),
+        ] {
+            assert_eq!(decode_color(encode_color(color)), Some(color));
+        }
+        assert_eq!(decode_color(0x0400_0000), None);
+    }
+
+    #[test]
+    fn test_screen_snapshot_round_trip() {
+        let mut shared = Term::new(Config::default(), &TermSize::new(12, 3), VoidListener);
+        let mut parser = Processor::<StdSyncHandler>::new();
+        parser.advance(&mut shared, b"plain \x1b[31mred\x1b[0m\r\nsecond");
+
+        let snapshot = ScreenSnapshot::new(&shared);
+        assert_eq!((snapshot.columns, snapshot.rows), (12, 3));
+        assert_eq!(snapshot.cursor, Some((1, 6)));
+        let first_line = &snapshot.lines[0];
+        assert_eq!(first_line.len(), 2);
+        assert_eq!(first_line[0].text, "plain ");
+        assert_eq!(first_line[1].text, "red");
+        assert_eq!(first_line[1].fg, AnsiColor::Named(NamedColor::Red));
+        assert!(snapshot.lines[2].is_empty());
+
+        let mut mirror = Term::new(Config::default(), &TermSize::new(5, 5), VoidListener);
+        snapshot.apply(&mut mirror);
+        assert_eq!(ScreenSnapshot::new(&mirror), snapshot);
+    }
+}
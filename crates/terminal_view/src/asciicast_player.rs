@@ -0,0 +1,405 @@
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use gpui::{
+    App, Bounds, Context, EventEmitter, FocusHandle, Focusable, FontStyle, MouseButton,
+    MouseDownEvent, Pixels, Render, Task, TextStyle, WhiteSpace, Window, actions, canvas, fill,
+    point, relative, size,
+};
+use project::DirectoryLister;
+use settings::Settings;
+use terminal::{
+    Asciicast, AsciicastReplay, TerminalBounds,
+    alacritty_terminal::vte::ansi::CursorShape as AlacCursorShape,
+    terminal_settings::TerminalSettings,
+};
+use theme::ThemeSettings;
+use ui::{IconButton, IconName, Tooltip, prelude::*};
+use workspace::{
+    Workspace,
+    item::{Item, ItemEvent},
+    notifications::DetachAndPromptErr,
+};
+
+use crate::{ReplayRecording, terminal_element::TerminalElement};
+
+actions!(
+    asciicast_player,
+    [
+        /// Plays or pauses the terminal recording.
+        TogglePlayback,
+        /// Seeks the terminal recording five seconds forward.
+        SeekForward,
+        /// Seeks the terminal recording five seconds backward.
+        SeekBackward
+    ]
+);
+
+const SEEK_STEP: Duration = Duration::from_secs(5);
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
+        workspace.register_action(AsciicastPlayer::open);
+    })
+    .detach();
+}
+
+/// Replays an asciicast recording of a terminal session.
+pub struct AsciicastPlayer {
+    replay: AsciicastReplay,
+    title: SharedString,
+    focus_handle: FocusHandle,
+    /// When the playback was resumed, and the position it was resumed from.
+    playing_since: Option<(Instant, Duration)>,
+    progress_bar_bounds: Bounds<Pixels>,
+    _playback: Task<()>,
+}
+
+impl AsciicastPlayer {
+    pub fn new(asciicast: Asciicast, title: SharedString, cx: &mut Context<Self>) -> Self {
+        let mut this = Self {
+            replay: AsciicastReplay::new(asciicast),
+            title,
+            focus_handle: cx.focus_handle(),
+            playing_since: None,
+            progress_bar_bounds: Bounds::default(),
+            _playback: Task::ready(()),
+        };
+        this.play(cx);
+        this
+    }
+
+    fn open(
+        workspace: &mut Workspace,
+        _: &ReplayRecording,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let fs = workspace.app_state().fs.clone();
+        let lister = DirectoryLister::Local(workspace.project().clone(), fs.clone());
+        let paths = workspace.prompt_for_open_path(
+            gpui::PathPromptOptions {
+                files: true,
+                directories: false,
+                multiple: false,
+            },
+            lister,
+            window,
+            cx,
+        );
+        cx.spawn_in(window, async move |workspace, cx| {
+            let Some(path) = paths.await?.into_iter().flatten().next() else {
+                return Ok(());
+            };
+            let asciicast = Asciicast::parse(&fs.load(&path).await?)?;
+            let title = asciicast
+                .header
+                .title
+                .clone()
+                .filter(|title| !title.is_empty())
+                .unwrap_or_else(|| file_name(&path));
+            workspace.update_in(cx, |workspace, window, cx| {
+                let player = cx.new(|cx| AsciicastPlayer::new(asciicast, title.into(), cx));
+                workspace.add_item_to_active_pane(Box::new(player), None, true, window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to open the recording", window, cx, |_, _, _| None);
+    }
+
+    fn is_playing(&self) -> bool {
+        self.playing_since.is_some()
+    }
+
+    fn play(&mut self, cx: &mut Context<Self>) {
+        if self.replay.is_finished() {
+            self.replay.seek(Duration::ZERO);
+        }
+        self.playing_since = Some((Instant::now(), self.replay.position()));
+        self._playback = cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(FRAME_INTERVAL).await;
+                let playing = this.update(cx, |this, cx| this.advance(cx));
+                if !matches!(playing, Ok(true)) {
+                    break;
+                }
+            }
+        });
+        cx.notify();
+    }
+
+    fn pause(&mut self, cx: &mut Context<Self>) {
+        self.playing_since = None;
+        self._playback = Task::ready(());
+        cx.notify();
+    }
+
+    /// Catches the replay up with the time, returns whether it is still playing.
+    fn advance(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some((resumed_at, resumed_from)) = self.playing_since else {
+            return false;
+        };
+        self.replay.seek(resumed_from + resumed_at.elapsed());
+        if self.replay.is_finished() {
+            self.playing_since = None;
+        }
+        cx.notify();
+        self.is_playing()
+    }
+
+    fn seek(&mut self, position: Duration, cx: &mut Context<Self>) {
+        self.replay.seek(position);
+        if self.replay.is_finished() {
+            self.pause(cx);
+        } else if self.is_playing() {
+            self.play(cx);
+        }
+        cx.notify();
+    }
+
+    fn toggle_playback(&mut self, _: &TogglePlayback, _: &mut Window, cx: &mut Context<Self>) {
+        if self.is_playing() {
+            self.pause(cx);
+        } else {
+            self.play(cx);
+        }
+    }
+
+    fn seek_forward(&mut self, _: &SeekForward, _: &mut Window, cx: &mut Context<Self>) {
+        self.seek(self.replay.position() + SEEK_STEP, cx);
+    }
+
+    fn seek_backward(&mut self, _: &SeekBackward, _: &mut Window, cx: &mut Context<Self>) {
+        self.seek(self.replay.position().saturating_sub(SEEK_STEP), cx);
+    }
+
+    fn seek_to_mouse(&mut self, event: &MouseDownEvent, cx: &mut Context<Self>) {
+        let bounds = self.progress_bar_bounds;
+        if bounds.size.width <= Pixels::ZERO {
+            return;
+        }
+        let fraction = ((event.position.x - bounds.origin.x) / bounds.size.width).clamp(0., 1.);
+        let duration = self.replay.asciicast().duration();
+        self.seek(duration.mul_f32(fraction), cx);
+    }
+
+    fn render_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let position = self.replay.position();
+        let duration = self.replay.asciicast().duration();
+        let progress = if duration.is_zero() {
+            1.
+        } else {
+            position.as_secs_f32() / duration.as_secs_f32()
+        };
+        let (icon, tooltip) = if self.is_playing() {
+            (IconName::DebugPause, "Pause")
+        } else {
+            (IconName::PlayFilled, "Play")
+        };
+
+        h_flex()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                IconButton::new("toggle-playback", icon)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::for_action_title_in(
+                        tooltip,
+                        &TogglePlayback,
+                        &self.focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_playback(&TogglePlayback, window, cx)
+                    })),
+            )
+            .child(
+                Label::new(format!(
+                    "{} / {}",
+                    format_duration(position),
+                    format_duration(duration)
+                ))
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .child(
+                div()
+                    .id("progress-bar")
+                    .relative()
+                    .flex_1()
+                    .h_1p5()
+                    .rounded_sm()
+                    .cursor_pointer()
+                    .bg(cx.theme().colors().element_background)
+                    .child(
+                        div()
+                            .h_full()
+                            .w(relative(progress.clamp(0., 1.)))
+                            .rounded_sm()
+                            .bg(cx.theme().colors().text_accent),
+                    )
+                    .child({
+                        let this = cx.entity();
+                        canvas(
+                            move |bounds, _, cx| {
+                                this.update(cx, |this, _| this.progress_bar_bounds = bounds)
+                            },
+                            |_, _, _, _| {},
+                        )
+                        .absolute()
+                        .size_full()
+                    })
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, event, _, cx| this.seek_to_mouse(event, cx)),
+                    ),
+            )
+    }
+
+    fn render_screen(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let text_style = text_style(cx);
+        let rem_size = window.rem_size();
+        let line_height = text_style.line_height_in_pixels(rem_size);
+        let font_id = window.text_system().resolve_font(&text_style.font());
+        let cell_width = window
+            .text_system()
+            .advance(font_id, text_style.font_size.to_pixels(rem_size), 'm')
+            .map(|advance| advance.width)
+            .unwrap_or(Pixels::ZERO);
+
+        let minimum_contrast = TerminalSettings::get_global(cx).minimum_contrast;
+        let (rects, batched_text_runs) = TerminalElement::layout_grid(
+            self.replay.cells().into_iter(),
+            0,
+            &text_style,
+            None,
+            minimum_contrast,
+            cx,
+        );
+        let cursor = self.replay.cursor();
+        let cursor_color = cx.theme().players().local().cursor.opacity(0.5);
+        let (columns, lines) = self.replay.size();
+
+        canvas(
+            |_, _, _| {},
+            move |bounds, _, window, cx| {
+                let dimensions = TerminalBounds::new(line_height, cell_width, bounds);
+                for rect in rects {
+                    rect.paint(bounds.origin, &dimensions, window);
+                }
+                for batch in batched_text_runs {
+                    batch.paint(bounds.origin, &dimensions, window, cx);
+                }
+                if !matches!(cursor.shape, AlacCursorShape::Hidden) && cursor.point.line.0 >= 0 {
+                    let origin = bounds.origin
+                        + point(
+                            cell_width * cursor.point.column.0 as f32,
+                            line_height * cursor.point.line.0 as f32,
+                        );
+                    window.paint_quad(fill(
+                        Bounds::new(origin, size(cell_width, line_height)),
+                        cursor_color,
+                    ));
+                }
+            },
+        )
+        .w(cell_width * columns as f32)
+        .h(line_height * lines as f32)
+    }
+}
+
+fn text_style(cx: &App) -> TextStyle {
+    let settings = ThemeSettings::get_global(cx);
+    let terminal_settings = TerminalSettings::get_global(cx);
+    let theme = cx.theme();
+    TextStyle {
+        font_family: terminal_settings.font_family.as_ref().map_or_else(
+            || settings.buffer_font.family.clone(),
+            |font_family| font_family.0.clone().into(),
+        ),
+        font_features: terminal_settings
+            .font_features
+            .clone()
+            .unwrap_or_else(gpui::FontFeatures::disable_ligatures),
+        font_weight: terminal_settings.font_weight.unwrap_or_default(),
+        font_fallbacks: terminal_settings
+            .font_fallbacks
+            .as_ref()
+            .or(settings.buffer_font.fallbacks.as_ref())
+            .cloned(),
+        font_size: terminal_settings
+            .font_size
+            .map_or(settings.buffer_font_size(cx), |size| {
+                theme::adjusted_font_size(size, cx)
+            })
+            .into(),
+        font_style: FontStyle::Normal,
+        line_height: terminal_settings.line_height.value().into(),
+        background_color: Some(theme.colors().terminal_ansi_background),
+        white_space: WhiteSpace::Normal,
+        // These are going to be overridden per-cell
+        color: theme.colors().terminal_foreground,
+        ..Default::default()
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+}
+
+impl EventEmitter<ItemEvent> for AsciicastPlayer {}
+
+impl Focusable for AsciicastPlayer {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for AsciicastPlayer {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("AsciicastPlayer")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_playback))
+            .on_action(cx.listener(Self::seek_forward))
+            .on_action(cx.listener(Self::seek_backward))
+            .size_full()
+            .bg(cx.theme().colors().terminal_background)
+            .child(self.render_controls(cx))
+            .child(
+                div()
+                    .id("asciicast-screen")
+                    .flex_1()
+                    .p_2()
+                    .overflow_scroll()
+                    .child(self.render_screen(window, cx)),
+            )
+    }
+}
+
+impl Item for AsciicastPlayer {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _: usize, _: &App) -> SharedString {
+        self.title.clone()
+    }
+
+    fn tab_icon(&self, _: &Window, _: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Terminal))
+    }
+}
@@ -412,6 +412,9 @@ define_connection! {
             ALTER TABLE terminals ADD COLUMN working_directory_path TEXT;
             UPDATE terminals SET working_directory_path = CAST(working_directory AS TEXT);
         ),
+        sql! (
+            ALTER TABLE terminals ADD COLUMN session_id TEXT;
+        ),
     ];
 }
 
@@ -464,4 +467,20 @@ impl TerminalDb {
             WHERE item_id = ? AND workspace_id = ?
         }
     }
+
+    query! {
+        pub async fn save_session_id(session_id: Option<String>, item_id: ItemId, workspace_id: WorkspaceId) -> Result<()> {
+            UPDATE terminals
+            SET session_id = ?
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
+
+    query! {
+        pub fn get_session_id(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT session_id
+            FROM terminals
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
 }
@@ -1,20 +1,20 @@
 use crate::color_contrast;
 use editor::{CursorLayout, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, DispatchPhase,
-    Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle, FontWeight,
-    GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity,
-    IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels,
-    Point, ShapedLine, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle,
-    UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window, div, fill, point, px, relative,
-    size,
+    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, Corners,
+    DispatchPhase, Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle,
+    FontWeight, GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement,
+    Interactivity, IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton,
+    MouseMoveEvent, Pixels, Point, RenderImage, ShapedLine, StatefulInteractiveElement,
+    StrikethroughStyle, Styled, TextRun, TextStyle, UTF16Selection, UnderlineStyle, WeakEntity,
+    WhiteSpace, Window, div, fill, point, px, relative, size,
 };
 use itertools::Itertools;
 use language::CursorShape;
 use settings::Settings;
 use std::time::Instant;
 use terminal::{
-    IndexedCell, Terminal, TerminalBounds, TerminalContent,
+    CommandStatus, IndexedCell, Terminal, TerminalBounds, TerminalContent,
     alacritty_terminal::{
         grid::Dimensions,
         index::Point as AlacPoint,
@@ -32,7 +32,7 @@ use util::ResultExt;
 use workspace::Workspace;
 
 use std::mem;
-use std::{fmt::Debug, ops::RangeInclusive, rc::Rc};
+use std::{fmt::Debug, ops::RangeInclusive, rc::Rc, sync::Arc};
 
 use crate::{BlockContext, BlockProperties, ContentMode, TerminalMode, TerminalView};
 
@@ -49,6 +49,10 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    /// Viewport lines of the command prompts to mark in the gutter, with the colors of their exit statuses.
+    command_marks: Vec<(usize, Hsla)>,
+    /// Inline images, with their bounds relative to the grid origin.
+    images: Vec<(Bounds<Pixels>, Arc<RenderImage>)>,
     block_below_cursor_element: Option<AnyElement>,
     base_text_style: TextStyle,
     content_mode: ContentMode,
@@ -374,6 +378,11 @@ impl TerminalElement {
                     continue;
                 }
 
+                // Skip image placeholders - the images are painted over them
+                if terminal::is_image_placeholder(cell.c) {
+                    continue;
+                }
+
                 // Skip spaces that follow cells with extras (emoji variation sequences)
                 if cell.c == ' ' && previous_cell_had_extras {
                     previous_cell_had_extras = false;
@@ -1015,11 +1024,46 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    command_marks,
+                    images,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
 
+                let command_marks = command_marks
+                    .iter()
+                    .filter_map(|mark| {
+                        let color = match mark.status {
+                            CommandStatus::Prompt => return None,
+                            CommandStatus::Running
+                            | CommandStatus::Finished { exit_code: None } => {
+                                theme.colors().text_muted
+                            }
+                            CommandStatus::Finished { exit_code: Some(0) } => {
+                                theme.status().success
+                            }
+                            CommandStatus::Finished { exit_code: Some(_) } => theme.status().error,
+                        };
+                        Some((mark.line, color))
+                    })
+                    .collect();
+
+                let images = images
+                    .iter()
+                    .map(|placement| {
+                        let line = placement.point.line.0 + display_offset as i32;
+                        let image_origin = point(
+                            dimensions.cell_width * placement.point.column.0 as f32,
+                            dimensions.line_height * line as f32,
+                        );
+                        (
+                            Bounds::new(image_origin, placement.size),
+                            placement.image.clone(),
+                        )
+                    })
+                    .collect();
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -1165,6 +1209,8 @@ impl Element for TerminalElement {
                     display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    command_marks,
+                    images,
                     block_below_cursor_element,
                     base_text_style: text_style,
                     content_mode,
@@ -1251,6 +1297,31 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, window);
                     }
 
+                    for (image_bounds, image) in &layout.images {
+                        window
+                            .paint_image(
+                                Bounds::new(origin + image_bounds.origin, image_bounds.size),
+                                Corners::default(),
+                                image.clone(),
+                                0,
+                                false,
+                            )
+                            .log_err();
+                    }
+
+                    let mark_width = (layout.gutter / 4.).max(px(2.));
+                    for (line, color) in &layout.command_marks {
+                        let mark_origin = point(
+                            bounds.origin.x + (layout.gutter - mark_width) / 2.,
+                            origin.y + layout.dimensions.line_height * *line as f32,
+                        );
+                        let mark_size = size(mark_width, layout.dimensions.line_height);
+                        window.paint_quad(
+                            fill(Bounds::new(mark_origin, mark_size), *color)
+                                .corner_radii(mark_width / 2.),
+                        );
+                    }
+
                     for (relative_highlighted_range, color) in
                         layout.relative_highlighted_ranges.iter()
                     {
@@ -1489,6 +1489,34 @@ impl workspace::TerminalProvider for TerminalProvider {
             }
         })
     }
+
+    fn spawn_background(
+        &self,
+        task: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Option<Result<()>>> {
+        let terminal_panel = self.0.clone();
+        window.spawn(cx, async move |cx| {
+            let terminal = terminal_panel
+                .update_in(cx, |terminal_panel, window, cx| {
+                    terminal_panel.spawn_task(&task, window, cx)
+                })
+                .ok()?
+                .await;
+            match terminal {
+                Ok(terminal) => Some(
+                    terminal
+                        .update(cx, |terminal, cx| {
+                            terminal.wait_for_background_task_ready(cx)
+                        })
+                        .ok()?
+                        .await,
+                ),
+                Err(e) => Some(Err(e)),
+            }
+        })
+    }
 }
 
 struct InlineAssistTabBarButton {
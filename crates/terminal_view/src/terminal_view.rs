@@ -1,3 +1,4 @@
+pub mod asciicast_player;
 mod color_contrast;
 mod persistence;
 pub mod terminal_element;
@@ -7,7 +8,7 @@ mod terminal_slash_command;
 pub mod terminal_tab_tooltip;
 
 use assistant_slash_command::SlashCommandRegistry;
-use editor::{Editor, EditorSettings, actions::SelectAll, scroll::ScrollbarAutoHide};
+use editor::{Editor, EditorSettings, MultiBuffer, actions::SelectAll, scroll::ScrollbarAutoHide};
 use gpui::{
     Action, AnyElement, App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
     KeyContext, KeyDownEvent, Keystroke, MouseButton, MouseDownEvent, Pixels, Render,
@@ -16,12 +17,18 @@ use gpui::{
 };
 use itertools::Itertools;
 use persistence::TERMINAL_DB;
-use project::{Entry, Metadata, Project, search::SearchQuery, terminals::TerminalKind};
+use project::{
+    DirectoryLister, Entry, Metadata, Project,
+    search::SearchQuery,
+    terminals::{TerminalAccess, TerminalKind},
+};
+use remote::ConnectionState;
 use schemars::JsonSchema;
 use task::TaskId;
 use terminal::{
-    Clear, Copy, Event, HoveredWord, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp,
-    ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskState,
+    Clear, Copy, CopyLastCommandOutput, Event, HoveredWord, MaybeNavigationTarget, Paste,
+    ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom,
+    ScrollToNextCommand, ScrollToPreviousCommand, ScrollToTop, ShowCharacterPalette, TaskState,
     TaskStatus, Terminal, TerminalBounds, ToggleViMode,
     alacritty_terminal::{
         index::Point,
@@ -35,7 +42,8 @@ use terminal_scrollbar::TerminalScrollHandle;
 use terminal_slash_command::TerminalSlashCommand;
 use terminal_tab_tooltip::TerminalTooltip;
 use ui::{
-    ContextMenu, Icon, IconName, Label, Scrollbar, ScrollbarState, Tooltip, h_flex, prelude::*,
+    ContextMenu, Icon, IconName, Indicator, Label, Scrollbar, ScrollbarState, Tooltip, h_flex,
+    prelude::*,
 };
 use util::{ResultExt, debug_panic, paths::PathWithPosition};
 use workspace::{
@@ -64,6 +72,8 @@ use std::{
 };
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+/// How often to check whether the terminal can attach to its persistent session again, once disconnected from it.
+const REATTACH_DELAY: Duration = Duration::from_secs(1);
 
 const GIT_DIFF_PATH_PREFIXES: &[&str] = &["a", "b"];
 
@@ -85,7 +95,19 @@ actions!(
     terminal,
     [
         /// Reruns the last executed task in the terminal.
-        RerunTask
+        RerunTask,
+        /// Starts recording the terminal session, or stops it and saves the recording as an asciicast file.
+        ToggleRecording,
+        /// Opens an asciicast recording of a terminal session to replay it.
+        ReplayRecording,
+        /// Opens the lines of the terminal output that match the current search in a read-only view.
+        FilterMatchingLines,
+        /// Shares the terminal with the guests of the shared project, who can see it but not type into it.
+        ShareReadOnly,
+        /// Shares the terminal with the guests of the shared project, who can see it and type into it.
+        ShareReadWrite,
+        /// Stops sharing the terminal with the guests of the shared project.
+        StopSharing
     ]
 );
 
@@ -93,11 +115,36 @@ pub fn init(cx: &mut App) {
     assistant_slash_command::init(cx);
     terminal_panel::init(cx);
     terminal::init(cx);
+    asciicast_player::init(cx);
 
     register_serializable_item::<TerminalView>(cx);
 
-    cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
         workspace.register_action(TerminalView::deploy);
+
+        let Some(window) = window else {
+            return;
+        };
+        // Open the terminals that the host shares with the guests.
+        let project = workspace.project().clone();
+        cx.subscribe_in(&project, window, |workspace, project, event, window, cx| {
+            if let project::Event::TerminalShared(terminal) = event {
+                let workspace_handle = cx.entity().downgrade();
+                let workspace_id = workspace.database_id();
+                let terminal_view = cx.new(|cx| {
+                    TerminalView::new(
+                        terminal.clone(),
+                        workspace_handle,
+                        workspace_id,
+                        project.downgrade(),
+                        window,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(Box::new(terminal_view), None, false, window, cx);
+            }
+        })
+        .detach();
     })
     .detach();
     SlashCommandRegistry::global(cx).register_command(TerminalSlashCommand, true);
@@ -128,6 +175,10 @@ pub struct TerminalView {
     mode: TerminalMode,
     blinking_terminal_enabled: bool,
     cwd_serialized: bool,
+    /// The query of the last search, for filtering the output by it.
+    last_search_query: Option<Arc<SearchQuery>>,
+    /// Set when the app quits, leaving the terminal's persistent session running for the next launch to reattach to.
+    app_quitting: bool,
     blinking_paused: bool,
     blink_epoch: usize,
     hover: Option<HoverTarget>,
@@ -142,6 +193,8 @@ pub struct TerminalView {
     hide_scrollbar_task: Option<Task<()>>,
     marked_text: Option<String>,
     marked_range_utf16: Option<Range<usize>>,
+    /// Attaches a new terminal to the persistent session that the terminal got disconnected from.
+    reattach_task: Option<Task<anyhow::Result<()>>>,
     _subscriptions: Vec<Subscription>,
     _terminal_subscriptions: Vec<Subscription>,
 }
@@ -267,13 +320,28 @@ impl TerminalView {
             show_scrollbar: !Self::should_autohide_scrollbar(cx),
             hide_scrollbar_task: None,
             cwd_serialized: false,
+            last_search_query: None,
+            app_quitting: false,
             marked_text: None,
             marked_range_utf16: None,
             _subscriptions: vec![
                 focus_in,
                 focus_out,
                 cx.observe_global::<SettingsStore>(Self::settings_changed),
+                cx.on_app_quit(|terminal_view, _| {
+                    terminal_view.app_quitting = true;
+                    async {}
+                }),
+                // Closing the terminal ends its persistent session, rather than leaving it detached.
+                cx.on_release(|terminal_view, cx| {
+                    if !terminal_view.app_quitting {
+                        terminal_view
+                            .terminal
+                            .update(cx, |terminal, cx| terminal.end_persistent_session(cx));
+                    }
+                }),
             ],
+            reattach_task: None,
             _terminal_subscriptions: terminal_subscriptions,
         }
     }
@@ -388,14 +456,49 @@ impl TerminalView {
             .map_or(false, |terminal_panel| {
                 terminal_panel.read(cx).assistant_enabled()
             });
+        let has_finished_command = self.terminal.read(cx).last_finished_command().is_some();
+        let is_recording = self.terminal.read(cx).is_recording();
+        let (is_project_host, access) = self
+            .project
+            .read_with(cx, |project, _| {
+                (
+                    project.is_shared() && !project.is_via_collab(),
+                    project.terminal_access(&self.terminal),
+                )
+            })
+            .unwrap_or_default();
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal))
                 .separator()
                 .action("Copy", Box::new(Copy))
                 .action("Paste", Box::new(Paste))
+                .when(has_finished_command, |menu| {
+                    menu.action("Copy Last Command Output", Box::new(CopyLastCommandOutput))
+                })
                 .action("Select All", Box::new(SelectAll))
                 .action("Clear", Box::new(Clear))
+                .action("Filter Matching Lines", Box::new(FilterMatchingLines))
+                .action(
+                    if is_recording {
+                        "Stop Recording"
+                    } else {
+                        "Start Recording"
+                    },
+                    Box::new(ToggleRecording),
+                )
+                .when(is_project_host, |menu| {
+                    menu.separator()
+                        .when(access != Some(TerminalAccess::ReadOnly), |menu| {
+                            menu.action("Share Read-Only", Box::new(ShareReadOnly))
+                        })
+                        .when(access != Some(TerminalAccess::ReadWrite), |menu| {
+                            menu.action("Share Read-Write", Box::new(ShareReadWrite))
+                        })
+                        .when(access.is_some(), |menu| {
+                            menu.action("Stop Sharing", Box::new(StopSharing))
+                        })
+                })
                 .when(assistant_enabled, |menu| {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
@@ -600,6 +703,28 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_command(
+        &mut self,
+        _: &ScrollToPreviousCommand,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_command());
+        cx.notify();
+    }
+
+    fn scroll_to_next_command(
+        &mut self,
+        _: &ScrollToNextCommand,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_command());
+        cx.notify();
+    }
+
     fn scroll_to_bottom(&mut self, _: &ScrollToBottom, _: &mut Window, cx: &mut Context<Self>) {
         self.terminal.update(cx, |term, _| term.scroll_to_bottom());
         if self.block_below_cursor.is_some() {
@@ -712,6 +837,136 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn copy_last_command_output(
+        &mut self,
+        _: &CopyLastCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, cx| term.copy_last_command_output(cx));
+    }
+
+    fn toggle_recording(
+        &mut self,
+        _: &ToggleRecording,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.emit(ItemEvent::UpdateTab);
+        cx.notify();
+        let Some(asciicast) = self
+            .terminal
+            .update(cx, |terminal, _| terminal.stop_recording())
+        else {
+            if let Err(error) = self
+                .terminal
+                .update(cx, |terminal, _| terminal.start_recording())
+            {
+                self.workspace
+                    .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+                    .ok();
+            }
+            return;
+        };
+
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let fs = workspace.app_state().fs.clone();
+                let lister = DirectoryLister::Local(workspace.project().clone(), fs.clone());
+                let abs_path = workspace.prompt_for_new_path(lister, window, cx);
+                cx.background_spawn(async move {
+                    let recording = asciicast.to_json_lines()?;
+                    let Some(abs_path) = abs_path.await?.into_iter().flatten().next() else {
+                        return Ok(());
+                    };
+                    fs.atomic_write(abs_path, recording).await
+                })
+                .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn share_read_only(&mut self, _: &ShareReadOnly, _: &mut Window, cx: &mut Context<Self>) {
+        self.share(TerminalAccess::ReadOnly, cx);
+    }
+
+    fn share_read_write(&mut self, _: &ShareReadWrite, _: &mut Window, cx: &mut Context<Self>) {
+        self.share(TerminalAccess::ReadWrite, cx);
+    }
+
+    fn share(&mut self, access: TerminalAccess, cx: &mut Context<Self>) {
+        let terminal = self.terminal.clone();
+        let result = self.project.update(cx, |project, cx| {
+            project.share_terminal(&terminal, access, cx)
+        });
+        if let Ok(Err(error)) = result {
+            self.workspace
+                .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+                .ok();
+        }
+    }
+
+    fn stop_sharing(&mut self, _: &StopSharing, _: &mut Window, cx: &mut Context<Self>) {
+        let terminal = self.terminal.clone();
+        self.project
+            .update(cx, |project, cx| project.unshare_terminal(&terminal, cx))
+            .ok();
+    }
+
+    fn filter_matching_lines(
+        &mut self,
+        _: &FilterMatchingLines,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(query) = self
+            .last_search_query
+            .clone()
+            .filter(|query| !query.as_str().is_empty())
+        else {
+            // Ask for the search first.
+            window.dispatch_action(Box::new(search::buffer_search::Deploy::find()), cx);
+            return;
+        };
+        let Some(searcher) = regex_search_for_query(&query) else {
+            return;
+        };
+        let matching_lines = self.terminal.update(cx, |terminal, cx| {
+            terminal.find_matching_lines(searcher, cx)
+        });
+        let workspace = self.workspace.clone();
+        let title = format!("Terminal Lines Matching “{}”", query.as_str());
+        cx.spawn_in(window, async move |_, cx| {
+            let matching_lines = matching_lines.await;
+            let number_width = matching_lines
+                .last()
+                .map_or(1, |line| line.line_number.to_string().len());
+            let text = matching_lines
+                .iter()
+                .map(|line| format!("{:>number_width$}: {}\n", line.line_number, line.text))
+                .collect::<String>();
+            workspace.update_in(cx, |workspace, window, cx| {
+                let buffer = cx.new(|cx| language::Buffer::local(text, cx));
+                let buffer =
+                    cx.new(|cx| MultiBuffer::singleton(buffer, cx).with_title(title.clone()));
+                let editor = cx.new(|cx| {
+                    let mut editor = Editor::for_multibuffer(
+                        buffer,
+                        Some(workspace.project().clone()),
+                        window,
+                        cx,
+                    );
+                    editor.set_read_only(true);
+                    editor.set_breadcrumb_header(title);
+                    editor
+                });
+                workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     ///Attempt to paste the clipboard into the terminal
     fn paste(&mut self, _: &Paste, _: &mut Window, cx: &mut Context<Self>) {
         if let Some(clipboard_string) = cx.read_from_clipboard().and_then(|item| item.text()) {
@@ -836,6 +1091,48 @@ impl TerminalView {
         self.terminal = terminal;
     }
 
+    /// Attaches a new terminal to the persistent session of the current one, once the remote connection,
+    /// if any, is back.
+    fn reattach_persistent_session(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let terminal = self.terminal.read(cx);
+        let Some(session_id) = terminal.persistent_session_id().map(ToOwned::to_owned) else {
+            return;
+        };
+        let working_directory = terminal.working_directory();
+        let project = self.project.clone();
+        let window_handle = window.window_handle();
+        self.reattach_task = Some(cx.spawn_in(window, async move |terminal_view, cx| {
+            loop {
+                cx.background_executor().timer(REATTACH_DELAY).await;
+                let connection_state =
+                    project.read_with(cx, |project, cx| project.ssh_connection_state(cx))?;
+                match connection_state {
+                    None | Some(ConnectionState::Connected) => break,
+                    Some(ConnectionState::Disconnected) => {
+                        anyhow::bail!("the remote connection is closed")
+                    }
+                    Some(_) => {}
+                }
+            }
+            let terminal = project
+                .update(cx, |project, cx| {
+                    project.create_terminal(
+                        TerminalKind::PersistentSession {
+                            session_id,
+                            working_directory,
+                        },
+                        window_handle,
+                        cx,
+                    )
+                })?
+                .await?;
+            terminal_view.update_in(cx, |terminal_view, window, cx| {
+                terminal_view.set_terminal(terminal, window, cx);
+                cx.notify();
+            })
+        }));
+    }
+
     // Hack: Using editor in terminal causes cyclic dependency i.e. editor -> terminal -> project -> editor.
     fn map_show_scrollbar_from_editor_to_terminal(
         show_scrollbar: editor::ShowScrollbar,
@@ -1200,6 +1497,12 @@ fn subscribe_for_terminal_events(
                     window.invalidate_character_coordinates();
                     cx.emit(SearchEvent::ActiveMatchChanged)
                 }
+                Event::TaskProblemsChanged => {}
+                Event::BackgroundTaskStatusChanged => cx.emit(ItemEvent::UpdateTab),
+                Event::CommandBlocksChanged => cx.notify(),
+                Event::PersistentSessionDetached => {
+                    terminal_view.reattach_persistent_session(window, cx)
+                }
             }
         },
     );
@@ -1443,14 +1746,25 @@ fn possible_open_target(
 
 fn regex_search_for_query(query: &project::search::SearchQuery) -> Option<RegexSearch> {
     let str = query.as_str();
-    if query.is_regex() {
+    let mut pattern = if query.is_regex() {
         if str == "." {
             return None;
         }
-        RegexSearch::new(str).ok()
+        str.to_string()
     } else {
-        RegexSearch::new(&regex::escape(str)).ok()
+        regex::escape(str)
+    };
+    if query.whole_word() {
+        // The terminal searches with DFAs, which only support the ASCII word boundaries.
+        pattern = format!("(?-u:\\b)(?:{pattern})(?-u:\\b)");
     }
+    // The flag overrides the search's default of matching case only for the queries with uppercase letters.
+    let case_flag = if query.case_sensitive() {
+        "(?-i)"
+    } else {
+        "(?i)"
+    };
+    RegexSearch::new(&format!("{case_flag}{pattern}")).ok()
 }
 
 impl TerminalView {
@@ -1513,6 +1827,14 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_command))
+            .on_action(cx.listener(TerminalView::scroll_to_next_command))
+            .on_action(cx.listener(TerminalView::copy_last_command_output))
+            .on_action(cx.listener(TerminalView::toggle_recording))
+            .on_action(cx.listener(TerminalView::filter_matching_lines))
+            .on_action(cx.listener(TerminalView::share_read_only))
+            .on_action(cx.listener(TerminalView::share_read_write))
+            .on_action(cx.listener(TerminalView::stop_sharing))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
@@ -1577,7 +1899,10 @@ impl Item for TerminalView {
     fn tab_tooltip_content(&self, cx: &App) -> Option<TabTooltipContent> {
         let terminal = self.terminal().read(cx);
         let title = terminal.title(false);
-        let pid = terminal.pty_info.pid_getter().fallback_pid();
+        let pid = terminal
+            .pty_info
+            .pid_getter()
+            .map(|pid_getter| pid_getter.fallback_pid());
 
         Some(TabTooltipContent::Custom(Box::new(move |_window, cx| {
             cx.new(|_| TerminalTooltip::new(title.clone(), pid)).into()
@@ -1636,6 +1961,9 @@ impl Item for TerminalView {
                     }),
             )
             .child(Label::new(title).color(params.text_color()))
+            .when(terminal.is_recording(), |this| {
+                this.child(Indicator::dot().color(Color::Error))
+            })
             .into_any()
     }
 
@@ -1654,6 +1982,9 @@ impl Item for TerminalView {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<Entity<Self>> {
+        if self.terminal.read(cx).is_mirror() {
+            return None;
+        }
         let window_handle = window.window_handle();
         let terminal = self
             .project
@@ -1779,10 +2110,14 @@ impl SerializableItem for TerminalView {
         }
 
         if let Some((cwd, workspace_id)) = terminal.working_directory().zip(self.workspace_id) {
+            let session_id = terminal.persistent_session_id().map(ToOwned::to_owned);
             self.cwd_serialized = true;
             Some(cx.background_spawn(async move {
                 TERMINAL_DB
                     .save_working_directory(item_id, workspace_id, cwd)
+                    .await?;
+                TERMINAL_DB
+                    .save_session_id(session_id, item_id, workspace_id)
                     .await
             }))
         } else {
@@ -1824,9 +2159,21 @@ impl SerializableItem for TerminalView {
                 .ok()
                 .flatten();
 
+            let session_id = TERMINAL_DB
+                .get_session_id(item_id, workspace_id)
+                .log_err()
+                .flatten()
+                .filter(|session_id| !session_id.is_empty());
+            let kind = match session_id {
+                Some(session_id) => TerminalKind::PersistentSession {
+                    session_id,
+                    working_directory: cwd,
+                },
+                None => TerminalKind::Shell(cwd),
+            };
             let terminal = project
                 .update(cx, |project, cx| {
-                    project.create_terminal(TerminalKind::Shell(cwd), window_handle, cx)
+                    project.create_terminal(kind, window_handle, cx)
                 })?
                 .await?;
             cx.update(|window, cx| {
@@ -1850,8 +2197,8 @@ impl SearchableItem for TerminalView {
 
     fn supported_options(&self) -> SearchOptions {
         SearchOptions {
-            case: false,
-            word: false,
+            case: true,
+            word: true,
             regex: true,
             replacement: false,
             selection: false,
@@ -1912,6 +2259,7 @@ impl SearchableItem for TerminalView {
         _: &mut Window,
         cx: &mut Context<Self>,
     ) -> Task<Vec<Self::Match>> {
+        self.last_search_query = Some(query.clone());
         if let Some(s) = regex_search_for_query(&query) {
             self.terminal()
                 .update(cx, |term, cx| term.find_matches(s, cx))
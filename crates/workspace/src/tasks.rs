@@ -1,14 +1,22 @@
-use std::process::ExitStatus;
+use std::{process::ExitStatus, sync::Arc};
 
-use anyhow::Result;
-use gpui::{AppContext, Context, Entity, Task};
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use futures::{
+    FutureExt as _,
+    future::{self, LocalBoxFuture, Shared},
+};
+use gpui::{AppContext, AsyncWindowContext, Context, Entity, Task, WeakEntity};
 use language::Buffer;
-use project::{TaskSourceKind, WorktreeId};
+use project::{TaskDependencyGraph, TaskDependencyNode, TaskSourceKind, WorktreeId};
 use remote::ConnectionState;
-use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
+use task::{
+    DebugScenario, DependsOrder, ResolvedTask, Shell, SpawnInTerminal, TaskContext, TaskInput,
+    TaskTemplate,
+};
 use ui::Window;
 
-use crate::Workspace;
+use crate::{TaskInputContext, Workspace};
 
 impl Workspace {
     pub fn schedule_task(
@@ -54,6 +62,36 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) {
+        if !resolved_task.original_task().referenced_inputs().is_empty() {
+            let resolved_task = self.resolve_task_inputs(resolved_task, window, cx);
+            let task = cx.spawn_in(window, async move |workspace, cx| {
+                match resolved_task.await {
+                    Ok(Some(resolved_task)) => {
+                        workspace
+                            .update_in(cx, |workspace, window, cx| {
+                                workspace.schedule_resolved_task(
+                                    task_source_kind,
+                                    resolved_task,
+                                    omit_history,
+                                    window,
+                                    cx,
+                                )
+                            })
+                            .ok();
+                    }
+                    Ok(None) => log::debug!("Task inputs prompt got dismissed"),
+                    Err(e) => {
+                        log::error!("Failed to resolve task inputs: {e:#}");
+                        workspace
+                            .update(cx, |workspace, cx| workspace.show_error(&e, cx))
+                            .ok();
+                    }
+                }
+            });
+            self.scheduled_tasks.push(task);
+            return;
+        }
+
         let spawn_in_terminal = resolved_task.resolved.clone();
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
@@ -65,12 +103,17 @@ impl Workspace {
                     project.task_store().read(cx).task_inventory().cloned()
                 {
                     task_inventory.update(cx, |inventory, _| {
-                        inventory.task_scheduled(task_source_kind, resolved_task);
+                        inventory.task_scheduled(task_source_kind.clone(), resolved_task.clone());
                     })
                 }
             });
         }
 
+        if !resolved_task.original_task().depends_on.is_empty() {
+            self.schedule_task_with_dependencies(task_source_kind, resolved_task, window, cx);
+            return;
+        }
+
         if let Some(terminal_provider) = self.terminal_provider.as_ref() {
             let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
             let task = cx.background_spawn(async move {
@@ -90,6 +133,161 @@ impl Workspace {
         }
     }
 
+    /// Runs all dependencies of the task first, as configured with [`TaskTemplate::depends_on`],
+    /// and only starts the task itself when all of them succeed.
+    fn schedule_task_with_dependencies(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(task_inventory) = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()
+        else {
+            return;
+        };
+        let worktree_id = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => self
+                .project
+                .read(cx)
+                .visible_worktrees(cx)
+                .next()
+                .map(|worktree| worktree.read(cx).id()),
+        };
+        let graph = task_inventory.read(cx).task_dependency_graph(
+            task_source_kind,
+            resolved_task,
+            worktree_id,
+            cx,
+        );
+
+        let task = cx.spawn_in(window, async move |workspace, cx| {
+            let result = match graph.await {
+                Ok(graph) => run_task_graph(graph, workspace.clone(), cx.clone()).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                log::error!("Task with dependencies failed: {e:#}");
+                workspace
+                    .update(cx, |workspace, cx| workspace.show_error(&e, cx))
+                    .ok();
+            }
+        });
+        self.scheduled_tasks.push(task);
+    }
+
+    /// Asks the user for the values of the inputs the task references, see [`TaskTemplate::referenced_inputs`],
+    /// and substitutes them into the task. Resolves to `None` if the prompt was dismissed.
+    pub fn resolve_task_inputs(
+        &mut self,
+        resolved_task: ResolvedTask,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<Option<ResolvedTask>>> {
+        let inputs = resolved_task
+            .original_task()
+            .referenced_inputs()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        if inputs.is_empty() {
+            return Task::ready(Ok(Some(resolved_task)));
+        }
+        let input_values = self.prompt_for_inputs(
+            resolved_task.original_task().label.clone(),
+            TaskInputContext::for_task(&resolved_task),
+            inputs,
+            window,
+            cx,
+        );
+        cx.spawn(async move |_, _| {
+            Ok(input_values
+                .await?
+                .map(|input_values| resolved_task.with_input_values(&input_values)))
+        })
+    }
+
+    /// Asks the user for the values of the inputs the debug scenario references, see [`DebugScenario::referenced_inputs`],
+    /// and substitutes them into the scenario. Resolves to `None` if the prompt was dismissed.
+    pub fn resolve_debug_scenario_inputs(
+        &mut self,
+        scenario: DebugScenario,
+        task_context: &TaskContext,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<Option<DebugScenario>>> {
+        let inputs = scenario
+            .referenced_inputs()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        if inputs.is_empty() {
+            return Task::ready(Ok(Some(scenario)));
+        }
+        let context = TaskInputContext {
+            label: scenario.label.to_string(),
+            cwd: task_context.cwd.clone(),
+            env: task_context.project_env.clone(),
+            shell: Shell::System,
+        };
+        let input_values =
+            self.prompt_for_inputs(scenario.label.to_string(), context, inputs, window, cx);
+        cx.spawn(async move |_, _| {
+            Ok(input_values
+                .await?
+                .map(|input_values| scenario.with_input_values(&input_values)))
+        })
+    }
+
+    /// Prompts for the inputs, starting from the values last entered for the task or scenario with the given label.
+    fn prompt_for_inputs(
+        &mut self,
+        label: String,
+        context: TaskInputContext,
+        inputs: Vec<TaskInput>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<Option<HashMap<String, String>>>> {
+        let Some(provider) = self.task_input_provider.clone() else {
+            return Task::ready(Err(anyhow!(
+                "Cannot ask for the inputs of `{}`",
+                context.label
+            )));
+        };
+
+        let task_inventory = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned();
+        let last_values = task_inventory
+            .as_ref()
+            .and_then(|inventory| inventory.read(cx).last_task_input_values(&label))
+            .cloned()
+            .unwrap_or_default();
+        let input_values = provider.prompt(context, inputs, last_values, self, window, cx);
+        cx.spawn(async move |_, cx| {
+            let Some(input_values) = input_values.await? else {
+                return Ok(None);
+            };
+            if let Some(task_inventory) = task_inventory {
+                task_inventory.update(cx, |inventory, _| {
+                    inventory.task_input_values_entered(label, input_values.clone())
+                })?;
+            }
+            Ok(Some(input_values))
+        })
+    }
+
     pub fn start_debug_session(
         &mut self,
         scenario: DebugScenario,
@@ -123,4 +321,216 @@ impl Workspace {
             Task::ready(None)
         }
     }
+
+    /// Spawns a background task, resolving once it becomes ready rather than when it exits.
+    pub fn spawn_background_task_in_terminal(
+        self: &mut Workspace,
+        spawn_in_terminal: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Option<Result<()>>> {
+        if let Some(terminal_provider) = self.terminal_provider.as_ref() {
+            terminal_provider.spawn_background(spawn_in_terminal, window, cx)
+        } else {
+            Task::ready(None)
+        }
+    }
+}
+
+type TaskRun = Shared<LocalBoxFuture<'static, Result<(), Arc<anyhow::Error>>>>;
+
+/// Spawns every task of the graph once all of its dependencies have succeeded.
+/// When a task fails, none of the tasks depending on it are started.
+async fn run_task_graph(
+    graph: TaskDependencyGraph,
+    workspace: WeakEntity<Workspace>,
+    cx: AsyncWindowContext,
+) -> Result<()> {
+    let mut runs = Vec::<TaskRun>::with_capacity(graph.tasks.len());
+    for node in graph.tasks {
+        let dependencies = node
+            .dependencies
+            .iter()
+            .map(|ix| runs[*ix].clone())
+            .collect();
+        let run = run_task_graph_node(node, dependencies, workspace.clone(), cx.clone())
+            .boxed_local()
+            .shared();
+        runs.push(run);
+    }
+    runs.pop()
+        .context("empty task dependency graph")?
+        .await
+        .map_err(|e| anyhow!("{e:#}"))
+}
+
+async fn run_task_graph_node(
+    node: TaskDependencyNode,
+    dependencies: Vec<TaskRun>,
+    workspace: WeakEntity<Workspace>,
+    mut cx: AsyncWindowContext,
+) -> Result<(), Arc<anyhow::Error>> {
+    match node.task.original_task().depends_order {
+        DependsOrder::Sequence => {
+            for dependency in dependencies {
+                dependency.await?;
+            }
+        }
+        DependsOrder::Parallel => {
+            future::try_join_all(dependencies).await?;
+        }
+    }
+    if node.task.original_task().is_compound() {
+        return Ok(());
+    }
+
+    let label = node.task.resolved.label.clone();
+    let Some(task) = workspace
+        .update_in(&mut cx, |workspace, window, cx| {
+            workspace.resolve_task_inputs(node.task, window, cx)
+        })
+        .map_err(Arc::new)?
+        .await
+        .map_err(Arc::new)?
+    else {
+        return Err(Arc::new(anyhow!("Task `{label}` was cancelled")));
+    };
+    // Background tasks keep running, so the tasks depending on them only wait for them to become ready.
+    if task.resolved.background.is_some() {
+        let task_ready = workspace
+            .update_in(&mut cx, |workspace, window, cx| {
+                workspace.spawn_background_task_in_terminal(task.resolved, window, cx)
+            })
+            .map_err(Arc::new)?;
+        return match task_ready.await {
+            Some(Ok(())) => Ok(()),
+            Some(Err(e)) => {
+                Err(e.context(format!("Background task `{label}` did not become ready")))
+            }
+            None => Err(anyhow!("Task `{label}` was cancelled")),
+        }
+        .map_err(Arc::new);
+    }
+
+    let task_status = workspace
+        .update_in(&mut cx, |workspace, window, cx| {
+            workspace.spawn_in_terminal(task.resolved, window, cx)
+        })
+        .map_err(Arc::new)?;
+    match task_status.await {
+        Some(Ok(status)) if status.success() => Ok(()),
+        Some(Ok(status)) => Err(anyhow!("Task `{label}` failed: {status}")),
+        Some(Err(e)) => Err(e.context(format!("Failed to spawn task `{label}`"))),
+        None => Err(anyhow!("Task `{label}` was cancelled")),
+    }
+    .map_err(Arc::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use fs::FakeFs;
+    use gpui::{App, TestAppContext};
+    use project::Project;
+    use task::{BackgroundPatterns, TaskContext};
+
+    use super::*;
+    use crate::{TerminalProvider, tests::init_test};
+
+    /// Records the spawned tasks. Background tasks never exit, but are ready right away.
+    struct FakeTerminalProvider {
+        spawned: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl TerminalProvider for FakeTerminalProvider {
+        fn spawn(
+            &self,
+            task: SpawnInTerminal,
+            _: &mut Window,
+            cx: &mut App,
+        ) -> Task<Option<Result<ExitStatus>>> {
+            self.spawned.borrow_mut().push(task.label.clone());
+            if task.background.is_some() {
+                cx.background_spawn(future::pending())
+            } else {
+                Task::ready(Some(Ok(ExitStatus::default())))
+            }
+        }
+
+        fn spawn_background(
+            &self,
+            task: SpawnInTerminal,
+            _: &mut Window,
+            _: &mut App,
+        ) -> Task<Option<Result<()>>> {
+            self.spawned
+                .borrow_mut()
+                .push(format!("{} (background)", task.label));
+            Task::ready(Some(Ok(())))
+        }
+    }
+
+    fn graph_node(template: TaskTemplate, dependencies: Vec<usize>) -> TaskDependencyNode {
+        TaskDependencyNode {
+            source_kind: TaskSourceKind::UserInput,
+            task: template
+                .resolve_task("test", &TaskContext::default())
+                .unwrap(),
+            dependencies,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_task_depending_on_background_task_starts_once_ready(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+
+        let spawned = Rc::new(RefCell::new(Vec::new()));
+        workspace.update(cx, |workspace, _| {
+            workspace.set_terminal_provider(FakeTerminalProvider {
+                spawned: spawned.clone(),
+            })
+        });
+
+        let graph = TaskDependencyGraph {
+            tasks: vec![
+                graph_node(
+                    TaskTemplate {
+                        label: "watch".to_string(),
+                        command: "npm run watch".to_string(),
+                        is_background: true,
+                        background_patterns: BackgroundPatterns {
+                            begins: None,
+                            ends: Some("^Watching".to_string()),
+                        },
+                        ..TaskTemplate::default()
+                    },
+                    Vec::new(),
+                ),
+                graph_node(
+                    TaskTemplate {
+                        label: "serve".to_string(),
+                        command: "npm run serve".to_string(),
+                        depends_on: vec!["watch".to_string()],
+                        ..TaskTemplate::default()
+                    },
+                    vec![0],
+                ),
+            ],
+        };
+        let run = workspace.update_in(cx, |_, window, cx| {
+            cx.spawn_in(window, async move |workspace, cx| {
+                run_task_graph(graph, workspace, cx.clone()).await
+            })
+        });
+        run.await.unwrap();
+        assert_eq!(
+            *spawned.borrow(),
+            vec!["watch (background)".to_string(), "serve".to_string()]
+        );
+    }
 }
@@ -99,7 +99,7 @@ use std::{
     sync::{Arc, LazyLock, Weak, atomic::AtomicUsize},
     time::Duration,
 };
-use task::{DebugScenario, SpawnInTerminal, TaskContext};
+use task::{DebugScenario, ResolvedTask, Shell, SpawnInTerminal, TaskContext, TaskInput};
 use theme::{ActiveTheme, SystemAppearance, ThemeSettings};
 pub use toolbar::{Toolbar, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
 pub use ui;
@@ -140,6 +140,47 @@ pub trait TerminalProvider {
         window: &mut Window,
         cx: &mut App,
     ) -> Task<Option<Result<ExitStatus>>>;
+
+    /// Spawns a background task, resolving once the task reports that it is ready instead of when it exits.
+    fn spawn_background(
+        &self,
+        task: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Option<Result<()>>>;
+}
+
+/// What the inputs are asked for: a task or a debug scenario, and where their commands run.
+#[derive(Clone, Debug, Default)]
+pub struct TaskInputContext {
+    pub label: String,
+    pub cwd: Option<PathBuf>,
+    pub env: HashMap<String, String>,
+    pub shell: Shell,
+}
+
+impl TaskInputContext {
+    pub fn for_task(task: &ResolvedTask) -> Self {
+        Self {
+            label: task.display_label().to_owned(),
+            cwd: task.resolved.cwd.clone(),
+            env: task.resolved.env.clone(),
+            shell: task.resolved.shell.clone(),
+        }
+    }
+}
+
+pub trait TaskInputProvider {
+    /// Asks the user for the values of the task inputs, resolves to `None` if the prompt was dismissed.
+    fn prompt(
+        &self,
+        context: TaskInputContext,
+        inputs: Vec<TaskInput>,
+        last_values: HashMap<String, String>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<Option<HashMap<String, String>>>>;
 }
 
 pub trait DebuggerProvider {
@@ -608,10 +649,24 @@ type BuildProjectItemForPathFn =
 struct ProjectItemRegistry {
     build_project_item_fns_by_type: HashMap<TypeId, BuildProjectItemFn>,
     build_project_item_for_path_fns: Vec<BuildProjectItemForPathFn>,
+    /// Tried in turn when the path couldn't be opened by any of the other project items.
+    build_fallback_project_item_for_path_fns: Vec<BuildProjectItemForPathFn>,
 }
 
 impl ProjectItemRegistry {
     fn register<T: ProjectItem>(&mut self) {
+        self.register_build_project_item::<T>();
+        self.build_project_item_for_path_fns
+            .push(Self::build_project_item_for_path::<T>);
+    }
+
+    fn register_fallback<T: ProjectItem>(&mut self) {
+        self.register_build_project_item::<T>();
+        self.build_fallback_project_item_for_path_fns
+            .push(Self::build_project_item_for_path::<T>);
+    }
+
+    fn register_build_project_item<T: ProjectItem>(&mut self) {
         self.build_project_item_fns_by_type.insert(
             TypeId::of::<T::Item>(),
             |item, project, pane, window, cx| {
@@ -620,25 +675,29 @@ impl ProjectItemRegistry {
                     as Box<dyn ItemHandle>
             },
         );
-        self.build_project_item_for_path_fns
-            .push(|project, project_path, window, cx| {
-                let project_item =
-                    <T::Item as project::ProjectItem>::try_open(project, project_path, cx)?;
-                let project = project.clone();
-                Some(window.spawn(cx, async move |cx| {
-                    let project_item = project_item.await?;
-                    let project_entry_id: Option<ProjectEntryId> =
-                        project_item.read_with(cx, project::ProjectItem::entry_id)?;
-                    let build_workspace_item = Box::new(
-                        |pane: &mut Pane, window: &mut Window, cx: &mut Context<Pane>| {
-                            Box::new(cx.new(|cx| {
-                                T::for_project_item(project, Some(pane), project_item, window, cx)
-                            })) as Box<dyn ItemHandle>
-                        },
-                    ) as Box<_>;
-                    Ok((project_entry_id, build_workspace_item))
-                }))
-            });
+    }
+
+    fn build_project_item_for_path<T: ProjectItem>(
+        project: &Entity<Project>,
+        project_path: &ProjectPath,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>>> {
+        let project_item = <T::Item as project::ProjectItem>::try_open(project, project_path, cx)?;
+        let project = project.clone();
+        Some(window.spawn(cx, async move |cx| {
+            let project_item = project_item.await?;
+            let project_entry_id: Option<ProjectEntryId> =
+                project_item.read_with(cx, project::ProjectItem::entry_id)?;
+            let build_workspace_item = Box::new(
+                |pane: &mut Pane, window: &mut Window, cx: &mut Context<Pane>| {
+                    Box::new(cx.new(|cx| {
+                        T::for_project_item(project, Some(pane), project_item, window, cx)
+                    })) as Box<dyn ItemHandle>
+                },
+            ) as Box<_>;
+            Ok((project_entry_id, build_workspace_item))
+        }))
     }
 
     fn open_path(
@@ -648,15 +707,39 @@ impl ProjectItemRegistry {
         window: &mut Window,
         cx: &mut App,
     ) -> Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>> {
-        let Some(open_project_item) = self
+        let open_project_item = self
             .build_project_item_for_path_fns
             .iter()
             .rev()
-            .find_map(|open_project_item| open_project_item(&project, &path, window, cx))
-        else {
-            return Task::ready(Err(anyhow!("cannot open file {:?}", path.path)));
-        };
-        open_project_item
+            .find_map(|open_project_item| open_project_item(&project, &path, window, cx));
+        let fallbacks = self.build_fallback_project_item_for_path_fns.clone();
+        if fallbacks.is_empty() {
+            return open_project_item
+                .unwrap_or_else(|| Task::ready(Err(anyhow!("cannot open file {:?}", path.path))));
+        }
+
+        let project = project.clone();
+        let path = path.clone();
+        window.spawn(cx, async move |cx| {
+            let error = match open_project_item {
+                Some(open_project_item) => match open_project_item.await {
+                    Ok(opened) => return Ok(opened),
+                    Err(error) => error,
+                },
+                None => anyhow!("cannot open file {:?}", path.path),
+            };
+            for open_fallback_item in fallbacks.iter().rev() {
+                let Some(open_fallback_item) =
+                    cx.update(|window, cx| open_fallback_item(&project, &path, window, cx))?
+                else {
+                    continue;
+                };
+                if let Ok(opened) = open_fallback_item.await {
+                    return Ok(opened);
+                }
+            }
+            Err(error)
+        })
     }
 
     fn build_item<T: project::ProjectItem>(
@@ -686,6 +769,13 @@ pub fn register_project_item<I: ProjectItem>(cx: &mut App) {
     cx.default_global::<ProjectItemRegistry>().register::<I>();
 }
 
+/// Registers a project item that opens the paths none of the other project items could open, like the files
+/// whose contents can't be loaded as text.
+pub fn register_fallback_project_item<I: ProjectItem>(cx: &mut App) {
+    cx.default_global::<ProjectItemRegistry>()
+        .register_fallback::<I>();
+}
+
 #[derive(Default)]
 pub struct FollowableViewRegistry(HashMap<TypeId, FollowableViewDescriptor>);
 
@@ -1105,6 +1195,7 @@ pub struct Workspace {
     on_prompt_for_open_path: Option<PromptForOpenPath>,
     terminal_provider: Option<Box<dyn TerminalProvider>>,
     debugger_provider: Option<Arc<dyn DebuggerProvider>>,
+    task_input_provider: Option<Arc<dyn TaskInputProvider>>,
     serializable_items_tx: UnboundedSender<Box<dyn SerializableItemHandle>>,
     serialized_ssh_project: Option<SerializedSshProject>,
     _items_serializer: Task<Result<()>>,
@@ -1438,6 +1529,7 @@ impl Workspace {
             on_prompt_for_open_path: None,
             terminal_provider: None,
             debugger_provider: None,
+            task_input_provider: None,
             serializable_items_tx,
             _items_serializer,
             session_id: Some(session_id),
@@ -2015,6 +2107,10 @@ impl Workspace {
         self.debugger_provider.clone()
     }
 
+    pub fn set_task_input_provider(&mut self, provider: impl TaskInputProvider + 'static) {
+        self.task_input_provider = Some(Arc::new(provider));
+    }
+
     pub fn serialized_ssh_project(&self) -> Option<SerializedSshProject> {
         self.serialized_ssh_project.clone()
     }
@@ -10730,6 +10826,66 @@ mod tests {
                 .await;
             assert!(handle.is_err());
         }
+
+        #[gpui::test]
+        async fn test_register_fallback_project_item(cx: &mut TestAppContext) {
+            init_test(cx);
+
+            cx.update(|cx| {
+                register_project_item::<TestPngItemView>(cx);
+                register_fallback_project_item::<TestIpynbItemView>(cx);
+            });
+
+            let fs = FakeFs::new(cx.executor());
+            fs.insert_tree(
+                "/root1",
+                json!({
+                    "one.png": "BINARYDATAHERE",
+                    "two.ipynb": "{ totally a notebook }",
+                    "three.txt": "editing text, sure why not?"
+                }),
+            )
+            .await;
+            let project = Project::test(fs, ["root1".as_ref()], cx).await;
+            let (workspace, cx) =
+                cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+            let worktree_id = project.update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            });
+
+            let handle = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    let project_path = (worktree_id, "one.png");
+                    workspace.open_path(project_path, None, true, window, cx)
+                })
+                .await
+                .unwrap();
+            assert_eq!(
+                handle.to_any().entity_type(),
+                TypeId::of::<TestPngItemView>()
+            );
+
+            // No other item opens notebooks, so the fallback does.
+            let handle = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    let project_path = (worktree_id, "two.ipynb");
+                    workspace.open_path(project_path, None, true, window, cx)
+                })
+                .await
+                .unwrap();
+            assert_eq!(
+                handle.to_any().entity_type(),
+                TypeId::of::<TestIpynbItemView>()
+            );
+
+            let handle = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    let project_path = (worktree_id, "three.txt");
+                    workspace.open_path(project_path, None, true, window, cx)
+                })
+                .await;
+            assert!(handle.is_err());
+        }
     }
 
     fn pane_items_paths(pane: &Entity<Pane>, cx: &App) -> Vec<String> {
@@ -7,7 +7,7 @@ use ::ignore::gitignore::{Gitignore, GitignoreBuilder};
 use anyhow::{Context as _, Result, anyhow};
 use clock::ReplicaId;
 use collections::{HashMap, HashSet, VecDeque};
-use fs::{Fs, MTime, PathEvent, RemoveOptions, Watcher, copy_recursive, read_dir_items};
+use fs::{Encoding, Fs, MTime, PathEvent, RemoveOptions, Watcher, copy_recursive, read_dir_items};
 use futures::{
     FutureExt as _, Stream, StreamExt,
     channel::{
@@ -100,6 +100,8 @@ pub enum CreatedEntry {
 pub struct LoadedFile {
     pub file: Arc<File>,
     pub text: String,
+    /// The encoding detected from the contents of the file.
+    pub encoding: Encoding,
 }
 
 pub struct LoadedBinaryFile {
@@ -830,10 +832,11 @@ impl Worktree {
         path: &Path,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &Context<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         match self {
-            Worktree::Local(this) => this.write_file(path, text, line_ending, cx),
+            Worktree::Local(this) => this.write_file(path, text, line_ending, encoding, cx),
             Worktree::Remote(_) => {
                 Task::ready(Err(anyhow!("remote worktree can't yet write files")))
             }
@@ -1528,7 +1531,7 @@ impl LocalWorktree {
                     }
                 }
             }
-            let text = fs.load(&abs_path).await?;
+            let (text, encoding) = fs.load_with_encoding(&abs_path, None).await?;
 
             let worktree = this.upgrade().context("worktree was dropped")?;
             let file = match entry.await? {
@@ -1556,7 +1559,11 @@ impl LocalWorktree {
                 }
             };
 
-            Ok(LoadedFile { file, text })
+            Ok(LoadedFile {
+                file,
+                text,
+                encoding,
+            })
         })
     }
 
@@ -1643,6 +1650,7 @@ impl LocalWorktree {
         path: impl Into<Arc<Path>>,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &Context<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         let path = path.into();
@@ -1655,7 +1663,10 @@ impl LocalWorktree {
         let write = cx.background_spawn({
             let fs = fs.clone();
             let abs_path = abs_path.clone();
-            async move { fs.save(&abs_path, &text, line_ending).await }
+            async move {
+                fs.save_with_encoding(&abs_path, &text, line_ending, encoding)
+                    .await
+            }
         });
 
         cx.spawn(async move |this, cx| {
@@ -3362,6 +3373,17 @@ impl language::LocalFile for File {
         let fs = worktree.fs.clone();
         cx.background_spawn(async move { fs.load_bytes(&abs_path?).await })
     }
+
+    fn load_with_encoding(
+        &self,
+        encoding: Option<Encoding>,
+        cx: &App,
+    ) -> Task<Result<(String, Encoding)>> {
+        let worktree = self.worktree.read(cx).as_local().unwrap();
+        let abs_path = worktree.absolutize(&self.path);
+        let fs = worktree.fs.clone();
+        cx.background_spawn(async move { fs.load_with_encoding(&abs_path?, encoding).await })
+    }
 }
 
 impl File {
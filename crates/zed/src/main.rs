@@ -195,6 +195,38 @@ pub fn main() {
         }
     }
 
+    // `zed --terminal-mux` Makes zed operate as the daemon keeping the persistent terminal sessions alive
+    #[cfg(unix)]
+    if let Some(socket) = &args.terminal_mux {
+        if let Err(err) = terminal::mux::run_daemon(socket) {
+            eprintln!("Error: {:#}", err);
+            process::exit(1);
+        }
+        return;
+    }
+
+    // `zed --terminal-attach` Makes zed attach the terminal it runs in to a persistent session
+    #[cfg(unix)]
+    if let Some(session_id) = &args.terminal_attach {
+        match terminal::mux::attach(session_id) {
+            Ok(exit_code) => process::exit(exit_code),
+            Err(err) => {
+                eprintln!("Error: {:#}", err);
+                process::exit(1);
+            }
+        }
+    }
+
+    // `zed --terminal-end` Ends a persistent terminal session
+    #[cfg(unix)]
+    if let Some(session_id) = &args.terminal_end {
+        if let Err(err) = terminal::mux::end_session(session_id) {
+            eprintln!("Error: {:#}", err);
+            process::exit(1);
+        }
+        return;
+    }
+
     // `zed --printenv` Outputs environment variables as JSON to stdout
     if args.printenv {
         util::shell_env::print_env();
@@ -578,6 +610,7 @@ pub fn main() {
             false,
             cx,
         );
+        semantic_index::init(app_state.client.http_client(), cx);
         assistant_tools::init(app_state.client.http_client(), cx);
         repl::init(app_state.fs.clone(), cx);
         extension_host::init(
@@ -606,6 +639,7 @@ pub fn main() {
         tab_switcher::init(cx);
         outline::init(cx);
         project_symbols::init(cx);
+        project_mcp_server::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
         tasks_ui::init(cx);
@@ -616,6 +650,7 @@ pub fn main() {
         terminal_view::init(cx);
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
+        encoding_selector::init(cx);
         toolchain_selector::init(cx);
         theme_selector::init(cx);
         settings_profile_selector::init(cx);
@@ -628,6 +663,9 @@ pub fn main() {
         feedback::init(cx);
         markdown_preview::init(cx);
         svg_preview::init(cx);
+        json_viewer::init(cx);
+        csv_editor::init(cx);
+        hex_editor::init(cx);
         welcome::init(cx);
         onboarding::init(cx);
         settings_ui::init(cx);
@@ -1199,6 +1237,22 @@ struct Args {
     #[arg(long, hide = true)]
     crash_handler: Option<PathBuf>,
 
+    /// Used for keeping the persistent terminal sessions alive, by having Zed run a separate
+    /// daemon process owning the sessions' shells, communicating over a socket.
+    #[arg(long, hide = true)]
+    #[cfg(unix)]
+    terminal_mux: Option<PathBuf>,
+
+    /// Used for attaching a terminal to its persistent session.
+    #[arg(long, hide = true)]
+    #[cfg(unix)]
+    terminal_attach: Option<String>,
+
+    /// Used for ending the persistent session of a closed terminal.
+    #[arg(long, hide = true)]
+    #[cfg(unix)]
+    terminal_end: Option<String>,
+
     /// Run zed in the foreground, only used on Windows, to match the behavior on macOS.
     #[arg(long)]
     #[cfg(target_os = "windows")]